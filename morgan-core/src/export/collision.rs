@@ -0,0 +1,301 @@
+//! Collision geometry for physics engines such as Rapier, kept apart from render
+//! meshes.
+//!
+//! Tiles the theme marks as solid or walkable become axis-aligned boxes, merged within
+//! each layer where they line up, so a run of wall tiles is one collider. Doors,
+//! stairs, moving bodies and anything turned off the grid keep colliders of their own.
+
+use crate::generation::decoration::recorded_theme;
+use crate::generation::themes::{Theme, ThemeLibrary};
+use crate::level::connectivity::CellKind;
+use crate::level::physics::{BodyType, ColliderShape};
+use crate::spatial::{BoundingBox, OrientedBox};
+use crate::{GameObject, LevelData, ObjectKind};
+use serde::{Deserialize, Serialize};
+
+/// Distance within which box faces count as touching.
+const EPSILON: f32 = 1e-3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollisionExport {
+    pub level_id: String,
+    pub level_name: String,
+    pub layers: Vec<CollisionLayer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollisionLayer {
+    pub name: String,
+    /// Fixed boxes covering the layer's solid and walkable tiles
+    pub boxes: Vec<CollisionBox>,
+    /// Colliders that can't be merged into the boxes
+    pub bodies: Vec<CollisionBody>,
+}
+
+/// An axis-aligned box, as a cuboid collider placed at `center`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollisionBox {
+    pub center: [f32; 3],
+    pub half_extents: [f32; 3],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollisionBody {
+    pub object_id: String,
+    pub body_type: BodyType,
+    pub position: [f32; 3],
+    pub rotation: [f32; 4],
+    /// `Mesh` colliders use the object's render mesh
+    pub collider: ColliderShape,
+    pub sensor: bool,
+}
+
+/// How an object collides, if at all.
+enum Collider {
+    Fixed(BoundingBox),
+    Body(CollisionBody),
+}
+
+/// Collision geometry for `level`, layer by layer in the level's layer order.
+#[must_use]
+pub fn collision_export(level: &LevelData) -> CollisionExport {
+    let theme = recorded_theme(level).and_then(|id| ThemeLibrary::current().get_theme(id));
+    let mut names = level.layers.clone();
+    for obj in &level.objects {
+        if !names.contains(&obj.layer) {
+            names.push(obj.layer.clone());
+        }
+    }
+
+    let layers = names
+        .into_iter()
+        .filter_map(|name| {
+            let mut boxes = Vec::new();
+            let mut bodies = Vec::new();
+            for obj in level.objects.iter().filter(|obj| obj.layer == name) {
+                match collider(obj, theme.as_ref()) {
+                    Some(Collider::Fixed(bounds)) => boxes.push(bounds),
+                    Some(Collider::Body(body)) => bodies.push(body),
+                    None => {}
+                }
+            }
+            let boxes: Vec<_> = merge_boxes(boxes)
+                .into_iter()
+                .map(|bounds| CollisionBox {
+                    center: std::array::from_fn(|i| (bounds.min[i] + bounds.max[i]) * 0.5),
+                    half_extents: std::array::from_fn(|i| (bounds.max[i] - bounds.min[i]) * 0.5),
+                })
+                .collect();
+            (!boxes.is_empty() || !bodies.is_empty()).then_some(CollisionLayer {
+                name,
+                boxes,
+                bodies,
+            })
+        })
+        .collect();
+
+    CollisionExport {
+        level_id: level.id.clone(),
+        level_name: level.name.clone(),
+        layers,
+    }
+}
+
+/// Whether the theme's tile for `obj`, found by its `tile_type` or tags, is solid or
+/// walkable. Without one, tiles tagged as collision or classed as cells are.
+fn is_collision_tile(obj: &GameObject, theme: Option<&Theme>) -> bool {
+    let tile_type = obj.metadata.get("tile_type").and_then(|v| v.as_str());
+    let tile = theme.and_then(|theme| {
+        tile_type
+            .into_iter()
+            .chain(obj.tags.iter().map(String::as_str))
+            .find_map(|key| theme.tiles.get(key))
+    });
+    match tile {
+        Some(tile) => tile.collision || tile.walkable,
+        None => {
+            obj.tags.iter().any(|tag| tag == "collision") || CellKind::from_object(obj).is_some()
+        }
+    }
+}
+
+/// Whether a box turned by `rotation` still has its faces along the world axes.
+fn is_axis_aligned(rotation: [f32; 4]) -> bool {
+    let turned = OrientedBox {
+        center: [0.0; 3],
+        half_extents: [0.0; 3],
+        rotation,
+    };
+    turned
+        .axes()
+        .iter()
+        .all(|axis| axis.iter().any(|c| c.abs() > 1.0 - EPSILON))
+}
+
+fn collider(obj: &GameObject, theme: Option<&Theme>) -> Option<Collider> {
+    let transform = &obj.transform;
+    let body = |body_type, collider, sensor| {
+        Collider::Body(CollisionBody {
+            object_id: obj.id.clone(),
+            body_type,
+            position: transform.position,
+            rotation: transform.rotation,
+            collider,
+            sensor,
+        })
+    };
+    let fitted = ColliderShape::Cuboid {
+        half_extents: transform.scale.map(|s| s.abs() * 0.5),
+    };
+
+    if let Some(physics) = &obj.physics {
+        let collider = physics.effective_collider(transform);
+        return Some(match collider {
+            ColliderShape::Cuboid { half_extents }
+                if physics.body_type == BodyType::Static
+                    && !physics.sensor
+                    && is_axis_aligned(transform.rotation) =>
+            {
+                let placed = OrientedBox {
+                    center: transform.position,
+                    half_extents,
+                    rotation: transform.rotation,
+                };
+                Collider::Fixed(placed.enclosing_bounds())
+            }
+            collider => body(physics.body_type, collider, physics.sensor),
+        });
+    }
+
+    // Doors open and close, so games move their colliders
+    let kind = CellKind::from_object(obj);
+    if matches!(obj.kind, ObjectKind::Door(_)) || kind == Some(CellKind::Door) {
+        return Some(body(BodyType::Kinematic, fitted, false));
+    }
+    if !obj.kind.has_geometry() || !is_collision_tile(obj, theme) {
+        return None;
+    }
+    // A box would block stairs instead of letting players climb them
+    if kind == Some(CellKind::Stairs) {
+        return Some(body(BodyType::Static, ColliderShape::Mesh, false));
+    }
+    Some(if is_axis_aligned(transform.rotation) {
+        Collider::Fixed(BoundingBox::from_transform(transform))
+    } else {
+        body(BodyType::Static, fitted, false)
+    })
+}
+
+fn snap(value: f32) -> i64 {
+    (value / EPSILON).round() as i64
+}
+
+/// Joins boxes that touch or overlap along `axis` and match across the other two.
+fn merge_along(mut boxes: Vec<BoundingBox>, axis: usize) -> Vec<BoundingBox> {
+    let key = |b: &BoundingBox| -> Vec<i64> {
+        (0..3)
+            .filter(|&i| i != axis)
+            .flat_map(|i| [snap(b.min[i]), snap(b.max[i])])
+            .collect()
+    };
+    boxes.sort_by(|a, b| {
+        key(a)
+            .cmp(&key(b))
+            .then_with(|| a.min[axis].total_cmp(&b.min[axis]))
+    });
+    let mut merged: Vec<BoundingBox> = Vec::new();
+    for next in boxes {
+        match merged.last_mut() {
+            Some(last) if key(last) == key(&next) && next.min[axis] <= last.max[axis] + EPSILON => {
+                last.max[axis] = last.max[axis].max(next.max[axis]);
+            }
+            _ => merged.push(next),
+        }
+    }
+    merged
+}
+
+/// Fewer, larger boxes covering the same space: rows along X, then joined along Z
+/// into slabs, then stacked along Y.
+fn merge_boxes(boxes: Vec<BoundingBox>) -> Vec<BoundingBox> {
+    [0, 2, 1].into_iter().fold(boxes, merge_along)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::doors::DoorProperties;
+    use crate::level::physics::PhysicsProperties;
+    use crate::testing::{assert_close, level};
+
+    fn tile(id: &str, layer: &str, tags: &[&str], position: [f32; 3]) -> GameObject {
+        GameObject {
+            mesh: Some("meshes/cube.mesh".to_string()),
+            layer: layer.to_string(),
+            tags: tags.iter().map(ToString::to_string).collect(),
+            ..GameObject::new(id, position)
+        }
+    }
+
+    #[test]
+    fn merges_tiles_and_keeps_moving_parts_apart() {
+        let mut objects: Vec<GameObject> = (0..3)
+            .map(|x| {
+                tile(
+                    &format!("wall_{}", x),
+                    "Walls",
+                    &["wall"],
+                    [x as f32, 1.0, 0.0],
+                )
+            })
+            .collect();
+        // A second row beside the first makes one slab
+        objects.extend((0..3).map(|x| {
+            tile(
+                &format!("back_{}", x),
+                "Walls",
+                &["wall"],
+                [x as f32, 1.0, 1.0],
+            )
+        }));
+        let mut turned = tile("turned", "Walls", &["wall"], [8.0, 1.0, 0.0]);
+        let half = std::f32::consts::FRAC_PI_8;
+        turned.transform.rotation = [0.0, half.sin(), 0.0, half.cos()];
+        objects.push(turned);
+        objects.push(tile("floor", "Floors", &["floor"], [0.0, 0.0, 0.0]));
+        objects.push(tile("statue", "Floors", &[], [5.0, 0.0, 0.0]));
+        let mut door = tile("door", "Doors", &["door"], [3.0, 1.0, 0.0]);
+        door.kind = ObjectKind::Door(DoorProperties::default());
+        objects.push(door);
+        let mut barrel = tile("barrel", "Props", &[], [2.0, 0.5, 2.0]);
+        barrel.physics = Some(PhysicsProperties {
+            body_type: BodyType::Dynamic,
+            ..PhysicsProperties::default()
+        });
+        objects.push(barrel);
+
+        let level = LevelData {
+            layers: vec!["Floors".to_string(), "Walls".to_string()],
+            generation_params: Some(serde_json::json!({ "theme": "dungeon" })),
+            ..level(objects)
+        };
+        let export = collision_export(&level);
+        let names: Vec<_> = export.layers.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["Floors", "Walls", "Doors", "Props"]);
+
+        // Dungeon floors are walkable, and the untagged statue isn't a tile
+        let floors = &export.layers[0];
+        assert_eq!(floors.boxes.len(), 1);
+        assert!(floors.bodies.is_empty());
+
+        let walls = &export.layers[1];
+        assert_eq!(walls.boxes.len(), 1);
+        assert_close(walls.boxes[0].center, [1.0, 1.0, 0.5]);
+        assert_close(walls.boxes[0].half_extents, [1.5, 0.5, 1.0]);
+        assert_eq!(walls.bodies.len(), 1);
+        assert_eq!(walls.bodies[0].object_id, "turned");
+
+        assert_eq!(export.layers[2].bodies[0].body_type, BodyType::Kinematic);
+        assert_eq!(export.layers[3].bodies[0].body_type, BodyType::Dynamic);
+    }
+}
@@ -0,0 +1,304 @@
+//! Conversion from the editor's coordinates to those of the engines levels are
+//! exported to.
+//!
+//! The editor, like Bevy, Godot and glTF, is right-handed with Y up and objects facing
+//! -Z, measured in metres. Other targets may put Z up, face the other way along the
+//! depth axis or be left-handed like Unity, which mirrors the Z axis and so also turns
+//! rotations about X and Y the other way. Any such change of axes is a signed
+//! permutation, so it is applied to positions directly, to rotation axes with the
+//! mirror's sign and to per-axis sizes without signs.
+
+use crate::level::annotations::AnnotationAnchor;
+use crate::level::paths::PathProperties;
+use crate::level::physics::ColliderShape;
+use crate::level::zones::ZoneShape;
+use crate::spatial::BoundingBox;
+use crate::{LevelData, ObjectKind, Transform3D};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpAxis {
+    #[default]
+    Y,
+    Z,
+}
+
+/// Which way objects face along the depth axis: Z, or Y when Z is up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Forward {
+    #[default]
+    Negative,
+    Positive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CoordinateSystem {
+    /// Whether Z points away from the viewer when X points right and Y up
+    pub left_handed: bool,
+    pub up: UpAxis,
+    pub forward: Forward,
+    /// Target units in one editor metre
+    pub units_per_meter: f32,
+    /// Editor position that becomes the target's origin
+    pub origin: [f32; 3],
+}
+
+impl CoordinateSystem {
+    /// The editor's own, shared by Bevy, Godot and glTF.
+    pub const EDITOR: Self = Self {
+        left_handed: false,
+        up: UpAxis::Y,
+        forward: Forward::Negative,
+        units_per_meter: 1.0,
+        origin: [0.0; 3],
+    };
+
+    /// Unity's: left-handed, Y up, facing +Z, metres.
+    pub const UNITY: Self = Self {
+        left_handed: true,
+        up: UpAxis::Y,
+        forward: Forward::Positive,
+        units_per_meter: 1.0,
+        origin: [0.0; 3],
+    };
+
+    #[must_use]
+    pub const fn with_units_per_meter(mut self, units_per_meter: f32) -> Self {
+        self.units_per_meter = units_per_meter;
+        self
+    }
+
+    /// Where the editor's X, Y and Z axes point in the target's coordinates.
+    fn axes(&self) -> [[f32; 3]; 3] {
+        let (up, depth) = match self.up {
+            UpAxis::Y => ([0.0, 1.0, 0.0], [0.0, 0.0, 1.0]),
+            UpAxis::Z => ([0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+        };
+        let forward = match self.forward {
+            Forward::Negative => -1.0,
+            Forward::Positive => 1.0,
+        };
+        // Right crossed with up is backwards in right-handed coordinates and forwards
+        // in left-handed ones, which fixes which way X has to point
+        let handedness = if self.left_handed { 1.0 } else { -1.0 };
+        let right = match self.up {
+            UpAxis::Y => handedness * forward,
+            UpAxis::Z => -handedness * forward,
+        };
+        // The editor's Z points backwards
+        [[right, 0.0, 0.0], up, depth.map(|c| -forward * c)]
+    }
+
+    /// `v` in the target's axes, unscaled.
+    fn turn(&self, v: [f32; 3]) -> [f32; 3] {
+        let [x, y, z] = self.axes();
+        std::array::from_fn(|i| v[0].mul_add(x[i], v[1].mul_add(y[i], v[2] * z[i])))
+    }
+
+    #[must_use]
+    pub fn length(&self, length: f32) -> f32 {
+        length * self.units_per_meter
+    }
+
+    /// A point in world space.
+    #[must_use]
+    pub fn position(&self, position: [f32; 3]) -> [f32; 3] {
+        let offset = std::array::from_fn(|i| position[i] - self.origin[i]);
+        self.turn(offset).map(|c| self.length(c))
+    }
+
+    /// A rotation quaternion `[x, y, z, w]`.
+    #[must_use]
+    pub fn rotation(&self, rotation: [f32; 4]) -> [f32; 4] {
+        // A rotation's axis keeps its handedness, so it flips when the axes are mirrored
+        let mirror = if self.left_handed { -1.0 } else { 1.0 };
+        let [x, y, z] = self.turn([rotation[0], rotation[1], rotation[2]]);
+        [mirror * x, mirror * y, mirror * z, rotation[3]]
+    }
+
+    /// Per-axis sizes in an object's own axes, such as scale, which only swap places.
+    pub fn extents(&self, extents: [f32; 3]) -> [f32; 3] {
+        self.turn(extents).map(f32::abs)
+    }
+
+    /// Scale is relative to an object's own size, so it isn't converted to target units.
+    #[must_use]
+    pub fn transform(&self, transform: &Transform3D) -> Transform3D {
+        Transform3D {
+            position: self.position(transform.position),
+            rotation: self.rotation(transform.rotation),
+            scale: self.extents(transform.scale),
+        }
+    }
+
+    #[must_use]
+    pub fn path(&self, path: &PathProperties) -> PathProperties {
+        let mut path = path.clone();
+        for node in &mut path.nodes {
+            node.position = self.position(node.position);
+        }
+        path
+    }
+
+    #[must_use]
+    pub fn zone_shape(&self, shape: &ZoneShape) -> ZoneShape {
+        match shape {
+            ZoneShape::Box { half_extents } => ZoneShape::Box {
+                half_extents: self.extents(*half_extents).map(|h| self.length(h)),
+            },
+            ZoneShape::Sphere { radius } => ZoneShape::Sphere {
+                radius: self.length(*radius),
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn collider(&self, collider: &ColliderShape) -> ColliderShape {
+        match collider {
+            ColliderShape::Cuboid { half_extents } => ColliderShape::Cuboid {
+                half_extents: self.extents(*half_extents).map(|h| self.length(h)),
+            },
+            ColliderShape::Sphere { radius } => ColliderShape::Sphere {
+                radius: self.length(*radius),
+            },
+            ColliderShape::Capsule {
+                radius,
+                half_height,
+            } => ColliderShape::Capsule {
+                radius: self.length(*radius),
+                half_height: self.length(*half_height),
+            },
+            ColliderShape::Mesh => ColliderShape::Mesh,
+        }
+    }
+
+    #[must_use]
+    pub fn bounds(&self, bounds: &BoundingBox) -> BoundingBox {
+        let (a, b) = (self.position(bounds.min), self.position(bounds.max));
+        BoundingBox::new(
+            std::array::from_fn(|i| a[i].min(b[i])),
+            std::array::from_fn(|i| a[i].max(b[i])),
+        )
+    }
+
+    /// `level` with everything placed in it moved into these coordinates.
+    #[must_use]
+    pub fn level(&self, level: &LevelData) -> LevelData {
+        let mut level = level.clone();
+        let rescaled = (self.units_per_meter - 1.0).abs() > f32::EPSILON;
+        for obj in &mut level.objects {
+            if let Some(physics) = obj.physics.as_mut() {
+                // A collider fitted to the scale would no longer be in target units
+                if physics.collider.is_some() || rescaled {
+                    physics.collider =
+                        Some(self.collider(&physics.effective_collider(&obj.transform)));
+                }
+            }
+            obj.transform = self.transform(&obj.transform);
+            match obj.kind {
+                ObjectKind::Zone(ref mut zone) => {
+                    zone.shape = self.zone_shape(&zone.shape);
+                }
+                ObjectKind::Path(ref mut path) => *path = self.path(path),
+                _ => {}
+            }
+        }
+        level.bounds = self.bounds(&level.bounds);
+        for annotation in &mut level.annotations {
+            if let AnnotationAnchor::Position { ref mut position } = annotation.anchor {
+                *position = self.position(*position);
+            }
+        }
+        for bookmark in &mut level.camera_bookmarks {
+            bookmark.position = self.position(bookmark.position);
+            bookmark.rotation = self.rotation(bookmark.rotation);
+            bookmark.target = bookmark.target.map(|target| self.position(target));
+        }
+        level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spatial::OrientedBox;
+    use crate::testing::assert_close;
+
+    fn tilted() -> Transform3D {
+        let half = std::f32::consts::FRAC_PI_6;
+        // Turned about a tilted axis, so every component of the rotation is in play
+        let axis = [0.48, 0.6, 0.64];
+        Transform3D {
+            position: [1.0, 2.0, 3.0],
+            rotation: [
+                axis[0] * half.sin(),
+                axis[1] * half.sin(),
+                axis[2] * half.sin(),
+                half.cos(),
+            ],
+            scale: [1.0, 2.0, 3.0],
+        }
+    }
+
+    /// Checks that converting then rotating lands where rotating then converting does.
+    fn assert_axes_follow(
+        coordinates: &CoordinateSystem,
+        transform: &Transform3D,
+        map: impl Fn([f32; 3]) -> [f32; 3],
+    ) {
+        let converted = coordinates.transform(transform);
+        let axes = OrientedBox::from_transform(transform).axes();
+        let converted_axes = OrientedBox::from_transform(&converted).axes();
+        // The object's own axes are converted too, so they come out in another order
+        let order = coordinates.extents([0.0, 1.0, 2.0]);
+        for (j, axis) in axes.iter().enumerate() {
+            let mapped = map(*axis);
+            let k = order
+                .iter()
+                .position(|&i| (i - j as f32).abs() < 0.5)
+                .unwrap();
+            let converted = converted_axes[k];
+            let same = mapped
+                .iter()
+                .zip(converted)
+                .all(|(a, b)| (a - b).abs() < 1e-5);
+            let opposite = mapped
+                .iter()
+                .zip(converted)
+                .all(|(a, b)| (a + b).abs() < 1e-5);
+            assert!(same || opposite, "axis {}: {:?} {:?}", j, mapped, converted);
+        }
+    }
+
+    #[test]
+    fn left_handed_transforms_mirror_the_z_axis() {
+        let transform = tilted();
+        let unity = CoordinateSystem::UNITY.with_units_per_meter(100.0);
+        let converted = unity.transform(&transform);
+        assert_close(converted.position, [100.0, 200.0, -300.0]);
+        assert_close(converted.scale, transform.scale);
+        assert_axes_follow(&unity, &transform, |v| [v[0], v[1], -v[2]]);
+
+        let unchanged = CoordinateSystem::EDITOR.transform(&transform);
+        assert_close(unchanged.position, transform.position);
+        assert_close(unchanged.rotation, transform.rotation);
+    }
+
+    #[test]
+    fn z_up_turns_depth_into_height() {
+        let transform = tilted();
+        let z_up = CoordinateSystem {
+            up: UpAxis::Z,
+            forward: Forward::Positive,
+            origin: [1.0, 0.0, 0.0],
+            ..CoordinateSystem::EDITOR
+        };
+        let converted = z_up.transform(&transform);
+        // As Blender imports glTF: facing -Z becomes facing +Y, keeping X
+        assert_close(converted.position, [0.0, -3.0, 2.0]);
+        assert_close(converted.scale, [1.0, 3.0, 2.0]);
+        assert_axes_follow(&z_up, &transform, |v| [v[0], -v[2], v[1]]);
+    }
+}
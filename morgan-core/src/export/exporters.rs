@@ -0,0 +1,2288 @@
+use crate::export::collision::collision_export;
+use crate::export::writers::{FormatWriter, WriteContext, WriterCapabilities, WriterRegistry};
+use crate::export::{CoordinateSystem, ExportFormat, ExportOptions, GltfMeshMode};
+use crate::level::doors::DoorOpenDirection;
+use crate::level::paths::PathMode;
+use crate::level::physics::{BodyType, ColliderShape};
+use crate::level::spawns::SpawnPointProperties;
+use crate::level::zones::ZoneShape;
+use crate::spatial::{BoundingBox, OrientedBox};
+use crate::stable;
+use crate::{GameObject, LevelData, ObjectKind, Transform3D};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportResult {
+    pub exported_files: Vec<ExportedFile>,
+    pub total_objects: usize,
+    pub export_time_ms: u64,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Whether the export was stopped early; formats after the cancelled one are skipped
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+/// How far an export has got, reported as each format starts and finishes and every
+/// [`PROGRESS_INTERVAL`] objects in between.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProgress {
+    pub format: ExportFormat,
+    /// Formats finished before this one
+    pub formats_done: usize,
+    pub formats_total: usize,
+    /// Objects the format's writer has got through
+    pub objects_done: usize,
+    pub objects_total: usize,
+}
+
+/// Objects written between progress reports within a format.
+pub const PROGRESS_INTERVAL: usize = 500;
+
+pub type ExportProgressCallback = Box<dyn Fn(&ExportProgress) + Send + Sync>;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedFile {
+    pub format: ExportFormat,
+    pub file_path: String,
+    pub file_size: u64,
+    pub success: bool,
+}
+
+#[derive(Default)]
+pub struct LevelExporter {
+    deterministic: bool,
+    gltf_meshes: GltfMeshMode,
+    writers: WriterRegistry,
+    on_progress: Option<ExportProgressCallback>,
+    /// Checked between objects; once set the export stops
+    cancel: Option<Arc<AtomicBool>>,
+    /// Progress of the format being written
+    progress: Mutex<Option<ExportProgress>>,
+}
+
+impl LevelExporter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An exporter whose output only changes when the level does: no timestamps in
+    /// file names or contents, objects in a stable order and floats rounded.
+    #[must_use]
+    pub fn deterministic() -> Self {
+        Self {
+            deterministic: true,
+            ..Self::default()
+        }
+    }
+
+    /// Lays out glTF meshes with `mode` instead of giving every object its own.
+    #[must_use]
+    pub const fn with_gltf_meshes(mut self, mode: GltfMeshMode) -> Self {
+        self.gltf_meshes = mode;
+        self
+    }
+
+    /// Writes `writer`'s format with it, in place of any built-in writer for it.
+    #[must_use]
+    pub fn with_writer(mut self, writer: impl FormatWriter + 'static) -> Self {
+        self.writers.register(writer);
+        self
+    }
+
+    pub fn writers(&self) -> &WriterRegistry {
+        &self.writers
+    }
+
+    /// Calls `callback` as the export makes progress.
+    #[must_use]
+    pub fn with_progress(mut self, callback: ExportProgressCallback) -> Self {
+        self.on_progress = Some(callback);
+        self
+    }
+
+    /// Stops the export, without writing the format in progress, once `cancel` is set.
+    #[must_use]
+    pub fn with_cancel(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+    }
+
+    /// The progress of the format being written. Only a count can be left half
+    /// updated, so a poisoned lock is recovered rather than failing the export.
+    fn progress(&self) -> MutexGuard<'_, Option<ExportProgress>> {
+        self.progress.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Updates the progress of the format being written, reporting it if `update`
+    /// returns true. The callback runs after the lock is released.
+    fn update_progress(&self, update: impl FnOnce(&mut ExportProgress) -> bool) {
+        let report = self
+            .progress()
+            .as_mut()
+            .and_then(|progress| update(progress).then(|| progress.clone()));
+        if let (Some(report), Some(on_progress)) = (report, &self.on_progress) {
+            on_progress(&report);
+        }
+    }
+
+    /// Counts an object as written, failing if the export has been cancelled.
+    pub(crate) fn object_written(&self) -> Result<()> {
+        self.update_progress(|progress| {
+            progress.objects_done += 1;
+            progress.objects_done % PROGRESS_INTERVAL == 0
+        });
+        if self.is_cancelled() {
+            anyhow::bail!("Export cancelled");
+        }
+        Ok(())
+    }
+
+    pub(crate) fn to_json<T: Serialize>(&self, value: &T) -> serde_json::Result<String> {
+        if self.deterministic {
+            stable::to_stable_json(value)
+        } else {
+            serde_json::to_string_pretty(value)
+        }
+    }
+
+    #[tracing::instrument(
+        name = "export",
+        skip_all,
+        fields(objects = level_data.objects.len(), formats = formats.len())
+    )]
+    pub async fn export_multi_format(
+        &self,
+        level_data: &LevelData,
+        formats: &[ExportFormat],
+        output_path: &str,
+        options: &ExportOptions,
+    ) -> Result<ExportResult> {
+        let start_time = std::time::Instant::now();
+        let filtered = options.filter.apply(level_data);
+        let level_data = &if self.deterministic {
+            stable::stable_level(&filtered)
+        } else {
+            filtered
+        };
+        let base_path = Path::new(output_path);
+        let mut result = ExportResult {
+            exported_files: Vec::new(),
+            total_objects: level_data.objects.len(),
+            export_time_ms: 0,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            cancelled: false,
+        };
+        if level_data.objects.is_empty() && !options.filter.is_empty() {
+            result
+                .warnings
+                .push("No objects match the export filter".to_string());
+        }
+
+        // Ensure output directory exists
+        if let Some(parent) = base_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        for (index, format) in formats.iter().enumerate() {
+            if self.is_cancelled() {
+                result.cancelled = true;
+                break;
+            }
+            let Some(writer) = self.writers.get(format) else {
+                result
+                    .errors
+                    .push(format!("No writer registered for {:?}", format));
+                continue;
+            };
+            *self.progress() = Some(ExportProgress {
+                format: format.clone(),
+                formats_done: index,
+                formats_total: formats.len(),
+                objects_done: 0,
+                objects_total: level_data.objects.len(),
+            });
+            self.update_progress(|_| true);
+
+            let file_path =
+                self.get_export_file_path(base_path, &writer.file_extension(), &level_data.name)?;
+            let native = writer.coordinate_system();
+            let level_data = &options.prepare(level_data, native);
+            result
+                .warnings
+                .extend(writer.capabilities().warnings(format, level_data));
+
+            let context = WriteContext {
+                exporter: self,
+                options,
+                coordinates: options.coordinates(native),
+            };
+            let export_result = tracing::info_span!("export_format", format = ?format)
+                .in_scope(|| writer.write(&context, level_data, &file_path));
+
+            match export_result {
+                Ok(()) => {
+                    let file_size = fs::metadata(&file_path)?.len();
+                    result.exported_files.push(ExportedFile {
+                        format: format.clone(),
+                        file_path: file_path.to_string_lossy().to_string(),
+                        file_size,
+                        success: true,
+                    });
+                    info!("Exported to: {:?}", file_path);
+                }
+                Err(_) if self.is_cancelled() => {
+                    info!("Export cancelled during {:?}", format);
+                    result.cancelled = true;
+                    break;
+                }
+                Err(e) => {
+                    result
+                        .errors
+                        .push(format!("Failed to export {:?}: {}", format, e));
+                    result.exported_files.push(ExportedFile {
+                        format: format.clone(),
+                        file_path: file_path.to_string_lossy().to_string(),
+                        file_size: 0,
+                        success: false,
+                    });
+                }
+            }
+            self.update_progress(|progress| {
+                progress.formats_done += 1;
+                progress.objects_done = progress.objects_total;
+                true
+            });
+        }
+
+        *self.progress() = None;
+        result.export_time_ms = start_time.elapsed().as_millis() as u64;
+        Ok(result)
+    }
+
+    fn get_export_file_path(
+        &self,
+        base_path: &Path,
+        extension: &str,
+        level_name: &str,
+    ) -> Result<PathBuf> {
+        let safe_level_name = level_name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>()
+            .to_lowercase();
+
+        let parent = base_path.parent().unwrap_or_else(|| Path::new("."));
+        let file_name = if self.deterministic {
+            format!("{}.{}", safe_level_name, extension)
+        } else {
+            format!(
+                "{}_{}.{}",
+                safe_level_name,
+                Utc::now().format("%Y%m%d_%H%M%S"),
+                extension
+            )
+        };
+        Ok(parent.join(file_name))
+    }
+
+    fn convert_to_bevy_format(&self, level_data: &LevelData) -> Result<BevyLevelData> {
+        let mut bevy_entities = Vec::new();
+
+        for obj in &level_data.objects {
+            self.object_written()?;
+            bevy_entities.push(BevyEntity {
+                name: obj.name.clone(),
+                transform: BevyTransform {
+                    translation: obj.transform.position,
+                    rotation: obj.transform.rotation,
+                    scale: obj.transform.scale,
+                },
+                mesh: obj.mesh.clone(),
+                material: obj.material.clone(),
+                layer: obj.layer.clone(),
+                tags: obj.tags.clone(),
+                components: self.bevy_components(obj),
+            });
+        }
+
+        Ok(BevyLevelData {
+            name: level_data.name.clone(),
+            entities: bevy_entities,
+            bounds: level_data.bounds.clone(),
+            metadata: Some(BevyMetadata {
+                generation_seed: level_data.generation_seed,
+                generator: "BSP".to_string(),
+                version: "0.1.0".to_string(),
+            }),
+        })
+    }
+
+    /// Gameplay components for an object in the RON scene format
+    fn bevy_components(&self, obj: &GameObject) -> Vec<BevyComponent> {
+        let mut components = Vec::new();
+
+        if let ObjectKind::Zone(ref zone) = obj.kind {
+            components.push(BevyComponent::TriggerZone {
+                shape: match zone.shape {
+                    ZoneShape::Box { half_extents } => BevyZoneShape::Cuboid { half_extents },
+                    ZoneShape::Sphere { radius } => BevyZoneShape::Sphere { radius },
+                },
+                zone_type: zone.zone_type.label().to_string(),
+                targets: zone.targets.clone(),
+                trigger_once: zone.trigger_once,
+            });
+        }
+
+        if let ObjectKind::SpawnPoint(ref spawn) = obj.kind {
+            components.push(match spawn {
+                SpawnPointProperties::PlayerStart => BevyComponent::PlayerStart,
+                SpawnPointProperties::EnemySpawner {
+                    enemy_kind,
+                    count,
+                    interval_secs,
+                } => BevyComponent::EnemySpawner {
+                    enemy_kind: enemy_kind.clone(),
+                    count: *count,
+                    interval_secs: *interval_secs,
+                },
+                SpawnPointProperties::ItemSpawn {
+                    item_kind,
+                    respawn_secs,
+                } => BevyComponent::ItemSpawn {
+                    item_kind: item_kind.clone(),
+                    respawn_secs: *respawn_secs,
+                },
+                SpawnPointProperties::LevelExit { target_level } => BevyComponent::LevelExit {
+                    target_level: target_level.clone(),
+                },
+            });
+        }
+
+        if let ObjectKind::Door(ref door) = obj.kind {
+            components.push(BevyComponent::Door {
+                locked: door.locked,
+                key_id: door.key_id.clone(),
+                open_direction: door.open_direction,
+                auto_close_secs: door.auto_close_secs,
+                linked_switch_id: door.linked_switch_id.clone(),
+            });
+        }
+
+        if let ObjectKind::Path(ref path) = obj.kind {
+            components.push(BevyComponent::PatrolPath {
+                waypoints: path.nodes.iter().map(|n| n.position).collect(),
+                wait_secs: path.nodes.iter().map(|n| n.wait_secs).collect(),
+                mode: path.mode,
+            });
+        }
+
+        if let Some(ref physics) = obj.physics {
+            components.push(BevyComponent::PhysicsBody {
+                body_type: physics.body_type,
+                collider: match physics.effective_collider(&obj.transform) {
+                    ColliderShape::Cuboid { half_extents } => BevyCollider::Cuboid { half_extents },
+                    ColliderShape::Sphere { radius } => BevyCollider::Sphere { radius },
+                    ColliderShape::Capsule {
+                        radius,
+                        half_height,
+                    } => BevyCollider::Capsule {
+                        radius,
+                        half_height,
+                    },
+                    ColliderShape::Mesh => BevyCollider::TriMesh,
+                },
+                mass: physics.mass,
+                friction: physics.friction,
+                sensor: physics.sensor,
+            });
+        }
+
+        components
+    }
+
+    /// Gameplay component expressions for an object in generated Rust code
+    fn rust_components(&self, obj: &GameObject) -> Vec<String> {
+        let mut components = Vec::new();
+
+        if let ObjectKind::Zone(ref zone) = obj.kind {
+            let shape = match zone.shape {
+                ZoneShape::Box { half_extents } => format!(
+                    "TriggerShape::Cuboid {{ half_extents: Vec3::new({:.2}, {:.2}, {:.2}) }}",
+                    half_extents[0], half_extents[1], half_extents[2]
+                ),
+                ZoneShape::Sphere { radius } => {
+                    format!("TriggerShape::Sphere {{ radius: {:.2} }}", radius)
+                }
+            };
+            let targets = zone
+                .targets
+                .iter()
+                .map(|t| format!("{:?}.to_string()", t))
+                .collect::<Vec<_>>()
+                .join(", ");
+            components.push(format!(
+                "TriggerZone {{\n            shape: {},\n            zone_type: {:?}.to_string(),\n            targets: vec![{}],\n            trigger_once: {},\n        }}",
+                shape,
+                zone.zone_type.label(),
+                targets,
+                zone.trigger_once
+            ));
+        }
+
+        if let ObjectKind::SpawnPoint(ref spawn) = obj.kind {
+            components.push(match spawn {
+                SpawnPointProperties::PlayerStart => "PlayerStart".to_string(),
+                SpawnPointProperties::EnemySpawner {
+                    enemy_kind,
+                    count,
+                    interval_secs,
+                } => format!(
+                    "EnemySpawner {{ enemy_kind: {:?}.to_string(), count: {}, interval_secs: {:.2} }}",
+                    enemy_kind, count, interval_secs
+                ),
+                SpawnPointProperties::ItemSpawn {
+                    item_kind,
+                    respawn_secs,
+                } => format!(
+                    "ItemSpawn {{ item_kind: {:?}.to_string(), respawn_secs: {} }}",
+                    item_kind,
+                    respawn_secs
+                        .map(|s| format!("Some({:.2})", s))
+                        .unwrap_or_else(|| "None".to_string())
+                ),
+                SpawnPointProperties::LevelExit { target_level } => format!(
+                    "LevelExit {{ target_level: {} }}",
+                    target_level
+                        .as_ref()
+                        .map(|t| format!("Some({:?}.to_string())", t))
+                        .unwrap_or_else(|| "None".to_string())
+                ),
+            });
+        }
+
+        if let ObjectKind::Door(ref door) = obj.kind {
+            let direction = match door.open_direction {
+                DoorOpenDirection::Both => "Both",
+                DoorOpenDirection::Inward => "Inward",
+                DoorOpenDirection::Outward => "Outward",
+                DoorOpenDirection::Sliding => "Sliding",
+            };
+            let optional_string = |value: &Option<String>| {
+                value
+                    .as_ref()
+                    .map(|v| format!("Some({:?}.to_string())", v))
+                    .unwrap_or_else(|| "None".to_string())
+            };
+            components.push(format!(
+                "Door {{\n            locked: {},\n            key_id: {},\n            open_direction: DoorOpenDirection::{},\n            auto_close_secs: {},\n            linked_switch_id: {},\n        }}",
+                door.locked,
+                optional_string(&door.key_id),
+                direction,
+                door.auto_close_secs
+                    .map(|s| format!("Some({:.2})", s))
+                    .unwrap_or_else(|| "None".to_string()),
+                optional_string(&door.linked_switch_id)
+            ));
+        }
+
+        if let ObjectKind::Path(ref path) = obj.kind {
+            let waypoints = path
+                .nodes
+                .iter()
+                .map(|n| {
+                    format!(
+                        "Vec3::new({:.2}, {:.2}, {:.2})",
+                        n.position[0], n.position[1], n.position[2]
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let wait_secs = path
+                .nodes
+                .iter()
+                .map(|n| format!("{:.2}", n.wait_secs))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mode = match path.mode {
+                PathMode::Once => "Once",
+                PathMode::Loop => "Loop",
+                PathMode::PingPong => "PingPong",
+            };
+            components.push(format!(
+                "PatrolPath {{\n            waypoints: vec![{}],\n            wait_secs: vec![{}],\n            mode: PatrolMode::{},\n        }}",
+                waypoints, wait_secs, mode
+            ));
+        }
+
+        if let Some(ref physics) = obj.physics {
+            let body_type = match physics.body_type {
+                BodyType::Static => "Static",
+                BodyType::Dynamic => "Dynamic",
+                BodyType::Kinematic => "Kinematic",
+            };
+            let collider = match physics.effective_collider(&obj.transform) {
+                ColliderShape::Cuboid { half_extents } => format!(
+                    "PhysicsCollider::Cuboid {{ half_extents: Vec3::new({:.2}, {:.2}, {:.2}) }}",
+                    half_extents[0], half_extents[1], half_extents[2]
+                ),
+                ColliderShape::Sphere { radius } => {
+                    format!("PhysicsCollider::Sphere {{ radius: {:.2} }}", radius)
+                }
+                ColliderShape::Capsule {
+                    radius,
+                    half_height,
+                } => format!(
+                    "PhysicsCollider::Capsule {{ radius: {:.2}, half_height: {:.2} }}",
+                    radius, half_height
+                ),
+                ColliderShape::Mesh => "PhysicsCollider::TriMesh".to_string(),
+            };
+            components.push(format!(
+                "PhysicsBody {{\n            body_type: PhysicsBodyType::{},\n            collider: {},\n            mass: {},\n            friction: {:.2},\n            sensor: {},\n        }}",
+                body_type,
+                collider,
+                physics
+                    .mass
+                    .map(|m| format!("Some({:.2})", m))
+                    .unwrap_or_else(|| "None".to_string()),
+                physics.friction,
+                physics.sensor
+            ));
+        }
+
+        components
+    }
+
+    /// Component type definitions needed by the generated spawn code
+    fn rust_component_definitions(&self, level_data: &LevelData) -> String {
+        let mut code = String::new();
+
+        if level_data
+            .objects
+            .iter()
+            .any(|o| matches!(o.kind, ObjectKind::Zone(_)))
+        {
+            code.push_str("#[derive(Debug, Clone)]\n");
+            code.push_str("pub enum TriggerShape {\n");
+            code.push_str("    Cuboid { half_extents: Vec3 },\n");
+            code.push_str("    Sphere { radius: f32 },\n");
+            code.push_str("}\n\n");
+            code.push_str("#[derive(Component, Debug, Clone)]\n");
+            code.push_str("pub struct TriggerZone {\n");
+            code.push_str("    pub shape: TriggerShape,\n");
+            code.push_str("    pub zone_type: String,\n");
+            code.push_str("    pub targets: Vec<String>,\n");
+            code.push_str("    pub trigger_once: bool,\n");
+            code.push_str("}\n\n");
+        }
+
+        if level_data
+            .objects
+            .iter()
+            .any(|o| matches!(o.kind, ObjectKind::SpawnPoint(_)))
+        {
+            code.push_str("#[derive(Component, Debug, Clone, Default)]\n");
+            code.push_str("pub struct PlayerStart;\n\n");
+            code.push_str("#[derive(Component, Debug, Clone)]\n");
+            code.push_str("pub struct EnemySpawner {\n");
+            code.push_str("    pub enemy_kind: String,\n");
+            code.push_str("    pub count: u32,\n");
+            code.push_str("    pub interval_secs: f32,\n");
+            code.push_str("}\n\n");
+            code.push_str("#[derive(Component, Debug, Clone)]\n");
+            code.push_str("pub struct ItemSpawn {\n");
+            code.push_str("    pub item_kind: String,\n");
+            code.push_str("    pub respawn_secs: Option<f32>,\n");
+            code.push_str("}\n\n");
+            code.push_str("#[derive(Component, Debug, Clone)]\n");
+            code.push_str("pub struct LevelExit {\n");
+            code.push_str("    pub target_level: Option<String>,\n");
+            code.push_str("}\n\n");
+        }
+
+        if level_data
+            .objects
+            .iter()
+            .any(|o| matches!(o.kind, ObjectKind::Door(_)))
+        {
+            code.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq)]\n");
+            code.push_str("pub enum DoorOpenDirection {\n");
+            code.push_str("    Both,\n");
+            code.push_str("    Inward,\n");
+            code.push_str("    Outward,\n");
+            code.push_str("    Sliding,\n");
+            code.push_str("}\n\n");
+            code.push_str("#[derive(Component, Debug, Clone)]\n");
+            code.push_str("pub struct Door {\n");
+            code.push_str("    pub locked: bool,\n");
+            code.push_str("    pub key_id: Option<String>,\n");
+            code.push_str("    pub open_direction: DoorOpenDirection,\n");
+            code.push_str("    pub auto_close_secs: Option<f32>,\n");
+            code.push_str("    pub linked_switch_id: Option<String>,\n");
+            code.push_str("}\n\n");
+        }
+
+        if level_data
+            .objects
+            .iter()
+            .any(|o| matches!(o.kind, ObjectKind::Path(_)))
+        {
+            code.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq)]\n");
+            code.push_str("pub enum PatrolMode {\n");
+            code.push_str("    Once,\n");
+            code.push_str("    Loop,\n");
+            code.push_str("    PingPong,\n");
+            code.push_str("}\n\n");
+            code.push_str("#[derive(Component, Debug, Clone)]\n");
+            code.push_str("pub struct PatrolPath {\n");
+            code.push_str("    pub waypoints: Vec<Vec3>,\n");
+            code.push_str("    pub wait_secs: Vec<f32>,\n");
+            code.push_str("    pub mode: PatrolMode,\n");
+            code.push_str("}\n\n");
+        }
+
+        if level_data.objects.iter().any(|o| o.physics.is_some()) {
+            code.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq)]\n");
+            code.push_str("pub enum PhysicsBodyType {\n");
+            code.push_str("    Static,\n");
+            code.push_str("    Dynamic,\n");
+            code.push_str("    Kinematic,\n");
+            code.push_str("}\n\n");
+            code.push_str("#[derive(Debug, Clone)]\n");
+            code.push_str("pub enum PhysicsCollider {\n");
+            code.push_str("    Cuboid { half_extents: Vec3 },\n");
+            code.push_str("    Sphere { radius: f32 },\n");
+            code.push_str("    Capsule { radius: f32, half_height: f32 },\n");
+            code.push_str("    TriMesh,\n");
+            code.push_str("}\n\n");
+            code.push_str("#[derive(Component, Debug, Clone)]\n");
+            code.push_str("pub struct PhysicsBody {\n");
+            code.push_str("    pub body_type: PhysicsBodyType,\n");
+            code.push_str("    pub collider: PhysicsCollider,\n");
+            code.push_str("    pub mass: Option<f32>,\n");
+            code.push_str("    pub friction: f32,\n");
+            code.push_str("    pub sensor: bool,\n");
+            code.push_str("}\n\n");
+        }
+
+        code
+    }
+
+    /// Bevy code spawning `level_data` for the Bevy release `options` target, with
+    /// transforms written to their precision if given.
+    fn generate_rust_code(
+        &self,
+        level_data: &LevelData,
+        options: &ExportOptions,
+    ) -> Result<String> {
+        let digits = options.precision.map_or(2, usize::from);
+        let rotation_digits = options.precision.map_or(4, usize::from);
+        let vec3 = |v: [f32; 3]| {
+            format!(
+                "Vec3::new({:.*}, {:.*}, {:.*})",
+                digits, v[0], digits, v[1], digits, v[2]
+            )
+        };
+        // A transform expression, its method calls indented under `indent`
+        let transform = |transform: &Transform3D, indent: &str| {
+            let rotation = transform
+                .rotation
+                .map(|c| format!("{:.*}", rotation_digits, c));
+            format!(
+                "Transform::from_translation({})\n{indent}    .with_rotation(Quat::from_xyzw({}))\n{indent}    .with_scale({})",
+                vec3(transform.position),
+                rotation.join(", "),
+                vec3(transform.scale),
+            )
+        };
+        let markers: BTreeMap<&str, String> = if options.tag_components {
+            level_data
+                .objects
+                .iter()
+                .flat_map(|obj| &obj.tags)
+                .filter_map(|tag| Some((tag.as_str(), tag_component(tag)?)))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+        let mut code = String::new();
+
+        // File header
+        code.push_str("// Generated level code for Bevy\n");
+        code.push_str(&format!(
+            "// This file was auto-generated by Morgan-Bevy Level Editor for Bevy {}\n\n",
+            options.bevy_version.label()
+        ));
+        code.push_str("use bevy::prelude::*;\n\n");
+        code.push_str(&self.rust_component_definitions(level_data));
+        // Tags differing only in case or punctuation share a marker
+        let mut defined = HashSet::new();
+        for marker in markers.values().filter(|marker| defined.insert(*marker)) {
+            code.push_str("#[derive(Component, Debug, Clone, Copy, Default)]\n");
+            code.push_str(&format!("pub struct {};\n\n", marker));
+        }
+
+        // Function signature
+        code.push_str(&format!(
+            "pub fn spawn_level_{}(commands: &mut Commands, asset_server: &Res<AssetServer>) {{\n",
+            level_data.name.to_lowercase().replace(' ', "_")
+        ));
+
+        // Spawn each object
+        for obj in &level_data.objects {
+            self.object_written()?;
+            code.push_str(&format!("    // {}\n    commands.spawn((\n", obj.name));
+
+            let material = obj.material.as_deref().unwrap_or("materials/default.mat");
+            match (&obj.mesh, options.bevy_version.required_components()) {
+                // Mesh3d brings in Transform and Visibility itself
+                (Some(mesh), true) => {
+                    code.push_str(&format!(
+                        "        Mesh3d(asset_server.load(\"{}\")),\n",
+                        mesh
+                    ));
+                    code.push_str(&format!(
+                        "        MeshMaterial3d::<StandardMaterial>(asset_server.load(\"{}\")),\n",
+                        material
+                    ));
+                    code.push_str(&format!(
+                        "        {},\n",
+                        transform(&obj.transform, "        ")
+                    ));
+                }
+                (None, true) => {
+                    code.push_str(&format!(
+                        "        {},\n",
+                        transform(&obj.transform, "        ")
+                    ));
+                }
+                // Bundles carry their own Transform, which a second one would clash with
+                (Some(mesh), false) => {
+                    code.push_str(&format!(
+                        "        PbrBundle {{\n            mesh: asset_server.load(\"{}\"),\n",
+                        mesh
+                    ));
+                    code.push_str(&format!(
+                        "            material: asset_server.load(\"{}\"),\n",
+                        material
+                    ));
+                    code.push_str(&format!(
+                        "            transform: {},\n",
+                        transform(&obj.transform, "            ")
+                    ));
+                    code.push_str("            ..default()\n        },\n");
+                }
+                (None, false) => {
+                    code.push_str(&format!(
+                        "        SpatialBundle::from_transform({}),\n",
+                        transform(&obj.transform, "        ")
+                    ));
+                }
+            }
+
+            // Name component
+            code.push_str(&format!("        Name::new(\"{}\"),\n", obj.name));
+
+            // Gameplay components
+            for component in self.rust_components(obj) {
+                code.push_str(&format!("        {},\n", component));
+            }
+
+            for tag in &obj.tags {
+                match markers.get(tag.as_str()) {
+                    Some(marker) => code.push_str(&format!("        {},\n", marker)),
+                    None => code.push_str(&format!("        // Tag: {}\n", tag)),
+                }
+            }
+
+            code.push_str("    ));\n\n");
+        }
+
+        // Function footer
+        code.push_str("}\n\n");
+
+        // Add convenience function for level bounds
+        code.push_str(&format!(
+            "pub fn get_level_{}_bounds() -> (Vec3, Vec3) {{\n",
+            level_data.name.to_lowercase().replace(' ', "_")
+        ));
+        code.push_str(&format!(
+            "    ({}, {})\n",
+            vec3(level_data.bounds.min),
+            vec3(level_data.bounds.max)
+        ));
+        code.push_str("}\n");
+
+        Ok(code)
+    }
+
+    /// The glTF document for `level_data`, with the contents of the buffer it reads
+    /// its geometry from at `buffer_uri`.
+    fn convert_to_gltf_format(
+        &self,
+        level_data: &LevelData,
+        buffer_uri: &str,
+    ) -> Result<(GltfDocument, Vec<u8>)> {
+        let mut gltf = GltfDocument {
+            asset: GltfAsset {
+                version: "2.0".to_string(),
+                generator: Some("Morgan-Bevy Level Editor".to_string()),
+            },
+            scene: Some(0),
+            scenes: Vec::new(),
+            nodes: Vec::new(),
+            meshes: Vec::new(),
+            materials: Vec::new(),
+            buffers: Vec::new(),
+            buffer_views: Vec::new(),
+            accessors: Vec::new(),
+        };
+        let mut geometry = GltfGeometry::default();
+        let (cube_positions, cube_indices) = geometry.add_triangles(&CUBE_CORNERS, &CUBE_INDICES);
+        let shared = self.gltf_meshes != GltfMeshMode::PerObject;
+        // Shared meshes by tile type and material, and materials by name
+        let mut meshes: HashMap<(String, String), usize> = HashMap::new();
+        let mut materials: HashMap<String, usize> = HashMap::new();
+        // Corners of the static geometry to combine, by material
+        let mut merged: BTreeMap<String, Vec<[f32; 3]>> = BTreeMap::new();
+
+        for obj in &level_data.objects {
+            self.object_written()?;
+            let transform_matrix = self.create_transform_matrix(&obj.transform);
+
+            // Gameplay and physics data travel in node extras
+            let extras = self.object_extras(obj);
+            let extras = (!extras.is_empty()).then_some(serde_json::Value::Object(extras));
+
+            // Objects without geometry become empty nodes
+            if !obj.kind.has_geometry() {
+                gltf.nodes.push(GltfNode {
+                    name: Some(obj.name.clone()),
+                    mesh: None,
+                    matrix: Some(transform_matrix),
+                    extras,
+                });
+                continue;
+            }
+
+            let material_name = obj
+                .material
+                .clone()
+                .unwrap_or_else(|| "default".to_string());
+            // Plain meshes without physics never move, so can be baked together
+            if self.gltf_meshes == GltfMeshMode::Merged
+                && matches!(obj.kind, ObjectKind::Mesh)
+                && extras.is_none()
+            {
+                merged.entry(material_name).or_default().extend(
+                    CUBE_CORNERS
+                        .iter()
+                        .map(|&corner| transform_point(&transform_matrix, corner)),
+                );
+                continue;
+            }
+
+            let material = if shared {
+                *materials
+                    .entry(material_name.clone())
+                    .or_insert_with(|| gltf.push_material(material_name.clone()))
+            } else {
+                gltf.push_material(material_name.clone())
+            };
+            let primitive = GltfPrimitive {
+                mode: 4, // TRIANGLES
+                material: Some(material),
+                attributes: GltfAttributes {
+                    position: cube_positions,
+                },
+                indices: Some(cube_indices),
+            };
+            let mesh = if shared {
+                let tile_type = obj
+                    .mesh
+                    .as_deref()
+                    .or_else(|| obj.metadata.get("tile_type")?.as_str())
+                    .unwrap_or("cube")
+                    .to_string();
+                *meshes
+                    .entry((tile_type.clone(), material_name))
+                    .or_insert_with(|| gltf.push_mesh(tile_type, primitive))
+            } else {
+                gltf.push_mesh(obj.name.clone(), primitive)
+            };
+            gltf.nodes.push(GltfNode {
+                name: Some(obj.name.clone()),
+                mesh: Some(mesh),
+                matrix: Some(transform_matrix),
+                extras,
+            });
+        }
+
+        for (material_name, corners) in merged {
+            let material = *materials
+                .entry(material_name.clone())
+                .or_insert_with(|| gltf.push_material(material_name.clone()));
+            let indices: Vec<u32> = (0..corners.len() as u32 / 8)
+                .flat_map(|cube| CUBE_INDICES.map(|index| cube * 8 + index))
+                .collect();
+            let (position, indices) = geometry.add_triangles(&corners, &indices);
+            let mesh = gltf.push_mesh(
+                format!("merged_{}", material_name),
+                GltfPrimitive {
+                    mode: 4, // TRIANGLES
+                    material: Some(material),
+                    attributes: GltfAttributes { position },
+                    indices: Some(indices),
+                },
+            );
+            gltf.nodes.push(GltfNode {
+                name: Some(format!("Merged {}", material_name)),
+                mesh: Some(mesh),
+                matrix: None,
+                extras: None,
+            });
+        }
+
+        gltf.scenes.push(GltfScene {
+            name: Some(level_data.name.clone()),
+            nodes: (0..gltf.nodes.len()).collect(),
+        });
+        gltf.buffers.push(GltfBuffer {
+            uri: buffer_uri.to_string(),
+            byte_length: geometry.bytes.len(),
+        });
+        gltf.buffer_views = geometry.buffer_views;
+        gltf.accessors = geometry.accessors;
+        Ok((gltf, geometry.bytes))
+    }
+
+    /// Gameplay and physics data of an object that engines don't have a place for,
+    /// keyed by what it describes.
+    fn object_extras(&self, obj: &GameObject) -> serde_json::Map<String, serde_json::Value> {
+        let mut extras = serde_json::Map::new();
+        match obj.kind {
+            ObjectKind::Mesh => {}
+            ObjectKind::Zone(ref zone) => {
+                extras.insert("trigger_zone".to_string(), serde_json::json!(zone));
+            }
+            ObjectKind::SpawnPoint(ref spawn) => {
+                extras.insert("spawn_point".to_string(), serde_json::json!(spawn));
+            }
+            ObjectKind::Path(ref path) => {
+                extras.insert("patrol_path".to_string(), serde_json::json!(path));
+            }
+            ObjectKind::Door(ref door) => {
+                extras.insert("door".to_string(), serde_json::json!(door));
+            }
+        }
+        if let Some(ref physics) = obj.physics {
+            extras.insert(
+                "physics".to_string(),
+                serde_json::json!({
+                    "body_type": physics.body_type,
+                    "collider": physics.effective_collider(&obj.transform),
+                    "mass": physics.mass,
+                    "friction": physics.friction,
+                    "sensor": physics.sensor,
+                }),
+            );
+        }
+        extras
+    }
+
+    /// A manifest for a Unity editor script to build the level from: the meshes and
+    /// materials to import, and each object's transform and gameplay data. The level
+    /// is expected to be in `coordinates` already, normally Unity's left-handed ones.
+    fn convert_to_unity_format(
+        &self,
+        level_data: &LevelData,
+        coordinates: CoordinateSystem,
+    ) -> UnityManifest {
+        let objects = level_data
+            .objects
+            .iter()
+            .map(|obj| UnityObject {
+                id: obj.id.clone(),
+                name: obj.name.clone(),
+                layer: obj.layer.clone(),
+                tags: obj.tags.clone(),
+                position: obj.transform.position,
+                rotation: obj.transform.rotation,
+                scale: obj.transform.scale,
+                mesh: obj.mesh.clone().filter(|_| obj.kind.has_geometry()),
+                material: obj.material.clone().filter(|_| obj.kind.has_geometry()),
+                is_static: matches!(obj.kind, ObjectKind::Mesh)
+                    && obj
+                        .physics
+                        .as_ref()
+                        .is_none_or(|physics| physics.body_type == BodyType::Static),
+                components: self.object_extras(obj),
+            })
+            .collect::<Vec<_>>();
+
+        let assets = |pick: fn(&UnityObject) -> Option<&String>| {
+            objects
+                .iter()
+                .filter_map(pick)
+                .cloned()
+                .collect::<std::collections::BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        };
+        UnityManifest {
+            format: UNITY_MANIFEST_FORMAT,
+            version: 1,
+            level_id: level_data.id.clone(),
+            level_name: level_data.name.clone(),
+            coordinate_system: coordinates,
+            layers: level_data.layers.clone(),
+            meshes: assets(|obj| obj.mesh.as_ref()),
+            materials: assets(|obj| obj.material.as_ref()),
+            objects,
+        }
+    }
+
+    fn create_transform_matrix(&self, transform: &Transform3D) -> [f32; 16] {
+        // Column-major: the rotated and scaled axes, then the translation
+        let axes = OrientedBox::from_transform(transform).axes();
+        let mut matrix = [0.0; 16];
+        for (column, (axis, scale)) in axes.iter().zip(transform.scale).enumerate() {
+            for (row, value) in axis.iter().enumerate() {
+                matrix[column * 4 + row] = value * scale;
+            }
+        }
+        matrix[12..15].copy_from_slice(&transform.position);
+        matrix[15] = 1.0;
+        matrix
+    }
+
+    fn generate_fbx_ascii(&self, level_data: &LevelData) -> Result<String> {
+        let mut fbx_content = String::new();
+
+        // FBX ASCII header
+        fbx_content.push_str("; FBX 7.7.0 project file\n");
+        fbx_content.push_str("; Generated by Morgan-Bevy Level Editor\n\n");
+
+        fbx_content.push_str("FBXHeaderExtension:  {\n");
+        fbx_content.push_str("    FBXHeaderVersion: 1004\n");
+        fbx_content.push_str("    FBXVersion: 7700\n");
+        fbx_content.push_str("    EncryptionType: 0\n");
+        fbx_content.push_str("}\n\n");
+
+        // Creation time
+        fbx_content.push_str("CreationTime: \"2025-11-24 00:00:00:000\"\n");
+        fbx_content.push_str(&format!(
+            "Creator: \"Morgan-Bevy Level Editor - {}\"\n\n",
+            level_data.name
+        ));
+
+        // Objects section
+        fbx_content.push_str("Objects:  {\n");
+
+        for (id, obj) in level_data.objects.iter().enumerate() {
+            self.object_written()?;
+            // Model object
+            fbx_content.push_str(&format!(
+                "    Model: {}, \"Model::{}\", \"Mesh\" {{\n",
+                id * 3 + 1,
+                obj.name
+            ));
+            fbx_content.push_str("        Version: 232\n");
+            fbx_content.push_str("        Properties70:  {\n");
+            fbx_content.push_str(&format!(
+                "            P: \"Lcl Translation\", \"Lcl Translation\", \"\", \"A\",{},{},{}\n",
+                obj.transform.position[0], obj.transform.position[1], obj.transform.position[2]
+            ));
+            fbx_content.push_str(&format!(
+                "            P: \"Lcl Scaling\", \"Lcl Scaling\", \"\", \"A\",{},{},{}\n",
+                obj.transform.scale[0], obj.transform.scale[1], obj.transform.scale[2]
+            ));
+            fbx_content.push_str("        }\n");
+            fbx_content.push_str("        MultiLayer: 0\n");
+            fbx_content.push_str("        MultiTake: 0\n");
+            fbx_content.push_str("        Shading: T\n");
+            fbx_content.push_str("        Culling: \"CullingOff\"\n");
+            fbx_content.push_str("    }\n");
+
+            // Geometry object (simplified cube for demonstration)
+            fbx_content.push_str(&format!(
+                "    Geometry: {}, \"Geometry::{}_Geometry\", \"Mesh\" {{\n",
+                id * 3 + 2,
+                obj.name
+            ));
+            fbx_content.push_str("        Vertices: *24 {\n");
+            fbx_content.push_str(
+                "            a: -1,-1,-1,1,-1,-1,1,1,-1,-1,1,-1,-1,-1,1,1,-1,1,1,1,1,-1,1,1\n",
+            );
+            fbx_content.push_str("        }\n");
+            fbx_content.push_str("        PolygonVertexIndex: *24 {\n");
+            fbx_content
+                .push_str("            a: 0,1,2,-4,4,7,6,-6,0,4,5,-2,2,6,7,-4,0,3,7,-5,2,1,5,-7\n");
+            fbx_content.push_str("        }\n");
+            fbx_content.push_str("    }\n");
+
+            // Material object
+            fbx_content.push_str(&format!(
+                "    Material: {}, \"Material::{}_Material\", \"\" {{\n",
+                id * 3 + 3,
+                obj.name
+            ));
+            fbx_content.push_str("        Version: 102\n");
+            fbx_content.push_str("        ShadingModel: \"lambert\"\n");
+            fbx_content.push_str("        MultiLayer: 0\n");
+            fbx_content.push_str("        Properties70:  {\n");
+            fbx_content.push_str("            P: \"DiffuseColor\", \"Color\", \"\", \"A\",1,1,1\n");
+            fbx_content.push_str("        }\n");
+            fbx_content.push_str("    }\n");
+        }
+
+        fbx_content.push_str("}\n\n");
+
+        // Connections section
+        fbx_content.push_str("Connections:  {\n");
+        for (id, _obj) in level_data.objects.iter().enumerate() {
+            fbx_content.push_str(&format!("    C: \"OO\",{},0\n", id * 3 + 1)); // Model to Scene
+            fbx_content.push_str(&format!("    C: \"OO\",{},{}\n", id * 3 + 2, id * 3 + 1)); // Geometry to Model
+            fbx_content.push_str(&format!("    C: \"OO\",{},{}\n", id * 3 + 3, id * 3 + 1));
+            // Material to Model
+        }
+        fbx_content.push_str("}\n");
+
+        Ok(fbx_content)
+    }
+
+    /// A Godot 4 text scene: a node for the level, one beneath it per layer and the
+    /// layer's objects beneath that. Meshes and materials are external resources under
+    /// `res://`, imported models such as glTF files are instanced as scenes, and objects
+    /// without a mesh get a box. Gameplay data goes in node metadata.
+    fn generate_godot_scene(&self, level_data: &LevelData) -> Result<String> {
+        let mut resources = GodotResources::default();
+        let mut nodes = String::new();
+        let mut layer_names = GodotNames::default();
+        let root = layer_names.unique(&level_data.name);
+        nodes.push_str(&format!("[node name=\"{}\" type=\"Node3D\"]\n", root));
+
+        let mut layers: Vec<&str> = level_data.layers.iter().map(String::as_str).collect();
+        for obj in &level_data.objects {
+            if !layers.contains(&obj.layer.as_str()) {
+                layers.push(&obj.layer);
+            }
+        }
+        for layer in layers {
+            let objects: Vec<&GameObject> = level_data
+                .objects
+                .iter()
+                .filter(|obj| obj.layer == layer)
+                .collect();
+            if objects.is_empty() {
+                continue;
+            }
+            let layer = layer_names.unique(layer);
+            nodes.push_str(&format!(
+                "\n[node name=\"{}\" type=\"Node3D\" parent=\".\"]\n",
+                layer
+            ));
+
+            let mut names = GodotNames::default();
+            for obj in objects {
+                self.object_written()?;
+                let name = names.unique(&obj.name);
+                let geometry = obj.kind.has_geometry();
+                let mesh = obj.mesh.as_deref().filter(|_| geometry);
+                let scene = mesh.filter(|mesh| is_godot_scene(mesh));
+                match scene {
+                    Some(scene) => nodes.push_str(&format!(
+                        "\n[node name=\"{}\" parent=\"{}\" instance=ExtResource(\"{}\")]\n",
+                        name,
+                        layer,
+                        resources.external("PackedScene", scene)
+                    )),
+                    None => nodes.push_str(&format!(
+                        "\n[node name=\"{}\" type=\"{}\" parent=\"{}\"]\n",
+                        name,
+                        if geometry { "MeshInstance3D" } else { "Node3D" },
+                        layer
+                    )),
+                }
+
+                // Godot writes the basis row by row, then the origin
+                let m = self.create_transform_matrix(&obj.transform);
+                nodes.push_str(&format!(
+                    "transform = Transform3D({}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {})\n",
+                    m[0], m[4], m[8], m[1], m[5], m[9], m[2], m[6], m[10], m[12], m[13], m[14]
+                ));
+                if geometry && scene.is_none() {
+                    let mesh = if let Some(mesh) = mesh {
+                        format!("ExtResource(\"{}\")", resources.external("Mesh", mesh))
+                    } else {
+                        resources.box_mesh = true;
+                        format!("SubResource(\"{}\")", GODOT_BOX_MESH)
+                    };
+                    nodes.push_str(&format!("mesh = {}\n", mesh));
+                    if let Some(material) = &obj.material {
+                        nodes.push_str(&format!(
+                            "surface_material_override/0 = ExtResource(\"{}\")\n",
+                            resources.external("Material", material)
+                        ));
+                    }
+                }
+
+                nodes.push_str(&format!("metadata/morgan_id = {}\n", godot_value(&obj.id)));
+                if !obj.tags.is_empty() {
+                    nodes.push_str(&format!("metadata/tags = {}\n", godot_value(&obj.tags)));
+                }
+                for (key, value) in self.object_extras(obj) {
+                    nodes.push_str(&format!("metadata/{} = {}\n", key, godot_value(&value)));
+                }
+            }
+        }
+
+        let mut scene = format!(
+            "[gd_scene load_steps={} format=3]\n\n",
+            resources.lines.len() + usize::from(resources.box_mesh) + 1
+        );
+        for line in &resources.lines {
+            scene.push_str(line);
+            scene.push('\n');
+        }
+        if resources.box_mesh {
+            scene.push_str(&format!(
+                "\n[sub_resource type=\"BoxMesh\" id=\"{}\"]\n",
+                GODOT_BOX_MESH
+            ));
+        }
+        if !resources.lines.is_empty() || resources.box_mesh {
+            scene.push('\n');
+        }
+        scene.push_str(&nodes);
+        Ok(scene)
+    }
+}
+
+/// Writers for the formats the crate supports itself.
+pub(crate) fn builtin_writers() -> Vec<Arc<dyn FormatWriter>> {
+    vec![
+        Arc::new(JsonWriter),
+        Arc::new(RonWriter),
+        Arc::new(RustCodeWriter),
+        Arc::new(GltfWriter),
+        Arc::new(FbxWriter),
+        Arc::new(GodotSceneWriter),
+        Arc::new(UnityWriter),
+        Arc::new(CollisionWriter(ExportFormat::CollisionRon)),
+        Arc::new(CollisionWriter(ExportFormat::CollisionJson)),
+    ]
+}
+
+struct JsonWriter;
+
+impl FormatWriter for JsonWriter {
+    fn format(&self) -> ExportFormat {
+        ExportFormat::JSON
+    }
+
+    fn capabilities(&self) -> WriterCapabilities {
+        WriterCapabilities {
+            materials: true,
+            hierarchy: true,
+            animations: false,
+        }
+    }
+
+    fn write(
+        &self,
+        context: &WriteContext,
+        level_data: &LevelData,
+        file_path: &Path,
+    ) -> Result<()> {
+        let export_data = ExportMetadata {
+            level: level_data.clone(),
+            export_info: context.options.include_metadata.then(|| ExportInfo {
+                exported_at: (!context.exporter.deterministic).then(Utc::now),
+                exporter_version: "0.1.0".to_string(),
+                format_version: "1.0".to_string(),
+                exported_by: "Morgan-Bevy Level Editor".to_string(),
+            }),
+        };
+
+        let json_data = context.to_json(&export_data)?;
+        fs::write(file_path, json_data)?;
+        Ok(())
+    }
+}
+
+struct RonWriter;
+
+impl FormatWriter for RonWriter {
+    fn format(&self) -> ExportFormat {
+        ExportFormat::RON
+    }
+
+    fn capabilities(&self) -> WriterCapabilities {
+        WriterCapabilities {
+            materials: true,
+            hierarchy: true,
+            animations: false,
+        }
+    }
+
+    fn write(
+        &self,
+        context: &WriteContext,
+        level_data: &LevelData,
+        file_path: &Path,
+    ) -> Result<()> {
+        // Convert to Bevy-compatible RON format
+        let mut bevy_level = context.exporter.convert_to_bevy_format(level_data)?;
+        if !context.options.include_metadata {
+            bevy_level.metadata = None;
+        }
+        let ron_data = ron::ser::to_string_pretty(&bevy_level, ron::ser::PrettyConfig::default())?;
+        fs::write(file_path, ron_data)?;
+        Ok(())
+    }
+}
+
+struct RustCodeWriter;
+
+impl FormatWriter for RustCodeWriter {
+    fn format(&self) -> ExportFormat {
+        ExportFormat::RustCode
+    }
+
+    fn capabilities(&self) -> WriterCapabilities {
+        WriterCapabilities {
+            materials: true,
+            hierarchy: false,
+            animations: false,
+        }
+    }
+
+    fn write(
+        &self,
+        context: &WriteContext,
+        level_data: &LevelData,
+        file_path: &Path,
+    ) -> Result<()> {
+        let rust_code = context
+            .exporter
+            .generate_rust_code(level_data, context.options)?;
+        fs::write(file_path, rust_code)?;
+        Ok(())
+    }
+}
+
+struct GltfWriter;
+
+impl FormatWriter for GltfWriter {
+    fn format(&self) -> ExportFormat {
+        ExportFormat::GLTF
+    }
+
+    fn capabilities(&self) -> WriterCapabilities {
+        WriterCapabilities {
+            materials: true,
+            hierarchy: false,
+            animations: true,
+        }
+    }
+
+    fn write(
+        &self,
+        context: &WriteContext,
+        level_data: &LevelData,
+        file_path: &Path,
+    ) -> Result<()> {
+        // Geometry goes in a .bin file beside the .gltf
+        let buffer_path = file_path.with_extension("bin");
+        let buffer_uri = buffer_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (gltf_data, buffer) = context
+            .exporter
+            .convert_to_gltf_format(level_data, &buffer_uri)?;
+        let gltf_json = context.to_json(&gltf_data)?;
+        fs::write(file_path, gltf_json)?;
+        if !buffer.is_empty() {
+            fs::write(buffer_path, buffer)?;
+        }
+        Ok(())
+    }
+}
+
+struct FbxWriter;
+
+impl FormatWriter for FbxWriter {
+    fn format(&self) -> ExportFormat {
+        ExportFormat::FBX
+    }
+
+    fn capabilities(&self) -> WriterCapabilities {
+        WriterCapabilities {
+            materials: true,
+            hierarchy: false,
+            animations: true,
+        }
+    }
+
+    fn write(
+        &self,
+        context: &WriteContext,
+        level_data: &LevelData,
+        file_path: &Path,
+    ) -> Result<()> {
+        // For FBX, we'll create a text-based FBX format as a placeholder
+        // In production, you'd use an FBX SDK library
+        let fbx_text = context.exporter.generate_fbx_ascii(level_data)?;
+        fs::write(file_path, fbx_text)?;
+        Ok(())
+    }
+}
+
+struct GodotSceneWriter;
+
+impl FormatWriter for GodotSceneWriter {
+    fn format(&self) -> ExportFormat {
+        ExportFormat::GodotScene
+    }
+
+    fn capabilities(&self) -> WriterCapabilities {
+        WriterCapabilities {
+            materials: true,
+            hierarchy: true,
+            animations: false,
+        }
+    }
+
+    fn write(
+        &self,
+        context: &WriteContext,
+        level_data: &LevelData,
+        file_path: &Path,
+    ) -> Result<()> {
+        let scene = context.exporter.generate_godot_scene(level_data)?;
+        fs::write(file_path, scene)?;
+        Ok(())
+    }
+}
+
+struct UnityWriter;
+
+impl FormatWriter for UnityWriter {
+    fn format(&self) -> ExportFormat {
+        ExportFormat::Unity
+    }
+
+    fn capabilities(&self) -> WriterCapabilities {
+        WriterCapabilities {
+            materials: true,
+            hierarchy: true,
+            animations: false,
+        }
+    }
+
+    fn write(
+        &self,
+        context: &WriteContext,
+        level_data: &LevelData,
+        file_path: &Path,
+    ) -> Result<()> {
+        let manifest = context
+            .exporter
+            .convert_to_unity_format(level_data, context.coordinates);
+        fs::write(file_path, context.to_json(&manifest)?)?;
+        Ok(())
+    }
+}
+
+/// Collision geometry, as `CollisionRon` or `CollisionJson`.
+struct CollisionWriter(ExportFormat);
+
+impl FormatWriter for CollisionWriter {
+    fn format(&self) -> ExportFormat {
+        self.0.clone()
+    }
+
+    fn capabilities(&self) -> WriterCapabilities {
+        WriterCapabilities {
+            materials: false,
+            hierarchy: true,
+            animations: false,
+        }
+    }
+
+    fn write(
+        &self,
+        context: &WriteContext,
+        level_data: &LevelData,
+        file_path: &Path,
+    ) -> Result<()> {
+        let collision = collision_export(level_data);
+        let data = if self.0 == ExportFormat::CollisionRon {
+            ron::ser::to_string_pretty(&collision, ron::ser::PrettyConfig::default())?
+        } else {
+            context.to_json(&collision)?
+        };
+        fs::write(file_path, data)?;
+        Ok(())
+    }
+}
+
+/// Name of the marker component for `tag` in generated Rust code, such as `LootRoomTag`
+/// for `loot-room`, or `None` if the tag has nothing to name it by.
+fn tag_component(tag: &str) -> Option<String> {
+    let name: String = tag
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect();
+    match name.chars().next() {
+        None => None,
+        // Identifiers can't start with a digit
+        Some(first) if first.is_ascii_digit() => Some(format!("Tag{}", name)),
+        Some(_) => Some(format!("{}Tag", name)),
+    }
+}
+
+/// Id of the box mesh shared by Godot nodes for objects without a mesh of their own.
+const GODOT_BOX_MESH: &str = "BoxMesh_1";
+
+/// External resources of a Godot scene, numbered as they're first used.
+#[derive(Default)]
+struct GodotResources {
+    lines: Vec<String>,
+    ids: HashMap<String, usize>,
+    box_mesh: bool,
+}
+
+impl GodotResources {
+    /// The id of the resource at `path`, declaring it as a `kind` the first time.
+    fn external(&mut self, kind: &str, path: &str) -> usize {
+        let id = self.ids.len() + 1;
+        *self.ids.entry(path.to_string()).or_insert_with(|| {
+            self.lines.push(format!(
+                "[ext_resource type=\"{}\" path={} id=\"{}\"]",
+                kind,
+                godot_value(&godot_path(path)),
+                id
+            ));
+            id
+        })
+    }
+}
+
+/// Node names unique among their siblings, without the characters Godot reserves.
+#[derive(Default)]
+struct GodotNames {
+    used: HashSet<String>,
+}
+
+impl GodotNames {
+    fn unique(&mut self, name: &str) -> String {
+        let base: String = name
+            .chars()
+            .map(|c| if ".:@/\"%".contains(c) { '_' } else { c })
+            .collect();
+        let base = if base.trim().is_empty() {
+            "Node".to_string()
+        } else {
+            base
+        };
+        let mut name = base.clone();
+        let mut count = 1;
+        while !self.used.insert(name.clone()) {
+            count += 1;
+            name = format!("{}_{}", base, count);
+        }
+        name
+    }
+}
+
+/// Models Godot imports as scenes rather than single meshes.
+fn is_godot_scene(path: &str) -> bool {
+    let extension = Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase());
+    matches!(
+        extension.as_deref(),
+        Some("glb" | "gltf" | "fbx" | "blend" | "dae" | "tscn" | "scn")
+    )
+}
+
+/// `path` inside the Godot project, taking asset paths to be relative to its root.
+fn godot_path(path: &str) -> String {
+    if path.starts_with("res://") || Path::new(path).is_absolute() {
+        path.to_string()
+    } else {
+        format!("res://{}", path.trim_start_matches("./").replace('\\', "/"))
+    }
+}
+
+/// A value in Godot's text format, which reads JSON strings, arrays and dictionaries.
+fn godot_value<T: Serialize + ?Sized>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "null".to_string())
+}
+
+/// Names the manifest format for the Unity importer script.
+const UNITY_MANIFEST_FORMAT: &str = "morgan-bevy-unity";
+
+#[derive(Serialize)]
+struct UnityManifest {
+    format: &'static str,
+    version: u32,
+    level_id: String,
+    level_name: String,
+    coordinate_system: CoordinateSystem,
+    layers: Vec<String>,
+    /// Every mesh and material the objects use, to import before building the scene
+    meshes: Vec<String>,
+    materials: Vec<String>,
+    objects: Vec<UnityObject>,
+}
+
+#[derive(Serialize)]
+struct UnityObject {
+    id: String,
+    name: String,
+    layer: String,
+    tags: Vec<String>,
+    position: [f32; 3],
+    rotation: [f32; 4],
+    scale: [f32; 3],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mesh: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    material: Option<String>,
+    /// Never moves, so can be marked static for batching and lightmaps
+    is_static: bool,
+    /// Spawn points, zones, paths, doors and physics, keyed as in glTF node extras
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    components: serde_json::Map<String, serde_json::Value>,
+}
+
+// Export metadata structures
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportMetadata {
+    level: LevelData,
+    /// Left out when export options turn metadata off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    export_info: Option<ExportInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportInfo {
+    /// Left out of deterministic exports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exported_at: Option<DateTime<Utc>>,
+    exporter_version: String,
+    format_version: String,
+    exported_by: String,
+}
+
+// Bevy-specific data structures for RON export
+#[derive(serde::Serialize)]
+struct BevyLevelData {
+    name: String,
+    entities: Vec<BevyEntity>,
+    bounds: BoundingBox,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<BevyMetadata>,
+}
+
+#[derive(serde::Serialize)]
+struct BevyEntity {
+    name: String,
+    transform: BevyTransform,
+    mesh: Option<String>,
+    material: Option<String>,
+    layer: String,
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    components: Vec<BevyComponent>,
+}
+
+#[derive(serde::Serialize)]
+enum BevyComponent {
+    TriggerZone {
+        shape: BevyZoneShape,
+        zone_type: String,
+        targets: Vec<String>,
+        trigger_once: bool,
+    },
+    PlayerStart,
+    EnemySpawner {
+        enemy_kind: String,
+        count: u32,
+        interval_secs: f32,
+    },
+    ItemSpawn {
+        item_kind: String,
+        respawn_secs: Option<f32>,
+    },
+    LevelExit {
+        target_level: Option<String>,
+    },
+    Door {
+        locked: bool,
+        key_id: Option<String>,
+        open_direction: DoorOpenDirection,
+        auto_close_secs: Option<f32>,
+        linked_switch_id: Option<String>,
+    },
+    PatrolPath {
+        waypoints: Vec<[f32; 3]>,
+        wait_secs: Vec<f32>,
+        mode: PathMode,
+    },
+    PhysicsBody {
+        body_type: BodyType,
+        collider: BevyCollider,
+        mass: Option<f32>,
+        friction: f32,
+        sensor: bool,
+    },
+}
+
+#[derive(serde::Serialize)]
+enum BevyZoneShape {
+    Cuboid { half_extents: [f32; 3] },
+    Sphere { radius: f32 },
+}
+
+#[derive(serde::Serialize)]
+enum BevyCollider {
+    Cuboid { half_extents: [f32; 3] },
+    Sphere { radius: f32 },
+    Capsule { radius: f32, half_height: f32 },
+    TriMesh,
+}
+
+#[derive(serde::Serialize)]
+struct BevyTransform {
+    translation: [f32; 3],
+    rotation: [f32; 4],
+    scale: [f32; 3],
+}
+
+#[derive(serde::Serialize)]
+struct BevyMetadata {
+    generation_seed: Option<u64>,
+    generator: String,
+    version: String,
+}
+
+/// Corners of a unit cube centred on the origin, the bits of each index giving which
+/// side of x, y and z it is on.
+const CUBE_CORNERS: [[f32; 3]; 8] = [
+    [-0.5, -0.5, -0.5],
+    [0.5, -0.5, -0.5],
+    [-0.5, 0.5, -0.5],
+    [0.5, 0.5, -0.5],
+    [-0.5, -0.5, 0.5],
+    [0.5, -0.5, 0.5],
+    [-0.5, 0.5, 0.5],
+    [0.5, 0.5, 0.5],
+];
+
+/// Two counter-clockwise triangles for each face of [`CUBE_CORNERS`].
+const CUBE_INDICES: [u32; 36] = [
+    0, 4, 6, 0, 6, 2, // -x
+    1, 3, 7, 1, 7, 5, // +x
+    0, 1, 5, 0, 5, 4, // -y
+    2, 6, 7, 2, 7, 3, // +y
+    0, 2, 3, 0, 3, 1, // -z
+    4, 5, 7, 4, 7, 6, // +z
+];
+
+/// `point` moved by a column-major transform matrix.
+fn transform_point(matrix: &[f32; 16], point: [f32; 3]) -> [f32; 3] {
+    std::array::from_fn(|row| {
+        matrix[8 + row].mul_add(
+            point[2],
+            matrix[4 + row].mul_add(point[1], matrix[row].mul_add(point[0], matrix[12 + row])),
+        )
+    })
+}
+
+// glTF buffer targets and component types
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+
+/// The one buffer of an export, with the views and accessors that read it.
+#[derive(Default)]
+struct GltfGeometry {
+    bytes: Vec<u8>,
+    buffer_views: Vec<GltfBufferView>,
+    accessors: Vec<GltfAccessor>,
+}
+
+impl GltfGeometry {
+    /// Adds a triangle list, returning the accessors of its positions and indices.
+    fn add_triangles(&mut self, positions: &[[f32; 3]], indices: &[u32]) -> (usize, usize) {
+        let bound = |pick: fn(f32, f32) -> f32, start: f32| {
+            std::array::from_fn(|axis| positions.iter().map(|p| p[axis]).fold(start, pick))
+        };
+        let position = self.add_accessor(
+            positions.iter().flatten().flat_map(|c| c.to_le_bytes()),
+            ARRAY_BUFFER,
+            GltfAccessor {
+                buffer_view: 0,
+                component_type: FLOAT,
+                count: positions.len(),
+                kind: "VEC3".to_string(),
+                min: Some(bound(f32::min, f32::INFINITY)),
+                max: Some(bound(f32::max, f32::NEG_INFINITY)),
+            },
+        );
+        let indices = self.add_accessor(
+            indices.iter().flat_map(|i| i.to_le_bytes()),
+            ELEMENT_ARRAY_BUFFER,
+            GltfAccessor {
+                buffer_view: 0,
+                component_type: UNSIGNED_INT,
+                count: indices.len(),
+                kind: "SCALAR".to_string(),
+                min: None,
+                max: None,
+            },
+        );
+        (position, indices)
+    }
+
+    /// Appends `data` in a view of its own for `accessor` to read.
+    fn add_accessor(
+        &mut self,
+        data: impl Iterator<Item = u8>,
+        target: u32,
+        mut accessor: GltfAccessor,
+    ) -> usize {
+        let byte_offset = self.bytes.len();
+        self.bytes.extend(data);
+        accessor.buffer_view = self.buffer_views.len();
+        self.buffer_views.push(GltfBufferView {
+            buffer: 0,
+            byte_offset,
+            byte_length: self.bytes.len() - byte_offset,
+            target,
+        });
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+}
+
+// GLTF data structures
+#[derive(serde::Serialize)]
+struct GltfDocument {
+    asset: GltfAsset,
+    scene: Option<usize>,
+    scenes: Vec<GltfScene>,
+    nodes: Vec<GltfNode>,
+    meshes: Vec<GltfMesh>,
+    materials: Vec<GltfMaterial>,
+    buffers: Vec<GltfBuffer>,
+    #[serde(rename = "bufferViews")]
+    buffer_views: Vec<GltfBufferView>,
+    accessors: Vec<GltfAccessor>,
+}
+
+impl GltfDocument {
+    fn push_material(&mut self, name: String) -> usize {
+        self.materials.push(GltfMaterial {
+            name: Some(name),
+            pbr_metallic_roughness: GltfPbrMetallicRoughness {
+                base_color_factor: [1.0, 1.0, 1.0, 1.0], // Default white
+                metallic_factor: 0.0,
+                roughness_factor: 0.9,
+            },
+        });
+        self.materials.len() - 1
+    }
+
+    fn push_mesh(&mut self, name: String, primitive: GltfPrimitive) -> usize {
+        self.meshes.push(GltfMesh {
+            name: Some(name),
+            primitives: vec![primitive],
+        });
+        self.meshes.len() - 1
+    }
+}
+
+#[derive(serde::Serialize)]
+struct GltfBuffer {
+    uri: String,
+    #[serde(rename = "byteLength")]
+    byte_length: usize,
+}
+
+#[derive(serde::Serialize)]
+struct GltfBufferView {
+    buffer: usize,
+    #[serde(rename = "byteOffset")]
+    byte_offset: usize,
+    #[serde(rename = "byteLength")]
+    byte_length: usize,
+    target: u32,
+}
+
+#[derive(serde::Serialize)]
+struct GltfAccessor {
+    #[serde(rename = "bufferView")]
+    buffer_view: usize,
+    #[serde(rename = "componentType")]
+    component_type: u32,
+    count: usize,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min: Option<[f32; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<[f32; 3]>,
+}
+
+#[derive(serde::Serialize)]
+struct GltfAsset {
+    version: String,
+    generator: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct GltfScene {
+    name: Option<String>,
+    nodes: Vec<usize>,
+}
+
+#[derive(serde::Serialize)]
+struct GltfNode {
+    name: Option<String>,
+    mesh: Option<usize>,
+    matrix: Option<[f32; 16]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extras: Option<serde_json::Value>,
+}
+
+#[derive(serde::Serialize)]
+struct GltfMesh {
+    name: Option<String>,
+    primitives: Vec<GltfPrimitive>,
+}
+
+#[derive(serde::Serialize)]
+struct GltfPrimitive {
+    mode: u32,
+    material: Option<usize>,
+    attributes: GltfAttributes,
+    indices: Option<usize>,
+}
+
+#[derive(serde::Serialize)]
+struct GltfAttributes {
+    #[serde(rename = "POSITION")]
+    position: usize,
+}
+
+#[derive(serde::Serialize)]
+struct GltfMaterial {
+    name: Option<String>,
+    #[serde(rename = "pbrMetallicRoughness")]
+    pbr_metallic_roughness: GltfPbrMetallicRoughness,
+}
+
+// Field names follow the glTF schema
+#[allow(clippy::struct_field_names)]
+#[derive(serde::Serialize)]
+struct GltfPbrMetallicRoughness {
+    #[serde(rename = "baseColorFactor")]
+    base_color_factor: [f32; 4],
+    #[serde(rename = "metallicFactor")]
+    metallic_factor: f32,
+    #[serde(rename = "roughnessFactor")]
+    roughness_factor: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::BevyVersion;
+    use crate::level::physics::{BodyType, PhysicsProperties};
+    use crate::testing;
+
+    fn cube(name: &str, mesh: &str, material: &str, position: [f32; 3]) -> GameObject {
+        GameObject {
+            material: Some(material.to_string()),
+            mesh: Some(mesh.to_string()),
+            ..GameObject::new(name, position)
+        }
+    }
+
+    fn level() -> LevelData {
+        let mut wall = cube("wall", "wall.mesh", "stone", [5.0, 1.0, 0.0]);
+        wall.transform.scale = [1.0, 2.0, 0.2];
+        // A quarter turn about y
+        wall.transform.rotation = [
+            0.0,
+            std::f32::consts::FRAC_1_SQRT_2,
+            0.0,
+            std::f32::consts::FRAC_1_SQRT_2,
+        ];
+        let mut barrel = cube("barrel", "barrel.mesh", "wood", [2.0, 0.5, 2.0]);
+        barrel.physics = Some(PhysicsProperties {
+            body_type: BodyType::Dynamic,
+            ..PhysicsProperties::default()
+        });
+        LevelData {
+            name: "Export".to_string(),
+            layers: vec!["Default".to_string()],
+            ..testing::level(vec![
+                cube("floor_0", "floor.mesh", "wood", [0.0, 0.0, 0.0]),
+                cube("floor_1", "floor.mesh", "wood", [1.0, 0.0, 0.0]),
+                cube("floor_2", "floor.mesh", "wood", [2.0, 0.0, 0.0]),
+                wall,
+                barrel,
+            ])
+        }
+    }
+
+    fn convert(mode: GltfMeshMode) -> serde_json::Value {
+        let exporter = LevelExporter::new().with_gltf_meshes(mode);
+        let (gltf, buffer) = exporter
+            .convert_to_gltf_format(&level(), "level.bin")
+            .unwrap();
+        let gltf = serde_json::to_value(gltf).unwrap();
+        assert_eq!(gltf["buffers"][0]["byteLength"], buffer.len());
+        gltf
+    }
+
+    fn count(gltf: &serde_json::Value, key: &str) -> usize {
+        gltf[key].as_array().unwrap().len()
+    }
+
+    #[test]
+    fn gltf_meshes_are_shared_or_merged_on_request() {
+        let separate = convert(GltfMeshMode::PerObject);
+        assert_eq!(count(&separate, "meshes"), 5);
+        assert_eq!(count(&separate, "materials"), 5);
+        assert_eq!(count(&separate, "nodes"), 5);
+
+        // Floors share a mesh, and the barrel shares their material
+        let instanced = convert(GltfMeshMode::Instanced);
+        assert_eq!(count(&instanced, "meshes"), 3);
+        assert_eq!(count(&instanced, "materials"), 2);
+        assert_eq!(count(&instanced, "nodes"), 5);
+        assert_eq!(instanced["nodes"][0]["mesh"], instanced["nodes"][2]["mesh"]);
+
+        // The dynamic barrel keeps its node, the rest is baked per material
+        let merged = convert(GltfMeshMode::Merged);
+        assert_eq!(count(&merged, "nodes"), 3);
+        assert_eq!(merged["nodes"][0]["name"], "barrel");
+        let wood = &merged["meshes"][2];
+        assert_eq!(wood["name"], "merged_wood");
+        let positions = &merged["accessors"][wood["primitives"][0]["attributes"]["POSITION"]
+            .as_u64()
+            .unwrap() as usize];
+        assert_eq!(positions["count"], 24);
+        assert_eq!(positions["min"], serde_json::json!([-0.5, -0.5, -0.5]));
+        assert_eq!(positions["max"], serde_json::json!([2.5, 0.5, 0.5]));
+
+        // The turned wall is thin along x and wide along z
+        let stone = &merged["meshes"][1];
+        let positions = &merged["accessors"][stone["primitives"][0]["attributes"]["POSITION"]
+            .as_u64()
+            .unwrap() as usize];
+        let min = positions["min"].as_array().unwrap();
+        let max = positions["max"].as_array().unwrap();
+        let width = |axis: usize| max[axis].as_f64().unwrap() - min[axis].as_f64().unwrap();
+        assert!((width(0) - 0.2).abs() < 1e-4 && (width(2) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn godot_scenes_nest_objects_under_layers() {
+        let mut level = level();
+        level.objects[1].name = "floor_0".to_string();
+        level.objects[3].mesh = Some("Models/wall.glb".to_string());
+        level.objects[4].mesh = None;
+        level.objects[4].layer = "Props".to_string();
+        let scene = LevelExporter::new().generate_godot_scene(&level).unwrap();
+
+        // The floor mesh and material and the glTF wall, plus a box for the barrel
+        assert!(scene.starts_with("[gd_scene load_steps=5 format=3]\n"));
+        assert!(scene.contains("[ext_resource type=\"Mesh\" path=\"res://floor.mesh\" id=\"1\"]"));
+        assert!(scene.contains(
+            "[ext_resource type=\"PackedScene\" path=\"res://Models/wall.glb\" id=\"3\"]"
+        ));
+        assert!(scene.contains("[node name=\"Export\" type=\"Node3D\"]"));
+        assert!(scene.contains("[node name=\"Props\" type=\"Node3D\" parent=\".\"]"));
+        assert!(scene.contains(
+            "[node name=\"floor_0_2\" type=\"MeshInstance3D\" parent=\"Default\"]\n\
+             transform = Transform3D(1, 0, 0, 0, 1, 0, 0, 0, 1, 1, 0, 0)\n\
+             mesh = ExtResource(\"1\")\n\
+             surface_material_override/0 = ExtResource(\"2\")\n"
+        ));
+        assert!(
+            scene.contains("[node name=\"wall\" parent=\"Default\" instance=ExtResource(\"3\")]")
+        );
+        assert!(scene.contains("mesh = SubResource(\"BoxMesh_1\")"));
+        assert!(scene.contains("metadata/physics = {\"body_type\":\"dynamic\""));
+    }
+
+    #[test]
+    fn unity_manifests_use_left_handed_centimetres() {
+        let options = ExportOptions {
+            unit_scale: 100.0,
+            ..ExportOptions::default()
+        };
+        let level = options.prepare(&level(), CoordinateSystem::UNITY);
+        let manifest = LevelExporter::new()
+            .convert_to_unity_format(&level, options.coordinates(CoordinateSystem::UNITY));
+        let manifest = serde_json::to_value(manifest).unwrap();
+
+        assert_eq!(manifest["coordinate_system"]["left_handed"], true);
+        assert_eq!(
+            manifest["meshes"],
+            serde_json::json!(["barrel.mesh", "floor.mesh", "wall.mesh"])
+        );
+        assert_eq!(manifest["materials"], serde_json::json!(["stone", "wood"]));
+
+        let wall = &manifest["objects"][3];
+        assert_eq!(wall["position"], serde_json::json!([500.0, 100.0, -0.0]));
+        assert_eq!(wall["scale"], serde_json::json!([1.0, 2.0, 0.2_f32]));
+        let turn = wall["rotation"].as_array().unwrap();
+        assert!(turn[1].as_f64().unwrap() < 0.0 && turn[3].as_f64().unwrap() > 0.0);
+        assert_eq!(wall["is_static"], true);
+
+        // The barrel moves, and its collider is scaled with the level
+        let barrel = &manifest["objects"][4];
+        assert_eq!(barrel["position"], serde_json::json!([200.0, 50.0, -200.0]));
+        assert_eq!(barrel["is_static"], false);
+        assert_eq!(
+            barrel["components"]["physics"]["collider"]["half_extents"],
+            serde_json::json!([50.0, 50.0, 50.0])
+        );
+    }
+    #[test]
+    fn reports_progress_and_stops_when_cancelled() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("level");
+        let cancel = Arc::new(AtomicBool::new(false));
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let exporter = LevelExporter::deterministic()
+            .with_cancel(cancel.clone())
+            .with_progress({
+                let reports = reports.clone();
+                Box::new(move |progress: &ExportProgress| {
+                    reports
+                        .lock()
+                        .unwrap()
+                        .push((progress.formats_done, progress.objects_done));
+                    // Cancel once the first format is written
+                    if progress.formats_done == 1 {
+                        cancel.store(true, Ordering::Relaxed);
+                    }
+                })
+            });
+
+        let result = tokio_test::block_on(exporter.export_multi_format(
+            &level(),
+            &[ExportFormat::JSON, ExportFormat::RON, ExportFormat::GLTF],
+            &output.to_string_lossy(),
+            &ExportOptions::default(),
+        ))
+        .unwrap();
+        assert!(result.cancelled);
+        assert!(result.errors.is_empty());
+        assert_eq!(result.exported_files.len(), 1);
+        assert!(dir.path().join("export.json").exists());
+        assert!(!dir.path().join("export.ron").exists());
+        assert_eq!(*reports.lock().unwrap(), [(0, 0), (1, 5)]);
+
+        // Cancelling partway through a format leaves no file for it
+        let cancel = Arc::new(AtomicBool::new(false));
+        let result = tokio_test::block_on(
+            LevelExporter::deterministic()
+                .with_cancel(cancel.clone())
+                .with_progress(Box::new(move |_| cancel.store(true, Ordering::Relaxed)))
+                .export_multi_format(
+                    &level(),
+                    &[ExportFormat::RON],
+                    &output.to_string_lossy(),
+                    &ExportOptions::default(),
+                ),
+        )
+        .unwrap();
+        assert!(result.cancelled && result.exported_files.is_empty());
+    }
+
+    #[test]
+    fn rust_code_follows_the_bevy_version() {
+        let mut level = level();
+        level.objects[0].mesh = None;
+        level.objects[3].tags = vec!["loot-room".to_string(), "2nd floor".to_string()];
+        let exporter = LevelExporter::new();
+        let options = |bevy_version| ExportOptions {
+            bevy_version,
+            tag_components: true,
+            ..ExportOptions::default()
+        };
+
+        let bundles = exporter
+            .generate_rust_code(&level, &options(BevyVersion::V0_14))
+            .unwrap();
+        assert!(bundles.contains(
+            "            transform: Transform::from_translation(Vec3::new(5.00, 1.00, 0.00))"
+        ));
+        assert!(bundles.contains("SpatialBundle::from_transform("));
+        assert!(!bundles.contains("Mesh3d"));
+        // Each object gets a single Transform, inside its bundle
+        assert_eq!(
+            bundles.matches("Transform::from_translation").count(),
+            level.objects.len()
+        );
+
+        let required = exporter
+            .generate_rust_code(&level, &options(BevyVersion::V0_15))
+            .unwrap();
+        assert!(required.contains(
+            "        Mesh3d(asset_server.load(\"wall.mesh\")),\n        MeshMaterial3d::<StandardMaterial>(asset_server.load(\"stone\")),"
+        ));
+        assert!(!required.contains("Bundle"));
+        assert!(required.contains("pub struct LootRoomTag;"));
+        assert!(required.contains("        Tag2ndFloor,\n"));
+
+        let commented = exporter
+            .generate_rust_code(&level, &ExportOptions::default())
+            .unwrap();
+        assert!(commented.contains("// Tag: loot-room"));
+        assert!(!commented.contains("LootRoomTag"));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::export::CoordinateSystem;
+use serde::{Deserialize, Serialize};
+
+/// How a glTF export lays out the meshes of a level's objects.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GltfMeshMode {
+    /// A mesh of its own for every object
+    #[default]
+    PerObject,
+    /// One mesh per tile type and material, shared by every node showing it
+    Instanced,
+    /// Static geometry baked into one mesh per material; doors and objects with
+    /// physics stay separate, sharing meshes as with `Instanced`
+    Merged,
+}
+
+/// The Bevy release generated Rust code is written for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum BevyVersion {
+    #[serde(rename = "0.13")]
+    V0_13,
+    #[serde(rename = "0.14")]
+    V0_14,
+    #[default]
+    #[serde(rename = "0.15")]
+    V0_15,
+}
+
+impl BevyVersion {
+    /// Whether components such as `Mesh3d` bring in the ones they need, which replaced
+    /// bundles like `PbrBundle` in 0.15.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::V0_13 => "0.13",
+            Self::V0_14 => "0.14",
+            Self::V0_15 => "0.15",
+        }
+    }
+
+    #[must_use]
+    pub fn required_components(self) -> bool {
+        self >= Self::V0_15
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ExportFormat {
+    JSON,
+    RON,
+    RustCode,
+    GLTF,
+    FBX,
+    /// Godot 4 text scene
+    GodotScene,
+    /// Scene manifest for a Unity importer script, in Unity's coordinates
+    Unity,
+    /// Simplified colliders for physics engines, as RON
+    CollisionRon,
+    /// Simplified colliders for physics engines, as JSON
+    CollisionJson,
+    /// A format whose writer was registered from outside the crate, by name
+    Custom(String),
+}
+
+#[allow(dead_code)]
+impl ExportFormat {
+    #[must_use]
+    pub fn file_extension(&self) -> &str {
+        match self {
+            ExportFormat::JSON => "json",
+            ExportFormat::RON => "ron",
+            ExportFormat::RustCode => "rs",
+            ExportFormat::GLTF => "gltf",
+            ExportFormat::FBX => "fbx",
+            ExportFormat::GodotScene => "tscn",
+            ExportFormat::Unity => "unity.json",
+            ExportFormat::CollisionRon => "collision.ron",
+            ExportFormat::CollisionJson => "collision.json",
+            ExportFormat::Custom(name) => name,
+        }
+    }
+
+    #[must_use]
+    pub fn description(&self) -> &'static str {
+        match self {
+            ExportFormat::JSON => "Universal JSON format for any engine",
+            ExportFormat::RON => "Rust Object Notation - native Bevy format",
+            ExportFormat::RustCode => "Generated Rust code for direct integration",
+            ExportFormat::GLTF => "glTF 2.0 format with PBR materials",
+            ExportFormat::FBX => "Autodesk FBX format for 3D software",
+            ExportFormat::GodotScene => "Godot 4 scene with mesh and material resources",
+            ExportFormat::Unity => "Scene manifest for Unity with left-handed transforms",
+            ExportFormat::CollisionRon | ExportFormat::CollisionJson => {
+                "Merged collision boxes and bodies for Rapier or bevy_xpbd"
+            }
+            ExportFormat::Custom(_) => "Format added by a registered writer",
+        }
+    }
+
+    /// The coordinates the format's consumers expect, before any export options.
+    #[must_use]
+    pub fn coordinate_system(&self) -> CoordinateSystem {
+        match self {
+            ExportFormat::Unity => CoordinateSystem::UNITY,
+            _ => CoordinateSystem::EDITOR,
+        }
+    }
+}
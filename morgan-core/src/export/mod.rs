@@ -0,0 +1,11 @@
+pub mod collision;
+pub mod coordinates;
+pub mod formats;
+pub mod exporters;
+pub mod options;
+pub mod writers;
+
+pub use coordinates::CoordinateSystem;
+pub use formats::{BevyVersion, ExportFormat, GltfMeshMode};
+pub use exporters::LevelExporter;
+pub use options::{ExportFilter, ExportOptions};
\ No newline at end of file
@@ -0,0 +1,236 @@
+//! Conventions a level is exported in, shared by every format.
+
+use crate::export::coordinates::{CoordinateSystem, Forward, UpAxis};
+use crate::export::BevyVersion;
+use crate::level::annotations::AnnotationAnchor;
+use crate::stable;
+use crate::{GameObject, LevelData};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Which objects an export includes. A list left empty doesn't restrict anything;
+/// objects have to pass every list that isn't.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportFilter {
+    pub layers: Vec<String>,
+    /// Objects need at least one of these tags
+    pub tags: Vec<String>,
+    /// IDs of the objects to export, such as the selection
+    pub object_ids: Vec<String>,
+}
+
+impl ExportFilter {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty() && self.tags.is_empty() && self.object_ids.is_empty()
+    }
+
+    #[must_use]
+    pub fn matches(&self, obj: &GameObject) -> bool {
+        (self.layers.is_empty() || self.layers.contains(&obj.layer))
+            && (self.tags.is_empty() || obj.tags.iter().any(|tag| self.tags.contains(tag)))
+            && (self.object_ids.is_empty() || self.object_ids.contains(&obj.id))
+    }
+
+    /// `level` with only the objects that pass, the layers asked for and annotations
+    /// on what is left.
+    #[must_use]
+    pub fn apply(&self, level: &LevelData) -> LevelData {
+        let mut level = level.clone();
+        if self.is_empty() {
+            return level;
+        }
+        level.objects.retain(|obj| self.matches(obj));
+        if !self.layers.is_empty() {
+            level.layers.retain(|layer| self.layers.contains(layer));
+        }
+        let kept: HashSet<&str> = level.objects.iter().map(|obj| obj.id.as_str()).collect();
+        level
+            .annotations
+            .retain(|annotation| match annotation.anchor {
+                AnnotationAnchor::Object { ref object_id } => kept.contains(object_id.as_str()),
+                AnnotationAnchor::Position { .. } => true,
+            });
+        level
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    /// Target units in one editor metre
+    pub unit_scale: f32,
+    /// Overrides the up axis of each format's own coordinates
+    pub up_axis: Option<UpAxis>,
+    /// Overrides which way each format has objects face along the depth axis
+    pub forward: Option<Forward>,
+    /// Editor position that becomes the exported origin
+    pub origin: [f32; 3],
+    /// Decimal places positions, rotations and scales are rounded to
+    pub precision: Option<u8>,
+    /// Whether object metadata and details of the export itself are written
+    pub include_metadata: bool,
+    /// Objects to export; all of them by default
+    pub filter: ExportFilter,
+    /// Bevy release the Rust code export targets
+    pub bevy_version: BevyVersion,
+    /// Whether the Rust code export gives objects a marker component per tag rather
+    /// than listing tags in comments
+    pub tag_components: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            unit_scale: 1.0,
+            up_axis: None,
+            forward: None,
+            origin: [0.0; 3],
+            precision: None,
+            include_metadata: true,
+            filter: ExportFilter::default(),
+            bevy_version: BevyVersion::default(),
+            tag_components: false,
+        }
+    }
+}
+
+impl ExportOptions {
+    /// The coordinates a format is written in: its `native` ones, changed as these
+    /// options ask.
+    #[must_use]
+    pub fn coordinates(&self, native: CoordinateSystem) -> CoordinateSystem {
+        CoordinateSystem {
+            up: self.up_axis.unwrap_or(native.up),
+            forward: self.forward.unwrap_or(native.forward),
+            units_per_meter: native.units_per_meter * self.unit_scale,
+            origin: self.origin,
+            ..native
+        }
+    }
+
+    /// `level` as a format with `native` coordinates should write it: moved into them
+    /// and rounded.
+    #[must_use]
+    pub fn prepare(&self, level: &LevelData, native: CoordinateSystem) -> LevelData {
+        let mut level = self.coordinates(native).level(level);
+        if let Some(precision) = self.precision {
+            stable::round_level(&mut level, i32::from(precision));
+        }
+        if !self.include_metadata {
+            for obj in &mut level.objects {
+                obj.metadata.clear();
+            }
+        }
+        level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::annotations::Annotation;
+    use crate::spatial::BoundingBox;
+    use crate::testing::{self, assert_close};
+    use crate::GameObject;
+    use std::collections::HashMap;
+
+    fn object(id: &str, layer: &str, tags: &[&str]) -> GameObject {
+        GameObject {
+            tags: tags.iter().map(ToString::to_string).collect(),
+            ..testing::object(id, layer)
+        }
+    }
+
+    fn level(objects: Vec<GameObject>) -> LevelData {
+        LevelData {
+            layers: vec!["Default".to_string(), "Collision".to_string()],
+            bounds: BoundingBox::new([0.0; 3], [20.0, 4.0, 20.0]),
+            ..testing::level(objects)
+        }
+    }
+
+    #[test]
+    fn prepared_levels_follow_the_options() {
+        let mut crate_box = object("crate", "Default", &[]);
+        crate_box.transform.position = [10.123_456, 0.5, -4.0];
+        crate_box.transform.scale = [1.0, 2.0, 3.0];
+        crate_box.metadata = HashMap::from([("tile_type".to_string(), "crate".into())]);
+        let level = level(vec![crate_box]);
+        let options = ExportOptions {
+            unit_scale: 100.0,
+            up_axis: Some(UpAxis::Z),
+            forward: Some(Forward::Positive),
+            origin: [10.0, 0.0, 0.0],
+            precision: Some(1),
+            include_metadata: false,
+            ..ExportOptions::default()
+        };
+
+        let prepared = options.prepare(&level, CoordinateSystem::EDITOR);
+        let crate_box = &prepared.objects[0];
+        assert_close(crate_box.transform.position, [12.3, 400.0, 50.0]);
+        assert_close(crate_box.transform.scale, [1.0, 3.0, 2.0]);
+        assert!(crate_box.metadata.is_empty());
+        assert_close(prepared.bounds.min, [-1000.0, -2000.0, 0.0]);
+        assert_close(prepared.bounds.max, [1000.0, 0.0, 400.0]);
+
+        // Unity stays left-handed, whatever else changes
+        let unity = options.coordinates(CoordinateSystem::UNITY);
+        assert!(unity.left_handed && unity.up == UpAxis::Z);
+        assert_eq!(
+            ExportOptions::default().coordinates(CoordinateSystem::EDITOR),
+            CoordinateSystem::EDITOR
+        );
+    }
+    #[test]
+    fn filters_keep_matching_objects_and_their_annotations() {
+        let mut level = level(vec![
+            object("wall", "Collision", &["solid"]),
+            object("floor", "Collision", &["walkable"]),
+            object("barrel", "Default", &["solid"]),
+        ]);
+        level.annotations = ["wall", "barrel"]
+            .iter()
+            .map(|id| {
+                Annotation::new(
+                    AnnotationAnchor::Object {
+                        object_id: id.to_string(),
+                    },
+                    "reviewer",
+                    "Check this",
+                )
+            })
+            .collect();
+        let ids = |level: &LevelData| {
+            level
+                .objects
+                .iter()
+                .map(|obj| obj.id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let collision = ExportFilter {
+            layers: vec!["Collision".to_string()],
+            ..ExportFilter::default()
+        };
+        let filtered = collision.apply(&level);
+        assert_eq!(ids(&filtered), ["wall", "floor"]);
+        assert_eq!(filtered.layers, ["Collision"]);
+        assert_eq!(filtered.annotations.len(), 1);
+
+        let solid_collision = ExportFilter {
+            tags: vec!["solid".to_string()],
+            ..collision
+        };
+        assert_eq!(ids(&solid_collision.apply(&level)), ["wall"]);
+
+        let selection = ExportFilter {
+            object_ids: vec!["barrel".to_string(), "floor".to_string()],
+            ..ExportFilter::default()
+        };
+        assert_eq!(ids(&selection.apply(&level)), ["floor", "barrel"]);
+        assert_eq!(ids(&ExportFilter::default().apply(&level)).len(), 3);
+    }
+}
@@ -0,0 +1,220 @@
+//! The writers that turn levels into files, one per format.
+//!
+//! [`LevelExporter`] looks each format up in a [`WriterRegistry`], so a format is added
+//! by registering a [`FormatWriter`] for it rather than by changing the exporter. Each
+//! writer declares what its format can hold, and the exporter warns when a level has
+//! something the format would leave out.
+
+use crate::export::exporters::builtin_writers;
+use crate::export::{CoordinateSystem, ExportFormat, ExportOptions, LevelExporter};
+use crate::LevelData;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::Arc;
+
+/// What a format can hold besides objects and their transforms.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriterCapabilities {
+    pub materials: bool,
+    /// Whether objects stay grouped by layer
+    pub hierarchy: bool,
+    /// Levels don't hold animations yet, so this never leads to a warning
+    pub animations: bool,
+}
+
+impl WriterCapabilities {
+    /// Warnings about what of `level` a format with these capabilities leaves out.
+    #[must_use]
+    pub fn warnings(&self, format: &ExportFormat, level: &LevelData) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let with_materials = level
+            .objects
+            .iter()
+            .filter(|obj| obj.material.is_some())
+            .count();
+        if !self.materials && with_materials > 0 {
+            warnings.push(format!(
+                "{:?} doesn't support materials; {} objects are written without theirs",
+                format, with_materials
+            ));
+        }
+        let layers: BTreeSet<&str> = level.objects.iter().map(|obj| obj.layer.as_str()).collect();
+        if !self.hierarchy && layers.len() > 1 {
+            warnings.push(format!(
+                "{:?} doesn't support hierarchy; objects from {} layers are written in one list",
+                format,
+                layers.len()
+            ));
+        }
+        warnings
+    }
+}
+
+/// What a writer gets besides the level: the export's options and the exporter's
+/// progress reporting and output settings.
+pub struct WriteContext<'a> {
+    pub(crate) exporter: &'a LevelExporter,
+    pub options: &'a ExportOptions,
+    /// Coordinates the level has been converted to
+    pub coordinates: CoordinateSystem,
+}
+
+impl WriteContext<'_> {
+    /// Counts an object as written, failing once the export has been cancelled.
+    pub fn object_written(&self) -> Result<()> {
+        self.exporter.object_written()
+    }
+
+    /// `value` as JSON, in a stable layout for deterministic exports.
+    pub fn to_json<T: Serialize>(&self, value: &T) -> serde_json::Result<String> {
+        self.exporter.to_json(value)
+    }
+}
+
+/// Writes levels in one format.
+pub trait FormatWriter: Send + Sync {
+    fn format(&self) -> ExportFormat;
+
+    fn capabilities(&self) -> WriterCapabilities;
+
+    /// Extension of the written file, after the level name.
+    fn file_extension(&self) -> String {
+        self.format().file_extension().to_string()
+    }
+
+    /// The coordinates the format's consumers expect, before any export options.
+    fn coordinate_system(&self) -> CoordinateSystem {
+        self.format().coordinate_system()
+    }
+
+    /// Writes `level`, already filtered and converted, to `file_path`. Writers should
+    /// call [`WriteContext::object_written`] for each object so exports can report
+    /// progress and be cancelled.
+    fn write(&self, context: &WriteContext, level: &LevelData, file_path: &Path) -> Result<()>;
+}
+
+/// The writer for each format an exporter can write.
+#[derive(Clone)]
+pub struct WriterRegistry {
+    writers: BTreeMap<ExportFormat, Arc<dyn FormatWriter>>,
+}
+
+impl Default for WriterRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        for writer in builtin_writers() {
+            registry.writers.insert(writer.format(), writer);
+        }
+        registry
+    }
+}
+
+impl WriterRegistry {
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            writers: BTreeMap::new(),
+        }
+    }
+
+    /// Adds `writer`, replacing any writer already registered for its format.
+    pub fn register(&mut self, writer: impl FormatWriter + 'static) {
+        self.writers.insert(writer.format(), Arc::new(writer));
+    }
+
+    pub fn get(&self, format: &ExportFormat) -> Option<&dyn FormatWriter> {
+        self.writers.get(format).map(AsRef::as_ref)
+    }
+
+    /// Every format with a writer, built-in ones first.
+    #[must_use]
+    pub fn formats(&self) -> Vec<ExportFormat> {
+        self.writers.keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, level};
+    use crate::GameObject;
+    use std::fs;
+
+    /// One line per object with its name and position.
+    struct CsvWriter;
+
+    impl FormatWriter for CsvWriter {
+        fn format(&self) -> ExportFormat {
+            ExportFormat::Custom("csv".to_string())
+        }
+
+        fn capabilities(&self) -> WriterCapabilities {
+            WriterCapabilities::default()
+        }
+
+        fn write(&self, context: &WriteContext, level: &LevelData, file_path: &Path) -> Result<()> {
+            let mut csv = String::new();
+            for obj in &level.objects {
+                context.object_written()?;
+                let [x, y, z] = obj.transform.position;
+                csv.push_str(&format!("{},{},{},{}\n", obj.name, x, y, z));
+            }
+            fs::write(file_path, csv)?;
+            Ok(())
+        }
+    }
+
+    fn object(name: &str, layer: &str, material: Option<&str>) -> GameObject {
+        let mut object = GameObject {
+            material: material.map(ToString::to_string),
+            ..testing::object(name, layer)
+        };
+        object.transform.position = [1.0, 0.0, 2.0];
+        object
+    }
+
+    #[test]
+    fn registered_writers_export_and_warn_about_what_they_drop() {
+        let level = LevelData {
+            name: "Writers".to_string(),
+            layers: vec!["Floors".to_string(), "Walls".to_string()],
+            ..level(vec![
+                object("floor", "Floors", Some("stone")),
+                object("wall", "Walls", None),
+            ])
+        };
+        let csv = ExportFormat::Custom("csv".to_string());
+        let exporter = LevelExporter::deterministic().with_writer(CsvWriter);
+        assert!(exporter.writers().formats().contains(&csv));
+        assert!(exporter.writers().get(&ExportFormat::JSON).is_some());
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("level");
+        let result = tokio_test::block_on(exporter.export_multi_format(
+            &level,
+            &[csv, ExportFormat::JSON],
+            &output.to_string_lossy(),
+            &ExportOptions::default(),
+        ))
+        .unwrap();
+        assert!(result.errors.is_empty());
+        let written = fs::read_to_string(dir.path().join("writers.csv")).unwrap();
+        assert_eq!(written, "floor,1,0,2\nwall,1,0,2\n");
+
+        // The CSV loses the floor's material and both layers; JSON keeps everything
+        assert_eq!(result.warnings.len(), 2);
+        assert!(result.warnings[0].contains("1 objects"));
+        assert!(result.warnings[1].contains("2 layers"));
+
+        let missing = tokio_test::block_on(LevelExporter::new().export_multi_format(
+            &level,
+            &[ExportFormat::Custom("csv".to_string())],
+            &output.to_string_lossy(),
+            &ExportOptions::default(),
+        ))
+        .unwrap();
+        assert_eq!(missing.errors.len(), 1);
+    }
+}
@@ -0,0 +1,1548 @@
+use crate::generation::placement::place_spawns;
+use crate::generation::themes::{Theme, ThemeLibrary, TileMesh};
+use crate::level::connectivity::record_connectivity;
+use crate::level::doors::DoorProperties;
+use crate::level::rooms::{
+    ConnectionKind, RoomConnection, RoomGraph, RoomInfo, RoomType, ROOM_GRAPH_KEY,
+};
+use crate::snapping::from_euler_yxz;
+use crate::spatial::BoundingBox;
+use crate::stable::{generated_id, generated_roll};
+use crate::{BSPGenerationParams, CorridorStyle, GameObject, LevelData, ObjectKind, Transform3D};
+use anyhow::{bail, Result};
+use log::info;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum TileType {
+    Empty,
+    Wall,
+    Floor,
+    /// A doorway, `facing_x` when the way through runs along X
+    Door {
+        facing_x: bool,
+    },
+    Corridor,
+    /// Stairs up to the same cell on the floor above
+    Stairs,
+}
+
+/// Storey height for themes not in the theme library, matching the height of walls.
+const DEFAULT_FLOOR_HEIGHT: f32 = 2.0;
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct Room {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub id: String,
+}
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct BSPNode {
+    pub bounds: Room,
+    pub left: Option<Box<BSPNode>>,
+    pub right: Option<Box<BSPNode>>,
+    pub room: Option<Room>,
+}
+
+fn room_id(seed: u64, floor: u32, x: u32, y: u32, width: u32, height: u32) -> String {
+    let mut cell = vec![x.into(), y.into(), width.into(), height.into()];
+    // Floor 0 keeps the IDs single-floor levels always had
+    if floor > 0 {
+        cell.push(floor.into());
+    }
+    generated_id(seed, "room", &cell)
+}
+
+/// Rooms no more than this many cells apart count as neighbours for loops.
+const MAX_LOOP_GAP: u32 = 2;
+
+/// Whether two rooms sit side by side, overlapping along one axis and at most
+/// [`MAX_LOOP_GAP`] cells apart along the other.
+fn rooms_adjacent(a: &Room, b: &Room) -> bool {
+    let gap = |start1: u32, length1: u32, start2: u32, length2: u32| {
+        if start1 + length1 <= start2 {
+            Some(start2 - (start1 + length1))
+        } else if start2 + length2 <= start1 {
+            Some(start1 - (start2 + length2))
+        } else {
+            None // Overlapping
+        }
+    };
+    match (
+        gap(a.x, a.width, b.x, b.width),
+        gap(a.y, a.height, b.y, b.height),
+    ) {
+        (Some(gap), None) | (None, Some(gap)) => gap <= MAX_LOOP_GAP,
+        _ => false,
+    }
+}
+
+const NEIGHBOURS: [(i64, i64); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+fn is_walkable(tile: TileType) -> bool {
+    matches!(tile, TileType::Floor | TileType::Corridor)
+}
+
+/// What a cell is built from: the theme's tile for it, or a plain stand-in.
+struct CellTile {
+    mesh: TileMesh,
+    /// The theme's mesh variant for the tile, used over a mesh named after `mesh_type`
+    variant: Option<String>,
+    tags: Vec<String>,
+    collision: bool,
+}
+
+impl CellTile {
+    fn mesh_path(&self) -> String {
+        self.variant
+            .clone()
+            .unwrap_or_else(|| format!("meshes/{}.mesh", self.mesh.mesh_type))
+    }
+
+    fn material_path(&self, theme: &str) -> String {
+        format!("materials/{}/{}.mat", theme, self.mesh.material)
+    }
+
+    /// `kind` tags first, then the tile's own and the theme's.
+    fn tags(&self, kind: &[&str], theme: &str) -> Vec<String> {
+        let collision = self.collision.then_some("collision");
+        let mut tags: Vec<String> = Vec::new();
+        let all = kind.iter().copied().chain(collision);
+        for tag in all
+            .chain(self.tags.iter().map(String::as_str))
+            .chain([theme])
+        {
+            if !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+        tags
+    }
+}
+
+/// A tile covering the whole cell, `height` tall and raised by `offset_y`, for themes
+/// without one.
+fn plain_mesh(mesh_type: &str, material: &str, height: f32, offset_y: f32) -> TileMesh {
+    TileMesh {
+        mesh_type: mesh_type.to_string(),
+        material: material.to_string(),
+        scale: (1.0, height, 1.0),
+        rotation: (0.0, 0.0, 0.0),
+        offset: (0.0, offset_y, 0.0),
+    }
+}
+
+#[derive(Default)]
+pub struct BSPGenerator {
+    rng: Option<StdRng>,
+    seed: u64,
+    grid: Vec<Vec<TileType>>,
+    width: u32,
+    height: u32,
+    depth: u32,
+    /// Floor currently being generated, 0 at ground level
+    floor: u32,
+    floor_height: f32,
+    /// Theme the level is built from, if the library has it
+    theme: Option<Theme>,
+    /// Rooms of every floor generated so far, with the floor they are on
+    rooms: Vec<(u32, Room)>,
+    connections: Vec<RoomConnection>,
+    /// Door IDs by the ID of the room they lead into
+    room_doors: HashMap<String, Vec<String>>,
+    /// Room types by room ID, assigned once every floor is laid out
+    room_types: HashMap<String, RoomType>,
+}
+
+impl BSPGenerator {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            rng: None,
+            seed: 0,
+            grid: Vec::new(),
+            width: 0,
+            height: 0,
+            depth: 0,
+            floor: 0,
+            floor_height: DEFAULT_FLOOR_HEIGHT,
+            theme: None,
+            rooms: Vec::new(),
+            connections: Vec::new(),
+            room_doors: HashMap::new(),
+            room_types: HashMap::new(),
+        }
+    }
+
+    #[tracing::instrument(
+        name = "bsp_generate",
+        skip_all,
+        fields(width = params.width, height = params.height, seed = ?params.seed)
+    )]
+    pub async fn generate(&self, params: BSPGenerationParams) -> Result<LevelData> {
+        info!(
+            "Starting BSP generation with dimensions: {}x{}x{}",
+            params.width, params.height, params.depth
+        );
+
+        if !(0.0..=1.0).contains(&params.door_probability) {
+            bail!("Door probability must be between 0 and 1");
+        }
+        if !(0.0..=1.0).contains(&params.loop_factor) {
+            bail!("Loop factor must be between 0 and 1");
+        }
+        if !(0.0..=1.0).contains(&params.treasure_room_weight)
+            || !(0.0..=1.0).contains(&params.shop_room_weight)
+        {
+            bail!("Room type weights must be between 0 and 1");
+        }
+
+        let seed = params.seed.unwrap_or_else(|| {
+            use std::time::{SystemTime, UNIX_EPOCH};
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        });
+
+        let mut generator = Self::new();
+        generator.rng = Some(StdRng::seed_from_u64(seed));
+        generator.seed = seed;
+        generator.width = params.width;
+        generator.height = params.height;
+        generator.depth = params.depth;
+
+        generator.theme = ThemeLibrary::current().get_theme(&params.theme);
+        generator.floor_height = generator
+            .theme
+            .as_ref()
+            .map_or(DEFAULT_FLOOR_HEIGHT, |theme| theme.wall_height);
+        let floors = params.depth.max(1);
+
+        // Each floor gets its own BSP layout, joined to the one below by stairs
+        let mut grids: Vec<Vec<Vec<TileType>>> = Vec::new();
+        for floor in 0..floors {
+            generator.floor = floor;
+            generator.grid =
+                vec![vec![TileType::Empty; params.width as usize]; params.height as usize];
+
+            let root_room = Room {
+                x: 0,
+                y: 0,
+                width: params.width,
+                height: params.height,
+                id: room_id(seed, floor, 0, 0, params.width, params.height),
+            };
+
+            let bsp_tree = tracing::info_span!("bsp_tree", floor)
+                .in_scope(|| generator.generate_bsp_tree(root_room, &params))?;
+            let mut floor_rooms = Vec::new();
+            Self::collect_rooms(&bsp_tree, &mut floor_rooms);
+            generator
+                .rooms
+                .extend(floor_rooms.into_iter().map(|room| (floor, room)));
+
+            // Convert BSP tree to rooms and corridors
+            tracing::info_span!("place_rooms", floor)
+                .in_scope(|| generator.place_rooms(&bsp_tree, &params))?;
+            tracing::info_span!("create_corridors", floor)
+                .in_scope(|| generator.create_corridors(&bsp_tree, &params))?;
+            if params.loop_factor > 0.0 {
+                tracing::info_span!("add_loops", floor)
+                    .in_scope(|| generator.add_loops(&params))?;
+            }
+            tracing::info_span!("place_doors", floor)
+                .in_scope(|| generator.place_doors(&bsp_tree, &params));
+
+            if let Some(below) = grids.last_mut() {
+                generator.connect_floors(below, &bsp_tree, &params)?;
+            }
+            grids.push(std::mem::take(&mut generator.grid));
+        }
+
+        let mut graph = generator.room_graph();
+        tracing::info_span!("assign_room_types").in_scope(|| {
+            graph.assign_room_types(
+                params.treasure_room_weight,
+                params.shop_room_weight,
+                generator.rng.as_mut().unwrap(),
+            );
+        });
+        generator.room_types = graph
+            .rooms
+            .iter()
+            .map(|room| (room.id.clone(), room.room_type))
+            .collect();
+
+        // Convert grids to 3D objects
+        let mut objects = Vec::new();
+        for (floor, grid) in (0..floors).zip(grids) {
+            generator.floor = floor;
+            generator.grid = grid;
+            objects.extend(generator.grid_to_objects(&params)?);
+        }
+
+        let mut layers = vec![
+            "Walls".to_string(),
+            "Floors".to_string(),
+            "Doors".to_string(),
+            "Collision".to_string(),
+        ];
+        if floors > 1 {
+            layers.push("Stairs".to_string());
+        }
+
+        let mut level_data = LevelData {
+            id: generated_id(
+                seed,
+                "bsp_level",
+                &[
+                    params.width.into(),
+                    params.height.into(),
+                    params.depth.into(),
+                ],
+            ),
+            name: format!("BSP Level {}", seed),
+            objects,
+            layers,
+            generation_seed: Some(seed),
+            generation_params: Some(generator.generation_params(&params, &graph)?),
+            bounds: BoundingBox {
+                min: [0.0, 0.0, 0.0],
+                max: [
+                    params.width as f32,
+                    floors as f32 * generator.floor_height,
+                    params.height as f32,
+                ],
+            },
+            annotations: Vec::new(),
+            camera_bookmarks: Vec::new(),
+        };
+
+        if let Some(spawns) = &params.spawns {
+            tracing::info_span!("place_spawns")
+                .in_scope(|| place_spawns(&mut level_data, spawns))?;
+        }
+
+        record_connectivity(&mut level_data)?;
+        info!(
+            "BSP generation complete. Created {} objects",
+            level_data.objects.len()
+        );
+        Ok(level_data)
+    }
+
+    fn generate_bsp_tree(&mut self, room: Room, params: &BSPGenerationParams) -> Result<BSPNode> {
+        let mut node = BSPNode {
+            bounds: room.clone(),
+            left: None,
+            right: None,
+            room: None,
+        };
+
+        // Stop subdividing if room is too small
+        if room.width <= params.max_room_size && room.height <= params.max_room_size {
+            if room.width >= params.min_room_size && room.height >= params.min_room_size {
+                node.room = Some(room);
+            }
+            return Ok(node);
+        }
+
+        let rng = self.rng.as_mut().unwrap();
+
+        // Decide whether to split horizontally or vertically
+        let split_horizontal = if room.width > room.height {
+            rng.gen_bool(0.8) // Prefer vertical split when width > height
+        } else if room.height > room.width {
+            rng.gen_bool(0.2) // Prefer horizontal split when height > width
+        } else {
+            rng.gen_bool(0.5) // Random when square
+        };
+
+        if split_horizontal && room.height >= params.min_room_size * 2 {
+            // Horizontal split
+            let split_point =
+                rng.gen_range(params.min_room_size..=(room.height - params.min_room_size));
+
+            let left_room = Room {
+                x: room.x,
+                y: room.y,
+                width: room.width,
+                height: split_point,
+                id: room_id(
+                    self.seed,
+                    self.floor,
+                    room.x,
+                    room.y,
+                    room.width,
+                    split_point,
+                ),
+            };
+
+            let right_room = Room {
+                x: room.x,
+                y: room.y + split_point,
+                width: room.width,
+                height: room.height - split_point,
+                id: room_id(
+                    self.seed,
+                    self.floor,
+                    room.x,
+                    room.y + split_point,
+                    room.width,
+                    room.height - split_point,
+                ),
+            };
+
+            node.left = Some(Box::new(self.generate_bsp_tree(left_room, params)?));
+            node.right = Some(Box::new(self.generate_bsp_tree(right_room, params)?));
+        } else if !split_horizontal && room.width >= params.min_room_size * 2 {
+            // Vertical split
+            let split_point =
+                rng.gen_range(params.min_room_size..=(room.width - params.min_room_size));
+
+            let left_room = Room {
+                x: room.x,
+                y: room.y,
+                width: split_point,
+                height: room.height,
+                id: room_id(
+                    self.seed,
+                    self.floor,
+                    room.x,
+                    room.y,
+                    split_point,
+                    room.height,
+                ),
+            };
+
+            let right_room = Room {
+                x: room.x + split_point,
+                y: room.y,
+                width: room.width - split_point,
+                height: room.height,
+                id: room_id(
+                    self.seed,
+                    self.floor,
+                    room.x + split_point,
+                    room.y,
+                    room.width - split_point,
+                    room.height,
+                ),
+            };
+
+            node.left = Some(Box::new(self.generate_bsp_tree(left_room, params)?));
+            node.right = Some(Box::new(self.generate_bsp_tree(right_room, params)?));
+        } else {
+            // Can't split further, make this a room
+            if room.width >= params.min_room_size && room.height >= params.min_room_size {
+                node.room = Some(room);
+            }
+        }
+
+        Ok(node)
+    }
+
+    fn place_rooms(&mut self, node: &BSPNode, _params: &BSPGenerationParams) -> Result<()> {
+        if let Some(ref room) = node.room {
+            // Place floor tiles
+            for y in room.y..room.y + room.height {
+                for x in room.x..room.x + room.width {
+                    if x < self.width && y < self.height {
+                        self.grid[y as usize][x as usize] = TileType::Floor;
+                    }
+                }
+            }
+
+            // Place wall tiles around the room
+            for y in room.y..room.y + room.height {
+                for x in room.x..room.x + room.width {
+                    if x < self.width && y < self.height {
+                        // Check if this is a border tile
+                        if x == room.x
+                            || x == room.x + room.width - 1
+                            || y == room.y
+                            || y == room.y + room.height - 1
+                        {
+                            if self.grid[y as usize][x as usize] != TileType::Floor {
+                                self.grid[y as usize][x as usize] = TileType::Wall;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Recursively process children
+        if let Some(ref left) = node.left {
+            self.place_rooms(left, _params)?;
+        }
+        if let Some(ref right) = node.right {
+            self.place_rooms(right, _params)?;
+        }
+
+        Ok(())
+    }
+
+    fn create_corridors(&mut self, node: &BSPNode, params: &BSPGenerationParams) -> Result<()> {
+        // Connect child rooms with corridors
+        if let (Some(ref left), Some(ref right)) = (&node.left, &node.right) {
+            self.create_corridors(left, params)?;
+            self.create_corridors(right, params)?;
+
+            // Connect the two sides
+            if let (Some(left_room), Some(right_room)) =
+                (self.find_room(left), self.find_room(right))
+            {
+                self.connect_rooms(&left_room, &right_room, params)?;
+                self.connections.push(RoomConnection {
+                    from: left_room.id,
+                    to: right_room.id,
+                    kind: ConnectionKind::Corridor,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn collect_rooms(node: &BSPNode, rooms: &mut Vec<Room>) {
+        if let Some(ref room) = node.room {
+            rooms.push(room.clone());
+        }
+        for child in [&node.left, &node.right].into_iter().flatten() {
+            Self::collect_rooms(child, rooms);
+        }
+    }
+
+    /// Turns corridor cells on a room's edge into doors where the corridor carries on
+    /// outside the room, each with a `door_probability` chance. Doorways wider than one
+    /// cell get a single door.
+    fn place_doors(&mut self, tree: &BSPNode, params: &BSPGenerationParams) {
+        let mut rooms = Vec::new();
+        Self::collect_rooms(tree, &mut rooms);
+
+        for room in rooms {
+            let (left, top) = (i64::from(room.x), i64::from(room.y));
+            let right = left + i64::from(room.width) - 1;
+            let bottom = top + i64::from(room.height) - 1;
+            let inside =
+                |x: i64, y: i64| (left..=right).contains(&x) && (top..=bottom).contains(&y);
+
+            for y in top..=bottom {
+                for x in left..=right {
+                    let on_edge = x == left || x == right || y == top || y == bottom;
+                    if !on_edge || self.tile(x, y) != Some(TileType::Corridor) {
+                        continue;
+                    }
+                    let exit = NEIGHBOURS.iter().find(|(dx, dy)| {
+                        !inside(x + dx, y + dy)
+                            && self.tile(x + dx, y + dy) == Some(TileType::Corridor)
+                    });
+                    let next_to_door = NEIGHBOURS.iter().any(|(dx, dy)| {
+                        matches!(self.tile(x + dx, y + dy), Some(TileType::Door { .. }))
+                    });
+                    if let Some((dx, _)) = exit {
+                        if !next_to_door
+                            && self
+                                .rng
+                                .as_mut()
+                                .unwrap()
+                                .gen_bool(f64::from(params.door_probability))
+                        {
+                            self.grid[y as usize][x as usize] =
+                                TileType::Door { facing_x: *dx != 0 };
+                            let door_id = generated_id(self.seed, "door", &self.cell_key(x, y));
+                            self.room_doors
+                                .entry(room.id.clone())
+                                .or_default()
+                                .push(door_id);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// The tile at a cell of the current floor, `None` outside the grid.
+    fn tile(&self, x: i64, y: i64) -> Option<TileType> {
+        let row = self.grid.get(usize::try_from(y).ok()?)?;
+        row.get(usize::try_from(x).ok()?).copied()
+    }
+
+    /// Joins neighbouring rooms of the current floor that have no corridor between them,
+    /// each pair with a `loop_factor` chance, so there is more than one way around.
+    fn add_loops(&mut self, params: &BSPGenerationParams) -> Result<()> {
+        let rooms: Vec<Room> = self
+            .rooms
+            .iter()
+            .filter(|(floor, _)| *floor == self.floor)
+            .map(|(_, room)| room.clone())
+            .collect();
+
+        for (i, first) in rooms.iter().enumerate() {
+            for second in &rooms[i + 1..] {
+                let joined = self.connections.iter().any(|c| {
+                    (c.from == first.id && c.to == second.id)
+                        || (c.from == second.id && c.to == first.id)
+                });
+                if joined
+                    || !rooms_adjacent(first, second)
+                    || !self
+                        .rng
+                        .as_mut()
+                        .unwrap()
+                        .gen_bool(f64::from(params.loop_factor))
+                {
+                    continue;
+                }
+                self.connect_rooms(first, second, params)?;
+                self.connections.push(RoomConnection {
+                    from: first.id.clone(),
+                    to: second.id.clone(),
+                    kind: ConnectionKind::Corridor,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::self_only_used_in_recursion)]
+    fn find_room(&self, node: &BSPNode) -> Option<Room> {
+        if let Some(ref room) = node.room {
+            Some(room.clone())
+        } else {
+            // Look for first available room in children
+            if let Some(ref left) = node.left {
+                if let Some(room) = self.find_room(left) {
+                    return Some(room);
+                }
+            }
+            if let Some(ref right) = node.right {
+                if let Some(room) = self.find_room(right) {
+                    return Some(room);
+                }
+            }
+            None
+        }
+    }
+
+    fn connect_rooms(
+        &mut self,
+        room1: &Room,
+        room2: &Room,
+        params: &BSPGenerationParams,
+    ) -> Result<()> {
+        let rng = self.rng.as_mut().unwrap();
+
+        // Find connection points (random points on room edges)
+        let point1_x = rng.gen_range(room1.x + 1..room1.x + room1.width - 1);
+        let point1_y = rng.gen_range(room1.y + 1..room1.y + room1.height - 1);
+
+        let point2_x = rng.gen_range(room2.x + 1..room2.x + room2.width - 1);
+        let point2_y = rng.gen_range(room2.y + 1..room2.y + room2.height - 1);
+
+        let width = if params.corridor_width_variation > 0 {
+            params.corridor_width + rng.gen_range(0..=params.corridor_width_variation)
+        } else {
+            params.corridor_width
+        };
+
+        match params.corridor_style {
+            CorridorStyle::LShaped => {
+                self.create_l_corridor(point1_x, point1_y, point2_x, point2_y, width)?;
+            }
+            CorridorStyle::Straight => self.create_straight_corridor(
+                room1,
+                room2,
+                (point1_x, point1_y),
+                (point2_x, point2_y),
+                width,
+            )?,
+            CorridorStyle::Winding => {
+                self.create_winding_corridor(
+                    (point1_x, point1_y),
+                    (point2_x, point2_y),
+                    width,
+                    false,
+                );
+            }
+            CorridorStyle::Organic => {
+                self.create_winding_corridor(
+                    (point1_x, point1_y),
+                    (point2_x, point2_y),
+                    width,
+                    true,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Marks a `width` by `width` block of corridor starting at a cell.
+    fn carve(&mut self, x: u32, y: u32, width: u32) {
+        for dy in 0..width {
+            for dx in 0..width {
+                if x + dx < self.width && y + dy < self.height {
+                    self.grid[(y + dy) as usize][(x + dx) as usize] = TileType::Corridor;
+                }
+            }
+        }
+    }
+
+    /// Runs straight across where the rooms overlap along one axis, falling back to an
+    /// L-shaped corridor when they don't.
+    fn create_straight_corridor(
+        &mut self,
+        room1: &Room,
+        room2: &Room,
+        from: (u32, u32),
+        to: (u32, u32),
+        width: u32,
+    ) -> Result<()> {
+        // Interior cells shared by two spans, excluding their edges
+        let shared = |start1: u32, length1: u32, start2: u32, length2: u32| {
+            let low = (start1 + 1).max(start2 + 1);
+            let high = (start1 + length1 - 1).min(start2 + length2 - 1);
+            (low < high).then_some((low, high))
+        };
+
+        if let Some((low, high)) = shared(room1.x, room1.width, room2.x, room2.width) {
+            let x = self.rng.as_mut().unwrap().gen_range(low..high);
+            for y in from.1.min(to.1)..=from.1.max(to.1) {
+                self.carve(x, y, width);
+            }
+        } else if let Some((low, high)) = shared(room1.y, room1.height, room2.y, room2.height) {
+            let y = self.rng.as_mut().unwrap().gen_range(low..high);
+            for x in from.0.min(to.0)..=from.0.max(to.0) {
+                self.carve(x, y, width);
+            }
+        } else {
+            self.create_l_corridor(from.0, from.1, to.0, to.1, width)?;
+        }
+        Ok(())
+    }
+
+    /// Carves the cheapest path between two cells with A*, over step costs drawn at
+    /// random so the path meanders. Cells that are already open cost the least, so new
+    /// corridors join existing ones. `organic` varies the width along the path.
+    fn create_winding_corridor(
+        &mut self,
+        from: (u32, u32),
+        to: (u32, u32),
+        width: u32,
+        organic: bool,
+    ) {
+        let (grid_width, grid_height) = (self.width as usize, self.height as usize);
+        let index = |(x, y): (u32, u32)| y as usize * grid_width + x as usize;
+        let rng = self.rng.as_mut().unwrap();
+        let costs: Vec<u32> = self
+            .grid
+            .iter()
+            .flatten()
+            .map(|&tile| {
+                if is_walkable(tile) {
+                    1
+                } else {
+                    rng.gen_range(2..=9)
+                }
+            })
+            .collect();
+
+        let heuristic = |(x, y): (u32, u32)| x.abs_diff(to.0) + y.abs_diff(to.1);
+        let mut best = vec![u32::MAX; grid_width * grid_height];
+        let mut came_from: Vec<Option<(u32, u32)>> = vec![None; grid_width * grid_height];
+        let mut open = BinaryHeap::from([Reverse((heuristic(from), from))]);
+        best[index(from)] = 0;
+
+        while let Some(Reverse((_, cell))) = open.pop() {
+            if cell == to {
+                break;
+            }
+            let (x, y) = cell;
+            let neighbours = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for next in neighbours {
+                if next.0 >= self.width || next.1 >= self.height {
+                    continue;
+                }
+                let cost = best[index(cell)] + costs[index(next)];
+                if cost < best[index(next)] {
+                    best[index(next)] = cost;
+                    came_from[index(next)] = Some(cell);
+                    open.push(Reverse((cost + heuristic(next), next)));
+                }
+            }
+        }
+
+        let mut path = vec![to];
+        let mut cell = to;
+        while let Some(previous) = came_from[index(cell)] {
+            path.push(previous);
+            cell = previous;
+        }
+        if cell != from {
+            // Unreachable, which can't happen on an open grid
+            return;
+        }
+
+        for (x, y) in path {
+            let width = if organic {
+                self.rng.as_mut().unwrap().gen_range(1..=width + 1)
+            } else {
+                width
+            };
+            self.carve(x, y, width);
+        }
+    }
+
+    fn create_l_corridor(&mut self, x1: u32, y1: u32, x2: u32, y2: u32, width: u32) -> Result<()> {
+        let rng = self.rng.as_mut().unwrap();
+
+        // Choose corner point randomly
+        let corner_x = if rng.gen_bool(0.5) { x1 } else { x2 };
+        let corner_y = if corner_x == x1 { y2 } else { y1 };
+
+        // Draw horizontal segment
+        let (start_x, end_x) = if x1 < corner_x {
+            (x1, corner_x)
+        } else {
+            (corner_x, x1)
+        };
+        for x in start_x..=end_x {
+            for w in 0..width {
+                let y = if corner_x == x1 { y1 + w } else { y2 + w };
+                if x < self.width && y < self.height {
+                    self.grid[y as usize][x as usize] = TileType::Corridor;
+                }
+            }
+        }
+
+        // Draw vertical segment
+        let (start_y, end_y) = if y1 < corner_y {
+            (y1, corner_y)
+        } else {
+            (corner_y, y1)
+        };
+        for y in start_y..=end_y {
+            for w in 0..width {
+                let x = if corner_x == x1 { x2 + w } else { x1 + w };
+                if x < self.width && y < self.height {
+                    self.grid[y as usize][x as usize] = TileType::Corridor;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Puts stairs on the floor below at a cell walkable on both floors. When no cell
+    /// lines up, a corridor is carved on this floor from above a walkable cell below to
+    /// one of this floor's rooms.
+    fn connect_floors(
+        &mut self,
+        below: &mut [Vec<TileType>],
+        tree: &BSPNode,
+        params: &BSPGenerationParams,
+    ) -> Result<()> {
+        let cells = |grid: &[Vec<TileType>], other: Option<&[Vec<TileType>]>| {
+            let mut cells = Vec::new();
+            for (y, row) in grid.iter().enumerate() {
+                for (x, &tile) in row.iter().enumerate() {
+                    if is_walkable(tile) && other.is_none_or(|other| is_walkable(other[y][x])) {
+                        cells.push((x, y));
+                    }
+                }
+            }
+            cells
+        };
+
+        let shared = cells(below, Some(&self.grid));
+        let (x, y) = if shared.is_empty() {
+            let open = cells(below, None);
+            if open.is_empty() {
+                return Ok(());
+            }
+            let rng = self.rng.as_mut().unwrap();
+            let (x, y) = open[rng.gen_range(0..open.len())];
+            self.grid[y][x] = TileType::Corridor;
+            if let Some(room) = self.find_room(tree) {
+                self.create_l_corridor(
+                    x as u32,
+                    y as u32,
+                    room.x + room.width / 2,
+                    room.y + room.height / 2,
+                    params.corridor_width,
+                )?;
+            }
+            (x, y)
+        } else {
+            let rng = self.rng.as_mut().unwrap();
+            shared[rng.gen_range(0..shared.len())]
+        };
+
+        below[y][x] = TileType::Stairs;
+        let (x, y) = (x as u32, y as u32);
+        if let (Some(lower), Some(upper)) = (
+            self.room_at(self.floor - 1, x, y),
+            self.room_at(self.floor, x, y),
+        ) {
+            self.connections.push(RoomConnection {
+                from: lower.id.clone(),
+                to: upper.id.clone(),
+                kind: ConnectionKind::Stairs,
+            });
+        }
+        Ok(())
+    }
+
+    fn room_at(&self, floor: u32, x: u32, y: u32) -> Option<&Room> {
+        self.rooms
+            .iter()
+            .filter(|(room_floor, _)| *room_floor == floor)
+            .map(|(_, room)| room)
+            .find(|room| {
+                (room.x..room.x + room.width).contains(&x)
+                    && (room.y..room.y + room.height).contains(&y)
+            })
+    }
+
+    fn room_graph(&self) -> RoomGraph {
+        let rooms = self
+            .rooms
+            .iter()
+            .map(|(floor, room)| {
+                let elevation = *floor as f32 * self.floor_height;
+                RoomInfo {
+                    id: room.id.clone(),
+                    floor: *floor,
+                    // Tiles are centered on their cell
+                    bounds: BoundingBox::new(
+                        [room.x as f32 - 0.5, elevation, room.y as f32 - 0.5],
+                        [
+                            (room.x + room.width) as f32 - 0.5,
+                            elevation + self.floor_height,
+                            (room.y + room.height) as f32 - 0.5,
+                        ],
+                    ),
+                    door_ids: self.room_doors.get(&room.id).cloned().unwrap_or_default(),
+                    room_type: RoomType::Normal,
+                }
+            })
+            .collect();
+        RoomGraph {
+            rooms,
+            connections: self.connections.clone(),
+        }
+    }
+
+    /// The parameters with the room graph added under [`ROOM_GRAPH_KEY`].
+    fn generation_params(
+        &self,
+        params: &BSPGenerationParams,
+        graph: &RoomGraph,
+    ) -> Result<serde_json::Value> {
+        let mut value = serde_json::to_value(params)?;
+        if let serde_json::Value::Object(ref mut map) = value {
+            map.insert(ROOM_GRAPH_KEY.to_string(), serde_json::to_value(graph)?);
+        }
+        Ok(value)
+    }
+
+    /// Height of the current floor's ground.
+    fn elevation(&self) -> f32 {
+        self.floor as f32 * self.floor_height
+    }
+
+    /// Cell coordinates that generated IDs on the current floor are derived from.
+    fn cell_key(&self, x: i64, y: i64) -> Vec<i64> {
+        // Floor 0 keeps the IDs single-floor levels always had
+        if self.floor > 0 {
+            vec![x, y, self.floor.into()]
+        } else {
+            vec![x, y]
+        }
+    }
+
+    /// Stable ID of the object generated in a grid cell.
+    fn cell_id(&self, kind: &str, x: f32, y: f32) -> String {
+        generated_id(self.seed, kind, &self.cell_key(x as i64, y as i64))
+    }
+
+    fn cell_name(&self, kind: &str, x: f32, y: f32) -> String {
+        if self.floor > 0 {
+            format!("{}_{}_{}_f{}", kind, x as u32, y as u32, self.floor)
+        } else {
+            format!("{}_{}_{}", kind, x as u32, y as u32)
+        }
+    }
+
+    fn floor_metadata(&self) -> HashMap<String, serde_json::Value> {
+        HashMap::from([("floor".to_string(), self.floor.into())])
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn grid_to_objects(&self, params: &BSPGenerationParams) -> Result<Vec<GameObject>> {
+        let mut objects = Vec::new();
+        let theme = params.theme.as_str();
+        let is_wall = |x: usize, y: Option<usize>| {
+            y.and_then(|y| self.grid.get(y)?.get(x))
+                .is_some_and(|&tile| tile == TileType::Wall)
+        };
+
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, &tile) in row.iter().enumerate() {
+                match tile {
+                    TileType::Floor => {
+                        objects.push(self.create_floor_object(x as f32, y as f32, theme)?);
+                    }
+                    TileType::Wall => {
+                        // Theme walls run along X; turn the ones that only continue along Z
+                        let along_x = (x > 0 && row[x - 1] == TileType::Wall)
+                            || row.get(x + 1) == Some(&TileType::Wall);
+                        let along_z = is_wall(x, y.checked_sub(1)) || is_wall(x, Some(y + 1));
+                        objects.push(self.create_wall_object(
+                            x as f32,
+                            y as f32,
+                            along_z && !along_x,
+                            theme,
+                        )?);
+                    }
+                    TileType::Corridor => {
+                        objects.push(self.create_corridor_object(x as f32, y as f32, theme)?);
+                    }
+                    TileType::Door { facing_x } => {
+                        // Doors stand on the corridor they close off
+                        objects.push(self.create_corridor_object(x as f32, y as f32, theme)?);
+                        objects.push(self.create_door_object(x as f32, y as f32, facing_x, theme)?);
+                    }
+                    TileType::Stairs => {
+                        objects.push(self.create_stairs_object(x as f32, y as f32, theme)?);
+                    }
+                    TileType::Empty => {} // Skip empty tiles
+                }
+            }
+        }
+
+        Ok(objects)
+    }
+
+    /// The theme's tile for the first of `keys` it has, or `fallback` for themes
+    /// without one. Mesh variants are picked at random, but the same for a seed and cell.
+    fn cell_tile(
+        &self,
+        keys: &[&str],
+        x: f32,
+        y: f32,
+        fallback: TileMesh,
+        collision: bool,
+    ) -> CellTile {
+        let themed = self.theme.as_ref().and_then(|theme| {
+            keys.iter().find_map(|&key| {
+                let tile = theme.tiles.get(key)?;
+                let roll = generated_roll(
+                    self.seed,
+                    &format!("{}_variant", key),
+                    &self.cell_key(x as i64, y as i64),
+                );
+                Some((tile, theme.mesh_variant(key, roll)))
+            })
+        });
+        match themed {
+            Some((tile, variant)) => CellTile {
+                mesh: tile.mesh.clone(),
+                variant: variant.map(str::to_string),
+                tags: tile.tags.clone(),
+                collision: tile.collision,
+            },
+            None => CellTile {
+                mesh: fallback,
+                variant: None,
+                tags: Vec::new(),
+                collision,
+            },
+        }
+    }
+
+    /// Places `tile` in cell (`x`, `y`) of the current floor, turned a quarter around
+    /// Y when `turned`.
+    fn tile_transform(&self, tile: &CellTile, x: f32, y: f32, turned: bool) -> Transform3D {
+        let (pitch, yaw, roll) = tile.mesh.rotation;
+        let (ox, oy, oz) = tile.mesh.offset;
+        // A quarter turn takes +X to -Z, and the offset with it
+        let (yaw, ox, oz) = if turned {
+            (yaw + 90.0, oz, -ox)
+        } else {
+            (yaw, ox, oz)
+        };
+        Transform3D {
+            position: [x + ox, self.elevation() + oy, y + oz],
+            rotation: from_euler_yxz(yaw.to_radians(), pitch.to_radians(), roll.to_radians()),
+            scale: tile.mesh.scale.into(),
+        }
+    }
+
+    fn create_floor_object(&self, x: f32, y: f32, theme: &str) -> Result<GameObject> {
+        let tile = self.cell_tile(
+            &["floor"],
+            x,
+            y,
+            plain_mesh("cube", "floor", 0.1, 0.0),
+            false,
+        );
+        Ok(GameObject {
+            id: self.cell_id("floor", x, y),
+            name: self.cell_name("floor", x, y),
+            transform: self.tile_transform(&tile, x, y, false),
+            material: Some(tile.material_path(theme)),
+            mesh: Some(tile.mesh_path()),
+            layer: "Floors".to_string(),
+            tags: tile.tags(&["floor"], theme),
+            metadata: {
+                let mut metadata = self.floor_metadata();
+                if let Some(room) = self.room_at(self.floor, x as u32, y as u32) {
+                    metadata.insert("room_id".to_string(), room.id.clone().into());
+                    let room_type = self.room_types.get(&room.id).copied().unwrap_or_default();
+                    metadata.insert("room_type".to_string(), room_type.as_str().into());
+                }
+                metadata
+            },
+            kind: ObjectKind::Mesh,
+            physics: None,
+        })
+    }
+
+    fn create_wall_object(&self, x: f32, y: f32, turned: bool, theme: &str) -> Result<GameObject> {
+        let tile = self.cell_tile(&["wall"], x, y, plain_mesh("cube", "wall", 2.0, 1.0), true);
+        Ok(GameObject {
+            id: self.cell_id("wall", x, y),
+            name: self.cell_name("wall", x, y),
+            transform: self.tile_transform(&tile, x, y, turned),
+            material: Some(tile.material_path(theme)),
+            mesh: Some(tile.mesh_path()),
+            layer: "Walls".to_string(),
+            tags: tile.tags(&["wall"], theme),
+            metadata: self.floor_metadata(),
+            kind: ObjectKind::Mesh,
+            physics: None,
+        })
+    }
+
+    fn create_corridor_object(&self, x: f32, y: f32, theme: &str) -> Result<GameObject> {
+        let fallback = plain_mesh("cube", "corridor", 0.1, 0.0);
+        let tile = self.cell_tile(&["corridor", "floor"], x, y, fallback, false);
+        Ok(GameObject {
+            id: self.cell_id("corridor", x, y),
+            name: self.cell_name("corridor", x, y),
+            transform: self.tile_transform(&tile, x, y, false),
+            material: Some(tile.material_path(theme)),
+            mesh: Some(tile.mesh_path()),
+            layer: "Floors".to_string(),
+            tags: tile.tags(&["corridor"], theme),
+            metadata: self.floor_metadata(),
+            kind: ObjectKind::Mesh,
+            physics: None,
+        })
+    }
+
+    fn create_door_object(
+        &self,
+        x: f32,
+        y: f32,
+        facing_x: bool,
+        theme: &str,
+    ) -> Result<GameObject> {
+        let fallback = TileMesh {
+            scale: (1.0, 2.0, 0.2),
+            ..plain_mesh("door", "door", 2.0, 1.0)
+        };
+        let tile = self.cell_tile(&["door"], x, y, fallback, false);
+        // Doors are thin along Z, so ones facing X are turned a quarter around Y
+        Ok(GameObject {
+            id: self.cell_id("door", x, y),
+            name: self.cell_name("door", x, y),
+            transform: self.tile_transform(&tile, x, y, facing_x),
+            material: Some(tile.material_path(theme)),
+            mesh: Some(tile.mesh_path()),
+            layer: "Doors".to_string(),
+            tags: tile.tags(&["door", "interactive"], theme),
+            metadata: self.floor_metadata(),
+            kind: ObjectKind::Door(DoorProperties::default()),
+            physics: None,
+        })
+    }
+
+    fn create_stairs_object(&self, x: f32, y: f32, theme: &str) -> Result<GameObject> {
+        let mut metadata = self.floor_metadata();
+        metadata.insert("to_floor".to_string(), (self.floor + 1).into());
+        let fallback = plain_mesh("stairs", "stairs", 1.0, 0.5);
+        let mut tile = self.cell_tile(&["stairs", "stairs_up"], x, y, fallback, false);
+        // Stair tiles are a storey of height 1, stretched to the real one
+        tile.mesh.scale.1 *= self.floor_height;
+        tile.mesh.offset.1 *= self.floor_height;
+        Ok(GameObject {
+            id: self.cell_id("stairs", x, y),
+            name: self.cell_name("stairs", x, y),
+            transform: self.tile_transform(&tile, x, y, false),
+            material: Some(tile.material_path(theme)),
+            mesh: Some(tile.mesh_path()),
+            layer: "Stairs".to_string(),
+            tags: tile.tags(&["stairs", "connector"], theme),
+            metadata,
+            kind: ObjectKind::Mesh,
+            physics: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::connectivity::{cell_of, world_to_cell, ConnectivityReport};
+    use crate::testing::assert_close;
+
+    fn params(depth: u32) -> BSPGenerationParams {
+        BSPGenerationParams {
+            width: 30,
+            height: 30,
+            depth,
+            min_room_size: 4,
+            max_room_size: 10,
+            corridor_width: 1,
+            theme: "office".to_string(),
+            seed: Some(11),
+            door_probability: 1.0,
+            corridor_style: CorridorStyle::LShaped,
+            corridor_width_variation: 0,
+            loop_factor: 0.0,
+            treasure_room_weight: 0.5,
+            shop_room_weight: 0.1,
+            spawns: None,
+        }
+    }
+
+    fn floor_of(obj: &GameObject) -> u64 {
+        obj.metadata["floor"].as_u64().unwrap()
+    }
+
+    #[test]
+    fn stacks_floors_joined_by_stairs() {
+        let generator = BSPGenerator::new();
+        let single = tokio_test::block_on(generator.generate(params(1))).unwrap();
+        let level = tokio_test::block_on(generator.generate(params(3))).unwrap();
+        let wall_height = Theme::office().wall_height;
+
+        for floor in 0..3 {
+            let on_floor: Vec<&GameObject> = level
+                .objects
+                .iter()
+                .filter(|o| floor_of(o) == floor && o.layer == "Floors")
+                .collect();
+            assert!(!on_floor.is_empty());
+            for obj in on_floor {
+                let above_floor = wall_height.mul_add(-(floor as f32), obj.transform.position[1]);
+                assert!(above_floor.abs() < 1e-5);
+            }
+        }
+
+        let stairs: Vec<&GameObject> = level
+            .objects
+            .iter()
+            .filter(|o| o.layer == "Stairs")
+            .collect();
+        assert_eq!(stairs.len(), 2);
+        for stair in stairs {
+            // The cell above the stairs is walkable
+            let (floor, x, z) = cell_of(stair);
+            assert!(level
+                .objects
+                .iter()
+                .any(|o| o.layer == "Floors" && cell_of(o) == (floor + 1, x, z)));
+        }
+
+        // The ground floor is laid out as it is in a single-floor level, IDs included
+        let ids: std::collections::HashSet<&str> =
+            level.objects.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(ids.len(), level.objects.len());
+        assert!(single.objects.iter().all(|o| o.layer != "Stairs"));
+        let ground: Vec<&str> = level
+            .objects
+            .iter()
+            .filter(|o| floor_of(o) == 0 && o.layer != "Stairs")
+            .map(|o| o.id.as_str())
+            .collect();
+        assert!(ground
+            .iter()
+            .all(|id| single.objects.iter().any(|o| o.id == *id)));
+        assert!(wall_height.mul_add(-3.0, level.bounds.max[1]).abs() < 1e-5);
+    }
+
+    #[test]
+    fn builds_cells_from_theme_tiles() {
+        let generator = BSPGenerator::new();
+        let build = |theme: &str| {
+            let params = BSPGenerationParams {
+                theme: theme.to_string(),
+                ..params(1)
+            };
+            tokio_test::block_on(generator.generate(params)).unwrap()
+        };
+        let castle = build("castle");
+        let dungeon = build("dungeon");
+        assert_eq!(dungeon.objects.len(), castle.objects.len());
+        for (level, theme) in [(&castle, Theme::castle()), (&dungeon, Theme::dungeon())] {
+            let door = &theme.tiles["door"].mesh;
+            for obj in level.objects.iter().filter(|o| o.layer == "Doors") {
+                assert_close(obj.transform.scale, door.scale.into());
+                assert!((obj.transform.position[1] - door.offset.1).abs() < 1e-5);
+                assert_eq!(obj.mesh, Some(format!("meshes/{}.mesh", door.mesh_type)));
+                assert_eq!(
+                    obj.material,
+                    Some(format!("materials/{}/door.mat", theme.id))
+                );
+            }
+        }
+
+        // Walls run along X, and are turned where they only continue along Z
+        let mut walled = BSPGenerator {
+            grid: vec![
+                vec![TileType::Wall, TileType::Wall, TileType::Wall],
+                vec![TileType::Wall, TileType::Floor, TileType::Floor],
+                vec![TileType::Wall, TileType::Floor, TileType::Floor],
+            ],
+            theme: Some(Theme::castle()),
+            ..BSPGenerator::new()
+        };
+        let wall = |generator: &BSPGenerator, cell: (i32, i32)| {
+            let objects = generator.grid_to_objects(&params(1)).unwrap();
+            objects
+                .into_iter()
+                .find(|o| o.layer == "Walls" && world_to_cell(o.transform.position) == cell)
+                .unwrap()
+        };
+        let castle_wall = &Theme::castle().tiles["wall"].mesh;
+        let corner = wall(&walled, (0, 0));
+        assert_close(corner.transform.scale, castle_wall.scale.into());
+        assert_close(corner.transform.rotation, [0.0, 0.0, 0.0, 1.0]);
+        assert!(corner.tags.contains(&"collision".to_string()));
+        let side = wall(&walled, (0, 2)).transform.rotation;
+        assert!((side[1] - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-5);
+
+        // Themes the library doesn't have get plain cubes
+        walled.theme = None;
+        assert_close(wall(&walled, (0, 2)).transform.scale, [1.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn picks_mesh_variants_per_cell() {
+        let mut theme = Theme::office();
+        let variants = vec![
+            "meshes/carpet_a.glb".to_string(),
+            "meshes/carpet_b.glb".to_string(),
+        ];
+        theme
+            .mesh_variants
+            .insert("floor".to_string(), variants.clone());
+        let floor_meshes = |seed: u64| -> Vec<String> {
+            let generator = BSPGenerator {
+                seed,
+                grid: vec![vec![TileType::Floor; 8]; 8],
+                theme: Some(theme.clone()),
+                ..BSPGenerator::new()
+            };
+            let objects = generator.grid_to_objects(&params(1)).unwrap();
+            objects.into_iter().filter_map(|o| o.mesh).collect()
+        };
+
+        let meshes = floor_meshes(1);
+        assert!(variants.iter().all(|variant| meshes.contains(variant)));
+        assert_eq!(floor_meshes(1), meshes);
+        assert_ne!(floor_meshes(2), meshes);
+    }
+
+    #[test]
+    fn places_doors_where_corridors_enter_rooms() {
+        let generator = BSPGenerator::new();
+        let level = tokio_test::block_on(generator.generate(params(1))).unwrap();
+        let doors: Vec<&GameObject> = level
+            .objects
+            .iter()
+            .filter(|o| o.layer == "Doors")
+            .collect();
+        assert!(!doors.is_empty());
+        for door in doors {
+            assert!(matches!(door.kind, ObjectKind::Door(_)));
+            let cell = world_to_cell(door.transform.position);
+            assert!(level.objects.iter().any(|o| {
+                o.tags.contains(&"corridor".to_string())
+                    && world_to_cell(o.transform.position) == cell
+            }));
+        }
+
+        let without = BSPGenerationParams {
+            door_probability: 0.0,
+            ..params(1)
+        };
+        let level = tokio_test::block_on(generator.generate(without)).unwrap();
+        assert!(level.objects.iter().all(|o| o.layer != "Doors"));
+
+        let invalid = BSPGenerationParams {
+            door_probability: 1.5,
+            ..params(1)
+        };
+        assert!(tokio_test::block_on(generator.generate(invalid)).is_err());
+    }
+
+    #[test]
+    fn records_room_graph() {
+        let generator = BSPGenerator::new();
+        let level = tokio_test::block_on(generator.generate(params(2))).unwrap();
+        let graph = RoomGraph::from_level(&level).unwrap();
+        assert!(graph.rooms.len() > 2);
+        assert!(graph.rooms.iter().any(|room| room.floor == 1));
+
+        let ids: std::collections::HashSet<&str> =
+            graph.rooms.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids.len(), graph.rooms.len());
+        for connection in &graph.connections {
+            assert!(ids.contains(connection.from.as_str()) && ids.contains(connection.to.as_str()));
+        }
+        assert!(graph
+            .connections
+            .iter()
+            .any(|c| c.kind == ConnectionKind::Corridor));
+
+        // Every door is listed on a room, and floor tiles know their room
+        let door_ids: Vec<&String> = graph.rooms.iter().flat_map(|r| &r.door_ids).collect();
+        for door in level.objects.iter().filter(|o| o.layer == "Doors") {
+            assert!(door_ids.contains(&&door.id));
+        }
+        for tile in level
+            .objects
+            .iter()
+            .filter(|o| o.tags.contains(&"floor".to_string()))
+        {
+            let room_id = tile.metadata["room_id"].as_str().unwrap();
+            let room = graph.room(room_id).unwrap();
+            let [x, _, z] = tile.transform.position;
+            assert!(room.bounds.min[0] < x && x < room.bounds.max[0]);
+            assert!(room.bounds.min[2] < z && z < room.bounds.max[2]);
+            assert_eq!(tile.metadata["room_type"], room.room_type.as_str());
+        }
+
+        // One spawn on the ground floor and one boss room
+        let of_type = |room_type| graph.rooms.iter().filter(move |r| r.room_type == room_type);
+        assert_eq!(of_type(RoomType::Spawn).count(), 1);
+        assert_eq!(of_type(RoomType::Spawn).next().unwrap().floor, 0);
+        assert_eq!(of_type(RoomType::Boss).count(), 1);
+
+        // The connectivity check's report travels with the level
+        let report = ConnectivityReport::from_level(&level).unwrap();
+        assert!(report.connected && report.unreachable_rooms.is_empty());
+    }
+
+    #[test]
+    fn corridor_styles_change_the_layout() {
+        let generator = BSPGenerator::new();
+        let corridor_cells = |style: CorridorStyle| {
+            let params = BSPGenerationParams {
+                corridor_style: style,
+                corridor_width_variation: 1,
+                ..params(1)
+            };
+            let level = tokio_test::block_on(generator.generate(params)).unwrap();
+            let mut cells: Vec<(i64, i64)> = level
+                .objects
+                .iter()
+                .filter(|o| o.tags.contains(&"corridor".to_string()))
+                .map(|o| {
+                    (
+                        o.transform.position[0] as i64,
+                        o.transform.position[2] as i64,
+                    )
+                })
+                .collect();
+            cells.sort_unstable();
+            cells
+        };
+
+        let layouts: Vec<Vec<(i64, i64)>> = [
+            CorridorStyle::LShaped,
+            CorridorStyle::Straight,
+            CorridorStyle::Winding,
+            CorridorStyle::Organic,
+        ]
+        .into_iter()
+        .map(corridor_cells)
+        .collect();
+        for (i, layout) in layouts.iter().enumerate() {
+            assert!(!layout.is_empty());
+            assert!(layouts[i + 1..].iter().all(|other| other != layout));
+        }
+    }
+
+    #[test]
+    fn loop_factor_adds_cycles() {
+        let generator = BSPGenerator::new();
+        let graph = |loop_factor: f32| {
+            let params = BSPGenerationParams {
+                loop_factor,
+                ..params(1)
+            };
+            let level = tokio_test::block_on(generator.generate(params)).unwrap();
+            RoomGraph::from_level(&level).unwrap()
+        };
+
+        let tree = graph(0.0);
+        let looped = graph(1.0);
+        assert!(tree.connections.len() < tree.rooms.len());
+        // As many connections as rooms means at least one cycle
+        assert!(looped.connections.len() >= looped.rooms.len());
+
+        let invalid = BSPGenerationParams {
+            loop_factor: -0.5,
+            ..params(1)
+        };
+        assert!(tokio_test::block_on(generator.generate(invalid)).is_err());
+    }
+
+    #[test]
+    fn places_spawns_in_the_spawn_room() {
+        let generator = BSPGenerator::new();
+        let params = BSPGenerationParams {
+            spawns: Some(crate::generation::placement::SpawnPlacementParams::default()),
+            ..params(1)
+        };
+        let level = tokio_test::block_on(generator.generate(params)).unwrap();
+        let graph = RoomGraph::from_level(&level).unwrap();
+        let spawn_room = graph
+            .rooms
+            .iter()
+            .find(|room| room.room_type == RoomType::Spawn)
+            .unwrap();
+
+        let report = crate::level::spawns::validate_level_spawns(&level);
+        assert!(report.valid, "{:?}", report.issues);
+        assert_eq!(report.level_exits, 1);
+        let start = level
+            .objects
+            .iter()
+            .find(|o| o.tags.contains(&"player_start".to_string()))
+            .unwrap();
+        assert_eq!(start.metadata["room_id"], spawn_room.id.as_str());
+    }
+}
@@ -0,0 +1,391 @@
+//! Prop scatter for generated and hand-edited levels.
+//!
+//! Props go on room floor tiles, against a wall, out in the open or in the middle of a
+//! room, and stay off corridors and the cells just inside doorways so paths through the
+//! level remain clear. Which props a theme uses comes from its prop catalog; meshes the
+//! catalog doesn't give are looked up by the caller, usually in the asset database.
+
+use crate::generation::themes::ThemeLibrary;
+use crate::level::connectivity::{cell_of, CellKind};
+use crate::stable::generated_id;
+use crate::{GameObject, LevelData, ObjectKind, Transform3D};
+use anyhow::{bail, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Layer decoration props are placed on.
+pub const PROPS_LAYER: &str = "Props";
+
+/// Where in a room a prop may go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PropPlacement {
+    /// Away from the walls
+    Floor,
+    /// Against a wall, facing into the room
+    Wall,
+    /// In the middle of a room, with no wall in any of the eight cells around it
+    Center,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropRule {
+    /// Prop name, also the term its mesh is searched for by
+    pub name: String,
+    pub placement: PropPlacement,
+    /// Chance, from 0 to 1, that a free cell of the right kind gets this prop
+    pub density: f32,
+    /// Mesh to use instead of looking one up by name
+    #[serde(default)]
+    pub mesh: Option<String>,
+    /// Theme material to put on the prop, instead of the mesh's own
+    #[serde(default)]
+    pub material: Option<String>,
+}
+
+impl PropRule {
+    #[must_use]
+    pub fn new(name: &str, placement: PropPlacement, density: f32) -> Self {
+        Self {
+            name: name.to_string(),
+            placement,
+            density,
+            mesh: None,
+            material: None,
+        }
+    }
+
+    fn fits(&self, cell: &FreeCell) -> bool {
+        match self.placement {
+            PropPlacement::Floor => cell.wall.is_none(),
+            PropPlacement::Wall => cell.wall.is_some(),
+            PropPlacement::Center => cell.center,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecorationParams {
+    /// Theme to pick props for, instead of the one the level was generated with
+    #[serde(default)]
+    pub theme: Option<String>,
+    /// Props to scatter instead of the theme's
+    #[serde(default)]
+    pub props: Option<Vec<PropRule>>,
+    /// Multiplies the density of every prop
+    #[serde(default = "default_density_scale")]
+    pub density_scale: f32,
+    /// Seed for reproducible placement, defaulting to the level's generation seed
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+const fn default_density_scale() -> f32 {
+    1.0
+}
+
+impl Default for DecorationParams {
+    fn default() -> Self {
+        Self {
+            theme: None,
+            props: None,
+            density_scale: default_density_scale(),
+            seed: None,
+        }
+    }
+}
+
+/// Props a theme is decorated with when none are given: its prop catalog, or crates
+/// for themes without one.
+#[must_use]
+pub fn theme_props(theme: &str) -> Vec<PropRule> {
+    ThemeLibrary::current()
+        .get_theme(theme)
+        .map(|theme| theme.props)
+        .filter(|props| !props.is_empty())
+        .unwrap_or_else(|| vec![PropRule::new("crate", PropPlacement::Floor, 0.05)])
+}
+
+/// The theme or WFC tileset recorded in `level`'s generation parameters.
+#[must_use]
+pub fn recorded_theme(level: &LevelData) -> Option<&str> {
+    level.generation_params.as_ref().and_then(|params| {
+        params
+            .get("theme")
+            .or_else(|| params.get("tileset"))
+            .and_then(serde_json::Value::as_str)
+    })
+}
+
+impl DecorationParams {
+    /// The theme to decorate `level` with: the given one, or the theme or WFC tileset
+    /// recorded in its generation parameters.
+    pub fn theme_for(&self, level: &LevelData) -> Result<String> {
+        if let Some(theme) = &self.theme {
+            return Ok(theme.clone());
+        }
+        match recorded_theme(level) {
+            Some(theme) => Ok(theme.to_string()),
+            None => bail!("No theme given and the level wasn't generated with one"),
+        }
+    }
+
+    /// The props to scatter over `level`.
+    pub fn props_for(&self, level: &LevelData) -> Result<Vec<PropRule>> {
+        let props = match &self.props {
+            Some(props) => props.clone(),
+            None => theme_props(&self.theme_for(level)?),
+        };
+        if !(self.density_scale.is_finite() && self.density_scale >= 0.0) {
+            bail!("Density scale must not be negative");
+        }
+        if props.iter().any(|p| !(0.0..=1.0).contains(&p.density)) {
+            bail!("Prop density must be between 0 and 1");
+        }
+        Ok(props)
+    }
+}
+
+/// A room floor cell: `(floor, x, z)`.
+type Cell = (i32, i32, i32);
+
+const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// A room floor cell props can go in.
+struct FreeCell {
+    elevation: f32,
+    room_id: Option<serde_json::Value>,
+    /// Direction of a wall beside the cell
+    wall: Option<(i32, i32)>,
+    /// Whether all eight cells around it are room floor
+    center: bool,
+}
+
+/// Room floor cells with nothing on them, leaving out the ones beside doorways and
+/// corridors.
+fn free_cells(level: &LevelData) -> BTreeMap<Cell, FreeCell> {
+    let mut kinds: HashMap<Cell, CellKind> = HashMap::new();
+    let mut occupied = HashSet::new();
+    let mut floors = Vec::new();
+    for obj in &level.objects {
+        let cell = cell_of(obj);
+        let Some(kind) = CellKind::from_object(obj) else {
+            occupied.insert(cell);
+            continue;
+        };
+        let entry = kinds.entry(cell).or_insert(kind);
+        if kind > *entry {
+            *entry = kind;
+        }
+        if kind == CellKind::Room {
+            floors.push((cell, obj));
+        }
+    }
+
+    let mut cells = BTreeMap::new();
+    for ((floor, x, z), tile) in floors {
+        if occupied.contains(&(floor, x, z)) || kinds[&(floor, x, z)] != CellKind::Room {
+            continue;
+        }
+        let beside = |(dx, dz): (i32, i32)| kinds.get(&(floor, x + dx, z + dz)).copied();
+        if DIRECTIONS.into_iter().any(|d| {
+            matches!(
+                beside(d),
+                Some(CellKind::Door | CellKind::Corridor | CellKind::Stairs)
+            )
+        }) {
+            continue;
+        }
+        cells.insert(
+            (floor, x, z),
+            FreeCell {
+                elevation: tile.transform.position[1],
+                room_id: tile.metadata.get("room_id").cloned(),
+                wall: DIRECTIONS
+                    .into_iter()
+                    .find(|&d| beside(d) == Some(CellKind::Blocked)),
+                center: (-1..=1)
+                    .flat_map(|dx| (-1..=1).map(move |dz| (dx, dz)))
+                    .all(|d| beside(d) == Some(CellKind::Room)),
+            },
+        );
+    }
+    cells
+}
+
+/// Scatters props over the free room cells of `level` and returns the objects added.
+///
+/// `mesh_for` picks the mesh of props that don't name one; props it has no mesh for
+/// get `meshes/<theme>/<name>.mesh`. Cells that already hold an object, including props
+/// from an earlier pass, are left alone.
+pub fn decorate(
+    level: &mut LevelData,
+    params: &DecorationParams,
+    mesh_for: impl Fn(&PropRule) -> Option<String>,
+) -> Result<Vec<GameObject>> {
+    let theme = params
+        .theme_for(level)
+        .unwrap_or_else(|_| "default".to_string());
+    let props = params.props_for(level)?;
+    let seed = params.seed.or(level.generation_seed).unwrap_or(0);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut added = Vec::new();
+    for ((floor, x, z), cell) in free_cells(level) {
+        let Some(prop) = props
+            .iter()
+            .filter(|p| p.fits(&cell))
+            .find(|p| rng.gen_bool(f64::from((p.density * params.density_scale).min(1.0))))
+        else {
+            continue;
+        };
+
+        // Wall props back onto the wall, floor props face any of the four ways
+        let (position, yaw) = match cell.wall {
+            Some((dx, dz)) => (
+                [
+                    (dx as f32).mul_add(0.35, x as f32),
+                    cell.elevation,
+                    (dz as f32).mul_add(0.35, z as f32),
+                ],
+                (-dx as f32).atan2(-dz as f32),
+            ),
+            None => (
+                [x as f32, cell.elevation, z as f32],
+                f32::from(rng.gen_range(0u8..4)) * std::f32::consts::FRAC_PI_2,
+            ),
+        };
+
+        let mut metadata = HashMap::from([
+            ("floor".to_string(), floor.into()),
+            ("prop".to_string(), prop.name.clone().into()),
+        ]);
+        if let Some(room_id) = cell.room_id {
+            metadata.insert("room_id".to_string(), room_id);
+        }
+        added.push(GameObject {
+            id: generated_id(seed, "prop", &[x.into(), z.into(), floor.into()]),
+            name: format!("{}_{}_{}", prop.name, x, z),
+            transform: Transform3D {
+                position,
+                rotation: [0.0, (yaw * 0.5).sin(), 0.0, (yaw * 0.5).cos()],
+                scale: [1.0, 1.0, 1.0],
+            },
+            material: prop
+                .material
+                .as_ref()
+                .map(|material| format!("materials/{}/{}.mat", theme, material)),
+            mesh: Some(
+                prop.mesh
+                    .clone()
+                    .or_else(|| mesh_for(prop))
+                    .unwrap_or_else(|| format!("meshes/{}/{}.mesh", theme, prop.name)),
+            ),
+            layer: PROPS_LAYER.to_string(),
+            tags: vec!["prop".to_string(), prop.name.clone(), theme.clone()],
+            metadata,
+            kind: ObjectKind::Mesh,
+            physics: None,
+        });
+    }
+
+    if !added.is_empty() && !level.layers.iter().any(|l| l == PROPS_LAYER) {
+        level.layers.push(PROPS_LAYER.to_string());
+    }
+    level.objects.extend(added.iter().cloned());
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::connectivity::world_to_cell;
+    use crate::testing::{level, tile};
+
+    /// A 6x6 room inside walls, with a corridor leading out of its west side.
+    fn room() -> LevelData {
+        let mut objects = Vec::new();
+        for x in 0..8 {
+            for z in 0..8 {
+                let tag = if x == 0 && z == 3 {
+                    "corridor"
+                } else if x == 0 || z == 0 || x == 7 || z == 7 {
+                    "wall"
+                } else {
+                    "floor"
+                };
+                objects.push(tile(x as f32, z as f32, tag));
+            }
+        }
+        LevelData {
+            layers: vec!["Floors".to_string()],
+            generation_seed: Some(3),
+            generation_params: Some(serde_json::json!({ "theme": "dungeon" })),
+            ..level(objects)
+        }
+    }
+
+    #[test]
+    fn scatters_theme_props_away_from_doorways() {
+        let mut level = room();
+        let params = DecorationParams {
+            density_scale: 100.0,
+            ..DecorationParams::default()
+        };
+        let meshes =
+            |prop: &PropRule| (prop.name == "torch").then(|| "Assets/torch.glb".to_string());
+        let added = decorate(&mut level, &params, meshes).unwrap();
+
+        // Every free cell gets a prop: 36 room cells less the one by the corridor
+        assert_eq!(added.len(), 35);
+        assert!(level.layers.contains(&PROPS_LAYER.to_string()));
+        for prop in &added {
+            let (x, z) = world_to_cell(prop.transform.position);
+            assert_ne!((x, z), (1, 3));
+            let against_wall = x == 1 || z == 1 || x == 6 || z == 6;
+            let name = prop.metadata["prop"].as_str().unwrap();
+            assert_eq!(name, if against_wall { "torch" } else { "crate" });
+            if against_wall {
+                assert_eq!(prop.mesh.as_deref(), Some("Assets/torch.glb"));
+            } else {
+                assert_eq!(prop.mesh.as_deref(), Some("meshes/dungeon/crate.mesh"));
+            }
+        }
+
+        // A second pass finds nowhere left to put anything
+        assert!(decorate(&mut level, &params, meshes).unwrap().is_empty());
+
+        let untitled = LevelData {
+            generation_params: None,
+            ..room()
+        };
+        assert!(DecorationParams::default().props_for(&untitled).is_err());
+    }
+
+    #[test]
+    fn places_catalog_props_in_room_centers() {
+        let mut level = room();
+        let table = PropRule {
+            mesh: Some("Assets/table.glb".to_string()),
+            material: Some("wood".to_string()),
+            ..PropRule::new("table", PropPlacement::Center, 1.0)
+        };
+        let params = DecorationParams {
+            props: Some(vec![table]),
+            ..DecorationParams::default()
+        };
+        let added = decorate(&mut level, &params, |_| None).unwrap();
+
+        // Only the 4x4 middle of the room is clear of walls on all sides
+        assert_eq!(added.len(), 16);
+        for prop in &added {
+            let (x, z) = world_to_cell(prop.transform.position);
+            assert!((2..=5).contains(&x) && (2..=5).contains(&z));
+            assert_eq!(prop.mesh.as_deref(), Some("Assets/table.glb"));
+            assert_eq!(prop.material.as_deref(), Some("materials/dungeon/wood.mat"));
+        }
+        assert_eq!(theme_props("cave")[1].placement, PropPlacement::Center);
+    }
+}
@@ -0,0 +1,12 @@
+pub mod bsp;
+pub mod decoration;
+pub mod overlapping;
+pub mod pipeline;
+pub mod placement;
+pub mod preview;
+pub mod wfc;
+pub mod themes;
+
+// pub use bsp::BSPGenerator;
+// pub use wfc::WFCGenerator;
+// pub use themes::Theme;
\ No newline at end of file
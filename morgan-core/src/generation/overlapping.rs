@@ -0,0 +1,429 @@
+//! Overlapping-model Wave Function Collapse.
+//!
+//! Instead of hand-written adjacency rules, the overlapping model learns every NxN
+//! pattern in a sample level along with how often it appears, then builds a new grid
+//! in which every NxN window is one of those patterns. The output keeps the sample's
+//! room shapes, wall thickness and corridor widths rather than the noise the tiled
+//! model tends to produce.
+
+use crate::generation::themes::{parse_grid_string, ThemeLibrary};
+use crate::level::connectivity::{cell_of, floor_of, CellKind};
+use crate::LevelData;
+use anyhow::{anyhow, bail, Result};
+use rand::rngs::StdRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Tile key of sample cells holding nothing; cells given this key get no object.
+pub const EMPTY_TILE: &str = "empty";
+
+/// Offsets of the four neighbours of a cell: north, east, south, west.
+const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Where the overlapping model learns its patterns from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum WFCSample {
+    /// One floor of an existing level, tiles keyed by their WFC `tile_type` or by what
+    /// their tags say they are
+    Level {
+        level: Box<LevelData>,
+        #[serde(default)]
+        floor: i32,
+    },
+    /// A grid string drawn with a theme's tile icons, as the 2D editor produces
+    Grid { theme: String, grid: String },
+}
+
+impl WFCSample {
+    /// The sample as rows of tile keys.
+    pub fn tile_map(&self) -> Result<Vec<Vec<String>>> {
+        let map = match self {
+            WFCSample::Level { level, floor } => level_tile_map(level, *floor),
+            WFCSample::Grid { theme, grid } => {
+                let theme = ThemeLibrary::current()
+                    .get_theme(theme)
+                    .ok_or_else(|| anyhow!("Theme not found: {}", theme))?;
+                parse_grid_string(&theme, grid)
+            }
+        };
+        let width = map.first().map_or(0, Vec::len);
+        if width == 0 || map.iter().any(|row| row.len() != width) {
+            bail!("Sample must be a non-empty rectangle of tiles");
+        }
+        Ok(map)
+    }
+}
+
+fn tile_key(kind: CellKind) -> &'static str {
+    match kind {
+        CellKind::Room => "floor",
+        CellKind::Corridor => "corridor",
+        CellKind::Door => "door",
+        CellKind::Stairs => "stairs",
+        CellKind::Blocked => "wall",
+    }
+}
+
+fn level_tile_map(level: &LevelData, floor: i32) -> Vec<Vec<String>> {
+    let mut cells: HashMap<(i32, i32), (Option<CellKind>, String)> = HashMap::new();
+    for obj in level.objects.iter().filter(|obj| floor_of(obj) == floor) {
+        let kind = CellKind::from_object(obj);
+        let tile_type = obj
+            .metadata
+            .get("tile_type")
+            .and_then(serde_json::Value::as_str);
+        let Some(key) = tile_type.or_else(|| kind.map(tile_key)) else {
+            continue;
+        };
+        // Turned WFC tiles are learned as their own variant, so patterns keep facing
+        let key = match obj
+            .metadata
+            .get("rotation")
+            .and_then(serde_json::Value::as_u64)
+        {
+            Some(rotation) if tile_type.is_some() && rotation != 0 => {
+                format!("{}_r{}", key, rotation)
+            }
+            _ => key.to_string(),
+        };
+        let (_, x, z) = cell_of(obj);
+        let entry = cells.entry((x, z)).or_insert_with(|| (kind, key.clone()));
+        if kind > entry.0 {
+            *entry = (kind, key);
+        }
+    }
+
+    let (Some(min_x), Some(max_x)) = (
+        cells.keys().map(|c| c.0).min(),
+        cells.keys().map(|c| c.0).max(),
+    ) else {
+        return Vec::new();
+    };
+    let min_z = cells.keys().map(|c| c.1).min().unwrap_or(0);
+    let max_z = cells.keys().map(|c| c.1).max().unwrap_or(0);
+    (min_z..=max_z)
+        .map(|z| {
+            (min_x..=max_x)
+                .map(|x| {
+                    cells
+                        .get(&(x, z))
+                        .map_or_else(|| EMPTY_TILE.to_string(), |(_, key)| key.clone())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlappingParams {
+    pub sample: WFCSample,
+    /// Width and height of the learned patterns; larger patterns copy more of the
+    /// sample's structure but need a larger sample
+    #[serde(default = "default_pattern_size")]
+    pub pattern_size: u32,
+}
+
+const fn default_pattern_size() -> u32 {
+    3
+}
+
+/// NxN patterns learned from a sample, with which may overlap which.
+#[derive(Debug)]
+pub struct OverlappingModel {
+    size: usize,
+    /// Tile keys, indexed by the values in `patterns`
+    tiles: Vec<String>,
+    /// Each pattern's tiles, row by row
+    patterns: Vec<Vec<usize>>,
+    /// How often each pattern appears in the sample
+    weights: Vec<f64>,
+    /// For each pattern and direction, the patterns that may sit one cell that way
+    compatible: Vec<[Vec<usize>; 4]>,
+}
+
+impl OverlappingModel {
+    /// Learns every `size`x`size` pattern in `sample`.
+    pub fn learn(sample: &[Vec<String>], size: usize) -> Result<Self> {
+        let height = sample.len();
+        let width = sample.first().map_or(0, Vec::len);
+        if size < 2 {
+            bail!("Pattern size must be at least 2");
+        }
+        if width < size || height < size {
+            bail!(
+                "A {}x{} sample is too small for {}x{} patterns",
+                width,
+                height,
+                size,
+                size
+            );
+        }
+
+        let mut tiles: Vec<String> = Vec::new();
+        let mut indexed = Vec::with_capacity(height);
+        for row in sample {
+            let row: Vec<usize> = row
+                .iter()
+                .map(|key| {
+                    if let Some(index) = tiles.iter().position(|t| t == key) {
+                        index
+                    } else {
+                        tiles.push(key.clone());
+                        tiles.len() - 1
+                    }
+                })
+                .collect();
+            indexed.push(row);
+        }
+
+        // Patterns in the order they're first seen, so learning is reproducible
+        let mut patterns: Vec<Vec<usize>> = Vec::new();
+        let mut weights: Vec<f64> = Vec::new();
+        let mut seen: HashMap<Vec<usize>, usize> = HashMap::new();
+        for y in 0..=height - size {
+            for x in 0..=width - size {
+                let pattern: Vec<usize> = (0..size)
+                    .flat_map(|dy| indexed[y + dy][x..x + size].iter().copied())
+                    .collect();
+                if let Some(&index) = seen.get(&pattern) {
+                    weights[index] += 1.0;
+                } else {
+                    seen.insert(pattern.clone(), patterns.len());
+                    patterns.push(pattern);
+                    weights.push(1.0);
+                }
+            }
+        }
+
+        let compatible = patterns
+            .iter()
+            .map(|a| {
+                DIRECTIONS.map(|(dx, dy)| {
+                    (0..patterns.len())
+                        .filter(|&b| overlaps(a, &patterns[b], size, dx, dy))
+                        .collect()
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            size,
+            tiles,
+            patterns,
+            weights,
+            compatible,
+        })
+    }
+
+    #[must_use]
+    pub fn pattern_count(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Generates a `width`x`height` grid of tile keys, starting over on a contradiction
+    /// up to `attempts` times.
+    pub fn generate(
+        &self,
+        width: usize,
+        height: usize,
+        rng: &mut StdRng,
+        attempts: u32,
+    ) -> Result<Vec<Vec<String>>> {
+        if width < self.size || height < self.size {
+            bail!(
+                "Output must be at least {}x{} for {}x{} patterns",
+                self.size,
+                self.size,
+                self.size,
+                self.size
+            );
+        }
+        // One wave cell per pattern position; the last row and column of patterns
+        // also cover the output cells past them
+        let wave_width = width - self.size + 1;
+        let wave_height = height - self.size + 1;
+        for _ in 0..attempts.max(1) {
+            let Some(wave) = self.observe(wave_width, wave_height, rng) else {
+                continue;
+            };
+            return Ok((0..height)
+                .map(|y| {
+                    (0..width)
+                        .map(|x| {
+                            let (px, py) = (x.min(wave_width - 1), y.min(wave_height - 1));
+                            let pattern = &self.patterns[wave[py * wave_width + px]];
+                            self.tiles[pattern[(y - py) * self.size + (x - px)]].clone()
+                        })
+                        .collect()
+                })
+                .collect());
+        }
+        bail!("Overlapping WFC failed: contradiction in every attempt")
+    }
+
+    /// Collapses a wave of the given size, returning the pattern at each position, or
+    /// `None` on a contradiction.
+    fn observe(&self, width: usize, height: usize, rng: &mut StdRng) -> Option<Vec<usize>> {
+        let count = self.patterns.len();
+        let mut wave = vec![vec![true; count]; width * height];
+        loop {
+            // Lowest Shannon entropy first, with a little noise to break ties
+            let mut lowest: Option<(f64, usize)> = None;
+            for (index, possible) in wave.iter().enumerate() {
+                let (mut sum, mut sum_log, mut options) = (0.0, 0.0, 0);
+                for (pattern, _) in possible.iter().enumerate().filter(|(_, &p)| p) {
+                    let weight = self.weights[pattern];
+                    sum += weight;
+                    sum_log += weight * weight.ln();
+                    options += 1;
+                }
+                match options {
+                    0 => return None,
+                    1 => continue,
+                    _ => {}
+                }
+                let entropy = rng.gen::<f64>().mul_add(1e-6, sum.ln() - sum_log / sum);
+                if lowest.is_none_or(|(e, _)| entropy < e) {
+                    lowest = Some((entropy, index));
+                }
+            }
+            let Some((_, index)) = lowest else {
+                return Some(
+                    wave.iter()
+                        .map(|possible| possible.iter().position(|&p| p).unwrap_or(0))
+                        .collect(),
+                );
+            };
+
+            let total: f64 = (0..count)
+                .filter(|&p| wave[index][p])
+                .map(|p| self.weights[p])
+                .sum();
+            let mut pick = rng.gen::<f64>() * total;
+            let mut chosen = 0;
+            for pattern in (0..count).filter(|&p| wave[index][p]) {
+                chosen = pattern;
+                pick -= self.weights[pattern];
+                if pick <= 0.0 {
+                    break;
+                }
+            }
+            for (pattern, possible) in wave[index].iter_mut().enumerate() {
+                *possible = pattern == chosen;
+            }
+
+            if !self.propagate(&mut wave, width, height, index) {
+                return None;
+            }
+        }
+    }
+
+    /// Removes patterns that no longer fit beside their neighbours, spreading out from
+    /// `start`. Returns false when a position is left with no pattern.
+    fn propagate(&self, wave: &mut [Vec<bool>], width: usize, height: usize, start: usize) -> bool {
+        let mut queue = VecDeque::from([start]);
+        while let Some(index) = queue.pop_front() {
+            let (x, y) = (index % width, index / width);
+            for (direction, (dx, dy)) in DIRECTIONS.into_iter().enumerate() {
+                let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy))
+                else {
+                    continue;
+                };
+                if nx >= width || ny >= height {
+                    continue;
+                }
+                let mut allowed = vec![false; self.patterns.len()];
+                for pattern in (0..self.patterns.len()).filter(|&p| wave[index][p]) {
+                    for &other in &self.compatible[pattern][direction] {
+                        allowed[other] = true;
+                    }
+                }
+                let neighbour = &mut wave[ny * width + nx];
+                let mut changed = false;
+                for (possible, allowed) in neighbour.iter_mut().zip(allowed) {
+                    if *possible && !allowed {
+                        *possible = false;
+                        changed = true;
+                    }
+                }
+                if changed {
+                    if !neighbour.contains(&true) {
+                        return false;
+                    }
+                    queue.push_back(ny * width + nx);
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Whether pattern `b`, shifted by `(dx, dy)` from pattern `a`, agrees with it
+/// wherever the two overlap.
+fn overlaps(a: &[usize], b: &[usize], size: usize, dx: isize, dy: isize) -> bool {
+    let n = size as isize;
+    for y in dy.max(0)..(n + dy).min(n) {
+        for x in dx.max(0)..(n + dx).min(n) {
+            let (bx, by) = (x - dx, y - dy);
+            if a[(x + n * y) as usize] != b[(bx + n * by) as usize] {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    /// Rooms of floor inside walls, with doors between them and a pool in one corner.
+    const SAMPLE: &str = "\
+##########
+#...#....#
+#...D....#
+#...#....#
+##D######~
+#.....#~~#
+#.....D~##
+##########";
+
+    #[test]
+    fn generates_only_patterns_from_the_sample() {
+        let sample = WFCSample::Grid {
+            theme: "office".to_string(),
+            grid: SAMPLE.to_string(),
+        };
+        let map = sample.tile_map().unwrap();
+        let model = OverlappingModel::learn(&map, 2).unwrap();
+        assert!(model.pattern_count() > 1);
+
+        let mut rng = StdRng::seed_from_u64(4);
+        let output = model.generate(16, 12, &mut rng, 20).unwrap();
+        assert_eq!(output.len(), 12);
+        assert!(output.iter().all(|row| row.len() == 16));
+
+        // Every 2x2 window of the output appears somewhere in the sample
+        let windows = |map: &[Vec<String>]| {
+            let mut windows = Vec::new();
+            for y in 0..map.len() - 1 {
+                for x in 0..map[0].len() - 1 {
+                    windows.push([
+                        map[y][x].clone(),
+                        map[y][x + 1].clone(),
+                        map[y + 1][x].clone(),
+                        map[y + 1][x + 1].clone(),
+                    ]);
+                }
+            }
+            windows
+        };
+        let learned = windows(&map);
+        assert!(windows(&output).iter().all(|w| learned.contains(w)));
+
+        assert!(OverlappingModel::learn(&map, 12).is_err());
+    }
+}
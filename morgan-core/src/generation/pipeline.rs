@@ -0,0 +1,393 @@
+//! Generation pipelines that chain generators and passes over one level.
+//!
+//! A pipeline is a list of stages run in order, such as a BSP layout, WFC detail in
+//! its rooms, props, spawn points and a final validation. Pipelines are written as JSON
+//! or RON, so a hybrid level is built in one go instead of the frontend calling each
+//! generator and rebuilding the level in between.
+
+use crate::generation::bsp::BSPGenerator;
+use crate::generation::decoration::{decorate, theme_props, DecorationParams, PropRule};
+use crate::generation::placement::{place_spawns, SpawnPlacementParams};
+use crate::generation::wfc::{WFCGenerationParams, WFCGenerator};
+use crate::level::connectivity::{cell_of, check_connectivity, Cell};
+use crate::level::spawns::validate_level_spawns;
+use crate::stable::generated_id;
+use crate::{BSPGenerationParams, GameObject, LevelData, ObjectKind, Transform3D};
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tracing::Instrument;
+
+/// Layer WFC room detail goes on.
+pub const DETAIL_LAYER: &str = "Detail";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValidationParams {
+    /// Fail the pipeline when parts of the level can't be reached
+    pub require_connected: bool,
+    /// Fail the pipeline when the spawn points don't validate
+    pub require_valid_spawns: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum PipelineStage {
+    /// Lays out a new level, replacing whatever earlier stages built
+    Bsp(BSPGenerationParams),
+    /// Generates a new level, or after a BSP stage fills each room with WFC detail.
+    /// Room fills ignore `width`, `height`, `constraints` and `chunks`.
+    Wfc(WFCGenerationParams),
+    Decorate(DecorationParams),
+    Spawns(SpawnPlacementParams),
+    /// Checks connectivity and spawn points, noting problems or failing on them
+    Validate(ValidationParams),
+}
+
+impl PipelineStage {
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            PipelineStage::Bsp(_) => "bsp",
+            PipelineStage::Wfc(_) => "wfc",
+            PipelineStage::Decorate(_) => "decorate",
+            PipelineStage::Spawns(_) => "spawns",
+            PipelineStage::Validate(_) => "validate",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenerationPipeline {
+    /// Seed for generator stages that don't set their own
+    #[serde(default)]
+    pub seed: Option<u64>,
+    pub stages: Vec<PipelineStage>,
+}
+
+/// What one stage did.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageReport {
+    pub stage: String,
+    /// Objects in the level once the stage finished
+    pub object_count: usize,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PipelineRun {
+    pub level: LevelData,
+    pub stages: Vec<StageReport>,
+}
+
+impl GenerationPipeline {
+    /// Reads a pipeline written as JSON or RON.
+    pub fn parse(text: &str) -> Result<Self> {
+        if text.trim_start().starts_with('{') {
+            serde_json::from_str(text).map_err(|e| anyhow!("Invalid pipeline JSON: {}", e))
+        } else {
+            ron::from_str(text).map_err(|e| anyhow!("Invalid pipeline RON: {}", e))
+        }
+    }
+
+    /// Themes and names of the props the decoration stages will place without a mesh
+    /// of their own, so meshes can be looked up before running. The theme is `None`
+    /// when it only becomes known from the generated level.
+    #[must_use]
+    pub fn planned_props(&self) -> Vec<(Option<String>, String)> {
+        let mut layout_theme = None;
+        let mut planned = Vec::new();
+        for stage in &self.stages {
+            match stage {
+                PipelineStage::Bsp(params) => layout_theme = Some(params.theme.clone()),
+                PipelineStage::Wfc(params) if layout_theme.is_none() => {
+                    layout_theme = Some(params.tileset.clone());
+                }
+                PipelineStage::Decorate(params) => {
+                    let theme = params.theme.clone().or_else(|| layout_theme.clone());
+                    let props = match (&params.props, &theme) {
+                        (Some(props), _) => props.clone(),
+                        (None, Some(theme)) => theme_props(theme),
+                        (None, None) => Vec::new(),
+                    };
+                    planned.extend(
+                        props
+                            .into_iter()
+                            .filter(|p| p.mesh.is_none())
+                            .map(|p| (theme.clone(), p.name)),
+                    );
+                }
+                _ => {}
+            }
+        }
+        planned
+    }
+
+    /// Runs the stages in order. `mesh_for` picks the meshes of decoration props.
+    pub async fn run(
+        self,
+        mesh_for: impl Fn(&PropRule) -> Option<String> + Sync,
+    ) -> Result<PipelineRun> {
+        if self.stages.is_empty() {
+            bail!("Pipeline has no stages");
+        }
+        let description = serde_json::to_value(&self.stages)?;
+
+        let mut level: Option<LevelData> = None;
+        let mut reports = Vec::new();
+        for (index, stage) in self.stages.into_iter().enumerate() {
+            let name = stage.name();
+            let span = tracing::info_span!("pipeline_stage", index, stage = name);
+            let notes = run_stage(stage, self.seed, &mut level, &mesh_for)
+                .instrument(span)
+                .await
+                .map_err(|e| anyhow!("Stage {} ({}) failed: {}", index + 1, name, e))?;
+            reports.push(StageReport {
+                stage: name.to_string(),
+                object_count: level.as_ref().map_or(0, |l| l.objects.len()),
+                notes,
+            });
+        }
+
+        let mut level = level.ok_or_else(|| anyhow!("Pipeline built no level"))?;
+        if let Some(serde_json::Value::Object(ref mut params)) = level.generation_params {
+            params.insert("pipeline".to_string(), description);
+        }
+        Ok(PipelineRun {
+            level,
+            stages: reports,
+        })
+    }
+}
+
+fn current_level<'a>(level: &'a mut Option<LevelData>, stage: &str) -> Result<&'a mut LevelData> {
+    level.as_mut().ok_or_else(|| {
+        anyhow!(
+            "The {} stage needs a level; start with a bsp or wfc stage",
+            stage
+        )
+    })
+}
+
+async fn run_stage(
+    stage: PipelineStage,
+    seed: Option<u64>,
+    level: &mut Option<LevelData>,
+    mesh_for: &(impl Fn(&PropRule) -> Option<String> + Sync),
+) -> Result<Vec<String>> {
+    let mut notes = Vec::new();
+    match stage {
+        PipelineStage::Bsp(mut params) => {
+            params.seed = params.seed.or(seed);
+            *level = Some(BSPGenerator::new().generate(params).await?);
+        }
+        PipelineStage::Wfc(mut params) => {
+            params.seed = params.seed.or(seed);
+            match level.as_mut() {
+                Some(level) => {
+                    let (filled, failed) = fill_rooms(level, &params)?;
+                    notes.push(format!("Filled {} rooms with detail", filled));
+                    if failed > 0 {
+                        notes.push(format!("WFC found no solution for {} rooms", failed));
+                    }
+                }
+                None => *level = Some(WFCGenerator::new().generate(params).await?),
+            }
+        }
+        PipelineStage::Decorate(params) => {
+            let level = current_level(level, "decorate")?;
+            let added = decorate(level, &params, mesh_for)?;
+            notes.push(format!("Placed {} props", added.len()));
+        }
+        PipelineStage::Spawns(params) => {
+            let level = current_level(level, "spawns")?;
+            let placement = place_spawns(level, &params)?;
+            notes.push(format!("Placed {} spawn points", placement.placed.len()));
+            notes.extend(placement.shortfalls);
+        }
+        PipelineStage::Validate(params) => {
+            let level = current_level(level, "validate")?;
+            let connectivity = check_connectivity(level);
+            if !connectivity.connected {
+                let problem = format!(
+                    "{} isolated regions and {} unreachable rooms",
+                    connectivity.regions.len() - 1,
+                    connectivity.unreachable_rooms.len()
+                );
+                if params.require_connected {
+                    bail!("Level has {}", problem);
+                }
+                notes.push(format!("Level has {}", problem));
+            }
+            let spawns = validate_level_spawns(level);
+            if params.require_valid_spawns && !spawns.valid {
+                bail!("{}", spawns.issues[0].message);
+            }
+            notes.extend(spawns.issues.into_iter().map(|issue| issue.message));
+        }
+    }
+    Ok(notes)
+}
+
+/// Runs WFC over each room's floor and lays the tiles it picks, apart from plain floor,
+/// over the room as flat detail. Detail counts as floor, so it never blocks a path.
+/// Returns how many rooms were filled and how many WFC failed on.
+fn fill_rooms(level: &mut LevelData, params: &WFCGenerationParams) -> Result<(usize, usize)> {
+    // Floor tiles by room, with the height of each
+    let mut rooms: BTreeMap<String, HashMap<Cell, f32>> = BTreeMap::new();
+    for obj in &level.objects {
+        if !obj.tags.iter().any(|t| t == "floor") {
+            continue;
+        }
+        if let Some(room_id) = obj.metadata.get("room_id").and_then(|v| v.as_str()) {
+            rooms
+                .entry(room_id.to_string())
+                .or_default()
+                .insert(cell_of(obj), obj.transform.position[1]);
+        }
+    }
+    if rooms.is_empty() {
+        bail!("WFC detail needs rooms to fill, such as those from a bsp stage");
+    }
+
+    let seed = params.seed.or(level.generation_seed).unwrap_or(0);
+    let (mut filled, mut failed) = (0, 0);
+    let mut detail = Vec::new();
+    for (index, (room_id, cells)) in rooms.into_iter().enumerate() {
+        let min_x = cells.keys().map(|c| c.1).min().unwrap_or(0);
+        let min_z = cells.keys().map(|c| c.2).min().unwrap_or(0);
+        let max_x = cells.keys().map(|c| c.1).max().unwrap_or(0);
+        let max_z = cells.keys().map(|c| c.2).max().unwrap_or(0);
+        let room_params = WFCGenerationParams {
+            width: (max_x - min_x + 1).unsigned_abs(),
+            height: (max_z - min_z + 1).unsigned_abs(),
+            depth: 1,
+            tileset: params.tileset.clone(),
+            seed: Some(seed.wrapping_add(index as u64)),
+            max_iterations: params.max_iterations,
+            backtrack_limit: params.backtrack_limit,
+            overlapping: params.overlapping.clone(),
+            constraints: Vec::new(),
+            chunks: None,
+            entropy: params.entropy,
+        };
+        let Ok(pattern) = WFCGenerator::new().generate_tiles(room_params) else {
+            failed += 1;
+            continue;
+        };
+        filled += 1;
+
+        for tile in pattern.objects {
+            let Some(tile_type) = tile.metadata.get("tile_type").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let (_, x, z) = cell_of(&tile);
+            let (x, z) = (x + min_x, z + min_z);
+            let Some((&(floor, _, _), &elevation)) =
+                cells.iter().find(|(cell, _)| cell.1 == x && cell.2 == z)
+            else {
+                continue;
+            };
+            if tile_type == "floor" {
+                continue;
+            }
+            detail.push(GameObject {
+                id: generated_id(seed, "wfc_detail", &[x.into(), z.into(), floor.into()]),
+                name: format!("detail_{}_{}_{}", tile_type, x, z),
+                transform: Transform3D {
+                    position: [x as f32, elevation, z as f32],
+                    rotation: tile.transform.rotation,
+                    scale: [1.0, 0.02, 1.0],
+                },
+                material: tile.material,
+                mesh: tile.mesh,
+                layer: DETAIL_LAYER.to_string(),
+                tags: vec![
+                    "wfc".to_string(),
+                    "floor_detail".to_string(),
+                    params.tileset.clone(),
+                ],
+                metadata: HashMap::from([
+                    ("detail".to_string(), tile_type.into()),
+                    ("floor".to_string(), floor.into()),
+                    ("room_id".to_string(), room_id.clone().into()),
+                    ("algorithm".to_string(), "WFC".into()),
+                ]),
+                kind: ObjectKind::Mesh,
+                physics: None,
+            });
+        }
+    }
+
+    if !detail.is_empty() && !level.layers.iter().any(|l| l == DETAIL_LAYER) {
+        level.layers.push(DETAIL_LAYER.to_string());
+    }
+    level.objects.extend(detail);
+    Ok((filled, failed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIPELINE: &str = r#"(
+        seed: Some(11),
+        stages: [
+            (
+                stage: "bsp",
+                width: 40,
+                height: 40,
+                depth: 1,
+                min_room_size: 6,
+                max_room_size: 12,
+                corridor_width: 1,
+                theme: "dungeon",
+                seed: None,
+            ),
+            (stage: "wfc", tileset: "dungeon"),
+            (stage: "decorate"),
+            (stage: "spawns", enemy_spawners: 2),
+            (stage: "validate", require_valid_spawns: true),
+        ],
+    )"#;
+
+    #[test]
+    fn runs_stages_over_one_level() {
+        let pipeline = GenerationPipeline::parse(PIPELINE).unwrap();
+        let props = pipeline.planned_props();
+        assert!(props.contains(&(Some("dungeon".to_string()), "torch".to_string())));
+
+        let run = tokio_test::block_on(pipeline.run(|_| None)).unwrap();
+        let stages: Vec<&str> = run.stages.iter().map(|s| s.stage.as_str()).collect();
+        assert_eq!(stages, ["bsp", "wfc", "decorate", "spawns", "validate"]);
+        assert_eq!(run.level.generation_seed, Some(11));
+        assert!(run
+            .stages
+            .windows(2)
+            .all(|w| w[0].object_count <= w[1].object_count));
+
+        // Detail and props were added without cutting anything off
+        for layer in [DETAIL_LAYER, "Props", "Spawns"] {
+            assert!(
+                run.level.objects.iter().any(|o| o.layer == layer),
+                "{}",
+                layer
+            );
+        }
+        let mut layout = run.level.clone();
+        layout
+            .objects
+            .retain(|o| o.layer != DETAIL_LAYER && o.layer != "Props");
+        assert_eq!(
+            check_connectivity(&run.level).regions.len(),
+            check_connectivity(&layout).regions.len()
+        );
+        assert!(run.level.generation_params.unwrap()["pipeline"].is_array());
+
+        // Pipelines can be JSON too, and need a layout stage first
+        let json = r#"{"stages": [{"stage": "decorate"}]}"#;
+        let pipeline = GenerationPipeline::parse(json).unwrap();
+        let error = tokio_test::block_on(pipeline.run(|_| None)).unwrap_err();
+        assert!(error.to_string().contains("start with a bsp or wfc stage"));
+    }
+}
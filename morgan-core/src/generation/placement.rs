@@ -0,0 +1,389 @@
+//! Automatic placement of the player start, enemy spawners and level exits.
+//!
+//! Points go on free room floor tiles. Distances from the player start are walking
+//! distances in cells, so an exit "far away" is far to walk to and not just across a
+//! wall, and anything the player can't reach is never picked.
+
+use crate::level::connectivity::{cell_of, walking_distances, Cell, CellKind};
+use crate::level::rooms::{RoomGraph, RoomType};
+use crate::level::spawns::{new_spawn_object, SpawnPointProperties};
+use crate::stable::generated_id;
+use crate::{GameObject, LevelData, ObjectKind};
+use anyhow::{bail, Result};
+use log::warn;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Layer placed spawn points go on.
+pub const SPAWNS_LAYER: &str = "Spawns";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpawnPlacementParams {
+    pub enemy_spawners: u32,
+    /// Enemy kind the spawners are given
+    pub enemy_kind: String,
+    /// Enemies each spawner produces
+    pub enemies_per_spawner: u32,
+    /// Seconds between a spawner's enemies
+    pub spawn_interval_secs: f32,
+    pub exits: u32,
+    /// Smallest distance in world units between any two placed points
+    pub min_separation: f32,
+    /// Enemy spawners are at least this many steps from the player start
+    pub min_enemy_distance: u32,
+    /// Exits are at least this many steps from the player start
+    pub min_exit_distance: u32,
+    /// Seed for reproducible placement, defaulting to the level's generation seed
+    pub seed: Option<u64>,
+}
+
+impl Default for SpawnPlacementParams {
+    fn default() -> Self {
+        Self {
+            enemy_spawners: 4,
+            enemy_kind: "enemy".to_string(),
+            enemies_per_spawner: 3,
+            spawn_interval_secs: 10.0,
+            exits: 1,
+            min_separation: 4.0,
+            min_enemy_distance: 8,
+            min_exit_distance: 12,
+            seed: None,
+        }
+    }
+}
+
+impl SpawnPlacementParams {
+    pub fn validate(&self) -> Result<()> {
+        if !(self.min_separation.is_finite() && self.min_separation >= 0.0) {
+            bail!("Minimum separation must not be negative");
+        }
+        if self.enemy_spawners > 0 && self.enemy_kind.trim().is_empty() {
+            bail!("Enemy spawners need an enemy kind");
+        }
+        if self.enemy_spawners > 0 && self.enemies_per_spawner == 0 {
+            bail!("Enemy spawners must spawn at least one enemy");
+        }
+        if self.enemy_spawners > 0 && self.spawn_interval_secs <= 0.0 {
+            bail!("Enemy spawn interval must be positive");
+        }
+        Ok(())
+    }
+}
+
+/// Spawn points added to a level, and the ones that didn't fit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnPlacement {
+    pub placed: Vec<GameObject>,
+    /// One message per kind of point that was placed fewer times than asked for
+    pub shortfalls: Vec<String>,
+}
+
+/// A free room floor cell and what's known about it.
+struct Spot {
+    position: [f32; 3],
+    room_id: Option<String>,
+}
+
+/// Room floor cells with nothing standing on them, in a fixed order.
+fn free_spots(level: &LevelData) -> BTreeMap<Cell, Spot> {
+    let mut occupied = HashSet::new();
+    let mut blocked = HashSet::new();
+    let mut spots = BTreeMap::new();
+    for obj in &level.objects {
+        let cell = cell_of(obj);
+        match CellKind::from_object(obj) {
+            None => {
+                occupied.insert(cell);
+            }
+            Some(CellKind::Room) => {
+                let room_id = obj.metadata.get("room_id").and_then(|v| v.as_str());
+                spots.insert(
+                    cell,
+                    Spot {
+                        position: obj.transform.position,
+                        room_id: room_id.map(ToString::to_string),
+                    },
+                );
+            }
+            Some(_) => {
+                blocked.insert(cell);
+            }
+        }
+    }
+    spots.retain(|cell, _| !occupied.contains(cell) && !blocked.contains(cell));
+    spots
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let [dx, dy, dz] = [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    dx.mul_add(dx, dy.mul_add(dy, dz * dz)).sqrt()
+}
+
+/// Up to `count` of `candidates`, in order, each at least `min_separation` from the
+/// positions in `taken` and from each other. Chosen positions are added to `taken`.
+fn pick_separated(
+    candidates: &[Cell],
+    count: u32,
+    spots: &BTreeMap<Cell, Spot>,
+    taken: &mut Vec<[f32; 3]>,
+    min_separation: f32,
+) -> Vec<Cell> {
+    let mut chosen = Vec::new();
+    for cell in candidates {
+        if chosen.len() == count as usize {
+            break;
+        }
+        let position = spots[cell].position;
+        if taken
+            .iter()
+            .all(|&other| distance(position, other) >= min_separation)
+        {
+            taken.push(position);
+            chosen.push(*cell);
+        }
+    }
+    chosen
+}
+
+/// Places a player start (unless the level has one), enemy spawners and exits.
+///
+/// A new player start goes in the middle of the room graph's spawn room when there is
+/// one, and on the first free floor tile otherwise. Exits are placed as far from the
+/// start as possible and spawners at random, both keeping to the distance rules;
+/// points that don't fit are reported as shortfalls rather than failing the whole
+/// placement.
+pub fn place_spawns(
+    level: &mut LevelData,
+    params: &SpawnPlacementParams,
+) -> Result<SpawnPlacement> {
+    params.validate()?;
+    let seed = params.seed.or(level.generation_seed).unwrap_or(0);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut spots = free_spots(level);
+
+    let mut placed = Vec::new();
+    let mut taken: Vec<[f32; 3]> = level
+        .objects
+        .iter()
+        .filter(|o| matches!(o.kind, ObjectKind::SpawnPoint(_)))
+        .map(|o| o.transform.position)
+        .collect();
+    let point = |cell: Cell, spot: &Spot, name: String, spawn: SpawnPointProperties| {
+        let (floor, x, z) = cell;
+        let mut object = new_spawn_object(
+            name,
+            spot.position,
+            [0.0, 0.0, 0.0, 1.0],
+            spawn,
+            SPAWNS_LAYER.to_string(),
+        );
+        object.id = generated_id(
+            seed,
+            object.tags[1].as_str(),
+            &[x.into(), z.into(), floor.into()],
+        );
+        object.metadata.insert("floor".to_string(), floor.into());
+        if let Some(room_id) = &spot.room_id {
+            object
+                .metadata
+                .insert("room_id".to_string(), room_id.clone().into());
+        }
+        object
+    };
+
+    let existing_start = level.objects.iter().find(|o| {
+        matches!(
+            o.kind,
+            ObjectKind::SpawnPoint(SpawnPointProperties::PlayerStart)
+        )
+    });
+    let start = if let Some(start) = existing_start {
+        cell_of(start)
+    } else {
+        let spawn_room = RoomGraph::from_level(level).and_then(|graph| {
+            graph
+                .rooms
+                .into_iter()
+                .find(|room| room.room_type == RoomType::Spawn)
+        });
+        let in_spawn_room: Vec<(&Cell, &Spot)> = spots
+            .iter()
+            .filter(|(_, spot)| {
+                spawn_room
+                    .as_ref()
+                    .is_some_and(|room| spot.room_id.as_deref() == Some(room.id.as_str()))
+            })
+            .collect();
+        let spot = if in_spawn_room.is_empty() {
+            spots.iter().next()
+        } else {
+            in_spawn_room.get(in_spawn_room.len() / 2).copied()
+        };
+        let Some((&cell, spot)) = spot else {
+            bail!("Level has no free room floor for a player start");
+        };
+        placed.push(point(
+            cell,
+            spot,
+            "PlayerStart".to_string(),
+            SpawnPointProperties::PlayerStart,
+        ));
+        taken.push(spot.position);
+        spots.remove(&cell);
+        cell
+    };
+
+    let distances: HashMap<Cell, usize> = walking_distances(level, start);
+    let reachable = |spots: &BTreeMap<Cell, Spot>, min_distance: u32| -> Vec<Cell> {
+        spots
+            .keys()
+            .copied()
+            .filter(|cell| {
+                distances
+                    .get(cell)
+                    .is_some_and(|&d| d >= min_distance as usize)
+            })
+            .collect()
+    };
+    let mut shortfalls = Vec::new();
+
+    // Exits as far from the start as the level goes
+    let mut candidates = reachable(&spots, params.min_exit_distance);
+    candidates.sort_by_key(|cell| std::cmp::Reverse(distances[cell]));
+    let exits = pick_separated(
+        &candidates,
+        params.exits,
+        &spots,
+        &mut taken,
+        params.min_separation,
+    );
+    if exits.len() < params.exits as usize {
+        shortfalls.push(format!("Placed {} of {} exits", exits.len(), params.exits));
+    }
+    for (i, cell) in exits.iter().enumerate() {
+        let spot = spots.remove(cell).unwrap();
+        let exit = SpawnPointProperties::LevelExit { target_level: None };
+        placed.push(point(*cell, &spot, format!("Exit_{}", i + 1), exit));
+    }
+
+    let mut candidates = reachable(&spots, params.min_enemy_distance);
+    candidates.shuffle(&mut rng);
+    let spawners = pick_separated(
+        &candidates,
+        params.enemy_spawners,
+        &spots,
+        &mut taken,
+        params.min_separation,
+    );
+    if spawners.len() < params.enemy_spawners as usize {
+        shortfalls.push(format!(
+            "Placed {} of {} enemy spawners",
+            spawners.len(),
+            params.enemy_spawners
+        ));
+    }
+    for (i, cell) in spawners.iter().enumerate() {
+        let spot = spots.remove(cell).unwrap();
+        let spawner = SpawnPointProperties::EnemySpawner {
+            enemy_kind: params.enemy_kind.clone(),
+            count: params.enemies_per_spawner,
+            interval_secs: params.spawn_interval_secs,
+        };
+        placed.push(point(
+            *cell,
+            &spot,
+            format!("EnemySpawner_{}", i + 1),
+            spawner,
+        ));
+    }
+
+    for message in &shortfalls {
+        warn!("Spawn placement in level {}: {}", level.id, message);
+    }
+    if !placed.is_empty() && !level.layers.iter().any(|l| l == SPAWNS_LAYER) {
+        level.layers.push(SPAWNS_LAYER.to_string());
+    }
+    level.objects.extend(placed.iter().cloned());
+    Ok(SpawnPlacement { placed, shortfalls })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::spawns::validate_level_spawns;
+    use crate::testing::{level, tile};
+
+    /// A strip of floor 30 cells long, with a cut-off cell past a wall at 31.
+    fn strip() -> LevelData {
+        let mut objects: Vec<GameObject> = (0..30).map(|x| tile(x as f32, 0.0, "floor")).collect();
+        objects.push(tile(30.0, 0.0, "wall"));
+        objects.push(tile(31.0, 0.0, "floor"));
+        LevelData {
+            layers: vec!["Floors".to_string()],
+            generation_seed: Some(5),
+            ..level(objects)
+        }
+    }
+
+    fn of_tag<'a>(placed: &'a [GameObject], tag: &'a str) -> impl Iterator<Item = f32> + 'a {
+        placed
+            .iter()
+            .filter(move |o| o.tags.iter().any(|t| t == tag))
+            .map(|o| o.transform.position[0])
+    }
+
+    #[test]
+    fn keeps_distance_and_separation_rules() {
+        let mut level = strip();
+        let params = SpawnPlacementParams {
+            enemy_spawners: 3,
+            min_separation: 3.0,
+            min_enemy_distance: 6,
+            min_exit_distance: 20,
+            ..SpawnPlacementParams::default()
+        };
+        let placement = place_spawns(&mut level, &params).unwrap();
+        assert!(
+            placement.shortfalls.is_empty(),
+            "{:?}",
+            placement.shortfalls
+        );
+
+        let start = of_tag(&placement.placed, "player_start").next().unwrap();
+        let exit = of_tag(&placement.placed, "level_exit").next().unwrap();
+        // The unreachable cell past the wall is never used
+        assert!((exit - 29.0).abs() < f32::EPSILON);
+        for enemy in of_tag(&placement.placed, "enemy_spawner") {
+            assert!((enemy - start).abs() >= 6.0);
+        }
+        let mut xs: Vec<f32> = placement
+            .placed
+            .iter()
+            .map(|o| o.transform.position[0])
+            .collect();
+        xs.sort_by(f32::total_cmp);
+        assert!(xs.windows(2).all(|w| w[1] - w[0] >= 3.0), "{:?}", xs);
+
+        let report = validate_level_spawns(&level);
+        assert!(report.valid);
+        assert_eq!(report.level_exits, 1);
+
+        // A second pass keeps the start and reports what no longer fits
+        let again = place_spawns(
+            &mut level,
+            &SpawnPlacementParams {
+                min_separation: 30.0,
+                ..params
+            },
+        )
+        .unwrap();
+        assert!(again.placed.is_empty());
+        assert_eq!(again.shortfalls.len(), 2);
+        assert!(validate_level_spawns(&level).valid);
+    }
+}
@@ -0,0 +1,187 @@
+//! Lightweight summaries of generated levels, for comparing seeds side by side.
+//!
+//! A preview keeps a few numbers and an ASCII map per floor, drawn with a theme's tile
+//! icons, so many candidate layouts can be shown without sending their objects around.
+
+use crate::generation::themes::{tile_to_char, Theme};
+use crate::level::connectivity::{cell_of, check_connectivity, Cell, CellKind};
+use crate::level::rooms::RoomGraph;
+use crate::LevelData;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelPreview {
+    pub seed: Option<u64>,
+    pub room_count: usize,
+    pub walkable_cells: usize,
+    pub connected: bool,
+    pub object_count: usize,
+    /// ASCII map of each floor from the lowest up, one row per grid row
+    pub floors: Vec<String>,
+}
+
+/// Theme tile keys to draw a cell with, the first one the theme has winning.
+fn tile_keys(kind: Option<CellKind>) -> &'static [&'static str] {
+    match kind {
+        Some(CellKind::Room) => &["floor"],
+        Some(CellKind::Corridor) => &["corridor", "floor"],
+        Some(CellKind::Door) => &["door"],
+        Some(CellKind::Stairs) => &["stairs", "stairs_up", "floor"],
+        Some(CellKind::Blocked) => &["wall"],
+        None => &["empty"],
+    }
+}
+
+fn cell_char(theme: &Theme, kind: Option<CellKind>) -> char {
+    let keys = tile_keys(kind);
+    let key = keys
+        .iter()
+        .find(|key| theme.tiles.contains_key(**key))
+        .unwrap_or(&keys[0]);
+    tile_to_char(theme, key)
+}
+
+/// The kind of every cell with a tile in it, the most significant one winning where
+/// tiles overlap.
+fn cell_kinds(level: &LevelData) -> HashMap<Cell, CellKind> {
+    let mut kinds: HashMap<Cell, CellKind> = HashMap::new();
+    for obj in &level.objects {
+        let Some(kind) = CellKind::from_object(obj) else {
+            continue;
+        };
+        let entry = kinds.entry(cell_of(obj)).or_insert(kind);
+        if kind > *entry {
+            *entry = kind;
+        }
+    }
+    kinds
+}
+
+/// Smallest and largest `(x, z)` among `cells`.
+fn extent<'a>(cells: impl Iterator<Item = &'a Cell> + Clone) -> ((i32, i32), (i32, i32)) {
+    let min_x = cells.clone().map(|&(_, x, _)| x).min().unwrap_or(0);
+    let max_x = cells.clone().map(|&(_, x, _)| x).max().unwrap_or(-1);
+    let min_z = cells.clone().map(|&(_, _, z)| z).min().unwrap_or(0);
+    let max_z = cells.map(|&(_, _, z)| z).max().unwrap_or(-1);
+    ((min_x, min_z), (max_x, max_z))
+}
+
+/// ASCII map of `floor` from `min` to `max`, one row per grid row.
+fn draw_floor(
+    kinds: &HashMap<Cell, CellKind>,
+    theme: &Theme,
+    floor: i32,
+    ((min_x, min_z), (max_x, max_z)): ((i32, i32), (i32, i32)),
+) -> String {
+    (min_z..=max_z)
+        .map(|z| {
+            (min_x..=max_x)
+                .map(|x| cell_char(theme, kinds.get(&(floor, x, z)).copied()))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Draws one floor of `level` with `theme`'s tile icons, cropped to that floor's tiles,
+/// or `None` if the floor has none. Without a floor the lowest one is drawn.
+#[must_use]
+pub fn render_floor(level: &LevelData, theme: &Theme, floor: Option<i32>) -> Option<String> {
+    let kinds = cell_kinds(level);
+    let floor = floor.or_else(|| kinds.keys().map(|&(floor, _, _)| floor).min())?;
+    let cells = kinds.keys().filter(|cell| cell.0 == floor);
+    cells.clone().next()?;
+    Some(draw_floor(&kinds, theme, floor, extent(cells)))
+}
+
+/// Summarises `level`, drawing its floors with `theme`'s tile icons.
+#[must_use]
+pub fn preview_level(level: &LevelData, theme: &Theme) -> LevelPreview {
+    let kinds = cell_kinds(level);
+    let floors: BTreeSet<i32> = kinds.keys().map(|&(floor, _, _)| floor).collect();
+    let bounds = extent(kinds.keys());
+    let maps = floors
+        .into_iter()
+        .map(|floor| draw_floor(&kinds, theme, floor, bounds))
+        .collect();
+
+    let connectivity = check_connectivity(level);
+    LevelPreview {
+        seed: level.generation_seed,
+        room_count: RoomGraph::from_level(level).map_or(0, |graph| graph.rooms.len()),
+        walkable_cells: connectivity.walkable_cells,
+        connected: connectivity.connected,
+        object_count: level.objects.len(),
+        floors: maps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::bsp::BSPGenerator;
+    use crate::testing::{floor_tile, level};
+    use crate::BSPGenerationParams;
+
+    #[test]
+    fn draws_each_floor_with_theme_icons() {
+        let params: BSPGenerationParams = serde_json::from_value(serde_json::json!({
+            "width": 30,
+            "height": 30,
+            "depth": 2,
+            "min_room_size": 4,
+            "max_room_size": 8,
+            "corridor_width": 1,
+            "theme": "dungeon",
+            "seed": 11,
+        }))
+        .unwrap();
+        let level = tokio_test::block_on(BSPGenerator::new().generate(params)).unwrap();
+        let theme = Theme::dungeon();
+        let preview = preview_level(&level, &theme);
+
+        assert_eq!(preview.seed, Some(11));
+        assert_eq!(preview.floors.len(), 2);
+        assert!(preview.room_count >= 2);
+        assert!(preview.walkable_cells > 0);
+        let floor = tile_to_char(&theme, "floor");
+        let corridor = tile_to_char(&theme, "corridor");
+        for map in &preview.floors {
+            assert!(map.contains(floor) && map.contains(corridor));
+            assert!(!map.contains('?'));
+            let width = map.lines().next().unwrap().chars().count();
+            assert!(map.lines().all(|row| row.chars().count() == width));
+        }
+    }
+
+    #[test]
+    fn renders_a_single_floor_cropped_to_its_tiles() {
+        let level = level(vec![
+            floor_tile(0.0, 0.0, 0, "wall"),
+            floor_tile(1.0, 0.0, 0, "door"),
+            floor_tile(2.0, 0.0, 0, "wall"),
+            floor_tile(1.0, 1.0, 0, "floor"),
+            floor_tile(5.0, 5.0, 1, "floor"),
+        ]);
+        let theme = Theme::dungeon();
+        let icon = |key| tile_to_char(&theme, key);
+
+        let ground = render_floor(&level, &theme, None).unwrap();
+        let expected = format!(
+            "{}{}{}\n{}{}{}",
+            icon("wall"),
+            icon("door"),
+            icon("wall"),
+            icon("empty"),
+            icon("floor"),
+            icon("empty")
+        );
+        assert_eq!(ground, expected);
+        assert_eq!(
+            render_floor(&level, &theme, Some(1)),
+            Some(icon("floor").to_string())
+        );
+        assert_eq!(render_floor(&level, &theme, Some(2)), None);
+    }
+}
@@ -0,0 +1,4131 @@
+use crate::generation::decoration::{PropPlacement, PropRule};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Folder holding theme files as `<id>.json` or `<id>.ron`, both beside the project's
+/// `Assets` directory and in the editor's app data.
+pub const THEMES_DIRECTORY: &str = "themes";
+
+/// Represents different tile types in the level
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum TileType {
+    Empty,
+    Floor,
+    Wall,
+    Door,
+    Window,
+    Corridor,
+    Room,
+    Stairs,
+    Special,
+    /// Open ground outdoors
+    Grass,
+    Water,
+    /// A drop or rock face that can't be crossed
+    Cliff,
+}
+
+/// Visual representation for 2D grid display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileVisual {
+    pub icon: char,
+    pub color: String,
+    pub background_color: Option<String>,
+}
+
+/// 3D mesh information for tile-to-3D conversion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileMesh {
+    pub mesh_type: String,
+    pub material: String,
+    pub scale: (f32, f32, f32),
+    pub rotation: (f32, f32, f32),
+    pub offset: (f32, f32, f32),
+}
+
+/// Complete tile definition combining type, visual, and 3D data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileDefinition {
+    pub tile_type: TileType,
+    pub name: String,
+    pub description: String,
+    pub visual: TileVisual,
+    pub mesh: TileMesh,
+    pub collision: bool,
+    pub walkable: bool,
+    pub tags: Vec<String>,
+}
+
+/// Theme lighting configuration  
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeLighting {
+    pub ambient_color: (f32, f32, f32),
+    pub ambient_intensity: f32,
+    pub directional_color: (f32, f32, f32),
+    pub directional_intensity: f32,
+    pub directional_direction: (f32, f32, f32),
+    pub shadow_enabled: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaterialInfo {
+    pub diffuse: Option<String>,
+    pub normal: Option<String>,
+    pub metallic: Option<String>,
+    pub roughness: Option<String>,
+    pub emission: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Theme {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub author: String,
+    pub version: String,
+    pub tiles: HashMap<String, TileDefinition>,
+    pub default_floor_height: f32,
+    pub wall_height: f32,
+    pub lighting: ThemeLighting,
+    pub materials: HashMap<String, MaterialInfo>,
+    pub mesh_variants: HashMap<String, Vec<String>>,
+    /// How often each of a key's mesh variants is picked, by index; variants without a
+    /// weight count as 1
+    #[serde(default)]
+    pub mesh_variant_weights: HashMap<String, Vec<f32>>,
+    /// Decorative objects scattered over the theme's rooms
+    #[serde(default)]
+    pub props: Vec<PropRule>,
+    /// Slots filled from the asset database, kept so they can follow the assets around
+    #[serde(default)]
+    pub asset_bindings: Vec<AssetBinding>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaterialChannel {
+    Diffuse,
+    Normal,
+    Metallic,
+    Roughness,
+    Emission,
+}
+
+/// A place in a theme that holds an asset path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ThemeSlot {
+    /// One texture of a material, e.g. the floor's diffuse map
+    Material {
+        material: String,
+        channel: MaterialChannel,
+    },
+    /// One mesh variant, e.g. the second `door` mesh; without an index the asset is
+    /// added as a new variant
+    MeshVariant { key: String, index: Option<usize> },
+}
+
+/// An asset database entry assigned to a theme slot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetBinding {
+    pub slot: ThemeSlot,
+    pub asset_id: i64,
+    /// The asset's path when it was bound or last moved, as written into the slot
+    pub path: String,
+}
+
+#[allow(dead_code)]
+impl Theme {
+    #[must_use]
+    pub fn office() -> Self {
+        let mut materials = HashMap::new();
+        materials.insert(
+            "floor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/office/carpet_diffuse.png".to_string()),
+                normal: Some("textures/office/carpet_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/office/carpet_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "wall".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/office/drywall_diffuse.png".to_string()),
+                normal: Some("textures/office/drywall_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/office/drywall_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "door".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/office/door_diffuse.png".to_string()),
+                normal: Some("textures/office/door_normal.png".to_string()),
+                metallic: Some("textures/office/door_metallic.png".to_string()),
+                roughness: Some("textures/office/door_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "corridor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/office/tile_diffuse.png".to_string()),
+                normal: Some("textures/office/tile_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/office/tile_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        let mut mesh_variants = HashMap::new();
+        mesh_variants.insert(
+            "wall".to_string(),
+            vec![
+                "meshes/office/wall_basic.mesh".to_string(),
+                "meshes/office/wall_window.mesh".to_string(),
+                "meshes/office/wall_corner.mesh".to_string(),
+            ],
+        );
+
+        let mut tiles = HashMap::new();
+
+        // Floor tile
+        tiles.insert(
+            "floor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Floor,
+                name: "Carpet Floor".to_string(),
+                description: "Office carpeted flooring".to_string(),
+                visual: TileVisual {
+                    icon: '.',
+                    color: "#8B7355".to_string(),
+                    background_color: Some("#F5F5DC".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["ground".to_string(), "office".to_string()],
+            },
+        );
+
+        // Wall tile
+        tiles.insert(
+            "wall".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Drywall".to_string(),
+                description: "Standard office drywall partition".to_string(),
+                visual: TileVisual {
+                    icon: '#',
+                    color: "#E6E6E6".to_string(),
+                    background_color: Some("#F0F0F0".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "cube".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 3.0, 0.2),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["barrier".to_string(), "office".to_string()],
+            },
+        );
+
+        // Door tile
+        tiles.insert(
+            "door".to_string(),
+            TileDefinition {
+                tile_type: TileType::Door,
+                name: "Office Door".to_string(),
+                description: "Standard office door with frame".to_string(),
+                visual: TileVisual {
+                    icon: 'D',
+                    color: "#8B4513".to_string(),
+                    background_color: Some("#DEB887".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "door_frame".to_string(),
+                    material: "door".to_string(),
+                    scale: (1.0, 3.0, 0.2),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["interactive".to_string(), "office".to_string()],
+            },
+        );
+
+        // Window tile
+        tiles.insert(
+            "window".to_string(),
+            TileDefinition {
+                tile_type: TileType::Window,
+                name: "Office Window".to_string(),
+                description: "Office window with blinds".to_string(),
+                visual: TileVisual {
+                    icon: 'O',
+                    color: "#87CEEB".to_string(),
+                    background_color: Some("#E0F6FF".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "window_frame".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 2.0, 0.2),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.0, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec![
+                    "barrier".to_string(),
+                    "transparent".to_string(),
+                    "office".to_string(),
+                ],
+            },
+        );
+
+        // Corridor tile
+        tiles.insert(
+            "corridor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Corridor,
+                name: "Tile Floor".to_string(),
+                description: "Office corridor with tile flooring".to_string(),
+                visual: TileVisual {
+                    icon: ' ',
+                    color: "#C0C0C0".to_string(),
+                    background_color: Some("#F8F8FF".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "corridor".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "corridor".to_string(),
+                    "office".to_string(),
+                ],
+            },
+        );
+
+        // Empty/void tile
+        tiles.insert(
+            "empty".to_string(),
+            TileDefinition {
+                tile_type: TileType::Empty,
+                name: "Empty Space".to_string(),
+                description: "Unoccupied space".to_string(),
+                visual: TileVisual {
+                    icon: ' ',
+                    color: "#000000".to_string(),
+                    background_color: None,
+                },
+                mesh: TileMesh {
+                    mesh_type: "none".to_string(),
+                    material: "none".to_string(),
+                    scale: (0.0, 0.0, 0.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: false,
+                tags: vec!["void".to_string()],
+            },
+        );
+
+        // Wall variations
+        tiles.insert(
+            "wall_horizontal".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Horizontal Wall".to_string(),
+                description: "Horizontal wall segment".to_string(),
+                visual: TileVisual {
+                    icon: '═',
+                    color: "#E6E6E6".to_string(),
+                    background_color: Some("#F0F0F0".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "cube".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 3.0, 0.2),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["barrier".to_string(), "office".to_string()],
+            },
+        );
+        tiles.insert(
+            "wall_vertical".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Vertical Wall".to_string(),
+                description: "Vertical wall segment".to_string(),
+                visual: TileVisual {
+                    icon: '║',
+                    color: "#E6E6E6".to_string(),
+                    background_color: Some("#F0F0F0".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "cube".to_string(),
+                    material: "wall".to_string(),
+                    scale: (0.2, 3.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["barrier".to_string(), "office".to_string()],
+            },
+        );
+        tiles.insert(
+            "wall_corner_tl".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Top-Left Corner".to_string(),
+                description: "Top-left wall corner".to_string(),
+                visual: TileVisual {
+                    icon: '╔',
+                    color: "#E6E6E6".to_string(),
+                    background_color: Some("#F0F0F0".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "corner".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 3.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["barrier".to_string(), "office".to_string()],
+            },
+        );
+        tiles.insert(
+            "wall_corner_tr".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Top-Right Corner".to_string(),
+                description: "Top-right wall corner".to_string(),
+                visual: TileVisual {
+                    icon: '╗',
+                    color: "#E6E6E6".to_string(),
+                    background_color: Some("#F0F0F0".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "corner".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 3.0, 1.0),
+                    rotation: (0.0, 90.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["barrier".to_string(), "office".to_string()],
+            },
+        );
+        tiles.insert(
+            "wall_corner_bl".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Bottom-Left Corner".to_string(),
+                description: "Bottom-left wall corner".to_string(),
+                visual: TileVisual {
+                    icon: '╚',
+                    color: "#E6E6E6".to_string(),
+                    background_color: Some("#F0F0F0".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "corner".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 3.0, 1.0),
+                    rotation: (0.0, 270.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["barrier".to_string(), "office".to_string()],
+            },
+        );
+        tiles.insert(
+            "wall_corner_br".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Bottom-Right Corner".to_string(),
+                description: "Bottom-right wall corner".to_string(),
+                visual: TileVisual {
+                    icon: '╝',
+                    color: "#E6E6E6".to_string(),
+                    background_color: Some("#F0F0F0".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "corner".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 3.0, 1.0),
+                    rotation: (0.0, 180.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["barrier".to_string(), "office".to_string()],
+            },
+        );
+        tiles.insert(
+            "wall_intersection".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Wall Intersection".to_string(),
+                description: "Four-way wall intersection".to_string(),
+                visual: TileVisual {
+                    icon: '╬',
+                    color: "#E6E6E6".to_string(),
+                    background_color: Some("#F0F0F0".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "intersection".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 3.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["barrier".to_string(), "office".to_string()],
+            },
+        );
+
+        // Window variations
+        tiles.insert(
+            "window_closed".to_string(),
+            TileDefinition {
+                tile_type: TileType::Window,
+                name: "Closed Window".to_string(),
+                description: "Closed office window".to_string(),
+                visual: TileVisual {
+                    icon: '[',
+                    color: "#87CEEB".to_string(),
+                    background_color: Some("#E0F6FF".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "window_frame".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 2.0, 0.2),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.0, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec![
+                    "barrier".to_string(),
+                    "transparent".to_string(),
+                    "office".to_string(),
+                ],
+            },
+        );
+        tiles.insert(
+            "window_open".to_string(),
+            TileDefinition {
+                tile_type: TileType::Window,
+                name: "Open Window".to_string(),
+                description: "Open office window".to_string(),
+                visual: TileVisual {
+                    icon: ']',
+                    color: "#87CEEB".to_string(),
+                    background_color: Some("#E0F6FF".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "window_frame".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 2.0, 0.2),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["transparent".to_string(), "office".to_string()],
+            },
+        );
+
+        // Wall block variations
+        tiles.insert(
+            "wall_thick".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Thick Wall Block".to_string(),
+                description: "Reinforced thick wall".to_string(),
+                visual: TileVisual {
+                    icon: '▓',
+                    color: "#C0C0C0".to_string(),
+                    background_color: Some("#808080".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "cube".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 3.0, 0.5),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["barrier".to_string(), "office".to_string()],
+            },
+        );
+        tiles.insert(
+            "wall_quarter".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Quarter Wall".to_string(),
+                description: "Low partition wall".to_string(),
+                visual: TileVisual {
+                    icon: '▒',
+                    color: "#D3D3D3".to_string(),
+                    background_color: Some("#A9A9A9".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "cube".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 1.5, 0.2),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.75, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["barrier".to_string(), "office".to_string()],
+            },
+        );
+
+        // Room types
+        tiles.insert(
+            "room_large".to_string(),
+            TileDefinition {
+                tile_type: TileType::Room,
+                name: "Large Room".to_string(),
+                description: "Open office area".to_string(),
+                visual: TileVisual {
+                    icon: 'O',
+                    color: "#8B7355".to_string(),
+                    background_color: Some("#F5F5DC".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "room".to_string(),
+                    "office".to_string(),
+                ],
+            },
+        );
+        tiles.insert(
+            "room_small".to_string(),
+            TileDefinition {
+                tile_type: TileType::Room,
+                name: "Small Room".to_string(),
+                description: "Private office".to_string(),
+                visual: TileVisual {
+                    icon: 'P',
+                    color: "#8B7355".to_string(),
+                    background_color: Some("#F0F8FF".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "room".to_string(),
+                    "office".to_string(),
+                ],
+            },
+        );
+
+        // Special rooms
+        tiles.insert(
+            "special_1".to_string(),
+            TileDefinition {
+                tile_type: TileType::Special,
+                name: "Elevator".to_string(),
+                description: "Elevator shaft".to_string(),
+                visual: TileVisual {
+                    icon: 'E',
+                    color: "#FFD700".to_string(),
+                    background_color: Some("#4B0082".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "elevator".to_string(),
+                    material: "special".to_string(),
+                    scale: (0.8, 3.0, 0.8),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["interactive".to_string(), "office".to_string()],
+            },
+        );
+        tiles.insert(
+            "special_2".to_string(),
+            TileDefinition {
+                tile_type: TileType::Special,
+                name: "Lobby".to_string(),
+                description: "Reception lobby area".to_string(),
+                visual: TileVisual {
+                    icon: 'L',
+                    color: "#DDA0DD".to_string(),
+                    background_color: Some("#FFB6C1".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "special".to_string(),
+                    "office".to_string(),
+                ],
+            },
+        );
+
+        // Stairs variations
+        tiles.insert(
+            "stairs_up".to_string(),
+            TileDefinition {
+                tile_type: TileType::Stairs,
+                name: "Stairs Up".to_string(),
+                description: "Staircase going up".to_string(),
+                visual: TileVisual {
+                    icon: '^',
+                    color: "#696969".to_string(),
+                    background_color: Some("#D3D3D3".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "stairs".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 1.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.5, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["vertical".to_string(), "office".to_string()],
+            },
+        );
+        tiles.insert(
+            "stairs_down".to_string(),
+            TileDefinition {
+                tile_type: TileType::Stairs,
+                name: "Stairs Down".to_string(),
+                description: "Staircase going down".to_string(),
+                visual: TileVisual {
+                    icon: 'v',
+                    color: "#696969".to_string(),
+                    background_color: Some("#A9A9A9".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "stairs".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 1.0, 1.0),
+                    rotation: (0.0, 180.0, 0.0),
+                    offset: (0.0, 0.5, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["vertical".to_string(), "office".to_string()],
+            },
+        );
+
+        // Special features
+        tiles.insert(
+            "water".to_string(),
+            TileDefinition {
+                tile_type: TileType::Special,
+                name: "Water".to_string(),
+                description: "Water feature".to_string(),
+                visual: TileVisual {
+                    icon: '~',
+                    color: "#0080FF".to_string(),
+                    background_color: Some("#00BFFF".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "water".to_string(),
+                    scale: (1.0, 0.02, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: false,
+                tags: vec!["liquid".to_string(), "office".to_string()],
+            },
+        );
+        tiles.insert(
+            "secret".to_string(),
+            TileDefinition {
+                tile_type: TileType::Special,
+                name: "Secret Passage".to_string(),
+                description: "Hidden passage".to_string(),
+                visual: TileVisual {
+                    icon: '?',
+                    color: "#800080".to_string(),
+                    background_color: Some("#4B0082".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "secret".to_string(),
+                    "office".to_string(),
+                ],
+            },
+        );
+        tiles.insert(
+            "tunnel".to_string(),
+            TileDefinition {
+                tile_type: TileType::Special,
+                name: "Underground Tunnel".to_string(),
+                description: "Underground passage".to_string(),
+                visual: TileVisual {
+                    icon: 'T',
+                    color: "#654321".to_string(),
+                    background_color: Some("#8B4513".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "tunnel".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.8, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.4, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["underground".to_string(), "office".to_string()],
+            },
+        );
+        tiles.insert(
+            "bridge".to_string(),
+            TileDefinition {
+                tile_type: TileType::Special,
+                name: "Sky Bridge".to_string(),
+                description: "Elevated walkway".to_string(),
+                visual: TileVisual {
+                    icon: 'B',
+                    color: "#C0C0C0".to_string(),
+                    background_color: Some("#87CEEB".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "bridge".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.1, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 2.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["elevated".to_string(), "office".to_string()],
+            },
+        );
+
+        Self {
+            id: "office".to_string(),
+            name: "Office".to_string(),
+            description: "Modern office environment with carpeted floors and drywall".to_string(),
+            author: "Morgan-Bevy".to_string(),
+            version: "1.0.0".to_string(),
+            tiles,
+            default_floor_height: 0.0,
+            wall_height: 3.0,
+            lighting: ThemeLighting {
+                ambient_color: (0.9, 0.9, 1.0),
+                ambient_intensity: 0.4,
+                directional_color: (1.0, 1.0, 0.9),
+                directional_intensity: 0.8,
+                directional_direction: (-0.5, -1.0, -0.3),
+                shadow_enabled: true,
+            },
+            materials,
+            mesh_variants,
+            mesh_variant_weights: HashMap::new(),
+            props: vec![
+                PropRule::new("desk", PropPlacement::Floor, 0.06),
+                PropRule::new("console", PropPlacement::Wall, 0.05),
+            ],
+            asset_bindings: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn dungeon() -> Self {
+        let mut materials = HashMap::new();
+        materials.insert(
+            "floor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/dungeon/stone_floor_diffuse.png".to_string()),
+                normal: Some("textures/dungeon/stone_floor_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/dungeon/stone_floor_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "wall".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/dungeon/stone_wall_diffuse.png".to_string()),
+                normal: Some("textures/dungeon/stone_wall_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/dungeon/stone_wall_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "door".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/dungeon/wooden_door_diffuse.png".to_string()),
+                normal: Some("textures/dungeon/wooden_door_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/dungeon/wooden_door_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "corridor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/dungeon/cobblestone_diffuse.png".to_string()),
+                normal: Some("textures/dungeon/cobblestone_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/dungeon/cobblestone_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        let mut mesh_variants = HashMap::new();
+        mesh_variants.insert(
+            "wall".to_string(),
+            vec![
+                "meshes/dungeon/stone_wall_basic.mesh".to_string(),
+                "meshes/dungeon/stone_wall_damaged.mesh".to_string(),
+                "meshes/dungeon/stone_wall_corner.mesh".to_string(),
+            ],
+        );
+
+        let mut tiles = HashMap::new();
+
+        // Floor tile
+        tiles.insert(
+            "floor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Floor,
+                name: "Stone Floor".to_string(),
+                description: "Weathered stone dungeon flooring".to_string(),
+                visual: TileVisual {
+                    icon: '·',
+                    color: "#696969".to_string(),
+                    background_color: Some("#2F2F2F".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.1, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["ground".to_string(), "dungeon".to_string()],
+            },
+        );
+
+        // Wall tile
+        tiles.insert(
+            "wall".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Stone Wall".to_string(),
+                description: "Thick stone dungeon wall".to_string(),
+                visual: TileVisual {
+                    icon: '█',
+                    color: "#555555".to_string(),
+                    background_color: Some("#1C1C1C".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "cube".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 4.0, 0.3),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 2.0, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["barrier".to_string(), "dungeon".to_string()],
+            },
+        );
+
+        // Door tile
+        tiles.insert(
+            "door".to_string(),
+            TileDefinition {
+                tile_type: TileType::Door,
+                name: "Iron Gate".to_string(),
+                description: "Heavy iron-reinforced wooden door".to_string(),
+                visual: TileVisual {
+                    icon: '☩',
+                    color: "#8B4513".to_string(),
+                    background_color: Some("#654321".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "gate_door".to_string(),
+                    material: "door".to_string(),
+                    scale: (1.0, 4.0, 0.3),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 2.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["interactive".to_string(), "dungeon".to_string()],
+            },
+        );
+
+        // Corridor tile
+        tiles.insert(
+            "corridor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Corridor,
+                name: "Cobblestone".to_string(),
+                description: "Uneven cobblestone corridor".to_string(),
+                visual: TileVisual {
+                    icon: '▓',
+                    color: "#808080".to_string(),
+                    background_color: Some("#404040".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "corridor".to_string(),
+                    scale: (1.0, 0.15, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "corridor".to_string(),
+                    "dungeon".to_string(),
+                ],
+            },
+        );
+
+        // Stairs tile
+        tiles.insert(
+            "stairs".to_string(),
+            TileDefinition {
+                tile_type: TileType::Stairs,
+                name: "Stone Stairs".to_string(),
+                description: "Worn stone staircase".to_string(),
+                visual: TileVisual {
+                    icon: '≡',
+                    color: "#696969".to_string(),
+                    background_color: Some("#3C3C3C".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "stairs".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 1.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.5, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["vertical".to_string(), "dungeon".to_string()],
+            },
+        );
+
+        // Empty/void tile
+        tiles.insert(
+            "empty".to_string(),
+            TileDefinition {
+                tile_type: TileType::Empty,
+                name: "Void".to_string(),
+                description: "Dark emptiness".to_string(),
+                visual: TileVisual {
+                    icon: ' ',
+                    color: "#000000".to_string(),
+                    background_color: None,
+                },
+                mesh: TileMesh {
+                    mesh_type: "none".to_string(),
+                    material: "none".to_string(),
+                    scale: (0.0, 0.0, 0.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: false,
+                tags: vec!["void".to_string()],
+            },
+        );
+
+        Self {
+            id: "dungeon".to_string(),
+            name: "Dungeon".to_string(),
+            description: "Medieval dungeon with stone walls and cobblestone floors".to_string(),
+            author: "Morgan-Bevy".to_string(),
+            version: "1.0.0".to_string(),
+            tiles,
+            default_floor_height: 0.0,
+            wall_height: 4.0,
+            lighting: ThemeLighting {
+                ambient_color: (0.3, 0.2, 0.4),
+                ambient_intensity: 0.2,
+                directional_color: (1.0, 0.8, 0.4),
+                directional_intensity: 0.6,
+                directional_direction: (-0.3, -1.0, -0.5),
+                shadow_enabled: true,
+            },
+            materials,
+            mesh_variants,
+            mesh_variant_weights: HashMap::new(),
+            props: vec![
+                PropRule::new("torch", PropPlacement::Wall, 0.08),
+                PropRule::new("crate", PropPlacement::Floor, 0.05),
+            ],
+            asset_bindings: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn scifi() -> Self {
+        let mut materials = HashMap::new();
+        materials.insert(
+            "floor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/scifi/metal_floor_diffuse.png".to_string()),
+                normal: Some("textures/scifi/metal_floor_normal.png".to_string()),
+                metallic: Some("textures/scifi/metal_floor_metallic.png".to_string()),
+                roughness: Some("textures/scifi/metal_floor_roughness.png".to_string()),
+                emission: Some("textures/scifi/metal_floor_emission.png".to_string()),
+            },
+        );
+
+        materials.insert(
+            "wall".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/scifi/panel_wall_diffuse.png".to_string()),
+                normal: Some("textures/scifi/panel_wall_normal.png".to_string()),
+                metallic: Some("textures/scifi/panel_wall_metallic.png".to_string()),
+                roughness: Some("textures/scifi/panel_wall_roughness.png".to_string()),
+                emission: Some("textures/scifi/panel_wall_emission.png".to_string()),
+            },
+        );
+
+        materials.insert(
+            "door".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/scifi/airlock_door_diffuse.png".to_string()),
+                normal: Some("textures/scifi/airlock_door_normal.png".to_string()),
+                metallic: Some("textures/scifi/airlock_door_metallic.png".to_string()),
+                roughness: Some("textures/scifi/airlock_door_roughness.png".to_string()),
+                emission: Some("textures/scifi/airlock_door_emission.png".to_string()),
+            },
+        );
+
+        materials.insert(
+            "corridor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/scifi/grate_floor_diffuse.png".to_string()),
+                normal: Some("textures/scifi/grate_floor_normal.png".to_string()),
+                metallic: Some("textures/scifi/grate_floor_metallic.png".to_string()),
+                roughness: Some("textures/scifi/grate_floor_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        let mut mesh_variants = HashMap::new();
+        mesh_variants.insert(
+            "wall".to_string(),
+            vec![
+                "meshes/scifi/panel_wall_basic.mesh".to_string(),
+                "meshes/scifi/panel_wall_console.mesh".to_string(),
+                "meshes/scifi/panel_wall_vent.mesh".to_string(),
+            ],
+        );
+
+        let mut tiles = HashMap::new();
+
+        // Floor tile
+        tiles.insert(
+            "floor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Floor,
+                name: "Metal Deck".to_string(),
+                description: "Reinforced metal deck plating".to_string(),
+                visual: TileVisual {
+                    icon: '░',
+                    color: "#C0C0C0".to_string(),
+                    background_color: Some("#1E1E2E".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["ground".to_string(), "scifi".to_string()],
+            },
+        );
+
+        // Wall tile
+        tiles.insert(
+            "wall".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Panel Wall".to_string(),
+                description: "High-tech metal panel wall with circuits".to_string(),
+                visual: TileVisual {
+                    icon: '▊',
+                    color: "#4682B4".to_string(),
+                    background_color: Some("#191970".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "cube".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 3.5, 0.2),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.75, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec![
+                    "barrier".to_string(),
+                    "scifi".to_string(),
+                    "electronic".to_string(),
+                ],
+            },
+        );
+
+        // Door tile
+        tiles.insert(
+            "door".to_string(),
+            TileDefinition {
+                tile_type: TileType::Door,
+                name: "Airlock".to_string(),
+                description: "Pressurized airlock door".to_string(),
+                visual: TileVisual {
+                    icon: '◊',
+                    color: "#FFD700".to_string(),
+                    background_color: Some("#4B0082".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "airlock_door".to_string(),
+                    material: "door".to_string(),
+                    scale: (1.0, 3.5, 0.2),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.75, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "interactive".to_string(),
+                    "scifi".to_string(),
+                    "electronic".to_string(),
+                ],
+            },
+        );
+
+        // Corridor tile
+        tiles.insert(
+            "corridor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Corridor,
+                name: "Grating".to_string(),
+                description: "Metal grating over maintenance areas".to_string(),
+                visual: TileVisual {
+                    icon: '▒',
+                    color: "#B0B0B0".to_string(),
+                    background_color: Some("#2F2F4F".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "grating".to_string(),
+                    material: "corridor".to_string(),
+                    scale: (1.0, 0.1, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "corridor".to_string(),
+                    "scifi".to_string(),
+                ],
+            },
+        );
+
+        // Console tile
+        tiles.insert(
+            "console".to_string(),
+            TileDefinition {
+                tile_type: TileType::Special,
+                name: "Control Console".to_string(),
+                description: "Interactive control terminal".to_string(),
+                visual: TileVisual {
+                    icon: '▲',
+                    color: "#00FF00".to_string(),
+                    background_color: Some("#000080".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "console".to_string(),
+                    material: "wall".to_string(),
+                    scale: (0.8, 1.5, 0.6),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.75, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec![
+                    "interactive".to_string(),
+                    "scifi".to_string(),
+                    "electronic".to_string(),
+                ],
+            },
+        );
+
+        // Empty/void tile
+        tiles.insert(
+            "empty".to_string(),
+            TileDefinition {
+                tile_type: TileType::Empty,
+                name: "Space Void".to_string(),
+                description: "Empty space - dangerous vacuum".to_string(),
+                visual: TileVisual {
+                    icon: ' ',
+                    color: "#000000".to_string(),
+                    background_color: None,
+                },
+                mesh: TileMesh {
+                    mesh_type: "none".to_string(),
+                    material: "none".to_string(),
+                    scale: (0.0, 0.0, 0.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: false,
+                tags: vec!["void".to_string(), "dangerous".to_string()],
+            },
+        );
+
+        Self {
+            id: "scifi".to_string(),
+            name: "SciFi".to_string(),
+            description: "Futuristic space station with metal panels and glowing accents"
+                .to_string(),
+            author: "Morgan-Bevy".to_string(),
+            version: "1.0.0".to_string(),
+            tiles,
+            default_floor_height: 0.0,
+            wall_height: 3.5,
+            lighting: ThemeLighting {
+                ambient_color: (0.1, 0.2, 0.4),
+                ambient_intensity: 0.3,
+                directional_color: (0.8, 1.0, 1.0),
+                directional_intensity: 0.7,
+                directional_direction: (0.0, -1.0, -0.2),
+                shadow_enabled: true,
+            },
+            materials,
+            mesh_variants,
+            mesh_variant_weights: HashMap::new(),
+            props: vec![
+                PropRule::new("console", PropPlacement::Wall, 0.08),
+                PropRule::new("crate", PropPlacement::Floor, 0.04),
+            ],
+            asset_bindings: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn castle() -> Self {
+        let mut materials = HashMap::new();
+        materials.insert(
+            "floor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/castle/stone_tile_diffuse.png".to_string()),
+                normal: Some("textures/castle/stone_tile_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/castle/stone_tile_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "wall".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/castle/brick_wall_diffuse.png".to_string()),
+                normal: Some("textures/castle/brick_wall_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/castle/brick_wall_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "door".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/castle/heavy_door_diffuse.png".to_string()),
+                normal: Some("textures/castle/heavy_door_normal.png".to_string()),
+                metallic: Some("textures/castle/heavy_door_metallic.png".to_string()),
+                roughness: Some("textures/castle/heavy_door_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "corridor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/castle/flagstone_diffuse.png".to_string()),
+                normal: Some("textures/castle/flagstone_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/castle/flagstone_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        let mut mesh_variants = HashMap::new();
+        mesh_variants.insert(
+            "wall".to_string(),
+            vec![
+                "meshes/castle/brick_wall_basic.mesh".to_string(),
+                "meshes/castle/brick_wall_torch.mesh".to_string(),
+                "meshes/castle/brick_wall_battlement.mesh".to_string(),
+            ],
+        );
+
+        let mut tiles = HashMap::new();
+
+        // Floor tile
+        tiles.insert(
+            "floor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Floor,
+                name: "Stone Tiles".to_string(),
+                description: "Polished castle stone flooring".to_string(),
+                visual: TileVisual {
+                    icon: '⬜',
+                    color: "#D3D3D3".to_string(),
+                    background_color: Some("#708090".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.1, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["ground".to_string(), "castle".to_string()],
+            },
+        );
+
+        // Wall tile
+        tiles.insert(
+            "wall".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Castle Wall".to_string(),
+                description: "Massive stone castle wall".to_string(),
+                visual: TileVisual {
+                    icon: '▌',
+                    color: "#778899".to_string(),
+                    background_color: Some("#2F4F4F".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "cube".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 5.0, 0.5),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 2.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["barrier".to_string(), "castle".to_string()],
+            },
+        );
+
+        // Door tile
+        tiles.insert(
+            "door".to_string(),
+            TileDefinition {
+                tile_type: TileType::Door,
+                name: "Great Door".to_string(),
+                description: "Heavy wooden door with iron reinforcement".to_string(),
+                visual: TileVisual {
+                    icon: '⚿',
+                    color: "#8B4513".to_string(),
+                    background_color: Some("#A0522D".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "great_door".to_string(),
+                    material: "door".to_string(),
+                    scale: (1.0, 5.0, 0.3),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 2.5, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["interactive".to_string(), "castle".to_string()],
+            },
+        );
+
+        // Window tile
+        tiles.insert(
+            "window".to_string(),
+            TileDefinition {
+                tile_type: TileType::Window,
+                name: "Arrow Slit".to_string(),
+                description: "Narrow defensive window".to_string(),
+                visual: TileVisual {
+                    icon: '⬦',
+                    color: "#87CEEB".to_string(),
+                    background_color: Some("#2F4F4F".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "arrow_slit".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 3.0, 0.5),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec![
+                    "barrier".to_string(),
+                    "defensive".to_string(),
+                    "castle".to_string(),
+                ],
+            },
+        );
+
+        // Corridor tile
+        tiles.insert(
+            "corridor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Corridor,
+                name: "Flagstone".to_string(),
+                description: "Large stone slabs forming corridors".to_string(),
+                visual: TileVisual {
+                    icon: '▥',
+                    color: "#A9A9A9".to_string(),
+                    background_color: Some("#696969".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "corridor".to_string(),
+                    scale: (1.0, 0.15, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "corridor".to_string(),
+                    "castle".to_string(),
+                ],
+            },
+        );
+
+        // Stairs tile
+        tiles.insert(
+            "stairs".to_string(),
+            TileDefinition {
+                tile_type: TileType::Stairs,
+                name: "Stone Steps".to_string(),
+                description: "Grand stone staircase".to_string(),
+                visual: TileVisual {
+                    icon: '⬌',
+                    color: "#D3D3D3".to_string(),
+                    background_color: Some("#778899".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "stairs".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 1.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.5, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["vertical".to_string(), "castle".to_string()],
+            },
+        );
+
+        // Empty/void tile
+        tiles.insert(
+            "empty".to_string(),
+            TileDefinition {
+                tile_type: TileType::Empty,
+                name: "Courtyard".to_string(),
+                description: "Open castle courtyard".to_string(),
+                visual: TileVisual {
+                    icon: ' ',
+                    color: "#228B22".to_string(),
+                    background_color: Some("#006400".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "grass".to_string(),
+                    material: "grass".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec!["outdoor".to_string(), "castle".to_string()],
+            },
+        );
+
+        Self {
+            id: "castle".to_string(),
+            name: "Castle".to_string(),
+            description: "Medieval castle with brick walls and stone floors".to_string(),
+            author: "Morgan-Bevy".to_string(),
+            version: "1.0.0".to_string(),
+            tiles,
+            default_floor_height: 0.0,
+            wall_height: 5.0,
+            lighting: ThemeLighting {
+                ambient_color: (0.8, 0.7, 0.6),
+                ambient_intensity: 0.3,
+                directional_color: (1.0, 0.9, 0.7),
+                directional_intensity: 0.9,
+                directional_direction: (-0.4, -1.0, -0.6),
+                shadow_enabled: true,
+            },
+            materials,
+            mesh_variants,
+            mesh_variant_weights: HashMap::new(),
+            props: vec![
+                PropRule::new("torch", PropPlacement::Wall, 0.08),
+                PropRule::new("crate", PropPlacement::Floor, 0.05),
+            ],
+            asset_bindings: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn cave() -> Self {
+        let mut materials = HashMap::new();
+        materials.insert(
+            "floor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/cave/rock_floor_diffuse.png".to_string()),
+                normal: Some("textures/cave/rock_floor_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/cave/rock_floor_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "wall".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/cave/rock_wall_diffuse.png".to_string()),
+                normal: Some("textures/cave/rock_wall_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/cave/rock_wall_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "corridor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/cave/gravel_diffuse.png".to_string()),
+                normal: Some("textures/cave/gravel_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/cave/gravel_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "water".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/cave/cave_pool_diffuse.png".to_string()),
+                normal: Some("textures/cave/cave_pool_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/cave/cave_pool_roughness.png".to_string()),
+                emission: Some("textures/cave/cave_pool_emission.png".to_string()),
+            },
+        );
+
+        materials.insert(
+            "cliff".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/cave/chasm_edge_diffuse.png".to_string()),
+                normal: Some("textures/cave/chasm_edge_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/cave/chasm_edge_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        let mut mesh_variants = HashMap::new();
+        mesh_variants.insert(
+            "wall".to_string(),
+            vec![
+                "meshes/cave/rock_wall_smooth.mesh".to_string(),
+                "meshes/cave/rock_wall_jagged.mesh".to_string(),
+                "meshes/cave/rock_wall_crystals.mesh".to_string(),
+            ],
+        );
+        mesh_variants.insert(
+            "floor".to_string(),
+            vec![
+                "meshes/cave/rock_floor_flat.mesh".to_string(),
+                "meshes/cave/rock_floor_stalagmite.mesh".to_string(),
+            ],
+        );
+
+        let mut mesh_variant_weights = HashMap::new();
+        mesh_variant_weights.insert("wall".to_string(), vec![4.0, 3.0, 1.0]);
+        mesh_variant_weights.insert("floor".to_string(), vec![6.0, 1.0]);
+
+        let mut tiles = HashMap::new();
+
+        // Floor tile
+        tiles.insert(
+            "floor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Floor,
+                name: "Cave Floor".to_string(),
+                description: "Uneven rock worn smooth by water".to_string(),
+                visual: TileVisual {
+                    icon: '·',
+                    color: "#8B8378".to_string(),
+                    background_color: Some("#2B2622".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "rock_floor".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.1, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "natural".to_string(),
+                    "cave".to_string(),
+                ],
+            },
+        );
+
+        // Wall tile
+        tiles.insert(
+            "wall".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Rock Wall".to_string(),
+                description: "Rough natural rock".to_string(),
+                visual: TileVisual {
+                    icon: '▓',
+                    color: "#5C5449".to_string(),
+                    background_color: Some("#1A1714".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "rock_wall".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 4.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 2.0, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec![
+                    "barrier".to_string(),
+                    "natural".to_string(),
+                    "cave".to_string(),
+                ],
+            },
+        );
+
+        // Door tile
+        tiles.insert(
+            "door".to_string(),
+            TileDefinition {
+                tile_type: TileType::Door,
+                name: "Narrow Gap".to_string(),
+                description: "A squeeze between two rock faces".to_string(),
+                visual: TileVisual {
+                    icon: '⁞',
+                    color: "#A0927D".to_string(),
+                    background_color: Some("#2B2622".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "rock_gap".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 4.0, 0.4),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 2.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "passage".to_string(),
+                    "natural".to_string(),
+                    "cave".to_string(),
+                ],
+            },
+        );
+
+        // Corridor tile
+        tiles.insert(
+            "corridor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Corridor,
+                name: "Tunnel".to_string(),
+                description: "Low gravel-strewn tunnel".to_string(),
+                visual: TileVisual {
+                    icon: '∙',
+                    color: "#7A7266".to_string(),
+                    background_color: Some("#231F1B".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "corridor".to_string(),
+                    scale: (1.0, 0.1, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "corridor".to_string(),
+                    "cave".to_string(),
+                ],
+            },
+        );
+
+        // Stairs tile
+        tiles.insert(
+            "stairs".to_string(),
+            TileDefinition {
+                tile_type: TileType::Stairs,
+                name: "Rock Ledges".to_string(),
+                description: "Natural ledges climbing to another level".to_string(),
+                visual: TileVisual {
+                    icon: '≣',
+                    color: "#A0927D".to_string(),
+                    background_color: Some("#3A332C".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "rock_ledges".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 1.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.5, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "vertical".to_string(),
+                    "natural".to_string(),
+                    "cave".to_string(),
+                ],
+            },
+        );
+
+        // Water tile
+        tiles.insert(
+            "water".to_string(),
+            TileDefinition {
+                tile_type: TileType::Water,
+                name: "Still Pool".to_string(),
+                description: "Shallow underground pool".to_string(),
+                visual: TileVisual {
+                    icon: '≈',
+                    color: "#4A7A8C".to_string(),
+                    background_color: Some("#12262E".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "water_plane".to_string(),
+                    material: "water".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, -0.2, 0.0),
+                },
+                collision: false,
+                walkable: false,
+                tags: vec![
+                    "liquid".to_string(),
+                    "natural".to_string(),
+                    "cave".to_string(),
+                ],
+            },
+        );
+
+        // Cliff tile
+        tiles.insert(
+            "cliff".to_string(),
+            TileDefinition {
+                tile_type: TileType::Cliff,
+                name: "Chasm".to_string(),
+                description: "Sheer drop into the dark".to_string(),
+                visual: TileVisual {
+                    icon: '▼',
+                    color: "#3A332C".to_string(),
+                    background_color: Some("#000000".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "chasm_edge".to_string(),
+                    material: "cliff".to_string(),
+                    scale: (1.0, 2.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, -1.0, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec![
+                    "hazard".to_string(),
+                    "natural".to_string(),
+                    "cave".to_string(),
+                ],
+            },
+        );
+
+        // Empty/void tile
+        tiles.insert(
+            "empty".to_string(),
+            TileDefinition {
+                tile_type: TileType::Empty,
+                name: "Solid Rock".to_string(),
+                description: "Unexcavated bedrock".to_string(),
+                visual: TileVisual {
+                    icon: ' ',
+                    color: "#000000".to_string(),
+                    background_color: Some("#0D0B09".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "none".to_string(),
+                    material: "none".to_string(),
+                    scale: (0.0, 0.0, 0.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["void".to_string(), "cave".to_string()],
+            },
+        );
+
+        Self {
+            id: "cave".to_string(),
+            name: "Cave".to_string(),
+            description: "Natural caverns with rough rock walls, still pools and chasms"
+                .to_string(),
+            author: "Morgan-Bevy".to_string(),
+            version: "1.0.0".to_string(),
+            tiles,
+            default_floor_height: 0.0,
+            wall_height: 4.0,
+            lighting: ThemeLighting {
+                ambient_color: (0.3, 0.35, 0.4),
+                ambient_intensity: 0.15,
+                directional_color: (0.6, 0.7, 0.8),
+                directional_intensity: 0.3,
+                directional_direction: (-0.2, -1.0, -0.1),
+                shadow_enabled: true,
+            },
+            materials,
+            mesh_variants,
+            mesh_variant_weights,
+            props: vec![
+                PropRule::new("mushrooms", PropPlacement::Wall, 0.06),
+                PropRule::new("stalagmite", PropPlacement::Center, 0.05),
+                PropRule::new("rocks", PropPlacement::Floor, 0.04),
+            ],
+            asset_bindings: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn forest() -> Self {
+        let mut materials = HashMap::new();
+        materials.insert(
+            "floor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/forest/leaf_litter_diffuse.png".to_string()),
+                normal: Some("textures/forest/leaf_litter_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/forest/leaf_litter_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "wall".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/forest/bark_diffuse.png".to_string()),
+                normal: Some("textures/forest/bark_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/forest/bark_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "corridor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/forest/dirt_path_diffuse.png".to_string()),
+                normal: Some("textures/forest/dirt_path_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/forest/dirt_path_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "grass".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/forest/grass_diffuse.png".to_string()),
+                normal: Some("textures/forest/grass_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/forest/grass_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "water".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/forest/stream_diffuse.png".to_string()),
+                normal: Some("textures/forest/stream_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/forest/stream_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "cliff".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/forest/rock_face_diffuse.png".to_string()),
+                normal: Some("textures/forest/rock_face_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/forest/rock_face_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        let mut mesh_variants = HashMap::new();
+        mesh_variants.insert(
+            "wall".to_string(),
+            vec![
+                "meshes/forest/oak_tree.mesh".to_string(),
+                "meshes/forest/pine_tree.mesh".to_string(),
+                "meshes/forest/birch_tree.mesh".to_string(),
+                "meshes/forest/dead_tree.mesh".to_string(),
+            ],
+        );
+        mesh_variants.insert(
+            "grass".to_string(),
+            vec![
+                "meshes/forest/grass_short.mesh".to_string(),
+                "meshes/forest/grass_tall.mesh".to_string(),
+                "meshes/forest/grass_flowers.mesh".to_string(),
+            ],
+        );
+        mesh_variants.insert(
+            "floor".to_string(),
+            vec![
+                "meshes/forest/leaf_litter_flat.mesh".to_string(),
+                "meshes/forest/leaf_litter_fern.mesh".to_string(),
+                "meshes/forest/leaf_litter_mushrooms.mesh".to_string(),
+            ],
+        );
+
+        let mut mesh_variant_weights = HashMap::new();
+        mesh_variant_weights.insert("wall".to_string(), vec![4.0, 3.0, 2.0, 0.5]);
+        mesh_variant_weights.insert("grass".to_string(), vec![5.0, 2.0, 1.0]);
+        mesh_variant_weights.insert("floor".to_string(), vec![6.0, 2.0, 1.0]);
+
+        let mut tiles = HashMap::new();
+
+        // Floor tile
+        tiles.insert(
+            "floor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Floor,
+                name: "Forest Floor".to_string(),
+                description: "Fallen leaves over soft earth".to_string(),
+                visual: TileVisual {
+                    icon: '·',
+                    color: "#8B6F47".to_string(),
+                    background_color: Some("#2E3B1F".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.1, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "outdoor".to_string(),
+                    "forest".to_string(),
+                ],
+            },
+        );
+
+        // Wall tile
+        tiles.insert(
+            "wall".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Tree Line".to_string(),
+                description: "Dense trees that block the way".to_string(),
+                visual: TileVisual {
+                    icon: '♣',
+                    color: "#2E8B57".to_string(),
+                    background_color: Some("#13260F".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "tree".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 8.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 4.0, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec![
+                    "barrier".to_string(),
+                    "outdoor".to_string(),
+                    "forest".to_string(),
+                ],
+            },
+        );
+
+        // Door tile
+        tiles.insert(
+            "door".to_string(),
+            TileDefinition {
+                tile_type: TileType::Door,
+                name: "Trail Gap".to_string(),
+                description: "An opening in the tree line".to_string(),
+                visual: TileVisual {
+                    icon: '╬',
+                    color: "#A0522D".to_string(),
+                    background_color: Some("#2E3B1F".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "corridor".to_string(),
+                    scale: (1.0, 0.1, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "passage".to_string(),
+                    "outdoor".to_string(),
+                    "forest".to_string(),
+                ],
+            },
+        );
+
+        // Corridor tile
+        tiles.insert(
+            "corridor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Corridor,
+                name: "Dirt Trail".to_string(),
+                description: "A beaten path through the undergrowth".to_string(),
+                visual: TileVisual {
+                    icon: '∷',
+                    color: "#A0785A".to_string(),
+                    background_color: Some("#3B2A1A".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "corridor".to_string(),
+                    scale: (1.0, 0.1, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "corridor".to_string(),
+                    "outdoor".to_string(),
+                    "forest".to_string(),
+                ],
+            },
+        );
+
+        // Stairs tile
+        tiles.insert(
+            "stairs".to_string(),
+            TileDefinition {
+                tile_type: TileType::Stairs,
+                name: "Root Steps".to_string(),
+                description: "Steps cut into a slope and held by roots".to_string(),
+                visual: TileVisual {
+                    icon: '≣',
+                    color: "#8B6F47".to_string(),
+                    background_color: Some("#3B2A1A".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "stairs".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 1.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.5, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "vertical".to_string(),
+                    "outdoor".to_string(),
+                    "forest".to_string(),
+                ],
+            },
+        );
+
+        // Grass tile
+        tiles.insert(
+            "grass".to_string(),
+            TileDefinition {
+                tile_type: TileType::Grass,
+                name: "Meadow".to_string(),
+                description: "Open grass between the trees".to_string(),
+                visual: TileVisual {
+                    icon: '"',
+                    color: "#7CFC00".to_string(),
+                    background_color: Some("#1F3B12".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "grass".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "outdoor".to_string(),
+                    "forest".to_string(),
+                ],
+            },
+        );
+
+        // Water tile
+        tiles.insert(
+            "water".to_string(),
+            TileDefinition {
+                tile_type: TileType::Water,
+                name: "Stream".to_string(),
+                description: "Shallow running water".to_string(),
+                visual: TileVisual {
+                    icon: '≈',
+                    color: "#4682B4".to_string(),
+                    background_color: Some("#102A43".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "water_plane".to_string(),
+                    material: "water".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, -0.2, 0.0),
+                },
+                collision: false,
+                walkable: false,
+                tags: vec![
+                    "liquid".to_string(),
+                    "outdoor".to_string(),
+                    "forest".to_string(),
+                ],
+            },
+        );
+
+        // Cliff tile
+        tiles.insert(
+            "cliff".to_string(),
+            TileDefinition {
+                tile_type: TileType::Cliff,
+                name: "Rock Face".to_string(),
+                description: "Steep rock too sheer to climb".to_string(),
+                visual: TileVisual {
+                    icon: '▲',
+                    color: "#808080".to_string(),
+                    background_color: Some("#2F2F2F".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "cliff".to_string(),
+                    material: "cliff".to_string(),
+                    scale: (1.0, 6.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 3.0, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec![
+                    "barrier".to_string(),
+                    "outdoor".to_string(),
+                    "forest".to_string(),
+                ],
+            },
+        );
+
+        // Empty/void tile
+        tiles.insert(
+            "empty".to_string(),
+            TileDefinition {
+                tile_type: TileType::Empty,
+                name: "Undergrowth".to_string(),
+                description: "Thick brush outside the playable area".to_string(),
+                visual: TileVisual {
+                    icon: ' ',
+                    color: "#228B22".to_string(),
+                    background_color: Some("#0F1F0A".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "none".to_string(),
+                    material: "none".to_string(),
+                    scale: (0.0, 0.0, 0.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["void".to_string(), "forest".to_string()],
+            },
+        );
+
+        Self {
+            id: "forest".to_string(),
+            name: "Forest".to_string(),
+            description:
+                "Woodland clearings bounded by tree lines, with streams, trails and rocky cliffs"
+                    .to_string(),
+            author: "Morgan-Bevy".to_string(),
+            version: "1.0.0".to_string(),
+            tiles,
+            default_floor_height: 0.0,
+            wall_height: 8.0,
+            lighting: ThemeLighting {
+                ambient_color: (0.6, 0.75, 0.6),
+                ambient_intensity: 0.4,
+                directional_color: (1.0, 0.95, 0.8),
+                directional_intensity: 1.1,
+                directional_direction: (-0.5, -1.0, -0.3),
+                shadow_enabled: true,
+            },
+            materials,
+            mesh_variants,
+            mesh_variant_weights,
+            props: vec![
+                PropRule::new("bush", PropPlacement::Wall, 0.1),
+                PropRule::new("campfire", PropPlacement::Center, 0.02),
+                PropRule::new("fallen_log", PropPlacement::Floor, 0.04),
+                PropRule::new("mushrooms", PropPlacement::Floor, 0.03),
+            ],
+            asset_bindings: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn cyberpunk() -> Self {
+        let mut materials = HashMap::new();
+        materials.insert(
+            "floor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/cyberpunk/wet_asphalt_diffuse.png".to_string()),
+                normal: Some("textures/cyberpunk/wet_asphalt_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/cyberpunk/wet_asphalt_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "wall".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/cyberpunk/concrete_panel_diffuse.png".to_string()),
+                normal: Some("textures/cyberpunk/concrete_panel_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/cyberpunk/concrete_panel_roughness.png".to_string()),
+                emission: Some("textures/cyberpunk/concrete_panel_emission.png".to_string()),
+            },
+        );
+
+        materials.insert(
+            "door".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/cyberpunk/shutter_diffuse.png".to_string()),
+                normal: Some("textures/cyberpunk/shutter_normal.png".to_string()),
+                metallic: Some("textures/cyberpunk/shutter_metallic.png".to_string()),
+                roughness: Some("textures/cyberpunk/shutter_roughness.png".to_string()),
+                emission: Some("textures/cyberpunk/shutter_emission.png".to_string()),
+            },
+        );
+
+        materials.insert(
+            "corridor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/cyberpunk/alley_grime_diffuse.png".to_string()),
+                normal: Some("textures/cyberpunk/alley_grime_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/cyberpunk/alley_grime_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "window".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/cyberpunk/neon_glass_diffuse.png".to_string()),
+                normal: Some("textures/cyberpunk/neon_glass_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/cyberpunk/neon_glass_roughness.png".to_string()),
+                emission: Some("textures/cyberpunk/neon_glass_emission.png".to_string()),
+            },
+        );
+
+        let mut mesh_variants = HashMap::new();
+        mesh_variants.insert(
+            "wall".to_string(),
+            vec![
+                "meshes/cyberpunk/concrete_panel_plain.mesh".to_string(),
+                "meshes/cyberpunk/concrete_panel_neon_sign.mesh".to_string(),
+                "meshes/cyberpunk/concrete_panel_pipes.mesh".to_string(),
+                "meshes/cyberpunk/concrete_panel_screen.mesh".to_string(),
+            ],
+        );
+        mesh_variants.insert(
+            "door".to_string(),
+            vec![
+                "meshes/cyberpunk/shutter_closed.mesh".to_string(),
+                "meshes/cyberpunk/shutter_half.mesh".to_string(),
+            ],
+        );
+
+        let mut mesh_variant_weights = HashMap::new();
+        mesh_variant_weights.insert("wall".to_string(), vec![5.0, 1.0, 2.0, 1.0]);
+        mesh_variant_weights.insert("door".to_string(), vec![3.0, 1.0]);
+
+        let mut tiles = HashMap::new();
+
+        // Floor tile
+        tiles.insert(
+            "floor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Floor,
+                name: "Wet Asphalt".to_string(),
+                description: "Rain-slick pavement reflecting the neon".to_string(),
+                visual: TileVisual {
+                    icon: '░',
+                    color: "#6A5ACD".to_string(),
+                    background_color: Some("#14101F".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "urban".to_string(),
+                    "cyberpunk".to_string(),
+                ],
+            },
+        );
+
+        // Wall tile
+        tiles.insert(
+            "wall".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Megablock Wall".to_string(),
+                description: "Stained concrete panels hung with signage".to_string(),
+                visual: TileVisual {
+                    icon: '█',
+                    color: "#FF00FF".to_string(),
+                    background_color: Some("#1A0A24".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "cube".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 6.0, 0.5),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 3.0, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec![
+                    "barrier".to_string(),
+                    "urban".to_string(),
+                    "cyberpunk".to_string(),
+                ],
+            },
+        );
+
+        // Door tile
+        tiles.insert(
+            "door".to_string(),
+            TileDefinition {
+                tile_type: TileType::Door,
+                name: "Roller Shutter".to_string(),
+                description: "Motorised steel shutter".to_string(),
+                visual: TileVisual {
+                    icon: '╪',
+                    color: "#00FFFF".to_string(),
+                    background_color: Some("#0A1F24".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "shutter_door".to_string(),
+                    material: "door".to_string(),
+                    scale: (1.0, 3.0, 0.2),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "interactive".to_string(),
+                    "urban".to_string(),
+                    "cyberpunk".to_string(),
+                ],
+            },
+        );
+
+        // Window tile
+        tiles.insert(
+            "window".to_string(),
+            TileDefinition {
+                tile_type: TileType::Window,
+                name: "Neon Window".to_string(),
+                description: "Glass lit by a sign behind it".to_string(),
+                visual: TileVisual {
+                    icon: '▣',
+                    color: "#FF1493".to_string(),
+                    background_color: Some("#1A0A24".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "window".to_string(),
+                    material: "window".to_string(),
+                    scale: (1.0, 3.0, 0.1),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec![
+                    "barrier".to_string(),
+                    "transparent".to_string(),
+                    "lit".to_string(),
+                    "cyberpunk".to_string(),
+                ],
+            },
+        );
+
+        // Corridor tile
+        tiles.insert(
+            "corridor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Corridor,
+                name: "Back Alley".to_string(),
+                description: "Narrow alley strewn with cables and trash".to_string(),
+                visual: TileVisual {
+                    icon: '▒',
+                    color: "#8A2BE2".to_string(),
+                    background_color: Some("#0F0A18".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "corridor".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "corridor".to_string(),
+                    "urban".to_string(),
+                    "cyberpunk".to_string(),
+                ],
+            },
+        );
+
+        // Stairs tile
+        tiles.insert(
+            "stairs".to_string(),
+            TileDefinition {
+                tile_type: TileType::Stairs,
+                name: "Fire Escape".to_string(),
+                description: "Steel stairs bolted to the outside of a block".to_string(),
+                visual: TileVisual {
+                    icon: '⇅',
+                    color: "#C0C0C0".to_string(),
+                    background_color: Some("#14101F".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "fire_escape".to_string(),
+                    material: "door".to_string(),
+                    scale: (1.0, 1.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.5, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "vertical".to_string(),
+                    "urban".to_string(),
+                    "cyberpunk".to_string(),
+                ],
+            },
+        );
+
+        // Empty/void tile
+        tiles.insert(
+            "empty".to_string(),
+            TileDefinition {
+                tile_type: TileType::Empty,
+                name: "Drop".to_string(),
+                description: "Open air between the towers".to_string(),
+                visual: TileVisual {
+                    icon: ' ',
+                    color: "#000000".to_string(),
+                    background_color: Some("#05030A".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "none".to_string(),
+                    material: "none".to_string(),
+                    scale: (0.0, 0.0, 0.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["void".to_string(), "cyberpunk".to_string()],
+            },
+        );
+
+        Self {
+            id: "cyberpunk".to_string(),
+            name: "Cyberpunk".to_string(),
+            description: "Rain-slick streets and cramped interiors lit by neon signage".to_string(),
+            author: "Morgan-Bevy".to_string(),
+            version: "1.0.0".to_string(),
+            tiles,
+            default_floor_height: 0.0,
+            wall_height: 6.0,
+            lighting: ThemeLighting {
+                ambient_color: (0.3, 0.2, 0.5),
+                ambient_intensity: 0.25,
+                directional_color: (0.9, 0.3, 0.9),
+                directional_intensity: 0.5,
+                directional_direction: (-0.2, -1.0, 0.4),
+                shadow_enabled: true,
+            },
+            materials,
+            mesh_variants,
+            mesh_variant_weights,
+            props: vec![
+                PropRule::new("vending_machine", PropPlacement::Wall, 0.05),
+                PropRule::new("neon_sign", PropPlacement::Wall, 0.04),
+                PropRule::new("hologram", PropPlacement::Center, 0.02),
+                PropRule::new("trash_bags", PropPlacement::Floor, 0.05),
+            ],
+            asset_bindings: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn shipwreck() -> Self {
+        let mut materials = HashMap::new();
+        materials.insert(
+            "floor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/shipwreck/rotten_deck_diffuse.png".to_string()),
+                normal: Some("textures/shipwreck/rotten_deck_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/shipwreck/rotten_deck_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "wall".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/shipwreck/hull_planks_diffuse.png".to_string()),
+                normal: Some("textures/shipwreck/hull_planks_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/shipwreck/hull_planks_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "door".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/shipwreck/cabin_door_diffuse.png".to_string()),
+                normal: Some("textures/shipwreck/cabin_door_normal.png".to_string()),
+                metallic: Some("textures/shipwreck/cabin_door_metallic.png".to_string()),
+                roughness: Some("textures/shipwreck/cabin_door_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "corridor".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/shipwreck/gangway_diffuse.png".to_string()),
+                normal: Some("textures/shipwreck/gangway_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/shipwreck/gangway_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "window".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/shipwreck/porthole_diffuse.png".to_string()),
+                normal: Some("textures/shipwreck/porthole_normal.png".to_string()),
+                metallic: Some("textures/shipwreck/porthole_metallic.png".to_string()),
+                roughness: Some("textures/shipwreck/porthole_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "water".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/shipwreck/seawater_diffuse.png".to_string()),
+                normal: Some("textures/shipwreck/seawater_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/shipwreck/seawater_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        materials.insert(
+            "sand".to_string(),
+            MaterialInfo {
+                diffuse: Some("textures/shipwreck/sand_diffuse.png".to_string()),
+                normal: Some("textures/shipwreck/sand_normal.png".to_string()),
+                metallic: None,
+                roughness: Some("textures/shipwreck/sand_roughness.png".to_string()),
+                emission: None,
+            },
+        );
+
+        let mut mesh_variants = HashMap::new();
+        mesh_variants.insert(
+            "wall".to_string(),
+            vec![
+                "meshes/shipwreck/hull_planks_intact.mesh".to_string(),
+                "meshes/shipwreck/hull_planks_barnacles.mesh".to_string(),
+                "meshes/shipwreck/hull_planks_broken.mesh".to_string(),
+            ],
+        );
+        mesh_variants.insert(
+            "floor".to_string(),
+            vec![
+                "meshes/shipwreck/rotten_deck_intact.mesh".to_string(),
+                "meshes/shipwreck/rotten_deck_hole.mesh".to_string(),
+                "meshes/shipwreck/rotten_deck_seaweed.mesh".to_string(),
+            ],
+        );
+
+        let mut mesh_variant_weights = HashMap::new();
+        mesh_variant_weights.insert("wall".to_string(), vec![4.0, 2.0, 1.0]);
+        mesh_variant_weights.insert("floor".to_string(), vec![5.0, 1.0, 2.0]);
+
+        let mut tiles = HashMap::new();
+
+        // Floor tile
+        tiles.insert(
+            "floor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Floor,
+                name: "Rotten Deck".to_string(),
+                description: "Warped planks soft with seawater".to_string(),
+                visual: TileVisual {
+                    icon: '═',
+                    color: "#8B7355".to_string(),
+                    background_color: Some("#2E2418".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "floor".to_string(),
+                    scale: (1.0, 0.1, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "wooden".to_string(),
+                    "shipwreck".to_string(),
+                ],
+            },
+        );
+
+        // Wall tile
+        tiles.insert(
+            "wall".to_string(),
+            TileDefinition {
+                tile_type: TileType::Wall,
+                name: "Hull Planks".to_string(),
+                description: "Curved hull planking crusted with barnacles".to_string(),
+                visual: TileVisual {
+                    icon: '▐',
+                    color: "#6B4E31".to_string(),
+                    background_color: Some("#1C140C".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "hull_wall".to_string(),
+                    material: "wall".to_string(),
+                    scale: (1.0, 3.0, 0.4),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec![
+                    "barrier".to_string(),
+                    "wooden".to_string(),
+                    "shipwreck".to_string(),
+                ],
+            },
+        );
+
+        // Door tile
+        tiles.insert(
+            "door".to_string(),
+            TileDefinition {
+                tile_type: TileType::Door,
+                name: "Cabin Door".to_string(),
+                description: "Swollen door hanging from rusted hinges".to_string(),
+                visual: TileVisual {
+                    icon: '▯',
+                    color: "#A0522D".to_string(),
+                    background_color: Some("#2E2418".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "cabin_door".to_string(),
+                    material: "door".to_string(),
+                    scale: (1.0, 2.2, 0.1),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.1, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "interactive".to_string(),
+                    "wooden".to_string(),
+                    "shipwreck".to_string(),
+                ],
+            },
+        );
+
+        // Window tile
+        tiles.insert(
+            "window".to_string(),
+            TileDefinition {
+                tile_type: TileType::Window,
+                name: "Porthole".to_string(),
+                description: "Round brass-framed porthole".to_string(),
+                visual: TileVisual {
+                    icon: '◎',
+                    color: "#B8860B".to_string(),
+                    background_color: Some("#1C140C".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "porthole".to_string(),
+                    material: "window".to_string(),
+                    scale: (1.0, 3.0, 0.4),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 1.5, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec![
+                    "barrier".to_string(),
+                    "transparent".to_string(),
+                    "shipwreck".to_string(),
+                ],
+            },
+        );
+
+        // Corridor tile
+        tiles.insert(
+            "corridor".to_string(),
+            TileDefinition {
+                tile_type: TileType::Corridor,
+                name: "Gangway".to_string(),
+                description: "Narrow passage between decks".to_string(),
+                visual: TileVisual {
+                    icon: '─',
+                    color: "#9C8260".to_string(),
+                    background_color: Some("#2E2418".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "corridor".to_string(),
+                    scale: (1.0, 0.1, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "corridor".to_string(),
+                    "wooden".to_string(),
+                    "shipwreck".to_string(),
+                ],
+            },
+        );
+
+        // Stairs tile
+        tiles.insert(
+            "stairs".to_string(),
+            TileDefinition {
+                tile_type: TileType::Stairs,
+                name: "Companionway Ladder".to_string(),
+                description: "Steep ladder between decks".to_string(),
+                visual: TileVisual {
+                    icon: '╫',
+                    color: "#8B7355".to_string(),
+                    background_color: Some("#1C140C".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "ladder".to_string(),
+                    material: "corridor".to_string(),
+                    scale: (1.0, 1.0, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.5, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "vertical".to_string(),
+                    "wooden".to_string(),
+                    "shipwreck".to_string(),
+                ],
+            },
+        );
+
+        // Water tile
+        tiles.insert(
+            "water".to_string(),
+            TileDefinition {
+                tile_type: TileType::Water,
+                name: "Flooded Hold".to_string(),
+                description: "Knee-deep seawater in the lower hold".to_string(),
+                visual: TileVisual {
+                    icon: '≈',
+                    color: "#2E8B8B".to_string(),
+                    background_color: Some("#0A2424".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "water_plane".to_string(),
+                    material: "water".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, -0.3, 0.0),
+                },
+                collision: false,
+                walkable: false,
+                tags: vec!["liquid".to_string(), "shipwreck".to_string()],
+            },
+        );
+
+        // Sand tile
+        tiles.insert(
+            "sand".to_string(),
+            TileDefinition {
+                tile_type: TileType::Floor,
+                name: "Sandbar".to_string(),
+                description: "Wet sand the wreck has settled into".to_string(),
+                visual: TileVisual {
+                    icon: '∴',
+                    color: "#F4A460".to_string(),
+                    background_color: Some("#3B2F1A".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "plane".to_string(),
+                    material: "sand".to_string(),
+                    scale: (1.0, 0.05, 1.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: false,
+                walkable: true,
+                tags: vec![
+                    "ground".to_string(),
+                    "outdoor".to_string(),
+                    "shipwreck".to_string(),
+                ],
+            },
+        );
+
+        // Empty/void tile
+        tiles.insert(
+            "empty".to_string(),
+            TileDefinition {
+                tile_type: TileType::Empty,
+                name: "Open Sea".to_string(),
+                description: "Deep water beyond the wreck".to_string(),
+                visual: TileVisual {
+                    icon: ' ',
+                    color: "#1E3A5F".to_string(),
+                    background_color: Some("#08121F".to_string()),
+                },
+                mesh: TileMesh {
+                    mesh_type: "none".to_string(),
+                    material: "none".to_string(),
+                    scale: (0.0, 0.0, 0.0),
+                    rotation: (0.0, 0.0, 0.0),
+                    offset: (0.0, 0.0, 0.0),
+                },
+                collision: true,
+                walkable: false,
+                tags: vec!["void".to_string(), "shipwreck".to_string()],
+            },
+        );
+
+        Self {
+            id: "shipwreck".to_string(),
+            name: "Shipwreck".to_string(),
+            description:
+                "The broken hull of a wooden ship run aground, partly flooded and open to the sand"
+                    .to_string(),
+            author: "Morgan-Bevy".to_string(),
+            version: "1.0.0".to_string(),
+            tiles,
+            default_floor_height: 0.0,
+            wall_height: 3.0,
+            lighting: ThemeLighting {
+                ambient_color: (0.5, 0.6, 0.65),
+                ambient_intensity: 0.3,
+                directional_color: (0.9, 0.9, 0.85),
+                directional_intensity: 0.7,
+                directional_direction: (-0.3, -1.0, -0.5),
+                shadow_enabled: true,
+            },
+            materials,
+            mesh_variants,
+            mesh_variant_weights,
+            props: vec![
+                PropRule::new("lantern", PropPlacement::Wall, 0.05),
+                PropRule::new("chest", PropPlacement::Center, 0.02),
+                PropRule::new("barrel", PropPlacement::Floor, 0.05),
+                PropRule::new("crate", PropPlacement::Floor, 0.04),
+            ],
+            asset_bindings: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn get_theme(name: &str) -> Option<Theme> {
+        match name.to_lowercase().as_str() {
+            "office" => Some(Self::office()),
+            "dungeon" => Some(Self::dungeon()),
+            "scifi" | "sci-fi" => Some(Self::scifi()),
+            "castle" => Some(Self::castle()),
+            "cave" => Some(Self::cave()),
+            "forest" => Some(Self::forest()),
+            "cyberpunk" => Some(Self::cyberpunk()),
+            "shipwreck" => Some(Self::shipwreck()),
+            _ => None,
+        }
+    }
+
+    /// Put an asset's path in a slot and remember which asset it came from, replacing
+    /// any earlier binding of the same slot.
+    pub fn bind_asset(&mut self, slot: ThemeSlot, asset_id: i64, path: &str) -> Result<(), String> {
+        let slot = self.fill_slot(slot, path)?;
+        self.asset_bindings.retain(|binding| binding.slot != slot);
+        self.asset_bindings.push(AssetBinding {
+            slot,
+            asset_id,
+            path: path.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Point every slot bound to an asset at its new path. Returns whether anything changed.
+    pub fn rebind_asset(&mut self, asset_id: i64, path: &str) -> bool {
+        let slots: Vec<ThemeSlot> = self
+            .asset_bindings
+            .iter()
+            .filter(|binding| binding.asset_id == asset_id && binding.path != path)
+            .map(|binding| binding.slot.clone())
+            .collect();
+        for slot in &slots {
+            // Slots only disappear if the file was edited by hand; drop those bindings
+            if self.fill_slot(slot.clone(), path).is_err() {
+                self.asset_bindings.retain(|binding| &binding.slot != slot);
+            }
+        }
+        for binding in &mut self.asset_bindings {
+            if binding.asset_id == asset_id {
+                binding.path = path.to_string();
+            }
+        }
+        !slots.is_empty()
+    }
+
+    /// Write a path into a slot, returning the slot with a mesh variant's index filled in.
+    fn fill_slot(&mut self, slot: ThemeSlot, path: &str) -> Result<ThemeSlot, String> {
+        match slot {
+            ThemeSlot::Material { material, channel } => {
+                let info = self.materials.entry(material.clone()).or_default();
+                let texture = match channel {
+                    MaterialChannel::Diffuse => &mut info.diffuse,
+                    MaterialChannel::Normal => &mut info.normal,
+                    MaterialChannel::Metallic => &mut info.metallic,
+                    MaterialChannel::Roughness => &mut info.roughness,
+                    MaterialChannel::Emission => &mut info.emission,
+                };
+                *texture = Some(path.to_string());
+                Ok(ThemeSlot::Material { material, channel })
+            }
+            ThemeSlot::MeshVariant { key, index } => {
+                let variants = self.mesh_variants.entry(key.clone()).or_default();
+                let index = index.unwrap_or(variants.len());
+                match index.cmp(&variants.len()) {
+                    std::cmp::Ordering::Less => variants[index] = path.to_string(),
+                    std::cmp::Ordering::Equal => variants.push(path.to_string()),
+                    std::cmp::Ordering::Greater => {
+                        return Err(format!(
+                            "Mesh variant {} has only {} entries",
+                            key,
+                            variants.len()
+                        ))
+                    }
+                }
+                Ok(ThemeSlot::MeshVariant {
+                    key,
+                    index: Some(index),
+                })
+            }
+        }
+    }
+
+    /// Checks the theme can be saved and used: its ID has to work as a file name, its
+    /// tiles, textures and meshes can't be blank, and asset bindings may only fill
+    /// materials and mesh variants it has. Tiles may name materials without textures.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut problems = Vec::new();
+        if !is_theme_id(&self.id) {
+            problems.push(format!(
+                "ID {:?} may only use letters, digits, '_' and '-'",
+                self.id
+            ));
+        }
+        if self.tiles.is_empty() {
+            problems.push("it has no tiles".to_string());
+        }
+        let mut tiles: Vec<(&String, &TileDefinition)> = self.tiles.iter().collect();
+        tiles.sort_by_key(|(key, _)| *key);
+        for (key, tile) in tiles {
+            if key.is_empty() || tile.mesh.material.is_empty() || tile.mesh.mesh_type.is_empty() {
+                problems.push(format!(
+                    "tile {:?} needs a key, mesh type and material",
+                    key
+                ));
+            }
+        }
+        let mut materials: Vec<(&String, &MaterialInfo)> = self.materials.iter().collect();
+        materials.sort_by_key(|(name, _)| *name);
+        for (name, info) in materials {
+            let textures = [
+                &info.diffuse,
+                &info.normal,
+                &info.metallic,
+                &info.roughness,
+                &info.emission,
+            ];
+            if textures
+                .iter()
+                .any(|texture| texture.as_deref() == Some(""))
+            {
+                problems.push(format!("material {} has a blank texture path", name));
+            }
+        }
+        let mut weights: Vec<(&String, &Vec<f32>)> = self.mesh_variant_weights.iter().collect();
+        weights.sort_by_key(|(key, _)| *key);
+        for (key, weights) in weights {
+            if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+                problems.push(format!("mesh variant {} has a negative weight", key));
+            }
+        }
+        let mut variants: Vec<(&String, &Vec<String>)> = self.mesh_variants.iter().collect();
+        variants.sort_by_key(|(key, _)| *key);
+        for (key, meshes) in variants {
+            if meshes.iter().any(String::is_empty) {
+                problems.push(format!("mesh variant {} has a blank mesh path", key));
+            }
+        }
+        for prop in &self.props {
+            if prop.name.is_empty() || !(0.0..=1.0).contains(&prop.density) {
+                problems.push(format!(
+                    "prop {:?} needs a name and a density between 0 and 1",
+                    prop.name
+                ));
+            }
+            if prop.mesh.as_deref() == Some("") || prop.material.as_deref() == Some("") {
+                problems.push(format!("prop {} has a blank mesh or material", prop.name));
+            }
+        }
+        for binding in &self.asset_bindings {
+            match &binding.slot {
+                ThemeSlot::Material { material, .. } if !self.materials.contains_key(material) => {
+                    problems.push(format!("binding for undefined material {}", material));
+                }
+                ThemeSlot::MeshVariant { key, index } => {
+                    let count = self.mesh_variants.get(key).map_or(0, Vec::len);
+                    if index.is_none_or(|index| index >= count) {
+                        problems.push(format!("binding for undefined mesh variant {}", key));
+                    }
+                }
+                ThemeSlot::Material { .. } => {}
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Invalid theme: {}", problems.join("; ")))
+        }
+    }
+
+    /// The mesh variant of `key` that `roll`, from 0 up to 1, lands on, by weight.
+    #[must_use]
+    pub fn mesh_variant(&self, key: &str, roll: f64) -> Option<&str> {
+        let variants = self.mesh_variants.get(key)?;
+        let weights = self.mesh_variant_weights.get(key);
+        let weight = |index: usize| {
+            let weight = weights.and_then(|w| w.get(index)).copied().unwrap_or(1.0);
+            f64::from(weight.max(0.0))
+        };
+        let total: f64 = (0..variants.len()).map(weight).sum();
+        let mut left = roll * total;
+        variants
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| weight(index) > 0.0)
+            .find(|&(index, _)| {
+                left -= weight(index);
+                left < 0.0
+            })
+            // Every weight is zero, or rounding ran past the end
+            .or_else(|| variants.iter().enumerate().next_back())
+            .map(|(_, variant)| variant.as_str())
+    }
+
+    #[must_use]
+    pub fn list_themes() -> Vec<String> {
+        vec![
+            "office".to_string(),
+            "dungeon".to_string(),
+            "scifi".to_string(),
+            "castle".to_string(),
+            "cave".to_string(),
+            "forest".to_string(),
+            "cyberpunk".to_string(),
+            "shipwreck".to_string(),
+        ]
+    }
+}
+
+/// Theme file formats, in the order they're looked for.
+const THEME_EXTENSIONS: &[&str] = &["json", "ron"];
+
+/// Where [`ThemeLibrary::current`] gets its library, set once by the editor.
+static LIBRARY_SOURCE: OnceLock<fn() -> ThemeLibrary> = OnceLock::new();
+
+/// Built-in theme library, with themes from theme folders added or taking precedence
+pub struct ThemeLibrary {
+    /// The project's `themes` folder, whether or not it exists yet
+    directory: Option<PathBuf>,
+    /// Themes installed for every project; the project's own take precedence
+    user_directory: Option<PathBuf>,
+}
+
+/// Every theme a library could read, and the theme files it couldn't.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeScan {
+    pub themes: Vec<Theme>,
+    pub problems: Vec<ThemeFileProblem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeFileProblem {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+impl ThemeLibrary {
+    /// A library that reads and saves theme overrides in `directory`, if given.
+    #[must_use]
+    pub fn new(directory: Option<PathBuf>) -> Self {
+        Self {
+            directory,
+            user_directory: None,
+        }
+    }
+
+    /// Has [`Self::current`] call `source` for its library, so generators see the same
+    /// themes as the editor. Only the first call has any effect.
+    pub fn set_current_source(source: fn() -> ThemeLibrary) {
+        let _ = LIBRARY_SOURCE.set(source);
+    }
+
+    /// The library generators look themes up in: the editor's, once it has set one,
+    /// else just the built-in themes.
+    pub fn current() -> Self {
+        LIBRARY_SOURCE
+            .get()
+            .map_or_else(|| Self::new(None), |source| source())
+    }
+
+    /// The library for a project, keeping its themes in `<project>/themes`.
+    #[must_use]
+    pub fn for_project(project_directory: Option<&Path>) -> Self {
+        Self::new(project_directory.map(|project| project.join(THEMES_DIRECTORY)))
+    }
+
+    /// Also reads themes installed in `directory`, under the project's own.
+    #[must_use]
+    pub fn with_user_directory(mut self, directory: Option<PathBuf>) -> Self {
+        self.user_directory = directory;
+        self
+    }
+
+    #[must_use]
+    pub fn directory(&self) -> Option<&Path> {
+        self.directory.as_deref()
+    }
+
+    #[must_use]
+    pub fn user_directory(&self) -> Option<&Path> {
+        self.user_directory.as_deref()
+    }
+
+    /// Theme folders, the one taking precedence first.
+    fn directories(&self) -> impl Iterator<Item = &Path> {
+        self.directory().into_iter().chain(self.user_directory())
+    }
+
+    /// Get all available themes
+    #[must_use]
+    pub fn get_all_themes(&self) -> Vec<Theme> {
+        let scan = self.scan();
+        for problem in &scan.problems {
+            warn!("Ignoring theme file {:?}: {}", problem.path, problem.error);
+        }
+        scan.themes
+    }
+
+    /// Reads every theme file in the theme folders. Themes come built-in ones first,
+    /// then the rest by ID, each from the file taking precedence; files that don't load
+    /// or validate are left out and reported.
+    #[must_use]
+    pub fn scan(&self) -> ThemeScan {
+        let mut problems = Vec::new();
+        let mut found: BTreeMap<String, Theme> = BTreeMap::new();
+        // Least precedence first, so later files replace earlier ones
+        let directories: Vec<&Path> = self.directories().collect();
+        for directory in directories.into_iter().rev() {
+            let Ok(entries) = fs::read_dir(directory) else {
+                continue;
+            };
+            let mut files: Vec<PathBuf> = entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| theme_extension(path).is_some())
+                .collect();
+            // JSON last, so it wins over a RON file of the same theme
+            files.sort_by_key(|path| std::cmp::Reverse(theme_extension(path)));
+            for path in files {
+                match load_valid_theme_file(&path) {
+                    Ok(theme) => {
+                        found.insert(theme.id.clone(), theme);
+                    }
+                    Err(error) => problems.push(ThemeFileProblem { path, error }),
+                }
+            }
+        }
+
+        let mut themes: Vec<Theme> = Theme::list_themes()
+            .iter()
+            .filter_map(|id| Theme::get_theme(id))
+            .map(|theme| found.remove(&theme.id).unwrap_or(theme))
+            .collect();
+        themes.extend(found.into_values());
+        ThemeScan { themes, problems }
+    }
+
+    /// Get theme by ID
+    #[must_use]
+    pub fn get_theme(&self, id: &str) -> Option<Theme> {
+        let built_in = Theme::get_theme(id);
+        let id = built_in.as_ref().map_or(id, |theme| theme.id.as_str());
+        for path in self.theme_files(id) {
+            match load_valid_theme_file(&path) {
+                Ok(theme) => return Some(theme),
+                Err(e) => warn!("Ignoring theme file {:?}: {}", path, e),
+            }
+        }
+        built_in
+    }
+
+    /// Save a theme to the project so it overrides the built-in one.
+    pub fn save_theme(&self, theme: &Theme) -> Result<PathBuf, String> {
+        let directory = self
+            .directory()
+            .ok_or("No project Assets directory to save themes beside")?;
+        save_theme_file(directory, theme)
+    }
+
+    /// Copies a JSON or RON theme file into the project's theme folder, or the user's
+    /// when `into_project` is false, after checking it's a valid theme.
+    pub fn install_theme(&self, source: &Path, into_project: bool) -> Result<Theme, String> {
+        let directory = if into_project {
+            self.directory()
+                .ok_or("No project Assets directory to install themes beside")?
+        } else {
+            self.user_directory()
+                .ok_or("No user theme directory to install themes in")?
+        };
+        let mut theme = load_theme_file(source)?;
+        // Hand-written themes can leave out their ID; the file name stands in for it
+        if theme.id.is_empty() {
+            theme.id = file_stem(source).unwrap_or_default().to_string();
+        }
+        theme.validate()?;
+        save_theme_file(directory, &theme)?;
+        // A RON copy left behind would be shadowed, but still confusing
+        let stale = directory.join(format!("{}.ron", theme.id));
+        if stale.exists() {
+            fs::remove_file(&stale).map_err(|e| format!("Failed to remove {:?}: {}", stale, e))?;
+        }
+        Ok(theme)
+    }
+
+    /// Whether a theme with this ID is built in or in a theme folder.
+    #[must_use]
+    pub fn contains(&self, id: &str) -> bool {
+        Theme::get_theme(id).is_some() || self.theme_files(id).next().is_some()
+    }
+
+    /// Files in the theme folders holding the theme `id`, the one taking precedence
+    /// first.
+    fn theme_files<'a>(&'a self, id: &'a str) -> impl Iterator<Item = PathBuf> + 'a {
+        self.directories()
+            // Any other ID could reach outside the folders
+            .filter(move |_| is_theme_id(id))
+            .flat_map(move |directory| {
+                THEME_EXTENSIONS
+                    .iter()
+                    .map(move |ext| directory.join(format!("{}.{}", id, ext)))
+            })
+            .filter(|path| path.exists())
+    }
+
+    /// Saves an edited theme over the file it was read from, so the edit takes
+    /// effect; built-in and new themes go in the user's theme folder.
+    pub fn save_edited_theme(&self, theme: &Theme) -> Result<PathBuf, String> {
+        theme.validate()?;
+        if let Some(path) = self.theme_files(&theme.id).next() {
+            write_theme_file(&path, theme)?;
+            return Ok(path);
+        }
+        let directory = self
+            .user_directory()
+            .ok_or("No user theme directory to save themes in")?;
+        save_theme_file(directory, theme)
+    }
+
+    /// Adds a new theme to the user's theme folder.
+    pub fn create_theme(&self, theme: &Theme) -> Result<PathBuf, String> {
+        if self.contains(&theme.id) {
+            return Err(format!("Theme {} already exists", theme.id));
+        }
+        self.save_edited_theme(theme)
+    }
+
+    /// Copies theme `id` as a new theme `new_id`, named `name` or after the original.
+    pub fn duplicate_theme(
+        &self,
+        id: &str,
+        new_id: &str,
+        name: Option<String>,
+    ) -> Result<Theme, String> {
+        let mut theme = self
+            .get_theme(id)
+            .ok_or_else(|| format!("Theme not found: {}", id))?;
+        theme.name = name.unwrap_or_else(|| format!("{} Copy", theme.name));
+        theme.id = new_id.to_string();
+        self.create_theme(&theme)?;
+        Ok(theme)
+    }
+
+    /// Adds or replaces tile `key` in theme `id`, or removes it when `tile` is `None`.
+    pub fn update_theme_tile(
+        &self,
+        id: &str,
+        key: &str,
+        tile: Option<TileDefinition>,
+    ) -> Result<Theme, String> {
+        let mut theme = self
+            .get_theme(id)
+            .ok_or_else(|| format!("Theme not found: {}", id))?;
+        match tile {
+            Some(tile) => {
+                theme.tiles.insert(key.to_string(), tile);
+            }
+            None => {
+                theme
+                    .tiles
+                    .remove(key)
+                    .ok_or_else(|| format!("Theme {} has no tile {}", id, key))?;
+            }
+        }
+        self.save_edited_theme(&theme)?;
+        Ok(theme)
+    }
+
+    /// Deletes every file of theme `id` from the theme folders, which puts a built-in
+    /// theme back as it shipped. Returns the files removed.
+    pub fn delete_theme(&self, id: &str) -> Result<Vec<PathBuf>, String> {
+        let files: Vec<PathBuf> = self.theme_files(id).collect();
+        if files.is_empty() {
+            return Err(if Theme::get_theme(id).is_some() {
+                format!(
+                    "Theme {} is built in and has no saved changes to delete",
+                    id
+                )
+            } else {
+                format!("Theme not found: {}", id)
+            });
+        }
+        for file in &files {
+            fs::remove_file(file).map_err(|e| format!("Failed to remove {:?}: {}", file, e))?;
+        }
+        Ok(files)
+    }
+
+    /// Rebind an asset that moved in every saved theme. Returns the IDs of themes changed.
+    pub fn rebind_moved_asset(&self, asset_id: i64, path: &str) -> Result<Vec<String>, String> {
+        let Some(directory) = self.directory() else {
+            return Ok(Vec::new());
+        };
+        rebind_theme_files(directory, asset_id, path)
+    }
+}
+
+#[must_use]
+pub fn theme_file_path(directory: &Path, id: &str) -> PathBuf {
+    directory.join(format!("{}.json", id))
+}
+
+fn theme_extension(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?;
+    THEME_EXTENSIONS
+        .iter()
+        .copied()
+        .find(|known| ext.eq_ignore_ascii_case(known))
+}
+
+/// Whether `id` works as a theme ID, which is also its file name.
+fn is_theme_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn file_stem(path: &Path) -> Option<&str> {
+    path.file_stem()?.to_str()
+}
+
+/// Reads a theme from a `.json` or `.ron` file.
+pub fn load_theme_file(path: &Path) -> Result<Theme, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Failed to read theme: {}", e))?;
+    match theme_extension(path) {
+        Some("ron") => ron::from_str(&text).map_err(|e| format!("Invalid theme file: {}", e)),
+        _ => serde_json::from_str(&text).map_err(|e| format!("Invalid theme file: {}", e)),
+    }
+}
+
+/// Reads a theme from a theme folder, where it has to be valid and named after its ID.
+fn load_valid_theme_file(path: &Path) -> Result<Theme, String> {
+    let theme = load_theme_file(path)?;
+    theme.validate()?;
+    if file_stem(path) != Some(theme.id.as_str()) {
+        return Err(format!(
+            "Theme {} has to be saved as {}.json or {}.ron",
+            theme.id, theme.id, theme.id
+        ));
+    }
+    Ok(theme)
+}
+
+pub fn save_theme_file(directory: &Path, theme: &Theme) -> Result<PathBuf, String> {
+    fs::create_dir_all(directory)
+        .map_err(|e| format!("Failed to create {:?}: {}", directory, e))?;
+    let path = theme_file_path(directory, &theme.id);
+    write_theme_file(&path, theme)?;
+    Ok(path)
+}
+
+/// Writes a theme in the format of the file's extension.
+fn write_theme_file(path: &Path, theme: &Theme) -> Result<(), String> {
+    let text = match theme_extension(path) {
+        Some("ron") => ron::ser::to_string_pretty(theme, ron::ser::PrettyConfig::default())
+            .map_err(|e| format!("Failed to serialize theme: {}", e))?,
+        _ => serde_json::to_string_pretty(theme)
+            .map_err(|e| format!("Failed to serialize theme: {}", e))?,
+    };
+    fs::write(path, text).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// Rebind a moved asset in each theme file in `directory`, rewriting those that change.
+pub fn rebind_theme_files(
+    directory: &Path,
+    asset_id: i64,
+    path: &str,
+) -> Result<Vec<String>, String> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Ok(Vec::new());
+    };
+    let mut updated = Vec::new();
+    for file in entries.flatten().map(|entry| entry.path()) {
+        if theme_extension(&file).is_none() {
+            continue;
+        }
+        let mut theme = match load_theme_file(&file) {
+            Ok(theme) => theme,
+            Err(e) => {
+                warn!("Skipping theme file {:?}: {}", file, e);
+                continue;
+            }
+        };
+        if theme.rebind_asset(asset_id, path) {
+            write_theme_file(&file, &theme)?;
+            updated.push(theme.id);
+        }
+    }
+    updated.sort();
+    Ok(updated)
+}
+
+/// Convert theme tile to 2D grid character
+#[must_use]
+pub fn tile_to_char(theme: &Theme, tile_key: &str) -> char {
+    theme
+        .tiles
+        .get(tile_key)
+        .map(|tile| tile.visual.icon)
+        .unwrap_or('?')
+}
+
+/// Convert 2D grid character to tile key
+#[must_use]
+pub fn char_to_tile(theme: &Theme, ch: char) -> Option<String> {
+    for (key, tile) in &theme.tiles {
+        if tile.visual.icon == ch {
+            return Some(key.clone());
+        }
+    }
+    None
+}
+
+/// Generate a legend for a theme showing all available tiles
+#[must_use]
+pub fn generate_theme_legend(theme: &Theme) -> String {
+    let mut legend = format!("Legend for {} Theme:\n", theme.name);
+
+    for (key, tile) in &theme.tiles {
+        legend.push_str(&format!(
+            "  {} = {} ({})\n",
+            tile.visual.icon, tile.name, key
+        ));
+    }
+
+    legend
+}
+
+/// Convert a 2D grid string to tile map using theme
+#[must_use]
+pub fn parse_grid_string(theme: &Theme, grid: &str) -> Vec<Vec<String>> {
+    grid.lines()
+        .map(|line| {
+            line.chars()
+                .map(|ch| char_to_tile(theme, ch).unwrap_or_else(|| "empty".to_string()))
+                .collect()
+        })
+        .collect()
+}
+
+/// Convert tile map to 2D grid string for display
+#[must_use]
+pub fn render_grid_string(theme: &Theme, tile_map: &[Vec<String>]) -> String {
+    tile_map
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|tile_key| tile_to_char(theme, tile_key))
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// First word of a token grid's header line, which goes on to give its size as
+/// `<width>x<height>`.
+pub const TOKEN_GRID_HEADER: &str = "tiles";
+
+/// Escapes the separators and backslashes in a tile key so it reads back as one token.
+fn escape_token(key: &str) -> String {
+    let mut token = String::with_capacity(key.len());
+    for ch in key.chars() {
+        if ch == '\\' || ch == ',' || ch.is_whitespace() {
+            token.push('\\');
+        }
+        token.push(ch);
+    }
+    token
+}
+
+/// Splits a token grid row at unescaped commas and whitespace. A run of whitespace
+/// holding at most one comma is a single separator, so doubled, leading and trailing
+/// commas give blank keys.
+fn split_tokens(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token: Option<String> = None;
+    // Whether a comma has been passed since the last key
+    let mut comma = false;
+    let mut chars = line.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            ',' => {
+                if let Some(token) = token.take() {
+                    tokens.push(token);
+                } else if comma || tokens.is_empty() {
+                    tokens.push(String::new());
+                }
+                comma = true;
+            }
+            ch if ch.is_whitespace() => {
+                if let Some(token) = token.take() {
+                    tokens.push(token);
+                    comma = false;
+                }
+            }
+            ch => {
+                let token = token.get_or_insert_with(|| {
+                    comma = false;
+                    String::new()
+                });
+                if ch == '\\' {
+                    token.extend(chars.next());
+                } else {
+                    token.push(ch);
+                }
+            }
+        }
+    }
+    match token {
+        Some(token) => tokens.push(token),
+        None if comma => tokens.push(String::new()),
+        None => {}
+    }
+    tokens
+}
+
+/// Renders a tile map as a token grid of comma-separated tile keys.
+///
+/// A `tiles <width>x<height>` header comes first, then one line per row. Unlike
+/// [`render_grid_string`] this keeps tiles that share an icon apart and only uses
+/// characters found on a keyboard.
+pub fn render_token_grid(tile_map: &[Vec<String>]) -> String {
+    let width = tile_map.first().map_or(0, Vec::len);
+    let mut lines = vec![format!(
+        "{} {}x{}",
+        TOKEN_GRID_HEADER,
+        width,
+        tile_map.len()
+    )];
+    lines.extend(tile_map.iter().map(|row| {
+        row.iter()
+            .map(|key| escape_token(key))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }));
+    lines.join("\n")
+}
+
+/// Reads a token grid written by [`render_token_grid`] or by hand.
+///
+/// Keys are separated by commas or whitespace, a backslash escapes the character after
+/// it, and blank lines and lines starting with `#` are skipped. Every key must be one
+/// of `theme`'s tiles.
+pub fn parse_token_grid(theme: &Theme, grid: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut lines = grid
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+    let (width, height) = lines
+        .next()
+        .and_then(|(_, header)| {
+            let size = header.trim().strip_prefix(TOKEN_GRID_HEADER)?;
+            let (width, height) = size.trim().split_once('x')?;
+            Some((width.parse::<usize>().ok()?, height.parse::<usize>().ok()?))
+        })
+        .ok_or_else(|| {
+            format!(
+                "Grid must start with \"{} <width>x<height>\"",
+                TOKEN_GRID_HEADER
+            )
+        })?;
+
+    let mut tile_map = Vec::with_capacity(height);
+    for (index, line) in lines {
+        let row = split_tokens(line);
+        if row.len() != width {
+            return Err(format!(
+                "Line {} has {} tiles, expected {}",
+                index + 1,
+                row.len(),
+                width
+            ));
+        }
+        if let Some(key) = row.iter().find(|key| !theme.tiles.contains_key(*key)) {
+            return Err(format!(
+                "Line {}: theme {} has no tile {:?}",
+                index + 1,
+                theme.id,
+                key
+            ));
+        }
+        tile_map.push(row);
+    }
+    if tile_map.len() != height {
+        return Err(format!(
+            "Grid has {} rows, expected {}",
+            tile_map.len(),
+            height
+        ));
+    }
+    Ok(tile_map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn bindings_follow_moved_assets() {
+        let temp_dir = tempdir().unwrap();
+        let mut theme = Theme::office();
+        let floor = ThemeSlot::Material {
+            material: "floor".to_string(),
+            channel: MaterialChannel::Diffuse,
+        };
+        theme
+            .bind_asset(floor.clone(), 7, "Kenney/carpet.png")
+            .unwrap();
+        theme.bind_asset(floor, 8, "Kenney/tiles.png").unwrap();
+        let variants = theme.mesh_variants["wall"].len();
+        let wall = ThemeSlot::MeshVariant {
+            key: "wall".to_string(),
+            index: None,
+        };
+        theme.bind_asset(wall, 9, "Kenney/wall.glb").unwrap();
+        assert_eq!(theme.asset_bindings.len(), 2);
+        assert!(theme
+            .bind_asset(
+                ThemeSlot::MeshVariant {
+                    key: "wall".to_string(),
+                    index: Some(variants + 5),
+                },
+                9,
+                "Kenney/wall.glb",
+            )
+            .is_err());
+
+        save_theme_file(temp_dir.path(), &theme).unwrap();
+        assert!(rebind_theme_files(temp_dir.path(), 7, "Old/carpet.png")
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            rebind_theme_files(temp_dir.path(), 9, "Walls/wall.glb").unwrap(),
+            vec!["office".to_string()]
+        );
+
+        let saved = load_theme_file(&theme_file_path(temp_dir.path(), "office")).unwrap();
+        assert_eq!(saved.mesh_variants["wall"][variants], "Walls/wall.glb");
+        assert_eq!(
+            saved.materials["floor"].diffuse.as_deref(),
+            Some("Kenney/tiles.png")
+        );
+        assert_eq!(saved.asset_bindings[1].path, "Walls/wall.glb");
+
+        // The saved copy overrides the built-in theme
+        let library = ThemeLibrary::new(Some(temp_dir.path().to_path_buf()));
+        let office = library.get_theme("office").unwrap();
+        assert_eq!(office.mesh_variants["wall"][variants], "Walls/wall.glb");
+    }
+
+    #[test]
+    fn themes_load_from_user_and_project_folders() {
+        for id in Theme::list_themes() {
+            Theme::get_theme(&id).unwrap().validate().unwrap();
+        }
+
+        let user = tempdir().unwrap();
+        let project = tempdir().unwrap();
+        let mut swamp = Theme::dungeon();
+        swamp.id = "swamp".to_string();
+        swamp.name = "Swamp".to_string();
+        let ron = ron::ser::to_string(&swamp).unwrap();
+        fs::write(user.path().join("swamp.ron"), ron).unwrap();
+        fs::write(user.path().join("broken.json"), "{").unwrap();
+        let mut bad = swamp.clone();
+        bad.id = "bad".to_string();
+        bad.mesh_variants
+            .insert("wall".to_string(), vec![String::new()]);
+        save_theme_file(user.path(), &bad).unwrap();
+
+        let library = ThemeLibrary::new(Some(project.path().to_path_buf()))
+            .with_user_directory(Some(user.path().to_path_buf()));
+        assert_eq!(library.get_theme("swamp").unwrap().name, "Swamp");
+        assert!(library.get_theme("bad").is_none());
+        let scan = library.scan();
+        let ids: Vec<&str> = scan.themes.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "office",
+                "dungeon",
+                "scifi",
+                "castle",
+                "cave",
+                "forest",
+                "cyberpunk",
+                "shipwreck",
+                "swamp"
+            ]
+        );
+        assert_eq!(scan.problems.len(), 2);
+
+        // Installing into the project overrides the user's copy
+        swamp.name = "Bog".to_string();
+        let source = tempdir().unwrap();
+        let file = source.path().join("bog-download.json");
+        fs::write(&file, serde_json::to_string(&swamp).unwrap()).unwrap();
+        assert_eq!(library.install_theme(&file, true).unwrap().id, "swamp");
+        assert_eq!(library.get_theme("swamp").unwrap().name, "Bog");
+        assert!(library
+            .install_theme(&theme_file_path(user.path(), "bad"), false)
+            .is_err());
+    }
+
+    #[test]
+    fn edited_themes_are_saved_where_they_take_effect() {
+        let user = tempdir().unwrap();
+        let project = tempdir().unwrap();
+        let library = ThemeLibrary::new(Some(project.path().to_path_buf()))
+            .with_user_directory(Some(user.path().to_path_buf()));
+
+        let crypt = library.duplicate_theme("dungeon", "crypt", None).unwrap();
+        assert_eq!(crypt.name, "Dungeon Copy");
+        assert!(theme_file_path(user.path(), "crypt").exists());
+        assert!(library.create_theme(&crypt).is_err());
+        assert!(library
+            .duplicate_theme("dungeon", "../crypt", None)
+            .is_err());
+
+        let mut bones = crypt.tiles["floor"].clone();
+        bones.name = "Bones".to_string();
+        library
+            .update_theme_tile("crypt", "bones", Some(bones))
+            .unwrap();
+        library.update_theme_tile("crypt", "stairs", None).unwrap();
+        let saved = library.get_theme("crypt").unwrap();
+        assert_eq!(saved.tiles["bones"].name, "Bones");
+        assert!(!saved.tiles.contains_key("stairs"));
+        assert!(library.update_theme_tile("crypt", "stairs", None).is_err());
+
+        // A project copy is edited in place rather than shadowed by the user's
+        save_theme_file(project.path(), &saved).unwrap();
+        library.update_theme_tile("crypt", "bones", None).unwrap();
+        let in_project = load_theme_file(&theme_file_path(project.path(), "crypt")).unwrap();
+        assert!(!in_project.tiles.contains_key("bones"));
+
+        assert_eq!(library.delete_theme("crypt").unwrap().len(), 2);
+        assert!(!library.contains("crypt"));
+        assert!(library.delete_theme("dungeon").is_err());
+    }
+
+    #[test]
+    fn mesh_variants_are_picked_by_weight() {
+        let mut theme = Theme::office();
+        let walls = theme.mesh_variants["wall"].clone();
+        assert_eq!(theme.mesh_variant("wall", 0.0), Some(walls[0].as_str()));
+        assert_eq!(theme.mesh_variant("wall", 0.99), Some(walls[2].as_str()));
+        assert!(theme.mesh_variant("floor", 0.5).is_none());
+
+        // The third variant has no weight, so counts as 1
+        theme
+            .mesh_variant_weights
+            .insert("wall".to_string(), vec![0.0, 3.0]);
+        assert_eq!(theme.mesh_variant("wall", 0.0), Some(walls[1].as_str()));
+        assert_eq!(theme.mesh_variant("wall", 0.74), Some(walls[1].as_str()));
+        assert_eq!(theme.mesh_variant("wall", 0.76), Some(walls[2].as_str()));
+        theme.validate().unwrap();
+
+        theme
+            .mesh_variant_weights
+            .insert("wall".to_string(), vec![-1.0]);
+        assert!(theme.validate().is_err());
+    }
+
+    #[test]
+    fn built_in_themes_cover_outdoor_tiles() {
+        let themes: Vec<Theme> = Theme::list_themes()
+            .iter()
+            .map(|id| Theme::get_theme(id).unwrap())
+            .collect();
+        for theme in &themes {
+            for (key, weights) in &theme.mesh_variant_weights {
+                assert_eq!(
+                    weights.len(),
+                    theme.mesh_variants[key].len(),
+                    "{}",
+                    theme.id
+                );
+            }
+        }
+        for tile_type in [TileType::Grass, TileType::Water, TileType::Cliff] {
+            assert!(themes
+                .iter()
+                .any(|theme| theme.tiles.values().any(|tile| tile.tile_type == tile_type)));
+        }
+        let forest = Theme::forest();
+        assert!(forest
+            .mesh_variant("wall", 0.0)
+            .unwrap()
+            .ends_with("oak_tree.mesh"));
+    }
+
+    #[test]
+    fn token_grids_round_trip_keys_icons_cannot() {
+        let mut theme = Theme::dungeon();
+        let mut rubble = theme.tiles["floor"].clone();
+        rubble.name = "Rubble".to_string();
+        theme.tiles.insert("rubble, loose".to_string(), rubble);
+        let tile_map: Vec<Vec<String>> =
+            [["wall", "wall", "wall"], ["wall", "floor", "rubble, loose"]]
+                .iter()
+                .map(|row| row.iter().map(ToString::to_string).collect())
+                .collect();
+
+        // The floor and rubble share an icon, so the icon grid loses the rubble
+        let icons = render_grid_string(&theme, &tile_map);
+        assert_ne!(parse_grid_string(&theme, &icons), tile_map);
+
+        let tokens = render_token_grid(&tile_map);
+        assert!(tokens.starts_with("tiles 3x2\nwall, wall, wall\n"));
+        assert!(tokens.ends_with("rubble\\,\\ loose"));
+        assert_eq!(parse_token_grid(&theme, &tokens).unwrap(), tile_map);
+
+        let typed = "# hand written\ntiles 3x2\n\nwall wall wall\nwall,floor , rubble\\,\\ loose\n";
+        assert_eq!(parse_token_grid(&theme, typed).unwrap(), tile_map);
+        assert!(parse_token_grid(&theme, "wall, wall").is_err());
+        assert!(parse_token_grid(&theme, "tiles 2x1\nwall, lava").is_err());
+        assert!(parse_token_grid(&theme, "tiles 2x2\nwall, wall").is_err());
+        assert!(parse_token_grid(&theme, "tiles 2x1\nwall,, wall").is_err());
+    }
+}
@@ -0,0 +1,1652 @@
+// Wave Function Collapse implementation for procedural level generation
+use crate::generation::overlapping::{OverlappingModel, OverlappingParams, EMPTY_TILE};
+use crate::generation::themes::ThemeLibrary;
+use crate::level::connectivity::record_connectivity;
+use crate::stable::{generated_id, generated_roll};
+use crate::{GameObject, LevelData, ObjectKind, Transform3D};
+use anyhow::{bail, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WFCGenerationParams {
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+    pub tileset: String,
+    pub seed: Option<u64>,
+    pub max_iterations: u32,
+    pub backtrack_limit: u32,
+    /// Learn patterns from a sample level instead of using the tileset's adjacency
+    /// rules; the tileset then only names and meshes the tiles
+    pub overlapping: Option<OverlappingParams>,
+    /// Cells fixed before solving, such as an entrance or a wall around the edge
+    pub constraints: Vec<CellConstraint>,
+    /// Solve the grid a chunk at a time, for maps too large to solve in one go
+    pub chunks: Option<ChunkParams>,
+    /// How the next cell to collapse is picked
+    pub entropy: EntropyHeuristic,
+}
+
+/// How WFC picks the next cell to collapse.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntropyHeuristic {
+    /// Fewest possible tiles, ties broken at random
+    Count,
+    /// Lowest Shannon entropy of the possible tiles' weights, with a little noise to
+    /// break ties; a cell left with one common tile and a few rare ones counts as
+    /// nearly decided, which suits tilesets with skewed weights
+    #[default]
+    Shannon,
+    /// The first open cell in reading order; fast, but more prone to dead ends
+    Scanline,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChunkParams {
+    /// Width and height of each chunk
+    pub size: u32,
+    /// Cells each chunk shares with the chunks solved before it, which it keeps as
+    /// they were solved so the seams line up; under half of `size`
+    pub overlap: u32,
+    /// Solve chunks that don't touch at the same time, with the `parallel` feature
+    pub parallel: bool,
+}
+
+impl Default for ChunkParams {
+    fn default() -> Self {
+        Self {
+            size: 32,
+            overlap: 2,
+            parallel: true,
+        }
+    }
+}
+
+impl Default for WFCGenerationParams {
+    fn default() -> Self {
+        Self {
+            width: 24,
+            height: 24,
+            depth: 1,
+            tileset: "dungeon".to_string(),
+            seed: None,
+            max_iterations: 10000,
+            backtrack_limit: 100,
+            overlapping: None,
+            constraints: Vec::new(),
+            chunks: None,
+            entropy: EntropyHeuristic::default(),
+        }
+    }
+}
+
+/// A restriction on the WFC grid applied before solving. `x` and `y` are grid cells,
+/// `y` running along the level's Z axis.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CellConstraint {
+    /// The cell holds this tile, in any of its rotations unless a variant such as
+    /// `door_r90` is named
+    Tile { x: u32, y: u32, tile: String },
+    /// The cell's edge in `direction` has this socket, as when stitching to a
+    /// neighbouring chunk
+    Socket {
+        x: u32,
+        y: u32,
+        direction: Direction,
+        socket: String,
+    },
+    /// Every edge on the outside of the grid has this socket, e.g. the solid one to
+    /// wall the level in
+    Border { socket: String },
+}
+
+/// A tile type and the sockets on its edges.
+///
+/// Two tiles may sit side by side when the sockets on their touching edges match, so a
+/// tileset only names what each edge is made of instead of listing every neighbour.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TileType {
+    pub id: String,
+    pub name: String,
+    pub weight: f32,
+    pub rotations: Vec<u32>, // Allowed rotations in degrees
+    pub mesh_type: String,   // For 3D representation
+    /// Socket on the north, east, south and west edge, unrotated
+    #[serde(default)]
+    pub sockets: [String; 4],
+    /// Degrees clockwise this variant is turned, for variants made from `rotations`
+    #[serde(default)]
+    pub rotation: u32,
+}
+
+impl TileType {
+    fn new(id: &str, name: &str, weight: f32, rotations: &[u32], sockets: [&str; 4]) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            weight,
+            rotations: rotations.to_vec(),
+            mesh_type: "cube".to_string(),
+            sockets: sockets.map(str::to_string),
+            rotation: 0,
+        }
+    }
+
+    /// The tile turned clockwise by `degrees`, a multiple of 90, with its sockets
+    /// turned along with it.
+    #[must_use]
+    pub fn rotated(&self, degrees: u32) -> TileType {
+        let mut sockets = self.sockets.clone();
+        sockets.rotate_right((degrees / 90 % 4) as usize);
+        TileType {
+            id: if degrees == 0 {
+                self.id.clone()
+            } else {
+                format!("{}_r{}", self.id, degrees)
+            },
+            sockets,
+            rotation: degrees,
+            ..self.clone()
+        }
+    }
+
+    /// ID of the tile this variant was turned from.
+    #[must_use]
+    pub fn base_id(&self) -> &str {
+        self.id
+            .strip_suffix(&format!("_r{}", self.rotation))
+            .unwrap_or(&self.id)
+    }
+
+    #[must_use]
+    pub fn socket(&self, direction: Direction) -> &str {
+        &self.sockets[direction as usize]
+    }
+}
+
+/// Every rotation of every tile, as separate tiles.
+#[must_use]
+pub fn expand_rotations(tiles: &[TileType]) -> Vec<TileType> {
+    tiles
+        .iter()
+        .flat_map(|tile| {
+            let rotations = if tile.rotations.is_empty() {
+                vec![0]
+            } else {
+                tile.rotations.clone()
+            };
+            rotations.into_iter().map(|degrees| tile.rotated(degrees))
+        })
+        .collect()
+}
+
+/// Adjacency rules derived from tile sockets: a tile allows a neighbour in a direction
+/// when the neighbour's facing edge has the same socket.
+#[must_use]
+pub fn socket_constraints(tiles: &[TileType]) -> Vec<ConstraintRule> {
+    let mut constraints = Vec::new();
+    for tile in tiles {
+        for direction in Direction::all() {
+            constraints.push(ConstraintRule {
+                tile_id: tile.id.clone(),
+                direction,
+                allowed_neighbors: tiles
+                    .iter()
+                    .filter(|other| other.socket(direction.opposite()) == tile.socket(direction))
+                    .map(|other| other.id.clone())
+                    .collect(),
+            });
+        }
+    }
+    constraints
+}
+
+/// Constraint rules for tile adjacency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstraintRule {
+    pub tile_id: String,
+    pub direction: Direction,
+    pub allowed_neighbors: HashSet<String>,
+}
+
+/// Cardinal directions for 2D WFC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    #[must_use]
+    pub fn all() -> Vec<Direction> {
+        vec![
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+        ]
+    }
+
+    #[must_use]
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::East => Direction::West,
+            Direction::West => Direction::East,
+        }
+    }
+}
+
+/// Represents a cell in the WFC grid
+#[derive(Debug, Clone)]
+pub struct WFCCell {
+    pub possible_tiles: HashSet<String>,
+    pub collapsed: bool,
+    pub collapsed_tile: Option<String>,
+}
+
+impl WFCCell {
+    #[must_use]
+    pub fn new(possible_tiles: HashSet<String>) -> Self {
+        Self {
+            possible_tiles,
+            collapsed: false,
+            collapsed_tile: None,
+        }
+    }
+
+    #[must_use]
+    pub fn entropy(&self) -> usize {
+        if self.collapsed {
+            0
+        } else {
+            self.possible_tiles.len()
+        }
+    }
+
+    pub fn collapse(&mut self, tile_id: String) {
+        self.collapsed = true;
+        self.collapsed_tile = Some(tile_id.clone());
+        self.possible_tiles.clear();
+        self.possible_tiles.insert(tile_id);
+    }
+}
+
+/// Tileset definitions for different themes.
+///
+/// Each tileset has two sockets, a solid one and an open one, and a tile for every way
+/// a cell's four edges can be solid or open, so any neighbours a cell ends up with
+/// leave it at least one tile.
+pub struct TilesetLibrary;
+
+const ALL_ROTATIONS: [u32; 4] = [0, 90, 180, 270];
+
+impl TilesetLibrary {
+    #[must_use]
+    pub fn get_tileset(name: &str) -> Vec<TileType> {
+        match name {
+            "dungeon" => Self::dungeon_tileset(),
+            "office" => Self::office_tileset(),
+            "scifi" => Self::scifi_tileset(),
+            _ => Self::dungeon_tileset(), // Default
+        }
+    }
+
+    fn dungeon_tileset() -> Vec<TileType> {
+        let (s, o) = ("stone", "open");
+        let all = &ALL_ROTATIONS;
+        vec![
+            TileType::new("wall", "Wall", 1.0, &[0], [s, s, s, s]),
+            TileType::new("floor", "Floor", 2.0, &[0], [o, o, o, o]),
+            // A doorway through a wall running east to west
+            TileType::new("door", "Door", 0.1, &[0, 90], [o, s, o, s]),
+            TileType::new("corner", "Corner", 0.5, all, [s, s, o, o]),
+            TileType::new("wall_end", "Wall End", 0.3, all, [s, s, s, o]),
+            TileType::new("floor_edge", "Floor Edge", 0.5, all, [s, o, o, o]),
+        ]
+    }
+
+    fn office_tileset() -> Vec<TileType> {
+        let (w, c) = ("wall", "carpet");
+        let all = &ALL_ROTATIONS;
+        vec![
+            TileType::new("carpet", "Carpet", 2.0, &[0], [c, c, c, c]),
+            TileType::new("wall", "Wall", 1.0, &[0], [w, w, w, w]),
+            // Desks stand with their backs to a wall
+            TileType::new("desk", "Desk", 0.3, all, [w, c, c, c]),
+            TileType::new("corner_desk", "Corner Desk", 0.2, all, [w, w, c, c]),
+            TileType::new("partition", "Partition", 0.2, &[0, 90], [c, w, c, w]),
+            TileType::new("wall_end", "Wall End", 0.2, all, [w, w, w, c]),
+        ]
+    }
+
+    fn scifi_tileset() -> Vec<TileType> {
+        let (h, d) = ("hull", "deck");
+        let all = &ALL_ROTATIONS;
+        vec![
+            TileType::new("metal_floor", "Metal Floor", 2.0, &[0], [d, d, d, d]),
+            TileType::new("hull_wall", "Hull Wall", 1.0, &[0], [h, h, h, h]),
+            // Consoles are mounted on the hull, facing the deck
+            TileType::new("console", "Control Console", 0.2, all, [h, d, d, d]),
+            TileType::new("hull_corner", "Hull Corner", 0.4, all, [h, h, d, d]),
+            TileType::new("airlock", "Airlock", 0.1, &[0, 90], [d, h, d, h]),
+            TileType::new("hull_end", "Hull End", 0.3, all, [h, h, h, d]),
+        ]
+    }
+}
+
+/// Tile types for the tiles in an overlapping-model result, taken from the tileset and
+/// its rotations or, for tiles it lacks, named and meshed after the theme of the same
+/// name.
+fn learned_tile_types(tile_map: &[Vec<String>], tileset: &str) -> Vec<TileType> {
+    let tiles = expand_rotations(&TilesetLibrary::get_tileset(tileset));
+    let theme = ThemeLibrary::current().get_theme(tileset);
+    let mut keys: Vec<&String> = tile_map.iter().flatten().collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .map(|key| {
+            if let Some(tile) = tiles.iter().find(|t| &t.id == key) {
+                return tile.clone();
+            }
+            let themed = theme.as_ref().and_then(|theme| theme.tiles.get(key));
+            TileType {
+                id: key.clone(),
+                name: themed.map_or_else(|| key.clone(), |t| t.name.clone()),
+                weight: 1.0,
+                rotations: vec![0],
+                mesh_type: themed.map_or_else(|| "cube".to_string(), |t| t.mesh.mesh_type.clone()),
+                sockets: Default::default(),
+                rotation: 0,
+            }
+        })
+        .collect()
+}
+
+/// The given seed, or one from the clock.
+fn seed_or_now(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    })
+}
+
+/// Shannon entropy of choosing between tiles with the given weights.
+fn shannon_entropy(weights: &[f64]) -> f64 {
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+    let weighted_logs: f64 = weights
+        .iter()
+        .filter(|&&w| w > 0.0)
+        .map(|w| w * w.ln())
+        .sum();
+    total.ln() - weighted_logs / total
+}
+
+/// Cells on the outside of a grid, with the direction of their outer edge.
+fn border_edges(width: usize, height: usize) -> impl Iterator<Item = (usize, usize, Direction)> {
+    let (w, h) = (width, height);
+    let rows = (0..w).flat_map(move |x| [(x, 0, Direction::North), (x, h - 1, Direction::South)]);
+    let columns = (0..h).flat_map(move |y| [(0, y, Direction::West), (w - 1, y, Direction::East)]);
+    rows.chain(columns)
+}
+
+/// Solves a large grid as overlapping chunks, each one keeping the cells it shares
+/// with chunks solved before it, and adds up their solving stats. Chunks are solved in waves of chunks that don't
+/// overlap each other, in parallel when the `parallel` feature is on.
+#[tracing::instrument(skip_all, fields(width = params.width, height = params.height))]
+fn solve_chunked(
+    tiles: &[TileType],
+    params: &WFCGenerationParams,
+    chunks: &ChunkParams,
+    seed: u64,
+) -> Result<(Vec<Vec<String>>, WFCStats)> {
+    let (width, height) = (params.width as usize, params.height as usize);
+    let (size, overlap) = (chunks.size as usize, chunks.overlap as usize);
+    if size < 2 || overlap * 2 >= size {
+        bail!("Chunks must be at least 2 cells, overlapping by less than half of that");
+    }
+    let step = size - overlap;
+    let count = |len: usize| {
+        if len <= size {
+            1
+        } else {
+            (len - overlap).div_ceil(step)
+        }
+    };
+    let (columns, rows) = (count(width), count(height));
+
+    let mut grid: Vec<Vec<Option<String>>> = vec![vec![None; width]; height];
+    let mut stats = WFCStats::default();
+    let solve_chunk = |(i, j): (usize, usize), grid: &[Vec<Option<String>>]| {
+        let (x0, y0) = (i * step, j * step);
+        let (w, h) = ((x0 + size).min(width) - x0, (y0 + size).min(height) - y0);
+        let inside = |x: u32, y: u32| {
+            let (x, y) = (x as usize, y as usize);
+            (x >= x0 && x < x0 + w && y >= y0 && y < y0 + h)
+                .then(|| ((x - x0) as u32, (y - y0) as u32))
+        };
+
+        let mut pinned = Vec::new();
+        for y in 0..h {
+            for x in 0..w {
+                if let Some(tile) = &grid[y0 + y][x0 + x] {
+                    pinned.push((x, y, tile.clone()));
+                }
+            }
+        }
+        let mut constraints = Vec::new();
+        for constraint in &params.constraints {
+            match constraint {
+                CellConstraint::Tile { x, y, tile } => {
+                    if let Some((x, y)) = inside(*x, *y) {
+                        let tile = tile.clone();
+                        constraints.push(CellConstraint::Tile { x, y, tile });
+                    }
+                }
+                CellConstraint::Socket {
+                    x,
+                    y,
+                    direction,
+                    socket,
+                } => {
+                    if let Some((x, y)) = inside(*x, *y) {
+                        let (direction, socket) = (*direction, socket.clone());
+                        constraints.push(CellConstraint::Socket {
+                            x,
+                            y,
+                            direction,
+                            socket,
+                        });
+                    }
+                }
+                // Only the edges of the chunk that are edges of the map
+                CellConstraint::Border { socket } => {
+                    for (x, y, direction) in border_edges(width, height) {
+                        if let Some((x, y)) = inside(x as u32, y as u32) {
+                            let socket = socket.clone();
+                            constraints.push(CellConstraint::Socket {
+                                x,
+                                y,
+                                direction,
+                                socket,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut generator = WFCGenerator::new();
+        let chunk_seed = seed ^ ((j as u64) << 32 | i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        generator.rng = StdRng::seed_from_u64(chunk_seed);
+        generator.heuristic = params.entropy;
+        generator.width = w;
+        generator.height = h;
+        generator
+            .solve(
+                tiles.to_vec(),
+                &pinned,
+                &constraints,
+                params.max_iterations,
+                params.backtrack_limit,
+            )
+            .map_err(|e| anyhow::anyhow!("Chunk ({}, {}) failed: {}", i, j, e))?;
+        Ok::<_, anyhow::Error>((x0, y0, generator.solved_tiles(), generator.stats))
+    };
+
+    // A chunk overlaps the ones left of it, above it and above to either side, all
+    // of which are in earlier waves
+    for wave in 0..columns + 2 * rows {
+        let chunks_in_wave: Vec<(usize, usize)> = (0..rows)
+            .filter_map(|j| Some((wave.checked_sub(2 * j)?, j)))
+            .filter(|&(i, _)| i < columns)
+            .collect();
+        let solved = map_chunks(chunks_in_wave, chunks.parallel, |chunk| {
+            solve_chunk(chunk, &grid)
+        })?;
+        for (x0, y0, tiles, chunk_stats) in solved {
+            stats.add(chunk_stats);
+            for (y, row) in tiles.into_iter().enumerate() {
+                for (x, tile) in row.into_iter().enumerate() {
+                    grid[y0 + y][x0 + x] = Some(tile);
+                }
+            }
+        }
+    }
+
+    let tile_map = grid
+        .into_iter()
+        .map(|row| row.into_iter().map(Option::unwrap_or_default).collect())
+        .collect();
+    Ok((tile_map, stats))
+}
+
+/// Runs `solve` on each chunk, on all cores when `parallel` is set.
+#[cfg(feature = "parallel")]
+fn map_chunks<T: Send>(
+    chunks: Vec<(usize, usize)>,
+    parallel: bool,
+    solve: impl Fn((usize, usize)) -> Result<T> + Sync + Send,
+) -> Result<Vec<T>> {
+    use rayon::prelude::*;
+    if parallel {
+        chunks.into_par_iter().map(solve).collect()
+    } else {
+        chunks.into_iter().map(solve).collect()
+    }
+}
+
+/// Runs `solve` on each chunk; without the `parallel` feature, one after another.
+#[cfg(not(feature = "parallel"))]
+fn map_chunks<T>(
+    chunks: Vec<(usize, usize)>,
+    _parallel: bool,
+    solve: impl Fn((usize, usize)) -> Result<T>,
+) -> Result<Vec<T>> {
+    chunks.into_iter().map(solve).collect()
+}
+
+/// What one step of a stepped solve did.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WFCStep {
+    /// `tile` was picked for cell (`x`, `y`) and propagated without trouble
+    Collapsed { x: usize, y: usize, tile: String },
+    /// Picking `tile` for (`x`, `y`) left the cell at `empty` with no tile, and the
+    /// last `undone` choices were taken back to recover
+    Contradiction {
+        x: usize,
+        y: usize,
+        tile: String,
+        empty: (usize, usize),
+        undone: u32,
+    },
+}
+
+/// The grid of a stepped solve.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WFCSnapshot {
+    pub seed: u64,
+    pub width: usize,
+    pub height: usize,
+    /// How many tiles each cell could still be, by row; 1 once collapsed
+    pub possibilities: Vec<Vec<usize>>,
+    /// The tile each cell collapsed to, by row
+    pub tiles: Vec<Vec<Option<String>>>,
+    pub last_step: Option<WFCStep>,
+    pub stats: WFCStats,
+    /// Every cell is collapsed
+    pub done: bool,
+    /// Why solving stopped early; stepping does nothing more after this
+    pub error: Option<String>,
+}
+
+/// A WFC solve run a step at a time, to watch the grid collapse.
+///
+/// Steps go exactly as [`WFCGenerator`] would solve the same parameters, and report
+/// where a tileset runs into contradictions. Only tileset solves can be stepped
+/// through, not the overlapping model or chunked maps.
+pub struct WFCDebugSession {
+    generator: WFCGenerator,
+    seed: u64,
+    max_iterations: u32,
+    backtrack_limit: u32,
+    last_step: Option<WFCStep>,
+    error: Option<String>,
+}
+
+impl WFCDebugSession {
+    /// Sets up the grid and applies the cell constraints, collapsing nothing yet.
+    pub fn start(params: &WFCGenerationParams) -> Result<Self> {
+        if params.overlapping.is_some() || params.chunks.is_some() {
+            bail!("Only tileset solves without overlapping or chunks can be stepped through");
+        }
+        let seed = seed_or_now(params.seed);
+        let mut generator = WFCGenerator::new();
+        generator.rng = StdRng::seed_from_u64(seed);
+        generator.heuristic = params.entropy;
+        generator.width = params.width as usize;
+        generator.height = params.height as usize;
+        let tiles = expand_rotations(&TilesetLibrary::get_tileset(&params.tileset));
+        generator.prepare(tiles, &[], &params.constraints)?;
+        generator.start_run();
+
+        Ok(Self {
+            generator,
+            seed,
+            max_iterations: params.max_iterations,
+            backtrack_limit: params.backtrack_limit,
+            last_step: None,
+            error: None,
+        })
+    }
+
+    /// Runs up to `steps` steps, stopping early once solved or stuck.
+    pub fn step(&mut self, steps: u32) -> WFCSnapshot {
+        for _ in 0..steps {
+            if self.error.is_some() {
+                break;
+            }
+            match self
+                .generator
+                .step(self.max_iterations, self.backtrack_limit)
+            {
+                Ok(Some(step)) => self.last_step = Some(step),
+                Ok(None) => break,
+                Err(e) => self.error = Some(e.to_string()),
+            }
+        }
+        self.snapshot()
+    }
+
+    #[must_use]
+    pub fn snapshot(&self) -> WFCSnapshot {
+        let grid = &self.generator.grid;
+        WFCSnapshot {
+            seed: self.seed,
+            width: self.generator.width,
+            height: self.generator.height,
+            possibilities: grid
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.possible_tiles.len()).collect())
+                .collect(),
+            tiles: grid
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.collapsed_tile.clone()).collect())
+                .collect(),
+            last_step: self.last_step.clone(),
+            stats: self.generator.stats,
+            done: grid.iter().flatten().all(|cell| cell.collapsed),
+            error: self.error.clone(),
+        }
+    }
+}
+
+/// Main WFC Generator
+pub struct WFCGenerator {
+    rng: StdRng,
+    tiles: Vec<TileType>,
+    constraints: HashMap<(String, Direction), HashSet<String>>,
+    grid: Vec<Vec<WFCCell>>,
+    width: usize,
+    height: usize,
+    /// Cells as they were before each change since solving started, newest last
+    trail: Vec<(usize, usize, WFCCell)>,
+    /// Choices made while solving that can still be undone, newest last
+    decisions: Vec<Decision>,
+    stats: WFCStats,
+    heuristic: EntropyHeuristic,
+    /// Tile weights by id, for the Shannon heuristic
+    weights: HashMap<String, f64>,
+}
+
+/// A cell collapsed while solving, and how long the trail was just before.
+struct Decision {
+    x: usize,
+    y: usize,
+    tile: String,
+    trail_len: usize,
+}
+
+/// How much work solving took, recorded in the level's generation parameters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WFCStats {
+    /// Cells collapsed by choosing a tile, counting ones later undone
+    pub observations: u32,
+    /// Times propagation left a cell with no tile
+    pub contradictions: u32,
+    /// Choices undone to recover from contradictions
+    pub backtracks: u32,
+    /// Most choices in effect at once
+    pub max_depth: u32,
+}
+
+impl WFCStats {
+    fn add(&mut self, other: WFCStats) {
+        self.observations += other.observations;
+        self.contradictions += other.contradictions;
+        self.backtracks += other.backtracks;
+        self.max_depth = self.max_depth.max(other.max_depth);
+    }
+}
+
+impl Default for WFCGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WFCGenerator {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(0),
+            tiles: Vec::new(),
+            constraints: HashMap::new(),
+            grid: Vec::new(),
+            width: 0,
+            height: 0,
+            trail: Vec::new(),
+            decisions: Vec::new(),
+            stats: WFCStats::default(),
+            heuristic: EntropyHeuristic::default(),
+            weights: HashMap::new(),
+        }
+    }
+
+    #[tracing::instrument(
+        name = "wfc_generate",
+        skip_all,
+        fields(width = params.width, height = params.height, tileset = %params.tileset)
+    )]
+    pub async fn generate(&mut self, params: WFCGenerationParams) -> Result<LevelData> {
+        let mut level_data = self.generate_tiles(params)?;
+        record_connectivity(&mut level_data)?;
+        Ok(level_data)
+    }
+
+    /// Runs WFC without checking the result is a connected level, for callers that
+    /// use the tiles as a pattern.
+    pub fn generate_tiles(&mut self, params: WFCGenerationParams) -> Result<LevelData> {
+        let seed = seed_or_now(params.seed);
+
+        self.rng = StdRng::seed_from_u64(seed);
+        self.stats = WFCStats::default();
+        self.heuristic = params.entropy;
+        self.width = params.width as usize;
+        self.height = params.height as usize;
+
+        if let Some(overlapping) = &params.overlapping {
+            if !params.constraints.is_empty() {
+                bail!("Cell constraints can't be used with the overlapping model");
+            }
+            let sample = overlapping.sample.tile_map()?;
+            let model = OverlappingModel::learn(&sample, overlapping.pattern_size as usize)?;
+            let tile_map = model.generate(
+                self.width,
+                self.height,
+                &mut self.rng,
+                params.backtrack_limit,
+            )?;
+            self.tiles = learned_tile_types(&tile_map, &params.tileset);
+            self.set_solved_grid(tile_map);
+            return self.create_level_data(seed, &params.tileset);
+        }
+
+        // Load tileset and constraints
+        let tiles = expand_rotations(&TilesetLibrary::get_tileset(&params.tileset));
+
+        if let Some(chunks) = &params.chunks {
+            let (tile_map, stats) = solve_chunked(&tiles, &params, chunks, seed)?;
+            self.tiles = tiles;
+            self.stats = stats;
+            self.set_solved_grid(tile_map);
+            return self.create_level_data(seed, &params.tileset);
+        }
+
+        // Run WFC algorithm
+        self.solve(
+            tiles,
+            &[],
+            &params.constraints,
+            params.max_iterations,
+            params.backtrack_limit,
+        )?;
+
+        // Convert to level data
+        self.create_level_data(seed, &params.tileset)
+    }
+
+    /// Solves a grid of the generator's size with `tiles`, after fixing the `pinned`
+    /// cells to exactly the tile given and applying `constraints`.
+    fn solve(
+        &mut self,
+        tiles: Vec<TileType>,
+        pinned: &[(usize, usize, String)],
+        constraints: &[CellConstraint],
+        max_iterations: u32,
+        backtrack_limit: u32,
+    ) -> Result<()> {
+        self.prepare(tiles, pinned, constraints)?;
+        self.run_wfc(max_iterations, backtrack_limit)
+    }
+
+    /// Sets up the grid for [`Self::solve`] without collapsing anything.
+    fn prepare(
+        &mut self,
+        tiles: Vec<TileType>,
+        pinned: &[(usize, usize, String)],
+        constraints: &[CellConstraint],
+    ) -> Result<()> {
+        self.setup_constraints(socket_constraints(&tiles));
+        self.weights = tiles
+            .iter()
+            .map(|t| (t.id.clone(), f64::from(t.weight)))
+            .collect();
+        self.tiles = tiles;
+
+        // Initialize grid
+        self.initialize_grid();
+        for (x, y, tile) in pinned {
+            self.grid[*y][*x].possible_tiles.retain(|t| t == tile);
+        }
+        let pinned: Vec<(usize, usize)> = pinned.iter().map(|(x, y, _)| (*x, *y)).collect();
+        self.apply_cell_constraints(constraints, pinned)
+    }
+
+    /// Replaces the grid with one collapsed to the given tiles, leaving empty cells
+    /// uncollapsed.
+    fn set_solved_grid(&mut self, tile_map: Vec<Vec<String>>) {
+        self.grid = tile_map
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|key| {
+                        let mut cell = WFCCell::new(HashSet::new());
+                        if key != EMPTY_TILE {
+                            cell.collapse(key);
+                        }
+                        cell
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    /// The collapsed tile of every cell.
+    fn solved_tiles(&self) -> Vec<Vec<String>> {
+        self.grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.collapsed_tile.clone().unwrap_or_default())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn setup_constraints(&mut self, constraint_rules: Vec<ConstraintRule>) {
+        self.constraints.clear();
+        for rule in constraint_rules {
+            let key = (rule.tile_id, rule.direction);
+            self.constraints.insert(key, rule.allowed_neighbors);
+        }
+    }
+
+    fn initialize_grid(&mut self) {
+        let all_tile_ids: HashSet<String> = self.tiles.iter().map(|t| t.id.clone()).collect();
+
+        self.grid = Vec::new();
+        for _y in 0..self.height {
+            let mut row = Vec::new();
+            for _x in 0..self.width {
+                row.push(WFCCell::new(all_tile_ids.clone()));
+            }
+            self.grid.push(row);
+        }
+    }
+
+    /// Narrows the cells named by `constraints` and spreads the effect to their
+    /// neighbours, and those of the already `narrowed` cells, collapsing cells left with
+    /// a single tile.
+    fn apply_cell_constraints(
+        &mut self,
+        constraints: &[CellConstraint],
+        mut narrowed: Vec<(usize, usize)>,
+    ) -> Result<()> {
+        for constraint in constraints {
+            let cells: Vec<(usize, usize, Option<Direction>)> = match constraint {
+                CellConstraint::Tile { x, y, .. } => vec![(*x as usize, *y as usize, None)],
+                CellConstraint::Socket {
+                    x, y, direction, ..
+                } => vec![(*x as usize, *y as usize, Some(*direction))],
+                CellConstraint::Border { .. } => border_edges(self.width, self.height)
+                    .map(|(x, y, direction)| (x, y, Some(direction)))
+                    .collect(),
+            };
+            for (x, y, direction) in cells {
+                if x >= self.width || y >= self.height {
+                    bail!("Constraint cell ({}, {}) is outside the grid", x, y);
+                }
+                let tiles = &self.tiles;
+                let keep = |id: &String| {
+                    let Some(tile) = tiles.iter().find(|t| &t.id == id) else {
+                        return false;
+                    };
+                    match (constraint, direction) {
+                        (CellConstraint::Tile { tile: wanted, .. }, _) => {
+                            &tile.id == wanted || tile.base_id() == wanted
+                        }
+                        (CellConstraint::Socket { socket, .. }, Some(direction))
+                        | (CellConstraint::Border { socket }, Some(direction)) => {
+                            tile.socket(direction) == socket
+                        }
+                        _ => true,
+                    }
+                };
+                self.grid[y][x].possible_tiles.retain(keep);
+                if self.grid[y][x].possible_tiles.is_empty() {
+                    bail!("No tile fits the constraints on cell ({}, {})", x, y);
+                }
+                narrowed.push((x, y));
+            }
+        }
+
+        for (x, y) in narrowed {
+            if let Err((x, y)) = self.propagate_constraints(x, y) {
+                bail!("Cell constraints leave no tile for cell ({}, {})", x, y);
+            }
+        }
+        for row in &mut self.grid {
+            for cell in row.iter_mut().filter(|cell| cell.possible_tiles.len() == 1) {
+                let tile = cell.possible_tiles.iter().next().cloned();
+                if let Some(tile) = tile {
+                    cell.collapse(tile);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Collapses the grid one cell at a time. On a contradiction the last choice is
+    /// undone along with everything propagation did after it, that tile is ruled out
+    /// for the cell, and solving carries on from there.
+    #[tracing::instrument(skip(self))]
+    fn run_wfc(&mut self, max_iterations: u32, backtrack_limit: u32) -> Result<()> {
+        self.start_run();
+        // Find cell with lowest entropy until all cells are collapsed
+        while self.step(max_iterations, backtrack_limit)?.is_some() {}
+        Ok(())
+    }
+
+    fn start_run(&mut self) {
+        self.trail.clear();
+        self.decisions.clear();
+        self.stats = WFCStats::default();
+    }
+
+    /// Collapses the lowest entropy cell and propagates, backtracking if that leads
+    /// to a contradiction. `None` once every cell is collapsed.
+    fn step(&mut self, max_iterations: u32, backtrack_limit: u32) -> Result<Option<WFCStep>> {
+        let Some((x, y)) = self.find_lowest_entropy_cell() else {
+            return Ok(None);
+        };
+        if self.stats.observations >= max_iterations {
+            bail!("WFC failed: max iterations exceeded");
+        }
+        self.stats.observations += 1;
+
+        let Some(tile) = self.choose_tile_for_cell(x, y) else {
+            bail!("WFC failed: no valid tiles");
+        };
+        self.decisions.push(Decision {
+            x,
+            y,
+            tile: tile.clone(),
+            trail_len: self.trail.len(),
+        });
+        self.stats.max_depth = self.stats.max_depth.max(self.decisions.len() as u32);
+        self.trail.push((x, y, self.grid[y][x].clone()));
+        self.grid[y][x].collapse(tile.clone());
+        let Err(empty) = self.propagate_constraints(x, y) else {
+            return Ok(Some(WFCStep::Collapsed { x, y, tile }));
+        };
+
+        // Constraint violation - undo choices until one can be ruled out cleanly
+        self.stats.contradictions += 1;
+        let mut undone = 0;
+        loop {
+            let Some(decision) = self.decisions.pop() else {
+                bail!("WFC failed: no tiling satisfies the constraints");
+            };
+            if self.stats.backtracks >= backtrack_limit {
+                bail!("WFC failed: too many backtracks");
+            }
+            self.stats.backtracks += 1;
+            undone += 1;
+            self.undo_to(decision.trail_len);
+
+            let (x, y) = (decision.x, decision.y);
+            self.trail.push((x, y, self.grid[y][x].clone()));
+            self.grid[y][x].possible_tiles.remove(&decision.tile);
+            if !self.grid[y][x].possible_tiles.is_empty()
+                && self.propagate_constraints(x, y).is_ok()
+            {
+                break;
+            }
+        }
+        Ok(Some(WFCStep::Contradiction {
+            x,
+            y,
+            tile,
+            empty,
+            undone,
+        }))
+    }
+
+    fn find_lowest_entropy_cell(&mut self) -> Option<(usize, usize)> {
+        match self.heuristic {
+            EntropyHeuristic::Count => self.fewest_tiles_cell(),
+            EntropyHeuristic::Shannon => self.lowest_shannon_cell(),
+            EntropyHeuristic::Scanline => (0..self.height)
+                .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+                .find(|&(x, y)| self.grid[y][x].entropy() > 0),
+        }
+    }
+
+    fn fewest_tiles_cell(&mut self) -> Option<(usize, usize)> {
+        let mut min_entropy = usize::MAX;
+        let mut candidates = Vec::new();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let cell = &self.grid[y][x];
+                if !cell.collapsed {
+                    let entropy = cell.entropy();
+                    if entropy > 0 && entropy < min_entropy {
+                        min_entropy = entropy;
+                        candidates.clear();
+                        candidates.push((x, y));
+                    } else if entropy == min_entropy {
+                        candidates.push((x, y));
+                    }
+                }
+            }
+        }
+
+        if candidates.is_empty() {
+            None
+        } else {
+            let idx = self.rng.gen_range(0..candidates.len());
+            Some(candidates[idx])
+        }
+    }
+
+    fn lowest_shannon_cell(&mut self) -> Option<(usize, usize)> {
+        let mut min_entropy = f64::MAX;
+        let mut lowest = None;
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let cell = &self.grid[y][x];
+                if cell.entropy() == 0 {
+                    continue;
+                }
+                // Sum in a fixed order so a seed always picks the same cell
+                let mut weights: Vec<f64> = cell
+                    .possible_tiles
+                    .iter()
+                    .map(|id| self.weights.get(id).copied().unwrap_or(1.0))
+                    .collect();
+                weights.sort_by(f64::total_cmp);
+                let entropy = self
+                    .rng
+                    .gen::<f64>()
+                    .mul_add(1e-6, shannon_entropy(&weights));
+                if entropy < min_entropy {
+                    min_entropy = entropy;
+                    lowest = Some((x, y));
+                }
+            }
+        }
+
+        lowest
+    }
+
+    fn choose_tile_for_cell(&mut self, x: usize, y: usize) -> Option<String> {
+        let cell = &self.grid[y][x];
+        if cell.possible_tiles.is_empty() {
+            return None;
+        }
+
+        // Weight-based selection
+        let mut weighted_tiles = Vec::new();
+        for tile_id in &cell.possible_tiles {
+            if let Some(tile) = self.tiles.iter().find(|t| &t.id == tile_id) {
+                weighted_tiles.push((tile_id.clone(), tile.weight));
+            }
+        }
+
+        if weighted_tiles.is_empty() {
+            return None;
+        }
+        // Sets iterate in any order; sort so a seed always picks the same tile
+        weighted_tiles.sort_by(|a, b| a.0.cmp(&b.0));
+
+        // Simple weighted random selection
+        let total_weight: f32 = weighted_tiles.iter().map(|(_, w)| w).sum();
+        let mut random_value = self.rng.gen::<f32>() * total_weight;
+
+        for (tile_id, weight) in &weighted_tiles {
+            random_value -= weight;
+            if random_value <= 0.0 {
+                return Some(tile_id.clone());
+            }
+        }
+
+        // Fallback to first tile
+        Some(weighted_tiles[0].0.clone())
+    }
+
+    /// Narrows the cells around (`start_x`, `start_y`) to tiles their neighbours allow,
+    /// failing with the first cell left with no tile.
+    fn propagate_constraints(
+        &mut self,
+        start_x: usize,
+        start_y: usize,
+    ) -> Result<(), (usize, usize)> {
+        let mut queue = VecDeque::new();
+        queue.push_back((start_x, start_y));
+
+        while let Some((x, y)) = queue.pop_front() {
+            let current_tiles: Vec<String> =
+                self.grid[y][x].possible_tiles.iter().cloned().collect();
+
+            // Check all neighbors
+            for direction in Direction::all() {
+                let Some((nx, ny)) = self.get_neighbor_coords(x, y, direction) else {
+                    continue;
+                };
+                let neighbor_cell = &self.grid[ny][nx];
+                if neighbor_cell.collapsed {
+                    continue;
+                }
+
+                // Neighbours any of this cell's remaining tiles allow; a tile without
+                // rules allows anything
+                let mut allowed = HashSet::new();
+                for tile in &current_tiles {
+                    let key = (tile.clone(), direction);
+                    let Some(tiles) = self.constraints.get(&key) else {
+                        allowed.clone_from(&neighbor_cell.possible_tiles);
+                        break;
+                    };
+                    allowed.extend(tiles.iter().cloned());
+                }
+                if neighbor_cell
+                    .possible_tiles
+                    .iter()
+                    .all(|t| allowed.contains(t))
+                {
+                    continue;
+                }
+
+                // Remove tiles that are not allowed, remembering the cell as it was
+                self.trail.push((nx, ny, neighbor_cell.clone()));
+                let neighbor_cell = &mut self.grid[ny][nx];
+                neighbor_cell.possible_tiles.retain(|t| allowed.contains(t));
+                if neighbor_cell.possible_tiles.is_empty() {
+                    return Err((nx, ny)); // Constraint violation
+                }
+                queue.push_back((nx, ny));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_neighbor_coords(
+        &self,
+        x: usize,
+        y: usize,
+        direction: Direction,
+    ) -> Option<(usize, usize)> {
+        match direction {
+            Direction::North => {
+                if y > 0 {
+                    Some((x, y - 1))
+                } else {
+                    None
+                }
+            }
+            Direction::South => {
+                if y < self.height - 1 {
+                    Some((x, y + 1))
+                } else {
+                    None
+                }
+            }
+            Direction::West => {
+                if x > 0 {
+                    Some((x - 1, y))
+                } else {
+                    None
+                }
+            }
+            Direction::East => {
+                if x < self.width - 1 {
+                    Some((x + 1, y))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Puts back every cell changed since the trail was `len` entries long.
+    fn undo_to(&mut self, len: usize) {
+        while self.trail.len() > len {
+            if let Some((x, y, cell)) = self.trail.pop() {
+                self.grid[y][x] = cell;
+            }
+        }
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn create_level_data(&self, seed: u64, tileset: &str) -> Result<LevelData> {
+        let mut objects = Vec::new();
+        let theme = ThemeLibrary::current().get_theme(tileset);
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if let Some(ref tile_id) = self.grid[y][x].collapsed_tile {
+                    if let Some(tile) = self.tiles.iter().find(|t| &t.id == tile_id) {
+                        // Clockwise seen from above is a negative turn about +Y
+                        let half_yaw = -(tile.rotation as f32).to_radians() * 0.5;
+                        let cell = [x as i64, y as i64];
+                        let variant = theme.as_ref().and_then(|theme| {
+                            let roll = generated_roll(seed, "wfc_variant", &cell);
+                            theme.mesh_variant(tile.base_id(), roll)
+                        });
+                        let object = GameObject {
+                            id: generated_id(seed, "wfc_tile", &cell),
+                            name: format!("{}_{}_{}_{}", tileset, tile.name, x, y),
+                            transform: Transform3D {
+                                position: [x as f32, 0.0, y as f32],
+                                rotation: [0.0, half_yaw.sin(), 0.0, half_yaw.cos()],
+                                scale: [1.0, 1.0, 1.0],
+                            },
+                            material: Some(format!("{}_{}", tileset, tile.base_id())),
+                            mesh: Some(variant.unwrap_or(&tile.mesh_type).to_string()),
+                            layer: "Generated".to_string(),
+                            tags: vec!["wfc".to_string(), tileset.to_string()],
+                            metadata: {
+                                let mut map = HashMap::new();
+                                map.insert(
+                                    "tile_type".to_string(),
+                                    serde_json::Value::String(tile.base_id().to_string()),
+                                );
+                                if tile.rotation != 0 {
+                                    map.insert("rotation".to_string(), tile.rotation.into());
+                                }
+                                map.insert(
+                                    "algorithm".to_string(),
+                                    serde_json::Value::String("WFC".to_string()),
+                                );
+                                map
+                            },
+                            kind: ObjectKind::Mesh,
+                            physics: None,
+                        };
+                        objects.push(object);
+                    }
+                }
+            }
+        }
+
+        Ok(LevelData {
+            id: generated_id(
+                seed,
+                &format!("wfc_level/{}", tileset),
+                &[self.width as i64, self.height as i64],
+            ),
+            name: format!("WFC Level {} ({})", seed, tileset),
+            objects,
+            layers: vec!["Generated".to_string()],
+            generation_seed: Some(seed),
+            generation_params: Some(serde_json::json!({
+                "algorithm": "wfc",
+                "tileset": tileset,
+                "width": self.width,
+                "height": self.height,
+                "stats": self.stats,
+            })),
+            bounds: crate::spatial::BoundingBox {
+                min: [0.0, 0.0, 0.0],
+                max: [self.width as f32, 1.0, self.height as f32],
+            },
+            annotations: Vec::new(),
+            camera_bookmarks: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::overlapping::WFCSample;
+
+    #[test]
+    fn test_wfc_generation() {
+        tokio_test::block_on(async {
+            let params = WFCGenerationParams::default();
+            let mut generator = WFCGenerator::new();
+
+            let result = generator.generate(params).await;
+            assert!(result.is_ok());
+
+            let level_data = result.unwrap();
+            assert!(!level_data.objects.is_empty());
+        });
+    }
+
+    #[test]
+    fn learns_from_a_sample_level() {
+        let mut sample = WFCGenerator::new();
+        let sample = sample
+            .generate_tiles(WFCGenerationParams {
+                width: 16,
+                height: 16,
+                seed: Some(2),
+                ..WFCGenerationParams::default()
+            })
+            .unwrap();
+        let params = WFCGenerationParams {
+            width: 20,
+            height: 16,
+            seed: Some(5),
+            overlapping: Some(OverlappingParams {
+                sample: WFCSample::Level {
+                    level: Box::new(sample.clone()),
+                    floor: 0,
+                },
+                pattern_size: 2,
+            }),
+            ..WFCGenerationParams::default()
+        };
+        let level = WFCGenerator::new().generate_tiles(params).unwrap();
+        assert_eq!(level.objects.len(), 20 * 16);
+
+        let tile_type = |obj: &GameObject| obj.metadata["tile_type"].as_str().unwrap().to_string();
+        let learned: HashSet<String> = sample.objects.iter().map(tile_type).collect();
+        assert!(level
+            .objects
+            .iter()
+            .all(|obj| learned.contains(&tile_type(obj))));
+    }
+
+    #[test]
+    fn test_tileset_loading() {
+        let tiles = TilesetLibrary::get_tileset("dungeon");
+        assert!(!tiles.is_empty());
+        assert!(!socket_constraints(&tiles).is_empty());
+    }
+
+    #[test]
+    fn rotations_turn_sockets_clockwise() {
+        let corner = TilesetLibrary::get_tileset("dungeon")
+            .into_iter()
+            .find(|t| t.id == "corner")
+            .unwrap();
+        let variants = expand_rotations(std::slice::from_ref(&corner));
+        assert_eq!(variants.len(), 4);
+        let turned = &variants[1];
+        assert_eq!(turned.id, "corner_r90");
+        assert_eq!(turned.base_id(), "corner");
+        // The north and east stone edges now face east and south
+        assert_eq!(turned.sockets, ["open", "stone", "stone", "open"]);
+    }
+
+    #[test]
+    fn rotated_tiles_are_turned_in_the_level() {
+        let level = WFCGenerator::new()
+            .generate_tiles(WFCGenerationParams {
+                seed: Some(6),
+                ..WFCGenerationParams::default()
+            })
+            .unwrap();
+        let mut turned = 0;
+        for obj in &level.objects {
+            let degrees = obj.metadata.get("rotation").and_then(|r| r.as_u64());
+            let half_yaw = -(degrees.unwrap_or(0) as f32).to_radians() * 0.5;
+            let [_, y, _, w] = obj.transform.rotation;
+            assert!((y - half_yaw.sin()).abs() < 1e-5 && (w - half_yaw.cos()).abs() < 1e-5);
+            assert!(!obj.metadata["tile_type"].as_str().unwrap().contains("_r"));
+            turned += usize::from(degrees.is_some());
+        }
+        assert!(turned > 0);
+    }
+
+    #[test]
+    fn fixed_cells_are_kept() {
+        let mut generator = WFCGenerator::new();
+        let level = generator
+            .generate_tiles(WFCGenerationParams {
+                width: 12,
+                height: 10,
+                seed: Some(3),
+                constraints: vec![
+                    CellConstraint::Border {
+                        socket: "stone".to_string(),
+                    },
+                    CellConstraint::Tile {
+                        x: 5,
+                        y: 0,
+                        tile: "door_r90".to_string(),
+                    },
+                    CellConstraint::Tile {
+                        x: 6,
+                        y: 5,
+                        tile: "floor".to_string(),
+                    },
+                ],
+                ..WFCGenerationParams::default()
+            })
+            .unwrap();
+        assert_eq!(level.objects.len(), 12 * 10);
+
+        let tile = |x: usize, y: usize| {
+            let id = generator.grid[y][x].collapsed_tile.as_ref().unwrap();
+            generator.tiles.iter().find(|t| &t.id == id).unwrap()
+        };
+        assert_eq!(tile(5, 0).id, "door_r90");
+        assert_eq!(tile(6, 5).id, "floor");
+        for (x, y, direction) in border_edges(12, 10) {
+            assert_eq!(tile(x, y).socket(direction), "stone");
+        }
+
+        let impossible = WFCGenerationParams {
+            constraints: vec![CellConstraint::Tile {
+                x: 40,
+                y: 0,
+                tile: "wall".to_string(),
+            }],
+            ..WFCGenerationParams::default()
+        };
+        assert!(WFCGenerator::new().generate_tiles(impossible).is_err());
+    }
+
+    #[test]
+    fn chunks_meet_at_matching_seams() {
+        let params = |parallel| WFCGenerationParams {
+            width: 80,
+            height: 70,
+            seed: Some(12),
+            constraints: vec![CellConstraint::Border {
+                socket: "stone".to_string(),
+            }],
+            chunks: Some(ChunkParams {
+                size: 24,
+                overlap: 2,
+                parallel,
+            }),
+            ..WFCGenerationParams::default()
+        };
+        let mut generator = WFCGenerator::new();
+        let level = generator.generate_tiles(params(true)).unwrap();
+        assert_eq!(level.objects.len(), 80 * 70);
+
+        let tile = |x: usize, y: usize| {
+            let id = generator.grid[y][x].collapsed_tile.as_ref().unwrap();
+            generator.tiles.iter().find(|t| &t.id == id).unwrap()
+        };
+        for y in 0..70 {
+            for x in 0..80 {
+                if x + 1 < 80 {
+                    assert_eq!(
+                        tile(x, y).socket(Direction::East),
+                        tile(x + 1, y).socket(Direction::West)
+                    );
+                }
+                if y + 1 < 70 {
+                    assert_eq!(
+                        tile(x, y).socket(Direction::South),
+                        tile(x, y + 1).socket(Direction::North)
+                    );
+                }
+            }
+        }
+        for (x, y, direction) in border_edges(80, 70) {
+            assert_eq!(tile(x, y).socket(direction), "stone");
+        }
+
+        // Chunk seeds don't depend on the order chunks are solved in
+        let sequential = WFCGenerator::new().generate_tiles(params(false)).unwrap();
+        assert_eq!(
+            serde_json::to_value(&level.objects).unwrap(),
+            serde_json::to_value(&sequential.objects).unwrap()
+        );
+    }
+
+    #[test]
+    fn backtracks_out_of_dead_ends() {
+        let tiles = vec![
+            TileType::new("floor", "Floor", 1.0, &[0], ["o", "o", "o", "o"]),
+            // Nothing fits east of a trap, so one anywhere but the east edge is a dead end
+            TileType::new("trap", "Trap", 20.0, &[0], ["o", "z", "o", "o"]),
+        ];
+        let mut generator = WFCGenerator::new();
+        generator.rng = StdRng::seed_from_u64(1);
+        generator.width = 8;
+        generator.height = 6;
+        generator.solve(tiles, &[], &[], 10_000, 1_000).unwrap();
+
+        assert!(generator.stats.contradictions > 0);
+        assert!(generator.stats.backtracks >= generator.stats.contradictions);
+        for (y, row) in generator.grid.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if cell.collapsed_tile.as_deref() == Some("trap") {
+                    assert_eq!(x, 7, "trap left at ({}, {})", x, y);
+                }
+            }
+        }
+
+        let level = generator.create_level_data(1, "test").unwrap();
+        let stats = &level.generation_params.unwrap()["stats"];
+        assert_eq!(stats["backtracks"], generator.stats.backtracks);
+    }
+
+    #[test]
+    fn neighbours_share_sockets() {
+        for tileset in ["dungeon", "office", "scifi"] {
+            let mut generator = WFCGenerator::new();
+            let level = generator
+                .generate_tiles(WFCGenerationParams {
+                    tileset: tileset.to_string(),
+                    seed: Some(9),
+                    ..WFCGenerationParams::default()
+                })
+                .unwrap();
+            assert_eq!(level.objects.len(), 24 * 24);
+
+            let tile = |x: usize, y: usize| {
+                let id = generator.grid[y][x].collapsed_tile.as_ref().unwrap();
+                generator.tiles.iter().find(|t| &t.id == id).unwrap()
+            };
+            for y in 0..24 {
+                for x in 0..24 {
+                    if x + 1 < 24 {
+                        assert_eq!(
+                            tile(x, y).socket(Direction::East),
+                            tile(x + 1, y).socket(Direction::West)
+                        );
+                    }
+                    if y + 1 < 24 {
+                        assert_eq!(
+                            tile(x, y).socket(Direction::South),
+                            tile(x, y + 1).socket(Direction::North)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn weighted_entropy_favours_skewed_cells() {
+        assert!(shannon_entropy(&[1.0]).abs() < 1e-9);
+        assert!((shannon_entropy(&[1.0; 4]) - 4f64.ln()).abs() < 1e-9);
+        // Two tiles, but one is almost always picked
+        assert!(shannon_entropy(&[1.0, 99.0]) < shannon_entropy(&[1.0, 1.0]));
+
+        for entropy in [
+            EntropyHeuristic::Count,
+            EntropyHeuristic::Shannon,
+            EntropyHeuristic::Scanline,
+        ] {
+            let params = || WFCGenerationParams {
+                seed: Some(4),
+                entropy,
+                ..WFCGenerationParams::default()
+            };
+            let mut generator = WFCGenerator::new();
+            let level = generator.generate_tiles(params()).unwrap();
+            assert_eq!(level.objects.len(), 24 * 24);
+            let again = WFCGenerator::new().generate_tiles(params()).unwrap();
+            assert_eq!(
+                serde_json::to_value(&level.objects).unwrap(),
+                serde_json::to_value(&again.objects).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn stepping_solves_like_the_generator() {
+        let params = WFCGenerationParams {
+            width: 12,
+            height: 10,
+            seed: Some(6),
+            ..WFCGenerationParams::default()
+        };
+        let mut session = WFCDebugSession::start(&params).unwrap();
+        let start = session.snapshot();
+        assert!(!start.done && start.last_step.is_none());
+        assert_eq!((start.width, start.height), (12, 10));
+
+        let first = session.step(1);
+        assert_eq!(first.stats.observations, 1);
+        let Some(WFCStep::Collapsed { x, y, tile }) = first.last_step else {
+            panic!("first step should collapse a cell");
+        };
+        assert_eq!(first.tiles[y][x].as_deref(), Some(tile.as_str()));
+        assert_eq!(first.possibilities[y][x], 1);
+
+        let last = session.step(u32::MAX);
+        assert!(last.done && last.error.is_none());
+        let mut generator = WFCGenerator::new();
+        generator.generate_tiles(params).unwrap();
+        let solved: Vec<Vec<Option<String>>> = generator
+            .solved_tiles()
+            .into_iter()
+            .map(|row| row.into_iter().map(Some).collect())
+            .collect();
+        assert_eq!(last.tiles, solved);
+        assert_eq!(last.stats, generator.stats);
+    }
+}
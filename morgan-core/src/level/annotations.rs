@@ -0,0 +1,64 @@
+// Review annotations attached to objects or free positions in a level
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Where an annotation is pinned in the level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AnnotationAnchor {
+    /// Attached to a game object; follows the object when it moves
+    Object { object_id: String },
+    /// Pinned to a fixed world-space position
+    Position { position: [f32; 3] },
+}
+
+impl AnnotationAnchor {
+    /// Objects the annotation is attached to, for change notifications.
+    #[must_use]
+    pub fn object_ids(&self) -> Vec<String> {
+        match self {
+            AnnotationAnchor::Object { object_id } => vec![object_id.clone()],
+            AnnotationAnchor::Position { .. } => Vec::new(),
+        }
+    }
+}
+
+/// A reply in an annotation's review thread.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnotationReply {
+    pub author: String,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A review note stored with the level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: String,
+    pub anchor: AnnotationAnchor,
+    pub author: String,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub resolved: bool,
+    #[serde(default)]
+    pub replies: Vec<AnnotationReply>,
+}
+
+impl Annotation {
+    #[must_use]
+    pub fn new(anchor: AnnotationAnchor, author: &str, text: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            anchor,
+            author: author.to_string(),
+            text: text.to_string(),
+            created_at: now,
+            updated_at: now,
+            resolved: false,
+            replies: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+// Named camera viewpoints saved with the level
+use serde::{Deserialize, Serialize};
+
+/// Orthographic projection settings for top-down and elevation views.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrthographicSettings {
+    /// Visible height of the view volume in world units
+    pub size: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+/// A named viewpoint such as "boss room overview".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraBookmark {
+    /// Unique name of the bookmark within the level
+    pub name: String,
+    /// Camera position in world units
+    pub position: [f32; 3],
+    /// Camera orientation as quaternion [x, y, z, w]
+    pub rotation: [f32; 4],
+    /// Optional orbit target the camera looks at
+    #[serde(default)]
+    pub target: Option<[f32; 3]>,
+    /// Vertical field of view in degrees for perspective cameras
+    #[serde(default)]
+    pub fov: Option<f32>,
+    /// Present when the bookmark uses an orthographic projection
+    #[serde(default)]
+    pub orthographic: Option<OrthographicSettings>,
+}
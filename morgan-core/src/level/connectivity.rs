@@ -0,0 +1,337 @@
+// Reachability of a level's walkable tiles, across floors joined by stairs
+use crate::{GameObject, LevelData};
+use anyhow::Result;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Key under which generators store their output's [`ConnectivityReport`] in
+/// `generation_params`.
+pub const CONNECTIVITY_KEY: &str = "connectivity";
+
+/// Classification of a single grid cell for walkability analysis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum CellKind {
+    Room,
+    Corridor,
+    Door,
+    /// Stairs leading up to the same cell on another floor
+    Stairs,
+    Blocked,
+}
+
+impl CellKind {
+    #[must_use]
+    pub fn is_walkable(self) -> bool {
+        self != CellKind::Blocked
+    }
+
+    /// Classify an object by its tags and WFC `tile_type` metadata.
+    pub fn from_object(obj: &GameObject) -> Option<CellKind> {
+        let mut keys: Vec<&str> = obj.tags.iter().map(String::as_str).collect();
+        if let Some(tile_type) = obj.metadata.get("tile_type").and_then(|v| v.as_str()) {
+            keys.push(tile_type);
+        }
+
+        if keys.iter().any(|k| k.contains("wall") || *k == "collision") {
+            Some(CellKind::Blocked)
+        } else if keys.iter().any(|k| k.contains("stairs")) {
+            Some(CellKind::Stairs)
+        } else if keys.iter().any(|k| k.contains("door")) {
+            Some(CellKind::Door)
+        } else if keys.iter().any(|k| k.contains("corridor")) {
+            Some(CellKind::Corridor)
+        } else if keys
+            .iter()
+            .any(|k| k.contains("floor") || *k == "carpet" || *k == "ground")
+        {
+            Some(CellKind::Room)
+        } else {
+            None
+        }
+    }
+}
+
+/// Converts a world position to the integer grid cell it occupies.
+#[must_use]
+pub fn world_to_cell(position: [f32; 3]) -> (i32, i32) {
+    (position[0].round() as i32, position[2].round() as i32)
+}
+
+/// Floor an object is on, from the `floor` metadata multi-floor generators add.
+pub fn floor_of(obj: &GameObject) -> i32 {
+    obj.metadata
+        .get("floor")
+        .and_then(serde_json::Value::as_i64)
+        .and_then(|floor| i32::try_from(floor).ok())
+        .unwrap_or(0)
+}
+
+/// A grid cell on one floor: `(floor, x, z)`.
+pub type Cell = (i32, i32, i32);
+
+/// The cell an object stands in.
+#[must_use]
+pub fn cell_of(obj: &GameObject) -> Cell {
+    let (x, z) = world_to_cell(obj.transform.position);
+    (floor_of(obj), x, z)
+}
+
+/// Cells connected to each other by walking, on one or more floors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalkableRegion {
+    pub floors: Vec<i32>,
+    pub cell_count: usize,
+    /// Smallest and largest X and Z of the region's cells
+    pub min: [i32; 2],
+    pub max: [i32; 2],
+}
+
+/// Result of checking that every walkable tile can be reached from every other.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectivityReport {
+    pub connected: bool,
+    pub walkable_cells: usize,
+    /// Regions that can't reach each other, largest first. The first is the level's
+    /// main area and the rest are isolated from it.
+    pub regions: Vec<WalkableRegion>,
+    /// Rooms outside the main area
+    pub unreachable_rooms: Vec<WalkableRegion>,
+}
+
+impl ConnectivityReport {
+    /// The report a generator stored with `level`, if it stored one.
+    #[must_use]
+    pub fn from_level(level: &LevelData) -> Option<ConnectivityReport> {
+        let report = level.generation_params.as_ref()?.get(CONNECTIVITY_KEY)?;
+        serde_json::from_value(report.clone()).ok()
+    }
+}
+
+fn region_of(cells: &[Cell]) -> WalkableRegion {
+    let mut floors: Vec<i32> = cells.iter().map(|c| c.0).collect();
+    floors.sort_unstable();
+    floors.dedup();
+    WalkableRegion {
+        floors,
+        cell_count: cells.len(),
+        min: [
+            cells.iter().map(|c| c.1).min().unwrap_or(0),
+            cells.iter().map(|c| c.2).min().unwrap_or(0),
+        ],
+        max: [
+            cells.iter().map(|c| c.1).max().unwrap_or(0),
+            cells.iter().map(|c| c.2).max().unwrap_or(0),
+        ],
+    }
+}
+
+/// What each tile's cell holds, and the stairs joining cells on different floors.
+#[derive(Debug, Clone, Default)]
+pub struct CellMap {
+    pub cells: HashMap<Cell, CellKind>,
+    /// Cells joined though not side by side, such as the two ends of a flight of stairs
+    pub links: HashMap<Cell, Vec<Cell>>,
+}
+
+impl CellMap {
+    /// Maps each tile to its cell. Stairs connect their cell to the same cell on the
+    /// `to_floor` named in their metadata.
+    #[must_use]
+    pub fn from_level(level: &LevelData) -> Self {
+        let mut map = Self::default();
+        for obj in &level.objects {
+            let Some(kind) = CellKind::from_object(obj) else {
+                continue;
+            };
+            let cell = cell_of(obj);
+            let (_, x, z) = cell;
+            // Blocking geometry wins over anything walkable in the same cell
+            let entry = map.cells.entry(cell).or_insert(kind);
+            if kind > *entry {
+                *entry = kind;
+            }
+
+            let to_floor = obj
+                .metadata
+                .get("to_floor")
+                .and_then(serde_json::Value::as_i64)
+                .and_then(|floor| i32::try_from(floor).ok());
+            if let (CellKind::Stairs, Some(to_floor)) = (kind, to_floor) {
+                let top = (to_floor, x, z);
+                map.links.entry(cell).or_default().push(top);
+                map.links.entry(top).or_default().push(cell);
+            }
+        }
+        map
+    }
+
+    #[must_use]
+    pub fn get(&self, cell: Cell) -> Option<CellKind> {
+        self.cells.get(&cell).copied()
+    }
+
+    #[must_use]
+    pub fn is_walkable(&self, cell: Cell) -> bool {
+        self.get(cell).is_some_and(CellKind::is_walkable)
+    }
+
+    /// The cells a step from `cell` can reach: the four beside it on its floor, and any
+    /// it's linked to.
+    fn steps(&self, cell: Cell) -> impl Iterator<Item = Cell> + '_ {
+        let (floor, x, z) = cell;
+        let beside = [
+            (floor, x, z - 1),
+            (floor, x + 1, z),
+            (floor, x, z + 1),
+            (floor, x - 1, z),
+        ];
+        let linked = self.links.get(&cell).into_iter().flatten().copied();
+        beside.into_iter().chain(linked)
+    }
+
+    /// Connected groups of the cells `filter` accepts, largest first.
+    pub fn regions(&self, filter: impl Fn(CellKind) -> bool) -> Vec<Vec<Cell>> {
+        let accepted = |cell: &Cell| self.get(*cell).is_some_and(&filter);
+        let mut starts: Vec<Cell> = self.cells.keys().copied().filter(accepted).collect();
+        // Sort for deterministic region ordering
+        starts.sort_unstable();
+
+        let mut visited = HashSet::new();
+        let mut regions = Vec::new();
+        for start in starts {
+            if !visited.insert(start) {
+                continue;
+            }
+            let mut region = vec![start];
+            let mut queue = VecDeque::from([start]);
+            while let Some(cell) = queue.pop_front() {
+                for next in self.steps(cell) {
+                    if accepted(&next) && visited.insert(next) {
+                        region.push(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            regions.push(region);
+        }
+
+        regions.sort_by_key(|r| std::cmp::Reverse(r.len()));
+        regions
+    }
+
+    /// Number of steps from `start` to each walkable cell reachable from it, taking
+    /// stairs between floors.
+    #[must_use]
+    pub fn distances_from(&self, start: Cell) -> HashMap<Cell, usize> {
+        let mut distances = HashMap::new();
+        if !self.is_walkable(start) {
+            return distances;
+        }
+        distances.insert(start, 0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(cell) = queue.pop_front() {
+            let distance = distances[&cell];
+            for next in self.steps(cell) {
+                if self.is_walkable(next) && !distances.contains_key(&next) {
+                    distances.insert(next, distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+}
+
+/// Number of steps from `start` to each walkable cell reachable from it, taking stairs
+/// between floors.
+#[must_use]
+pub fn walking_distances(level: &LevelData, start: Cell) -> HashMap<Cell, usize> {
+    CellMap::from_level(level).distances_from(start)
+}
+
+/// Flood-fills the level's walkable tiles and reports regions that can't be reached
+/// from the main one.
+pub fn check_connectivity(level: &LevelData) -> ConnectivityReport {
+    let map = CellMap::from_level(level);
+    let regions = map.regions(CellKind::is_walkable);
+    let main: HashSet<Cell> = regions.first().into_iter().flatten().copied().collect();
+    // Stairs aren't rooms, so rooms never reach past their own floor
+    let unreachable_rooms = map
+        .regions(|kind| kind == CellKind::Room)
+        .iter()
+        .filter(|room| !main.contains(&room[0]))
+        .map(|room| region_of(room))
+        .collect();
+
+    ConnectivityReport {
+        connected: regions.len() <= 1,
+        walkable_cells: regions.iter().map(Vec::len).sum(),
+        regions: regions.iter().map(|region| region_of(region)).collect(),
+        unreachable_rooms,
+    }
+}
+
+/// The check generators run on their output, logging a warning for a level with parts
+/// that can't be reached and storing the report under [`CONNECTIVITY_KEY`].
+#[tracing::instrument(skip_all)]
+pub fn record_connectivity(level: &mut LevelData) -> Result<ConnectivityReport> {
+    let report = check_connectivity(level);
+    if !report.connected {
+        warn!(
+            "Level {} has {} isolated regions and {} unreachable rooms",
+            level.id,
+            report.regions.len() - 1,
+            report.unreachable_rooms.len()
+        );
+    }
+    let params = level
+        .generation_params
+        .get_or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+    if let serde_json::Value::Object(map) = params {
+        map.insert(CONNECTIVITY_KEY.to_string(), serde_json::to_value(&report)?);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{floor_tile, level};
+
+    #[test]
+    fn stairs_join_floors_and_islands_are_reported() {
+        let mut objects = Vec::new();
+        for x in 0..3 {
+            objects.push(floor_tile(x as f32, 0.0, 0, "floor"));
+            objects.push(floor_tile(x as f32, 0.0, 1, "floor"));
+        }
+        // Stairs from the ground floor up to the first
+        let mut stairs = floor_tile(2.0, 0.0, 0, "stairs");
+        stairs.metadata.insert("to_floor".to_string(), 1.into());
+        objects.push(stairs);
+
+        let report = check_connectivity(&level(objects.clone()));
+        assert!(report.connected, "{:?}", report);
+        assert_eq!(report.walkable_cells, 6);
+        assert_eq!(report.regions[0].floors, vec![0, 1]);
+        let distances = walking_distances(&level(objects.clone()), (0, 0, 0));
+        assert_eq!(distances[&(1, 0, 0)], 5);
+
+        // A floor island nothing leads to, and a wall cutting off the stairs
+        objects.push(floor_tile(6.0, 6.0, 1, "floor"));
+        objects.push(floor_tile(6.0, 7.0, 1, "floor"));
+        objects.push(floor_tile(1.0, 0.0, 0, "wall"));
+        let mut level = level(objects);
+        let report = record_connectivity(&mut level).unwrap();
+        assert!(!report.connected);
+        assert_eq!(report.regions.len(), 3);
+        assert_eq!(report.unreachable_rooms.len(), 2);
+        assert_eq!(report.unreachable_rooms[0].min, [6, 6]);
+        assert_eq!(report.unreachable_rooms[0].cell_count, 2);
+
+        // The report is stored with the level for the frontend
+        let stored = ConnectivityReport::from_level(&level).unwrap();
+        assert_eq!(stored.unreachable_rooms.len(), 2);
+    }
+}
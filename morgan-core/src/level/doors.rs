@@ -0,0 +1,93 @@
+// Structured door and gate behavior
+use crate::{LevelData, ObjectKind};
+use serde::{Deserialize, Serialize};
+
+/// Which way a door swings or slides when opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoorOpenDirection {
+    #[default]
+    Both,
+    Inward,
+    Outward,
+    Sliding,
+}
+
+impl DoorOpenDirection {
+    /// Parse the legacy `opens` metadata string written by older generators.
+    fn from_legacy(value: &str) -> Self {
+        match value {
+            "inward" => DoorOpenDirection::Inward,
+            "outward" => DoorOpenDirection::Outward,
+            "sliding" => DoorOpenDirection::Sliding,
+            _ => DoorOpenDirection::Both,
+        }
+    }
+}
+
+/// Typed data for [`ObjectKind::Door`] objects.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DoorProperties {
+    #[serde(default)]
+    pub locked: bool,
+    /// Key item that unlocks the door
+    #[serde(default)]
+    pub key_id: Option<String>,
+    #[serde(default)]
+    pub open_direction: DoorOpenDirection,
+    /// Close automatically after this many seconds
+    #[serde(default)]
+    pub auto_close_secs: Option<f32>,
+    /// Object ID of a switch or lever that toggles the door
+    #[serde(default)]
+    pub linked_switch_id: Option<String>,
+}
+
+impl DoorProperties {
+    pub fn validate(&self, level: &LevelData, door_id: &str) -> Result<(), String> {
+        if let Some(ref key_id) = self.key_id {
+            if key_id.trim().is_empty() {
+                return Err("Door key ID cannot be empty".to_string());
+            }
+            if !self.locked {
+                return Err("Only locked doors can require a key".to_string());
+            }
+        }
+
+        if self.auto_close_secs.is_some_and(|s| s <= 0.0) {
+            return Err("Door auto-close delay must be positive".to_string());
+        }
+
+        if let Some(ref switch_id) = self.linked_switch_id {
+            if switch_id == door_id {
+                return Err("A door cannot be linked to itself".to_string());
+            }
+            if !level.objects.iter().any(|o| &o.id == switch_id) {
+                return Err(format!("Linked switch not found: {}", switch_id));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Convert door meshes from older levels, which stored behavior in metadata.
+pub fn migrate_legacy_doors(level: &mut LevelData) -> usize {
+    let mut migrated = 0;
+    for obj in &mut level.objects {
+        if matches!(obj.kind, ObjectKind::Mesh) && obj.tags.iter().any(|t| t == "door") {
+            let open_direction = obj
+                .metadata
+                .remove("opens")
+                .and_then(|v| v.as_str().map(DoorOpenDirection::from_legacy))
+                .unwrap_or_default();
+            obj.metadata.remove("interactive");
+            obj.kind = ObjectKind::Door(DoorProperties {
+                open_direction,
+                ..DoorProperties::default()
+            });
+            migrated += 1;
+        }
+    }
+    migrated
+}
@@ -0,0 +1,12 @@
+//! Authored level content stored in [`LevelData`](crate::LevelData) next to the
+//! generated geometry: gameplay object data, review notes and camera bookmarks.
+
+pub mod annotations;
+pub mod bookmarks;
+pub mod connectivity;
+pub mod doors;
+pub mod paths;
+pub mod physics;
+pub mod rooms;
+pub mod spawns;
+pub mod zones;
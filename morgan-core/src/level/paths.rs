@@ -0,0 +1,103 @@
+// Waypoint paths for AI patrol routes
+use crate::Transform3D;
+use serde::{Deserialize, Serialize};
+
+/// How an agent continues once it reaches the last node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathMode {
+    /// Stop at the last node
+    #[default]
+    Once,
+    /// Return to the first node and repeat
+    Loop,
+    /// Walk back along the path, then forward again
+    PingPong,
+}
+
+/// A single waypoint in world space.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathNode {
+    pub position: [f32; 3],
+    /// Seconds to wait at this node before moving on
+    #[serde(default)]
+    pub wait_secs: f32,
+}
+
+/// Typed data for [`ObjectKind::Path`] objects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathProperties {
+    pub nodes: Vec<PathNode>,
+    #[serde(default)]
+    pub mode: PathMode,
+}
+
+/// Line-strip representation of a path for drawing in the viewport.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathPolyline {
+    pub points: Vec<[f32; 3]>,
+    pub closed: bool,
+    pub total_length: f32,
+}
+
+impl PathProperties {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.nodes.len() < 2 {
+            return Err("A path needs at least two nodes".to_string());
+        }
+        if self.nodes.iter().any(|n| n.wait_secs < 0.0) {
+            return Err("Node wait time cannot be negative".to_string());
+        }
+        Ok(())
+    }
+
+    /// Transform covering every node, so the spatial index sees the whole path.
+    #[must_use]
+    pub fn enclosing_transform(&self) -> Transform3D {
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for node in &self.nodes {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(node.position[axis]);
+                max[axis] = max[axis].max(node.position[axis]);
+            }
+        }
+        if self.nodes.is_empty() {
+            min = [0.0; 3];
+            max = [0.0; 3];
+        }
+
+        Transform3D {
+            position: [
+                (min[0] + max[0]) * 0.5,
+                (min[1] + max[1]) * 0.5,
+                (min[2] + max[2]) * 0.5,
+            ],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [max[0] - min[0], max[1] - min[1], max[2] - min[2]],
+        }
+    }
+
+    #[must_use]
+    pub fn polyline(&self) -> PathPolyline {
+        let mut points: Vec<[f32; 3]> = self.nodes.iter().map(|n| n.position).collect();
+        let closed = self.mode == PathMode::Loop && points.len() > 2;
+        if closed {
+            points.push(points[0]);
+        }
+
+        let total_length = points
+            .windows(2)
+            .map(|w| {
+                let d = [w[1][0] - w[0][0], w[1][1] - w[0][1], w[1][2] - w[0][2]];
+                d[0].mul_add(d[0], d[1].mul_add(d[1], d[2] * d[2])).sqrt()
+            })
+            .sum();
+
+        PathPolyline {
+            points,
+            closed,
+            total_length,
+        }
+    }
+}
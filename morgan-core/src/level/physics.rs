@@ -0,0 +1,126 @@
+// Per-object rigid body and collider settings
+use crate::Transform3D;
+use serde::{Deserialize, Serialize};
+
+/// How the physics engine simulates a body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyType {
+    /// Never moves; level geometry
+    #[default]
+    Static,
+    /// Fully simulated
+    Dynamic,
+    /// Moved by game code, pushes dynamic bodies
+    Kinematic,
+}
+
+/// Collision volume, centered on the object's position.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum ColliderShape {
+    Cuboid {
+        half_extents: [f32; 3],
+    },
+    Sphere {
+        radius: f32,
+    },
+    /// Y-aligned capsule; `half_height` excludes the end caps
+    Capsule {
+        radius: f32,
+        half_height: f32,
+    },
+    /// Use the object's render mesh as a triangle collider
+    Mesh,
+}
+
+fn default_friction() -> f32 {
+    0.5
+}
+
+/// Physics settings stored on a [`GameObject`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhysicsProperties {
+    #[serde(default)]
+    pub body_type: BodyType,
+    /// Explicit collider; when unset a cuboid matching the object's scale is used
+    #[serde(default)]
+    pub collider: Option<ColliderShape>,
+    /// Mass in kilograms; only meaningful for dynamic bodies
+    #[serde(default)]
+    pub mass: Option<f32>,
+    #[serde(default = "default_friction")]
+    pub friction: f32,
+    /// Detect overlaps without generating contact forces
+    #[serde(default)]
+    pub sensor: bool,
+}
+
+impl Default for PhysicsProperties {
+    fn default() -> Self {
+        Self {
+            body_type: BodyType::default(),
+            collider: None,
+            mass: None,
+            friction: default_friction(),
+            sensor: false,
+        }
+    }
+}
+
+impl PhysicsProperties {
+    pub fn validate(&self) -> Result<(), String> {
+        match self.collider {
+            Some(ColliderShape::Cuboid { half_extents }) => {
+                if half_extents.iter().any(|&e| e <= 0.0) {
+                    return Err("Collider extents must be positive".to_string());
+                }
+            }
+            Some(ColliderShape::Sphere { radius }) => {
+                if radius <= 0.0 {
+                    return Err("Collider radius must be positive".to_string());
+                }
+            }
+            Some(ColliderShape::Capsule {
+                radius,
+                half_height,
+            }) => {
+                if radius <= 0.0 || half_height < 0.0 {
+                    return Err(
+                        "Capsule radius must be positive and height non-negative".to_string()
+                    );
+                }
+            }
+            Some(ColliderShape::Mesh) | None => {}
+        }
+
+        if let Some(mass) = self.mass {
+            if mass <= 0.0 {
+                return Err("Mass must be positive".to_string());
+            }
+            if self.body_type != BodyType::Dynamic {
+                return Err("Only dynamic bodies can have a mass".to_string());
+            }
+        }
+
+        if self.friction < 0.0 {
+            return Err("Friction cannot be negative".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Collider to export: the override if set, otherwise a box fitted to the transform.
+    #[must_use]
+    pub fn effective_collider(&self, transform: &Transform3D) -> ColliderShape {
+        self.collider
+            .clone()
+            .unwrap_or_else(|| ColliderShape::Cuboid {
+                half_extents: [
+                    transform.scale[0].abs() * 0.5,
+                    transform.scale[1].abs() * 0.5,
+                    transform.scale[2].abs() * 0.5,
+                ],
+            })
+    }
+}
@@ -0,0 +1,235 @@
+// Rooms of a generated level and how they are connected
+use crate::spatial::BoundingBox;
+use crate::LevelData;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Key under which generators store the room graph in `generation_params`.
+pub const ROOM_GRAPH_KEY: &str = "room_graph";
+
+/// What a room is for, assigned by [`RoomGraph::assign_room_types`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoomType {
+    #[default]
+    Normal,
+    /// Where the player starts
+    Spawn,
+    /// The room farthest from spawn
+    Boss,
+    /// A dead end worth exploring
+    Treasure,
+    Shop,
+}
+
+impl RoomType {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            RoomType::Normal => "normal",
+            RoomType::Spawn => "spawn",
+            RoomType::Boss => "boss",
+            RoomType::Treasure => "treasure",
+            RoomType::Shop => "shop",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomInfo {
+    pub id: String,
+    pub floor: u32,
+    /// World-space extent of the room, floor to ceiling
+    pub bounds: BoundingBox,
+    /// Doors on the room's edge
+    #[serde(default)]
+    pub door_ids: Vec<String>,
+    #[serde(default)]
+    pub room_type: RoomType,
+}
+
+impl RoomInfo {
+    /// Floor area in world units
+    fn area(&self) -> f32 {
+        (self.bounds.max[0] - self.bounds.min[0]) * (self.bounds.max[2] - self.bounds.min[2])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionKind {
+    Corridor,
+    Stairs,
+}
+
+/// Two rooms joined by a corridor or stairs, which can be walked both ways.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomConnection {
+    pub from: String,
+    pub to: String,
+    pub kind: ConnectionKind,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoomGraph {
+    pub rooms: Vec<RoomInfo>,
+    pub connections: Vec<RoomConnection>,
+}
+
+impl RoomGraph {
+    /// The graph a generator stored with `level`, if it stored one.
+    #[must_use]
+    pub fn from_level(level: &LevelData) -> Option<RoomGraph> {
+        let graph = level.generation_params.as_ref()?.get(ROOM_GRAPH_KEY)?;
+        serde_json::from_value(graph.clone()).ok()
+    }
+
+    #[must_use]
+    pub fn room(&self, id: &str) -> Option<&RoomInfo> {
+        self.rooms.iter().find(|room| room.id == id)
+    }
+
+    /// IDs of the rooms directly connected to `id`.
+    pub fn neighbours<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.connections.iter().filter_map(move |c| {
+            if c.from == id {
+                Some(c.to.as_str())
+            } else if c.to == id {
+                Some(c.from.as_str())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Number of connections on the shortest path from `id` to each room it can reach.
+    #[must_use]
+    pub fn distances_from(&self, id: &str) -> HashMap<String, usize> {
+        let mut distances = HashMap::from([(id.to_string(), 0)]);
+        let mut queue = VecDeque::from([(id.to_string(), 0)]);
+        while let Some((room, distance)) = queue.pop_front() {
+            for next in self.neighbours(&room) {
+                if !distances.contains_key(next) {
+                    distances.insert(next.to_string(), distance + 1);
+                    queue.push_back((next.to_string(), distance + 1));
+                }
+            }
+        }
+        distances
+    }
+
+    /// Labels the rooms. Spawn is the smallest room on the lowest floor and the boss
+    /// room is the one farthest from it, the larger winning a tie. Every other dead end
+    /// becomes a treasure room with `treasure_chance`, and each room left over a shop
+    /// with `shop_chance`. Rooms spawn can't reach stay normal.
+    pub fn assign_room_types(
+        &mut self,
+        treasure_chance: f32,
+        shop_chance: f32,
+        rng: &mut impl Rng,
+    ) {
+        for room in &mut self.rooms {
+            room.room_type = RoomType::Normal;
+        }
+        let Some(spawn) = self
+            .rooms
+            .iter()
+            .min_by(|a, b| a.floor.cmp(&b.floor).then(a.area().total_cmp(&b.area())))
+            .map(|room| room.id.clone())
+        else {
+            return;
+        };
+
+        let distances = self.distances_from(&spawn);
+        let boss = self
+            .rooms
+            .iter()
+            .filter(|room| room.id != spawn)
+            .filter_map(|room| Some((distances.get(&room.id)?, room)))
+            .max_by(|(d1, a), (d2, b)| d1.cmp(d2).then(a.area().total_cmp(&b.area())))
+            .map(|(_, room)| room.id.clone());
+        let dead_ends: HashSet<String> = self
+            .rooms
+            .iter()
+            .filter(|room| self.neighbours(&room.id).collect::<HashSet<_>>().len() == 1)
+            .map(|room| room.id.clone())
+            .collect();
+
+        for room in &mut self.rooms {
+            room.room_type = if room.id == spawn {
+                RoomType::Spawn
+            } else if Some(&room.id) == boss.as_ref() {
+                RoomType::Boss
+            } else if !distances.contains_key(&room.id) {
+                RoomType::Normal
+            } else if dead_ends.contains(&room.id) && rng.gen_bool(f64::from(treasure_chance)) {
+                RoomType::Treasure
+            } else if rng.gen_bool(f64::from(shop_chance)) {
+                RoomType::Shop
+            } else {
+                RoomType::Normal
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn room(id: &str, size: f32) -> RoomInfo {
+        RoomInfo {
+            id: id.to_string(),
+            floor: 0,
+            bounds: BoundingBox::new([0.0; 3], [size, 2.0, size]),
+            door_ids: Vec::new(),
+            room_type: RoomType::Normal,
+        }
+    }
+
+    fn corridor(from: &str, to: &str) -> RoomConnection {
+        RoomConnection {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind: ConnectionKind::Corridor,
+        }
+    }
+
+    #[test]
+    fn labels_spawn_boss_and_dead_ends() {
+        // hall - a - b - c, with a dead end off a and d unreachable
+        let mut graph = RoomGraph {
+            rooms: vec![
+                room("hall", 3.0),
+                room("a", 5.0),
+                room("b", 6.0),
+                room("c", 4.0),
+                room("side", 5.0),
+                room("d", 8.0),
+            ],
+            connections: vec![
+                corridor("hall", "a"),
+                corridor("a", "b"),
+                corridor("b", "c"),
+                corridor("side", "a"),
+            ],
+        };
+        assert_eq!(graph.distances_from("hall")["c"], 3);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        graph.assign_room_types(1.0, 0.0, &mut rng);
+        let room_type = |id| graph.room(id).unwrap().room_type;
+        assert_eq!(room_type("hall"), RoomType::Spawn);
+        assert_eq!(room_type("c"), RoomType::Boss);
+        assert_eq!(room_type("side"), RoomType::Treasure);
+        assert_eq!(room_type("a"), RoomType::Normal);
+        assert_eq!(room_type("d"), RoomType::Normal);
+
+        graph.assign_room_types(0.0, 1.0, &mut rng);
+        assert_eq!(graph.room("side").unwrap().room_type, RoomType::Shop);
+        assert_eq!(graph.room("hall").unwrap().room_type, RoomType::Spawn);
+    }
+}
@@ -0,0 +1,175 @@
+// Typed spawn points: player starts, enemy spawners, item spawns and level exits
+use crate::{GameObject, LevelData, ObjectKind, Transform3D};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Typed data for [`ObjectKind::SpawnPoint`] objects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "spawn_type", rename_all = "snake_case")]
+pub enum SpawnPointProperties {
+    /// Where the player enters the level; exactly one per level
+    PlayerStart,
+    /// Periodically spawns enemies of one kind
+    EnemySpawner {
+        enemy_kind: String,
+        count: u32,
+        interval_secs: f32,
+    },
+    /// Places a pickup, optionally respawning it
+    ItemSpawn {
+        item_kind: String,
+        #[serde(default)]
+        respawn_secs: Option<f32>,
+    },
+    /// Ends the level, optionally naming the level that follows
+    LevelExit {
+        #[serde(default)]
+        target_level: Option<String>,
+    },
+}
+
+impl SpawnPointProperties {
+    #[must_use]
+    pub fn tag(&self) -> &'static str {
+        match self {
+            SpawnPointProperties::PlayerStart => "player_start",
+            SpawnPointProperties::EnemySpawner { .. } => "enemy_spawner",
+            SpawnPointProperties::ItemSpawn { .. } => "item_spawn",
+            SpawnPointProperties::LevelExit { .. } => "level_exit",
+        }
+    }
+
+    /// Check the spawn point's own fields.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            SpawnPointProperties::PlayerStart => Ok(()),
+            SpawnPointProperties::EnemySpawner {
+                enemy_kind,
+                count,
+                interval_secs,
+            } => {
+                if enemy_kind.trim().is_empty() {
+                    Err("Enemy spawner needs an enemy kind".to_string())
+                } else if *count == 0 {
+                    Err("Enemy spawner count must be at least 1".to_string())
+                } else if *interval_secs <= 0.0 {
+                    Err("Enemy spawner interval must be positive".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+            SpawnPointProperties::ItemSpawn {
+                item_kind,
+                respawn_secs,
+            } => {
+                if item_kind.trim().is_empty() {
+                    Err("Item spawn needs an item kind".to_string())
+                } else if respawn_secs.is_some_and(|s| s <= 0.0) {
+                    Err("Item respawn time must be positive".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+            SpawnPointProperties::LevelExit { target_level } => {
+                if target_level.as_ref().is_some_and(|t| t.trim().is_empty()) {
+                    Err("Level exit target must not be empty".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+/// A level-wide problem with spawn point setup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnValidationIssue {
+    pub message: String,
+    pub object_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnValidationReport {
+    pub valid: bool,
+    pub player_starts: usize,
+    pub enemy_spawners: usize,
+    pub item_spawns: usize,
+    pub level_exits: usize,
+    pub issues: Vec<SpawnValidationIssue>,
+}
+
+/// Validate every spawn point in the level, including the single-player-start rule.
+#[must_use]
+pub fn validate_level_spawns(level: &LevelData) -> SpawnValidationReport {
+    let mut issues = Vec::new();
+    let mut player_starts = Vec::new();
+    let mut enemy_spawners = 0;
+    let mut item_spawns = 0;
+    let mut level_exits = 0;
+
+    for obj in &level.objects {
+        if let ObjectKind::SpawnPoint(ref spawn) = obj.kind {
+            match spawn {
+                SpawnPointProperties::PlayerStart => player_starts.push(obj.id.clone()),
+                SpawnPointProperties::EnemySpawner { .. } => enemy_spawners += 1,
+                SpawnPointProperties::ItemSpawn { .. } => item_spawns += 1,
+                SpawnPointProperties::LevelExit { .. } => level_exits += 1,
+            }
+            if let Err(message) = spawn.validate() {
+                issues.push(SpawnValidationIssue {
+                    message,
+                    object_ids: vec![obj.id.clone()],
+                });
+            }
+        }
+    }
+
+    match player_starts.len() {
+        0 => issues.push(SpawnValidationIssue {
+            message: "Level has no player start".to_string(),
+            object_ids: Vec::new(),
+        }),
+        1 => {}
+        n => issues.push(SpawnValidationIssue {
+            message: format!("Level has {} player starts, expected exactly one", n),
+            object_ids: player_starts.clone(),
+        }),
+    }
+
+    SpawnValidationReport {
+        valid: issues.is_empty(),
+        player_starts: player_starts.len(),
+        enemy_spawners,
+        item_spawns,
+        level_exits,
+        issues,
+    }
+}
+
+/// Build a spawn point object without adding it to a level.
+#[must_use]
+pub fn new_spawn_object(
+    name: String,
+    position: [f32; 3],
+    rotation: [f32; 4],
+    spawn: SpawnPointProperties,
+    layer: String,
+) -> GameObject {
+    GameObject {
+        id: Uuid::new_v4().to_string(),
+        name,
+        transform: Transform3D {
+            position,
+            rotation,
+            scale: [1.0, 1.0, 1.0],
+        },
+        material: None,
+        mesh: None,
+        layer,
+        tags: vec!["spawn".to_string(), spawn.tag().to_string()],
+        metadata: HashMap::new(),
+        kind: ObjectKind::SpawnPoint(spawn),
+        physics: None,
+    }
+}
@@ -0,0 +1,98 @@
+// Trigger volumes and gameplay zones
+use crate::LevelData;
+use serde::{Deserialize, Serialize};
+
+/// Volume covered by a zone, centered on the object's position.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum ZoneShape {
+    Box { half_extents: [f32; 3] },
+    Sphere { radius: f32 },
+}
+
+impl ZoneShape {
+    /// Object scale matching the volume, so spatial queries see the real extent.
+    #[must_use]
+    pub fn scale(&self) -> [f32; 3] {
+        match self {
+            ZoneShape::Box { half_extents } => [
+                half_extents[0] * 2.0,
+                half_extents[1] * 2.0,
+                half_extents[2] * 2.0,
+            ],
+            ZoneShape::Sphere { radius } => [radius * 2.0; 3],
+        }
+    }
+}
+
+/// Gameplay purpose of a zone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneType {
+    Trigger,
+    Damage,
+    Checkpoint,
+    Audio,
+    Camera,
+    Kill,
+    Custom(String),
+}
+
+impl ZoneType {
+    #[must_use]
+    pub fn label(&self) -> &str {
+        match self {
+            ZoneType::Trigger => "trigger",
+            ZoneType::Damage => "damage",
+            ZoneType::Checkpoint => "checkpoint",
+            ZoneType::Audio => "audio",
+            ZoneType::Camera => "camera",
+            ZoneType::Kill => "kill",
+            ZoneType::Custom(name) => name,
+        }
+    }
+}
+
+/// Typed data for [`ObjectKind::Zone`] objects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZoneProperties {
+    pub shape: ZoneShape,
+    pub zone_type: ZoneType,
+    /// IDs of objects the zone acts on (doors to open, spawners to wake, ...)
+    #[serde(default)]
+    pub targets: Vec<String>,
+    /// Fire only the first time something enters the zone
+    #[serde(default)]
+    pub trigger_once: bool,
+}
+
+impl ZoneProperties {
+    pub fn validate(&self, level: &LevelData) -> Result<(), String> {
+        match self.shape {
+            ZoneShape::Box { half_extents } => {
+                if half_extents.iter().any(|&e| e <= 0.0) {
+                    return Err("Zone box extents must be positive".to_string());
+                }
+            }
+            ZoneShape::Sphere { radius } => {
+                if radius <= 0.0 {
+                    return Err("Zone sphere radius must be positive".to_string());
+                }
+            }
+        }
+
+        if let ZoneType::Custom(ref name) = self.zone_type {
+            if name.trim().is_empty() {
+                return Err("Custom zone type needs a name".to_string());
+            }
+        }
+
+        for target in &self.targets {
+            if !level.objects.iter().any(|o| &o.id == target) {
+                return Err(format!("Zone target not found: {}", target));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+//! Core level data, procedural generation and export for Morgan-Bevy.
+//!
+//! This crate has no editor or GUI dependencies, so games and build tools can generate
+//! and export levels directly, and the logic can be tested without a running editor.
+//! The Tauri application in `src-tauri` is a command layer on top of it.
+
+pub mod export;
+pub mod generation;
+pub mod level;
+pub mod snapping;
+pub mod spatial;
+pub mod stable;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+use generation::placement::SpawnPlacementParams;
+use level::annotations::Annotation;
+use level::bookmarks::CameraBookmark;
+use level::doors::DoorProperties;
+use level::paths::PathProperties;
+use level::physics::PhysicsProperties;
+use level::spawns::SpawnPointProperties;
+use level::zones::ZoneProperties;
+use serde::{Deserialize, Serialize};
+use spatial::BoundingBox;
+use std::collections::HashMap;
+
+// Core data structures for level editing
+/// 3D transformation data for positioning, rotating, and scaling objects in 3D space.
+///
+/// Uses standard 3D graphics conventions with Y-up coordinate system.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transform3D {
+    /// Position coordinates in 3D space [x, y, z] in world units
+    pub position: [f32; 3],
+    /// Rotation as quaternion [x, y, z, w] for smooth interpolation
+    pub rotation: [f32; 4], // quaternion [x, y, z, w]
+    /// Scale factors [x, y, z] for non-uniform scaling support
+    pub scale: [f32; 3],
+}
+
+/// Represents a 3D object in the editor with transform, material, and metadata.
+///
+/// GameObjects are the fundamental building blocks of levels in Morgan-Bevy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameObject {
+    /// Unique identifier for the object within the level
+    pub id: String,
+    /// Human-readable name displayed in the editor hierarchy
+    pub name: String,
+    /// 3D transformation (position, rotation, scale) data
+    pub transform: Transform3D,
+    /// Optional material reference for rendering
+    pub material: Option<String>,
+    /// Optional mesh reference for geometry
+    pub mesh: Option<String>,
+    /// Layer assignment for organization and visibility control
+    pub layer: String,
+    /// Tags for categorization and scripting hooks
+    pub tags: Vec<String>,
+    /// Additional metadata for custom properties and game logic
+    pub metadata: HashMap<String, serde_json::Value>,
+    /// Object category with its typed gameplay data
+    #[serde(default)]
+    pub kind: ObjectKind,
+    /// Rigid body and collider settings used by the physics export
+    #[serde(default)]
+    pub physics: Option<PhysicsProperties>,
+}
+
+impl GameObject {
+    /// A plain mesh object at `position`, named after its ID, on the `Default` layer with
+    /// no rotation, unit scale and nothing else set.
+    #[must_use]
+    pub fn new(id: &str, position: [f32; 3]) -> Self {
+        Self {
+            id: id.to_string(),
+            name: id.to_string(),
+            transform: Transform3D {
+                position,
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                scale: [1.0, 1.0, 1.0],
+            },
+            material: None,
+            mesh: None,
+            layer: "Default".to_string(),
+            tags: Vec::new(),
+            metadata: HashMap::new(),
+            kind: ObjectKind::Mesh,
+            physics: None,
+        }
+    }
+}
+
+/// Category of a [`GameObject`], carrying typed data for non-mesh objects.
+///
+/// Plain geometry uses [`ObjectKind::Mesh`]; gameplay objects get dedicated
+/// variants so exporters can emit proper components instead of guessing from tags.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ObjectKind {
+    /// Regular renderable geometry
+    #[default]
+    Mesh,
+    /// Trigger volume or gameplay zone
+    Zone(ZoneProperties),
+    /// Player start, enemy spawner or item spawn
+    SpawnPoint(SpawnPointProperties),
+    /// Ordered waypoints for AI patrol routes
+    Path(PathProperties),
+    /// Door or gate geometry with typed open/lock behavior
+    Door(DoorProperties),
+}
+
+impl ObjectKind {
+    /// Whether objects of this kind are rendered with a mesh
+    #[must_use]
+    pub fn has_geometry(&self) -> bool {
+        matches!(self, ObjectKind::Mesh | ObjectKind::Door(_))
+    }
+}
+
+/// Complete level data containing all objects, layers, and generation information.
+///
+/// This is the main data structure for saving and loading levels in Morgan-Bevy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelData {
+    /// Unique identifier for the level
+    pub id: String,
+    /// Human-readable level name
+    pub name: String,
+    /// All game objects contained in this level
+    pub objects: Vec<GameObject>,
+    /// Layer names for organization and visibility control
+    pub layers: Vec<String>,
+    /// Random seed used for procedural generation (if applicable)
+    pub generation_seed: Option<u64>,
+    /// Parameters used for procedural generation algorithms
+    pub generation_params: Option<serde_json::Value>,
+    /// 3D bounding box defining the level's spatial extent
+    pub bounds: BoundingBox,
+    /// Review notes pinned to objects or positions in the level
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+    /// Named camera viewpoints shared with everyone who opens the level
+    #[serde(default)]
+    pub camera_bookmarks: Vec<CameraBookmark>,
+}
+
+impl LevelData {
+    /// A level with no objects or layers, named after its ID, with empty bounds at the
+    /// origin.
+    #[must_use]
+    pub fn empty(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: id.to_string(),
+            objects: Vec::new(),
+            layers: Vec::new(),
+            generation_seed: None,
+            generation_params: None,
+            bounds: BoundingBox::new([0.0; 3], [0.0; 3]),
+            annotations: Vec::new(),
+            camera_bookmarks: Vec::new(),
+        }
+    }
+}
+
+/// Parameters for Binary Space Partitioning (BSP) level generation.
+///
+/// Controls the procedural generation of rooms and corridors using BSP algorithm.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BSPGenerationParams {
+    /// Level width in grid units
+    pub width: u32,
+    /// Level height in grid units
+    pub height: u32,
+    /// Number of floors, stacked one theme wall height apart (0 is treated as 1)
+    pub depth: u32,
+    /// Minimum room size to prevent tiny rooms
+    pub min_room_size: u32,
+    /// Maximum room size to prevent oversized rooms
+    pub max_room_size: u32,
+    /// Width of corridors connecting rooms
+    pub corridor_width: u32,
+    /// Theme name determining tiles, materials, and styling
+    pub theme: String,
+    /// Optional random seed for reproducible generation
+    pub seed: Option<u64>,
+    /// Chance, from 0 to 1, that a doorway where a corridor enters a room gets a door
+    #[serde(default = "default_door_probability")]
+    pub door_probability: f32,
+    /// Shape of the corridors between rooms
+    #[serde(default)]
+    pub corridor_style: CorridorStyle,
+    /// Up to this many cells are added to `corridor_width`, picked per corridor
+    #[serde(default)]
+    pub corridor_width_variation: u32,
+    /// Chance, from 0 to 1, that two neighbouring rooms not yet joined get a corridor of
+    /// their own, turning the tree of rooms into a layout with loops
+    #[serde(default)]
+    pub loop_factor: f32,
+    /// Chance, from 0 to 1, that a dead-end room becomes a treasure room
+    #[serde(default = "default_treasure_room_weight")]
+    pub treasure_room_weight: f32,
+    /// Chance, from 0 to 1, that a room left unlabelled becomes a shop
+    #[serde(default = "default_shop_room_weight")]
+    pub shop_room_weight: f32,
+    /// Place a player start, enemy spawners and exits once the level is built
+    #[serde(default)]
+    pub spawns: Option<SpawnPlacementParams>,
+}
+
+const fn default_door_probability() -> f32 {
+    1.0
+}
+
+const fn default_treasure_room_weight() -> f32 {
+    0.5
+}
+
+const fn default_shop_room_weight() -> f32 {
+    0.1
+}
+
+/// How BSP corridors run between the rooms they connect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CorridorStyle {
+    /// One horizontal and one vertical segment
+    #[default]
+    LShaped,
+    /// A single straight run where the rooms line up, L-shaped otherwise
+    Straight,
+    /// A meandering path found with A* over random step costs
+    Winding,
+    /// A winding path whose width changes as it goes, like a cave passage
+    Organic,
+}
@@ -0,0 +1,227 @@
+//! Grid, angle and surface snapping for placing objects.
+//!
+//! The editor snaps through these functions instead of doing the math in the frontend,
+//! so snapped transforms use the same conventions (Y up, quaternions as `[x, y, z, w]`)
+//! as the spatial index and exporters.
+
+use crate::spatial::{BoundingBox, SpatialIndex};
+use crate::Transform3D;
+use serde::{Deserialize, Serialize};
+
+/// How transforms are snapped. Each kind of snapping can be turned off without losing
+/// its setting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapSettings {
+    pub grid_enabled: bool,
+    /// Grid spacing in world units
+    pub grid_size: f32,
+    /// Where the grid starts, for grids not lined up with the origin
+    pub grid_offset: [f32; 3],
+    pub rotation_enabled: bool,
+    /// Angle increment in degrees, applied to yaw, pitch and roll
+    pub rotation_increment: f32,
+    /// Drop objects onto the surface below them
+    pub surface_enabled: bool,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self {
+            grid_enabled: true,
+            grid_size: 1.0,
+            grid_offset: [0.0; 3],
+            rotation_enabled: true,
+            rotation_increment: 15.0,
+            surface_enabled: false,
+        }
+    }
+}
+
+impl SnapSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.grid_size.is_finite() && self.grid_size > 0.0) {
+            return Err("Grid size must be positive".to_string());
+        }
+        if !(self.rotation_increment.is_finite()
+            && self.rotation_increment > 0.0
+            && self.rotation_increment <= 180.0)
+        {
+            return Err("Rotation increment must be between 0 and 180 degrees".to_string());
+        }
+        if self.grid_offset.iter().any(|o| !o.is_finite()) {
+            return Err("Grid offset must be finite".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// `transform` with its position on the grid and its rotation on the angle increments.
+/// Scale is left alone. Surface snapping needs the level, see [`snap_to_surface`].
+#[must_use]
+pub fn snap_transform(transform: &Transform3D, settings: &SnapSettings) -> Transform3D {
+    let mut snapped = transform.clone();
+    if settings.grid_enabled {
+        snapped.position = std::array::from_fn(|axis| {
+            snap_value(
+                transform.position[axis] - settings.grid_offset[axis],
+                settings.grid_size,
+            ) + settings.grid_offset[axis]
+        });
+    }
+    if settings.rotation_enabled {
+        snapped.rotation = snap_rotation(transform.rotation, settings.rotation_increment);
+    }
+    snapped
+}
+
+/// `transform` moved down or up so its bounds rest on the first object below its
+/// center, skipping `ignore_id` (usually the object being placed). Returns `None` when
+/// there is nothing below.
+#[must_use]
+pub fn snap_to_surface(
+    transform: &Transform3D,
+    index: &SpatialIndex,
+    ignore_id: Option<&str>,
+) -> Option<Transform3D> {
+    let position = transform.position;
+    let hit = index
+        .raycast(position, [0.0, -1.0, 0.0], f32::INFINITY)
+        .into_iter()
+        // Objects around the center are ones this object overlaps, not ones below it
+        .find(|hit| Some(hit.object_id.as_str()) != ignore_id && hit.distance > 0.0)?;
+
+    let bounds = BoundingBox::from_transform(transform);
+    let surface = position[1] - hit.distance;
+    let mut snapped = transform.clone();
+    snapped.position[1] = surface + (position[1] - bounds.min[1]);
+    Some(snapped)
+}
+
+fn snap_value(value: f32, step: f32) -> f32 {
+    // Adding zero turns -0.0 into 0.0
+    (value / step).round().mul_add(step, 0.0)
+}
+
+/// Snaps yaw, pitch and roll (Bevy's `EulerRot::YXZ`) to multiples of `increment_deg`.
+#[must_use]
+pub fn snap_rotation(rotation: [f32; 4], increment_deg: f32) -> [f32; 4] {
+    let step = increment_deg.to_radians();
+    let [yaw, pitch, roll] = to_euler_yxz(rotation).map(|angle| snap_value(angle, step));
+    from_euler_yxz(yaw, pitch, roll)
+}
+
+fn quat_mul(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    let [ax, ay, az, aw] = a;
+    let [bx, by, bz, bw] = b;
+    [
+        aw.mul_add(bx, ax.mul_add(bw, ay.mul_add(bz, -az * by))),
+        aw.mul_add(by, ay.mul_add(bw, az.mul_add(bx, -ax * bz))),
+        aw.mul_add(bz, az.mul_add(bw, ax.mul_add(by, -ay * bx))),
+        aw.mul_add(bw, -ax.mul_add(bx, ay.mul_add(by, az * bz))),
+    ]
+}
+
+/// Quaternion rotating by `yaw` around Y, then `pitch` around X, then `roll` around Z,
+/// in radians.
+#[must_use]
+pub fn from_euler_yxz(yaw: f32, pitch: f32, roll: f32) -> [f32; 4] {
+    let (sy, cy) = (yaw * 0.5).sin_cos();
+    let (sx, cx) = (pitch * 0.5).sin_cos();
+    let (sz, cz) = (roll * 0.5).sin_cos();
+    quat_mul(
+        quat_mul([0.0, sy, 0.0, cy], [sx, 0.0, 0.0, cx]),
+        [0.0, 0.0, sz, cz],
+    )
+}
+
+/// Yaw, pitch and roll in radians of a quaternion, the inverse of [`from_euler_yxz`].
+#[must_use]
+pub fn to_euler_yxz(rotation: [f32; 4]) -> [f32; 3] {
+    let length = rotation.iter().map(|c| c * c).sum::<f32>().sqrt();
+    if length <= f32::EPSILON {
+        return [0.0; 3];
+    }
+    let [x, y, z, w] = rotation.map(|c| c / length);
+    let pitch = (2.0 * w.mul_add(x, -y * z)).clamp(-1.0, 1.0).asin();
+    let yaw = (2.0 * x.mul_add(z, w * y)).atan2(x.mul_add(x, y * y).mul_add(-2.0, 1.0));
+    let roll = (2.0 * x.mul_add(y, w * z)).atan2(x.mul_add(x, z * z).mul_add(-2.0, 1.0));
+    [yaw, pitch, roll]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(position: [f32; 3], rotation: [f32; 4]) -> Transform3D {
+        Transform3D {
+            position,
+            rotation,
+            scale: [1.0, 1.0, 1.0],
+        }
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-4, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn snaps_to_grid_and_angles() {
+        let angles = [0.3, -0.7, 1.1];
+        let rotation = from_euler_yxz(angles[0], angles[1], angles[2]);
+        assert_close(&to_euler_yxz(rotation), &angles);
+
+        let settings = SnapSettings {
+            grid_size: 0.5,
+            grid_offset: [0.25, 0.0, 0.0],
+            rotation_increment: 45.0,
+            ..SnapSettings::default()
+        };
+        let yawed = from_euler_yxz(50f32.to_radians(), 0.0, 0.0);
+        let snapped = snap_transform(&transform([0.6, -0.2, 1.3], yawed), &settings);
+        assert_close(&snapped.position, &[0.75, 0.0, 1.5]);
+        assert_close(
+            &snapped.rotation,
+            &from_euler_yxz(45f32.to_radians(), 0.0, 0.0),
+        );
+
+        let off = SnapSettings {
+            grid_enabled: false,
+            rotation_enabled: false,
+            ..settings
+        };
+        let unchanged = snap_transform(&transform([0.6, -0.2, 1.3], yawed), &off);
+        assert_close(&unchanged.position, &[0.6, -0.2, 1.3]);
+        assert!(off.validate().is_ok());
+        assert!(SnapSettings {
+            grid_size: 0.0,
+            ..SnapSettings::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn drops_onto_surface_below() {
+        let mut index = SpatialIndex::new();
+        let floor = Transform3D {
+            position: [0.0, 0.0, 0.0],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [10.0, 0.2, 10.0],
+        };
+        index.insert("floor", &floor);
+        let crate_transform = transform([1.0, 3.0, 1.0], [0.0, 0.0, 0.0, 1.0]);
+        index.insert("crate", &crate_transform);
+
+        let snapped = snap_to_surface(&crate_transform, &index, Some("crate")).unwrap();
+        assert_close(&snapped.position, &[1.0, 0.6, 1.0]);
+        assert!(snap_to_surface(
+            &transform([20.0, 3.0, 0.0], [0.0, 0.0, 0.0, 1.0]),
+            &index,
+            None
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,531 @@
+// Spatial data structures for 3D level editing
+// This module provides efficient spatial queries and collision detection
+
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use crate::Transform3D;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl BoundingBox {
+    #[must_use]
+    pub fn new(min: [f32; 3], max: [f32; 3]) -> Self {
+        Self { min, max }
+    }
+    
+    /// Axis-aligned bounds of an object, enclosing it as rotated by its transform.
+    #[must_use]
+    pub fn from_transform(transform: &Transform3D) -> Self {
+        OrientedBox::from_transform(transform).enclosing_bounds()
+    }
+}
+
+/// An object's box as placed by its transform, rotation included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrientedBox {
+    pub center: [f32; 3],
+    pub half_extents: [f32; 3],
+    /// Unit quaternion `[x, y, z, w]`
+    pub rotation: [f32; 4],
+}
+
+impl OrientedBox {
+    #[must_use]
+    pub fn from_transform(transform: &Transform3D) -> Self {
+        Self {
+            center: transform.position,
+            half_extents: transform.scale.map(|s| s.abs() * 0.5),
+            rotation: normalize_quaternion(transform.rotation),
+        }
+    }
+
+    /// The box's local axes in world space.
+    #[must_use]
+    pub fn axes(&self) -> [[f32; 3]; 3] {
+        [
+            rotate(self.rotation, [1.0, 0.0, 0.0]),
+            rotate(self.rotation, [0.0, 1.0, 0.0]),
+            rotate(self.rotation, [0.0, 0.0, 1.0]),
+        ]
+    }
+
+    /// The smallest axis-aligned box containing this one.
+    #[must_use]
+    pub fn enclosing_bounds(&self) -> BoundingBox {
+        let axes = self.axes();
+        let extent: [f32; 3] = std::array::from_fn(|i| {
+            (0..3)
+                .map(|j| axes[j][i].abs() * self.half_extents[j])
+                .sum()
+        });
+        BoundingBox {
+            min: std::array::from_fn(|i| self.center[i] - extent[i]),
+            max: std::array::from_fn(|i| self.center[i] + extent[i]),
+        }
+    }
+
+    /// A world-space vector in the box's local frame.
+    fn to_local(&self, vector: [f32; 3]) -> [f32; 3] {
+        self.axes().map(|axis| dot(axis, vector))
+    }
+
+    /// Distance along a normalized ray to where it enters the box.
+    #[must_use]
+    pub fn ray_distance(&self, origin: [f32; 3], direction: [f32; 3]) -> Option<f32> {
+        let local_origin = self.to_local(std::array::from_fn(|i| origin[i] - self.center[i]));
+        let local_bounds = BoundingBox::new(self.half_extents.map(|h| -h), self.half_extents);
+        ray_distance(local_origin, self.to_local(direction), &local_bounds)
+    }
+
+    /// Distance from `point` to the box, 0 when inside.
+    #[must_use]
+    pub fn distance_to(&self, point: [f32; 3]) -> f32 {
+        let local = self.to_local(std::array::from_fn(|i| point[i] - self.center[i]));
+        (0..3)
+            .map(|axis| {
+                (local[axis].abs() - self.half_extents[axis])
+                    .max(0.0)
+                    .powi(2)
+            })
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    /// Whether the box lies entirely on the negative side of `plane`, given as
+    /// `[a, b, c, d]` for `a*x + b*y + c*z + d = 0`.
+    #[must_use]
+    pub fn is_behind_plane(&self, plane: [f32; 4]) -> bool {
+        let normal = [plane[0], plane[1], plane[2]];
+        let radius: f32 = self
+            .axes()
+            .iter()
+            .zip(self.half_extents)
+            .map(|(axis, half)| dot(normal, *axis).abs() * half)
+            .sum();
+        dot(normal, self.center) + plane[3] + radius < 0.0
+    }
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0].mul_add(b[0], a[1].mul_add(b[1], a[2] * b[2]))
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1].mul_add(b[2], -a[2] * b[1]),
+        a[2].mul_add(b[0], -a[0] * b[2]),
+        a[0].mul_add(b[1], -a[1] * b[0]),
+    ]
+}
+
+/// A unit quaternion, or the identity for a zero one.
+fn normalize_quaternion(q: [f32; 4]) -> [f32; 4] {
+    let length = q.iter().map(|c| c * c).sum::<f32>().sqrt();
+    if length <= f32::EPSILON {
+        [0.0, 0.0, 0.0, 1.0]
+    } else {
+        q.map(|c| c / length)
+    }
+}
+
+/// `v` rotated by the unit quaternion `q`.
+fn rotate(q: [f32; 4], v: [f32; 3]) -> [f32; 3] {
+    let u = [q[0], q[1], q[2]];
+    let t = cross(u, v).map(|c| 2.0 * c);
+    let ut = cross(u, t);
+    std::array::from_fn(|i| q[3].mul_add(t[i], v[i]) + ut[i])
+}
+
+/// Two objects whose bounds overlap, `depth` being how far they overlap along each axis.
+/// `duplicate` marks bounds that match to within the tolerance, typically an object
+/// placed twice.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Overlap {
+    pub first: String,
+    pub second: String,
+    pub depth: [f32; 3],
+    pub duplicate: bool,
+}
+
+/// An object hit by a ray, `distance` along the ray from its origin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RayHit {
+    pub object_id: String,
+    pub distance: f32,
+}
+
+/// An object near a query point, `distance` to the closest point of its bounds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NearbyObject {
+    pub object_id: String,
+    pub distance: f32,
+}
+
+/// What the index keeps per object: its exact box, and the axis-aligned bounds around
+/// it for quick rejection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedObject {
+    bounds: BoundingBox,
+    shape: OrientedBox,
+}
+
+impl IndexedObject {
+    fn new(transform: &Transform3D) -> Self {
+        let shape = OrientedBox::from_transform(transform);
+        Self {
+            bounds: shape.enclosing_bounds(),
+            shape,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpatialIndex {
+    objects: HashMap<String, IndexedObject>,
+}
+
+impl SpatialIndex {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            objects: HashMap::new(),
+        }
+    }
+    
+    pub fn insert(&mut self, object_id: &str, transform: &Transform3D) {
+        self.objects.insert(object_id.to_string(), IndexedObject::new(transform));
+    }
+    
+    pub fn update(&mut self, object_id: &str, transform: &Transform3D) {
+        self.objects.insert(object_id.to_string(), IndexedObject::new(transform));
+    }
+    
+    pub fn remove(&mut self, object_id: &str) {
+        self.objects.remove(object_id);
+    }
+    
+    pub fn clear(&mut self) {
+        self.objects.clear();
+    }
+    
+    #[must_use]
+    pub fn query_bounds(&self, bounds: &BoundingBox) -> Vec<String> {
+        let mut results = Vec::new();
+        for (id, object) in &self.objects {
+            if bounds_intersect(bounds, &object.bounds) {
+                results.push(id.clone());
+            }
+        }
+        results
+    }
+
+    /// Objects whose bounds the ray hits within `max_distance`, nearest first. An object
+    /// containing the origin is hit at distance 0.
+    #[must_use]
+    pub fn raycast(&self, origin: [f32; 3], direction: [f32; 3], max_distance: f32) -> Vec<RayHit> {
+        let length = direction.iter().map(|d| d * d).sum::<f32>().sqrt();
+        if length <= f32::EPSILON {
+            return Vec::new();
+        }
+        let direction = direction.map(|d| d / length);
+
+        let mut hits: Vec<RayHit> = self
+            .objects
+            .iter()
+            .filter_map(|(id, object)| {
+                object
+                    .shape
+                    .ray_distance(origin, direction)
+                    .filter(|distance| *distance <= max_distance)
+                    .map(|distance| RayHit {
+                        object_id: id.clone(),
+                        distance,
+                    })
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            a.distance
+                .total_cmp(&b.distance)
+                .then_with(|| a.object_id.cmp(&b.object_id))
+        });
+        hits
+    }
+
+    /// The `k` objects closest to `point`, nearest first, leaving out any further than
+    /// `radius`. Distances are to each object's box, so 0 when `point` is inside.
+    #[must_use]
+    pub fn query_nearest(
+        &self,
+        point: [f32; 3],
+        k: usize,
+        radius: Option<f32>,
+    ) -> Vec<NearbyObject> {
+        let radius = radius.unwrap_or(f32::INFINITY);
+        let mut nearby: Vec<NearbyObject> = self
+            .objects
+            .iter()
+            .map(|(id, object)| NearbyObject {
+                object_id: id.clone(),
+                distance: object.shape.distance_to(point),
+            })
+            .filter(|object| object.distance <= radius)
+            .collect();
+        nearby.sort_by(|a, b| {
+            a.distance
+                .total_cmp(&b.distance)
+                .then_with(|| a.object_id.cmp(&b.object_id))
+        });
+        nearby.truncate(k);
+        nearby
+    }
+
+    /// Pairs of objects whose bounds overlap by more than `tolerance` along every axis,
+    /// so objects merely touching, such as neighbouring floor tiles, are left out.
+    /// Sorted by ID, each pair listed once with `first < second`.
+    #[must_use]
+    pub fn overlapping_pairs(&self, tolerance: f32) -> Vec<Overlap> {
+        // Sweep along X: only objects whose X ranges overlap need a full check
+        let mut sorted: Vec<(&String, &BoundingBox)> = self
+            .objects
+            .iter()
+            .map(|(id, object)| (id, &object.bounds))
+            .collect();
+        sorted.sort_by(|a, b| a.1.min[0].total_cmp(&b.1.min[0]).then_with(|| a.0.cmp(b.0)));
+
+        let mut overlaps = Vec::new();
+        for (i, (id, bounds)) in sorted.iter().enumerate() {
+            for (other_id, other) in &sorted[i + 1..] {
+                if other.min[0] >= bounds.max[0] - tolerance {
+                    break;
+                }
+                let depth: [f32; 3] = std::array::from_fn(|axis| {
+                    bounds.max[axis].min(other.max[axis]) - bounds.min[axis].max(other.min[axis])
+                });
+                if depth.iter().any(|d| *d <= tolerance) {
+                    continue;
+                }
+                let duplicate = (0..3).all(|axis| {
+                    (bounds.min[axis] - other.min[axis]).abs() <= tolerance
+                        && (bounds.max[axis] - other.max[axis]).abs() <= tolerance
+                });
+                let (first, second) = if id < other_id {
+                    (id, other_id)
+                } else {
+                    (other_id, id)
+                };
+                overlaps.push(Overlap {
+                    first: (*first).clone(),
+                    second: (*second).clone(),
+                    depth,
+                    duplicate,
+                });
+            }
+        }
+        overlaps.sort_by(|a, b| a.first.cmp(&b.first).then_with(|| a.second.cmp(&b.second)));
+        overlaps
+    }
+
+    /// Objects at least partly inside a view frustum, sorted by ID. Each plane is
+    /// `[a, b, c, d]` with the inside where `a*x + b*y + c*z + d >= 0`, as the viewport
+    /// camera reports them. Boxes straddling two planes outside a corner may still be
+    /// returned, which costs a draw but never drops a visible object.
+    #[must_use]
+    pub fn query_frustum(&self, planes: [[f32; 4]; 6]) -> Vec<String> {
+        let mut visible: Vec<String> = self
+            .objects
+            .iter()
+            .filter(|(_, object)| {
+                !planes
+                    .iter()
+                    .any(|plane| object.shape.is_behind_plane(*plane))
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        visible.sort();
+        visible
+    }
+}
+
+/// Distance along a normalized ray to where it enters `bounds`, using the slab method.
+fn ray_distance(origin: [f32; 3], direction: [f32; 3], bounds: &BoundingBox) -> Option<f32> {
+    let mut enter = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    for axis in 0..3 {
+        if direction[axis].abs() <= f32::EPSILON {
+            // Parallel to this slab: the ray is either always inside it or never
+            if origin[axis] < bounds.min[axis] || origin[axis] > bounds.max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let t1 = (bounds.min[axis] - origin[axis]) / direction[axis];
+        let t2 = (bounds.max[axis] - origin[axis]) / direction[axis];
+        enter = enter.max(t1.min(t2));
+        exit = exit.min(t1.max(t2));
+    }
+    (exit >= enter.max(0.0)).then_some(enter.max(0.0))
+}
+
+fn bounds_intersect(a: &BoundingBox, b: &BoundingBox) -> bool {
+    a.max[0] >= b.min[0] && a.min[0] <= b.max[0] &&
+    a.max[1] >= b.min[1] && a.min[1] <= b.max[1] &&
+    a.max[2] >= b.min[2] && a.min[2] <= b.max[2]
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(position: [f32; 3], scale: [f32; 3]) -> Transform3D {
+        Transform3D {
+            position,
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale,
+        }
+    }
+
+    #[test]
+    fn raycast_returns_hits_nearest_first() {
+        let mut index = SpatialIndex::new();
+        index.insert("floor", &transform([0.0, -0.05, 0.0], [10.0, 0.1, 10.0]));
+        index.insert("near_wall", &transform([0.0, 1.0, 2.0], [4.0, 2.0, 0.2]));
+        index.insert("far_wall", &transform([0.0, 1.0, 6.0], [4.0, 2.0, 0.2]));
+        index.insert("behind", &transform([0.0, 1.0, -3.0], [1.0, 1.0, 1.0]));
+
+        // Looking along +Z at head height passes through both walls, not the floor
+        let hits = index.raycast([0.0, 1.0, 0.0], [0.0, 0.0, 2.0], 100.0);
+        let ids: Vec<_> = hits.iter().map(|hit| hit.object_id.as_str()).collect();
+        assert_eq!(ids, ["near_wall", "far_wall"]);
+        assert!((hits[0].distance - 1.9).abs() < 1e-5);
+
+        // Cut off by distance
+        assert_eq!(
+            index.raycast([0.0, 1.0, 0.0], [0.0, 0.0, 1.0], 3.0).len(),
+            1
+        );
+
+        // Looking down from above hits the floor
+        let hits = index.raycast([3.0, 5.0, 3.0], [0.0, -1.0, 0.0], 100.0);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].object_id, "floor");
+
+        assert!(index.raycast([0.0, 1.0, 0.0], [0.0; 3], 100.0).is_empty());
+    }
+
+    #[test]
+    fn finds_nearest_objects() {
+        let mut index = SpatialIndex::new();
+        for (id, x) in [("a", 0.0), ("b", 3.0), ("c", 10.0)] {
+            index.insert(id, &transform([x, 0.0, 0.0], [1.0, 1.0, 1.0]));
+        }
+
+        let nearest = index.query_nearest([2.0, 0.0, 0.0], 2, None);
+        let ids: Vec<_> = nearest.iter().map(|o| o.object_id.as_str()).collect();
+        assert_eq!(ids, ["b", "a"]);
+        assert!((nearest[0].distance - 0.5).abs() < 1e-6);
+        assert!((nearest[1].distance - 1.5).abs() < 1e-6);
+
+        // Inside an object is distance 0, and the radius cuts off the rest
+        let nearest = index.query_nearest([10.2, 0.0, 0.0], 5, Some(2.0));
+        assert_eq!(nearest.len(), 1);
+        assert_eq!(nearest[0].object_id, "c");
+        assert!(nearest[0].distance.abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn frustum_keeps_visible_objects() {
+        let mut index = SpatialIndex::new();
+        index.insert("inside", &transform([0.0, 0.0, -5.0], [1.0, 1.0, 1.0]));
+        index.insert("straddling", &transform([10.0, 0.0, -5.0], [1.0, 1.0, 1.0]));
+        index.insert("behind", &transform([0.0, 0.0, 5.0], [1.0, 1.0, 1.0]));
+        index.insert("far", &transform([0.0, 0.0, -200.0], [1.0, 1.0, 1.0]));
+
+        // A box-shaped frustum looking down -Z: -10..10 across, 0.1..100 deep
+        let planes = [
+            [1.0, 0.0, 0.0, 10.0],
+            [-1.0, 0.0, 0.0, 10.0],
+            [0.0, 1.0, 0.0, 10.0],
+            [0.0, -1.0, 0.0, 10.0],
+            [0.0, 0.0, -1.0, -0.1],
+            [0.0, 0.0, 1.0, 100.0],
+        ];
+        assert_eq!(index.query_frustum(planes), vec!["inside", "straddling"]);
+    }
+
+    #[test]
+    fn reports_overlaps_beyond_tolerance() {
+        let mut index = SpatialIndex::new();
+        // Touching tiles
+        index.insert("tile_a", &transform([0.0, 0.0, 0.0], [1.0, 0.2, 1.0]));
+        index.insert("tile_b", &transform([1.0, 0.0, 0.0], [1.0, 0.2, 1.0]));
+        // A wall sunk into the first tile, and a copy of the second
+        index.insert("wall", &transform([0.0, 0.65, 0.0], [1.0, 1.2, 0.2]));
+        index.insert("tile_b_copy", &transform([1.0, 0.0, 0.0], [1.0, 0.2, 1.0]));
+
+        let overlaps = index.overlapping_pairs(0.01);
+        let pairs: Vec<(&str, &str, bool)> = overlaps
+            .iter()
+            .map(|o| (o.first.as_str(), o.second.as_str(), o.duplicate))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![("tile_a", "wall", false), ("tile_b", "tile_b_copy", true)]
+        );
+        assert!((overlaps[0].depth[2] - 0.2).abs() < 1e-5);
+
+        // The wall only sinks 0.05 into the tile
+        assert_eq!(index.overlapping_pairs(0.15).len(), 1);
+    }
+
+    #[test]
+    fn bounds_follow_rotation() {
+        // A 4 x 2 x 0.2 wall turned a quarter around Y runs along Z instead of X
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        let wall = Transform3D {
+            position: [1.0, 1.0, 1.0],
+            rotation: [0.0, half, 0.0, half],
+            scale: [4.0, 2.0, 0.2],
+        };
+        let bounds = BoundingBox::from_transform(&wall);
+        for (actual, expected) in bounds.min.iter().zip([0.9, 0.0, -1.0]) {
+            assert!((actual - expected).abs() < 1e-5, "{:?}", bounds);
+        }
+        for (actual, expected) in bounds.max.iter().zip([1.1, 2.0, 3.0]) {
+            assert!((actual - expected).abs() < 1e-5, "{:?}", bounds);
+        }
+
+        // Turned by 45 degrees, the wall leaves the +X +Z corner of its bounds empty
+        let angle = std::f32::consts::FRAC_PI_8;
+        let mut index = SpatialIndex::new();
+        index.insert(
+            "diagonal",
+            &Transform3D {
+                position: [0.0, 0.0, 0.0],
+                rotation: [0.0, angle.sin(), 0.0, angle.cos()],
+                scale: [4.0, 2.0, 0.2],
+            },
+        );
+        assert!(index
+            .raycast([1.3, 5.0, 1.3], [0.0, -1.0, 0.0], 100.0)
+            .is_empty());
+        assert_eq!(
+            index
+                .raycast([1.3, 5.0, -1.3], [0.0, -1.0, 0.0], 100.0)
+                .len(),
+            1
+        );
+        assert!(index
+            .query_nearest([1.3, 0.0, 1.3], 1, Some(0.5))
+            .is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+//! Deterministic output for levels kept in version control.
+//!
+//! Generated objects get IDs derived from the generation seed and their grid cell, so
+//! regenerating a level with the same seed gives the same IDs. Saving or exporting in
+//! deterministic mode also sorts objects by a stable key, rounds floats to a fixed
+//! precision, sorts map keys and leaves out timestamps, so an unchanged level is written
+//! byte for byte the same.
+
+use crate::{LevelData, ObjectKind};
+use serde::Serialize;
+use serde_json::{Number, Value};
+use uuid::Uuid;
+
+/// Decimal places floats are rounded to in deterministic output.
+pub const FLOAT_DECIMALS: i32 = 4;
+
+/// Namespace of generated IDs (the bytes of "morgan-bevy-gen.").
+const GENERATED_NAMESPACE: Uuid = Uuid::from_u128(0x6d6f_7267_616e_2d62_6576_792d_6765_6e2e);
+
+/// A stable ID for something generated from `seed`. `kind` tells apart things in the
+/// same cell, such as a floor and the wall above it.
+#[must_use]
+pub fn generated_id(seed: u64, kind: &str, cell: &[i64]) -> String {
+    generated_uuid(seed, kind, cell).to_string()
+}
+
+/// A number in `[0, 1)` that is always the same for the same seed, kind and cell, for
+/// random choices that should come out the same when a level is regenerated.
+#[must_use]
+pub fn generated_roll(seed: u64, kind: &str, cell: &[i64]) -> f64 {
+    // The low 53 bits stay clear of the UUID's version and variant bits
+    let bits = generated_uuid(seed, kind, cell).as_u128() as u64 & ((1 << 53) - 1);
+    bits as f64 / (1u64 << 53) as f64
+}
+
+fn generated_uuid(seed: u64, kind: &str, cell: &[i64]) -> Uuid {
+    let cell = cell
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    let name = format!("{}/{}/{}", seed, kind, cell);
+    Uuid::new_v5(&GENERATED_NAMESPACE, name.as_bytes())
+}
+
+fn round_f64(value: f64, decimals: i32) -> f64 {
+    let scale = 10f64.powi(decimals);
+    // Adding zero turns -0.0 into 0.0
+    (value * scale).round() / scale + 0.0
+}
+
+#[must_use]
+pub fn round_f32(value: f32) -> f32 {
+    round_f64(f64::from(value), FLOAT_DECIMALS) as f32
+}
+
+/// Rounds the transforms, path nodes and bounds in `level` to `decimals` places.
+pub fn round_level(level: &mut LevelData, decimals: i32) {
+    let round = |value: &mut f32| *value = round_f64(f64::from(*value), decimals) as f32;
+    for obj in &mut level.objects {
+        let transform = &mut obj.transform;
+        transform
+            .position
+            .iter_mut()
+            .chain(&mut transform.rotation)
+            .chain(&mut transform.scale)
+            .for_each(round);
+        if let ObjectKind::Path(ref mut path) = obj.kind {
+            for node in &mut path.nodes {
+                node.position.iter_mut().for_each(round);
+            }
+        }
+    }
+    level
+        .bounds
+        .min
+        .iter_mut()
+        .chain(&mut level.bounds.max)
+        .for_each(round);
+}
+
+/// `level` with its objects sorted by layer and ID, and transforms and bounds rounded.
+#[must_use]
+pub fn stable_level(level: &LevelData) -> LevelData {
+    let mut level = level.clone();
+    round_level(&mut level, FLOAT_DECIMALS);
+    level
+        .objects
+        .sort_by(|a, b| a.layer.cmp(&b.layer).then_with(|| a.id.cmp(&b.id)));
+    level
+}
+
+fn canonicalize(value: &mut Value) {
+    match value {
+        Value::Number(number) if number.is_f64() => {
+            if let Some(rounded) = number
+                .as_f64()
+                .map(|value| round_f64(value, FLOAT_DECIMALS))
+                .and_then(Number::from_f64)
+            {
+                *number = rounded;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(canonicalize),
+        Value::Object(map) => {
+            let mut entries: Vec<_> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            for (_, entry) in &mut entries {
+                canonicalize(entry);
+            }
+            *map = entries.into_iter().collect();
+        }
+        _ => {}
+    }
+}
+
+/// `value` as JSON with sorted keys and rounded floats.
+pub fn stable_value<T: Serialize>(value: &T) -> serde_json::Result<Value> {
+    let mut value = serde_json::to_value(value)?;
+    canonicalize(&mut value);
+    Ok(value)
+}
+
+/// Pretty JSON with sorted keys and rounded floats, ending in a newline.
+pub fn to_stable_json<T: Serialize>(value: &T) -> serde_json::Result<String> {
+    let mut json = serde_json::to_string_pretty(&stable_value(value)?)?;
+    json.push('\n');
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::bsp::BSPGenerator;
+    use crate::{BSPGenerationParams, CorridorStyle};
+
+    fn params() -> BSPGenerationParams {
+        BSPGenerationParams {
+            width: 24,
+            height: 24,
+            depth: 3,
+            min_room_size: 4,
+            max_room_size: 8,
+            corridor_width: 1,
+            theme: "office".to_string(),
+            seed: Some(7),
+            door_probability: 1.0,
+            corridor_style: CorridorStyle::LShaped,
+            corridor_width_variation: 0,
+            loop_factor: 0.0,
+            treasure_room_weight: 0.5,
+            shop_room_weight: 0.1,
+            spawns: None,
+        }
+    }
+
+    #[test]
+    fn regenerating_writes_the_same_bytes() {
+        let generator = BSPGenerator::new();
+        let mut first = tokio_test::block_on(generator.generate(params())).unwrap();
+        let mut second = tokio_test::block_on(generator.generate(params())).unwrap();
+        assert_eq!(first.id, second.id);
+
+        // Order and metadata map order don't matter
+        second.objects.reverse();
+        second.objects[0]
+            .metadata
+            .insert("b".to_string(), Value::from(0.1_f32));
+        second.objects[0]
+            .metadata
+            .insert("a".to_string(), Value::from(-0.000_01));
+        let last = first.objects.len() - 1;
+        first.objects[last]
+            .metadata
+            .insert("a".to_string(), Value::from(0.0));
+        first.objects[last]
+            .metadata
+            .insert("b".to_string(), Value::from(0.1));
+
+        let first_json = to_stable_json(&stable_level(&first)).unwrap();
+        let second_json = to_stable_json(&stable_level(&second)).unwrap();
+        assert_eq!(first_json, second_json);
+        assert!(first_json.contains("\"b\": 0.1,\n"));
+    }
+}
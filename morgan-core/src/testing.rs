@@ -0,0 +1,75 @@
+//! Helpers and fixtures shared by the tests of this crate and the crates built on it.
+//!
+//! Compiled for this crate's tests, and for other crates' with the `testing` feature.
+
+use crate::spatial::BoundingBox;
+use crate::{GameObject, LevelData};
+use std::collections::HashMap;
+
+/// Asserts two float arrays match to within rounding error.
+///
+/// # Panics
+///
+/// If any pair of values differs by more than rounding error.
+pub fn assert_close<const N: usize>(actual: [f32; N], expected: [f32; N]) {
+    assert!(
+        actual
+            .iter()
+            .zip(expected)
+            .all(|(a, b)| (a - b).abs() < 1e-5),
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+/// A plain object on `layer` at the origin.
+#[must_use]
+pub fn object(id: &str, layer: &str) -> GameObject {
+    GameObject {
+        layer: layer.to_string(),
+        ..GameObject::new(id, [0.0; 3])
+    }
+}
+
+/// An object on the `Props` layer at the origin, drawn with `mesh`.
+#[must_use]
+pub fn prop(id: &str, mesh: &str) -> GameObject {
+    GameObject {
+        mesh: Some(mesh.to_string()),
+        ..object(id, "Props")
+    }
+}
+
+/// A ground floor tile at `(x, z)` on the `Floors` layer, named and tagged `tag` the
+/// way generators tag their cells.
+#[must_use]
+pub fn tile(x: f32, z: f32, tag: &str) -> GameObject {
+    GameObject {
+        name: tag.to_string(),
+        layer: "Floors".to_string(),
+        tags: vec![tag.to_string()],
+        ..GameObject::new(&format!("{}_{}_{}", tag, x, z), [x, 0.0, z])
+    }
+}
+
+/// A [`tile`] on `floor` of a multi-floor level.
+#[must_use]
+pub fn floor_tile(x: f32, z: f32, floor: u32, tag: &str) -> GameObject {
+    GameObject {
+        id: format!("{}_{}_{}_{}", tag, x, z, floor),
+        metadata: HashMap::from([("floor".to_string(), floor.into())]),
+        ..tile(x, z, tag)
+    }
+}
+
+/// A 10 unit square level holding `objects`.
+#[must_use]
+pub fn level(objects: Vec<GameObject>) -> LevelData {
+    LevelData {
+        name: "Test".to_string(),
+        objects,
+        bounds: BoundingBox::new([0.0; 3], [10.0; 3]),
+        ..LevelData::empty("test")
+    }
+}
@@ -1,14 +1,61 @@
+pub mod atlas;
+pub mod backup;
+pub mod bundle;
+pub mod catalog;
+pub mod collect;
 pub mod database;
+pub mod fbx;
+pub mod file_types;
+pub mod import;
+pub mod jobs;
+pub mod kenney;
+pub mod lod;
+pub mod maintenance;
+pub mod metadata;
+pub mod migrations;
+pub mod paths;
+pub mod preview;
+pub mod references;
+pub mod roots;
 pub mod scanner;
+pub mod usage;
+pub mod watcher;
+pub mod waveform;
 
-use database::AssetSearchResult;
-use log::info;
-use scanner::{AssetScanner, DatabaseStats, ScanProgress, ScanResult};
+use crate::generation::themes::{Theme, ThemeLibrary, ThemeSlot, THEMES_DIRECTORY};
+use crate::level::events::{emit_level_changed, LevelChangeKind};
+use crate::{AppState, LevelData};
+use atlas::SpriteFrame;
+use backup::{DatabaseBackup, IntegrityReport};
+use bundle::BundleSummary;
+use catalog::{CatalogFormat, CatalogSummary};
+use collect::CollectSummary;
+use database::{
+    AssetChange, AssetDatabase, AssetQuery, AssetSearchPage, AssetSearchResult, AssetSortField,
+    ChecksumMode, MaterialTexture, MetadataFilter,
+};
+use file_types::{ExtensionConfig, FileTypes};
+use import::{ImportMode, ImportSummary};
+use jobs::{ScanJobStatus, ScanJobs, ScanKind, ScanOutcome};
+use kenney::{KenneyPack, KenneyPackStatus, PackInstallSummary, PackProgress, PackStage};
+use lod::LodGroup;
+use log::{info, warn};
+use maintenance::{CleanupSummary, StorageReport};
+use paths::AssetPathResolver;
+use preview::AssetPreview;
+use references::{AssetReferenceIssue, AssetReferenceReport};
+use roots::{AssetRoot, AssetRootScope};
+use scanner::{AssetScanner, DatabaseStats, RescanSummary, ScanProgress, ScanResult};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
 use tauri::{Emitter, Manager};
+use usage::{AssetDeleteSummary, AssetMoveSummary};
+use watcher::AssetWatcher;
+use waveform::Waveform;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AssetFile {
@@ -25,34 +72,113 @@ pub struct AssetSearchParams {
     pub query: String,
     pub asset_type: Option<String>,
     pub collection: Option<String>,
+    /// Metadata conditions every result must meet
+    pub metadata: Option<Vec<MetadataFilter>>,
     pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    pub sort_by: Option<AssetSortField>,
+    pub descending: Option<bool>,
 }
 
-// Asset database state for Tauri
+/// Page size for searches that don't specify one.
+const DEFAULT_SEARCH_LIMIT: usize = 200;
+/// Upper bound on a single search page.
+const MAX_SEARCH_LIMIT: usize = 1000;
+
+impl AssetSearchParams {
+    fn to_query(&self, default_limit: usize) -> AssetQuery {
+        AssetQuery {
+            query: self.query.clone(),
+            asset_type: self.asset_type.clone(),
+            collection: self.collection.clone(),
+            metadata: self.metadata.clone().unwrap_or_default(),
+            sort_by: self.sort_by.unwrap_or_default(),
+            descending: self.descending.unwrap_or(false),
+            offset: self.offset.unwrap_or(0),
+            limit: Some(
+                self.limit
+                    .unwrap_or(default_limit)
+                    .clamp(1, MAX_SEARCH_LIMIT),
+            ),
+        }
+    }
+}
+
+// Asset database state for Tauri. Scans and edits go through `scanner`; queries use
+// `reader`, a second read-only connection, so they don't queue behind a long scan.
 pub struct AssetDatabaseState {
     pub scanner: Arc<Mutex<Option<AssetScanner>>>,
+    pub reader: Arc<Mutex<Option<AssetDatabase>>>,
+    pub watcher: Mutex<Option<AssetWatcher>>,
+    pub scan_jobs: Arc<Mutex<ScanJobs>>,
 }
 
 impl AssetDatabaseState {
     pub fn new() -> Self {
         Self {
             scanner: Arc::new(Mutex::new(None)),
+            reader: Arc::new(Mutex::new(None)),
+            watcher: Mutex::new(None),
+            scan_jobs: Arc::new(Mutex::new(ScanJobs::default())),
         }
     }
 }
 
+/// Lock part of the asset state, failing instead of panicking if a thread panicked
+/// while holding it.
+pub fn lock<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>, String> {
+    mutex
+        .lock()
+        .map_err(|_| "Asset database lock poisoned".to_string())
+}
+
+/// Run database work on the blocking thread pool instead of an async worker.
+pub async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Asset database task failed: {}", e))?
+}
+
+/// Run `f` with exclusive use of the scanner, for scans and anything that writes.
+async fn with_scanner<T: Send + 'static>(
+    app_handle: &tauri::AppHandle,
+    f: impl FnOnce(&mut AssetScanner) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let scanner = app_handle.state::<AssetDatabaseState>().scanner.clone();
+    run_blocking(move || {
+        let mut scanner_guard = lock(&scanner)?;
+        let scanner = scanner_guard
+            .as_mut()
+            .ok_or("Asset database not initialized")?;
+        f(scanner)
+    })
+    .await
+}
+
+/// Run a query on the read-only connection, which stays usable while a scan runs.
+async fn with_reader<T: Send + 'static>(
+    app_handle: &tauri::AppHandle,
+    f: impl FnOnce(&AssetDatabase) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let reader = app_handle.state::<AssetDatabaseState>().reader.clone();
+    run_blocking(move || {
+        let reader_guard = lock(&reader)?;
+        let database = reader_guard
+            .as_ref()
+            .ok_or("Asset database not initialized")?;
+        f(database)
+    })
+    .await
+}
+
 #[tauri::command]
 pub async fn initialize_asset_database(app_handle: tauri::AppHandle) -> Result<(), String> {
     info!("Initializing asset database");
 
-    // Get app data directory
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
     // Ensure .morgana directory exists
-    let morgana_dir = app_data_dir.join(".morgana");
+    let morgana_dir = morgana_directory(&app_handle)?;
     if !morgana_dir.exists() {
         fs::create_dir_all(&morgana_dir)
             .map_err(|e| format!("Failed to create .morgana directory: {}", e))?;
@@ -61,51 +187,603 @@ pub async fn initialize_asset_database(app_handle: tauri::AppHandle) -> Result<(
     // Create database path
     let db_path = morgana_dir.join("assets.db");
 
-    // Initialize scanner with database
-    let scanner = AssetScanner::new(&db_path)
-        .map_err(|e| format!("Failed to initialize asset scanner: {}", e))?;
+    // Project-specific extensions
+    let file_types = match extension_config_path() {
+        Some(config_path) => FileTypes::with_config(&ExtensionConfig::load(&config_path)?)?,
+        None => FileTypes::default(),
+    };
+
+    // Initialize scanner with database; opening it may run schema migrations
+    let (scanner, reader) = run_blocking(move || {
+        let mut scanner = AssetScanner::new(&db_path)
+            .map_err(|e| format!("Failed to initialize asset scanner: {}", e))?;
+        scanner.database_mut().set_file_types(file_types);
+        let reader = AssetDatabase::open_reader(&db_path)
+            .map_err(|e| format!("Failed to open asset database for reading: {}", e))?;
+        Ok((scanner, reader))
+    })
+    .await?;
 
-    // Store scanner in app state
+    // Store scanner and read connection in app state
     let state: tauri::State<AssetDatabaseState> = app_handle.state();
-    let mut scanner_lock = state.scanner.lock().unwrap();
-    *scanner_lock = Some(scanner);
+    *lock(&state.scanner)? = Some(scanner);
+    *lock(&state.reader)? = Some(reader);
 
     info!("Asset database initialized successfully");
+
+    // Keep the database current as files change; scanning still works without it
+    if let Err(e) = start_watching(&app_handle) {
+        warn!("Asset watcher not started: {}", e);
+    }
+
+    // Compact the database now and then; this waits behind any scan started meanwhile
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = with_scanner(&handle, |scanner| {
+            maintenance::vacuum_if_due(scanner.database())
+        })
+        .await;
+        match result {
+            Ok(true) => info!("Ran scheduled asset database vacuum"),
+            Ok(false) => {}
+            Err(e) => warn!("{}", e),
+        }
+    });
     Ok(())
 }
 
+fn backup_directory(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    morgana_directory(app_handle).map(|dir| dir.join("backups"))
+}
+
+#[tauri::command]
+pub async fn backup_asset_database(app_handle: tauri::AppHandle) -> Result<DatabaseBackup, String> {
+    let backup_dir = backup_directory(&app_handle)?;
+    with_reader(&app_handle, move |database| {
+        backup::create_backup(database, &backup_dir)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_asset_database_backups(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<DatabaseBackup>, String> {
+    backup::list_backups(&backup_directory(&app_handle)?)
+}
+
+/// Replace the asset database with a backup made by `backup_asset_database`.
 #[tauri::command]
-pub async fn scan_assets_database(app_handle: tauri::AppHandle) -> Result<ScanResult, String> {
+pub async fn restore_asset_database(
+    name: String,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    let backup_dir = backup_directory(&app_handle)?;
+    with_scanner(&app_handle, move |scanner| {
+        backup::restore_backup(scanner.database_mut(), &backup_dir, &name)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn check_asset_database_integrity(
+    app_handle: tauri::AppHandle,
+) -> Result<IntegrityReport, String> {
+    with_reader(&app_handle, backup::check_integrity).await
+}
+
+/// Disk usage per collection and asset type, plus orphaned previews and rows.
+#[tauri::command]
+pub async fn get_asset_storage_report(
+    app_handle: tauri::AppHandle,
+) -> Result<StorageReport, String> {
+    let cache_dir = preview_cache_directory(&app_handle)?;
+    with_reader(&app_handle, move |database| {
+        maintenance::storage_report(database, &cache_dir)
+    })
+    .await
+}
+
+/// Delete orphaned previews and rows, then compact the database.
+#[tauri::command]
+pub async fn clean_up_asset_storage(
+    app_handle: tauri::AppHandle,
+) -> Result<CleanupSummary, String> {
+    let cache_dir = preview_cache_directory(&app_handle)?;
+    with_scanner(&app_handle, move |scanner| {
+        maintenance::clean_up(scanner.database_mut(), &cache_dir)
+    })
+    .await
+}
+
+/// Rendered asset previews, named by content checksum.
+fn preview_cache_directory(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    morgana_directory(app_handle).map(|dir| dir.join("thumbnails"))
+}
+
+/// The editor's `.morgana` folder in the app data directory, which holds the asset database.
+pub fn morgana_directory(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(".morgana"))
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+fn start_watching(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let asset_roots = roots::scan_roots(app_handle)?;
+    let state: tauri::State<AssetDatabaseState> = app_handle.state();
+    let watcher = AssetWatcher::start(asset_roots, state.scanner.clone(), app_handle.clone())?;
+    *lock(&state.watcher)? = Some(watcher);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn start_asset_watcher(app_handle: tauri::AppHandle) -> Result<(), String> {
+    start_watching(&app_handle)
+}
+
+#[tauri::command]
+pub async fn stop_asset_watcher(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let state: tauri::State<AssetDatabaseState> = app_handle.state();
+    let watcher = lock(&state.watcher)?.take();
+    match watcher {
+        Some(watcher) => {
+            info!("Stopped watching asset directories: {:?}", watcher.roots());
+            Ok(())
+        }
+        None => Err("Asset watcher is not running".to_string()),
+    }
+}
+
+/// Index every asset root, waiting for the scan to finish. Files are hashed in full
+/// unless `checksum_mode` asks for fingerprints.
+#[tauri::command]
+pub async fn scan_assets_database(
+    app_handle: tauri::AppHandle,
+    checksum_mode: Option<ChecksumMode>,
+) -> Result<ScanResult, String> {
     info!("Starting comprehensive asset database scan");
 
+    let (job_id, cancel) = begin_scan_job(&app_handle, ScanKind::Full)?;
+    match run_scan_job(&app_handle, job_id, ScanKind::Full, checksum_mode, cancel).await? {
+        ScanOutcome::Full(result) => {
+            info!(
+                "Asset scan completed: {} assets processed",
+                result.total_assets
+            );
+            Ok(result)
+        }
+        ScanOutcome::Incremental(_) => unreachable!("full scan job produced a rescan summary"),
+    }
+}
+
+/// Rescan only what changed since the last scan and prune deleted files.
+#[tauri::command]
+pub async fn rescan_assets_database(
+    app_handle: tauri::AppHandle,
+    checksum_mode: Option<ChecksumMode>,
+) -> Result<RescanSummary, String> {
+    info!("Starting incremental asset database rescan");
+
+    let (job_id, cancel) = begin_scan_job(&app_handle, ScanKind::Incremental)?;
+    match run_scan_job(
+        &app_handle,
+        job_id,
+        ScanKind::Incremental,
+        checksum_mode,
+        cancel,
+    )
+    .await?
+    {
+        ScanOutcome::Incremental(summary) => Ok(summary),
+        ScanOutcome::Full(_) => unreachable!("incremental scan job produced a full scan result"),
+    }
+}
+
+/// Start a scan in the background and return its job ID right away.
+///
+/// Progress is emitted as `asset_scan_progress` and the final status as
+/// `asset_scan_finished`; fails if another scan is already running.
+#[tauri::command]
+pub async fn start_scan(
+    app_handle: tauri::AppHandle,
+    kind: ScanKind,
+    checksum_mode: Option<ChecksumMode>,
+) -> Result<u64, String> {
+    let (job_id, cancel) = begin_scan_job(&app_handle, kind)?;
+    info!("Started scan job {} ({:?})", job_id, kind);
+
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = run_scan_job(&handle, job_id, kind, checksum_mode, cancel).await {
+            warn!("Scan job {} failed: {}", job_id, e);
+        }
+    });
+    Ok(job_id)
+}
+
+/// Status of a scan job, or of the current (else most recent) scan without an ID.
+#[tauri::command]
+pub async fn get_scan_status(
+    app_handle: tauri::AppHandle,
+    job_id: Option<u64>,
+) -> Result<Option<ScanJobStatus>, String> {
     let state: tauri::State<AssetDatabaseState> = app_handle.state();
-    let mut scanner_guard = state.scanner.lock().unwrap();
+    let jobs = lock(&state.scan_jobs)?;
+    Ok(jobs.status(job_id))
+}
 
-    let scanner = scanner_guard
-        .as_mut()
-        .ok_or("Asset database not initialized")?;
+/// Stop a running scan after its current batch; files indexed so far are kept.
+#[tauri::command]
+pub async fn cancel_scan(app_handle: tauri::AppHandle, job_id: u64) -> Result<(), String> {
+    let state: tauri::State<AssetDatabaseState> = app_handle.state();
+    let mut jobs = lock(&state.scan_jobs)?;
+    jobs.cancel(job_id)?;
+    info!("Cancelling scan job {}", job_id);
+    Ok(())
+}
+
+fn begin_scan_job(
+    app_handle: &tauri::AppHandle,
+    kind: ScanKind,
+) -> Result<(u64, Arc<AtomicBool>), String> {
+    let state: tauri::State<AssetDatabaseState> = app_handle.state();
+    let mut jobs = lock(&state.scan_jobs)?;
+    jobs.start(kind)
+}
+
+/// Scan every asset root for a registered job, stopping between roots or batches once
+/// the job is cancelled, then record and announce how it ended.
+async fn run_scan_job(
+    app_handle: &tauri::AppHandle,
+    job_id: u64,
+    kind: ScanKind,
+    checksum_mode: Option<ChecksumMode>,
+    cancel: Arc<AtomicBool>,
+) -> Result<ScanOutcome, String> {
+    let checksum_mode = checksum_mode.unwrap_or_default();
+    let jobs = app_handle.state::<AssetDatabaseState>().scan_jobs.clone();
+    let result = async {
+        let asset_roots = roots::scan_roots(app_handle)?;
+        let handle = app_handle.clone();
+        let job_progress = jobs.clone();
+        with_scanner(app_handle, move |scanner| {
+            let mut outcome = match kind {
+                ScanKind::Full => ScanOutcome::Full(ScanResult::default()),
+                ScanKind::Incremental => ScanOutcome::Incremental(RescanSummary::default()),
+            };
+            for assets_dir in asset_roots {
+                if cancel.load(Ordering::Relaxed) {
+                    match &mut outcome {
+                        ScanOutcome::Full(result) => result.cancelled = true,
+                        ScanOutcome::Incremental(summary) => summary.cancelled = true,
+                    }
+                    break;
+                }
+
+                let progress_callback = {
+                    let handle = handle.clone();
+                    let jobs = job_progress.clone();
+                    Box::new(move |progress: ScanProgress| {
+                        let _ = handle.emit("asset_scan_progress", &progress);
+                        if let Ok(mut jobs) = jobs.lock() {
+                            jobs.report_progress(job_id, progress);
+                        }
+                    })
+                };
+                match &mut outcome {
+                    ScanOutcome::Full(result) => result.merge(
+                        scanner
+                            .scan_directory(
+                                &assets_dir,
+                                checksum_mode,
+                                Some(progress_callback),
+                                Some(&cancel),
+                            )
+                            .map_err(|e| format!("Asset scan of {:?} failed: {}", assets_dir, e))?,
+                    ),
+                    ScanOutcome::Incremental(summary) => summary.merge(
+                        scanner
+                            .rescan_directory(
+                                &assets_dir,
+                                checksum_mode,
+                                Some(progress_callback),
+                                Some(&cancel),
+                            )
+                            .map_err(|e| {
+                                format!("Asset rescan of {:?} failed: {}", assets_dir, e)
+                            })?,
+                    ),
+                }
+            }
+            Ok(outcome)
+        })
+        .await
+    }
+    .await;
 
-    // Find Assets directory
+    let status = lock(&jobs)?.finish(job_id, result.clone());
+    if let Some(status) = status {
+        let _ = app_handle.emit("asset_scan_finished", &status);
+    }
+    result
+}
+
+/// Copy (or link) external files into `Assets/<collection>` and index them.
+#[tauri::command]
+pub async fn import_assets(
+    paths: Vec<String>,
+    collection: String,
+    mode: Option<ImportMode>,
+    app_handle: tauri::AppHandle,
+) -> Result<ImportSummary, String> {
     let assets_dir = find_assets_directory().ok_or("Assets directory not found")?;
+    let sources: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
 
-    // Create progress callback
-    let progress_callback = {
+    with_scanner(&app_handle, move |scanner| {
+        import::import_files(
+            scanner.database_mut(),
+            &sources,
+            &assets_dir,
+            &collection,
+            mode.unwrap_or_default(),
+        )
+        .map_err(|e| format!("Asset import failed: {}", e))
+    })
+    .await
+}
+
+/// Managed asset root that downloaded packs are extracted into.
+fn packs_directory(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    morgana_directory(app_handle).map(|dir| dir.join("packs"))
+}
+
+/// Pack catalogs: the app-wide one, then the project's, which can override it.
+fn kenney_catalog_files(app_handle: &tauri::AppHandle) -> Result<Vec<PathBuf>, String> {
+    let mut files = vec![morgana_directory(app_handle)?.join(kenney::CATALOG_FILE)];
+    if let Some(project) = project_directory() {
+        files.push(project.join(".morgan").join(kenney::CATALOG_FILE));
+    }
+    Ok(files)
+}
+
+#[tauri::command]
+pub async fn list_kenney_packs(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<KenneyPackStatus>, String> {
+    let packs_dir = packs_directory(&app_handle)?;
+    Ok(kenney::load_catalog(&kenney_catalog_files(&app_handle)?)?
+        .into_iter()
+        .map(|pack| KenneyPackStatus {
+            installed: kenney::is_installed(&packs_dir, &pack.id),
+            pack,
+        })
+        .collect())
+}
+
+/// Download catalog packs, extract them into the managed packs root and index them.
+///
+/// Progress is reported through `kenney_pack_progress` events, then `asset_scan_progress`
+/// while indexing. A pack that fails doesn't stop the others. Packs the catalog pins no
+/// checksum for are listed in `unverified` and skipped unless `allow_unverified` is set.
+#[tauri::command]
+pub async fn download_kenney_packs(
+    pack_ids: Vec<String>,
+    allow_unverified: Option<bool>,
+    app_handle: tauri::AppHandle,
+) -> Result<PackInstallSummary, String> {
+    let catalog = kenney::load_catalog(&kenney_catalog_files(&app_handle)?)?;
+    let packs = pack_ids
+        .iter()
+        .map(|id| {
+            catalog
+                .iter()
+                .find(|pack| pack.id == *id)
+                .cloned()
+                .ok_or_else(|| format!("Unknown Kenney pack: {}", id))
+        })
+        .collect::<Result<Vec<KenneyPack>, String>>()?;
+    let packs_dir = packs_directory(&app_handle)?;
+
+    let client = reqwest::Client::new();
+    let progress = {
         let handle = app_handle.clone();
-        Box::new(move |progress: ScanProgress| {
-            let _ = handle.emit("asset_scan_progress", &progress);
+        move |progress: PackProgress| {
+            let _ = handle.emit("kenney_pack_progress", &progress);
+        }
+    };
+
+    let mut summary = PackInstallSummary::default();
+    for pack in packs {
+        if pack.sha256.is_none() && !allow_unverified.unwrap_or(false) {
+            info!("Kenney pack {} has no pinned checksum; skipping", pack.id);
+            summary.unverified.push(pack.id);
+            continue;
+        }
+
+        info!("Downloading Kenney pack {} from {}", pack.id, pack.url);
+        let target = kenney::pack_directory(&packs_dir, &pack.id);
+        let archive = target.with_extension("zip.download");
+        let download = match kenney::download_pack(&client, &pack, &archive, &progress).await {
+            Ok(download) => download,
+            Err(e) => {
+                let _ = fs::remove_file(&archive);
+                warn!("{}", e);
+                summary.errors.push(e);
+                continue;
+            }
+        };
+
+        let stage_progress = progress.clone();
+        let installed = run_blocking(move || {
+            let report = |stage: PackStage| {
+                stage_progress(PackProgress {
+                    pack_id: pack.id.clone(),
+                    stage,
+                    downloaded_bytes: download.size,
+                    total_bytes: Some(download.size),
+                });
+            };
+            report(PackStage::Verifying);
+            let installed = kenney::verify_checksum(&pack, &download.sha256).and_then(|verified| {
+                report(PackStage::Extracting);
+                let files = kenney::extract_pack(&download.path, &target)?;
+                Ok(kenney::InstalledPack {
+                    id: pack.id.clone(),
+                    files,
+                    sha256: download.sha256,
+                    verified,
+                })
+            });
+            let _ = fs::remove_file(&download.path);
+            installed
         })
+        .await;
+
+        match installed {
+            Ok(installed) => {
+                info!(
+                    "Installed Kenney pack {} ({} files)",
+                    installed.id, installed.files
+                );
+                summary.installed.push(installed);
+            }
+            Err(e) => {
+                warn!("{}", e);
+                summary.errors.push(e);
+            }
+        }
+    }
+
+    if !summary.installed.is_empty() {
+        if roots::ensure_root(&app_handle, &packs_dir, AssetRootScope::Global)? {
+            info!("Registered Kenney packs directory as an asset root");
+            restart_watcher(&app_handle);
+        }
+
+        let handle = app_handle.clone();
+        summary.scan = Some(
+            with_scanner(&app_handle, move |scanner| {
+                let progress_callback = Box::new(move |progress: ScanProgress| {
+                    let _ = handle.emit("asset_scan_progress", &progress);
+                });
+                scanner
+                    .rescan_directory(
+                        &packs_dir,
+                        ChecksumMode::Full,
+                        Some(progress_callback),
+                        None,
+                    )
+                    .map_err(|e| format!("Indexing Kenney packs failed: {}", e))
+            })
+            .await?,
+        );
+    }
+    Ok(summary)
+}
+
+/// Project file with changes to the accepted asset extensions.
+fn extension_config_path() -> Option<PathBuf> {
+    project_directory().map(|project| project.join(".morgan").join("asset_types.json"))
+}
+
+/// The project's extension overrides and the resulting extensions per asset type.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssetExtensions {
+    pub config: ExtensionConfig,
+    pub extensions: std::collections::BTreeMap<String, Vec<String>>,
+}
+
+fn asset_extensions(config: ExtensionConfig, file_types: &FileTypes) -> AssetExtensions {
+    AssetExtensions {
+        config,
+        extensions: file_types
+            .extensions_by_type()
+            .into_iter()
+            .map(|(asset_type, extensions)| (asset_type.to_string(), extensions))
+            .collect(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_asset_extensions() -> Result<AssetExtensions, String> {
+    let config = match extension_config_path() {
+        Some(path) => ExtensionConfig::load(&path)?,
+        None => ExtensionConfig::default(),
     };
+    let file_types = FileTypes::with_config(&config)?;
+    Ok(asset_extensions(config, &file_types))
+}
 
-    // Perform scan
-    let result = scanner
-        .scan_directory(&assets_dir, Some(progress_callback))
-        .map_err(|e| format!("Asset scan failed: {}", e))?;
+/// Save the project's extension overrides; they apply to the next scan.
+#[tauri::command]
+pub async fn set_asset_extensions(
+    config: ExtensionConfig,
+    app_handle: tauri::AppHandle,
+) -> Result<AssetExtensions, String> {
+    let file_types = FileTypes::with_config(&config)?;
+    let path = extension_config_path().ok_or("Project directory not found")?;
+    config.save(&path)?;
 
-    info!(
-        "Asset scan completed: {} assets processed",
-        result.total_assets
-    );
-    Ok(result)
+    let scanner = app_handle.state::<AssetDatabaseState>().scanner.clone();
+    let scanner_file_types = file_types.clone();
+    run_blocking(move || {
+        if let Some(scanner) = lock(&scanner)?.as_mut() {
+            scanner.database_mut().set_file_types(scanner_file_types);
+        }
+        Ok(())
+    })
+    .await?;
+    info!("Updated asset extensions for project");
+    Ok(asset_extensions(config, &file_types))
+}
+
+#[tauri::command]
+pub async fn list_asset_roots(app_handle: tauri::AppHandle) -> Result<Vec<AssetRoot>, String> {
+    roots::asset_roots(&app_handle)
+}
+
+/// Register an extra asset directory for this project or for all projects.
+#[tauri::command]
+pub async fn add_asset_root(
+    path: String,
+    scope: AssetRootScope,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<AssetRoot>, String> {
+    roots::add_root(&app_handle, Path::new(&path), scope)?;
+    info!("Added {:?} asset root: {}", scope, path);
+    restart_watcher(&app_handle);
+    roots::asset_roots(&app_handle)
+}
+
+#[tauri::command]
+pub async fn remove_asset_root(
+    path: String,
+    scope: AssetRootScope,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<AssetRoot>, String> {
+    roots::remove_root(&app_handle, Path::new(&path), scope)?;
+    info!("Removed {:?} asset root: {}", scope, path);
+    restart_watcher(&app_handle);
+    roots::asset_roots(&app_handle)
+}
+
+/// Pick up a changed set of roots if the watcher is running.
+fn restart_watcher(app_handle: &tauri::AppHandle) {
+    let state: tauri::State<AssetDatabaseState> = app_handle.state();
+    let running = match lock(&state.watcher) {
+        Ok(mut watcher) => watcher.take().is_some(),
+        Err(e) => {
+            warn!("Asset watcher not restarted: {}", e);
+            return;
+        }
+    };
+    if running {
+        if let Err(e) = start_watching(app_handle) {
+            warn!("Asset watcher not restarted: {}", e);
+        }
+    }
 }
 
 #[tauri::command]
@@ -113,59 +791,757 @@ pub async fn search_assets_database(
     params: AssetSearchParams,
     app_handle: tauri::AppHandle,
 ) -> Result<Vec<AssetSearchResult>, String> {
-    let state: tauri::State<AssetDatabaseState> = app_handle.state();
-    let scanner_guard = state.scanner.lock().unwrap();
-
-    let scanner = scanner_guard
-        .as_ref()
-        .ok_or("Asset database not initialized")?;
-
-    let results = scanner
-        .database()
-        .search_assets(
-            &params.query,
-            params.asset_type.as_deref(),
-            params.collection.as_deref(),
+    let query = params.to_query(MAX_SEARCH_LIMIT);
+    let page = with_reader(&app_handle, move |database| {
+        database
+            .search_assets_page(&query)
+            .map_err(|e| format!("Search failed: {}", e))
+    })
+    .await?;
+
+    Ok(page.results)
+}
+
+/// Sorted, paginated search that also reports the total number of matches.
+#[tauri::command]
+pub async fn search_assets_page(
+    params: AssetSearchParams,
+    app_handle: tauri::AppHandle,
+) -> Result<AssetSearchPage, String> {
+    let query = params.to_query(DEFAULT_SEARCH_LIMIT);
+    with_reader(&app_handle, move |database| {
+        database
+            .search_assets_page(&query)
+            .map_err(|e| format!("Search failed: {}", e))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn set_asset_rating(
+    asset_id: i64,
+    rating: Option<u8>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    if rating.is_some_and(|rating| !(1..=5).contains(&rating)) {
+        return Err("Rating must be between 1 and 5".to_string());
+    }
+
+    let found = with_scanner(&app_handle, move |scanner| {
+        scanner
+            .database_mut()
+            .set_asset_rating(asset_id, rating)
+            .map_err(|e| format!("Failed to set rating: {}", e))
+    })
+    .await?;
+    if found {
+        Ok(())
+    } else {
+        Err(format!("Asset not found: {}", asset_id))
+    }
+}
+
+/// Move an asset to `destination`, a path relative to its asset root such as `Props/crate.glb`.
+///
+/// The database row keeps its ID, so metadata, tags and thumbnails follow the file;
+/// saved levels known to use the asset and the open level are rewritten to the new path.
+#[tauri::command]
+pub async fn move_asset(
+    asset_id: i64,
+    destination: String,
+    state: tauri::State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<AssetMoveSummary, String> {
+    relocate_asset(asset_id, destination, &state, &app_handle).await
+}
+
+/// Rename an asset's file in place; see [`move_asset`].
+#[tauri::command]
+pub async fn rename_asset(
+    asset_id: i64,
+    new_name: String,
+    state: tauri::State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<AssetMoveSummary, String> {
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() || new_name.starts_with('.') || new_name.contains(['/', '\\']) {
+        return Err(format!("Invalid asset name: {}", new_name));
+    }
+
+    let destination = with_reader(&app_handle, move |database| {
+        let asset = database
+            .get_asset_by_id(asset_id)
+            .map_err(|e| format!("Failed to look up asset {}: {}", asset_id, e))?
+            .ok_or_else(|| format!("Asset not found: {}", asset_id))?
+            .asset;
+        let root = asset
+            .asset_root
+            .ok_or("Asset has no recorded root; rescan before renaming it")?;
+        let relative = Path::new(&asset.file_path)
+            .strip_prefix(&root)
+            .map_err(|_| format!("Asset is outside its root: {}", asset.file_path))?;
+        Ok(relative
+            .with_file_name(&new_name)
+            .to_string_lossy()
+            .to_string())
+    })
+    .await?;
+    relocate_asset(asset_id, destination, &state, &app_handle).await
+}
+
+async fn relocate_asset(
+    asset_id: i64,
+    destination: String,
+    state: &tokio::sync::RwLock<AppState>,
+    app_handle: &tauri::AppHandle,
+) -> Result<AssetMoveSummary, String> {
+    // The file moves inside the scanner lock so the watcher never sees it unindexed
+    let resolver = AssetPathResolver::for_project();
+    let moved = with_scanner(app_handle, move |scanner| {
+        usage::move_asset_file(
+            scanner.database_mut(),
+            asset_id,
+            &destination,
+            resolver.as_ref(),
         )
-        .map_err(|e| format!("Search failed: {}", e))?;
+    })
+    .await?;
+
+    let mut summary = AssetMoveSummary {
+        asset_id,
+        old_path: moved.old_path.clone(),
+        new_path: moved.new_path.clone(),
+        ..Default::default()
+    };
+
+    let level_paths: BTreeSet<String> = moved
+        .usages
+        .iter()
+        .map(|usage| usage.level_path.clone())
+        .collect();
+    let replacements = moved.replacements.clone();
+    let rewritten = run_blocking(move || {
+        Ok(level_paths
+            .into_iter()
+            .map(|path| {
+                let result = usage::rewrite_level_file(Path::new(&path), &replacements);
+                (path, result)
+            })
+            .collect::<Vec<_>>())
+    })
+    .await?;
+    for (path, result) in rewritten {
+        match result {
+            Ok((level, changed)) => {
+                if changed > 0 {
+                    summary.updated_levels.push(path.clone());
+                    summary.updated_objects += changed;
+                }
+                track_level_usage(app_handle, &path, level);
+            }
+            Err(e) => summary.errors.push(e),
+        }
+    }
+
+    let theme_path = theme_asset_path(AssetPathResolver::for_project().as_ref(), &moved.new_path);
+    match run_blocking(move || theme_library().rebind_moved_asset(asset_id, &theme_path)).await {
+        Ok(themes) => summary.updated_themes = themes,
+        Err(e) => summary.errors.push(e),
+    }
+
+    let changed_ids = state
+        .write()
+        .await
+        .current_level
+        .as_mut()
+        .map(|level| usage::rewrite_references(level, &moved.replacements))
+        .unwrap_or_default();
+    if !changed_ids.is_empty() {
+        summary.updated_objects += changed_ids.len();
+        emit_level_changed(app_handle, LevelChangeKind::ObjectsUpdated, changed_ids);
+    }
 
-    Ok(results)
+    info!(
+        "Moved asset {} to {}, updating {} objects in {} saved levels",
+        asset_id,
+        summary.new_path,
+        summary.updated_objects,
+        summary.updated_levels.len()
+    );
+    for error in &summary.errors {
+        warn!(
+            "Failed to update level after moving asset {}: {}",
+            asset_id, error
+        );
+    }
+    Ok(summary)
 }
 
+/// Delete assets from the database and, if `delete_files` is set, from disk.
+///
+/// The summary lists saved levels that still reference the deleted assets.
 #[tauri::command]
-pub async fn get_asset_database_stats(
+pub async fn delete_assets(
+    ids: Vec<i64>,
+    delete_files: bool,
     app_handle: tauri::AppHandle,
-) -> Result<DatabaseStats, String> {
-    let state: tauri::State<AssetDatabaseState> = app_handle.state();
-    let scanner_guard = state.scanner.lock().unwrap();
+) -> Result<AssetDeleteSummary, String> {
+    let preview_cache = preview_cache_directory(&app_handle)?;
+    let summary = with_scanner(&app_handle, move |scanner| {
+        Ok(usage::delete_assets(
+            scanner.database_mut(),
+            &ids,
+            delete_files,
+            &preview_cache,
+        ))
+    })
+    .await?;
+
+    info!(
+        "Deleted {} assets ({} files)",
+        summary.deleted.len(),
+        summary.deleted_files
+    );
+    for usage in &summary.referencing_levels {
+        warn!(
+            "Deleted asset {} is still used by {} objects in {}",
+            usage.asset_id, usage.object_count, usage.level_path
+        );
+    }
+    for error in &summary.errors {
+        warn!("{}", error);
+    }
+    Ok(summary)
+}
+
+/// Record which assets a saved level uses, in the background.
+pub fn track_level_usage(app_handle: &tauri::AppHandle, level_path: &str, level: LevelData) {
+    let level_path = Path::new(level_path).canonicalize().map_or_else(
+        |_| level_path.to_string(),
+        |path| path.to_string_lossy().to_string(),
+    );
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let resolver = AssetPathResolver::for_project();
+        let result = with_scanner(&handle, move |scanner| {
+            let usages =
+                usage::level_usage(&level_path, &level, resolver.as_ref(), scanner.database());
+            scanner
+                .database_mut()
+                .record_level_usage(&level_path, &usages)
+                .map_err(|e| format!("Failed to record asset usage for {}: {}", level_path, e))
+        })
+        .await;
+        if let Err(e) = result {
+            warn!("{}", e);
+        }
+    });
+}
+
+/// Times an asset's contents changed on disk, newest first.
+#[tauri::command]
+pub async fn get_asset_history(
+    asset_id: i64,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<AssetChange>, String> {
+    with_reader(&app_handle, move |database| {
+        database
+            .get_asset_history(asset_id)
+            .map_err(|e| format!("Failed to get asset history: {}", e))
+    })
+    .await
+}
+
+/// Textures a material file refers to, with the indexed assets they resolve to.
+#[tauri::command]
+pub async fn get_material_textures(
+    asset_id: i64,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<MaterialTexture>, String> {
+    with_reader(&app_handle, move |database| {
+        database
+            .get_material_textures(asset_id)
+            .map_err(|e| format!("Failed to read material textures: {}", e))
+    })
+    .await
+}
+
+/// Materials that use a texture asset.
+#[tauri::command]
+pub async fn find_materials_using_texture(
+    asset_id: i64,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<AssetSearchResult>, String> {
+    with_reader(&app_handle, move |database| {
+        database
+            .find_materials_using_texture(asset_id)
+            .map_err(|e| format!("Failed to find materials: {}", e))
+    })
+    .await
+}
+
+/// Peak amplitudes of an audio asset for drawing its waveform, if one was generated.
+#[tauri::command]
+pub async fn get_asset_waveform(
+    asset_id: i64,
+    app_handle: tauri::AppHandle,
+) -> Result<Option<Waveform>, String> {
+    with_reader(&app_handle, move |database| {
+        database
+            .get_waveform(asset_id)
+            .map_err(|e| format!("Failed to get waveform: {}", e))
+    })
+    .await
+}
+
+/// The LOD set a model belongs to by its `_LOD<n>` name; `None` for standalone models.
+#[tauri::command]
+pub async fn get_lod_group(
+    asset_id: i64,
+    app_handle: tauri::AppHandle,
+) -> Result<Option<LodGroup>, String> {
+    with_reader(&app_handle, move |database| {
+        database
+            .get_lod_group(asset_id)
+            .map_err(|e| format!("Failed to get LOD group: {}", e))
+    })
+    .await
+}
+
+/// Every frame of a sprite sheet texture; `None` if it isn't one.
+#[tauri::command]
+pub async fn get_sprite_frames(
+    asset_id: i64,
+    app_handle: tauri::AppHandle,
+) -> Result<Option<Vec<SpriteFrame>>, String> {
+    with_reader(&app_handle, move |database| {
+        database
+            .get_sprite_frames(asset_id)
+            .map_err(|e| format!("Failed to get sprite frames: {}", e))
+    })
+    .await
+}
+
+/// One sprite sheet frame, by name or by index.
+#[tauri::command]
+pub async fn get_sprite_frame(
+    asset_id: i64,
+    frame: String,
+    app_handle: tauri::AppHandle,
+) -> Result<SpriteFrame, String> {
+    let frames = get_sprite_frames(asset_id, app_handle)
+        .await?
+        .ok_or_else(|| format!("Asset {} is not a sprite sheet", asset_id))?;
+    atlas::find_frame(&frames, &frame)
+        .cloned()
+        .ok_or_else(|| format!("Sprite sheet {} has no frame {}", asset_id, frame))
+}
+
+/// Assign an asset to a theme slot, such as the floor's diffuse map or a door mesh variant.
+///
+/// The theme is saved to the project's `themes` folder with the binding, which is updated
+/// whenever the asset is moved or renamed.
+#[tauri::command]
+pub async fn bind_theme_slot(
+    theme_id: String,
+    slot: ThemeSlot,
+    asset_id: i64,
+    app_handle: tauri::AppHandle,
+) -> Result<Theme, String> {
+    let mut theme = theme_library()
+        .get_theme(&theme_id)
+        .ok_or_else(|| format!("Theme not found: {}", theme_id))?;
+    let expected = match slot {
+        ThemeSlot::Material { .. } => file_types::TEXTURE,
+        ThemeSlot::MeshVariant { .. } => file_types::MODEL,
+    };
+    let resolver = AssetPathResolver::for_project();
+    let path = with_reader(&app_handle, move |database| {
+        let asset = database
+            .get_asset_by_id(asset_id)
+            .map_err(|e| format!("Failed to look up asset {}: {}", asset_id, e))?
+            .ok_or_else(|| format!("Asset not found: {}", asset_id))?
+            .asset;
+        if asset.asset_type != expected {
+            return Err(format!(
+                "Asset {} is a {}, but this slot takes a {}",
+                asset_id, asset.asset_type, expected
+            ));
+        }
+        Ok(theme_asset_path(resolver.as_ref(), &asset.file_path))
+    })
+    .await?;
+
+    theme.bind_asset(slot, asset_id, &path)?;
+    let saved = run_blocking({
+        let theme = theme.clone();
+        move || theme_library().save_theme(&theme)
+    })
+    .await?;
+    info!(
+        "Bound asset {} in theme {} ({:?})",
+        asset_id, theme.id, saved
+    );
+    Ok(theme)
+}
+
+/// How themes refer to an asset file: relative to the asset root when inside it.
+fn theme_asset_path(resolver: Option<&AssetPathResolver>, file_path: &str) -> String {
+    resolver
+        .and_then(|resolver| resolver.relative_reference(file_path, None).ok())
+        .unwrap_or_else(|| file_path.to_string())
+}
 
-    let scanner = scanner_guard
-        .as_ref()
-        .ok_or("Asset database not initialized")?;
+/// Path of the best-rated model whose name matches `name`, the way themes refer to it.
+pub async fn find_model_path(
+    app_handle: &tauri::AppHandle,
+    name: String,
+) -> Result<Option<String>, String> {
+    let query = AssetQuery {
+        query: name,
+        asset_type: Some(file_types::MODEL.to_string()),
+        collection: None,
+        metadata: Vec::new(),
+        sort_by: AssetSortField::Rating,
+        descending: true,
+        offset: 0,
+        limit: Some(1),
+    };
+    let resolver = AssetPathResolver::for_project();
+    with_reader(app_handle, move |database| {
+        let page = database
+            .search_assets_page(&query)
+            .map_err(|e| format!("Search failed: {}", e))?;
+        Ok(page
+            .results
+            .first()
+            .map(|result| theme_asset_path(resolver.as_ref(), &result.asset.file_path)))
+    })
+    .await
+}
 
-    scanner
-        .get_stats()
-        .map_err(|e| format!("Failed to get stats: {}", e))
+#[tauri::command]
+pub async fn get_asset_database_stats(
+    app_handle: tauri::AppHandle,
+) -> Result<DatabaseStats, String> {
+    with_reader(&app_handle, |database| {
+        DatabaseStats::collect(database).map_err(|e| format!("Failed to get stats: {}", e))
+    })
+    .await
 }
 
 #[tauri::command]
 pub async fn get_asset_collections(
     app_handle: tauri::AppHandle,
 ) -> Result<Vec<database::Collection>, String> {
+    with_reader(&app_handle, |database| {
+        database
+            .get_collections()
+            .map_err(|e| format!("Failed to get collections: {}", e))
+    })
+    .await
+}
+
+/// Resized preview of a texture or model, rendered once and cached by file checksum.
+///
+/// Returns the path of a PNG no larger than `size` pixels (default 256), so the asset
+/// grid never has to load full-size source files.
+#[tauri::command]
+pub async fn get_asset_preview(
+    asset_id: i64,
+    size: Option<u32>,
+    app_handle: tauri::AppHandle,
+) -> Result<AssetPreview, String> {
+    let size = preview::clamp_size(size);
+    let cache_dir = preview_cache_directory(&app_handle)?;
+
+    let asset = with_reader(&app_handle, move |database| {
+        database
+            .get_asset_by_id(asset_id)
+            .map_err(|e| format!("Failed to look up asset {}: {}", asset_id, e))?
+            .map(|result| result.asset)
+            .ok_or_else(|| format!("Asset not found: {}", asset_id))
+    })
+    .await?;
+
+    let preview = run_blocking(move || {
+        preview::cached_preview(
+            asset_id,
+            Path::new(&asset.file_path),
+            &asset.asset_type,
+            &asset.checksum,
+            &cache_dir,
+            size,
+        )
+    })
+    .await?;
+
+    if !preview.cached {
+        // Recording the thumbnail can wait for a running scan; the caller needn't
+        let handle = app_handle.clone();
+        let thumbnail_path = preview.path.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = with_scanner(&handle, move |scanner| {
+                scanner
+                    .database_mut()
+                    .add_thumbnail(asset_id, &thumbnail_path)
+                    .map_err(|e| e.to_string())
+            })
+            .await;
+            if let Err(e) = result {
+                warn!("Failed to record thumbnail for asset {}: {}", asset_id, e);
+            }
+        });
+    }
+    Ok(preview)
+}
+
+/// Run `f` against the asset database, passing `None` if it isn't initialized.
+fn with_asset_database<T>(
+    app_handle: &tauri::AppHandle,
+    f: impl FnOnce(Option<&AssetDatabase>) -> Result<T, String>,
+) -> Result<T, String> {
     let state: tauri::State<AssetDatabaseState> = app_handle.state();
-    let scanner_guard = state.scanner.lock().unwrap();
+    let reader_guard = lock(&state.reader)?;
+    f(reader_guard.as_ref())
+}
+
+/// Resolve a level's `mesh`/`material` reference to an absolute path for previewing.
+#[tauri::command]
+pub async fn resolve_asset_path(
+    reference: String,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let resolver = AssetPathResolver::for_project().ok_or("Assets directory not found")?;
+    let path = with_asset_database(&app_handle, |database| {
+        resolver.resolve(&reference, database)
+    })?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Write every indexed asset with its type, size, checksum, tags, collection and license
+/// to a JSON or CSV file. Without a `format`, the file extension picks one.
+#[tauri::command]
+pub async fn export_asset_catalog(
+    output_path: String,
+    format: Option<CatalogFormat>,
+    app_handle: tauri::AppHandle,
+) -> Result<CatalogSummary, String> {
+    let summary = with_reader(&app_handle, move |database| {
+        catalog::export_catalog(database, Path::new(&output_path), format)
+    })
+    .await?;
+    info!(
+        "Exported {} assets to catalog {}",
+        summary.asset_count, summary.output_path
+    );
+    Ok(summary)
+}
+
+/// Pack a level and every asset it references into one zip that opens on another machine.
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn export_asset_bundle(
+    level_data: LevelData,
+    output_path: String,
+    app_handle: tauri::AppHandle,
+) -> Result<BundleSummary, String> {
+    let resolver = AssetPathResolver::for_project().ok_or("Assets directory not found")?;
+    let reader = app_handle.state::<AssetDatabaseState>().reader.clone();
+
+    let summary = run_blocking(move || {
+        let reader_guard = lock(&reader)?;
+        bundle::write_bundle(
+            &level_data,
+            &resolver,
+            reader_guard.as_ref(),
+            Path::new(&output_path),
+        )
+    })
+    .await?;
+    info!(
+        "Bundled {} assets ({} bytes) into {}",
+        summary.asset_count, summary.total_bytes, summary.output_path
+    );
+    if !summary.missing.is_empty() {
+        warn!(
+            "{} asset references could not be bundled",
+            summary.missing.len()
+        );
+    }
+    Ok(summary)
+}
+
+/// Find `mesh`/`material` references in the current level that don't resolve.
+#[tauri::command]
+pub async fn check_asset_references(
+    state: tauri::State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<AssetReferenceReport, String> {
+    let level = state
+        .read()
+        .await
+        .current_level
+        .clone()
+        .ok_or("No level currently loaded")?;
+    let resolver = AssetPathResolver::for_project().ok_or("Assets directory not found")?;
 
-    let scanner = scanner_guard
-        .as_ref()
-        .ok_or("Asset database not initialized")?;
+    let report = with_asset_database(&app_handle, |database| {
+        references::check_level_references(&level, &resolver, database)
+    })?;
+    info!(
+        "Checked {} asset references, {} broken",
+        report.checked,
+        report.issues.len()
+    );
+    Ok(report)
+}
 
-    scanner
-        .database()
-        .get_collections()
-        .map_err(|e| format!("Failed to get collections: {}", e))
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetRepairSummary {
+    /// Broken references and what they now point at
+    pub repaired: BTreeMap<String, String>,
+    pub objects_changed: usize,
+    /// Broken references with no certain replacement
+    pub unresolved: Vec<AssetReferenceIssue>,
 }
 
-fn find_assets_directory() -> Option<PathBuf> {
+/// Point broken references in the current level at the indexed asset with the same
+/// file name, where there is exactly one, e.g. after the project moved machines.
+#[tauri::command]
+pub async fn repair_asset_paths(
+    state: tauri::State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<AssetRepairSummary, String> {
+    let resolver = AssetPathResolver::for_project().ok_or("Assets directory not found")?;
+    let mut app_state = state.write().await;
+    let level = app_state
+        .current_level
+        .as_mut()
+        .ok_or("No level currently loaded")?;
+
+    let report = with_asset_database(&app_handle, |database| {
+        references::check_level_references(level, &resolver, database)
+    })?;
+    let repairs = references::repairs(&report);
+    let changed = usage::rewrite_references(level, &repairs);
+    drop(app_state);
+
+    info!(
+        "Repaired {} of {} broken asset references",
+        repairs.len(),
+        report.issues.len()
+    );
+    let objects_changed = changed.len();
+    if !changed.is_empty() {
+        emit_level_changed(&app_handle, LevelChangeKind::ObjectsUpdated, changed);
+    }
+    Ok(AssetRepairSummary {
+        unresolved: report
+            .issues
+            .into_iter()
+            .filter(|issue| !repairs.contains_key(&issue.reference))
+            .collect(),
+        repaired: repairs.into_iter().collect(),
+        objects_changed,
+    })
+}
+
+/// Copy the assets the current level uses from outside the project into its asset
+/// folder, so the project can be moved or committed on its own.
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn collect_project_assets(
+    state: tauri::State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<CollectSummary, String> {
+    let assets_dir = find_assets_directory().ok_or("Assets directory not found")?;
+    let resolver = AssetPathResolver::new(&assets_dir);
+    let level = state
+        .read()
+        .await
+        .current_level
+        .clone()
+        .ok_or("No level currently loaded")?;
+    let reader = app_handle.state::<AssetDatabaseState>().reader.clone();
+
+    let (summary, replacements) = run_blocking(move || {
+        let reader_guard = lock(&reader)?;
+        collect::collect_level_assets(&level, &resolver, &assets_dir, reader_guard.as_ref())
+    })
+    .await?;
+
+    let changed = match state.write().await.current_level.as_mut() {
+        Some(level) => usage::rewrite_references(level, &replacements),
+        None => Vec::new(),
+    };
+    info!(
+        "Collected {} assets into the project, updating {} objects",
+        summary.copied.len(),
+        changed.len()
+    );
+    if !summary.conflicts.is_empty() {
+        warn!(
+            "{} assets clash with files already in the project",
+            summary.conflicts.len()
+        );
+    }
+    if !changed.is_empty() {
+        emit_level_changed(&app_handle, LevelChangeKind::ObjectsUpdated, changed);
+    }
+    Ok(summary)
+}
+
+/// Copy of `level` with every asset reference relative to the project's asset root.
+pub fn level_for_export(
+    app_handle: &tauri::AppHandle,
+    level: &LevelData,
+) -> Result<LevelData, String> {
+    if let Some(resolver) = AssetPathResolver::for_project() {
+        with_asset_database(app_handle, |database| {
+            resolver.relative_level(level, database)
+        })
+    } else {
+        warn!("Assets directory not found, exporting asset references unchanged");
+        Ok(level.clone())
+    }
+}
+
+/// The project directory: the open project's folder, or else the parent of the
+/// `Assets` folder.
+pub fn project_directory() -> Option<PathBuf> {
+    crate::project::open_project_directory().or_else(|| {
+        find_assets_directory().and_then(|assets_dir| assets_dir.parent().map(PathBuf::from))
+    })
+}
+
+/// The `themes` folder in the app data directory, set once the app starts.
+static USER_THEMES_DIRECTORY: OnceLock<PathBuf> = OnceLock::new();
+
+/// Finds the app data folder for themes installed outside any project.
+pub fn init_user_themes(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let directory = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join(THEMES_DIRECTORY);
+    // Only fails if already set, which leaves the same folder in place
+    let _ = USER_THEMES_DIRECTORY.set(directory);
+    Ok(())
+}
+
+/// Built-in themes together with the user's installed themes and the project's own,
+/// the project's taking precedence.
+pub fn theme_library() -> ThemeLibrary {
+    ThemeLibrary::for_project(project_directory().as_deref())
+        .with_user_directory(USER_THEMES_DIRECTORY.get().cloned())
+}
+
+pub fn find_assets_directory() -> Option<PathBuf> {
+    if let Some(assets_dir) = crate::project::open_assets_directory().filter(|dir| dir.is_dir()) {
+        return Some(assets_dir);
+    }
+
     let possible_paths = vec![
         PathBuf::from("Assets"),       // Relative to current working directory
         PathBuf::from("../Assets"),    // One level up (if running from src-tauri)
@@ -224,25 +1600,10 @@ pub fn scan_assets_folder(folder_path: String) -> Result<Vec<AssetFile>, String>
 }
 
 // Legacy helper functions for compatibility
-const MODEL_EXTENSIONS: &[&str] = &["fbx", "obj", "gltf", "glb", "dae", "3ds", "blend"];
-const TEXTURE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "tga", "bmp", "hdr", "exr"];
-const MATERIAL_EXTENSIONS: &[&str] = &["mtl", "mat"];
-const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "ogg", "flac"];
-
 fn get_asset_type(extension: &str) -> String {
-    let ext = extension.to_lowercase();
-
-    if MODEL_EXTENSIONS.contains(&ext.as_str()) {
-        "model".to_string()
-    } else if TEXTURE_EXTENSIONS.contains(&ext.as_str()) {
-        "texture".to_string()
-    } else if MATERIAL_EXTENSIONS.contains(&ext.as_str()) {
-        "material".to_string()
-    } else if AUDIO_EXTENSIONS.contains(&ext.as_str()) {
-        "audio".to_string()
-    } else {
-        "other".to_string()
-    }
+    FileTypes::default()
+        .asset_type_for_extension(extension)
+        .map_or_else(|| "other".to_string(), str::to_lowercase)
 }
 
 fn scan_directory_recursive(dir: &Path, assets: &mut Vec<AssetFile>) -> Result<(), String> {
@@ -309,7 +1670,7 @@ fn create_asset_from_file(path: &Path) -> Result<Option<AssetFile>, String> {
     Ok(Some(AssetFile {
         id: format!("{:x}", id),
         name: filename.to_string(),
-        path: path_str.to_string(),
+        path: path_str,
         asset_type,
         size: metadata.len(),
         last_modified,
@@ -0,0 +1,221 @@
+// Sprite sheet frame layouts, read from an atlas file beside the texture or guessed from a tile grid
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Tile sizes tried, in order, when guessing a grid.
+const GRID_TILE_SIZES: &[u32] = &[16, 32, 64, 128, 8];
+/// File name fragments that mark a texture without an atlas file as a tile grid.
+const GRID_NAME_HINTS: &[&str] = &["sheet", "tilemap", "tileset", "tiles", "atlas"];
+/// Grids with more cells than this are more likely a mistaken guess than a tile set.
+const MAX_GRID_FRAMES: u32 = 4096;
+
+/// One frame of a sprite sheet, in pixels from the top-left of the texture.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpriteFrame {
+    /// Name from the atlas file; grid cells are named by their index
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AtlasSource {
+    /// Starling/Kenney `<TextureAtlas>` XML
+    Xml,
+    /// TexturePacker JSON, as a hash or an array of frames
+    Json,
+    /// Evenly sized tiles, guessed from the file name and dimensions
+    Grid,
+}
+
+impl AtlasSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Xml => "xml",
+            Self::Json => "json",
+            Self::Grid => "grid",
+        }
+    }
+}
+
+/// Frame layout of a texture: from a `.xml` or `.json` atlas with the same name, else
+/// from a tile grid if the file name suggests one.
+///
+/// Atlas files aren't assets themselves, so editing one takes effect when its texture
+/// is next rescanned.
+pub fn detect_sprite_sheet(
+    path: &Path,
+    width: u32,
+    height: u32,
+) -> Option<(AtlasSource, Vec<SpriteFrame>)> {
+    let image_name = path.file_name()?.to_str()?;
+    for (extension, source) in [("xml", AtlasSource::Xml), ("json", AtlasSource::Json)] {
+        let Ok(text) = fs::read_to_string(path.with_extension(extension)) else {
+            continue;
+        };
+        let frames = match source {
+            AtlasSource::Xml => xml_frames(&text, image_name),
+            _ => json_frames(&text, image_name),
+        };
+        if let Some(frames) = frames.filter(|frames| !frames.is_empty()) {
+            return Some((source, frames));
+        }
+    }
+
+    let stem = path.file_stem()?.to_str()?.to_lowercase();
+    if !GRID_NAME_HINTS.iter().any(|hint| stem.contains(hint)) {
+        return None;
+    }
+    grid_frames(width, height).map(|frames| (AtlasSource::Grid, frames))
+}
+
+/// The frame called `frame`, or at that index when no frame has the name.
+pub fn find_frame<'a>(frames: &'a [SpriteFrame], frame: &str) -> Option<&'a SpriteFrame> {
+    frames
+        .iter()
+        .find(|candidate| candidate.name == frame)
+        .or_else(|| frames.get(frame.parse::<usize>().ok()?))
+}
+
+/// `<TextureAtlas imagePath="sheet.png"><SubTexture name=".." x=".." .../>`
+fn xml_frames(text: &str, image_name: &str) -> Option<Vec<SpriteFrame>> {
+    let document = roxmltree::Document::parse(text).ok()?;
+    let root = document.root_element();
+    if !root.has_tag_name("TextureAtlas") || !names_image(root.attribute("imagePath"), image_name) {
+        return None;
+    }
+    root.children()
+        .filter(|node| node.has_tag_name("SubTexture"))
+        .map(|node| {
+            let number = |name: &str| node.attribute(name)?.trim().parse::<u32>().ok();
+            Some(SpriteFrame {
+                name: node.attribute("name")?.to_string(),
+                x: number("x")?,
+                y: number("y")?,
+                width: number("width")?,
+                height: number("height")?,
+            })
+        })
+        .collect()
+}
+
+/// `{"frames": {"name": {"frame": {"x", "y", "w", "h"}}}, "meta": {"image": "sheet.png"}}`,
+/// or `frames` as an array of objects with a `filename`.
+fn json_frames(text: &str, image_name: &str) -> Option<Vec<SpriteFrame>> {
+    let json: serde_json::Value = serde_json::from_str(text).ok()?;
+    let meta_image = json.pointer("/meta/image").and_then(|image| image.as_str());
+    if !names_image(meta_image, image_name) {
+        return None;
+    }
+
+    let frame = |name: &str, value: &serde_json::Value| {
+        let rect = value.get("frame")?;
+        let number = |key: &str| u32::try_from(rect.get(key)?.as_u64()?).ok();
+        Some(SpriteFrame {
+            name: name.to_string(),
+            x: number("x")?,
+            y: number("y")?,
+            width: number("w")?,
+            height: number("h")?,
+        })
+    };
+    match json.get("frames")? {
+        serde_json::Value::Object(frames) => frames
+            .iter()
+            .map(|(name, value)| frame(name, value))
+            .collect(),
+        serde_json::Value::Array(frames) => frames
+            .iter()
+            .map(|value| frame(value.get("filename")?.as_str()?, value))
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Whether an atlas's image reference, if it has one, is this texture.
+fn names_image(reference: Option<&str>, image_name: &str) -> bool {
+    reference.is_none_or(|reference| {
+        Path::new(reference)
+            .file_name()
+            .is_some_and(|name| name.eq_ignore_ascii_case(image_name))
+    })
+}
+
+/// Square tiles filling the texture exactly, either packed or with one pixel between them.
+fn grid_frames(width: u32, height: u32) -> Option<Vec<SpriteFrame>> {
+    let (tile, spacing) = GRID_TILE_SIZES.iter().find_map(|&tile| {
+        [0, 1].into_iter().find_map(|spacing| {
+            let pitch = tile + spacing;
+            let fits = |extent: u32| (extent + spacing).is_multiple_of(pitch) && extent > tile;
+            (fits(width) && fits(height)).then_some((tile, spacing))
+        })
+    })?;
+
+    let pitch = tile + spacing;
+    let (columns, rows) = ((width + spacing) / pitch, (height + spacing) / pitch);
+    if columns * rows > MAX_GRID_FRAMES {
+        return None;
+    }
+    Some(
+        (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (row, column)))
+            .enumerate()
+            .map(|(index, (row, column))| SpriteFrame {
+                name: index.to_string(),
+                x: column * pitch,
+                y: row * pitch,
+                width: tile,
+                height: tile,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reads_atlas_files_and_grids() {
+        let temp_dir = tempdir().unwrap();
+        let sheet = temp_dir.path().join("sheet.png");
+        fs::write(
+            temp_dir.path().join("sheet.xml"),
+            r#"<TextureAtlas imagePath="sheet.png">
+                <SubTexture name="tank.png" x="0" y="0" width="32" height="48"/>
+                <SubTexture name="barrel.png" x="32" y="0" width="16" height="16"/>
+            </TextureAtlas>"#,
+        )
+        .unwrap();
+        let (source, frames) = detect_sprite_sheet(&sheet, 64, 64).unwrap();
+        assert_eq!(source, AtlasSource::Xml);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(find_frame(&frames, "barrel.png").unwrap().x, 32);
+        assert_eq!(find_frame(&frames, "0").unwrap().name, "tank.png");
+
+        let packer = temp_dir.path().join("ui.png");
+        fs::write(
+            temp_dir.path().join("ui.json"),
+            r#"{"frames": [{"filename": "button", "frame": {"x": 4, "y": 8, "w": 10, "h": 6}}],
+                "meta": {"image": "ui.png"}}"#,
+        )
+        .unwrap();
+        let (source, frames) = detect_sprite_sheet(&packer, 64, 64).unwrap();
+        assert_eq!(source, AtlasSource::Json);
+        assert_eq!((frames[0].x, frames[0].height), (4, 6));
+
+        // Kenney tilemaps leave a pixel between 16px tiles
+        let (source, frames) =
+            detect_sprite_sheet(&temp_dir.path().join("tilemap.png"), 203, 186).unwrap();
+        assert_eq!(source, AtlasSource::Grid);
+        assert_eq!(frames.len(), 12 * 11);
+        assert_eq!((frames[1].x, frames[12].y), (17, 17));
+
+        assert!(detect_sprite_sheet(&temp_dir.path().join("grass.png"), 64, 64).is_none());
+    }
+}
@@ -0,0 +1,165 @@
+// Timestamped copies of the asset database, so a corrupt file doesn't force a full rescan
+use super::database::{integrity_problems, AssetDatabase};
+use chrono::{DateTime, Utc};
+use log::info;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BACKUP_PREFIX: &str = "assets-";
+const BACKUP_EXTENSION: &str = ".db";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseBackup {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub ok: bool,
+    /// Messages from `PRAGMA integrity_check`, empty when `ok`
+    pub problems: Vec<String>,
+}
+
+impl IntegrityReport {
+    fn new(problems: Vec<String>) -> Self {
+        Self {
+            ok: problems.is_empty(),
+            problems,
+        }
+    }
+}
+
+pub fn create_backup(
+    database: &AssetDatabase,
+    backup_dir: &Path,
+) -> Result<DatabaseBackup, String> {
+    fs::create_dir_all(backup_dir)
+        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+
+    let stamp = Utc::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut name = format!("{}{}{}", BACKUP_PREFIX, stamp, BACKUP_EXTENSION);
+    let mut n = 1;
+    while backup_dir.join(&name).exists() {
+        name = format!("{}{}-{}{}", BACKUP_PREFIX, stamp, n, BACKUP_EXTENSION);
+        n += 1;
+    }
+    let path = backup_dir.join(&name);
+
+    database
+        .backup_to(&path)
+        .map_err(|e| format!("Failed to back up asset database: {}", e))?;
+    info!("Backed up asset database to {:?}", path);
+    backup_info(&path).ok_or_else(|| format!("Backup not found after writing: {}", name))
+}
+
+/// Backups in `backup_dir`, newest first.
+pub fn list_backups(backup_dir: &Path) -> Result<Vec<DatabaseBackup>, String> {
+    if !backup_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(backup_dir).map_err(|e| format!("Failed to read backups: {}", e))?;
+    let mut backups: Vec<DatabaseBackup> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| backup_info(&entry.path()))
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    Ok(backups)
+}
+
+/// Replace the live database with a backup, after checking the backup is intact.
+pub fn restore_backup(
+    database: &mut AssetDatabase,
+    backup_dir: &Path,
+    name: &str,
+) -> Result<(), String> {
+    let path = backup_path(backup_dir, name)?;
+    if !path.is_file() {
+        return Err(format!("Backup not found: {}", name));
+    }
+
+    let backup = Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open backup: {}", e))?;
+    let problems =
+        integrity_problems(&backup).map_err(|e| format!("Failed to check backup: {}", e))?;
+    if !problems.is_empty() {
+        return Err(format!(
+            "Backup {} is damaged: {}",
+            name,
+            problems.join("; ")
+        ));
+    }
+    drop(backup);
+
+    database
+        .restore_from(&path)
+        .map_err(|e| format!("Failed to restore asset database: {}", e))?;
+    info!("Restored asset database from {:?}", path);
+    Ok(())
+}
+
+pub fn check_integrity(database: &AssetDatabase) -> Result<IntegrityReport, String> {
+    database
+        .integrity_check()
+        .map(IntegrityReport::new)
+        .map_err(|e| format!("Integrity check failed: {}", e))
+}
+
+fn backup_path(backup_dir: &Path, name: &str) -> Result<PathBuf, String> {
+    // Only plain backup file names, never paths
+    if !name.starts_with(BACKUP_PREFIX)
+        || !name.ends_with(BACKUP_EXTENSION)
+        || name.contains(['/', '\\'])
+    {
+        return Err(format!("Invalid backup name: {}", name));
+    }
+    Ok(backup_dir.join(name))
+}
+
+fn backup_info(path: &Path) -> Option<DatabaseBackup> {
+    let name = path.file_name()?.to_str()?;
+    if !name.starts_with(BACKUP_PREFIX) || !name.ends_with(BACKUP_EXTENSION) {
+        return None;
+    }
+    let metadata = fs::metadata(path).ok()?;
+    Some(DatabaseBackup {
+        name: name.to_string(),
+        created_at: metadata.modified().ok()?.into(),
+        size_bytes: metadata.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn backup_and_restore_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let assets_root = temp_dir.path().join("Assets");
+        let collection_dir = assets_root.join("Kenney");
+        fs::create_dir_all(&collection_dir).unwrap();
+        fs::write(collection_dir.join("crate.png"), b"crate").unwrap();
+
+        let mut database = AssetDatabase::new(temp_dir.path().join("assets.db")).unwrap();
+        let asset_id = database
+            .insert_asset(&collection_dir.join("crate.png"), "Kenney", &assets_root)
+            .unwrap();
+
+        let backup_dir = temp_dir.path().join("backups");
+        let backup = create_backup(&database, &backup_dir).unwrap();
+        assert_eq!(list_backups(&backup_dir).unwrap().len(), 1);
+
+        database.remove_asset(asset_id, "Kenney").unwrap();
+        assert!(database.get_asset_by_id(asset_id).unwrap().is_none());
+
+        restore_backup(&mut database, &backup_dir, &backup.name).unwrap();
+        assert!(database.get_asset_by_id(asset_id).unwrap().is_some());
+        assert!(check_integrity(&database).unwrap().ok);
+        assert!(restore_backup(&mut database, &backup_dir, "../assets.db").is_err());
+    }
+}
@@ -0,0 +1,414 @@
+// Packing a level and every asset it uses into one zip for another machine
+use super::database::AssetDatabase;
+use super::metadata::MTL_TEXTURE_STATEMENTS;
+use super::paths::AssetPathResolver;
+use crate::LevelData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+const LEVEL_FILE: &str = "level.json";
+/// Archive folder that mirrors the project's asset root.
+const ASSETS_DIR: &str = "assets";
+/// Where assets from outside the asset root go, inside `ASSETS_DIR`.
+const EXTERNAL_DIR: &str = "external";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledAsset {
+    /// Path relative to the bundle's asset folder, as the bundled level references it
+    pub path: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub level_id: String,
+    pub level_name: String,
+    pub created_at: DateTime<Utc>,
+    /// Folder inside the archive holding the assets; unpack it as the asset root
+    pub asset_root: String,
+    pub assets: Vec<BundledAsset>,
+    /// References that couldn't be found and aren't in the bundle
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleSummary {
+    pub output_path: String,
+    pub asset_count: usize,
+    pub total_bytes: u64,
+    pub missing: Vec<String>,
+}
+
+/// Files to pack, keyed by their path inside the asset folder.
+#[derive(Default)]
+pub struct BundlePlan {
+    pub files: BTreeMap<String, PathBuf>,
+    /// References that couldn't be found
+    pub missing: Vec<String>,
+}
+
+impl BundlePlan {
+    /// Plan every asset `level` references, returning the level with its references
+    /// rewritten to the paths they are packed under.
+    pub fn add_level(
+        &mut self,
+        level: &LevelData,
+        resolver: &AssetPathResolver,
+        database: Option<&AssetDatabase>,
+    ) -> LevelData {
+        let references: BTreeSet<&String> = level
+            .objects
+            .iter()
+            .flat_map(|obj| [&obj.mesh, &obj.material])
+            .flatten()
+            .collect();
+        let rewritten: HashMap<String, String> = references
+            .into_iter()
+            .filter_map(|reference| {
+                self.add_reference(reference, resolver, database)
+                    .map(|bundle_path| (reference.clone(), bundle_path))
+            })
+            .collect();
+
+        let mut bundled_level = level.clone();
+        for obj in &mut bundled_level.objects {
+            for reference in [&mut obj.mesh, &mut obj.material].into_iter().flatten() {
+                if let Some(bundle_path) = rewritten.get(reference.as_str()) {
+                    reference.clone_from(bundle_path);
+                }
+            }
+        }
+        bundled_level
+    }
+
+    /// Plan a level reference, returning the path the bundled level should use.
+    fn add_reference(
+        &mut self,
+        reference: &str,
+        resolver: &AssetPathResolver,
+        database: Option<&AssetDatabase>,
+    ) -> Option<String> {
+        let source = match resolver.resolve(reference, database) {
+            Ok(source) if source.is_file() => source,
+            Ok(_) => {
+                self.missing.push(reference.to_string());
+                return None;
+            }
+            Err(e) => {
+                self.missing.push(format!("{}: {}", reference, e));
+                return None;
+            }
+        };
+
+        let bundle_path = resolver
+            .relative_reference(reference, database)
+            .ok()
+            .filter(|relative| !Path::new(relative).is_absolute())
+            .unwrap_or_else(|| self.external_path(&source));
+        self.add_file(bundle_path.clone(), source);
+        Some(bundle_path)
+    }
+
+    /// Add a file and, for models and materials, the files it loads alongside it.
+    fn add_file(&mut self, bundle_path: String, source: PathBuf) {
+        if self.files.contains_key(&bundle_path) {
+            return;
+        }
+        let companions = companion_files(&source);
+        let source_dir = source.parent().map(Path::to_path_buf).unwrap_or_default();
+        let bundle_dir = bundle_path
+            .rsplit_once('/')
+            .map_or(String::new(), |(dir, _)| format!("{}/", dir));
+        self.files.insert(bundle_path.clone(), source);
+
+        for uri in companions {
+            let Some(relative) = safe_relative(&uri) else {
+                self.missing.push(format!(
+                    "{} (needed by {}): unsupported path",
+                    uri, bundle_path
+                ));
+                continue;
+            };
+            let companion = source_dir.join(&relative);
+            if companion.is_file() {
+                self.add_file(format!("{}{}", bundle_dir, relative), companion);
+            } else {
+                self.missing
+                    .push(format!("{} (needed by {})", relative, bundle_path));
+            }
+        }
+    }
+
+    /// A unique `external/` path for a file outside the asset root.
+    fn external_path(&self, source: &Path) -> String {
+        let file_name = source.file_name().map_or_else(
+            || "asset".to_string(),
+            |name| name.to_string_lossy().to_string(),
+        );
+        let (stem, extension) = match file_name.rsplit_once('.') {
+            Some((stem, extension)) => (stem.to_string(), format!(".{}", extension)),
+            None => (file_name.clone(), String::new()),
+        };
+
+        let mut candidate = format!("{}/{}", EXTERNAL_DIR, file_name);
+        let mut n = 1;
+        while self
+            .files
+            .get(&candidate)
+            .is_some_and(|existing| existing != source)
+        {
+            candidate = format!("{}/{}_{}{}", EXTERNAL_DIR, stem, n, extension);
+            n += 1;
+        }
+        candidate
+    }
+}
+
+/// Write `level` and every asset it references to a zip at `output`.
+///
+/// References in the bundled level are rewritten relative to the bundle's asset
+/// folder. Missing assets are listed in the manifest rather than failing the export.
+pub fn write_bundle(
+    level: &LevelData,
+    resolver: &AssetPathResolver,
+    database: Option<&AssetDatabase>,
+    output: &Path,
+) -> Result<BundleSummary, String> {
+    let mut plan = BundlePlan::default();
+    let bundled_level = plan.add_level(level, resolver, database);
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+    let result = write_archive(&bundled_level, &plan, output);
+    if result.is_err() {
+        let _ = fs::remove_file(output);
+    }
+    let manifest = result?;
+
+    Ok(BundleSummary {
+        output_path: output.to_string_lossy().to_string(),
+        asset_count: manifest.assets.len(),
+        total_bytes: manifest.assets.iter().map(|asset| asset.size_bytes).sum(),
+        missing: manifest.missing,
+    })
+}
+
+fn write_archive(
+    level: &LevelData,
+    plan: &BundlePlan,
+    output: &Path,
+) -> Result<BundleManifest, String> {
+    let file = File::create(output).map_err(|e| format!("Failed to create {:?}: {}", output, e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let zip_error = |e: zip::result::ZipError| format!("Failed to write bundle: {}", e);
+    let io_error = |e: std::io::Error| format!("Failed to write bundle: {}", e);
+
+    let assets = write_assets(&mut zip, plan, ASSETS_DIR, options)?;
+
+    let manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        level_id: level.id.clone(),
+        level_name: level.name.clone(),
+        created_at: Utc::now(),
+        asset_root: ASSETS_DIR.to_string(),
+        assets,
+        missing: plan.missing.clone(),
+    };
+
+    let level_json = serde_json::to_vec_pretty(level)
+        .map_err(|e| format!("Failed to serialize level: {}", e))?;
+    zip.start_file(LEVEL_FILE, options).map_err(zip_error)?;
+    zip.write_all(&level_json).map_err(io_error)?;
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize bundle manifest: {}", e))?;
+    zip.start_file(MANIFEST_FILE, options).map_err(zip_error)?;
+    zip.write_all(&manifest_json).map_err(io_error)?;
+
+    zip.finish().map_err(zip_error)?;
+    Ok(manifest)
+}
+
+/// Write the planned files into `folder` of the archive, returning what was written.
+pub fn write_assets<W: Write + std::io::Seek>(
+    zip: &mut ZipWriter<W>,
+    plan: &BundlePlan,
+    folder: &str,
+    options: SimpleFileOptions,
+) -> Result<Vec<BundledAsset>, String> {
+    let mut assets = Vec::new();
+    for (bundle_path, source) in &plan.files {
+        let contents =
+            fs::read(source).map_err(|e| format!("Failed to read {:?}: {}", source, e))?;
+        zip.start_file(format!("{}/{}", folder, bundle_path), options)
+            .map_err(|e| format!("Failed to write bundle: {}", e))?;
+        zip.write_all(&contents)
+            .map_err(|e| format!("Failed to write bundle: {}", e))?;
+        assets.push(BundledAsset {
+            path: bundle_path.clone(),
+            size_bytes: contents.len() as u64,
+            sha256: format!("{:x}", Sha256::digest(&contents)),
+        });
+    }
+    Ok(assets)
+}
+
+/// Files a model or material loads by relative path: glTF buffers and images,
+/// OBJ material libraries and their textures.
+fn companion_files(path: &Path) -> Vec<String> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase);
+    match extension.as_deref() {
+        Some("gltf") => gltf_uris(path),
+        Some("obj") => statement_paths(path, &["mtllib"]),
+        Some("mtl") => statement_paths(path, MTL_TEXTURE_STATEMENTS),
+        _ => Vec::new(),
+    }
+}
+
+fn gltf_uris(path: &Path) -> Vec<String> {
+    let Some(json) = fs::read(path)
+        .ok()
+        .and_then(|data| serde_json::from_slice::<serde_json::Value>(&data).ok())
+    else {
+        return Vec::new();
+    };
+    ["buffers", "images"]
+        .iter()
+        .filter_map(|key| json.get(key)?.as_array())
+        .flatten()
+        .filter_map(|entry| entry.get("uri")?.as_str())
+        .filter(|uri| !uri.starts_with("data:"))
+        .map(|uri| uri.replace("%20", " "))
+        .collect()
+}
+
+/// Last argument of each line starting with one of `statements` (case-insensitive).
+fn statement_paths(path: &Path, statements: &[&str]) -> Vec<String> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let statement = parts.next()?.to_lowercase();
+            if statements.contains(&statement.as_str()) {
+                parts.last().map(str::to_string)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// A `/`-separated relative path that stays inside its directory.
+pub fn safe_relative(uri: &str) -> Option<String> {
+    let uri = uri.replace('\\', "/");
+    let parts: Vec<&str> = Path::new(&uri)
+        .components()
+        .map(|component| match component {
+            Component::Normal(part) => part.to_str(),
+            Component::CurDir => Some(""),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    let joined = parts
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
+    (!joined.is_empty()).then_some(joined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{level, prop};
+    use crate::GameObject;
+    use std::io::Read;
+    use tempfile::tempdir;
+
+    #[test]
+    fn bundles_referenced_assets_and_companions() {
+        let temp_dir = tempdir().unwrap();
+        let assets_root = temp_dir.path().join("Assets");
+        let models = assets_root.join("Kenney").join("Models");
+        fs::create_dir_all(models.join("textures")).unwrap();
+        fs::write(models.join("chair.obj"), "mtllib chair.mtl\nv 0 0 0\n").unwrap();
+        fs::write(
+            models.join("chair.mtl"),
+            "newmtl wood\nmap_Kd textures/wood.png\n",
+        )
+        .unwrap();
+        fs::write(models.join("textures/wood.png"), b"png").unwrap();
+        let external = temp_dir.path().join("paint.mat");
+        fs::write(&external, b"paint").unwrap();
+
+        let level = level(vec![
+            GameObject {
+                material: Some(external.to_string_lossy().to_string()),
+                ..prop("chair", "Kenney/Models/chair.obj")
+            },
+            prop("ghost", "Kenney/Models/gone.fbx"),
+        ]);
+
+        let output = temp_dir.path().join("out").join("level.zip");
+        let resolver = AssetPathResolver::new(&assets_root);
+        let summary = write_bundle(&level, &resolver, None, &output).unwrap();
+        assert_eq!(summary.asset_count, 4);
+        assert_eq!(summary.missing, vec!["Kenney/Models/gone.fbx".to_string()]);
+
+        let mut archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        for name in [
+            "assets/Kenney/Models/chair.obj",
+            "assets/Kenney/Models/chair.mtl",
+            "assets/Kenney/Models/textures/wood.png",
+            "assets/external/paint.mat",
+            MANIFEST_FILE,
+        ] {
+            assert!(archive.by_name(name).is_ok(), "missing {}", name);
+        }
+
+        let mut level_json = String::new();
+        archive
+            .by_name(LEVEL_FILE)
+            .unwrap()
+            .read_to_string(&mut level_json)
+            .unwrap();
+        let bundled: LevelData = serde_json::from_str(&level_json).unwrap();
+        assert_eq!(
+            bundled.objects[0].material.as_deref(),
+            Some("external/paint.mat")
+        );
+        assert_eq!(
+            bundled.objects[0].mesh.as_deref(),
+            Some("Kenney/Models/chair.obj")
+        );
+    }
+
+    #[test]
+    fn companion_paths_must_stay_relative() {
+        assert_eq!(
+            safe_relative("./textures\\wood.png").as_deref(),
+            Some("textures/wood.png")
+        );
+        assert_eq!(safe_relative("../shared/wood.png"), None);
+        assert_eq!(safe_relative("/etc/passwd"), None);
+    }
+}
@@ -0,0 +1,212 @@
+// The full asset inventory as JSON or CSV, for audits and build tooling outside the editor
+use super::database::{AssetDatabase, ChecksumMode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::Path;
+
+const CATALOG_FORMAT_VERSION: u32 = 1;
+/// Separator between tags in the CSV `tags` column.
+const CSV_TAG_SEPARATOR: &str = ";";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogFormat {
+    Json,
+    Csv,
+}
+
+impl CatalogFormat {
+    /// Format named by a file's extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    pub id: i64,
+    pub name: String,
+    pub file_path: String,
+    pub asset_type: String,
+    pub collection: String,
+    pub file_size: i64,
+    pub checksum: String,
+    pub checksum_mode: ChecksumMode,
+    pub tags: Vec<String>,
+    /// License of the asset's collection, where one is recorded
+    pub license: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetCatalog {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub total_bytes: i64,
+    pub assets: Vec<CatalogEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogSummary {
+    pub output_path: String,
+    pub format: CatalogFormat,
+    pub asset_count: usize,
+    pub total_bytes: i64,
+}
+
+pub fn build_catalog(database: &AssetDatabase) -> Result<AssetCatalog, String> {
+    let sql_error = |e: rusqlite::Error| format!("Failed to read asset catalog: {}", e);
+    let mut tags = database.get_all_tags().map_err(sql_error)?;
+    let licenses: HashMap<String, String> = database
+        .get_collections()
+        .map_err(sql_error)?
+        .into_iter()
+        .filter_map(|collection| Some((collection.name, collection.license_info?)))
+        .collect();
+
+    let assets: Vec<CatalogEntry> = database
+        .get_all_assets()
+        .map_err(sql_error)?
+        .into_iter()
+        .map(|asset| CatalogEntry {
+            tags: tags.remove(&asset.id).unwrap_or_default(),
+            license: licenses.get(&asset.collection).cloned(),
+            id: asset.id,
+            name: asset.name,
+            file_path: asset.file_path,
+            asset_type: asset.asset_type,
+            collection: asset.collection,
+            file_size: asset.file_size,
+            checksum: asset.checksum,
+            checksum_mode: asset.checksum_mode,
+            updated_at: asset.updated_at,
+        })
+        .collect();
+
+    Ok(AssetCatalog {
+        format_version: CATALOG_FORMAT_VERSION,
+        exported_at: Utc::now(),
+        total_bytes: assets.iter().map(|asset| asset.file_size).sum(),
+        assets,
+    })
+}
+
+/// Write the catalog to `output_path`, in `format` or else the one its extension names.
+pub fn export_catalog(
+    database: &AssetDatabase,
+    output_path: &Path,
+    format: Option<CatalogFormat>,
+) -> Result<CatalogSummary, String> {
+    let format = format
+        .or_else(|| CatalogFormat::from_path(output_path))
+        .ok_or("Choose a catalog format or give the file a .json or .csv extension")?;
+    let catalog = build_catalog(database)?;
+
+    if let Some(parent) = output_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+    match format {
+        CatalogFormat::Json => {
+            let json = serde_json::to_string_pretty(&catalog)
+                .map_err(|e| format!("Failed to serialize asset catalog: {}", e))?;
+            fs::write(output_path, json)
+                .map_err(|e| format!("Failed to write {:?}: {}", output_path, e))?;
+        }
+        CatalogFormat::Csv => write_csv(&catalog, output_path)
+            .map_err(|e| format!("Failed to write {:?}: {}", output_path, e))?,
+    }
+
+    Ok(CatalogSummary {
+        output_path: output_path.to_string_lossy().to_string(),
+        format,
+        asset_count: catalog.assets.len(),
+        total_bytes: catalog.total_bytes,
+    })
+}
+
+fn write_csv(catalog: &AssetCatalog, output_path: &Path) -> csv::Result<()> {
+    let mut writer = csv::Writer::from_writer(File::create(output_path)?);
+    writer.write_record([
+        "id",
+        "name",
+        "file_path",
+        "asset_type",
+        "collection",
+        "file_size",
+        "checksum",
+        "checksum_mode",
+        "tags",
+        "license",
+        "updated_at",
+    ])?;
+    for asset in &catalog.assets {
+        writer.write_record([
+            asset.id.to_string(),
+            asset.name.clone(),
+            asset.file_path.clone(),
+            asset.asset_type.clone(),
+            asset.collection.clone(),
+            asset.file_size.to_string(),
+            asset.checksum.clone(),
+            asset.checksum_mode.as_str().to_string(),
+            asset.tags.join(CSV_TAG_SEPARATOR),
+            asset.license.clone().unwrap_or_default(),
+            asset.updated_at.to_rfc3339(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn exports_json_and_csv() {
+        let temp_dir = tempdir().unwrap();
+        let assets_dir = temp_dir.path().join("Assets/Kenney");
+        fs::create_dir_all(&assets_dir).unwrap();
+        fs::write(assets_dir.join("crate, wooden.png"), b"png").unwrap();
+        fs::write(assets_dir.join("step.ogg"), b"ogg").unwrap();
+
+        let mut database = AssetDatabase::new(&temp_dir.path().join("assets.db")).unwrap();
+        for file in ["crate, wooden.png", "step.ogg"] {
+            database
+                .insert_asset(&assets_dir.join(file), "Kenney", &assets_dir)
+                .unwrap();
+        }
+
+        let json_path = temp_dir.path().join("out/catalog.json");
+        let summary = export_catalog(&database, &json_path, None).unwrap();
+        assert_eq!(
+            (summary.format, summary.asset_count),
+            (CatalogFormat::Json, 2)
+        );
+        let catalog: AssetCatalog =
+            serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(catalog.total_bytes, 6);
+        assert!(catalog.assets[0]
+            .license
+            .as_ref()
+            .unwrap()
+            .starts_with("CC0"));
+
+        let csv_path = temp_dir.path().join("catalog.txt");
+        assert!(export_catalog(&database, &csv_path, None).is_err());
+        export_catalog(&database, &csv_path, Some(CatalogFormat::Csv)).unwrap();
+        let mut reader = csv::Reader::from_path(&csv_path).unwrap();
+        let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[0][1], "crate, wooden.png");
+        assert_eq!(&rows[1][3], "Audio");
+    }
+}
@@ -0,0 +1,143 @@
+// Copying assets a level uses from elsewhere on disk into the project's asset folder
+use super::bundle::BundlePlan;
+use super::database::AssetDatabase;
+use super::paths::{AssetPathResolver, AssetRef};
+use crate::LevelData;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CollectSummary {
+    /// Files copied in, relative to the asset folder
+    pub copied: Vec<String>,
+    /// References that couldn't be found
+    pub missing: Vec<String>,
+    /// Files whose place in the asset folder is taken by a different file
+    pub conflicts: Vec<String>,
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Copy every asset `level` references from outside `assets_dir` into its `external`
+/// folder, along with the files those assets load.
+///
+/// Returns the replacements that make the level's references relative to the asset
+/// folder: absolute paths, and asset IDs of files that were copied in. References
+/// that would land on a different file already in the folder are left alone.
+pub fn collect_level_assets(
+    level: &LevelData,
+    resolver: &AssetPathResolver,
+    assets_dir: &Path,
+    database: Option<&AssetDatabase>,
+) -> Result<(CollectSummary, HashMap<String, String>), String> {
+    let mut plan = BundlePlan::default();
+    let collected = plan.add_level(level, resolver, database);
+    let mut summary = CollectSummary {
+        missing: std::mem::take(&mut plan.missing),
+        ..CollectSummary::default()
+    };
+
+    let mut conflicts = BTreeSet::new();
+    for (relative, source) in &plan.files {
+        let target = assets_dir.join(relative);
+        if same_file(&target, source) {
+            continue;
+        }
+        if target.exists() {
+            let unchanged =
+                matches!((fs::read(&target), fs::read(source)), (Ok(a), Ok(b)) if a == b);
+            if !unchanged {
+                conflicts.insert(relative.clone());
+            }
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+        }
+        fs::copy(source, &target)
+            .map_err(|e| format!("Failed to copy {:?} to {:?}: {}", source, target, e))?;
+        summary.copied.push(relative.clone());
+    }
+
+    let mut replacements = HashMap::new();
+    for (original, collected) in level.objects.iter().zip(&collected.objects) {
+        for (from, to) in [
+            (&original.mesh, &collected.mesh),
+            (&original.material, &collected.material),
+        ] {
+            let (Some(from), Some(to)) = (from, to) else {
+                continue;
+            };
+            let rewrite = match AssetRef::parse(from) {
+                AssetRef::Absolute(_) => true,
+                AssetRef::Id(_) => summary.copied.contains(to),
+                AssetRef::Relative(_) => false,
+            };
+            if rewrite && from != to && !conflicts.contains(to) {
+                replacements.insert(from.clone(), to.clone());
+            }
+        }
+    }
+    summary.conflicts = conflicts.into_iter().collect();
+    Ok((summary, replacements))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::usage::rewrite_references;
+    use crate::testing::{level, prop};
+    use crate::GameObject;
+    use tempfile::tempdir;
+
+    fn object(id: &str, mesh: &Path) -> GameObject {
+        prop(id, &mesh.to_string_lossy())
+    }
+
+    #[test]
+    fn copies_outside_assets_in_and_rewrites_their_references() {
+        let temp_dir = tempdir().unwrap();
+        let assets_dir = temp_dir.path().join("Assets");
+        let downloads = temp_dir.path().join("Downloads");
+        fs::create_dir_all(assets_dir.join("external")).unwrap();
+        fs::create_dir_all(&downloads).unwrap();
+        fs::write(downloads.join("lamp.obj"), "mtllib lamp.mtl\nv 0 0 0\n").unwrap();
+        fs::write(downloads.join("lamp.mtl"), "newmtl brass\n").unwrap();
+        fs::write(downloads.join("chest.obj"), "v 1 1 1\n").unwrap();
+        // Someone else's chest already sits where this one would go
+        fs::write(assets_dir.join("external/chest.obj"), "v 2 2 2\n").unwrap();
+        fs::write(assets_dir.join("crate.obj"), "v 0 0 0\n").unwrap();
+
+        let mut level = level(vec![
+            object("lamp", &downloads.join("lamp.obj")),
+            object("chest", &downloads.join("chest.obj")),
+            object("crate", &assets_dir.join("crate.obj")),
+        ]);
+
+        let resolver = AssetPathResolver::new(&assets_dir);
+        let (summary, replacements) =
+            collect_level_assets(&level, &resolver, &assets_dir, None).unwrap();
+        assert_eq!(summary.copied, ["external/lamp.mtl", "external/lamp.obj"]);
+        assert_eq!(summary.conflicts, ["external/chest.obj"]);
+        assert!(summary.missing.is_empty());
+        assert!(assets_dir.join("external/lamp.mtl").is_file());
+
+        rewrite_references(&mut level, &replacements);
+        let meshes: Vec<_> = level
+            .objects
+            .iter()
+            .map(|obj| obj.mesh.clone().unwrap())
+            .collect();
+        assert_eq!(meshes[0], "external/lamp.obj");
+        assert_eq!(Path::new(&meshes[1]), downloads.join("chest.obj"));
+        assert_eq!(meshes[2], "crate.obj");
+    }
+}
@@ -1,10 +1,22 @@
+use super::atlas::SpriteFrame;
+use super::file_types::{FileTypes, AUDIO, MATERIAL, MODEL, TEXTURE};
+use super::lod::{self, LodGroup, LodLevel};
+use super::metadata;
+use super::migrations;
+use super::waveform::{self, Waveform};
 use chrono::{DateTime, Utc};
-use log::info;
-use rusqlite::{params, Connection, Result as SqlResult, Transaction};
+use log::{info, warn};
+use rusqlite::{
+    params, Connection, DatabaseName, OpenFlags, OptionalExtension, Result as SqlResult,
+};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::fs;
-use std::path::Path;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetRecord {
@@ -13,12 +25,200 @@ pub struct AssetRecord {
     pub file_path: String,
     pub asset_type: String,
     pub collection: String,
+    /// Asset root directory the file was found under
+    pub asset_root: Option<String>,
     pub file_size: i64,
     pub checksum: String,
+    /// How `checksum` was computed
+    pub checksum_mode: ChecksumMode,
+    /// User rating from 1 to 5 stars
+    pub rating: Option<u8>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Change-detection data for a file already in the database.
+#[derive(Debug, Clone)]
+pub struct IndexedFile {
+    pub asset_id: i64,
+    pub collection: String,
+    pub file_size: i64,
+    pub file_mtime: Option<i64>,
+    pub checksum: String,
+    pub checksum_mode: ChecksumMode,
+}
+
+/// File modification time in milliseconds since the Unix epoch.
+pub fn file_mtime_millis(metadata: &fs::Metadata) -> Option<i64> {
+    metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_millis() as i64)
+}
+
+/// Bytes hashed from each end of a file by [`ChecksumMode::Fingerprint`].
+const FINGERPRINT_CHUNK: u64 = 1024 * 1024;
+
+/// How a file's checksum was computed. Checksums are only comparable within one mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumMode {
+    /// SHA-256 of the whole file
+    #[default]
+    Full,
+    /// SHA-256 of the size and the first and last MiB, for scanning huge files quickly;
+    /// misses edits confined to the middle of a file
+    Fingerprint,
+}
+
+impl ChecksumMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Fingerprint => "fingerprint",
+        }
+    }
+
+    fn from_column(value: &str) -> Self {
+        match value {
+            "fingerprint" => Self::Fingerprint,
+            _ => Self::Full,
+        }
+    }
+}
+
+/// SHA-256 of a file in lowercase hex, streamed so large files are never held in memory.
+pub fn file_checksum(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Checksum a file in `mode`, returning the mode actually used: files too small for the
+/// ends to matter are always hashed in full.
+pub fn checksum_with_mode(
+    path: &Path,
+    file_size: u64,
+    mode: ChecksumMode,
+) -> std::io::Result<(String, ChecksumMode)> {
+    if mode == ChecksumMode::Full || file_size <= 2 * FINGERPRINT_CHUNK {
+        return Ok((file_checksum(path)?, ChecksumMode::Full));
+    }
+
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    hasher.update(file_size.to_le_bytes());
+    std::io::copy(&mut (&mut file).take(FINGERPRINT_CHUNK), &mut hasher)?;
+    file.seek(SeekFrom::End(-(FINGERPRINT_CHUNK as i64)))?;
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok((
+        format!("{:x}", hasher.finalize()),
+        ChecksumMode::Fingerprint,
+    ))
+}
+
+/// Everything read from an asset file for its database row, gathered without a
+/// connection so files can be prepared on several threads at once.
+#[derive(Debug, Clone)]
+pub struct PreparedAsset {
+    pub path: PathBuf,
+    pub asset_type: String,
+    pub file_size: i64,
+    pub file_mtime: Option<i64>,
+    pub checksum: String,
+    pub checksum_mode: ChecksumMode,
+    /// Left empty when the contents match what is already indexed
+    metadata: Vec<(Cow<'static, str>, String)>,
+    waveform: Option<Vec<f32>>,
+}
+
+impl PreparedAsset {
+    /// Stat and hash a file, extracting its metadata unless the contents still match
+    /// the `previous` checksum.
+    pub fn read(
+        path: &Path,
+        file_types: &FileTypes,
+        mode: ChecksumMode,
+        previous: Option<(&str, ChecksumMode)>,
+    ) -> Result<Self, String> {
+        let stat = fs::metadata(path).map_err(|e| e.to_string())?;
+        let (checksum, checksum_mode) =
+            checksum_with_mode(path, stat.len(), mode).map_err(|e| e.to_string())?;
+        let mut prepared = Self {
+            path: path.to_path_buf(),
+            asset_type: file_types.asset_type(path).unwrap_or("Unknown").to_string(),
+            file_size: stat.len() as i64,
+            file_mtime: file_mtime_millis(&stat),
+            checksum,
+            checksum_mode,
+            metadata: Vec::new(),
+            waveform: None,
+        };
+        if previous != Some((prepared.checksum.as_str(), checksum_mode)) {
+            prepared.extract_metadata(file_types);
+        }
+        Ok(prepared)
+    }
+
+    fn extract_metadata(&mut self, file_types: &FileTypes) {
+        let path = self.path.clone();
+        let static_keys = |entries: metadata::MetadataEntries| {
+            entries
+                .into_iter()
+                .map(|(key, value)| (Cow::Borrowed(key), value))
+                .collect::<Vec<_>>()
+        };
+        let asset_type = self.asset_type.as_str();
+        let (kind, entries) = match asset_type {
+            TEXTURE => ("image", metadata::image_metadata(&path).map(static_keys)),
+            AUDIO => ("audio", metadata::audio_metadata(&path).map(static_keys)),
+            MODEL => ("model", metadata::model_metadata(&path).map(static_keys)),
+            MATERIAL => (
+                "material",
+                metadata::material_metadata(&path, file_types).map(static_keys),
+            ),
+            custom => match file_types.extractor(custom) {
+                Some(extractor) => (
+                    custom,
+                    metadata::extracted_metadata(extractor, &path).map(|entries| {
+                        entries
+                            .into_iter()
+                            .map(|(key, value)| (Cow::Owned(key), value))
+                            .collect()
+                    }),
+                ),
+                None => return,
+            },
+        };
+
+        if let Some(ext) = path.extension() {
+            let ext = ext.to_string_lossy();
+            // Textures and audio have always recorded the extension as written
+            let format = if matches!(asset_type, TEXTURE | AUDIO) {
+                ext.to_string()
+            } else {
+                ext.to_lowercase()
+            };
+            self.metadata.push((Cow::Borrowed("format"), format));
+        }
+        // A broken file is still indexed, just without its properties
+        match entries {
+            Ok(entries) => self.metadata.extend(entries),
+            Err(e) => warn!("No {} metadata for {:?}: {}", kind, path, e),
+        }
+
+        if self.asset_type == AUDIO {
+            match waveform::audio_peaks(&path, waveform::WAVEFORM_POINTS) {
+                Ok(peaks) => self.waveform = Some(peaks),
+                Err(e) => warn!("No waveform for {:?}: {}", path, e),
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetMetadata {
     pub asset_id: i64,
@@ -26,6 +226,52 @@ pub struct AssetMetadata {
     pub value: String,
 }
 
+/// One asset reference recorded from a saved level file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetUsage {
+    pub level_path: String,
+    pub asset_id: i64,
+    /// The reference as written in the level
+    pub reference: String,
+    pub object_count: usize,
+}
+
+/// A rescan finding different contents for an indexed file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetChange {
+    pub id: i64,
+    pub asset_id: i64,
+    pub old_checksum: String,
+    pub new_checksum: String,
+    pub old_size: i64,
+    pub new_size: i64,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// A texture named by a material file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterialTexture {
+    /// The path as written in the material
+    pub reference: String,
+    /// Indexed texture the path resolves to, if any
+    pub asset_id: Option<i64>,
+}
+
+/// Disk space taken by the indexed files of one collection or asset type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageUsage {
+    pub name: String,
+    pub asset_count: i64,
+    pub total_bytes: i64,
+}
+
+/// Rows in a per-asset table whose asset no longer exists.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedRows {
+    pub table: String,
+    pub count: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Collection {
     pub id: i64,
@@ -50,8 +296,119 @@ pub struct AssetSearchResult {
     pub has_thumbnail: bool,
 }
 
+/// Column to order search results by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetSortField {
+    #[default]
+    Name,
+    Size,
+    Type,
+    DateAdded,
+    Rating,
+}
+
+impl AssetSortField {
+    fn column(self) -> &'static str {
+        match self {
+            AssetSortField::Name => "a.name",
+            AssetSortField::Size => "a.file_size",
+            AssetSortField::Type => "a.asset_type",
+            AssetSortField::DateAdded => "a.created_at",
+            AssetSortField::Rating => "a.rating",
+        }
+    }
+}
+
+/// Comparison applied to a metadata value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataComparison {
+    Equals,
+    /// Numeric `>=`
+    AtLeast,
+    /// Numeric `<=`
+    AtMost,
+}
+
+/// Restricts a search to assets whose metadata `key` matches, e.g. `width` at least `2048`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataFilter {
+    pub key: String,
+    pub comparison: MetadataComparison,
+    pub value: String,
+}
+
+/// Filters, ordering and paging for an asset search.
+#[derive(Debug, Clone, Default)]
+pub struct AssetQuery {
+    pub query: String,
+    pub asset_type: Option<String>,
+    pub collection: Option<String>,
+    pub metadata: Vec<MetadataFilter>,
+    pub sort_by: AssetSortField,
+    pub descending: bool,
+    pub offset: usize,
+    /// `None` returns every match
+    pub limit: Option<usize>,
+}
+
+/// One page of search results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetSearchPage {
+    pub results: Vec<AssetSearchResult>,
+    pub offset: usize,
+    /// Number of assets matching the filters across all pages
+    pub total: usize,
+    /// Offset of the next page, if there is one
+    pub next_offset: Option<usize>,
+}
+
+const ASSET_SELECT: &str = "SELECT a.id, a.name, a.file_path, a.asset_type, a.collection,
+        a.file_size, a.checksum, a.created_at, a.updated_at, a.asset_root, a.rating,
+        CASE WHEN t.asset_id IS NOT NULL THEN 1 ELSE 0 END as has_thumbnail, a.checksum_mode
+     FROM assets a
+     LEFT JOIN thumbnails t ON a.id = t.asset_id";
+
+/// Map a row selected with `ASSET_SELECT` to a record and its thumbnail flag.
+fn asset_from_row(row: &rusqlite::Row) -> SqlResult<(AssetRecord, bool)> {
+    Ok((
+        AssetRecord {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            file_path: row.get(2)?,
+            asset_type: row.get(3)?,
+            collection: row.get(4)?,
+            asset_root: row.get(9)?,
+            file_size: row.get(5)?,
+            checksum: row.get(6)?,
+            checksum_mode: ChecksumMode::from_column(&row.get::<usize, String>(12)?),
+            rating: row.get(10)?,
+            created_at: row.get(7)?,
+            updated_at: row.get(8)?,
+        },
+        row.get::<usize, i32>(11)? == 1, // has_thumbnail
+    ))
+}
+
+/// How long a connection waits for another one's write lock before giving up.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Tables keyed by `asset_id`. Cascading deletes keep them clean, but databases written
+/// while foreign keys weren't enforced can still hold rows for deleted assets.
+const ASSET_TABLES: [&str; 7] = [
+    "asset_metadata",
+    "thumbnails",
+    "asset_tags",
+    "asset_usage",
+    "asset_history",
+    "waveforms",
+    "lod_members",
+];
+
 pub struct AssetDatabase {
     connection: Connection,
+    file_types: FileTypes,
 }
 
 impl AssetDatabase {
@@ -62,88 +419,49 @@ impl AssetDatabase {
         }
 
         let connection = Connection::open(db_path)?;
-        let mut db = Self { connection };
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        // Write-ahead logging lets readers carry on while a scan is writing
+        connection.pragma_update_and_check(None, "journal_mode", "WAL", |row| {
+            row.get::<usize, String>(0)
+        })?;
+
+        let mut db = Self {
+            connection,
+            file_types: FileTypes::default(),
+        };
         db.initialize_schema()?;
         Ok(db)
     }
 
+    /// Open a second, read-only connection to a database already set up by `new`,
+    /// for queries that shouldn't wait behind scans and imports.
+    pub fn open_reader<P: AsRef<Path>>(db_path: P) -> SqlResult<Self> {
+        let connection = Connection::open_with_flags(
+            db_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        Ok(Self {
+            connection,
+            file_types: FileTypes::default(),
+        })
+    }
+
     fn initialize_schema(&mut self) -> SqlResult<()> {
         info!("Initializing asset database schema");
 
         // Enable foreign keys
         self.connection.execute("PRAGMA foreign_keys = ON", [])?;
 
-        // Collections table
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS collections (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT UNIQUE NOT NULL,
-                description TEXT,
-                license_info TEXT,
-                asset_count INTEGER DEFAULT 0,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )?;
-
-        // Assets table
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS assets (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL,
-                file_path TEXT UNIQUE NOT NULL,
-                asset_type TEXT NOT NULL,
-                collection TEXT NOT NULL,
-                file_size INTEGER NOT NULL,
-                checksum TEXT NOT NULL,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (collection) REFERENCES collections (name)
-            )",
-            [],
-        )?;
-
-        // Asset metadata table (key-value pairs)
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS asset_metadata (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                asset_id INTEGER NOT NULL,
-                key TEXT NOT NULL,
-                value TEXT NOT NULL,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (asset_id) REFERENCES assets (id) ON DELETE CASCADE,
-                UNIQUE(asset_id, key)
-            )",
-            [],
-        )?;
-
-        // Thumbnails table
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS thumbnails (
-                asset_id INTEGER PRIMARY KEY,
-                thumbnail_path TEXT NOT NULL,
-                generated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (asset_id) REFERENCES assets (id) ON DELETE CASCADE
-            )",
-            [],
-        )?;
-
-        // Asset tags table (many-to-many)
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS asset_tags (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                asset_id INTEGER NOT NULL,
-                tag_name TEXT NOT NULL,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (asset_id) REFERENCES assets (id) ON DELETE CASCADE,
-                UNIQUE(asset_id, tag_name)
-            )",
-            [],
-        )?;
-
-        // Create indexes for performance
-        self.create_indexes()?;
+        // Create or upgrade tables and indexes
+        let previous_version = migrations::run(&mut self.connection)?;
+        if previous_version < migrations::latest_version() {
+            info!(
+                "Asset database schema upgraded from version {} to {}",
+                previous_version,
+                migrations::latest_version()
+            );
+        }
 
         // Insert default collections
         self.insert_default_collections()?;
@@ -152,43 +470,6 @@ impl AssetDatabase {
         Ok(())
     }
 
-    fn create_indexes(&mut self) -> SqlResult<()> {
-        // Search optimization indexes
-        self.connection.execute(
-            "CREATE INDEX IF NOT EXISTS idx_assets_name ON assets(name)",
-            [],
-        )?;
-
-        self.connection.execute(
-            "CREATE INDEX IF NOT EXISTS idx_assets_type ON assets(asset_type)",
-            [],
-        )?;
-
-        self.connection.execute(
-            "CREATE INDEX IF NOT EXISTS idx_assets_collection ON assets(collection)",
-            [],
-        )?;
-
-        self.connection.execute(
-            "CREATE INDEX IF NOT EXISTS idx_assets_search ON assets(name, asset_type, collection)",
-            [],
-        )?;
-
-        // Metadata search index
-        self.connection.execute(
-            "CREATE INDEX IF NOT EXISTS idx_metadata_key ON asset_metadata(key)",
-            [],
-        )?;
-
-        // Tags search index
-        self.connection.execute(
-            "CREATE INDEX IF NOT EXISTS idx_tags_name ON asset_tags(tag_name)",
-            [],
-        )?;
-
-        Ok(())
-    }
-
     fn insert_default_collections(&mut self) -> SqlResult<()> {
         let collections = [
             (
@@ -222,100 +503,550 @@ impl AssetDatabase {
         &mut self,
         asset_path: &Path,
         collection: &str,
+        asset_root: &Path,
     ) -> Result<i64, Box<dyn std::error::Error>> {
-        let metadata = fs::metadata(asset_path)?;
-        let file_size = metadata.len() as i64;
+        let prepared = PreparedAsset::read(asset_path, &self.file_types, ChecksumMode::Full, None)?;
+        let asset_id = self.insert_prepared(&prepared, collection, asset_root)?;
 
-        // Calculate checksum
-        let checksum = self.calculate_file_checksum(asset_path)?;
+        // Update collection asset count
+        self.update_collection_count(collection)?;
+        Ok(asset_id)
+    }
 
-        let file_name = asset_path
+    /// Insert a file read by [`PreparedAsset::read`].
+    ///
+    /// The collection's asset count is left for the caller to refresh, so batches
+    /// only recount once.
+    pub fn insert_prepared(
+        &mut self,
+        prepared: &PreparedAsset,
+        collection: &str,
+        asset_root: &Path,
+    ) -> Result<i64, Box<dyn std::error::Error>> {
+        let file_name = prepared
+            .path
             .file_name()
             .and_then(|n| n.to_str())
             .ok_or("Invalid filename")?;
 
-        let asset_type = self.determine_asset_type(asset_path);
-        let file_path_str = asset_path.to_string_lossy().to_string();
-
-        let _asset_id = self.connection.execute(
-            "INSERT INTO assets (name, file_path, asset_type, collection, file_size, checksum) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        self.connection.execute(
+            "INSERT INTO assets (name, file_path, asset_type, collection, asset_root, file_size, file_mtime, checksum, checksum_mode) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
                 file_name,
-                file_path_str,
-                asset_type,
+                prepared.path.to_string_lossy(),
+                prepared.asset_type,
                 collection,
-                file_size,
-                checksum
+                asset_root.to_string_lossy(),
+                prepared.file_size,
+                prepared.file_mtime,
+                prepared.checksum,
+                prepared.checksum_mode.as_str()
             ],
         )?;
 
         let asset_id = self.connection.last_insert_rowid();
-
-        // Extract and store metadata based on file type
-        self.extract_and_store_metadata(asset_id, asset_path)?;
-
-        // Update collection asset count
-        self.update_collection_count(collection)?;
+        self.store_metadata(asset_id, prepared)?;
+        self.set_lod_member(asset_id, &prepared.path, &prepared.asset_type)?;
 
         info!("Inserted asset: {} (ID: {})", file_name, asset_id);
         Ok(asset_id)
     }
 
-    fn calculate_file_checksum(
-        &self,
-        file_path: &Path,
-    ) -> Result<String, Box<dyn std::error::Error>> {
-        let contents = fs::read(file_path)?;
-        let mut hasher = Sha256::new();
-        hasher.update(&contents);
-        Ok(format!("{:x}", hasher.finalize()))
+    /// Size and modification time of every indexed file, keyed by path.
+    pub fn get_file_index(&self) -> SqlResult<HashMap<String, IndexedFile>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, file_path, collection, file_size, file_mtime, checksum, checksum_mode
+             FROM assets",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<usize, String>(1)?,
+                IndexedFile {
+                    asset_id: row.get(0)?,
+                    collection: row.get(2)?,
+                    file_size: row.get(3)?,
+                    file_mtime: row.get(4)?,
+                    checksum: row.get(5)?,
+                    checksum_mode: ChecksumMode::from_column(&row.get::<usize, String>(6)?),
+                },
+            ))
+        })?;
+
+        rows.collect()
     }
 
-    pub fn determine_asset_type(&self, file_path: &Path) -> String {
-        match file_path.extension().and_then(|ext| ext.to_str()) {
-            Some("fbx" | "FBX") => "Model",
-            Some("png" | "PNG" | "jpg" | "JPG" | "jpeg" | "JPEG") => {
-                "Texture"
-            }
-            Some("wav" | "WAV" | "mp3" | "MP3" | "ogg" | "OGG") => {
-                "Audio"
+    /// Change-detection data for a single file, if it is indexed.
+    pub fn get_indexed_file(&self, file_path: &str) -> SqlResult<Option<IndexedFile>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, collection, file_size, file_mtime, checksum, checksum_mode FROM assets
+             WHERE file_path = ?1",
+        )?;
+        let mut rows = stmt.query_map([file_path], |row| {
+            Ok(IndexedFile {
+                asset_id: row.get(0)?,
+                collection: row.get(1)?,
+                file_size: row.get(2)?,
+                file_mtime: row.get(3)?,
+                checksum: row.get(4)?,
+                checksum_mode: ChecksumMode::from_column(&row.get::<usize, String>(5)?),
+            })
+        })?;
+        rows.next().transpose()
+    }
+
+    /// Re-read an indexed file after it changed on disk, hashing it the same way as before.
+    ///
+    /// Returns `false` if the contents are identical and only the timestamp moved.
+    pub fn update_asset(
+        &mut self,
+        asset_id: i64,
+        asset_path: &Path,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let (checksum, mode): (String, String) = self.connection.query_row(
+            "SELECT checksum, checksum_mode FROM assets WHERE id = ?1",
+            [asset_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let mode = ChecksumMode::from_column(&mode);
+        let prepared =
+            PreparedAsset::read(asset_path, &self.file_types, mode, Some((&checksum, mode)))?;
+        self.update_prepared(asset_id, &prepared)
+    }
+
+    /// Update an indexed file from a fresh [`PreparedAsset::read`], recording a history
+    /// row if its contents changed.
+    ///
+    /// Checksums from different modes can't be compared, so switching modes only
+    /// replaces the checksum and never counts as a change.
+    pub fn update_prepared(
+        &mut self,
+        asset_id: i64,
+        prepared: &PreparedAsset,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let (old_checksum, old_size, old_mode): (String, i64, String) = self.connection.query_row(
+            "SELECT checksum, file_size, checksum_mode FROM assets WHERE id = ?1",
+            [asset_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+        let checksum_changed = old_checksum != prepared.checksum;
+        let content_changed =
+            checksum_changed && ChecksumMode::from_column(&old_mode) == prepared.checksum_mode;
+
+        // A savepoint rather than a transaction, as scans call this inside their own
+        let savepoint = self.connection.savepoint()?;
+        savepoint.execute(
+            "UPDATE assets SET file_size = ?1, file_mtime = ?2, checksum = ?3, checksum_mode = ?4,
+             updated_at = CASE WHEN ?5 THEN CURRENT_TIMESTAMP ELSE updated_at END
+             WHERE id = ?6",
+            params![
+                prepared.file_size,
+                prepared.file_mtime,
+                prepared.checksum,
+                prepared.checksum_mode.as_str(),
+                content_changed,
+                asset_id
+            ],
+        )?;
+        if content_changed {
+            savepoint.execute(
+                "INSERT INTO asset_history (asset_id, old_checksum, new_checksum, old_size, new_size)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    asset_id,
+                    old_checksum,
+                    prepared.checksum,
+                    old_size,
+                    prepared.file_size
+                ],
+            )?;
+        }
+        savepoint.commit()?;
+
+        if checksum_changed {
+            self.store_metadata(asset_id, prepared)?;
+        }
+        if content_changed {
+            info!("Updated asset: {:?} (ID: {})", prepared.path, asset_id);
+        }
+        Ok(content_changed)
+    }
+
+    /// ID of an asset with identical contents, if one is indexed.
+    pub fn find_asset_by_checksum(&self, checksum: &str) -> SqlResult<Option<i64>> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT id FROM assets WHERE checksum = ?1 ORDER BY id LIMIT 1")?;
+        let mut rows = stmt.query_map([checksum], |row| row.get(0))?;
+        rows.next().transpose()
+    }
+
+    /// Create a collection if it doesn't exist yet, so assets can reference it.
+    pub fn ensure_collection(&mut self, name: &str) -> SqlResult<()> {
+        self.connection.execute(
+            "INSERT OR IGNORE INTO collections (name) VALUES (?1)",
+            params![name],
+        )?;
+        Ok(())
+    }
+
+    /// Delete an asset row; metadata, tags and thumbnails cascade.
+    pub fn remove_asset(&mut self, asset_id: i64, collection: &str) -> SqlResult<()> {
+        self.connection
+            .execute("DELETE FROM assets WHERE id = ?1", [asset_id])?;
+        self.update_collection_count(collection)?;
+        info!("Removed asset ID: {}", asset_id);
+        Ok(())
+    }
+
+    /// ID of the asset stored at `path`, however the path is spelled.
+    ///
+    /// Files are stored under the root they were scanned from, which may be relative,
+    /// so the path is also tried against each known root.
+    pub fn find_asset_by_location(&self, path: &Path) -> SqlResult<Option<i64>> {
+        if let Some(indexed) = self.get_indexed_file(&path.to_string_lossy())? {
+            return Ok(Some(indexed.asset_id));
+        }
+
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let mut stmt = self
+            .connection
+            .prepare("SELECT DISTINCT asset_root FROM assets WHERE asset_root IS NOT NULL")?;
+        let roots = stmt
+            .query_map([], |row| row.get::<usize, String>(0))?
+            .collect::<SqlResult<Vec<String>>>()?;
+        for root in roots {
+            let Ok(canonical_root) = Path::new(&root).canonicalize() else {
+                continue;
+            };
+            if let Ok(relative) = canonical.strip_prefix(&canonical_root) {
+                let stored = Path::new(&root).join(relative);
+                if let Some(indexed) = self.get_indexed_file(&stored.to_string_lossy())? {
+                    return Ok(Some(indexed.asset_id));
+                }
             }
-            Some("mat" | "MAT") => "Material",
-            _ => "Unknown",
         }
-        .to_string()
+        Ok(None)
     }
 
-    fn extract_and_store_metadata(
+    /// Point an asset row at the file's new location after it was moved on disk.
+    ///
+    /// Metadata, tags, thumbnails and usage rows are keyed by ID and carry over.
+    pub fn move_asset(
         &mut self,
         asset_id: i64,
-        asset_path: &Path,
+        new_path: &Path,
+        collection: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let asset_type = self.determine_asset_type(asset_path);
-
-        match asset_type.as_str() {
-            "Texture" => {
-                // For images, we could use an image library to extract dimensions
-                // For now, just store file extension
-                if let Some(ext) = asset_path.extension() {
-                    self.insert_metadata(asset_id, "format", ext.to_string_lossy().as_ref())?;
-                }
+        let (old_collection, asset_type): (String, String) = self.connection.query_row(
+            "SELECT collection, asset_type FROM assets WHERE id = ?1",
+            [asset_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let name = new_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or("Invalid filename")?;
+
+        self.ensure_collection(collection)?;
+        self.connection.execute(
+            "UPDATE assets SET name = ?1, file_path = ?2, collection = ?3,
+             updated_at = CURRENT_TIMESTAMP WHERE id = ?4",
+            params![name, new_path.to_string_lossy(), collection, asset_id],
+        )?;
+        self.set_lod_member(asset_id, new_path, &asset_type)?;
+        self.update_collection_count(&old_collection)?;
+        if old_collection != collection {
+            self.update_collection_count(collection)?;
+        }
+        info!("Moved asset {} to {:?}", asset_id, new_path);
+        Ok(())
+    }
+
+    /// File a model under the LOD group its name puts it in, if any.
+    fn set_lod_member(&self, asset_id: i64, path: &Path, asset_type: &str) -> SqlResult<()> {
+        self.connection
+            .execute("DELETE FROM lod_members WHERE asset_id = ?1", [asset_id])?;
+        if let Some(member) = lod::lod_member(path).filter(|_| asset_type == MODEL) {
+            self.connection.execute(
+                "INSERT INTO lod_members (asset_id, group_key, group_name, lod_level)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![asset_id, member.key, member.name, member.level],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// The LOD set a model belongs to, from most to least detailed.
+    pub fn get_lod_group(&self, asset_id: i64) -> SqlResult<Option<LodGroup>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT g.group_name, g.lod_level, g.asset_id, a.file_path
+             FROM lod_members m
+             JOIN lod_members g ON g.group_key = m.group_key
+             JOIN assets a ON a.id = g.asset_id
+             WHERE m.asset_id = ?1
+             ORDER BY g.lod_level, a.file_path",
+        )?;
+        let rows = stmt.query_map([asset_id], |row| {
+            Ok((
+                row.get::<usize, String>(0)?,
+                LodLevel {
+                    level: row.get(1)?,
+                    asset_id: row.get(2)?,
+                    file_path: row.get(3)?,
+                },
+            ))
+        })?;
+        let (names, levels): (Vec<String>, Vec<LodLevel>) =
+            rows.collect::<SqlResult<Vec<_>>>()?.into_iter().unzip();
+        Ok(names
+            .into_iter()
+            .next()
+            .map(|name| LodGroup { name, levels }))
+    }
+
+    /// Replace everything recorded for a level file with `usages`.
+    pub fn record_level_usage(&mut self, level_path: &str, usages: &[AssetUsage]) -> SqlResult<()> {
+        let tx = self.connection.transaction()?;
+        tx.execute(
+            "DELETE FROM asset_usage WHERE level_path = ?1",
+            [level_path],
+        )?;
+        for usage in usages {
+            // Assets deleted since the level was written have nothing to record
+            tx.execute(
+                "INSERT OR REPLACE INTO asset_usage (level_path, asset_id, reference, object_count)
+                 SELECT ?1, id, ?2, ?3 FROM assets WHERE id = ?4",
+                params![
+                    level_path,
+                    usage.reference,
+                    usage.object_count as i64,
+                    usage.asset_id
+                ],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Content changes recorded for an asset, newest first.
+    pub fn get_asset_history(&self, asset_id: i64) -> SqlResult<Vec<AssetChange>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, asset_id, old_checksum, new_checksum, old_size, new_size, changed_at
+             FROM asset_history WHERE asset_id = ?1 ORDER BY changed_at DESC, id DESC",
+        )?;
+        let rows = stmt.query_map([asset_id], |row| {
+            Ok(AssetChange {
+                id: row.get(0)?,
+                asset_id: row.get(1)?,
+                old_checksum: row.get(2)?,
+                new_checksum: row.get(3)?,
+                old_size: row.get(4)?,
+                new_size: row.get(5)?,
+                changed_at: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Textures a material refers to, resolved to indexed assets where possible.
+    pub fn get_material_textures(
+        &self,
+        material_id: i64,
+    ) -> Result<Vec<MaterialTexture>, Box<dyn std::error::Error>> {
+        let Some((file_path, asset_root, textures)) = self
+            .connection
+            .query_row(
+                "SELECT a.file_path, a.asset_root, m.value FROM assets a
+                 JOIN asset_metadata m ON m.asset_id = a.id AND m.key = 'textures'
+                 WHERE a.id = ?1",
+                [material_id],
+                |row| {
+                    Ok((
+                        row.get::<usize, String>(0)?,
+                        row.get::<usize, Option<String>>(1)?,
+                        row.get::<usize, String>(2)?,
+                    ))
+                },
+            )
+            .optional()?
+        else {
+            return Ok(Vec::new());
+        };
+        self.resolve_material_textures(&file_path, asset_root.as_deref(), &textures)
+    }
+
+    /// Materials whose texture references resolve to the given texture asset.
+    pub fn find_materials_using_texture(
+        &self,
+        texture_id: i64,
+    ) -> Result<Vec<AssetSearchResult>, Box<dyn std::error::Error>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT a.id, a.file_path, a.asset_root, m.value FROM assets a
+             JOIN asset_metadata m ON m.asset_id = a.id AND m.key = 'textures'
+             ORDER BY a.name",
+        )?;
+        let materials = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<usize, i64>(0)?,
+                    row.get::<usize, String>(1)?,
+                    row.get::<usize, Option<String>>(2)?,
+                    row.get::<usize, String>(3)?,
+                ))
+            })?
+            .collect::<SqlResult<Vec<_>>>()?;
+
+        let mut results = Vec::new();
+        for (material_id, file_path, asset_root, textures) in materials {
+            let uses_texture = self
+                .resolve_material_textures(&file_path, asset_root.as_deref(), &textures)?
+                .iter()
+                .any(|texture| texture.asset_id == Some(texture_id));
+            if uses_texture {
+                results.extend(self.get_asset_by_id(material_id)?);
             }
-            "Audio" => {
-                // For audio files, we could extract duration, sample rate, etc.
-                if let Some(ext) = asset_path.extension() {
-                    self.insert_metadata(asset_id, "format", ext.to_string_lossy().as_ref())?;
-                }
+        }
+        Ok(results)
+    }
+
+    /// Resolve a material's `textures` metadata next to the material, then under its root.
+    fn resolve_material_textures(
+        &self,
+        material_path: &str,
+        asset_root: Option<&str>,
+        textures: &str,
+    ) -> Result<Vec<MaterialTexture>, Box<dyn std::error::Error>> {
+        let references: Vec<String> = serde_json::from_str(textures)?;
+        let material_dir = Path::new(material_path)
+            .parent()
+            .unwrap_or_else(|| Path::new(""));
+
+        let mut resolved = Vec::new();
+        for reference in references {
+            let relative = PathBuf::from(reference.replace('\\', "/"));
+            let mut candidates = vec![normalize_path(&material_dir.join(&relative))];
+            if let Some(root) = asset_root {
+                candidates.push(normalize_path(&Path::new(root).join(&relative)));
             }
-            "Model" => {
-                // For FBX files, we could extract vertex count, material info, etc.
-                // This would require an FBX parser library
-                self.insert_metadata(asset_id, "format", "fbx")?;
+
+            let mut asset_id = None;
+            for candidate in candidates {
+                if let Some(indexed) = self.get_indexed_file(&candidate.to_string_lossy())? {
+                    asset_id = Some(indexed.asset_id);
+                    break;
+                }
             }
-            _ => {}
+            resolved.push(MaterialTexture {
+                reference,
+                asset_id,
+            });
         }
+        Ok(resolved)
+    }
+
+    /// Store an audio asset's waveform, replacing any earlier one.
+    pub fn set_waveform(
+        &mut self,
+        asset_id: i64,
+        peaks: &[f32],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO waveforms (asset_id, peaks, generated_at)
+             VALUES (?1, ?2, CURRENT_TIMESTAMP)",
+            params![asset_id, serde_json::to_string(peaks)?],
+        )?;
+        Ok(())
+    }
+
+    /// Waveform generated when an audio asset was scanned.
+    pub fn get_waveform(
+        &self,
+        asset_id: i64,
+    ) -> Result<Option<Waveform>, Box<dyn std::error::Error>> {
+        let peaks: Option<String> = self
+            .connection
+            .query_row(
+                "SELECT peaks FROM waveforms WHERE asset_id = ?1",
+                [asset_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let peaks = peaks
+            .map(|peaks| serde_json::from_str(&peaks))
+            .transpose()?;
+        Ok(peaks.map(|peaks| Waveform { asset_id, peaks }))
+    }
+
+    /// Frames recorded for a texture detected as a sprite sheet.
+    pub fn get_sprite_frames(
+        &self,
+        asset_id: i64,
+    ) -> Result<Option<Vec<SpriteFrame>>, Box<dyn std::error::Error>> {
+        let frames: Option<String> = self
+            .connection
+            .query_row(
+                "SELECT value FROM asset_metadata WHERE asset_id = ?1 AND key = 'sprite_frames'",
+                [asset_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(frames
+            .map(|frames| serde_json::from_str(&frames))
+            .transpose()?)
+    }
+
+    /// Level files known to reference an asset.
+    pub fn get_asset_usage(&self, asset_id: i64) -> SqlResult<Vec<AssetUsage>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT level_path, asset_id, reference, object_count FROM asset_usage
+             WHERE asset_id = ?1 ORDER BY level_path, reference",
+        )?;
+        let rows = stmt.query_map([asset_id], |row| {
+            Ok(AssetUsage {
+                level_path: row.get(0)?,
+                asset_id: row.get(1)?,
+                reference: row.get(2)?,
+                object_count: row.get::<usize, i64>(3)? as usize,
+            })
+        })?;
+        rows.collect()
+    }
+
+    pub fn calculate_file_checksum(
+        &self,
+        file_path: &Path,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(file_checksum(file_path)?)
+    }
+
+    pub fn determine_asset_type(&self, file_path: &Path) -> String {
+        self.file_types
+            .asset_type(file_path)
+            .unwrap_or("Unknown")
+            .to_string()
+    }
+
+    /// Extensions recognized as assets, and their types.
+    pub fn file_types(&self) -> &FileTypes {
+        &self.file_types
+    }
 
+    pub fn set_file_types(&mut self, file_types: FileTypes) {
+        self.file_types = file_types;
+    }
+
+    fn store_metadata(
+        &mut self,
+        asset_id: i64,
+        prepared: &PreparedAsset,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for (key, value) in &prepared.metadata {
+            self.insert_metadata(asset_id, key, value)?;
+        }
+        if let Some(peaks) = &prepared.waveform {
+            self.set_waveform(asset_id, peaks)?;
+        }
         Ok(())
     }
 
@@ -327,7 +1058,8 @@ impl AssetDatabase {
         Ok(())
     }
 
-    fn update_collection_count(&mut self, collection_name: &str) -> SqlResult<()> {
+    /// Recount a collection's assets after files were added to or removed from it.
+    pub fn update_collection_count(&mut self, collection_name: &str) -> SqlResult<()> {
         self.connection.execute(
             "UPDATE collections SET 
              asset_count = (SELECT COUNT(*) FROM assets WHERE collection = ?1),
@@ -338,60 +1070,82 @@ impl AssetDatabase {
         Ok(())
     }
 
+    /// Every asset matching the filters, sorted by name.
     pub fn search_assets(
         &self,
         query: &str,
         asset_type: Option<&str>,
         collection: Option<&str>,
     ) -> Result<Vec<AssetSearchResult>, Box<dyn std::error::Error>> {
-        let mut sql = String::from(
-            "SELECT a.id, a.name, a.file_path, a.asset_type, a.collection, 
-                    a.file_size, a.checksum, a.created_at, a.updated_at,
-                    CASE WHEN t.asset_id IS NOT NULL THEN 1 ELSE 0 END as has_thumbnail
-             FROM assets a
-             LEFT JOIN thumbnails t ON a.id = t.asset_id
-             WHERE 1=1",
-        );
+        let page = self.search_assets_page(&AssetQuery {
+            query: query.to_string(),
+            asset_type: asset_type.map(String::from),
+            collection: collection.map(String::from),
+            ..AssetQuery::default()
+        })?;
+        Ok(page.results)
+    }
 
+    /// One sorted page of matching assets, plus the total number of matches.
+    pub fn search_assets_page(
+        &self,
+        query: &AssetQuery,
+    ) -> Result<AssetSearchPage, Box<dyn std::error::Error>> {
+        let mut filter = String::from(" WHERE 1=1");
         let mut params = Vec::new();
 
-        if !query.is_empty() {
-            sql.push_str(" AND a.name LIKE ?");
-            params.push(format!("%{}%", query));
+        if !query.query.is_empty() {
+            filter.push_str(" AND a.name LIKE ?");
+            params.push(format!("%{}%", query.query));
         }
 
-        if let Some(asset_type) = asset_type {
-            sql.push_str(" AND a.asset_type = ?");
-            params.push(asset_type.to_string());
+        if let Some(asset_type) = &query.asset_type {
+            filter.push_str(" AND a.asset_type = ?");
+            params.push(asset_type.clone());
         }
 
-        if let Some(collection) = collection {
-            sql.push_str(" AND a.collection = ?");
-            params.push(collection.to_string());
+        if let Some(collection) = &query.collection {
+            filter.push_str(" AND a.collection = ?");
+            params.push(collection.clone());
         }
 
-        sql.push_str(" ORDER BY a.name ASC LIMIT 1000");
+        for condition in &query.metadata {
+            let value_check = match condition.comparison {
+                MetadataComparison::Equals => "m.value = ?",
+                MetadataComparison::AtLeast => "CAST(m.value AS REAL) >= CAST(? AS REAL)",
+                MetadataComparison::AtMost => "CAST(m.value AS REAL) <= CAST(? AS REAL)",
+            };
+            filter.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM asset_metadata m WHERE m.asset_id = a.id AND m.key = ? AND {})",
+                value_check
+            ));
+            params.push(condition.key.clone());
+            params.push(condition.value.clone());
+        }
 
-        let mut stmt = self.connection.prepare(&sql)?;
         let param_refs: Vec<&dyn rusqlite::ToSql> =
             params.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
 
-        let asset_iter = stmt.query_map(param_refs.as_slice(), |row| {
-            Ok((
-                AssetRecord {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    file_path: row.get(2)?,
-                    asset_type: row.get(3)?,
-                    collection: row.get(4)?,
-                    file_size: row.get(5)?,
-                    checksum: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                },
-                row.get::<usize, i32>(9)? == 1, // has_thumbnail
-            ))
-        })?;
+        let total: i64 = self.connection.query_row(
+            &format!("SELECT COUNT(*) FROM assets a{}", filter),
+            param_refs.as_slice(),
+            |row| row.get(0),
+        )?;
+        let total = total as usize;
+
+        // Name and ID break ties so pages stay stable
+        let sql = format!(
+            "{}{} ORDER BY {} {}, a.name ASC, a.id ASC LIMIT {} OFFSET {}",
+            ASSET_SELECT,
+            filter,
+            query.sort_by.column(),
+            if query.descending { "DESC" } else { "ASC" },
+            query.limit.map_or(-1, |limit| limit as i64),
+            query.offset
+        );
+
+        let mut stmt = self.connection.prepare(&sql)?;
+        let asset_iter = stmt.query_map(param_refs.as_slice(), asset_from_row)?;
 
         let mut results = Vec::new();
         for asset_result in asset_iter {
@@ -405,7 +1159,22 @@ impl AssetDatabase {
             });
         }
 
-        Ok(results)
+        let end = query.offset + results.len();
+        Ok(AssetSearchPage {
+            results,
+            offset: query.offset,
+            total,
+            next_offset: (end < total).then_some(end),
+        })
+    }
+
+    /// Set or clear an asset's 1-5 star rating. Returns `false` if the asset doesn't exist.
+    pub fn set_asset_rating(&mut self, asset_id: i64, rating: Option<u8>) -> SqlResult<bool> {
+        let updated = self.connection.execute(
+            "UPDATE assets SET rating = ?1 WHERE id = ?2",
+            params![rating, asset_id],
+        )?;
+        Ok(updated > 0)
     }
 
     fn get_asset_metadata(&self, asset_id: i64) -> SqlResult<Vec<AssetMetadata>> {
@@ -452,7 +1221,29 @@ impl AssetDatabase {
         Ok(collections)
     }
 
-    #[allow(dead_code)]
+    /// Every indexed asset, ordered by collection and path.
+    pub fn get_all_assets(&self) -> SqlResult<Vec<AssetRecord>> {
+        let mut stmt = self.connection.prepare(&format!(
+            "{} ORDER BY a.collection, a.file_path",
+            ASSET_SELECT
+        ))?;
+        let rows = stmt.query_map([], |row| asset_from_row(row).map(|(asset, _)| asset))?;
+        rows.collect()
+    }
+
+    /// Tags of every tagged asset, each asset's sorted by name.
+    pub fn get_all_tags(&self) -> SqlResult<HashMap<i64, Vec<String>>> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT asset_id, tag_name FROM asset_tags ORDER BY asset_id, tag_name")?;
+        let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+        for row in stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))? {
+            let (asset_id, tag) = row?;
+            tags.entry(asset_id).or_default().push(tag);
+        }
+        Ok(tags)
+    }
+
     pub fn add_thumbnail(&mut self, asset_id: i64, thumbnail_path: &str) -> SqlResult<()> {
         self.connection.execute(
             "INSERT OR REPLACE INTO thumbnails (asset_id, thumbnail_path) VALUES (?1, ?2)",
@@ -461,36 +1252,15 @@ impl AssetDatabase {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn get_asset_by_id(
         &self,
         asset_id: i64,
     ) -> Result<Option<AssetSearchResult>, Box<dyn std::error::Error>> {
-        let mut stmt = self.connection.prepare(
-            "SELECT a.id, a.name, a.file_path, a.asset_type, a.collection, 
-                    a.file_size, a.checksum, a.created_at, a.updated_at,
-                    CASE WHEN t.asset_id IS NOT NULL THEN 1 ELSE 0 END as has_thumbnail
-             FROM assets a
-             LEFT JOIN thumbnails t ON a.id = t.asset_id
-             WHERE a.id = ?",
-        )?;
+        let mut stmt = self
+            .connection
+            .prepare(&format!("{} WHERE a.id = ?", ASSET_SELECT))?;
 
-        let mut rows = stmt.query_map([asset_id], |row| {
-            Ok((
-                AssetRecord {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    file_path: row.get(2)?,
-                    asset_type: row.get(3)?,
-                    collection: row.get(4)?,
-                    file_size: row.get(5)?,
-                    checksum: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                },
-                row.get::<usize, i32>(9)? == 1, // has_thumbnail
-            ))
-        })?;
+        let mut rows = stmt.query_map([asset_id], asset_from_row)?;
 
         if let Some(row) = rows.next() {
             let (asset, has_thumbnail) = row?;
@@ -506,15 +1276,179 @@ impl AssetDatabase {
         }
     }
 
-    #[allow(dead_code)]
-    pub fn begin_transaction(&mut self) -> Result<Transaction<'_>, rusqlite::Error> {
-        self.connection.transaction()
+    /// Run `f` inside one transaction, so a batch of writes is committed together.
+    ///
+    /// A write that fails inside `f` only undoes itself; the others are still committed.
+    pub fn in_transaction<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> SqlResult<T> {
+        self.connection.execute_batch("BEGIN IMMEDIATE")?;
+        let result = f(self);
+        if let Err(e) = self.connection.execute_batch("COMMIT") {
+            let _ = self.connection.execute_batch("ROLLBACK");
+            return Err(e);
+        }
+        Ok(result)
+    }
+
+    /// Copy the live database to `path` using SQLite's online backup API.
+    pub fn backup_to(&self, path: &Path) -> SqlResult<()> {
+        self.connection.backup(DatabaseName::Main, path, None)
+    }
+
+    /// Replace the live database with the contents of `path`.
+    ///
+    /// The schema is brought up to date afterwards, so older backups can be restored.
+    pub fn restore_from(&mut self, path: &Path) -> SqlResult<()> {
+        self.connection.restore(
+            DatabaseName::Main,
+            path,
+            None::<fn(rusqlite::backup::Progress)>,
+        )?;
+        self.initialize_schema()
+    }
+
+    /// Problems reported by `PRAGMA integrity_check`; empty when the database is healthy.
+    pub fn integrity_check(&self) -> SqlResult<Vec<String>> {
+        integrity_problems(&self.connection)
+    }
+
+    /// Indexed file sizes per collection, largest first.
+    pub fn storage_by_collection(&self) -> SqlResult<Vec<StorageUsage>> {
+        self.storage_grouped_by("collection")
+    }
+
+    /// Indexed file sizes per asset type, largest first.
+    pub fn storage_by_type(&self) -> SqlResult<Vec<StorageUsage>> {
+        self.storage_grouped_by("asset_type")
+    }
+
+    fn storage_grouped_by(&self, column: &str) -> SqlResult<Vec<StorageUsage>> {
+        let mut stmt = self.connection.prepare(&format!(
+            "SELECT {column}, COUNT(*), COALESCE(SUM(file_size), 0) FROM assets
+             GROUP BY {column} ORDER BY 3 DESC, 1"
+        ))?;
+        let rows = stmt.query_map([], |row| {
+            Ok(StorageUsage {
+                name: row.get(0)?,
+                asset_count: row.get(1)?,
+                total_bytes: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Every distinct content checksum, for matching cache files against live assets.
+    pub fn asset_checksums(&self) -> SqlResult<HashSet<String>> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT DISTINCT checksum FROM assets")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    /// Per-asset tables holding rows for assets that no longer exist.
+    pub fn orphaned_rows(&self) -> SqlResult<Vec<OrphanedRows>> {
+        let mut orphaned = Vec::new();
+        for table in ASSET_TABLES {
+            let count: i64 = self.connection.query_row(
+                &format!(
+                    "SELECT COUNT(*) FROM {table}
+                     WHERE asset_id NOT IN (SELECT id FROM assets)"
+                ),
+                [],
+                |row| row.get(0),
+            )?;
+            if count > 0 {
+                orphaned.push(OrphanedRows {
+                    table: table.to_string(),
+                    count,
+                });
+            }
+        }
+        Ok(orphaned)
+    }
+
+    /// Delete the rows reported by [`Self::orphaned_rows`], returning how many went.
+    pub fn remove_orphaned_rows(&mut self) -> SqlResult<usize> {
+        self.in_transaction(|database| {
+            ASSET_TABLES.iter().try_fold(0, |removed, table| {
+                database
+                    .connection
+                    .execute(
+                        &format!(
+                            "DELETE FROM {table} WHERE asset_id NOT IN (SELECT id FROM assets)"
+                        ),
+                        [],
+                    )
+                    .map(|count| removed + count)
+            })
+        })?
+    }
+
+    /// Size of the database file and how much of it `VACUUM` would give back, in bytes.
+    pub fn file_usage(&self) -> SqlResult<(i64, i64)> {
+        let pragma = |name: &str| {
+            self.connection
+                .query_row(&format!("PRAGMA {name}"), [], |row| {
+                    row.get::<usize, i64>(0)
+                })
+        };
+        let page_size = pragma("page_size")?;
+        Ok((
+            pragma("page_count")? * page_size,
+            pragma("freelist_count")? * page_size,
+        ))
+    }
+
+    /// When a maintenance task such as `VACUUM` last ran.
+    pub fn last_maintenance(&self, task: &str) -> SqlResult<Option<DateTime<Utc>>> {
+        self.connection
+            .query_row(
+                "SELECT last_run_at FROM maintenance WHERE task = ?1",
+                [task],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    pub fn record_maintenance(&self, task: &str) -> SqlResult<()> {
+        self.connection.execute(
+            "INSERT INTO maintenance (task, last_run_at) VALUES (?1, CURRENT_TIMESTAMP)
+             ON CONFLICT(task) DO UPDATE SET last_run_at = excluded.last_run_at",
+            [task],
+        )?;
+        Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn vacuum(&self) -> SqlResult<()> {
         info!("Performing database vacuum operation");
         self.connection.execute("VACUUM", [])?;
         Ok(())
     }
 }
+
+/// Fold `.` and `..` components without touching the file system, keeping leading `..`.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            // `file_name` is `None` after a `..` or the root, which must stay
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Run `PRAGMA integrity_check` on any connection, e.g. a backup before restoring it.
+pub fn integrity_problems(connection: &Connection) -> SqlResult<Vec<String>> {
+    let mut stmt = connection.prepare("PRAGMA integrity_check")?;
+    let rows = stmt.query_map([], |row| row.get::<usize, String>(0))?;
+    let messages = rows.collect::<SqlResult<Vec<String>>>()?;
+    Ok(messages
+        .into_iter()
+        .filter(|message| message != "ok")
+        .collect())
+}
@@ -0,0 +1,218 @@
+// Minimal reader for the binary FBX node tree, enough to gather model statistics
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+
+const MAGIC: &[u8] = b"Kaydara FBX Binary  \0";
+/// Versions from 7.5 on use 64-bit node offsets.
+const WIDE_OFFSET_VERSION: u32 = 7500;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FbxProperty {
+    Int(i64),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    IntArray(Vec<i64>),
+    FloatArray(Vec<f64>),
+}
+
+#[derive(Debug, Clone)]
+pub struct FbxNode {
+    pub name: String,
+    pub properties: Vec<FbxProperty>,
+    pub children: Vec<FbxNode>,
+}
+
+impl FbxNode {
+    pub fn child(&self, name: &str) -> Option<&FbxNode> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// Object name from a `"Name\0\x01Class"` property, without the class suffix.
+    pub fn object_name(&self) -> Option<&str> {
+        self.properties.iter().find_map(|property| match property {
+            FbxProperty::String(s) => Some(s.split("\0\u{1}").next().unwrap_or(s)),
+            _ => None,
+        })
+    }
+
+    /// Object sub-class, e.g. `Mesh` for geometry nodes.
+    pub fn object_class(&self) -> Option<&str> {
+        match self.properties.get(2) {
+            Some(FbxProperty::String(class)) => Some(class),
+            _ => None,
+        }
+    }
+}
+
+/// Parse the top-level nodes of a binary FBX file.
+pub fn parse(data: &[u8]) -> Result<Vec<FbxNode>, String> {
+    if !data.starts_with(MAGIC) {
+        return Err(if data.starts_with(b"; FBX") {
+            "ASCII FBX files are not supported".to_string()
+        } else {
+            "Not an FBX file".to_string()
+        });
+    }
+
+    let mut reader = Reader {
+        data,
+        pos: MAGIC.len() + 2,
+        wide: false,
+    };
+    reader.wide = reader.u32()? >= WIDE_OFFSET_VERSION;
+
+    let mut nodes = Vec::new();
+    while let Some(node) = reader.node()? {
+        nodes.push(node);
+    }
+    Ok(nodes)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    wide: bool,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or("Unexpected end of FBX data")?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().expect("slice has N bytes"))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn offset(&mut self) -> Result<u64, String> {
+        if self.wide {
+            Ok(u64::from_le_bytes(self.array()?))
+        } else {
+            Ok(u64::from(self.u32()?))
+        }
+    }
+
+    /// Next node record, or `None` at a null record (end of a node list).
+    fn node(&mut self) -> Result<Option<FbxNode>, String> {
+        if self.pos >= self.data.len() {
+            return Ok(None);
+        }
+        let end_offset = self.offset()? as usize;
+        let property_count = self.offset()?;
+        let _property_list_len = self.offset()?;
+        let name_len = self.take(1)?[0] as usize;
+        if end_offset == 0 {
+            return Ok(None);
+        }
+        let name = String::from_utf8_lossy(self.take(name_len)?).to_string();
+
+        let mut properties = Vec::new();
+        for _ in 0..property_count {
+            properties.push(self.property()?);
+        }
+
+        let mut children = Vec::new();
+        while self.pos < end_offset {
+            match self.node()? {
+                Some(child) => children.push(child),
+                None => break,
+            }
+        }
+        if end_offset > self.data.len() {
+            return Err("FBX node extends past end of file".to_string());
+        }
+        self.pos = end_offset;
+
+        Ok(Some(FbxNode {
+            name,
+            properties,
+            children,
+        }))
+    }
+
+    fn property(&mut self) -> Result<FbxProperty, String> {
+        let type_code = self.take(1)?[0];
+        Ok(match type_code {
+            b'Y' => FbxProperty::Int(i64::from(i16::from_le_bytes(self.array()?))),
+            b'C' => FbxProperty::Int(i64::from(self.take(1)?[0])),
+            b'I' => FbxProperty::Int(i64::from(i32::from_le_bytes(self.array()?))),
+            b'L' => FbxProperty::Int(i64::from_le_bytes(self.array()?)),
+            b'F' => FbxProperty::Float(f64::from(f32::from_le_bytes(self.array()?))),
+            b'D' => FbxProperty::Float(f64::from_le_bytes(self.array()?)),
+            b'S' => {
+                let len = self.u32()? as usize;
+                FbxProperty::String(String::from_utf8_lossy(self.take(len)?).to_string())
+            }
+            b'R' => {
+                let len = self.u32()? as usize;
+                FbxProperty::Bytes(self.take(len)?.to_vec())
+            }
+            b'f' | b'd' | b'l' | b'i' | b'b' => {
+                let count = self.u32()? as usize;
+                let encoding = self.u32()?;
+                let stored_len = self.u32()? as usize;
+                let stored = self.take(stored_len)?;
+                let bytes = match encoding {
+                    0 => stored.to_vec(),
+                    1 => {
+                        let mut bytes = Vec::new();
+                        ZlibDecoder::new(stored)
+                            .read_to_end(&mut bytes)
+                            .map_err(|e| format!("Failed to inflate FBX array: {}", e))?;
+                        bytes
+                    }
+                    other => return Err(format!("Unknown FBX array encoding: {}", other)),
+                };
+                array_property(type_code, count, &bytes)?
+            }
+            other => return Err(format!("Unknown FBX property type: {:?}", other as char)),
+        })
+    }
+}
+
+fn array_property(type_code: u8, count: usize, bytes: &[u8]) -> Result<FbxProperty, String> {
+    let element_size = match type_code {
+        b'f' | b'i' => 4,
+        b'd' | b'l' => 8,
+        _ => 1,
+    };
+    if bytes.len() < count * element_size {
+        return Err("FBX array shorter than its declared length".to_string());
+    }
+    let elements = bytes.chunks_exact(element_size).take(count);
+
+    Ok(match type_code {
+        b'f' => FbxProperty::FloatArray(
+            elements
+                .map(|b| f64::from(f32::from_le_bytes(b.try_into().expect("4 bytes"))))
+                .collect(),
+        ),
+        b'd' => FbxProperty::FloatArray(
+            elements
+                .map(|b| f64::from_le_bytes(b.try_into().expect("8 bytes")))
+                .collect(),
+        ),
+        b'i' => FbxProperty::IntArray(
+            elements
+                .map(|b| i64::from(i32::from_le_bytes(b.try_into().expect("4 bytes"))))
+                .collect(),
+        ),
+        b'l' => FbxProperty::IntArray(
+            elements
+                .map(|b| i64::from_le_bytes(b.try_into().expect("8 bytes")))
+                .collect(),
+        ),
+        _ => FbxProperty::IntArray(elements.map(|b| i64::from(b[0])).collect()),
+    })
+}
@@ -0,0 +1,250 @@
+// The one list of file extensions the editor treats as assets
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+pub const MODEL: &str = "Model";
+pub const TEXTURE: &str = "Texture";
+pub const MATERIAL: &str = "Material";
+pub const AUDIO: &str = "Audio";
+
+const ASSET_TYPES: &[&str] = &[MODEL, TEXTURE, MATERIAL, AUDIO];
+
+/// Built-in extensions for each asset type (lowercase, without the dot).
+const BUILTIN_EXTENSIONS: &[(&str, &[&str])] = &[
+    (
+        MODEL,
+        &["fbx", "gltf", "glb", "obj", "vox", "dae", "3ds", "blend"],
+    ),
+    (TEXTURE, &["png", "jpg", "jpeg", "tga", "bmp", "hdr", "exr"]),
+    (MATERIAL, &["mat", "mtl"]),
+    (AUDIO, &["wav", "mp3", "ogg", "flac"]),
+];
+
+/// Per-project changes to the built-in extension list.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtensionConfig {
+    /// Extra extensions keyed by asset type, e.g. `{"Model": ["ply"]}`
+    #[serde(default)]
+    pub additional: BTreeMap<String, Vec<String>>,
+    /// Built-in extensions to ignore
+    #[serde(default)]
+    pub disabled: Vec<String>,
+    /// Project-specific asset types; their extensions take precedence over built-in ones
+    #[serde(default)]
+    pub custom_types: Vec<CustomAssetType>,
+}
+
+/// An asset type the editor doesn't know about, e.g. `.ldtk` maps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomAssetType {
+    /// Stored as the asset type, e.g. `"Level Map"`
+    pub label: String,
+    pub extensions: Vec<String>,
+    /// Hook that reads metadata from files of this type
+    #[serde(default)]
+    pub extractor: Option<MetadataExtractor>,
+}
+
+/// A program run on each scanned file of a custom type, with the file path as its last
+/// argument. It prints a JSON object whose fields become the asset's metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataExtractor {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl ExtensionConfig {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read asset extensions {:?}: {}", path, e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse asset extensions {:?}: {}", path, e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize asset extensions: {}", e))?;
+        fs::write(path, json)
+            .map_err(|e| format!("Failed to write asset extensions {:?}: {}", path, e))
+    }
+}
+
+/// Maps file extensions to asset types.
+#[derive(Debug, Clone)]
+pub struct FileTypes {
+    by_extension: HashMap<String, Cow<'static, str>>,
+    /// Metadata hooks keyed by custom asset type
+    extractors: HashMap<String, MetadataExtractor>,
+}
+
+impl Default for FileTypes {
+    fn default() -> Self {
+        let by_extension = BUILTIN_EXTENSIONS
+            .iter()
+            .flat_map(|(asset_type, extensions)| {
+                extensions
+                    .iter()
+                    .map(|ext| (ext.to_string(), Cow::Borrowed(*asset_type)))
+            })
+            .collect();
+        Self {
+            by_extension,
+            extractors: HashMap::new(),
+        }
+    }
+}
+
+impl FileTypes {
+    /// Built-in extensions adjusted by a project's configuration.
+    pub fn with_config(config: &ExtensionConfig) -> Result<Self, String> {
+        let mut file_types = Self::default();
+        for ext in &config.disabled {
+            file_types.by_extension.remove(&normalize(ext));
+        }
+        for (asset_type, extensions) in &config.additional {
+            let asset_type = ASSET_TYPES
+                .iter()
+                .find(|known| known.eq_ignore_ascii_case(asset_type))
+                .ok_or_else(|| format!("Unknown asset type: {}", asset_type))?;
+            for ext in extensions {
+                file_types
+                    .by_extension
+                    .insert(normalize(ext), Cow::Borrowed(*asset_type));
+            }
+        }
+        for custom in &config.custom_types {
+            file_types.register(custom)?;
+        }
+        Ok(file_types)
+    }
+
+    /// Add a custom asset type, taking over its extensions from any other type.
+    pub fn register(&mut self, custom: &CustomAssetType) -> Result<(), String> {
+        let label = custom.label.trim();
+        if label.is_empty() || label.eq_ignore_ascii_case("Unknown") {
+            return Err(format!("Invalid asset type label: {:?}", custom.label));
+        }
+        if ASSET_TYPES
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(label))
+        {
+            return Err(format!(
+                "{} is a built-in asset type; add its extensions under `additional` instead",
+                label
+            ));
+        }
+        if custom.extensions.is_empty() {
+            return Err(format!("Asset type {} has no extensions", label));
+        }
+
+        for ext in &custom.extensions {
+            self.by_extension
+                .insert(normalize(ext), Cow::Owned(label.to_string()));
+        }
+        if let Some(extractor) = &custom.extractor {
+            self.extractors.insert(label.to_string(), extractor.clone());
+        }
+        Ok(())
+    }
+
+    pub fn asset_type(&self, path: &Path) -> Option<&str> {
+        let ext = path.extension()?.to_str()?;
+        self.asset_type_for_extension(ext)
+    }
+
+    pub fn asset_type_for_extension(&self, ext: &str) -> Option<&str> {
+        self.by_extension.get(&normalize(ext)).map(AsRef::as_ref)
+    }
+
+    /// The metadata hook registered for a custom asset type.
+    pub fn extractor(&self, asset_type: &str) -> Option<&MetadataExtractor> {
+        self.extractors.get(asset_type)
+    }
+
+    /// Accepted extensions grouped by asset type, sorted for display.
+    pub fn extensions_by_type(&self) -> BTreeMap<&str, Vec<String>> {
+        let mut grouped: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for (ext, asset_type) in &self.by_extension {
+            grouped.entry(asset_type).or_default().push(ext.clone());
+        }
+        for extensions in grouped.values_mut() {
+            extensions.sort();
+        }
+        grouped
+    }
+}
+
+fn normalize(ext: &str) -> String {
+    ext.trim().trim_start_matches('.').to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_adds_and_disables_extensions() {
+        let config = ExtensionConfig {
+            additional: BTreeMap::from([("model".to_string(), vec![".PLY".to_string()])]),
+            disabled: vec!["blend".to_string()],
+            ..ExtensionConfig::default()
+        };
+        let file_types = FileTypes::with_config(&config).unwrap();
+
+        assert_eq!(file_types.asset_type(Path::new("scan.ply")), Some(MODEL));
+        assert_eq!(file_types.asset_type(Path::new("ship.GLB")), Some(MODEL));
+        assert_eq!(file_types.asset_type(Path::new("chair.vox")), Some(MODEL));
+        assert_eq!(file_types.asset_type(Path::new("scene.blend")), None);
+        assert_eq!(file_types.asset_type(Path::new("README")), None);
+
+        let bad = ExtensionConfig {
+            additional: BTreeMap::from([("Shader".to_string(), vec!["wgsl".to_string()])]),
+            ..ExtensionConfig::default()
+        };
+        assert!(FileTypes::with_config(&bad).is_err());
+    }
+
+    #[test]
+    fn custom_types_take_over_extensions() {
+        let config = ExtensionConfig {
+            custom_types: vec![CustomAssetType {
+                label: "Voxel Model".to_string(),
+                extensions: vec![".VOX".to_string()],
+                extractor: Some(MetadataExtractor {
+                    program: "vox-info".to_string(),
+                    args: Vec::new(),
+                }),
+            }],
+            ..ExtensionConfig::default()
+        };
+        let file_types = FileTypes::with_config(&config).unwrap();
+
+        assert_eq!(
+            file_types.asset_type(Path::new("chair.vox")),
+            Some("Voxel Model")
+        );
+        assert!(file_types.extractor("Voxel Model").is_some());
+        assert!(file_types.extractor(MODEL).is_none());
+
+        let shadowing = ExtensionConfig {
+            custom_types: vec![CustomAssetType {
+                label: "texture".to_string(),
+                extensions: vec!["ktx2".to_string()],
+                extractor: None,
+            }],
+            ..ExtensionConfig::default()
+        };
+        assert!(FileTypes::with_config(&shadowing).is_err());
+    }
+}
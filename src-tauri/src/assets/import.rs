@@ -0,0 +1,187 @@
+// Bringing external files into the project's managed asset folder
+use super::database::AssetDatabase;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How imported files get into the asset folder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    #[default]
+    Copy,
+    /// Hard link to the original, falling back to a copy across file systems
+    Link,
+}
+
+/// A file that was skipped because the library already has its contents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateImport {
+    pub source: String,
+    pub existing_asset_id: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportSummary {
+    /// IDs of the newly created assets, in input order
+    pub imported: Vec<i64>,
+    pub duplicates: Vec<DuplicateImport>,
+    pub errors: Vec<String>,
+}
+
+/// Import files into `<assets_root>/<collection>`, skipping contents already indexed.
+pub fn import_files(
+    database: &mut AssetDatabase,
+    sources: &[PathBuf],
+    assets_root: &Path,
+    collection: &str,
+    mode: ImportMode,
+) -> Result<ImportSummary, Box<dyn std::error::Error>> {
+    let collection = collection.trim();
+    if collection.is_empty() || collection.contains(['/', '\\']) || collection.starts_with('.') {
+        return Err(format!("Invalid collection name: {}", collection).into());
+    }
+
+    let target_dir = assets_root.join(collection);
+    fs::create_dir_all(&target_dir)?;
+    database.ensure_collection(collection)?;
+
+    let mut summary = ImportSummary::default();
+    for source in sources {
+        match import_file(database, source, &target_dir, assets_root, collection, mode) {
+            Ok(ImportOutcome::New(asset_id)) => summary.imported.push(asset_id),
+            Ok(ImportOutcome::Duplicate(existing_asset_id)) => {
+                summary.duplicates.push(DuplicateImport {
+                    source: source.to_string_lossy().to_string(),
+                    existing_asset_id,
+                });
+            }
+            Err(e) => {
+                let error_msg = format!("Failed to import {}: {}", source.display(), e);
+                warn!("{}", error_msg);
+                summary.errors.push(error_msg);
+            }
+        }
+    }
+
+    info!(
+        "Imported {} assets into {} ({} duplicates, {} errors)",
+        summary.imported.len(),
+        collection,
+        summary.duplicates.len(),
+        summary.errors.len()
+    );
+    Ok(summary)
+}
+
+enum ImportOutcome {
+    New(i64),
+    Duplicate(i64),
+}
+
+fn import_file(
+    database: &mut AssetDatabase,
+    source: &Path,
+    target_dir: &Path,
+    assets_root: &Path,
+    collection: &str,
+    mode: ImportMode,
+) -> Result<ImportOutcome, Box<dyn std::error::Error>> {
+    if !source.is_file() {
+        return Err("Not a file".into());
+    }
+    if database.determine_asset_type(source) == "Unknown" {
+        return Err("Unsupported asset type".into());
+    }
+
+    let checksum = database.calculate_file_checksum(source)?;
+    if let Some(existing) = database.find_asset_by_checksum(&checksum)? {
+        return Ok(ImportOutcome::Duplicate(existing));
+    }
+
+    let target = unique_target(target_dir, source)?;
+    match mode {
+        ImportMode::Copy => {
+            fs::copy(source, &target)?;
+        }
+        ImportMode::Link => {
+            if let Err(e) = fs::hard_link(source, &target) {
+                warn!("Hard link failed for {:?} ({}), copying instead", source, e);
+                fs::copy(source, &target)?;
+            }
+        }
+    }
+
+    match database.insert_asset(&target, collection, assets_root) {
+        Ok(asset_id) => Ok(ImportOutcome::New(asset_id)),
+        Err(e) => {
+            // Don't leave an untracked file behind
+            let _ = fs::remove_file(&target);
+            Err(e)
+        }
+    }
+}
+
+/// Destination path for `source`, numbered if the name is already taken.
+fn unique_target(target_dir: &Path, source: &Path) -> Result<PathBuf, String> {
+    let stem = source
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or("Invalid filename")?;
+    let extension = source.extension().and_then(|e| e.to_str());
+
+    let file_name = |stem: String| match extension {
+        Some(ext) => format!("{}.{}", stem, ext),
+        None => stem,
+    };
+
+    let mut target = target_dir.join(file_name(stem.to_string()));
+    let mut n = 1;
+    while target.exists() {
+        target = target_dir.join(file_name(format!("{}_{}", stem, n)));
+        n += 1;
+    }
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn imports_copy_and_skip_duplicates() {
+        let temp_dir = tempdir().unwrap();
+        let assets_root = temp_dir.path().join("Assets");
+        let external = temp_dir.path().join("external");
+        fs::create_dir_all(&external).unwrap();
+        fs::write(external.join("crate.png"), b"crate").unwrap();
+        fs::write(external.join("notes.txt"), b"notes").unwrap();
+
+        let mut database = AssetDatabase::new(temp_dir.path().join("assets.db")).unwrap();
+        let sources = vec![external.join("crate.png"), external.join("notes.txt")];
+        let summary = import_files(
+            &mut database,
+            &sources,
+            &assets_root,
+            "Props",
+            ImportMode::Copy,
+        )
+        .unwrap();
+        assert_eq!(summary.imported.len(), 1);
+        assert_eq!(summary.errors.len(), 1);
+        assert!(assets_root.join("Props").join("crate.png").exists());
+
+        let again = import_files(
+            &mut database,
+            &sources[..1],
+            &assets_root,
+            "Props",
+            ImportMode::Copy,
+        )
+        .unwrap();
+        assert!(again.imported.is_empty());
+        assert_eq!(again.duplicates[0].existing_asset_id, summary.imported[0]);
+    }
+}
@@ -0,0 +1,193 @@
+// Background scan jobs, so a scan can be watched and stopped from the UI
+use super::scanner::{RescanSummary, ScanProgress, ScanResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Finished jobs kept around for `get_scan_status`.
+const FINISHED_JOBS_KEPT: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanKind {
+    /// Hash and index every file
+    Full,
+    /// Only read files whose size or modification time changed, and prune deleted ones
+    Incremental,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanJobState {
+    Running,
+    /// Cancellation was requested; the scan stops after its current batch
+    Cancelling,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScanOutcome {
+    Full(ScanResult),
+    Incremental(RescanSummary),
+}
+
+impl ScanOutcome {
+    fn cancelled(&self) -> bool {
+        match self {
+            Self::Full(result) => result.cancelled,
+            Self::Incremental(summary) => summary.cancelled,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanJobStatus {
+    pub job_id: u64,
+    pub kind: ScanKind,
+    pub state: ScanJobState,
+    /// Latest progress report from the scanner
+    pub progress: Option<ScanProgress>,
+    /// Set once the job has finished without failing
+    pub outcome: Option<ScanOutcome>,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// The running scan and a short history of finished ones. Only one scan may run at a time.
+#[derive(Default)]
+pub struct ScanJobs {
+    next_id: u64,
+    running: Option<(ScanJobStatus, Arc<AtomicBool>)>,
+    finished: VecDeque<ScanJobStatus>,
+}
+
+impl ScanJobs {
+    /// Register a new job, returning its ID and the flag the scanner polls for cancellation.
+    pub fn start(&mut self, kind: ScanKind) -> Result<(u64, Arc<AtomicBool>), String> {
+        if let Some((job, _)) = &self.running {
+            return Err(format!("Scan job {} is already running", job.job_id));
+        }
+
+        self.next_id += 1;
+        let cancel = Arc::new(AtomicBool::new(false));
+        let job = ScanJobStatus {
+            job_id: self.next_id,
+            kind,
+            state: ScanJobState::Running,
+            progress: None,
+            outcome: None,
+            error: None,
+            started_at: Utc::now(),
+            finished_at: None,
+        };
+        self.running = Some((job, cancel.clone()));
+        Ok((self.next_id, cancel))
+    }
+
+    pub fn report_progress(&mut self, job_id: u64, progress: ScanProgress) {
+        if let Some((job, _)) = self
+            .running
+            .as_mut()
+            .filter(|(job, _)| job.job_id == job_id)
+        {
+            job.progress = Some(progress);
+        }
+    }
+
+    /// Ask a running job to stop. Files already indexed stay indexed.
+    pub fn cancel(&mut self, job_id: u64) -> Result<(), String> {
+        match &mut self.running {
+            Some((job, cancel)) if job.job_id == job_id => {
+                cancel.store(true, Ordering::Relaxed);
+                job.state = ScanJobState::Cancelling;
+                Ok(())
+            }
+            _ if self.finished.iter().any(|job| job.job_id == job_id) => {
+                Err(format!("Scan job {} has already finished", job_id))
+            }
+            _ => Err(format!("No scan job with ID {}", job_id)),
+        }
+    }
+
+    /// Record how a job ended and free the slot for the next scan.
+    pub fn finish(
+        &mut self,
+        job_id: u64,
+        result: Result<ScanOutcome, String>,
+    ) -> Option<ScanJobStatus> {
+        let (mut job, _) = self.running.take_if(|(job, _)| job.job_id == job_id)?;
+
+        job.finished_at = Some(Utc::now());
+        match result {
+            Ok(outcome) => {
+                job.state = if outcome.cancelled() {
+                    ScanJobState::Cancelled
+                } else {
+                    ScanJobState::Completed
+                };
+                job.outcome = Some(outcome);
+            }
+            Err(error) => {
+                job.state = ScanJobState::Failed;
+                job.error = Some(error);
+            }
+        }
+
+        if self.finished.len() == FINISHED_JOBS_KEPT {
+            self.finished.pop_front();
+        }
+        self.finished.push_back(job.clone());
+        Some(job)
+    }
+
+    /// Status of a job, or of the running (else most recent) one when `job_id` is `None`.
+    pub fn status(&self, job_id: Option<u64>) -> Option<ScanJobStatus> {
+        let running = self.running.as_ref().map(|(job, _)| job);
+        match job_id {
+            Some(job_id) => running
+                .filter(|job| job.job_id == job_id)
+                .or_else(|| self.finished.iter().find(|job| job.job_id == job_id))
+                .cloned(),
+            None => running.or_else(|| self.finished.back()).cloned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_one_job_at_a_time() {
+        let mut jobs = ScanJobs::default();
+        let (job_id, cancel) = jobs.start(ScanKind::Incremental).unwrap();
+        assert!(jobs.start(ScanKind::Full).is_err());
+
+        jobs.cancel(job_id).unwrap();
+        assert!(cancel.load(Ordering::Relaxed));
+        assert_eq!(jobs.status(None).unwrap().state, ScanJobState::Cancelling);
+
+        let summary = RescanSummary {
+            cancelled: true,
+            ..RescanSummary::default()
+        };
+        let status = jobs
+            .finish(job_id, Ok(ScanOutcome::Incremental(summary)))
+            .unwrap();
+        assert_eq!(status.state, ScanJobState::Cancelled);
+        assert!(jobs.cancel(job_id).is_err());
+
+        let (next_id, _) = jobs.start(ScanKind::Full).unwrap();
+        assert_ne!(next_id, job_id);
+        assert_eq!(
+            jobs.status(Some(job_id)).unwrap().state,
+            ScanJobState::Cancelled
+        );
+    }
+}
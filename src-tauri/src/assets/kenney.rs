@@ -0,0 +1,342 @@
+// Downloading free Kenney asset packs into a managed asset root
+use super::scanner::RescanSummary;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+/// Collection that downloaded packs are indexed into.
+pub const COLLECTION: &str = "Kenney";
+pub const CATALOG_FILE: &str = "kenney_packs.json";
+/// Packs shipped with the app, pinned to known checksums. Catalog files can add to or
+/// replace them.
+const BUILTIN_CATALOG: &str = include_str!("kenney_packs.json");
+/// Download progress is reported at most once per this many bytes.
+const PROGRESS_INTERVAL_BYTES: u64 = 512 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KenneyPack {
+    /// Directory the pack is extracted to, e.g. `furniture-kit`
+    pub id: String,
+    pub name: String,
+    /// Direct link to the pack's zip file
+    pub url: String,
+    /// Expected SHA-256 of the zip in lowercase hex; mismatching downloads are discarded.
+    /// Packs without one are only installed once the user confirms it.
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KenneyPackStatus {
+    #[serde(flatten)]
+    pub pack: KenneyPack,
+    pub installed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PackStage {
+    Downloading,
+    Verifying,
+    Extracting,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackProgress {
+    pub pack_id: String,
+    pub stage: PackStage,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledPack {
+    pub id: String,
+    pub files: usize,
+    /// Checksum of the downloaded zip, for pinning in the catalog
+    pub sha256: String,
+    /// Whether the checksum matched the catalog's; false for confirmed unpinned packs
+    pub verified: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackInstallSummary {
+    pub installed: Vec<InstalledPack>,
+    pub errors: Vec<String>,
+    /// Packs with no pinned checksum, skipped until the user confirms installing them
+    /// unverified
+    pub unverified: Vec<String>,
+    /// Result of indexing the managed root, when anything was installed
+    pub scan: Option<RescanSummary>,
+}
+
+/// Read the built-in catalog, then `files` in order; a later entry replaces an earlier
+/// one with the same id.
+pub fn load_catalog(files: &[PathBuf]) -> Result<Vec<KenneyPack>, String> {
+    let mut packs: BTreeMap<String, KenneyPack> = BTreeMap::new();
+    add_catalog(&mut packs, "built-in", BUILTIN_CATALOG)?;
+    for file in files.iter().filter(|file| file.exists()) {
+        let json = fs::read_to_string(file)
+            .map_err(|e| format!("Failed to read Kenney pack catalog {:?}: {}", file, e))?;
+        add_catalog(&mut packs, &format!("{:?}", file), &json)?;
+    }
+    Ok(packs.into_values().collect())
+}
+
+fn add_catalog(
+    packs: &mut BTreeMap<String, KenneyPack>,
+    source: &str,
+    json: &str,
+) -> Result<(), String> {
+    let entries: Vec<KenneyPack> = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse Kenney pack catalog {}: {}", source, e))?;
+    for pack in entries {
+        if !valid_pack_id(&pack.id) {
+            return Err(format!("Invalid Kenney pack id in {}: {}", source, pack.id));
+        }
+        packs.insert(pack.id.clone(), pack);
+    }
+    Ok(())
+}
+
+fn valid_pack_id(id: &str) -> bool {
+    !id.is_empty() && !id.starts_with('.') && !id.contains(['/', '\\'])
+}
+
+/// Where a pack lives once installed under `packs_root`.
+pub fn pack_directory(packs_root: &Path, pack_id: &str) -> PathBuf {
+    packs_root.join(COLLECTION).join(pack_id)
+}
+
+pub fn is_installed(packs_root: &Path, pack_id: &str) -> bool {
+    pack_directory(packs_root, pack_id).is_dir()
+}
+
+/// A pack's zip, downloaded to disk.
+#[derive(Debug)]
+pub struct DownloadedPack {
+    pub path: PathBuf,
+    pub size: u64,
+    /// SHA-256 of the zip in lowercase hex
+    pub sha256: String,
+}
+
+/// Stream a pack's zip to `path`, hashing it as it arrives.
+pub async fn download_pack(
+    client: &reqwest::Client,
+    pack: &KenneyPack,
+    path: &Path,
+    progress: &(dyn Fn(PackProgress) + Send + Sync),
+) -> Result<DownloadedPack, String> {
+    let mut response = client
+        .get(&pack.url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| format!("Failed to download {}: {}", pack.name, e))?;
+
+    let total_bytes = response.content_length();
+    let report = |downloaded_bytes: u64| {
+        progress(PackProgress {
+            pack_id: pack.id.clone(),
+            stage: PackStage::Downloading,
+            downloaded_bytes,
+            total_bytes,
+        });
+    };
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .map_err(|e| format!("Failed to create {:?}: {}", path, e))?;
+    let mut hasher = Sha256::new();
+    let mut downloaded = 0;
+    let mut last_report = 0;
+    report(0);
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", pack.name, e))?
+    {
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        downloaded += chunk.len() as u64;
+        if downloaded - last_report >= PROGRESS_INTERVAL_BYTES {
+            report(downloaded);
+            last_report = downloaded;
+        }
+    }
+    file.flush()
+        .await
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    report(downloaded);
+    Ok(DownloadedPack {
+        path: path.to_path_buf(),
+        size: downloaded,
+        sha256: format!("{:x}", hasher.finalize()),
+    })
+}
+
+/// Check a download's checksum against the catalog's. Returns whether it was verified:
+/// false when the catalog pins no checksum for the pack.
+pub fn verify_checksum(pack: &KenneyPack, actual: &str) -> Result<bool, String> {
+    match &pack.sha256 {
+        Some(expected) if !expected.eq_ignore_ascii_case(actual) => Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            pack.name, expected, actual
+        )),
+        Some(_) => Ok(true),
+        None => Ok(false),
+    }
+}
+
+/// Unpack the zip at `archive` into `target`, replacing any previous install. Returns
+/// the number of files.
+///
+/// Files are unpacked next to `target` first, so a failed extraction never looks installed.
+pub fn extract_pack(archive: &Path, target: &Path) -> Result<usize, String> {
+    let file = fs::File::open(archive)
+        .map_err(|e| format!("Failed to open pack archive {:?}: {}", archive, e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to open pack archive: {}", e))?;
+
+    let staging = target.with_extension("partial");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)
+            .map_err(|e| format!("Failed to clear {:?}: {}", staging, e))?;
+    }
+
+    let mut files = 0;
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read pack archive: {}", e))?;
+        let relative = entry
+            .enclosed_name()
+            .ok_or_else(|| format!("Unsafe path in pack archive: {}", entry.name()))?;
+        let path = staging.join(relative);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&path).map_err(|e| format!("Failed to create {:?}: {}", path, e))?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        let mut file =
+            fs::File::create(&path).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        std::io::copy(&mut entry, &mut file)
+            .map_err(|e| format!("Failed to extract {}: {}", entry.name(), e))?;
+        files += 1;
+    }
+
+    if target.exists() {
+        fs::remove_dir_all(target).map_err(|e| format!("Failed to replace {:?}: {}", target, e))?;
+    }
+    fs::rename(&staging, target)
+        .map_err(|e| format!("Failed to move pack into {:?}: {}", target, e))?;
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+    use zip::write::SimpleFileOptions;
+
+    fn zip_with(path: &Path, files: &[(&str, &[u8])]) {
+        let mut writer = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        for (name, contents) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn verifies_and_extracts_packs() {
+        let temp_dir = tempdir().unwrap();
+        let archive = temp_dir.path().join("furniture-kit.zip");
+        zip_with(
+            &archive,
+            &[
+                ("License.txt", b"CC0"),
+                ("Models/GLB format/chair.glb", b"glTF"),
+            ],
+        );
+        let checksum = format!("{:x}", Sha256::digest(fs::read(&archive).unwrap()));
+        let mut pack = KenneyPack {
+            id: "furniture-kit".to_string(),
+            name: "Furniture Kit".to_string(),
+            url: "https://example.com/furniture-kit.zip".to_string(),
+            sha256: None,
+        };
+
+        // Unpinned packs pass only as unverified
+        assert!(!verify_checksum(&pack, &checksum).unwrap());
+        pack.sha256 = Some(checksum.to_uppercase());
+        assert!(verify_checksum(&pack, &checksum).unwrap());
+        assert!(verify_checksum(&pack, "corrupt").is_err());
+
+        let target = pack_directory(temp_dir.path(), &pack.id);
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("stale.png"), b"old").unwrap();
+        assert_eq!(extract_pack(&archive, &target).unwrap(), 2);
+        assert!(target.join("Models/GLB format/chair.glb").is_file());
+        assert!(!target.join("stale.png").exists());
+        assert!(is_installed(temp_dir.path(), "furniture-kit"));
+
+        let evil = temp_dir.path().join("evil.zip");
+        zip_with(&evil, &[("../escape.txt", b"x")]);
+        let other = pack_directory(temp_dir.path(), "evil");
+        assert!(extract_pack(&evil, &other).is_err());
+        assert!(!other.exists());
+        assert!(!temp_dir.path().join(COLLECTION).join("escape.txt").exists());
+    }
+
+    #[test]
+    fn later_catalogs_override_earlier_ones() {
+        let temp_dir = tempdir().unwrap();
+        let global = temp_dir.path().join("global.json");
+        let project = temp_dir.path().join("project.json");
+        fs::write(
+            &global,
+            r#"[{"id": "city-kit", "name": "City Kit", "url": "https://example.com/a.zip"}]"#,
+        )
+        .unwrap();
+        fs::write(
+            &project,
+            r#"[{"id": "city-kit", "name": "City Kit", "url": "https://example.com/b.zip", "sha256": "ab"}]"#,
+        )
+        .unwrap();
+
+        let builtin = load_catalog(&[]).unwrap();
+        let packs =
+            load_catalog(&[global, project.clone(), temp_dir.path().join("none.json")]).unwrap();
+        let city = packs.iter().find(|pack| pack.id == "city-kit").unwrap();
+        assert_eq!(city.url, "https://example.com/b.zip");
+        assert!(packs.len() <= builtin.len() + 1);
+        // Everything shipped with the app is pinned
+        assert!(builtin.iter().all(|pack| pack.sha256.is_some()));
+
+        fs::write(
+            &project,
+            r#"[{"id": "../up", "name": "Bad", "url": "https://example.com/c.zip"}]"#,
+        )
+        .unwrap();
+        assert!(load_catalog(&[project]).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+// LOD sets of models named by the `name_LOD0`, `name_LOD1`, ... convention
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LodLevel {
+    pub level: u32,
+    pub asset_id: i64,
+    pub file_path: String,
+}
+
+/// Models in one folder sharing a base name, ordered from most to least detailed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LodGroup {
+    /// The base name without the LOD suffix, e.g. `tree` for `tree_LOD1.fbx`
+    pub name: String,
+    pub levels: Vec<LodLevel>,
+}
+
+/// Where a model falls in its LOD group.
+pub struct LodMember {
+    /// The folder plus the lowercased base name and extension, so the same model in
+    /// different formats or folders doesn't mix
+    pub key: String,
+    pub name: String,
+    pub level: u32,
+}
+
+/// A model's LOD group and level, if its name ends in `_LOD<n>` (also `-LOD<n>` or
+/// ` LOD<n>`, in any case).
+pub fn lod_member(path: &Path) -> Option<LodMember> {
+    let stem = path.file_stem()?.to_str()?;
+    let digits_at = stem.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let (prefix, digits) = stem.split_at(digits_at);
+    let level = digits.parse().ok()?;
+    let lod_at = prefix.len().checked_sub(3)?;
+    if !prefix.get(lod_at..)?.eq_ignore_ascii_case("lod") {
+        return None;
+    }
+    let base = prefix[..lod_at].strip_suffix(['_', '-', ' '])?;
+    if base.is_empty() {
+        return None;
+    }
+
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    let key = path
+        .with_file_name(format!(
+            "{}.{}",
+            base.to_lowercase(),
+            extension.to_lowercase()
+        ))
+        .to_string_lossy()
+        .to_string();
+    Some(LodMember {
+        key,
+        name: base.to_string(),
+        level,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lod_suffixes() {
+        let lod0 = lod_member(Path::new("trees/Oak_LOD0.fbx")).unwrap();
+        let lod2 = lod_member(Path::new("trees/oak-lod2.FBX")).unwrap();
+        assert_eq!((lod0.name.as_str(), lod0.level), ("Oak", 0));
+        assert_eq!(lod2.level, 2);
+        assert_eq!(lod0.key, lod2.key);
+
+        assert_ne!(
+            lod_member(Path::new("trees/Oak_LOD1.glb")).unwrap().key,
+            lod0.key
+        );
+        assert!(lod_member(Path::new("trees/Oak.fbx")).is_none());
+        assert!(lod_member(Path::new("trees/Oak_LOD.fbx")).is_none());
+        assert!(lod_member(Path::new("trees/SLOD1.fbx")).is_none());
+        assert!(lod_member(Path::new("trees/_LOD1.fbx")).is_none());
+    }
+}
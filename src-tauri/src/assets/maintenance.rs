@@ -0,0 +1,196 @@
+// Disk usage reporting and cleanup, so the database and preview cache don't grow unbounded
+use super::database::{AssetDatabase, OrphanedRows, StorageUsage};
+use super::preview::preview_checksum;
+use chrono::{DateTime, TimeDelta, Utc};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const VACUUM_TASK: &str = "vacuum";
+/// Days between the automatic `VACUUM`s run when the database is opened.
+const VACUUM_INTERVAL_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageReport {
+    pub collections: Vec<StorageUsage>,
+    pub asset_types: Vec<StorageUsage>,
+    pub database_bytes: i64,
+    /// Free pages in the database file that `VACUUM` would give back
+    pub reclaimable_bytes: i64,
+    pub preview_cache_bytes: u64,
+    /// Cached previews rendered from contents no indexed asset has any more
+    pub orphaned_previews: Vec<String>,
+    pub orphaned_preview_bytes: u64,
+    pub orphaned_rows: Vec<OrphanedRows>,
+    pub last_vacuum: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CleanupSummary {
+    pub removed_previews: usize,
+    pub freed_preview_bytes: u64,
+    pub removed_rows: usize,
+    /// How much smaller the database file got after `VACUUM`
+    pub reclaimed_bytes: i64,
+}
+
+pub fn storage_report(database: &AssetDatabase, cache_dir: &Path) -> Result<StorageReport, String> {
+    let sql_error = |e: rusqlite::Error| format!("Failed to measure asset storage: {}", e);
+    let (database_bytes, reclaimable_bytes) = database.file_usage().map_err(sql_error)?;
+    let checksums = database.asset_checksums().map_err(sql_error)?;
+
+    let mut preview_cache_bytes = 0;
+    let mut orphaned_previews = Vec::new();
+    let mut orphaned_preview_bytes = 0;
+    for (path, size) in cache_entries(cache_dir) {
+        preview_cache_bytes += size;
+        if is_orphaned_preview(&path, &checksums) {
+            orphaned_preview_bytes += size;
+            orphaned_previews.push(
+                path.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+            );
+        }
+    }
+    orphaned_previews.sort();
+
+    Ok(StorageReport {
+        collections: database.storage_by_collection().map_err(sql_error)?,
+        asset_types: database.storage_by_type().map_err(sql_error)?,
+        database_bytes,
+        reclaimable_bytes,
+        preview_cache_bytes,
+        orphaned_previews,
+        orphaned_preview_bytes,
+        orphaned_rows: database.orphaned_rows().map_err(sql_error)?,
+        last_vacuum: database.last_maintenance(VACUUM_TASK).map_err(sql_error)?,
+    })
+}
+
+/// Delete orphaned previews and rows, then `VACUUM` to shrink the database file.
+pub fn clean_up(database: &mut AssetDatabase, cache_dir: &Path) -> Result<CleanupSummary, String> {
+    let sql_error = |e: rusqlite::Error| format!("Failed to clean up asset storage: {}", e);
+    let mut summary = CleanupSummary::default();
+
+    let checksums = database.asset_checksums().map_err(sql_error)?;
+    for (path, size) in cache_entries(cache_dir) {
+        if is_orphaned_preview(&path, &checksums) && fs::remove_file(&path).is_ok() {
+            summary.removed_previews += 1;
+            summary.freed_preview_bytes += size;
+        }
+    }
+    summary.removed_rows = database.remove_orphaned_rows().map_err(sql_error)?;
+
+    let (size_before, _) = database.file_usage().map_err(sql_error)?;
+    vacuum(database).map_err(sql_error)?;
+    let (size_after, _) = database.file_usage().map_err(sql_error)?;
+    summary.reclaimed_bytes = size_before - size_after;
+
+    info!(
+        "Asset storage cleanup removed {} previews and {} rows, reclaiming {} bytes",
+        summary.removed_previews, summary.removed_rows, summary.reclaimed_bytes
+    );
+    Ok(summary)
+}
+
+/// Run `VACUUM` if it hasn't run in the last week. Returns whether it ran.
+pub fn vacuum_if_due(database: &AssetDatabase) -> Result<bool, String> {
+    let sql_error = |e: rusqlite::Error| format!("Scheduled vacuum failed: {}", e);
+    let due = database
+        .last_maintenance(VACUUM_TASK)
+        .map_err(sql_error)?
+        .is_none_or(|last| Utc::now() - last >= TimeDelta::days(VACUUM_INTERVAL_DAYS));
+    if due {
+        vacuum(database).map_err(sql_error)?;
+    }
+    Ok(due)
+}
+
+fn vacuum(database: &AssetDatabase) -> rusqlite::Result<()> {
+    database.vacuum()?;
+    database.record_maintenance(VACUUM_TASK)
+}
+
+/// Files directly inside the preview cache with their sizes.
+fn cache_entries(cache_dir: &Path) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(cache_dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(fs::Metadata::is_file)?;
+            Some((entry.path(), metadata.len()))
+        })
+        .collect()
+}
+
+/// A preview whose source contents are gone. Files the cache didn't write are left alone.
+fn is_orphaned_preview(path: &Path, checksums: &HashSet<String>) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(preview_checksum)
+        .is_some_and(|checksum| !checksums.contains(checksum))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::preview::preview_path;
+    use tempfile::tempdir;
+
+    #[test]
+    fn finds_and_removes_orphans() {
+        let temp_dir = tempdir().unwrap();
+        let assets_dir = temp_dir.path().join("Assets/Kenney");
+        fs::create_dir_all(&assets_dir).unwrap();
+        fs::write(assets_dir.join("a.png"), b"kept").unwrap();
+        fs::write(assets_dir.join("b.png"), b"deleted").unwrap();
+
+        let mut database = AssetDatabase::new(&temp_dir.path().join("assets.db")).unwrap();
+        let kept = database
+            .insert_asset(&assets_dir.join("a.png"), "Kenney", &assets_dir)
+            .unwrap();
+        let deleted = database
+            .insert_asset(&assets_dir.join("b.png"), "Kenney", &assets_dir)
+            .unwrap();
+        let checksum_of = |database: &AssetDatabase, id| {
+            database
+                .get_asset_by_id(id)
+                .unwrap()
+                .unwrap()
+                .asset
+                .checksum
+        };
+        let (kept_checksum, deleted_checksum) = (
+            checksum_of(&database, kept),
+            checksum_of(&database, deleted),
+        );
+
+        let cache_dir = temp_dir.path().join("thumbnails");
+        fs::create_dir_all(&cache_dir).unwrap();
+        fs::write(preview_path(&cache_dir, &kept_checksum, 128), b"png").unwrap();
+        fs::write(preview_path(&cache_dir, &deleted_checksum, 128), b"png").unwrap();
+        fs::write(cache_dir.join("notes.txt"), b"not a preview").unwrap();
+
+        database.remove_asset(deleted, "Kenney").unwrap();
+        let report = storage_report(&database, &cache_dir).unwrap();
+        assert_eq!(report.collections.len(), 1);
+        assert_eq!(report.collections[0].total_bytes, 4);
+        assert_eq!(
+            report.orphaned_previews,
+            vec![format!("{}-128.png", deleted_checksum)]
+        );
+        assert!(report.last_vacuum.is_none());
+
+        let summary = clean_up(&mut database, &cache_dir).unwrap();
+        assert_eq!(summary.removed_previews, 1);
+        assert!(preview_path(&cache_dir, &kept_checksum, 128).exists());
+        assert!(cache_dir.join("notes.txt").exists());
+        assert!(!vacuum_if_due(&database).unwrap());
+    }
+}
@@ -0,0 +1,760 @@
+// Format-specific metadata read from asset files at scan time
+use super::atlas;
+use super::fbx::{self, FbxProperty};
+use super::file_types::{FileTypes, MetadataExtractor, TEXTURE};
+use image::{ImageDecoder, ImageReader};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::path::Path;
+use std::process::Command;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{MediaSourceStream, MediaSourceStreamOptions};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Metadata keys and values for one asset, as stored in `asset_metadata`.
+pub type MetadataEntries = Vec<(&'static str, String)>;
+
+/// `.mtl` statements whose last argument is a texture path.
+pub const MTL_TEXTURE_STATEMENTS: &[&str] = &[
+    "map_ka", "map_kd", "map_ks", "map_ns", "map_d", "map_bump", "bump", "disp", "decal", "norm",
+];
+
+/// Dimensions and pixel format of an image, read from its header without decoding pixels,
+/// plus its frames if it is a sprite sheet.
+pub fn image_metadata(path: &Path) -> Result<MetadataEntries, String> {
+    let decoder = ImageReader::open(path)
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to detect image format: {}", e))?
+        .into_decoder()
+        .map_err(|e| format!("Failed to read image header: {}", e))?;
+
+    let (width, height) = decoder.dimensions();
+    let color_type = decoder.color_type();
+    let channels = color_type.channel_count();
+
+    let mut entries = vec![
+        ("width", width.to_string()),
+        ("height", height.to_string()),
+        ("channels", channels.to_string()),
+        (
+            "bit_depth",
+            (color_type.bits_per_pixel() / u16::from(channels)).to_string(),
+        ),
+        ("has_alpha", color_type.has_alpha().to_string()),
+    ];
+    if let Some((source, frames)) = atlas::detect_sprite_sheet(path, width, height) {
+        entries.push(("sprite_sheet", source.as_str().to_string()));
+        entries.push(("sprite_frame_count", frames.len().to_string()));
+        entries.push(("sprite_frames", to_json(&frames)));
+    }
+    Ok(entries)
+}
+
+/// Duration, sample rate and channel layout of an audio file's default track.
+pub fn audio_metadata(path: &Path) -> Result<MetadataEntries, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open audio: {}", e))?;
+    let stream = MediaSourceStream::new(Box::new(file), MediaSourceStreamOptions::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("Unrecognized audio format: {}", e))?
+        .format;
+    let track = format.default_track().ok_or("No audio track")?;
+    let track_id = track.id;
+    let params = track.codec_params.clone();
+
+    let mut entries = Vec::new();
+    if let Some(sample_rate) = params.sample_rate {
+        entries.push(("sample_rate", sample_rate.to_string()));
+    }
+    if let Some(channels) = params.channels {
+        entries.push(("channels", channels.count().to_string()));
+    }
+    if let Some(bits) = params.bits_per_sample {
+        entries.push(("bit_depth", bits.to_string()));
+    }
+
+    // Not every header records the length (e.g. MP3 without a Xing frame), so
+    // fall back to adding up packet durations, which needs no decoding
+    let frames = params.n_frames.or_else(|| {
+        let mut total = 0;
+        while let Ok(packet) = format.next_packet() {
+            if packet.track_id() == track_id {
+                total += packet.dur;
+            }
+        }
+        (total > 0).then_some(total)
+    });
+    if let (Some(frames), Some(sample_rate)) = (frames, params.sample_rate) {
+        entries.push((
+            "duration_seconds",
+            format!("{:.3}", frames as f64 / f64::from(sample_rate)),
+        ));
+    }
+
+    Ok(entries)
+}
+
+/// Mesh statistics, material and animation names, and local bounds of a model file.
+pub fn model_metadata(path: &Path) -> Result<MetadataEntries, String> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let stats = match extension.as_str() {
+        "fbx" => {
+            let data = fs::read(path).map_err(|e| format!("Failed to read model: {}", e))?;
+            fbx_stats(&data)?
+        }
+        "gltf" | "glb" => gltf_stats(path)?,
+        "obj" => {
+            let text =
+                fs::read_to_string(path).map_err(|e| format!("Failed to read model: {}", e))?;
+            obj_stats(&text)
+        }
+        // Recognized, but nothing to extract yet
+        _ => return Ok(Vec::new()),
+    };
+    Ok(stats.into_entries())
+}
+
+/// Texture paths referenced by a material file, as written in it.
+///
+/// `.mtl` files are read statement by statement. `.mat` files come from many engines, so
+/// JSON and plain-text ones are searched for texture file names; Unity materials only
+/// name textures by GUID, which are recorded separately.
+pub fn material_metadata(path: &Path, file_types: &FileTypes) -> Result<MetadataEntries, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Failed to read material: {}", e))?;
+    let is_texture = |reference: &str| file_types.asset_type(Path::new(reference)) == Some(TEXTURE);
+
+    let mut guids = Vec::new();
+    let (format, mut textures) = if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mtl"))
+    {
+        ("mtl", mtl_textures(&text))
+    } else if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) {
+        let mut textures = Vec::new();
+        json_textures(&json, &is_texture, &mut textures);
+        ("json", textures)
+    } else if text.starts_with("%YAML") {
+        guids = unity_texture_guids(&text);
+        ("unity", Vec::new())
+    } else {
+        let textures = text
+            .split(|c: char| c.is_whitespace() || "\"'=,;(){}[]".contains(c))
+            .filter(|token| is_texture(token))
+            .map(String::from)
+            .collect();
+        ("text", textures)
+    };
+    let mut seen = HashSet::new();
+    textures.retain(|texture| seen.insert(texture.clone()));
+
+    let mut entries = vec![
+        ("material_format", format.to_string()),
+        ("texture_count", (textures.len() + guids.len()).to_string()),
+        ("textures", to_json(&textures)),
+    ];
+    if !guids.is_empty() {
+        entries.push(("texture_guids", to_json(&guids)));
+    }
+    Ok(entries)
+}
+
+/// Run a custom asset type's extractor on a file and read the JSON object it prints.
+///
+/// String values are stored as-is and anything else as JSON text.
+pub fn extracted_metadata(
+    extractor: &MetadataExtractor,
+    path: &Path,
+) -> Result<Vec<(String, String)>, String> {
+    let output = Command::new(&extractor.program)
+        .args(&extractor.args)
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", extractor.program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} exited with {}: {}",
+            extractor.program,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let fields: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("{} printed invalid JSON: {}", extractor.program, e))?;
+    Ok(fields
+        .into_iter()
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, value)| match value {
+            serde_json::Value::String(text) => (key, text),
+            other => (key, other.to_string()),
+        })
+        .collect())
+}
+
+fn mtl_textures(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let statement = parts.next()?.to_lowercase();
+            // Options such as `-bm 0.5` come first, so the path is the last argument
+            MTL_TEXTURE_STATEMENTS
+                .contains(&statement.as_str())
+                .then(|| parts.last().map(String::from))
+                .flatten()
+        })
+        .collect()
+}
+
+fn json_textures(
+    value: &serde_json::Value,
+    is_texture: &dyn Fn(&str) -> bool,
+    out: &mut Vec<String>,
+) {
+    match value {
+        serde_json::Value::String(text) if is_texture(text) => out.push(text.clone()),
+        serde_json::Value::Array(values) => {
+            for value in values {
+                json_textures(value, is_texture, out);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for value in map.values() {
+                json_textures(value, is_texture, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// GUIDs of textures assigned in a Unity material's `m_TexEnvs`; unassigned slots have none.
+fn unity_texture_guids(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| line.trim().strip_prefix("m_Texture:"))
+        .filter_map(|value| value.split("guid:").nth(1))
+        .filter_map(|rest| rest.split([',', '}']).next())
+        .map(|guid| guid.trim().to_string())
+        .filter(|guid| !guid.is_empty())
+        .collect()
+}
+
+#[derive(Debug, Default)]
+struct ModelStats {
+    mesh_count: usize,
+    vertex_count: usize,
+    triangle_count: usize,
+    materials: Vec<String>,
+    animations: Vec<String>,
+    /// Min and max corners in the model's own coordinate space
+    bounds: Option<([f64; 3], [f64; 3])>,
+}
+
+impl ModelStats {
+    fn include_point(&mut self, point: [f64; 3]) {
+        let (min, max) = self.bounds.get_or_insert((point, point));
+        for axis in 0..3 {
+            min[axis] = min[axis].min(point[axis]);
+            max[axis] = max[axis].max(point[axis]);
+        }
+    }
+
+    fn into_entries(self) -> MetadataEntries {
+        let mut entries = vec![
+            ("mesh_count", self.mesh_count.to_string()),
+            ("vertex_count", self.vertex_count.to_string()),
+            ("triangle_count", self.triangle_count.to_string()),
+            ("materials", to_json(&self.materials)),
+            ("animations", to_json(&self.animations)),
+        ];
+        if let Some((min, max)) = self.bounds {
+            entries.push(("bounds_min", to_json(&min)));
+            entries.push(("bounds_max", to_json(&max)));
+        }
+        entries
+    }
+}
+
+fn fbx_stats(data: &[u8]) -> Result<ModelStats, String> {
+    let nodes = fbx::parse(data)?;
+    let objects = nodes
+        .iter()
+        .find(|node| node.name == "Objects")
+        .ok_or("FBX file has no objects")?;
+
+    let mut stats = ModelStats::default();
+    for object in &objects.children {
+        match object.name.as_str() {
+            "Geometry" if object.object_class() == Some("Mesh") => {
+                stats.mesh_count += 1;
+                if let Some(FbxProperty::FloatArray(vertices)) = object
+                    .child("Vertices")
+                    .and_then(|node| node.properties.first())
+                {
+                    stats.vertex_count += vertices.len() / 3;
+                    for point in vertices.chunks_exact(3) {
+                        stats.include_point([point[0], point[1], point[2]]);
+                    }
+                }
+                if let Some(FbxProperty::IntArray(indices)) = object
+                    .child("PolygonVertexIndex")
+                    .and_then(|node| node.properties.first())
+                {
+                    // A negative (bitwise-negated) index closes each polygon
+                    let mut corners: usize = 0;
+                    for &index in indices {
+                        corners += 1;
+                        if index < 0 {
+                            stats.triangle_count += corners.saturating_sub(2);
+                            corners = 0;
+                        }
+                    }
+                }
+            }
+            "Material" => stats
+                .materials
+                .extend(object.object_name().map(String::from)),
+            "AnimationStack" => stats
+                .animations
+                .extend(object.object_name().map(String::from)),
+            _ => {}
+        }
+    }
+    Ok(stats)
+}
+
+fn obj_stats(text: &str) -> ModelStats {
+    let mut stats = ModelStats::default();
+    for line in text.lines() {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("v") => {
+                let coords: Vec<f64> = parts.take(3).filter_map(|v| v.parse().ok()).collect();
+                if let [x, y, z] = coords[..] {
+                    stats.vertex_count += 1;
+                    stats.include_point([x, y, z]);
+                }
+            }
+            // Faces are fans of 3 or more corners
+            Some("f") => stats.triangle_count += parts.count().saturating_sub(2),
+            Some("o") => stats.mesh_count += 1,
+            Some("usemtl") => {
+                if let Some(name) = parts.next() {
+                    if !stats.materials.iter().any(|m| m == name) {
+                        stats.materials.push(name.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    // A file without `o` statements is a single unnamed object
+    if stats.mesh_count == 0 && stats.vertex_count > 0 {
+        stats.mesh_count = 1;
+    }
+    stats
+}
+
+fn gltf_stats(path: &Path) -> Result<ModelStats, String> {
+    let document = gltf::Gltf::open(path).map_err(|e| format!("Failed to read glTF: {}", e))?;
+
+    let mut stats = ModelStats::default();
+    for mesh in document.meshes() {
+        stats.mesh_count += 1;
+        for primitive in mesh.primitives() {
+            let Some(positions) = primitive.get(&gltf::Semantic::Positions) else {
+                continue;
+            };
+            stats.vertex_count += positions.count();
+
+            let corners = primitive
+                .indices()
+                .map_or_else(|| positions.count(), |indices| indices.count());
+            stats.triangle_count += match primitive.mode() {
+                gltf::mesh::Mode::Triangles => corners / 3,
+                gltf::mesh::Mode::TriangleStrip | gltf::mesh::Mode::TriangleFan => {
+                    corners.saturating_sub(2)
+                }
+                _ => 0,
+            };
+
+            // The spec requires min/max on POSITION accessors
+            for corner in [positions.min(), positions.max()].into_iter().flatten() {
+                if let Some(point) = json_point(&corner) {
+                    stats.include_point(point);
+                }
+            }
+        }
+    }
+
+    stats.materials = document
+        .materials()
+        .filter_map(|material| material.name().map(String::from))
+        .collect();
+    stats.animations = document
+        .animations()
+        .filter_map(|animation| animation.name().map(String::from))
+        .collect();
+    Ok(stats)
+}
+
+fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn json_point(value: &serde_json::Value) -> Option<[f64; 3]> {
+    let values = value.as_array()?;
+    Some([
+        values.first()?.as_f64()?,
+        values.get(1)?.as_f64()?,
+        values.get(2)?.as_f64()?,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reads_image_header() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("texture.png");
+        image::RgbaImage::new(64, 32).save(&path).unwrap();
+
+        let metadata = image_metadata(&path).unwrap();
+        let value = |key: &str| {
+            metadata
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(value("width"), Some("64"));
+        assert_eq!(value("height"), Some("32"));
+        assert_eq!(value("channels"), Some("4"));
+        assert_eq!(value("bit_depth"), Some("8"));
+        assert_eq!(value("has_alpha"), Some("true"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_custom_extractor() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("world.ldtk");
+        fs::write(&path, "{}").unwrap();
+
+        // The script sees the file path as `$1`
+        let extractor = MetadataExtractor {
+            program: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                r#"echo "{\"levels\": 3, \"source\": \"$(basename "$1")\"}""#.to_string(),
+                "extractor".to_string(),
+            ],
+        };
+        let metadata = extracted_metadata(&extractor, &path).unwrap();
+        assert!(metadata.contains(&("levels".to_string(), "3".to_string())));
+        assert!(metadata.contains(&("source".to_string(), "world.ldtk".to_string())));
+
+        let failing = MetadataExtractor {
+            program: "sh".to_string(),
+            args: vec!["-c".to_string(), "exit 1".to_string()],
+        };
+        assert!(extracted_metadata(&failing, &path).is_err());
+    }
+
+    #[test]
+    fn reads_wav_header() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("click.wav");
+
+        // One second of 16-bit mono silence at 8 kHz
+        let sample_rate: u32 = 8000;
+        let data_len: u32 = sample_rate * 2;
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        wav.resize(wav.len() + data_len as usize, 0);
+        std::fs::write(&path, wav).unwrap();
+
+        let metadata = audio_metadata(&path).unwrap();
+        let value = |key: &str| {
+            metadata
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(value("sample_rate"), Some("8000"));
+        assert_eq!(value("channels"), Some("1"));
+        assert_eq!(value("bit_depth"), Some("16"));
+        assert_eq!(value("duration_seconds"), Some("1.000"));
+    }
+
+    fn metadata_value(metadata: &MetadataEntries, key: &str) -> Option<String> {
+        metadata
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.clone())
+    }
+
+    /// Node record for a binary FBX 7.4 file; offsets are absolute, so write in place.
+    struct FbxTestNode {
+        name: &'static str,
+        properties: Vec<Vec<u8>>,
+        children: Vec<FbxTestNode>,
+    }
+
+    impl FbxTestNode {
+        fn write(&self, out: &mut Vec<u8>) {
+            let start = out.len();
+            let properties = self.properties.concat();
+            out.extend_from_slice(&[0; 12]);
+            out.push(self.name.len() as u8);
+            out.extend_from_slice(self.name.as_bytes());
+            out.extend_from_slice(&properties);
+            if !self.children.is_empty() {
+                for child in &self.children {
+                    child.write(out);
+                }
+                out.extend_from_slice(&[0; 13]);
+            }
+            let end = out.len() as u32;
+            out[start..start + 4].copy_from_slice(&end.to_le_bytes());
+            out[start + 4..start + 8]
+                .copy_from_slice(&(self.properties.len() as u32).to_le_bytes());
+            out[start + 8..start + 12].copy_from_slice(&(properties.len() as u32).to_le_bytes());
+        }
+    }
+
+    fn fbx_string(value: &str) -> Vec<u8> {
+        let mut bytes = vec![b'S'];
+        bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(value.as_bytes());
+        bytes
+    }
+
+    fn fbx_id(id: i64) -> Vec<u8> {
+        let mut bytes = vec![b'L'];
+        bytes.extend_from_slice(&id.to_le_bytes());
+        bytes
+    }
+
+    fn fbx_array(type_code: u8, count: usize, data: Vec<u8>) -> Vec<u8> {
+        let mut bytes = vec![type_code];
+        bytes.extend_from_slice(&(count as u32).to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend(data);
+        bytes
+    }
+
+    fn fbx_object(
+        node_name: &'static str,
+        id: i64,
+        name: &str,
+        class: &str,
+        children: Vec<FbxTestNode>,
+    ) -> FbxTestNode {
+        FbxTestNode {
+            name: node_name,
+            properties: vec![fbx_id(id), fbx_string(name), fbx_string(class)],
+            children,
+        }
+    }
+
+    #[test]
+    fn reads_binary_fbx_statistics() {
+        let vertices = [
+            0.0f64, 0.0, 0.0, 2.0, 0.0, 0.0, 2.0, 1.0, 0.0, 0.0, 1.0, -3.0,
+        ];
+        let indices = [0i32, 1, 2, -4];
+        let geometry = fbx_object(
+            "Geometry",
+            1,
+            "Crate\0\u{1}Geometry",
+            "Mesh",
+            vec![
+                FbxTestNode {
+                    name: "Vertices",
+                    properties: vec![fbx_array(
+                        b'd',
+                        vertices.len(),
+                        vertices.iter().flat_map(|v| v.to_le_bytes()).collect(),
+                    )],
+                    children: Vec::new(),
+                },
+                FbxTestNode {
+                    name: "PolygonVertexIndex",
+                    properties: vec![fbx_array(
+                        b'i',
+                        indices.len(),
+                        indices.iter().flat_map(|i| i.to_le_bytes()).collect(),
+                    )],
+                    children: Vec::new(),
+                },
+            ],
+        );
+        let material = fbx_object("Material", 2, "Wood\0\u{1}Material", "", Vec::new());
+        let animation = fbx_object("AnimationStack", 3, "Open\0\u{1}AnimStack", "", Vec::new());
+        let objects = FbxTestNode {
+            name: "Objects",
+            properties: Vec::new(),
+            children: vec![geometry, material, animation],
+        };
+
+        let mut data = b"Kaydara FBX Binary  \0\x1a\0".to_vec();
+        data.extend_from_slice(&7400u32.to_le_bytes());
+        objects.write(&mut data);
+        data.extend_from_slice(&[0; 13]);
+
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("crate.fbx");
+        std::fs::write(&path, data).unwrap();
+
+        let metadata = model_metadata(&path).unwrap();
+        assert_eq!(metadata_value(&metadata, "mesh_count").unwrap(), "1");
+        assert_eq!(metadata_value(&metadata, "vertex_count").unwrap(), "4");
+        assert_eq!(metadata_value(&metadata, "triangle_count").unwrap(), "2");
+        assert_eq!(
+            metadata_value(&metadata, "materials").unwrap(),
+            r#"["Wood"]"#
+        );
+        assert_eq!(
+            metadata_value(&metadata, "animations").unwrap(),
+            r#"["Open"]"#
+        );
+        assert_eq!(
+            metadata_value(&metadata, "bounds_min").unwrap(),
+            "[0.0,0.0,-3.0]"
+        );
+        assert_eq!(
+            metadata_value(&metadata, "bounds_max").unwrap(),
+            "[2.0,1.0,0.0]"
+        );
+    }
+
+    #[test]
+    fn finds_material_textures() {
+        let temp_dir = tempdir().unwrap();
+        let file_types = FileTypes::default();
+        let read = |name: &str, contents: &str| {
+            let path = temp_dir.path().join(name);
+            std::fs::write(&path, contents).unwrap();
+            material_metadata(&path, &file_types).unwrap()
+        };
+
+        let mtl = read(
+            "crate.mtl",
+            "newmtl Wood\nKd 1 1 1\nmap_Kd textures/wood.png\nmap_Bump -bm 0.5 textures/wood_n.png\n",
+        );
+        assert_eq!(metadata_value(&mtl, "material_format").unwrap(), "mtl");
+        assert_eq!(
+            metadata_value(&mtl, "textures").unwrap(),
+            r#"["textures/wood.png","textures/wood_n.png"]"#
+        );
+
+        let json = read(
+            "metal.mat",
+            r#"{"name": "Metal", "maps": {"albedo": "metal.jpg", "normal": "metal.jpg"}, "roughness": 0.4}"#,
+        );
+        assert_eq!(metadata_value(&json, "material_format").unwrap(), "json");
+        assert_eq!(
+            metadata_value(&json, "textures").unwrap(),
+            r#"["metal.jpg"]"#
+        );
+
+        let text = read(
+            "stone.mat",
+            "texture_unit\n{\n    texture \"stone.TGA\"\n}\n",
+        );
+        assert_eq!(
+            metadata_value(&text, "textures").unwrap(),
+            r#"["stone.TGA"]"#
+        );
+
+        let unity = read(
+            "grass.mat",
+            "%YAML 1.1\nMaterial:\n  m_SavedProperties:\n    m_TexEnvs:\n    - _MainTex:\n        \
+             m_Texture: {fileID: 2800000, guid: 4f1c2a, type: 3}\n    - _BumpMap:\n        \
+             m_Texture: {fileID: 0}\n",
+        );
+        assert_eq!(metadata_value(&unity, "material_format").unwrap(), "unity");
+        assert_eq!(
+            metadata_value(&unity, "texture_guids").unwrap(),
+            r#"["4f1c2a"]"#
+        );
+        assert_eq!(metadata_value(&unity, "texture_count").unwrap(), "1");
+    }
+
+    #[test]
+    fn reads_obj_statistics() {
+        let stats = obj_stats(
+            "# crate\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 2\nusemtl Wood\nf 1 2 3 4\nf 1 2 3\n",
+        );
+        assert_eq!(stats.mesh_count, 1);
+        assert_eq!(stats.vertex_count, 4);
+        assert_eq!(stats.triangle_count, 3);
+        assert_eq!(stats.materials, vec!["Wood".to_string()]);
+        assert_eq!(stats.bounds, Some(([0.0, 0.0, 0.0], [1.0, 1.0, 2.0])));
+    }
+
+    #[test]
+    fn reads_gltf_statistics() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("ramp.gltf");
+        let document = serde_json::json!({
+            "asset": { "version": "2.0" },
+            "buffers": [{ "byteLength": 72, "uri": "ramp.bin" }],
+            "bufferViews": [{ "buffer": 0, "byteLength": 72 }],
+            "accessors": [{
+                "bufferView": 0,
+                "componentType": 5126,
+                "count": 6,
+                "type": "VEC3",
+                "min": [-1.0, 0.0, -1.0],
+                "max": [1.0, 0.5, 1.0]
+            }],
+            "materials": [{ "name": "Concrete" }],
+            "meshes": [{
+                "primitives": [{ "attributes": { "POSITION": 0 }, "material": 0 }]
+            }]
+        });
+        std::fs::write(&path, document.to_string()).unwrap();
+
+        let metadata = model_metadata(&path).unwrap();
+        assert_eq!(metadata_value(&metadata, "mesh_count").unwrap(), "1");
+        assert_eq!(metadata_value(&metadata, "vertex_count").unwrap(), "6");
+        assert_eq!(metadata_value(&metadata, "triangle_count").unwrap(), "2");
+        assert_eq!(
+            metadata_value(&metadata, "materials").unwrap(),
+            r#"["Concrete"]"#
+        );
+        assert_eq!(
+            metadata_value(&metadata, "bounds_max").unwrap(),
+            "[1.0,0.5,1.0]"
+        );
+    }
+}
@@ -0,0 +1,384 @@
+// Versioned schema changes for the asset database
+use super::lod;
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension, Result as SqlResult};
+use std::path::Path;
+
+/// One schema change. Steps run in order and each runs once per database, so a
+/// released step must never be edited; add a new one instead.
+struct Migration {
+    version: u32,
+    description: &'static str,
+    apply: fn(&Connection) -> SqlResult<()>,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial schema",
+        apply: initial_schema,
+    },
+    Migration {
+        version: 2,
+        description: "track file modification times",
+        apply: |conn| add_column(conn, "assets", "file_mtime", "INTEGER"),
+    },
+    Migration {
+        version: 3,
+        description: "record the asset root each file came from",
+        apply: |conn| add_column(conn, "assets", "asset_root", "TEXT"),
+    },
+    Migration {
+        version: 4,
+        description: "asset ratings and checksum lookups",
+        apply: |conn| {
+            add_column(conn, "assets", "rating", "INTEGER")?;
+            conn.execute(
+                "CREATE INDEX IF NOT EXISTS idx_assets_checksum ON assets(checksum)",
+                [],
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        version: 5,
+        description: "track which levels use each asset",
+        apply: |conn| {
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS asset_usage (
+                    level_path TEXT NOT NULL,
+                    asset_id INTEGER NOT NULL,
+                    reference TEXT NOT NULL,
+                    object_count INTEGER NOT NULL,
+                    recorded_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                    PRIMARY KEY (level_path, asset_id, reference),
+                    FOREIGN KEY (asset_id) REFERENCES assets (id) ON DELETE CASCADE
+                );
+                CREATE INDEX IF NOT EXISTS idx_usage_asset ON asset_usage(asset_id);",
+            )
+        },
+    },
+    Migration {
+        version: 6,
+        description: "record asset content changes",
+        apply: |conn| {
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS asset_history (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    asset_id INTEGER NOT NULL,
+                    old_checksum TEXT NOT NULL,
+                    new_checksum TEXT NOT NULL,
+                    old_size INTEGER NOT NULL,
+                    new_size INTEGER NOT NULL,
+                    changed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                    FOREIGN KEY (asset_id) REFERENCES assets (id) ON DELETE CASCADE
+                );
+                CREATE INDEX IF NOT EXISTS idx_history_asset ON asset_history(asset_id);",
+            )
+        },
+    },
+    Migration {
+        version: 7,
+        description: "audio waveform overviews",
+        apply: |conn| {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS waveforms (
+                    asset_id INTEGER PRIMARY KEY,
+                    peaks TEXT NOT NULL,
+                    generated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                    FOREIGN KEY (asset_id) REFERENCES assets (id) ON DELETE CASCADE
+                )",
+                [],
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        version: 8,
+        description: "record how each checksum was computed",
+        apply: |conn| {
+            add_column(
+                conn,
+                "assets",
+                "checksum_mode",
+                "TEXT NOT NULL DEFAULT 'full'",
+            )
+        },
+    },
+    Migration {
+        version: 9,
+        description: "maintenance task log",
+        apply: |conn| {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS maintenance (
+                    task TEXT PRIMARY KEY,
+                    last_run_at DATETIME NOT NULL
+                )",
+                [],
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        version: 10,
+        description: "group models into LOD sets",
+        apply: lod_groups,
+    },
+];
+
+/// Schema version a fully migrated database is at.
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Bring the database up to the latest schema, returning the version it started at.
+pub fn run(conn: &mut Connection) -> SqlResult<u32> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    let mut current = current_version(conn)?;
+    if current == 0 {
+        // Databases from before versioning have tables but no recorded version
+        current = legacy_version(conn)?;
+        if current > 0 {
+            info!(
+                "Found unversioned asset database at schema version {}",
+                current
+            );
+            for migration in MIGRATIONS.iter().filter(|m| m.version <= current) {
+                record(conn, migration)?;
+            }
+        }
+    }
+    let starting_version = current;
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        info!(
+            "Migrating asset database to version {}: {}",
+            migration.version, migration.description
+        );
+        let tx = conn.transaction()?;
+        (migration.apply)(&tx)?;
+        record(&tx, migration)?;
+        tx.commit()?;
+    }
+
+    Ok(starting_version)
+}
+
+fn current_version(conn: &Connection) -> SqlResult<u32> {
+    conn.query_row("SELECT MAX(version) FROM schema_version", [], |row| {
+        row.get::<usize, Option<u32>>(0)
+    })
+    .map(Option::unwrap_or_default)
+}
+
+fn record(conn: &Connection, migration: &Migration) -> SqlResult<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO schema_version (version, description) VALUES (?1, ?2)",
+        params![migration.version, migration.description],
+    )?;
+    Ok(())
+}
+
+/// Newest schema released before versions were recorded; later ones always have a version.
+const LAST_UNVERSIONED: u32 = 4;
+
+/// Infer the version of an unversioned database from the columns it has.
+fn legacy_version(conn: &Connection) -> SqlResult<u32> {
+    let has_assets: Option<String> = conn
+        .query_row(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'assets'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if has_assets.is_none() {
+        return Ok(0);
+    }
+
+    Ok(if has_column(conn, "assets", "rating")? {
+        LAST_UNVERSIONED
+    } else if has_column(conn, "assets", "asset_root")? {
+        3
+    } else if has_column(conn, "assets", "file_mtime")? {
+        2
+    } else {
+        1
+    })
+}
+
+fn has_column(conn: &Connection, table: &str, column: &str) -> SqlResult<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+        params![table, column],
+        |row| row.get::<usize, i64>(0).map(|count| count > 0),
+    )
+}
+
+fn add_column(conn: &Connection, table: &str, column: &str, column_type: &str) -> SqlResult<()> {
+    if !has_column(conn, table, column)? {
+        conn.execute(
+            &format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table, column, column_type
+            ),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+/// Create the LOD table and sort already indexed models into it.
+fn lod_groups(conn: &Connection) -> SqlResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS lod_members (
+            asset_id INTEGER PRIMARY KEY,
+            group_key TEXT NOT NULL,
+            group_name TEXT NOT NULL,
+            lod_level INTEGER NOT NULL,
+            FOREIGN KEY (asset_id) REFERENCES assets (id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_lod_group ON lod_members(group_key);",
+    )?;
+
+    let mut stmt = conn.prepare("SELECT id, file_path FROM assets WHERE asset_type = 'Model'")?;
+    let models = stmt
+        .query_map([], |row| {
+            Ok((row.get::<usize, i64>(0)?, row.get::<usize, String>(1)?))
+        })?
+        .collect::<SqlResult<Vec<_>>>()?;
+    for (asset_id, file_path) in models {
+        if let Some(member) = lod::lod_member(Path::new(&file_path)) {
+            conn.execute(
+                "INSERT OR REPLACE INTO lod_members (asset_id, group_key, group_name, lod_level)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![asset_id, member.key, member.name, member.level],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn initial_schema(conn: &Connection) -> SqlResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS collections (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT UNIQUE NOT NULL,
+            description TEXT,
+            license_info TEXT,
+            asset_count INTEGER DEFAULT 0,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS assets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            file_path TEXT UNIQUE NOT NULL,
+            asset_type TEXT NOT NULL,
+            collection TEXT NOT NULL,
+            file_size INTEGER NOT NULL,
+            checksum TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (collection) REFERENCES collections (name)
+        );
+
+        -- Key-value pairs per asset
+        CREATE TABLE IF NOT EXISTS asset_metadata (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            asset_id INTEGER NOT NULL,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (asset_id) REFERENCES assets (id) ON DELETE CASCADE,
+            UNIQUE(asset_id, key)
+        );
+
+        CREATE TABLE IF NOT EXISTS thumbnails (
+            asset_id INTEGER PRIMARY KEY,
+            thumbnail_path TEXT NOT NULL,
+            generated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (asset_id) REFERENCES assets (id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS asset_tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            asset_id INTEGER NOT NULL,
+            tag_name TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (asset_id) REFERENCES assets (id) ON DELETE CASCADE,
+            UNIQUE(asset_id, tag_name)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_assets_name ON assets(name);
+        CREATE INDEX IF NOT EXISTS idx_assets_type ON assets(asset_type);
+        CREATE INDEX IF NOT EXISTS idx_assets_collection ON assets(collection);
+        CREATE INDEX IF NOT EXISTS idx_assets_search ON assets(name, asset_type, collection);
+        CREATE INDEX IF NOT EXISTS idx_metadata_key ON asset_metadata(key);
+        CREATE INDEX IF NOT EXISTS idx_tags_name ON asset_tags(tag_name);",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A database as an older release left it: schema up to `version`, no version table.
+    fn unversioned_database(version: u32) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        for migration in MIGRATIONS.iter().filter(|m| m.version <= version) {
+            (migration.apply)(&conn).unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO collections (name) VALUES ('Kenney');
+             INSERT INTO assets (name, file_path, asset_type, collection, file_size, checksum)
+             VALUES ('crate.png', '/Assets/Kenney/crate.png', 'Texture', 'Kenney', 5, 'abc');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn upgrades_every_historical_schema() {
+        for version in 1..=LAST_UNVERSIONED {
+            let mut conn = unversioned_database(version);
+            assert_eq!(run(&mut conn).unwrap(), version);
+            assert_eq!(current_version(&conn).unwrap(), latest_version());
+
+            for column in ["file_mtime", "asset_root", "rating"] {
+                assert!(
+                    has_column(&conn, "assets", column).unwrap(),
+                    "v{} missing {}",
+                    version,
+                    column
+                );
+            }
+            let name: String = conn
+                .query_row("SELECT name FROM assets", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(name, "crate.png");
+        }
+    }
+
+    #[test]
+    fn fresh_database_is_created_at_latest_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(run(&mut conn).unwrap(), 0);
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+
+        // Running again is a no-op
+        assert_eq!(run(&mut conn).unwrap(), latest_version());
+        let recorded: i64 = conn
+            .query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(recorded, i64::from(latest_version()));
+    }
+}
@@ -0,0 +1,187 @@
+use super::database::AssetDatabase;
+use crate::LevelData;
+use std::path::{Component, Path, PathBuf};
+
+/// Prefix marking a reference to a row in the asset database, e.g. `asset:42`.
+pub const ASSET_ID_PREFIX: &str = "asset:";
+
+/// A parsed `mesh`/`material` reference from a level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetRef {
+    /// Path relative to the project's asset root, always `/`-separated
+    Relative(String),
+    /// Asset database ID
+    Id(i64),
+    /// Machine-specific path written by older versions of the editor
+    Absolute(PathBuf),
+}
+
+impl AssetRef {
+    pub fn parse(reference: &str) -> Self {
+        if let Some(id) = reference
+            .strip_prefix(ASSET_ID_PREFIX)
+            .and_then(|id| id.parse().ok())
+        {
+            return AssetRef::Id(id);
+        }
+
+        let path = Path::new(reference);
+        if path.is_absolute() {
+            AssetRef::Absolute(path.to_path_buf())
+        } else {
+            AssetRef::Relative(reference.replace('\\', "/"))
+        }
+    }
+}
+
+/// Resolves level asset references against a project's asset root.
+///
+/// Levels store references relative to the root (or asset database IDs) so
+/// they survive being moved between machines; absolute paths only exist at
+/// preview time.
+#[derive(Debug, Clone)]
+pub struct AssetPathResolver {
+    root: PathBuf,
+}
+
+impl AssetPathResolver {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        let root = root.as_ref();
+        Self {
+            root: root.canonicalize().unwrap_or_else(|_| root.to_path_buf()),
+        }
+    }
+
+    /// Resolver for the project's `Assets` directory, if one can be found.
+    pub fn for_project() -> Option<Self> {
+        super::find_assets_directory().map(Self::new)
+    }
+
+    /// Absolute path of a reference on this machine.
+    pub fn resolve(
+        &self,
+        reference: &str,
+        database: Option<&AssetDatabase>,
+    ) -> Result<PathBuf, String> {
+        match AssetRef::parse(reference) {
+            AssetRef::Relative(path) => Ok(self.root.join(path)),
+            AssetRef::Absolute(path) => Ok(path),
+            AssetRef::Id(id) => {
+                let path = PathBuf::from(Self::database_path(id, database)?);
+                Ok(path.canonicalize().unwrap_or(path))
+            }
+        }
+    }
+
+    /// Root-relative form of a reference, as consumed by Bevy's asset server.
+    ///
+    /// Absolute paths outside the asset root are returned unchanged.
+    pub fn relative_reference(
+        &self,
+        reference: &str,
+        database: Option<&AssetDatabase>,
+    ) -> Result<String, String> {
+        match AssetRef::parse(reference) {
+            AssetRef::Relative(path) => Ok(path),
+            AssetRef::Absolute(path) => Ok(self
+                .relativize(&path)
+                .unwrap_or_else(|| reference.to_string())),
+            AssetRef::Id(id) => {
+                let path = PathBuf::from(Self::database_path(id, database)?);
+                let path = path.canonicalize().unwrap_or(path);
+                self.relativize(&path)
+                    .ok_or_else(|| format!("Asset {} is outside the project: {:?}", id, path))
+            }
+        }
+    }
+
+    /// Rewrite absolute paths inside the asset root as relative references.
+    ///
+    /// Returns the number of references changed.
+    pub fn make_portable(&self, level: &mut LevelData) -> usize {
+        let mut changed = 0;
+        for obj in &mut level.objects {
+            for reference in [&mut obj.mesh, &mut obj.material].into_iter().flatten() {
+                if let AssetRef::Absolute(path) = AssetRef::parse(reference) {
+                    if let Some(relative) = self.relativize(&path) {
+                        *reference = relative;
+                        changed += 1;
+                    }
+                }
+            }
+        }
+        changed
+    }
+
+    /// Resolve every reference in a level to its root-relative form for export.
+    pub fn relative_level(
+        &self,
+        level: &LevelData,
+        database: Option<&AssetDatabase>,
+    ) -> Result<LevelData, String> {
+        let mut level = level.clone();
+        for obj in &mut level.objects {
+            for reference in [&mut obj.mesh, &mut obj.material].into_iter().flatten() {
+                *reference = self.relative_reference(reference, database)?;
+            }
+        }
+        Ok(level)
+    }
+
+    fn relativize(&self, path: &Path) -> Option<String> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let parts: Vec<String> = relative
+            .components()
+            .map(|c| match c {
+                Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .collect::<Option<_>>()?;
+        Some(parts.join("/"))
+    }
+
+    fn database_path(id: i64, database: Option<&AssetDatabase>) -> Result<String, String> {
+        let database = database.ok_or("Asset database not initialized")?;
+        database
+            .get_asset_by_id(id)
+            .map_err(|e| format!("Failed to look up asset {}: {}", id, e))?
+            .map(|result| result.asset.file_path)
+            .ok_or_else(|| format!("Asset not found: {}", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_reference_kinds() {
+        assert_eq!(AssetRef::parse("asset:42"), AssetRef::Id(42));
+        assert_eq!(
+            AssetRef::parse("meshes\\cube.mesh"),
+            AssetRef::Relative("meshes/cube.mesh".to_string())
+        );
+        assert!(matches!(
+            AssetRef::parse(&std::env::temp_dir().join("cube.mesh").to_string_lossy()),
+            AssetRef::Absolute(_)
+        ));
+    }
+
+    #[test]
+    fn absolute_paths_under_root_become_relative() {
+        let root = std::env::temp_dir();
+        let resolver = AssetPathResolver::new(&root);
+        let absolute = resolver.root.join("meshes").join("cube.mesh");
+
+        let relative = resolver
+            .relative_reference(&absolute.to_string_lossy(), None)
+            .unwrap();
+        assert_eq!(relative, "meshes/cube.mesh");
+        assert_eq!(
+            resolver.resolve("meshes/cube.mesh", None).unwrap(),
+            resolver.root.join("meshes/cube.mesh")
+        );
+        assert!(resolver.resolve("asset:1", None).is_err());
+    }
+}
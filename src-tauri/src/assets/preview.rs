@@ -0,0 +1,493 @@
+// Small PNG previews of textures and models for the asset browser
+use super::fbx::{self, FbxProperty};
+use super::file_types::{MODEL, TEXTURE};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_PREVIEW_SIZE: u32 = 256;
+pub const MAX_PREVIEW_SIZE: u32 = 1024;
+const MIN_PREVIEW_SIZE: u32 = 16;
+
+/// Camera angles for model previews, in radians: a three-quarter view from above.
+const VIEW_YAW: f32 = 0.6;
+const VIEW_PITCH: f32 = 0.5;
+/// Fraction of the image left empty on each side of a model.
+const VIEW_MARGIN: f32 = 0.08;
+const MODEL_COLOR: [f32; 3] = [176.0, 188.0, 204.0];
+const LIGHT_DIRECTION: [f32; 3] = [0.36, 0.48, 0.8];
+
+type Triangle = [[f32; 3]; 3];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetPreview {
+    pub asset_id: i64,
+    /// PNG file in the preview cache
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    /// False when the preview was rendered by this request
+    pub cached: bool,
+}
+
+pub fn clamp_size(size: Option<u32>) -> u32 {
+    size.unwrap_or(DEFAULT_PREVIEW_SIZE)
+        .clamp(MIN_PREVIEW_SIZE, MAX_PREVIEW_SIZE)
+}
+
+/// Cache file for a preview; keyed by checksum so edited files get a new one.
+pub fn preview_path(cache_dir: &Path, checksum: &str, size: u32) -> PathBuf {
+    cache_dir.join(format!("{}-{}.png", checksum, size))
+}
+
+/// Checksum of the file a cache entry named by [`preview_path`] was rendered from.
+pub fn preview_checksum(file_name: &str) -> Option<&str> {
+    let (checksum, size) = file_name.strip_suffix(".png")?.rsplit_once('-')?;
+    size.parse::<u32>().ok().map(|_| checksum)
+}
+
+/// Delete every cached size of a file's preview, returning how many were removed.
+pub fn remove_cached_previews(cache_dir: &Path, checksum: &str) -> usize {
+    let prefix = format!("{}-", checksum);
+    let Ok(entries) = fs::read_dir(cache_dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .filter(|entry| fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+/// Return the cached preview for a file, rendering it first if needed.
+pub fn cached_preview(
+    asset_id: i64,
+    source: &Path,
+    asset_type: &str,
+    checksum: &str,
+    cache_dir: &Path,
+    size: u32,
+) -> Result<AssetPreview, String> {
+    let path = preview_path(cache_dir, checksum, size);
+    let cached = path.is_file();
+    let (width, height) = if cached {
+        image::image_dimensions(&path).map_err(|e| format!("Failed to read preview: {}", e))?
+    } else {
+        let preview = render_preview(source, asset_type, size)?;
+        fs::create_dir_all(cache_dir)
+            .map_err(|e| format!("Failed to create preview cache: {}", e))?;
+        preview
+            .save(&path)
+            .map_err(|e| format!("Failed to write preview: {}", e))?;
+        preview.dimensions()
+    };
+
+    Ok(AssetPreview {
+        asset_id,
+        path: path.to_string_lossy().to_string(),
+        width,
+        height,
+        cached,
+    })
+}
+
+/// Render a preview no larger than `size` pixels on either side.
+pub fn render_preview(source: &Path, asset_type: &str, size: u32) -> Result<RgbaImage, String> {
+    match asset_type {
+        TEXTURE => Ok(image::open(source)
+            .map_err(|e| format!("Failed to open image: {}", e))?
+            .thumbnail(size, size)
+            .to_rgba8()),
+        MODEL => Ok(render_triangles(&model_triangles(source)?, size)),
+        other => Err(format!("No preview available for {} assets", other)),
+    }
+}
+
+fn model_triangles(path: &Path) -> Result<Vec<Triangle>, String> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let triangles = match extension.as_str() {
+        "obj" => {
+            let text =
+                fs::read_to_string(path).map_err(|e| format!("Failed to read model: {}", e))?;
+            obj_triangles(&text)
+        }
+        "fbx" => {
+            let data = fs::read(path).map_err(|e| format!("Failed to read model: {}", e))?;
+            fbx_triangles(&data)?
+        }
+        "gltf" | "glb" => gltf_triangles(path)?,
+        _ => return Err(format!("Can't preview .{} models", extension)),
+    };
+    if triangles.is_empty() {
+        return Err("Model has no triangles to preview".to_string());
+    }
+    Ok(triangles)
+}
+
+/// Split a polygon into a fan of triangles.
+fn fan(corners: &[[f32; 3]], triangles: &mut Vec<Triangle>) {
+    for pair in corners.windows(2).skip(1) {
+        triangles.push([corners[0], pair[0], pair[1]]);
+    }
+}
+
+fn obj_triangles(text: &str) -> Vec<Triangle> {
+    let mut vertices: Vec<[f32; 3]> = Vec::new();
+    let mut triangles = Vec::new();
+    for line in text.lines() {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("v") => {
+                let coords: Vec<f32> = parts.take(3).filter_map(|v| v.parse().ok()).collect();
+                if let [x, y, z] = coords[..] {
+                    vertices.push([x, y, z]);
+                }
+            }
+            Some("f") => {
+                // `v`, `v/vt`, `v//vn` or `v/vt/vn`; negative indices count back from the end
+                let corners: Option<Vec<[f32; 3]>> = parts
+                    .map(|corner| {
+                        let index: i64 = corner.split('/').next()?.parse().ok()?;
+                        let index = if index < 0 {
+                            vertices.len().checked_sub(index.unsigned_abs() as usize)?
+                        } else {
+                            usize::try_from(index).ok()?.checked_sub(1)?
+                        };
+                        vertices.get(index).copied()
+                    })
+                    .collect();
+                if let Some(corners) = corners {
+                    fan(&corners, &mut triangles);
+                }
+            }
+            _ => {}
+        }
+    }
+    triangles
+}
+
+fn fbx_triangles(data: &[u8]) -> Result<Vec<Triangle>, String> {
+    let nodes = fbx::parse(data)?;
+    let objects = nodes
+        .iter()
+        .find(|node| node.name == "Objects")
+        .ok_or("FBX file has no objects")?;
+
+    let mut triangles = Vec::new();
+    for geometry in objects
+        .children
+        .iter()
+        .filter(|node| node.name == "Geometry" && node.object_class() == Some("Mesh"))
+    {
+        let (Some(FbxProperty::FloatArray(vertices)), Some(FbxProperty::IntArray(indices))) = (
+            geometry
+                .child("Vertices")
+                .and_then(|node| node.properties.first()),
+            geometry
+                .child("PolygonVertexIndex")
+                .and_then(|node| node.properties.first()),
+        ) else {
+            continue;
+        };
+        let vertex = |index: i64| -> Option<[f32; 3]> {
+            let start = usize::try_from(index).ok()? * 3;
+            let point = vertices.get(start..start + 3)?;
+            Some([point[0] as f32, point[1] as f32, point[2] as f32])
+        };
+
+        // A negative (bitwise-negated) index closes each polygon
+        let mut polygon = Vec::new();
+        for &index in indices {
+            let last = index < 0;
+            if let Some(point) = vertex(if last { !index } else { index }) {
+                polygon.push(point);
+            }
+            if last {
+                fan(&polygon, &mut triangles);
+                polygon.clear();
+            }
+        }
+    }
+    Ok(triangles)
+}
+
+fn gltf_triangles(path: &Path) -> Result<Vec<Triangle>, String> {
+    let gltf::Gltf { document, blob } =
+        gltf::Gltf::open(path).map_err(|e| format!("Failed to read glTF: {}", e))?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let buffers = document
+        .buffers()
+        .map(|buffer| match buffer.source() {
+            gltf::buffer::Source::Bin => blob
+                .clone()
+                .ok_or_else(|| "glTF binary chunk missing".to_string()),
+            gltf::buffer::Source::Uri(uri) if uri.starts_with("data:") => {
+                Err("Embedded glTF buffers can't be previewed".to_string())
+            }
+            gltf::buffer::Source::Uri(uri) => fs::read(base_dir.join(uri.replace("%20", " ")))
+                .map_err(|e| format!("Failed to read glTF buffer {}: {}", uri, e)),
+        })
+        .collect::<Result<Vec<Vec<u8>>, String>>()?;
+
+    let mut triangles = Vec::new();
+    let mut add_mesh = |mesh: gltf::Mesh, transform: &[[f32; 4]; 4]| {
+        for primitive in mesh.primitives() {
+            let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(Vec::as_slice));
+            let Some(positions) = reader.read_positions() else {
+                continue;
+            };
+            let positions: Vec<[f32; 3]> = positions
+                .map(|point| transform_point(transform, point))
+                .collect();
+            let indices: Vec<usize> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().map(|i| i as usize).collect(),
+                None => (0..positions.len()).collect(),
+            };
+            let corner = |i: usize| indices.get(i).and_then(|&index| positions.get(index));
+            let count = indices.len();
+            let mut push = |a: usize, b: usize, c: usize| {
+                if let (Some(a), Some(b), Some(c)) = (corner(a), corner(b), corner(c)) {
+                    triangles.push([*a, *b, *c]);
+                }
+            };
+            match primitive.mode() {
+                gltf::mesh::Mode::Triangles => {
+                    for start in (0..count.saturating_sub(2)).step_by(3) {
+                        push(start, start + 1, start + 2);
+                    }
+                }
+                gltf::mesh::Mode::TriangleStrip => {
+                    for start in 0..count.saturating_sub(2) {
+                        push(start, start + 1, start + 2);
+                    }
+                }
+                gltf::mesh::Mode::TriangleFan => {
+                    for start in 1..count.saturating_sub(1) {
+                        push(0, start, start + 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+    };
+
+    match document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+    {
+        Some(scene) => {
+            let mut stack: Vec<(gltf::Node, [[f32; 4]; 4])> = scene
+                .nodes()
+                .map(|node| {
+                    let matrix = node.transform().matrix();
+                    (node, matrix)
+                })
+                .collect();
+            while let Some((node, transform)) = stack.pop() {
+                if let Some(mesh) = node.mesh() {
+                    add_mesh(mesh, &transform);
+                }
+                for child in node.children() {
+                    let matrix = multiply(&transform, &child.transform().matrix());
+                    stack.push((child, matrix));
+                }
+            }
+        }
+        // No scene graph: draw every mesh untransformed
+        None => {
+            for mesh in document.meshes() {
+                add_mesh(mesh, &IDENTITY);
+            }
+        }
+    }
+    Ok(triangles)
+}
+
+const IDENTITY: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+/// Product of two column-major 4x4 matrices.
+fn multiply(a: &[[f32; 4]; 4], b: &[[f32; 4]; 4]) -> [[f32; 4]; 4] {
+    let mut out = [[0.0; 4]; 4];
+    for (col, out_col) in out.iter_mut().enumerate() {
+        for (row, value) in out_col.iter_mut().enumerate() {
+            *value = (0..4).map(|k| a[k][row] * b[col][k]).sum();
+        }
+    }
+    out
+}
+
+fn transform_point(m: &[[f32; 4]; 4], p: [f32; 3]) -> [f32; 3] {
+    let mut out = [0.0; 3];
+    for (row, value) in out.iter_mut().enumerate() {
+        *value = m[0][row].mul_add(
+            p[0],
+            m[1][row].mul_add(p[1], m[2][row].mul_add(p[2], m[3][row])),
+        );
+    }
+    out
+}
+
+/// Flat-shaded orthographic render on a transparent background.
+fn render_triangles(triangles: &[Triangle], size: u32) -> RgbaImage {
+    let (sin_yaw, cos_yaw) = VIEW_YAW.sin_cos();
+    let (sin_pitch, cos_pitch) = VIEW_PITCH.sin_cos();
+    // View space: x right, y up, z towards the camera
+    let view = |p: [f32; 3]| {
+        let x = p[0].mul_add(cos_yaw, -p[2] * sin_yaw);
+        let z = p[0].mul_add(sin_yaw, p[2] * cos_yaw);
+        [
+            x,
+            p[1].mul_add(cos_pitch, -z * sin_pitch),
+            p[1].mul_add(sin_pitch, z * cos_pitch),
+        ]
+    };
+    let projected: Vec<Triangle> = triangles.iter().map(|t| t.map(view)).collect();
+
+    let mut min = [f32::INFINITY; 2];
+    let mut max = [f32::NEG_INFINITY; 2];
+    for point in projected.iter().flatten() {
+        for axis in 0..2 {
+            min[axis] = min[axis].min(point[axis]);
+            max[axis] = max[axis].max(point[axis]);
+        }
+    }
+    let extent = (max[0] - min[0]).max(max[1] - min[1]).max(f32::EPSILON);
+    let size_f = size as f32;
+    let scale = size_f * 2.0f32.mul_add(-VIEW_MARGIN, 1.0) / extent;
+    let center = [f32::midpoint(min[0], max[0]), f32::midpoint(min[1], max[1])];
+    let to_screen = |p: [f32; 3]| {
+        [
+            (p[0] - center[0]).mul_add(scale, size_f / 2.0),
+            (center[1] - p[1]).mul_add(scale, size_f / 2.0),
+            p[2],
+        ]
+    };
+
+    let light_length = dot(LIGHT_DIRECTION, LIGHT_DIRECTION).sqrt();
+    let light = LIGHT_DIRECTION.map(|v| v / light_length);
+
+    let mut image = RgbaImage::new(size, size);
+    let mut depth = vec![f32::NEG_INFINITY; (size * size) as usize];
+    for triangle in &projected {
+        let normal = cross(sub(triangle[1], triangle[0]), sub(triangle[2], triangle[0]));
+        let length = dot(normal, normal).sqrt();
+        if length <= f32::EPSILON {
+            continue;
+        }
+        // Winding isn't reliable across formats, so light both faces
+        let facing = dot(normal, light).abs() / length;
+        let shade = 0.7f32.mul_add(facing, 0.3);
+        let color = Rgba([
+            (MODEL_COLOR[0] * shade) as u8,
+            (MODEL_COLOR[1] * shade) as u8,
+            (MODEL_COLOR[2] * shade) as u8,
+            255,
+        ]);
+
+        let [v0, v1, v2] = triangle.map(to_screen);
+        let area = edge(v0, v1, v2);
+        if area.abs() <= f32::EPSILON {
+            continue;
+        }
+        let x_start = v0[0].min(v1[0]).min(v2[0]).floor().max(0.0) as u32;
+        let x_end = (v0[0].max(v1[0]).max(v2[0]).ceil().max(0.0) as u32).min(size);
+        let y_start = v0[1].min(v1[1]).min(v2[1]).floor().max(0.0) as u32;
+        let y_end = (v0[1].max(v1[1]).max(v2[1]).ceil().max(0.0) as u32).min(size);
+        for row in y_start..y_end {
+            for col in x_start..x_end {
+                let pixel = [col as f32 + 0.5, row as f32 + 0.5, 0.0];
+                let weights = [
+                    edge(v1, v2, pixel) / area,
+                    edge(v2, v0, pixel) / area,
+                    edge(v0, v1, pixel) / area,
+                ];
+                if weights.iter().any(|&weight| weight < 0.0) {
+                    continue;
+                }
+                let depths = [v0[2], v1[2], v2[2]];
+                let z = dot(weights, depths);
+                let slot = &mut depth[(row * size + col) as usize];
+                if z > *slot {
+                    *slot = z;
+                    image.put_pixel(col, row, color);
+                }
+            }
+        }
+    }
+    image
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0].mul_add(b[0], a[1].mul_add(b[1], a[2] * b[2]))
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1].mul_add(b[2], -a[2] * b[1]),
+        a[2].mul_add(b[0], -a[0] * b[2]),
+        a[0].mul_add(b[1], -a[1] * b[0]),
+    ]
+}
+
+/// Twice the signed area of the screen-space triangle `a`, `b`, `p`.
+fn edge(a: [f32; 3], b: [f32; 3], p: [f32; 3]) -> f32 {
+    (b[0] - a[0]).mul_add(p[1] - a[1], -(b[1] - a[1]) * (p[0] - a[0]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const CUBE_OBJ: &str = "v -1 -1 -1\nv 1 -1 -1\nv 1 1 -1\nv -1 1 -1\n\
+                            v -1 -1 1\nv 1 -1 1\nv 1 1 1\nv -1 1 1\n\
+                            f 1 2 3 4\nf 5 6 7 8\nf 1 2 6 5\nf 2 3 7 6\nf 3 4 8 7\nf 4 1 5 8\n";
+
+    #[test]
+    fn renders_and_caches_previews() {
+        let temp_dir = tempdir().unwrap();
+        let texture = temp_dir.path().join("wall.png");
+        RgbaImage::from_pixel(64, 32, Rgba([200, 10, 10, 255]))
+            .save(&texture)
+            .unwrap();
+        let model = temp_dir.path().join("cube.obj");
+        fs::write(&model, CUBE_OBJ).unwrap();
+        let cache_dir = temp_dir.path().join("thumbnails");
+
+        let preview = cached_preview(1, &texture, TEXTURE, "abc", &cache_dir, 16).unwrap();
+        assert_eq!((preview.width, preview.height), (16, 8));
+        assert!(!preview.cached);
+        assert!(
+            cached_preview(1, &texture, TEXTURE, "abc", &cache_dir, 16)
+                .unwrap()
+                .cached
+        );
+
+        let image = render_preview(&model, MODEL, 32).unwrap();
+        assert_eq!(image.get_pixel(16, 16)[3], 255);
+        assert_eq!(image.get_pixel(0, 0)[3], 0);
+        // Faces at different angles get different shades
+        assert_ne!(image.get_pixel(16, 8), image.get_pixel(16, 24));
+
+        assert!(render_preview(&model, "Audio", 32).is_err());
+        assert_eq!(obj_triangles(CUBE_OBJ).len(), 12);
+        assert_eq!(
+            obj_triangles("v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3 -2 -1\n").len(),
+            1
+        );
+    }
+}
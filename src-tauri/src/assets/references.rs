@@ -0,0 +1,223 @@
+use super::database::AssetDatabase;
+use super::paths::{AssetPathResolver, AssetRef};
+use crate::LevelData;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// How many replacement candidates to offer per broken reference.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Which object field holds a reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceField {
+    Mesh,
+    Material,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceProblem {
+    /// Nothing with this path or name exists
+    Missing,
+    /// The file is gone, but a file with the same name exists elsewhere
+    Moved,
+    /// `asset:<id>` points at a row that no longer exists
+    UnknownAssetId,
+}
+
+/// A broken reference and every object using it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetReferenceIssue {
+    pub reference: String,
+    pub field: ReferenceField,
+    pub problem: ReferenceProblem,
+    pub object_ids: Vec<String>,
+    /// Closest existing assets, best match first
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetReferenceReport {
+    /// Distinct references checked
+    pub checked: usize,
+    pub issues: Vec<AssetReferenceIssue>,
+}
+
+/// Check every `mesh`/`material` reference in a level against the database and disk.
+pub fn check_level_references(
+    level: &LevelData,
+    resolver: &AssetPathResolver,
+    database: Option<&AssetDatabase>,
+) -> Result<AssetReferenceReport, String> {
+    let mut references: BTreeMap<(ReferenceField, String), Vec<String>> = BTreeMap::new();
+    for obj in &level.objects {
+        for (field, reference) in [
+            (ReferenceField::Mesh, &obj.mesh),
+            (ReferenceField::Material, &obj.material),
+        ] {
+            if let Some(reference) = reference {
+                references
+                    .entry((field, reference.clone()))
+                    .or_default()
+                    .push(obj.id.clone());
+            }
+        }
+    }
+
+    // Candidate replacements: everything indexed, as project-relative references
+    let known_files: Vec<String> = match database {
+        Some(database) => database
+            .get_file_index()
+            .map_err(|e| format!("Failed to read asset index: {}", e))?
+            .into_keys()
+            .map(|path| resolver.relative_reference(&path, None).unwrap_or(path))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut issues = Vec::new();
+    for ((field, reference), object_ids) in &references {
+        let problem = match AssetRef::parse(reference) {
+            AssetRef::Id(_) => match resolver.resolve(reference, database) {
+                Ok(path) if path.exists() => None,
+                Ok(_) => Some(ReferenceProblem::Missing),
+                Err(_) => Some(ReferenceProblem::UnknownAssetId),
+            },
+            _ => match resolver.resolve(reference, database) {
+                Ok(path) if path.exists() => None,
+                _ => Some(ReferenceProblem::Missing),
+            },
+        };
+        let Some(mut problem) = problem else {
+            continue;
+        };
+
+        let wanted = file_name(reference);
+        let mut candidates: Vec<(usize, &String)> = known_files
+            .iter()
+            .map(|candidate| (edit_distance(&wanted, &file_name(candidate)), candidate))
+            .collect();
+        candidates.sort();
+
+        if problem == ReferenceProblem::Missing && candidates.first().is_some_and(|(d, _)| *d == 0)
+        {
+            problem = ReferenceProblem::Moved;
+        }
+
+        issues.push(AssetReferenceIssue {
+            reference: reference.clone(),
+            field: *field,
+            problem,
+            object_ids: object_ids.clone(),
+            suggestions: candidates
+                .into_iter()
+                .take(MAX_SUGGESTIONS)
+                .map(|(_, candidate)| candidate.clone())
+                .collect(),
+        });
+    }
+
+    Ok(AssetReferenceReport {
+        checked: references.len(),
+        issues,
+    })
+}
+
+/// Replacements for broken references that exactly one indexed asset, with the same
+/// file name, could stand in for. Anything less certain is left for the user.
+pub fn repairs(report: &AssetReferenceReport) -> HashMap<String, String> {
+    report
+        .issues
+        .iter()
+        .filter(|issue| issue.problem == ReferenceProblem::Moved)
+        .filter_map(|issue| {
+            let wanted = file_name(&issue.reference);
+            let mut same_name = issue
+                .suggestions
+                .iter()
+                .filter(|candidate| file_name(candidate) == wanted);
+            match (same_name.next(), same_name.next()) {
+                (Some(candidate), None) => Some((issue.reference.clone(), candidate.clone())),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn file_name(reference: &str) -> String {
+    Path::new(reference)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_else(|| reference.to_lowercase())
+}
+
+/// Levenshtein distance between two strings, by character.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance_counts_single_edits() {
+        assert_eq!(edit_distance("wall.png", "wall.png"), 0);
+        assert_eq!(edit_distance("wall.png", "wal.png"), 1);
+        assert_eq!(edit_distance("floor", "flour"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn repairs_only_unambiguous_moves() {
+        let issue = |reference: &str, problem, suggestions: &[&str]| AssetReferenceIssue {
+            reference: reference.to_string(),
+            field: ReferenceField::Mesh,
+            problem,
+            object_ids: vec!["crate".to_string()],
+            suggestions: suggestions.iter().map(ToString::to_string).collect(),
+        };
+        let report = AssetReferenceReport {
+            checked: 3,
+            issues: vec![
+                issue(
+                    "/old/machine/Models/crate.glb",
+                    ReferenceProblem::Moved,
+                    &["Models/Props/crate.glb", "Models/crates.glb"],
+                ),
+                issue(
+                    "Models/barrel.glb",
+                    ReferenceProblem::Moved,
+                    &["Dungeon/barrel.glb", "Tavern/barrel.glb"],
+                ),
+                issue(
+                    "Models/lamp.glb",
+                    ReferenceProblem::Missing,
+                    &["Models/lamps.glb"],
+                ),
+            ],
+        };
+
+        let repairs = repairs(&report);
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(
+            repairs["/old/machine/Models/crate.glb"],
+            "Models/Props/crate.glb"
+        );
+    }
+}
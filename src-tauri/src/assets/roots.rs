@@ -0,0 +1,164 @@
+// Asset root directories registered per project and globally
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const ROOTS_FILE: &str = "asset_roots.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetRootScope {
+    /// The project's `Assets` folder, found automatically
+    Default,
+    /// Registered for the current project, stored under its `.morgan` directory
+    Project,
+    /// Registered for every project, stored in the app data directory
+    Global,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetRoot {
+    pub path: PathBuf,
+    pub scope: AssetRootScope,
+    /// Whether the directory currently exists; missing roots are skipped when scanning
+    pub exists: bool,
+}
+
+fn roots_file(app_handle: &tauri::AppHandle, scope: AssetRootScope) -> Result<PathBuf, String> {
+    match scope {
+        AssetRootScope::Default => Err("The default asset root cannot be changed".to_string()),
+        AssetRootScope::Project => super::project_directory()
+            .map(|project| project.join(".morgan").join(ROOTS_FILE))
+            .ok_or_else(|| "Project directory not found".to_string()),
+        AssetRootScope::Global => {
+            super::morgana_directory(app_handle).map(|dir| dir.join(ROOTS_FILE))
+        }
+    }
+}
+
+fn load_roots(path: &Path) -> Result<Vec<PathBuf>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read asset roots {:?}: {}", path, e))?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse asset roots {:?}: {}", path, e))
+}
+
+fn save_roots(path: &Path, roots: &[PathBuf]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+    let json = serde_json::to_string_pretty(roots)
+        .map_err(|e| format!("Failed to serialize asset roots: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write asset roots {:?}: {}", path, e))
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Every configured asset root: the default one first, then project and global roots.
+///
+/// A directory registered in more than one place is listed once, under its first scope.
+pub fn asset_roots(app_handle: &tauri::AppHandle) -> Result<Vec<AssetRoot>, String> {
+    let mut roots: Vec<AssetRoot> = Vec::new();
+    let mut push = |path: PathBuf, scope: AssetRootScope| {
+        let normalized = normalize(&path);
+        if !roots.iter().any(|root| normalize(&root.path) == normalized) {
+            roots.push(AssetRoot {
+                exists: path.is_dir(),
+                path,
+                scope,
+            });
+        }
+    };
+
+    if let Some(assets_dir) = super::find_assets_directory() {
+        push(assets_dir, AssetRootScope::Default);
+    }
+    for scope in [AssetRootScope::Project, AssetRootScope::Global] {
+        // Project roots are unavailable until a project directory exists
+        let Ok(file) = roots_file(app_handle, scope) else {
+            continue;
+        };
+        for path in load_roots(&file)? {
+            push(path, scope);
+        }
+    }
+
+    Ok(roots)
+}
+
+/// Existing root directories to scan and watch.
+pub fn scan_roots(app_handle: &tauri::AppHandle) -> Result<Vec<PathBuf>, String> {
+    let roots: Vec<PathBuf> = asset_roots(app_handle)?
+        .into_iter()
+        .filter(|root| root.exists)
+        .map(|root| root.path)
+        .collect();
+
+    if roots.is_empty() {
+        Err(
+            "No asset directories found. Create an 'Assets' folder or add an asset root"
+                .to_string(),
+        )
+    } else {
+        Ok(roots)
+    }
+}
+
+pub fn add_root(
+    app_handle: &tauri::AppHandle,
+    path: &Path,
+    scope: AssetRootScope,
+) -> Result<(), String> {
+    if !path.is_dir() {
+        return Err(format!("Not a directory: {:?}", path));
+    }
+    let file = roots_file(app_handle, scope)?;
+    let mut roots = load_roots(&file)?;
+
+    let normalized = normalize(path);
+    if roots.iter().any(|root| normalize(root) == normalized) {
+        return Err(format!("Asset root already registered: {:?}", path));
+    }
+    roots.push(normalized);
+    save_roots(&file, &roots)
+}
+
+/// Register `path` unless it's already a root in any scope. Returns whether it was added.
+pub fn ensure_root(
+    app_handle: &tauri::AppHandle,
+    path: &Path,
+    scope: AssetRootScope,
+) -> Result<bool, String> {
+    let normalized = normalize(path);
+    if asset_roots(app_handle)?
+        .iter()
+        .any(|root| normalize(&root.path) == normalized)
+    {
+        return Ok(false);
+    }
+    add_root(app_handle, path, scope)?;
+    Ok(true)
+}
+
+pub fn remove_root(
+    app_handle: &tauri::AppHandle,
+    path: &Path,
+    scope: AssetRootScope,
+) -> Result<(), String> {
+    let file = roots_file(app_handle, scope)?;
+    let mut roots = load_roots(&file)?;
+
+    let normalized = normalize(path);
+    let count = roots.len();
+    roots.retain(|root| normalize(root) != normalized);
+    if roots.len() == count {
+        return Err(format!("Asset root not registered: {:?}", path));
+    }
+    save_roots(&file, &roots)
+}
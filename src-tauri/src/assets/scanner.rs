@@ -1,9 +1,12 @@
-use super::database::AssetDatabase;
+use super::database::{file_mtime_millis, AssetDatabase, ChecksumMode, IndexedFile, PreparedAsset};
+use super::file_types::FileTypes;
 use log::{info, warn};
-
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanProgress {
@@ -14,13 +17,84 @@ pub struct ScanProgress {
     pub errors: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ScanResult {
     pub total_assets: usize,
     pub collections_found: Vec<String>,
     pub assets_by_type: std::collections::HashMap<String, usize>,
     pub scan_duration_ms: u64,
     pub errors: Vec<String>,
+    /// The scan was stopped before every file was processed
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+impl ScanResult {
+    /// Fold another root's scan into this one.
+    pub fn merge(&mut self, other: ScanResult) {
+        self.total_assets += other.total_assets;
+        for collection in other.collections_found {
+            if !self.collections_found.contains(&collection) {
+                self.collections_found.push(collection);
+            }
+        }
+        for (asset_type, count) in other.assets_by_type {
+            *self.assets_by_type.entry(asset_type).or_insert(0) += count;
+        }
+        self.scan_duration_ms += other.scan_duration_ms;
+        self.errors.extend(other.errors);
+        self.cancelled |= other.cancelled;
+    }
+}
+
+/// Outcome of an incremental rescan.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RescanSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+    pub scan_duration_ms: u64,
+    pub errors: Vec<String>,
+    /// The rescan was stopped early, so deleted files were not pruned
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+impl RescanSummary {
+    /// Fold another root's rescan into this one.
+    pub fn merge(&mut self, other: RescanSummary) {
+        self.added += other.added;
+        self.updated += other.updated;
+        self.unchanged += other.unchanged;
+        self.removed += other.removed;
+        self.scan_duration_ms += other.scan_duration_ms;
+        self.errors.extend(other.errors);
+        self.cancelled |= other.cancelled;
+    }
+}
+
+/// What happened to a single file when it was synced with the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetChange {
+    Added,
+    Updated,
+    Removed,
+}
+
+/// Files prepared on the thread pool and then written in one transaction at a time.
+/// Bounds how much extracted metadata is held in memory during a scan.
+const SCAN_BATCH_SIZE: usize = 256;
+
+type ProgressCallback = Box<dyn Fn(ScanProgress) + Send + Sync>;
+
+/// A discovered file after syncing it with the database.
+struct ProcessedFile {
+    path: PathBuf,
+    collection: String,
+    /// `None` when the indexed record was already up to date
+    result: Result<Option<AssetChange>, String>,
 }
 
 pub struct AssetScanner {
@@ -37,7 +111,9 @@ impl AssetScanner {
     pub fn scan_directory<P: AsRef<Path>>(
         &mut self,
         assets_dir: P,
-        progress_callback: Option<Box<dyn Fn(ScanProgress) + Send + Sync>>,
+        checksum_mode: ChecksumMode,
+        progress_callback: Option<ProgressCallback>,
+        cancel: Option<&AtomicBool>,
     ) -> Result<ScanResult, Box<dyn std::error::Error>> {
         let start_time = std::time::Instant::now();
         let assets_path = assets_dir.as_ref();
@@ -49,69 +125,43 @@ impl AssetScanner {
         }
 
         // Discover all asset files first
-        let discovered_assets = self.discover_assets(assets_path)?;
+        let discovered_assets = discover_assets(assets_path, self.database.file_types())?;
         let total_assets = discovered_assets.len();
 
         info!("Discovered {} potential assets", total_assets);
 
+        let mut index = self.database.get_file_index()?;
+        let processed = self.process_files(
+            assets_path,
+            discovered_assets,
+            &mut index,
+            checksum_mode,
+            progress_callback.as_ref(),
+            cancel,
+        )?;
+
         let mut scan_result = ScanResult {
             total_assets,
             collections_found: Vec::new(),
             assets_by_type: std::collections::HashMap::new(),
             scan_duration_ms: 0,
             errors: Vec::new(),
+            cancelled: processed.len() < total_assets,
         };
 
-        // Group assets by collection (based on top-level directory)
-        let mut assets_by_collection: std::collections::HashMap<String, Vec<PathBuf>> =
-            std::collections::HashMap::new();
-
-        for asset_path in discovered_assets {
-            let collection = self.determine_collection(&asset_path, assets_path);
-            assets_by_collection
-                .entry(collection)
-                .or_default()
-                .push(asset_path);
-        }
-
-        let mut processed = 0;
-
-        // Process each collection
-        for (collection_name, asset_paths) in assets_by_collection {
-            info!("Processing collection: {}", collection_name);
-            scan_result.collections_found.push(collection_name.clone());
-
-            // Process assets in this collection
-            for asset_path in asset_paths {
-                if let Some(ref callback) = progress_callback {
-                    let progress = ScanProgress {
-                        current_file: asset_path
-                            .file_name()
-                            .unwrap_or_default()
-                            .to_string_lossy()
-                            .to_string(),
-                        processed,
-                        total: total_assets,
-                        current_collection: collection_name.clone(),
-                        errors: scan_result.errors.clone(),
-                    };
-                    callback(progress);
+        for file in processed {
+            if !scan_result.collections_found.contains(&file.collection) {
+                scan_result.collections_found.push(file.collection);
+            }
+            match file.result {
+                Ok(_) => {
+                    let asset_type = self.database.determine_asset_type(&file.path);
+                    *scan_result.assets_by_type.entry(asset_type).or_insert(0) += 1;
                 }
-
-                match self.process_asset(&asset_path, &collection_name) {
-                    Ok(_) => {
-                        let asset_type = self.database.determine_asset_type(&asset_path);
-                        *scan_result.assets_by_type.entry(asset_type).or_insert(0) += 1;
-                    }
-                    Err(e) => {
-                        let error_msg =
-                            format!("Failed to process {}: {}", asset_path.display(), e);
-                        warn!("{}", error_msg);
-                        scan_result.errors.push(error_msg);
-                    }
+                Err(error_msg) => {
+                    warn!("{}", error_msg);
+                    scan_result.errors.push(error_msg);
                 }
-
-                processed += 1;
             }
         }
 
@@ -120,111 +170,240 @@ impl AssetScanner {
         info!(
             "Asset scan completed in {}ms. Processed {} assets with {} errors",
             scan_result.scan_duration_ms,
-            processed,
+            total_assets,
             scan_result.errors.len()
         );
 
         Ok(scan_result)
     }
 
-    /// Discover all asset files in a directory tree
-    fn discover_assets<P: AsRef<Path>>(
-        &self,
-        root_path: P,
-    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
-        let mut assets = Vec::new();
-        self.walk_directory(root_path.as_ref(), &mut assets)?;
-        Ok(assets)
-    }
+    /// Incrementally rescan a directory against what is already indexed.
+    ///
+    /// Files whose size and modification time are unchanged are skipped without
+    /// hashing, and records for files under `assets_dir` that no longer exist
+    /// are pruned.
+    pub fn rescan_directory<P: AsRef<Path>>(
+        &mut self,
+        assets_dir: P,
+        checksum_mode: ChecksumMode,
+        progress_callback: Option<ProgressCallback>,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<RescanSummary, Box<dyn std::error::Error>> {
+        let start_time = std::time::Instant::now();
+        let assets_path = assets_dir.as_ref();
 
-    /// Recursively walk directory and collect asset files
-    fn walk_directory(
-        &self,
-        dir: &Path,
-        assets: &mut Vec<PathBuf>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        if !dir.is_dir() {
-            return Ok(());
-        }
+        info!(
+            "Starting incremental rescan of directory: {:?}",
+            assets_path
+        );
 
-        let entries = fs::read_dir(dir)?;
+        if !assets_path.exists() {
+            return Err(format!("Assets directory does not exist: {:?}", assets_path).into());
+        }
 
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
+        let discovered_assets = discover_assets(assets_path, self.database.file_types())?;
+        let total_assets = discovered_assets.len();
+        // Whatever is left in the index afterwards was not found on disk
+        let mut index = self.database.get_file_index()?;
+        let processed = self.process_files(
+            assets_path,
+            discovered_assets,
+            &mut index,
+            checksum_mode,
+            progress_callback.as_ref(),
+            cancel,
+        )?;
+
+        let mut summary = RescanSummary {
+            cancelled: processed.len() < total_assets,
+            ..RescanSummary::default()
+        };
+        for file in processed {
+            match file.result {
+                Ok(Some(AssetChange::Added)) => summary.added += 1,
+                Ok(Some(AssetChange::Updated)) => summary.updated += 1,
+                Ok(_) => summary.unchanged += 1,
+                Err(error_msg) => {
+                    warn!("{}", error_msg);
+                    summary.errors.push(error_msg);
+                }
+            }
+        }
 
-            if path.is_dir() {
-                // Skip hidden directories and known artifact directories
-                if let Some(dir_name) = path.file_name().and_then(|n| n.to_str()) {
-                    if dir_name.starts_with('.')
-                        || dir_name == "node_modules"
-                        || dir_name == "target"
-                    {
-                        continue;
-                    }
+        // Prune records for files that disappeared from this directory. A cancelled scan
+        // leaves unvisited files in the index, so nothing can be pruned.
+        for (file_path, indexed) in index.into_iter().filter(|_| !summary.cancelled) {
+            let path = Path::new(&file_path);
+            if path.starts_with(assets_path) && !path.exists() {
+                match self
+                    .database
+                    .remove_asset(indexed.asset_id, &indexed.collection)
+                {
+                    Ok(()) => summary.removed += 1,
+                    Err(e) => summary
+                        .errors
+                        .push(format!("Failed to remove {}: {}", file_path, e)),
                 }
-                self.walk_directory(&path, assets)?;
-            } else if self.is_asset_file(&path) {
-                assets.push(path);
             }
         }
 
-        Ok(())
+        summary.scan_duration_ms = start_time.elapsed().as_millis() as u64;
+
+        info!(
+            "Incremental rescan completed in {}ms: {} added, {} updated, {} removed, {} unchanged",
+            summary.scan_duration_ms,
+            summary.added,
+            summary.updated,
+            summary.removed,
+            summary.unchanged
+        );
+
+        Ok(summary)
     }
 
-    /// Determine if a file is an asset we should track
-    fn is_asset_file(&self, path: &Path) -> bool {
-        // Skip hidden files and known non-assets
-        if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-            if file_name.starts_with('.')
-                || file_name.ends_with(".meta")
-                || file_name.ends_with(".import")
-                || file_name == "Thumbs.db"
-                || file_name == ".DS_Store"
-            {
-                return false;
+    /// Sync discovered files with the database in batches.
+    ///
+    /// Indexed files with the same size and modification time are settled without
+    /// reading them. The rest are hashed in `checksum_mode` and read in parallel, then
+    /// each batch is written in one transaction. Files found in `index` are removed from it.
+    ///
+    /// Setting `cancel` stops the scan before the next batch, leaving the database
+    /// consistent; only the files processed so far are returned.
+    fn process_files(
+        &mut self,
+        assets_root: &Path,
+        files: Vec<PathBuf>,
+        index: &mut HashMap<String, IndexedFile>,
+        checksum_mode: ChecksumMode,
+        progress_callback: Option<&ProgressCallback>,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<Vec<ProcessedFile>, Box<dyn std::error::Error>> {
+        let total = files.len();
+        let file_types = self.database.file_types().clone();
+        let mut processed: Vec<ProcessedFile> = Vec::with_capacity(total);
+        let mut errors = Vec::new();
+
+        for batch in files.chunks(SCAN_BATCH_SIZE) {
+            if cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+                info!(
+                    "Scan cancelled after {} of {} files",
+                    processed.len(),
+                    total
+                );
+                break;
             }
-        }
+            let indexed: Vec<Option<IndexedFile>> = batch
+                .iter()
+                .map(|path| index.remove(path.to_string_lossy().as_ref()))
+                .collect();
+            let prepared: Vec<Option<Result<PreparedAsset, String>>> = batch
+                .par_iter()
+                .zip(indexed.par_iter())
+                .map(|(path, indexed)| match indexed {
+                    Some(indexed) if is_unchanged(path, indexed) => None,
+                    _ => Some(PreparedAsset::read(
+                        path,
+                        &file_types,
+                        checksum_mode,
+                        indexed
+                            .as_ref()
+                            .map(|indexed| (indexed.checksum.as_str(), indexed.checksum_mode)),
+                    )),
+                })
+                .collect();
+
+            self.database.in_transaction(|database| {
+                for ((path, indexed), prepared) in batch.iter().zip(indexed).zip(prepared) {
+                    let collection = collection_for(path, assets_root);
+                    if let Some(callback) = progress_callback {
+                        callback(ScanProgress {
+                            current_file: path
+                                .file_name()
+                                .unwrap_or_default()
+                                .to_string_lossy()
+                                .to_string(),
+                            processed: processed.len(),
+                            total,
+                            current_collection: collection.clone(),
+                            errors: errors.clone(),
+                        });
+                    }
 
-        match path.extension().and_then(|ext| ext.to_str()) {
-            Some(
-                "fbx" | "FBX" | "png" | "PNG" | "jpg" | "JPG" | "jpeg" | "JPEG" | "wav" | "WAV"
-                | "mp3" | "MP3" | "ogg" | "OGG" | "mat" | "MAT",
-            ) => true,
-            _ => false,
+                    let result = match (prepared, indexed) {
+                        (None, _) => Ok(None),
+                        (Some(Err(e)), _) => Err(e),
+                        (Some(Ok(asset)), None) => database
+                            .insert_prepared(&asset, &collection, assets_root)
+                            .map(|_| Some(AssetChange::Added))
+                            .map_err(|e| e.to_string()),
+                        (Some(Ok(asset)), Some(indexed)) => database
+                            .update_prepared(indexed.asset_id, &asset)
+                            .map(|changed| changed.then_some(AssetChange::Updated))
+                            .map_err(|e| e.to_string()),
+                    }
+                    .map_err(|e| format!("Failed to process {}: {}", path.display(), e));
+                    if let Err(error_msg) = &result {
+                        errors.push(error_msg.clone());
+                    }
+
+                    processed.push(ProcessedFile {
+                        path: path.clone(),
+                        collection,
+                        result,
+                    });
+                }
+            })?;
         }
-    }
 
-    /// Determine collection name based on file path
-    fn determine_collection(&self, asset_path: &Path, assets_root: &Path) -> String {
-        if let Ok(relative_path) = asset_path.strip_prefix(assets_root) {
-            if let Some(first_component) = relative_path.components().next() {
-                return first_component.as_os_str().to_string_lossy().to_string();
-            }
+        // Counts are refreshed once per scan rather than after every insert
+        let collections: BTreeSet<&str> = processed
+            .iter()
+            .map(|file| file.collection.as_str())
+            .collect();
+        for collection in collections {
+            self.database.update_collection_count(collection)?;
         }
-        "Unknown".to_string()
+        Ok(processed)
     }
 
-    /// Process a single asset file
-    fn process_asset(
+    /// Bring the database in line with one file's current state on disk.
+    ///
+    /// Returns `None` when nothing needed to change.
+    pub fn sync_file(
         &mut self,
         asset_path: &Path,
-        collection: &str,
-    ) -> Result<i64, Box<dyn std::error::Error>> {
-        // Check if asset already exists (by file path)
-        if let Ok(existing_assets) = self.database.search_assets("", None, None) {
-            let file_path_str = asset_path.to_string_lossy().to_string();
-            if existing_assets
-                .iter()
-                .any(|a| a.asset.file_path == file_path_str)
-            {
-                // Asset already exists, could check if it needs updating based on modification time
-                return Ok(0); // Return 0 to indicate no new asset was added
+        assets_root: &Path,
+    ) -> Result<Option<AssetChange>, Box<dyn std::error::Error>> {
+        if !self.is_asset_file(asset_path) {
+            return Ok(None);
+        }
+
+        let file_path_str = asset_path.to_string_lossy().to_string();
+        let indexed = self.database.get_indexed_file(&file_path_str)?;
+
+        match (indexed, asset_path.is_file()) {
+            (None, true) => {
+                let collection = collection_for(asset_path, assets_root);
+                self.database
+                    .insert_asset(asset_path, &collection, assets_root)?;
+                Ok(Some(AssetChange::Added))
             }
+            (Some(indexed), true) => {
+                let changed = self.database.update_asset(indexed.asset_id, asset_path)?;
+                Ok(changed.then_some(AssetChange::Updated))
+            }
+            (Some(indexed), false) => {
+                self.database
+                    .remove_asset(indexed.asset_id, &indexed.collection)?;
+                Ok(Some(AssetChange::Removed))
+            }
+            (None, false) => Ok(None),
         }
+    }
 
-        // Insert new asset
-        self.database.insert_asset(asset_path, collection)
+    /// Determine if a file is an asset we should track
+    fn is_asset_file(&self, path: &Path) -> bool {
+        is_asset_path(path, self.database.file_types())
     }
 
     /// Get database reference for direct operations
@@ -233,7 +412,6 @@ impl AssetScanner {
     }
 
     /// Get mutable database reference
-    #[allow(dead_code)]
     pub fn database_mut(&mut self) -> &mut AssetDatabase {
         &mut self.database
     }
@@ -244,7 +422,7 @@ impl AssetScanner {
         &mut self,
         assets_dir: P,
         collection_name: &str,
-        progress_callback: Option<Box<dyn Fn(ScanProgress) + Send + Sync>>,
+        progress_callback: Option<ProgressCallback>,
     ) -> Result<ScanResult, Box<dyn std::error::Error>> {
         let collection_path = assets_dir.as_ref().join(collection_name);
 
@@ -262,13 +440,92 @@ impl AssetScanner {
         // 2. Update assets that have changed
         // 3. Add new assets
 
-        self.scan_directory(collection_path, progress_callback)
+        self.scan_directory(collection_path, ChecksumMode::Full, progress_callback, None)
+    }
+}
+
+/// Every asset file under `dir`, walking subdirectories in parallel.
+fn discover_assets(dir: &Path, file_types: &FileTypes) -> std::io::Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<PathBuf>>>()?;
+    let (dirs, files): (Vec<PathBuf>, Vec<PathBuf>) =
+        entries.into_iter().partition(|path| path.is_dir());
+
+    let mut assets: Vec<PathBuf> = files
+        .into_iter()
+        .filter(|path| is_asset_path(path, file_types))
+        .collect();
+    let nested = dirs
+        .par_iter()
+        .filter(|path| {
+            // Skip hidden directories and known artifact directories
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_none_or(|dir_name| {
+                    !(dir_name.starts_with('.')
+                        || dir_name == "node_modules"
+                        || dir_name == "target")
+                })
+        })
+        .map(|path| discover_assets(path, file_types))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    assets.extend(nested.into_iter().flatten());
+    Ok(assets)
+}
+
+fn is_asset_path(path: &Path, file_types: &FileTypes) -> bool {
+    // Skip hidden files and known non-assets
+    if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
+        if file_name.starts_with('.')
+            || file_name.ends_with(".meta")
+            || file_name.ends_with(".import")
+            || file_name == "Thumbs.db"
+            || file_name == ".DS_Store"
+        {
+            return false;
+        }
     }
 
+    file_types.asset_type(path).is_some()
+}
+
+/// Collection name: the first directory under the asset root.
+fn collection_for(asset_path: &Path, assets_root: &Path) -> String {
+    if let Ok(relative_path) = asset_path.strip_prefix(assets_root) {
+        if let Some(first_component) = relative_path.components().next() {
+            return first_component.as_os_str().to_string_lossy().to_string();
+        }
+    }
+    "Unknown".to_string()
+}
+
+/// Whether an indexed file's size and modification time still match, so it needn't be read.
+fn is_unchanged(path: &Path, indexed: &IndexedFile) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| {
+        metadata.len() as i64 == indexed.file_size
+            && file_mtime_millis(&metadata) == indexed.file_mtime
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseStats {
+    pub total_assets: usize,
+    pub total_collections: usize,
+    pub assets_by_type: std::collections::HashMap<String, usize>,
+    pub total_size_bytes: i64,
+    pub collections: std::collections::HashMap<String, usize>,
+}
+
+impl DatabaseStats {
     /// Get database statistics
-    pub fn get_stats(&self) -> Result<DatabaseStats, Box<dyn std::error::Error>> {
-        let collections = self.database.get_collections()?;
-        let all_assets = self.database.search_assets("", None, None)?;
+    pub fn collect(database: &AssetDatabase) -> Result<Self, Box<dyn std::error::Error>> {
+        let collections = database.get_collections()?;
+        let all_assets = database.search_assets("", None, None)?;
 
         let mut assets_by_type = std::collections::HashMap::new();
         let mut total_size = 0i64;
@@ -279,7 +536,7 @@ impl AssetScanner {
             total_size += asset_result.asset.file_size;
         }
 
-        Ok(DatabaseStats {
+        Ok(Self {
             total_assets: all_assets.len(),
             total_collections: collections.len(),
             assets_by_type,
@@ -292,15 +549,6 @@ impl AssetScanner {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DatabaseStats {
-    pub total_assets: usize,
-    pub total_collections: usize,
-    pub assets_by_type: std::collections::HashMap<String, usize>,
-    pub total_size_bytes: i64,
-    pub collections: std::collections::HashMap<String, usize>,
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -315,6 +563,208 @@ mod tests {
         assert!(scanner.is_ok());
     }
 
+    #[test]
+    fn test_rescan_detects_changes() {
+        let temp_dir = tempdir().unwrap();
+        let assets_dir = temp_dir.path().join("Assets");
+        let collection_dir = assets_dir.join("Kenney");
+        fs::create_dir_all(&collection_dir).unwrap();
+        fs::write(collection_dir.join("a.png"), b"first").unwrap();
+        fs::write(collection_dir.join("b.wav"), b"audio").unwrap();
+
+        let mut scanner = AssetScanner::new(&temp_dir.path().join("assets.db")).unwrap();
+        let summary = scanner
+            .rescan_directory(&assets_dir, ChecksumMode::Full, None, None)
+            .unwrap();
+        assert_eq!(summary.added, 2);
+
+        fs::write(collection_dir.join("a.png"), b"second version").unwrap();
+        fs::remove_file(collection_dir.join("b.wav")).unwrap();
+
+        let summary = scanner
+            .rescan_directory(&assets_dir, ChecksumMode::Full, None, None)
+            .unwrap();
+        assert_eq!(summary.added, 0);
+        assert_eq!(summary.updated, 1);
+        assert_eq!(summary.removed, 1);
+        assert!(summary.errors.is_empty());
+
+        let asset_id = scanner
+            .database()
+            .find_asset_by_location(&collection_dir.join("a.png"))
+            .unwrap()
+            .unwrap();
+        let history = scanner.database().get_asset_history(asset_id).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!((history[0].old_size, history[0].new_size), (5, 14));
+        assert_ne!(history[0].old_checksum, history[0].new_checksum);
+    }
+
+    #[test]
+    fn test_cancelled_rescan_keeps_index() {
+        let temp_dir = tempdir().unwrap();
+        let assets_dir = temp_dir.path().join("Assets");
+        let collection_dir = assets_dir.join("Kenney");
+        fs::create_dir_all(&collection_dir).unwrap();
+        fs::write(collection_dir.join("a.png"), b"first").unwrap();
+
+        let mut scanner = AssetScanner::new(&temp_dir.path().join("assets.db")).unwrap();
+        scanner
+            .rescan_directory(&assets_dir, ChecksumMode::Full, None, None)
+            .unwrap();
+        fs::remove_file(collection_dir.join("a.png")).unwrap();
+        fs::write(collection_dir.join("b.png"), b"second").unwrap();
+
+        // A cancelled rescan neither adds new files nor prunes deleted ones
+        let cancel = AtomicBool::new(true);
+        let summary = scanner
+            .rescan_directory(&assets_dir, ChecksumMode::Full, None, Some(&cancel))
+            .unwrap();
+        assert!(summary.cancelled);
+        assert_eq!((summary.added, summary.removed), (0, 0));
+        assert_eq!(
+            DatabaseStats::collect(scanner.database())
+                .unwrap()
+                .total_assets,
+            1
+        );
+    }
+
+    #[test]
+    fn test_reader_sees_scanned_assets() {
+        let temp_dir = tempdir().unwrap();
+        let assets_dir = temp_dir.path().join("Assets");
+        let collection_dir = assets_dir.join("Kenney");
+        fs::create_dir_all(&collection_dir).unwrap();
+        fs::write(collection_dir.join("a.png"), b"first").unwrap();
+
+        let db_path = temp_dir.path().join("assets.db");
+        let mut scanner = AssetScanner::new(&db_path).unwrap();
+        let mut reader = AssetDatabase::open_reader(&db_path).unwrap();
+        assert_eq!(DatabaseStats::collect(&reader).unwrap().total_assets, 0);
+
+        scanner
+            .rescan_directory(&assets_dir, ChecksumMode::Full, None, None)
+            .unwrap();
+        assert_eq!(DatabaseStats::collect(&reader).unwrap().total_assets, 1);
+        assert!(reader.remove_asset(1, "Kenney").is_err());
+    }
+
+    #[test]
+    fn test_materials_resolve_textures() {
+        let temp_dir = tempdir().unwrap();
+        let assets_dir = temp_dir.path().join("Assets");
+        let collection_dir = assets_dir.join("Kenney");
+        fs::create_dir_all(collection_dir.join("Materials")).unwrap();
+        fs::create_dir_all(collection_dir.join("Textures")).unwrap();
+        fs::write(collection_dir.join("Textures/wood.png"), b"png").unwrap();
+        fs::write(
+            collection_dir.join("Materials/crate.mtl"),
+            "newmtl Wood\nmap_Kd ../Textures/wood.png\nmap_Ks missing.png\n",
+        )
+        .unwrap();
+
+        let mut scanner = AssetScanner::new(&temp_dir.path().join("assets.db")).unwrap();
+        scanner
+            .rescan_directory(&assets_dir, ChecksumMode::Full, None, None)
+            .unwrap();
+        let database = scanner.database();
+        let id_of = |path: &str| {
+            database
+                .find_asset_by_location(&collection_dir.join(path))
+                .unwrap()
+                .unwrap()
+        };
+        let (texture_id, material_id) = (id_of("Textures/wood.png"), id_of("Materials/crate.mtl"));
+
+        let textures = database.get_material_textures(material_id).unwrap();
+        assert_eq!(textures.len(), 2);
+        assert_eq!(textures[0].asset_id, Some(texture_id));
+        assert_eq!(textures[1].asset_id, None);
+
+        let materials = database.find_materials_using_texture(texture_id).unwrap();
+        assert_eq!(materials.len(), 1);
+        assert_eq!(materials[0].asset.id, material_id);
+    }
+
+    #[test]
+    fn test_fingerprint_checksums() {
+        let temp_dir = tempdir().unwrap();
+        let assets_dir = temp_dir.path().join("Assets");
+        let collection_dir = assets_dir.join("Kenney");
+        fs::create_dir_all(&collection_dir).unwrap();
+        let mut model = vec![0u8; 3 * 1024 * 1024];
+        fs::write(collection_dir.join("intro.fbx"), &model).unwrap();
+        fs::write(collection_dir.join("a.png"), b"small").unwrap();
+
+        let mut scanner = AssetScanner::new(&temp_dir.path().join("assets.db")).unwrap();
+        scanner
+            .rescan_directory(&assets_dir, ChecksumMode::Fingerprint, None, None)
+            .unwrap();
+        let mode_of = |scanner: &AssetScanner, path: &str| {
+            let database = scanner.database();
+            let id = database
+                .find_asset_by_location(&collection_dir.join(path))
+                .unwrap()
+                .unwrap();
+            database
+                .get_asset_by_id(id)
+                .unwrap()
+                .unwrap()
+                .asset
+                .checksum_mode
+        };
+        assert_eq!(mode_of(&scanner, "intro.fbx"), ChecksumMode::Fingerprint);
+        assert_eq!(mode_of(&scanner, "a.png"), ChecksumMode::Full);
+
+        // Edits to the middle of a file are invisible to its fingerprint
+        model[1536 * 1024] = 1;
+        fs::write(collection_dir.join("intro.fbx"), &model).unwrap();
+        let summary = scanner
+            .rescan_directory(&assets_dir, ChecksumMode::Fingerprint, None, None)
+            .unwrap();
+        assert_eq!((summary.updated, summary.unchanged), (0, 2));
+    }
+
+    #[test]
+    fn test_lod_groups() {
+        let temp_dir = tempdir().unwrap();
+        let assets_dir = temp_dir.path().join("Assets");
+        let collection_dir = assets_dir.join("Kenney");
+        fs::create_dir_all(&collection_dir).unwrap();
+        for name in ["Tree_LOD1.obj", "Tree_LOD0.obj", "Rock.obj"] {
+            fs::write(collection_dir.join(name), b"v 0 0 0\n").unwrap();
+        }
+
+        let mut scanner = AssetScanner::new(&temp_dir.path().join("assets.db")).unwrap();
+        scanner
+            .rescan_directory(&assets_dir, ChecksumMode::Full, None, None)
+            .unwrap();
+        let database = scanner.database();
+        let id_of = |name: &str| {
+            database
+                .find_asset_by_location(&collection_dir.join(name))
+                .unwrap()
+                .unwrap()
+        };
+
+        let group = database
+            .get_lod_group(id_of("Tree_LOD1.obj"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(group.name, "Tree");
+        let levels: Vec<(u32, i64)> = group
+            .levels
+            .iter()
+            .map(|level| (level.level, level.asset_id))
+            .collect();
+        assert_eq!(
+            levels,
+            vec![(0, id_of("Tree_LOD0.obj")), (1, id_of("Tree_LOD1.obj"))]
+        );
+        assert!(database.get_lod_group(id_of("Rock.obj")).unwrap().is_none());
+    }
+
     #[test]
     fn test_is_asset_file() {
         let temp_dir = tempdir().unwrap();
@@ -324,6 +774,8 @@ mod tests {
         assert!(scanner.is_asset_file(Path::new("test.fbx")));
         assert!(scanner.is_asset_file(Path::new("texture.png")));
         assert!(scanner.is_asset_file(Path::new("audio.wav")));
+        assert!(scanner.is_asset_file(Path::new("ship.glb")));
+        assert!(scanner.is_asset_file(Path::new("crate.OBJ")));
         assert!(!scanner.is_asset_file(Path::new("script.cs")));
         assert!(!scanner.is_asset_file(Path::new("model.fbx.meta")));
         assert!(!scanner.is_asset_file(Path::new(".hidden")));
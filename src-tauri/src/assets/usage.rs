@@ -0,0 +1,436 @@
+// Which saved levels use each asset, and keeping their references valid when assets move
+use super::database::{AssetDatabase, AssetUsage};
+use super::paths::{AssetPathResolver, AssetRef};
+use super::preview;
+use crate::LevelData;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+
+/// Outcome of moving or renaming an asset.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetMoveSummary {
+    pub asset_id: i64,
+    pub old_path: String,
+    pub new_path: String,
+    /// Saved level files whose references were rewritten
+    pub updated_levels: Vec<String>,
+    /// Objects changed across those files and the open level
+    pub updated_objects: usize,
+    /// Saved themes with a slot bound to the asset
+    pub updated_themes: Vec<String>,
+    /// Levels or themes that couldn't be updated; their references to the asset are now broken
+    pub errors: Vec<String>,
+}
+
+/// Outcome of deleting assets.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetDeleteSummary {
+    pub deleted: Vec<i64>,
+    /// Files removed from disk, when requested
+    pub deleted_files: usize,
+    /// Saved levels that referenced a deleted asset; those references are now broken
+    pub referencing_levels: Vec<AssetUsage>,
+    pub errors: Vec<String>,
+}
+
+/// The ways a level can spell a path: absolute, or relative to the project's asset root.
+#[derive(Debug, Clone)]
+pub struct ReferenceForms {
+    absolute: String,
+    relative: Option<String>,
+}
+
+impl ReferenceForms {
+    /// Forms for an existing file; call before moving it away.
+    pub fn of(path: &Path, resolver: Option<&AssetPathResolver>) -> Self {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let absolute = canonical.to_string_lossy().to_string();
+        let relative = resolver
+            .and_then(|resolver| resolver.relative_reference(&absolute, None).ok())
+            .filter(|relative| !Path::new(relative).is_absolute());
+        Self { absolute, relative }
+    }
+
+    /// This path spelled the way `reference` was: absolute stays absolute.
+    fn matching(&self, reference: &str) -> String {
+        match AssetRef::parse(reference) {
+            AssetRef::Absolute(_) => self.absolute.clone(),
+            _ => self
+                .relative
+                .clone()
+                .unwrap_or_else(|| self.absolute.clone()),
+        }
+    }
+}
+
+/// A file moved on disk with its database row updated to match.
+#[derive(Debug, Clone)]
+pub struct MovedAsset {
+    pub old_path: String,
+    pub new_path: String,
+    /// Old reference spelling mapped to the new one; `asset:<id>` references need no change
+    pub replacements: HashMap<String, String>,
+    /// Usage recorded before the move
+    pub usages: Vec<AssetUsage>,
+}
+
+/// Move an asset's file to `destination` (relative to its asset root) and update its row.
+///
+/// The file is moved back if the database update fails, so the two never disagree.
+pub fn move_asset_file(
+    database: &mut AssetDatabase,
+    asset_id: i64,
+    destination: &str,
+    resolver: Option<&AssetPathResolver>,
+) -> Result<MovedAsset, String> {
+    let asset = database
+        .get_asset_by_id(asset_id)
+        .map_err(|e| format!("Failed to look up asset {}: {}", asset_id, e))?
+        .ok_or_else(|| format!("Asset not found: {}", asset_id))?
+        .asset;
+    let root = PathBuf::from(
+        asset
+            .asset_root
+            .as_deref()
+            .ok_or("Asset has no recorded root; rescan before moving it")?,
+    );
+    let old_path = PathBuf::from(&asset.file_path);
+
+    let relative = relative_destination(destination)?;
+    let collection = relative
+        .iter()
+        .next()
+        .map(|part| part.to_string_lossy().to_string())
+        .filter(|_| relative.components().count() > 1)
+        .ok_or("Assets must be inside a collection folder, e.g. Kenney/crate.glb")?;
+    let extension = |path: &Path| path.extension().map(|ext| ext.to_ascii_lowercase());
+    if extension(&relative) != extension(&old_path) {
+        return Err("Moving an asset can't change its file extension".to_string());
+    }
+    let new_path = root.join(&relative);
+    if new_path.exists() {
+        return Err(format!("{} already exists", destination));
+    }
+    if !old_path.is_file() {
+        return Err(format!("Asset file is missing: {}", asset.file_path));
+    }
+
+    let usages = database
+        .get_asset_usage(asset_id)
+        .map_err(|e| format!("Failed to read asset usage: {}", e))?;
+    let old_forms = ReferenceForms::of(&old_path, resolver);
+
+    if let Some(parent) = new_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+    move_file(&old_path, &new_path)?;
+    if let Err(e) = database.move_asset(asset_id, &new_path, &collection) {
+        let _ = move_file(&new_path, &old_path);
+        return Err(format!("Failed to update asset {}: {}", asset_id, e));
+    }
+    let new_forms = ReferenceForms::of(&new_path, resolver);
+
+    let mut candidates = vec![old_forms.absolute.clone(), asset.file_path.clone()];
+    candidates.extend(old_forms.relative.clone());
+    candidates.extend(usages.iter().map(|usage| usage.reference.clone()));
+    let replacements = candidates
+        .into_iter()
+        .filter(|reference| !matches!(AssetRef::parse(reference), AssetRef::Id(_)))
+        .map(|reference| {
+            let replacement = new_forms.matching(&reference);
+            (reference, replacement)
+        })
+        .filter(|(old, new)| old != new)
+        .collect();
+
+    Ok(MovedAsset {
+        old_path: asset.file_path,
+        new_path: new_path.to_string_lossy().to_string(),
+        replacements,
+        usages,
+    })
+}
+
+/// Delete assets' rows, with their metadata, tags, thumbnails and cached previews.
+///
+/// Levels using an asset are looked up before its usage rows cascade away, so the
+/// summary can say which ones now have broken references.
+pub fn delete_assets(
+    database: &mut AssetDatabase,
+    asset_ids: &[i64],
+    delete_files: bool,
+    preview_cache: &Path,
+) -> AssetDeleteSummary {
+    let mut summary = AssetDeleteSummary::default();
+    for &asset_id in asset_ids {
+        if let Err(e) = delete_asset(
+            database,
+            asset_id,
+            delete_files,
+            preview_cache,
+            &mut summary,
+        ) {
+            summary.errors.push(e);
+        }
+    }
+    summary
+}
+
+fn delete_asset(
+    database: &mut AssetDatabase,
+    asset_id: i64,
+    delete_files: bool,
+    preview_cache: &Path,
+    summary: &mut AssetDeleteSummary,
+) -> Result<(), String> {
+    let asset = database
+        .get_asset_by_id(asset_id)
+        .map_err(|e| format!("Failed to look up asset {}: {}", asset_id, e))?
+        .ok_or_else(|| format!("Asset not found: {}", asset_id))?
+        .asset;
+    let usages = database
+        .get_asset_usage(asset_id)
+        .map_err(|e| format!("Failed to read usage of asset {}: {}", asset_id, e))?;
+
+    database
+        .remove_asset(asset_id, &asset.collection)
+        .map_err(|e| format!("Failed to delete asset {}: {}", asset_id, e))?;
+    summary.deleted.push(asset_id);
+    summary.referencing_levels.extend(usages);
+
+    // Duplicates share previews, so only drop them with the last copy
+    if matches!(database.find_asset_by_checksum(&asset.checksum), Ok(None)) {
+        preview::remove_cached_previews(preview_cache, &asset.checksum);
+    }
+
+    if delete_files {
+        match fs::remove_file(&asset.file_path) {
+            Ok(()) => summary.deleted_files += 1,
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(format!("Failed to delete {}: {}", asset.file_path, e));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A `/`-separated path that stays inside the asset root.
+fn relative_destination(destination: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(destination.trim().replace('\\', "/"));
+    let valid = path.components().count() > 0
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if valid {
+        Ok(path)
+    } else {
+        Err(format!(
+            "Destination must be relative to the asset root: {}",
+            destination
+        ))
+    }
+}
+
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    // Renames fail across file systems; fall back to copying
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to).map_err(|e| format!("Failed to move {:?} to {:?}: {}", from, to, e))?;
+    fs::remove_file(from).map_err(|e| format!("Failed to remove {:?}: {}", from, e))
+}
+
+/// Indexed assets a level references, one entry per distinct reference.
+pub fn level_usage(
+    level_path: &str,
+    level: &LevelData,
+    resolver: Option<&AssetPathResolver>,
+    database: &AssetDatabase,
+) -> Vec<AssetUsage> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for obj in &level.objects {
+        for reference in [&obj.mesh, &obj.material].into_iter().flatten() {
+            *counts.entry(reference).or_default() += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .filter_map(|(reference, object_count)| {
+            let asset_id = match AssetRef::parse(reference) {
+                AssetRef::Id(id) => Some(id),
+                AssetRef::Absolute(path) => database.find_asset_by_location(&path).ok()?,
+                AssetRef::Relative(_) => {
+                    let path = resolver?.resolve(reference, None).ok()?;
+                    database.find_asset_by_location(&path).ok()?
+                }
+            }?;
+            Some(AssetUsage {
+                level_path: level_path.to_string(),
+                asset_id,
+                reference: reference.to_string(),
+                object_count,
+            })
+        })
+        .collect()
+}
+
+/// Swap references that exactly match a key, returning the IDs of changed objects.
+pub fn rewrite_references(
+    level: &mut LevelData,
+    replacements: &HashMap<String, String>,
+) -> Vec<String> {
+    let mut changed = Vec::new();
+    for obj in &mut level.objects {
+        let mut object_changed = false;
+        for reference in [&mut obj.mesh, &mut obj.material].into_iter().flatten() {
+            if let Some(replacement) = replacements.get(reference.as_str()) {
+                reference.clone_from(replacement);
+                object_changed = true;
+            }
+        }
+        if object_changed {
+            changed.push(obj.id.clone());
+        }
+    }
+    changed
+}
+
+/// Apply `replacements` to a saved level file, returning the level and objects changed.
+pub fn rewrite_level_file(
+    path: &Path,
+    replacements: &HashMap<String, String>,
+) -> Result<(LevelData, usize), String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let mut level: LevelData =
+        serde_json::from_str(&json).map_err(|e| format!("Failed to parse {:?}: {}", path, e))?;
+
+    let changed = rewrite_references(&mut level, replacements).len();
+    if changed > 0 {
+        let json = serde_json::to_string_pretty(&level)
+            .map_err(|e| format!("Failed to serialize level data: {}", e))?;
+        fs::write(path, json).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    }
+    Ok((level, changed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{level, prop};
+    use tempfile::tempdir;
+
+    #[test]
+    fn moving_an_asset_rewrites_level_references() {
+        let temp_dir = tempdir().unwrap();
+        let assets_root = temp_dir.path().join("Assets");
+        fs::create_dir_all(assets_root.join("Kenney")).unwrap();
+        let old_file = assets_root.join("Kenney").join("crate.glb");
+        fs::write(&old_file, b"glTF").unwrap();
+
+        let mut database = AssetDatabase::new(temp_dir.path().join("assets.db")).unwrap();
+        let asset_id = database
+            .insert_asset(&old_file, "Kenney", &assets_root)
+            .unwrap();
+        let resolver = AssetPathResolver::new(&assets_root);
+
+        let absolute = old_file
+            .canonicalize()
+            .unwrap()
+            .to_string_lossy()
+            .to_string();
+        let level = level(vec![
+            prop("a", "Kenney/crate.glb"),
+            prop("b", &absolute),
+            prop("c", &format!("asset:{}", asset_id)),
+            prop("d", "Kenney/other.glb"),
+        ]);
+        let level_path = temp_dir.path().join("level.json");
+        fs::write(&level_path, serde_json::to_string(&level).unwrap()).unwrap();
+        let level_key = level_path.to_string_lossy().to_string();
+        let usage = level_usage(&level_key, &level, Some(&resolver), &database);
+        assert_eq!(usage.len(), 3);
+        database.record_level_usage(&level_key, &usage).unwrap();
+
+        assert!(move_asset_file(&mut database, asset_id, "../crate.glb", Some(&resolver)).is_err());
+        assert!(
+            move_asset_file(&mut database, asset_id, "Props/crate.fbx", Some(&resolver)).is_err()
+        );
+        let moved =
+            move_asset_file(&mut database, asset_id, "Props/box.glb", Some(&resolver)).unwrap();
+        assert!(!old_file.exists());
+        assert!(assets_root.join("Props/box.glb").is_file());
+
+        let record = database.get_asset_by_id(asset_id).unwrap().unwrap().asset;
+        assert_eq!(record.name, "box.glb");
+        assert_eq!(record.collection, "Props");
+
+        let (rewritten, changed) = rewrite_level_file(&level_path, &moved.replacements).unwrap();
+        assert_eq!(changed, 2);
+        assert_eq!(rewritten.objects[0].mesh.as_deref(), Some("Props/box.glb"));
+        assert!(Path::new(rewritten.objects[1].mesh.as_deref().unwrap()).is_absolute());
+        assert!(rewritten.objects[1]
+            .mesh
+            .as_deref()
+            .unwrap()
+            .ends_with("box.glb"));
+        assert_eq!(
+            rewritten.objects[2].mesh,
+            Some(format!("asset:{}", asset_id))
+        );
+        assert_eq!(
+            rewritten.objects[3].mesh.as_deref(),
+            Some("Kenney/other.glb")
+        );
+    }
+
+    #[test]
+    fn deleting_assets_reports_levels_using_them() {
+        let temp_dir = tempdir().unwrap();
+        let assets_root = temp_dir.path().join("Assets");
+        fs::create_dir_all(assets_root.join("Kenney")).unwrap();
+        let file = assets_root.join("Kenney").join("barrel.glb");
+        fs::write(&file, b"glTF").unwrap();
+
+        let mut database = AssetDatabase::new(temp_dir.path().join("assets.db")).unwrap();
+        let asset_id = database
+            .insert_asset(&file, "Kenney", &assets_root)
+            .unwrap();
+        let usage = AssetUsage {
+            level_path: "dungeon.json".to_string(),
+            asset_id,
+            reference: "Kenney/barrel.glb".to_string(),
+            object_count: 3,
+        };
+        database
+            .record_level_usage("dungeon.json", &[usage])
+            .unwrap();
+
+        let cache_dir = temp_dir.path().join("thumbnails");
+        let checksum = database
+            .get_asset_by_id(asset_id)
+            .unwrap()
+            .unwrap()
+            .asset
+            .checksum;
+        fs::create_dir_all(&cache_dir).unwrap();
+        fs::write(preview::preview_path(&cache_dir, &checksum, 64), b"png").unwrap();
+
+        let summary = delete_assets(&mut database, &[asset_id, 999], true, &cache_dir);
+        assert_eq!(summary.deleted, vec![asset_id]);
+        assert_eq!(summary.deleted_files, 1);
+        assert_eq!(summary.referencing_levels.len(), 1);
+        assert_eq!(summary.referencing_levels[0].level_path, "dungeon.json");
+        assert_eq!(summary.errors.len(), 1);
+
+        assert!(!file.exists());
+        assert!(database.get_asset_by_id(asset_id).unwrap().is_none());
+        assert!(database.get_asset_usage(asset_id).unwrap().is_empty());
+        assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,152 @@
+use super::scanner::{AssetChange, AssetScanner};
+use log::{error, info, warn};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted after watched files were applied to the database.
+pub const ASSETS_CHANGED_EVENT: &str = "assets_changed";
+
+/// Quiet period used to batch bursts of file events (editors often write several times).
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Payload of the `assets_changed` event, listing file paths by change.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetsChangedEvent {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl AssetsChangedEvent {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Watches the asset roots and keeps the database in sync with them.
+///
+/// Dropping the watcher stops watching and ends the worker thread.
+pub struct AssetWatcher {
+    _watcher: RecommendedWatcher,
+    roots: Vec<PathBuf>,
+}
+
+impl AssetWatcher {
+    pub fn start(
+        roots: Vec<PathBuf>,
+        scanner: Arc<Mutex<Option<AssetScanner>>>,
+        app_handle: AppHandle,
+    ) -> Result<Self, String> {
+        let (tx, rx) = mpsc::channel::<PathBuf>();
+
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+                Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                    for path in event.paths {
+                        let _ = tx.send(path);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Asset watcher error: {}", e),
+            })
+            .map_err(|e| format!("Failed to create asset watcher: {}", e))?;
+
+        for root in &roots {
+            watcher
+                .watch(root, RecursiveMode::Recursive)
+                .map_err(|e| format!("Failed to watch {:?}: {}", root, e))?;
+        }
+
+        let worker_roots = roots.clone();
+        thread::spawn(move || {
+            // Ends once the watcher (and with it the sender) is dropped
+            while let Ok(first) = rx.recv() {
+                let mut paths = BTreeSet::from([first]);
+                while let Ok(path) = rx.recv_timeout(DEBOUNCE) {
+                    paths.insert(path);
+                }
+
+                let changes = apply_changes(&worker_roots, &paths, &scanner);
+                if !changes.is_empty() {
+                    if let Err(e) = app_handle.emit(ASSETS_CHANGED_EVENT, &changes) {
+                        warn!("Failed to emit {} event: {}", ASSETS_CHANGED_EVENT, e);
+                    }
+                }
+            }
+        });
+
+        info!("Watching asset directories: {:?}", roots);
+        Ok(Self {
+            _watcher: watcher,
+            roots,
+        })
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+}
+
+fn apply_changes(
+    roots: &[PathBuf],
+    paths: &BTreeSet<PathBuf>,
+    scanner: &Mutex<Option<AssetScanner>>,
+) -> AssetsChangedEvent {
+    let mut changes = AssetsChangedEvent::default();
+    let Ok(mut scanner_guard) = scanner.lock() else {
+        error!("Asset database lock poisoned, dropping file events");
+        return changes;
+    };
+    let Some(scanner) = scanner_guard.as_mut() else {
+        return changes;
+    };
+
+    let canonical_roots: Vec<Option<PathBuf>> =
+        roots.iter().map(|root| root.canonicalize().ok()).collect();
+    for path in paths {
+        let Some((root, path)) =
+            roots
+                .iter()
+                .zip(&canonical_roots)
+                .find_map(|(root, canonical)| {
+                    to_root_form(root, canonical.as_deref(), path).map(|path| (root, path))
+                })
+        else {
+            continue;
+        };
+        match scanner.sync_file(&path, root) {
+            Ok(Some(change)) => {
+                let path = path.to_string_lossy().to_string();
+                match change {
+                    AssetChange::Added => changes.added.push(path),
+                    AssetChange::Updated => changes.updated.push(path),
+                    AssetChange::Removed => changes.removed.push(path),
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to sync asset {:?}: {}", path, e),
+        }
+    }
+
+    changes
+}
+
+/// Express an event path relative to `root` the same way the scanner does, so
+/// it matches the paths stored in the database.
+///
+/// Returns `None` if the path is not under `root`.
+fn to_root_form(root: &Path, canonical_root: Option<&Path>, path: &Path) -> Option<PathBuf> {
+    if path.starts_with(root) {
+        return Some(path.to_path_buf());
+    }
+    canonical_root
+        .and_then(|canonical| path.strip_prefix(canonical).ok())
+        .map(|relative| root.join(relative))
+}
@@ -0,0 +1,156 @@
+// Waveform overviews of audio assets, so the audio browser needn't decode files itself
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as AudioError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{MediaSourceStream, MediaSourceStreamOptions};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Number of peaks stored per audio asset.
+pub const WAVEFORM_POINTS: usize = 256;
+/// Frames folded into one peak while decoding, before downsampling to `WAVEFORM_POINTS`.
+const BLOCK_FRAMES: usize = 512;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Waveform {
+    pub asset_id: i64,
+    /// Peak amplitude of each slice of the file across all channels, from 0 to 1
+    pub peaks: Vec<f32>,
+}
+
+/// Decode an audio file and reduce it to `points` evenly spaced peak amplitudes.
+///
+/// Memory stays bounded by keeping one peak per `BLOCK_FRAMES` frames while decoding.
+pub fn audio_peaks(path: &Path, points: usize) -> Result<Vec<f32>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open audio: {}", e))?;
+    let stream = MediaSourceStream::new(Box::new(file), MediaSourceStreamOptions::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("Unrecognized audio format: {}", e))?
+        .format;
+    let track = format.default_track().ok_or("No audio track")?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported audio codec: {}", e))?;
+
+    let mut blocks = Vec::new();
+    let mut block_peak = 0.0f32;
+    let mut block_frames = 0;
+    let mut samples: Option<SampleBuffer<f32>> = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(AudioError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("Failed to read audio: {}", e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet is skipped rather than losing the whole waveform
+            Err(AudioError::DecodeError(_)) => continue,
+            Err(e) => return Err(format!("Failed to decode audio: {}", e)),
+        };
+
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let buffer = match &mut samples {
+            Some(buffer) if buffer.capacity() >= decoded.capacity() * channels => buffer,
+            _ => samples.insert(SampleBuffer::new(decoded.capacity() as u64, spec)),
+        };
+        buffer.copy_interleaved_ref(decoded);
+
+        for frame in buffer.samples().chunks(channels) {
+            let peak = frame
+                .iter()
+                .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+            block_peak = block_peak.max(peak);
+            block_frames += 1;
+            if block_frames == BLOCK_FRAMES {
+                blocks.push(block_peak);
+                block_peak = 0.0;
+                block_frames = 0;
+            }
+        }
+    }
+    if block_frames > 0 {
+        blocks.push(block_peak);
+    }
+    if blocks.is_empty() {
+        return Err("Audio file has no samples".to_string());
+    }
+    Ok(downsample(&blocks, points))
+}
+
+/// Reduce peaks to `points` values, each the largest of its slice.
+fn downsample(blocks: &[f32], points: usize) -> Vec<f32> {
+    (0..points)
+        .map(|point| {
+            let start = point * blocks.len() / points;
+            let end = ((point + 1) * blocks.len() / points).max(start + 1);
+            blocks[start..end]
+                .iter()
+                .fold(0.0f32, |peak, value| peak.max(*value))
+                .min(1.0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reads_wav_peaks() {
+        // Half a second of silence, then half a second at half volume, 16-bit mono 8 kHz
+        let sample_rate: u32 = 8000;
+        let samples: Vec<i16> = (0..sample_rate)
+            .map(|i| if i < sample_rate / 2 { 0 } else { i16::MAX / 2 })
+            .collect();
+        let data_len = (samples.len() * 2) as u32;
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        for sample in &samples {
+            wav.extend_from_slice(&sample.to_le_bytes());
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("swell.wav");
+        std::fs::write(&path, wav).unwrap();
+
+        let peaks = audio_peaks(&path, 4).unwrap();
+        assert_eq!(peaks.len(), 4);
+        assert!(peaks[0] < 0.01);
+        assert!((peaks[3] - 0.5).abs() < 0.01);
+
+        assert_eq!(downsample(&[0.2, 0.9], 4), vec![0.2, 0.2, 0.9, 0.9]);
+    }
+}
@@ -0,0 +1,301 @@
+//! Background autosave of the open level, so work survives a crash.
+//!
+//! Every couple of minutes the current level, if it changed since the last autosave, is
+//! written to a compressed recovery file in the app data folder together with the path
+//! of the open project. Only the newest few are kept. Restoring one replaces the current
+//! level the way restoring a snapshot does. Project files themselves aren't autosaved;
+//! the recorded path only tells the editor which project to reopen with the level.
+
+use crate::level::events::{emit_level_changed, LevelChangeKind};
+use crate::level::objects::{LevelResponse, ResponseMode};
+use crate::{assets, project, AppState, LevelData};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+const RECOVERY_DIRECTORY: &str = "recovery";
+const RECOVERY_EXTENSION: &str = ".json.gz";
+const AUTOSAVE_INTERVAL: Duration = Duration::from_mins(2);
+/// Recovery files kept; older ones are deleted as new ones are written.
+const MAX_RECOVERY_FILES: usize = 10;
+
+/// On-disk recovery file contents.
+#[derive(Debug, Serialize, Deserialize)]
+struct RecoveryFile {
+    created_at: DateTime<Utc>,
+    /// The project open when the level was saved
+    project_file: Option<String>,
+    level: LevelData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoverySnapshotInfo {
+    pub id: String,
+    pub level_id: String,
+    pub level_name: String,
+    pub object_count: usize,
+    pub project_file: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+/// Result of `restore_recovery_snapshot`: the level, and the project to reopen with it.
+#[derive(Debug, Serialize)]
+pub struct RecoveredLevel {
+    level: LevelResponse,
+    project_file: Option<String>,
+}
+
+fn recovery_directory(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join(RECOVERY_DIRECTORY))
+}
+
+/// IDs are file names, newest sorting last, so only a safe character set is accepted.
+fn recovery_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+    {
+        return Err(format!("Invalid recovery snapshot: {}", id));
+    }
+    Ok(dir.join(format!("{}{}", id, RECOVERY_EXTENSION)))
+}
+
+/// IDs of the recovery files in `dir`, newest first.
+fn recovery_ids(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut ids: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            file_name
+                .strip_suffix(RECOVERY_EXTENSION)
+                .map(str::to_string)
+        })
+        .collect();
+    ids.sort_by(|a, b| b.cmp(a));
+    ids
+}
+
+/// Writes `recovery` to `dir`, deleting all but the newest `MAX_RECOVERY_FILES`, and
+/// returns its ID.
+fn write_recovery(dir: &Path, recovery: &RecoveryFile) -> Result<String, String> {
+    let level_id: String = recovery
+        .level
+        .id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let id = format!(
+        "{}-{}",
+        recovery.created_at.format("%Y%m%dT%H%M%S%3f"),
+        level_id
+    );
+    let path = recovery_path(dir, &id)?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create recovery directory: {}", e))?;
+
+    let json = serde_json::to_vec(recovery)
+        .map_err(|e| format!("Failed to serialize recovery file: {}", e))?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder
+        .write_all(&json)
+        .map_err(|e| format!("Failed to compress recovery file: {}", e))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| format!("Failed to compress recovery file: {}", e))?;
+    // A crash mid-write leaves only the temporary file behind
+    let partial = path.with_extension("partial");
+    fs::write(&partial, compressed).map_err(|e| format!("Failed to write recovery file: {}", e))?;
+    fs::rename(&partial, &path).map_err(|e| format!("Failed to write recovery file: {}", e))?;
+
+    for old in recovery_ids(dir).iter().skip(MAX_RECOVERY_FILES) {
+        if let Err(e) = fs::remove_file(recovery_path(dir, old)?) {
+            warn!("Failed to delete old recovery file {}: {}", old, e);
+        }
+    }
+    Ok(id)
+}
+
+fn read_recovery(path: &Path) -> Result<RecoveryFile, String> {
+    let compressed = fs::read(path).map_err(|e| format!("Failed to read recovery file: {}", e))?;
+    let mut json = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut json)
+        .map_err(|e| format!("Failed to decompress recovery file: {}", e))?;
+    serde_json::from_slice(&json).map_err(|e| format!("Failed to parse recovery file: {}", e))
+}
+
+/// Readable recovery files in `dir`, newest first.
+fn list_recovery(dir: &Path) -> Vec<RecoverySnapshotInfo> {
+    recovery_ids(dir)
+        .into_iter()
+        .filter_map(|id| {
+            let path = recovery_path(dir, &id).ok()?;
+            let recovery = read_recovery(&path)
+                .map_err(|e| warn!("Skipping recovery file {}: {}", id, e))
+                .ok()?;
+            Some(RecoverySnapshotInfo {
+                id,
+                level_id: recovery.level.id,
+                level_name: recovery.level.name,
+                object_count: recovery.level.objects.len(),
+                project_file: recovery.project_file,
+                created_at: recovery.created_at,
+                size_bytes: fs::metadata(&path).map_or(0, |metadata| metadata.len()),
+            })
+        })
+        .collect()
+}
+
+fn level_hash(level: &LevelData) -> Result<u64, String> {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_vec(level)
+        .map_err(|e| format!("Failed to serialize level: {}", e))?
+        .hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// Saves the current level unless it's unchanged since `last_saved`, its hash.
+async fn autosave(app_handle: &AppHandle, last_saved: &mut Option<u64>) -> Result<(), String> {
+    let state = app_handle.state::<tokio::sync::RwLock<AppState>>();
+    let Some(level) = state.read().await.current_level.clone() else {
+        return Ok(());
+    };
+
+    let dir = recovery_directory(app_handle)?;
+    let project_file = project::open_project_file().map(|file| file.to_string_lossy().to_string());
+    let previous = *last_saved;
+    // Hashing serializes the whole level, so it runs off the async runtime with the write
+    let saved = assets::run_blocking(move || {
+        let hash = level_hash(&level)?;
+        if previous == Some(hash) {
+            return Ok(None);
+        }
+        let recovery = RecoveryFile {
+            created_at: Utc::now(),
+            project_file,
+            level,
+        };
+        write_recovery(&dir, &recovery).map(|id| Some((hash, id)))
+    })
+    .await?;
+    if let Some((hash, id)) = saved {
+        *last_saved = Some(hash);
+        info!("Autosaved level to recovery file {}", id);
+    }
+    Ok(())
+}
+
+/// Starts autosaving in the background for as long as the app runs.
+pub fn start(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_saved = None;
+        let mut ticker = tokio::time::interval(AUTOSAVE_INTERVAL);
+        // The first tick is immediate, before there is anything to save
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if let Err(e) = autosave(&app_handle, &mut last_saved).await {
+                warn!("Autosave failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Autosaved levels, newest first.
+#[tauri::command]
+pub async fn list_recovery_snapshots(
+    app_handle: AppHandle,
+) -> Result<Vec<RecoverySnapshotInfo>, String> {
+    let dir = recovery_directory(&app_handle)?;
+    assets::run_blocking(move || Ok(list_recovery(&dir))).await
+}
+
+/// Replace the current level with an autosaved one.
+#[tauri::command]
+pub async fn restore_recovery_snapshot(
+    id: String,
+    response_mode: Option<ResponseMode>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<RecoveredLevel, String> {
+    let path = recovery_path(&recovery_directory(&app_handle)?, &id)?;
+    if !path.exists() {
+        return Err(format!("Recovery snapshot not found: {}", id));
+    }
+    let recovery = assets::run_blocking(move || read_recovery(&path)).await?;
+
+    let mut app_state = state.write().await;
+    app_state.spatial_index.clear();
+    for obj in &recovery.level.objects {
+        app_state.spatial_index.insert(&obj.id, &obj.transform);
+    }
+    app_state.current_level = Some(recovery.level.clone());
+
+    info!(
+        "Restored recovery snapshot {} from {}",
+        id, recovery.created_at
+    );
+    emit_level_changed(&app_handle, LevelChangeKind::LevelReplaced, Vec::new());
+    Ok(RecoveredLevel {
+        level: LevelResponse::new(recovery.level, response_mode),
+        project_file: recovery.project_file,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn level(id: &str) -> LevelData {
+        LevelData {
+            id: id.to_string(),
+            ..testing::level(Vec::new())
+        }
+    }
+
+    #[test]
+    fn keeps_the_newest_recovery_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let start = Utc::now();
+        let mut ids = Vec::new();
+        for minute in 0..MAX_RECOVERY_FILES + 2 {
+            let recovery = RecoveryFile {
+                created_at: start + chrono::Duration::minutes(minute as i64),
+                project_file: Some("/work/game/game.mbp".to_string()),
+                level: level("level/crypt"),
+            };
+            ids.push(write_recovery(dir.path(), &recovery).unwrap());
+        }
+
+        let listed = list_recovery(dir.path());
+        assert_eq!(listed.len(), MAX_RECOVERY_FILES);
+        assert_eq!(listed[0].id, ids[ids.len() - 1]);
+        assert_eq!(listed[MAX_RECOVERY_FILES - 1].id, ids[2]);
+        assert_eq!(listed[0].level_id, "level/crypt");
+        assert!(!recovery_path(dir.path(), &ids[0]).unwrap().exists());
+
+        let restored = read_recovery(&recovery_path(dir.path(), &ids[5]).unwrap()).unwrap();
+        assert_eq!(restored.created_at, start + chrono::Duration::minutes(5));
+        assert!(recovery_path(dir.path(), "../game").is_err());
+        assert_eq!(level_hash(&level("a")), level_hash(&level("a")));
+    }
+}
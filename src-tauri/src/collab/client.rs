@@ -0,0 +1,93 @@
+// A collaborator's connection to the hosting editor
+use super::{ClientMessage, ServerMessage};
+use futures_util::{SinkExt, StreamExt};
+use log::warn;
+use tauri::AppHandle;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+pub type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+fn parse(text: &str) -> Option<ServerMessage> {
+    serde_json::from_str(text)
+        .map_err(|e| warn!("Ignoring malformed message from the host: {}", e))
+        .ok()
+}
+
+async fn send(socket: &mut Socket, message: &ClientMessage) -> Result<(), String> {
+    let text = serde_json::to_string(message)
+        .map_err(|e| format!("Failed to serialize message for the host: {}", e))?;
+    socket
+        .send(Message::Text(text.into()))
+        .await
+        .map_err(|e| format!("Lost connection to the host: {}", e))
+}
+
+/// Connect to the host at `address` and introduce ourselves, returning the host's welcome.
+pub async fn connect(
+    address: &str,
+    token: &str,
+    name: &str,
+) -> Result<(Socket, ServerMessage), String> {
+    let address = address.trim_start_matches("ws://").trim_end_matches('/');
+    let url = format!("ws://{}/collab?token={}", address, token);
+    let (mut socket, _) = tokio_tungstenite::connect_async(url)
+        .await
+        .map_err(|e| format!("Failed to connect to {}: {}", address, e))?;
+    send(
+        &mut socket,
+        &ClientMessage::Hello {
+            name: name.to_string(),
+        },
+    )
+    .await?;
+
+    while let Some(received) = socket.next().await {
+        match received.map_err(|e| format!("Lost connection to the host: {}", e))? {
+            Message::Text(text) => {
+                if let Some(welcome @ ServerMessage::Welcome { .. }) = parse(text.as_str()) {
+                    return Ok((socket, welcome));
+                }
+            }
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    Err("The host closed the connection".to_string())
+}
+
+/// Relay messages both ways until the host goes away or we leave the session.
+pub async fn run(
+    app_handle: AppHandle,
+    generation: u64,
+    mut socket: Socket,
+    mut outgoing: mpsc::UnboundedReceiver<ClientMessage>,
+) {
+    loop {
+        tokio::select! {
+            received = socket.next() => match received {
+                Some(Ok(Message::Text(text))) => {
+                    if let Some(message) = parse(text.as_str()) {
+                        super::receive_from_host(&app_handle, generation, message).await;
+                    }
+                }
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            message = outgoing.recv() => {
+                // The session was left
+                let Some(message) = message else {
+                    let _ = socket.close(None).await;
+                    return;
+                };
+                if let Err(e) = send(&mut socket, &message).await {
+                    warn!("{}", e);
+                    break;
+                }
+            }
+        }
+    }
+    super::end(&app_handle, generation, "Lost connection to the host").await;
+}
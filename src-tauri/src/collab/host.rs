@@ -0,0 +1,105 @@
+// The hosting editor's WebSocket endpoint for collaborators
+use super::ClientMessage;
+use crate::server::routes::{authorized, TokenQuery};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use log::warn;
+use tauri::AppHandle;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+#[derive(Clone)]
+pub struct HostContext {
+    pub app_handle: AppHandle,
+    pub token: String,
+    pub generation: u64,
+}
+
+/// Accept collaborators on `listener` until `stopped` fires.
+pub async fn serve(listener: TcpListener, context: HostContext, stopped: oneshot::Receiver<()>) {
+    let router = Router::new()
+        .route("/collab", get(open_socket))
+        .with_state(context);
+    let served = axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            let _ = stopped.await;
+        })
+        .await;
+    if let Err(e) = served {
+        warn!("Collaboration server stopped: {}", e);
+    }
+}
+
+async fn open_socket(
+    State(context): State<HostContext>,
+    Query(query): Query<TokenQuery>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    if !authorized(&headers, query.token.as_deref(), &context.token) {
+        return (StatusCode::UNAUTHORIZED, "Missing or invalid session token").into_response();
+    }
+    upgrade.on_upgrade(move |socket| serve_peer(socket, context))
+}
+
+enum Received {
+    Message(ClientMessage),
+    /// Pings and anything that is not a message we understand
+    Ignored,
+    Closed,
+}
+
+fn parse(received: Option<Result<Message, axum::Error>>) -> Received {
+    match received {
+        Some(Ok(Message::Text(text))) => {
+            serde_json::from_str(&text).map_or(Received::Ignored, Received::Message)
+        }
+        Some(Ok(Message::Close(_)) | Err(_)) | None => Received::Closed,
+        Some(Ok(_)) => Received::Ignored,
+    }
+}
+
+/// Greet a collaborator, then relay messages both ways until either side leaves.
+async fn serve_peer(mut socket: WebSocket, context: HostContext) {
+    let name = loop {
+        match parse(socket.recv().await) {
+            Received::Message(ClientMessage::Hello { name }) => break name,
+            Received::Message(_) | Received::Ignored => {}
+            Received::Closed => return,
+        }
+    };
+    let app_handle = &context.app_handle;
+    let Some((user_id, mut outgoing)) =
+        super::add_peer(app_handle, context.generation, &name).await
+    else {
+        return;
+    };
+
+    loop {
+        tokio::select! {
+            received = socket.recv() => match parse(received) {
+                Received::Message(message) => {
+                    super::receive_from_peer(app_handle, context.generation, user_id, message).await;
+                }
+                Received::Ignored => {}
+                Received::Closed => break,
+            },
+            message = outgoing.recv() => {
+                let Some(message) = message else {
+                    break;
+                };
+                let Ok(text) = serde_json::to_string(&message) else {
+                    continue;
+                };
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    super::remove_peer(app_handle, context.generation, user_id).await;
+}
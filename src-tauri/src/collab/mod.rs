@@ -0,0 +1,745 @@
+//! Real-time collaborative editing between editors on the same network.
+//!
+//! One editor hosts a session and the others join it over a WebSocket. The host's level
+//! is the authority: local edits are turned into [`ops::LevelOp`]s by comparing the level
+//! with the copy last shared, sent to the host, applied and numbered there, then sent to
+//! every collaborator. A collaborator keeps its own unconfirmed edits on top of whatever
+//! arrives from others until the host confirms them, so everyone ends up with the
+//! host's order. Presence (who has what selected) travels the same way.
+//!
+//! The frontend hears about the session through the `collab_presence`,
+//! `collab_conflict`, `collab_error` and `collab_ended` events, alongside the usual
+//! `level_changed` for edits made by others.
+
+mod client;
+mod host;
+pub mod ops;
+
+use crate::level::events::{emit_level_changed, LevelChangeKind};
+use crate::{AppState, LevelData};
+use log::{info, warn};
+use ops::{AppliedChange, LevelOp, OpLog, SequencedOp, TransformConflict};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+pub const PRESENCE_EVENT: &str = "collab_presence";
+pub const CONFLICT_EVENT: &str = "collab_conflict";
+pub const ERROR_EVENT: &str = "collab_error";
+pub const ENDED_EVENT: &str = "collab_ended";
+
+const DEFAULT_PORT: u16 = 7879;
+/// The hosting editor's user id; collaborators are numbered from 1 as they join.
+const HOST_USER_ID: u64 = 0;
+
+/// Someone in the session and what they are working on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collaborator {
+    pub user_id: u64,
+    pub name: String,
+    pub host: bool,
+    /// Objects selected in their editor
+    pub selected_ids: Vec<String>,
+    /// Object they are dragging or editing right now
+    pub editing: Option<String>,
+}
+
+impl Collaborator {
+    fn new(user_id: u64, name: &str) -> Self {
+        let name = name.trim();
+        Self {
+            user_id,
+            name: if name.is_empty() {
+                format!("User {}", user_id)
+            } else {
+                name.to_string()
+            },
+            host: user_id == HOST_USER_ID,
+            selected_ids: Vec::new(),
+            editing: None,
+        }
+    }
+}
+
+/// Messages from a collaborator to the host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// First message on a new connection
+    Hello { name: String },
+    /// A local edit, made after seeing operations up to `base_seq`
+    Submit { base_seq: u64, op: Box<LevelOp> },
+    Presence {
+        selected_ids: Vec<String>,
+        editing: Option<String>,
+    },
+}
+
+/// Messages from the host to collaborators.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Answer to `hello`: the session's level and who is in it
+    Welcome {
+        user_id: u64,
+        last_seq: u64,
+        level: Option<LevelData>,
+        users: Vec<Collaborator>,
+    },
+    /// An accepted operation, including the recipient's own as confirmation
+    Op(SequencedOp),
+    /// The recipient's oldest unconfirmed operation could not be applied
+    Rejected {
+        reason: String,
+    },
+    /// The host's level, sent after a rejection to put the recipient back in step
+    Snapshot {
+        last_seq: u64,
+        level: Option<LevelData>,
+    },
+    Conflict(TransformConflict),
+    Presence {
+        users: Vec<Collaborator>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollabRole {
+    Host,
+    Client,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollabStatus {
+    pub role: CollabRole,
+    /// Where the host listens, or the host this editor joined
+    pub address: String,
+    /// What collaborators need to join; only known to the host
+    pub token: Option<String>,
+    pub user_id: u64,
+    pub users: Vec<Collaborator>,
+    /// Number of the last operation applied here
+    pub last_seq: u64,
+}
+
+struct HostLink {
+    address: SocketAddr,
+    token: String,
+    log: OpLog,
+    peers: HashMap<u64, mpsc::UnboundedSender<ServerMessage>>,
+    next_user_id: u64,
+    shutdown: oneshot::Sender<()>,
+}
+
+struct ClientLink {
+    address: String,
+    outgoing: mpsc::UnboundedSender<ClientMessage>,
+    last_seq: u64,
+    /// Operations sent to the host and not yet confirmed, oldest first
+    pending: VecDeque<LevelOp>,
+}
+
+enum Link {
+    Host(HostLink),
+    Client(ClientLink),
+}
+
+struct Session {
+    /// Tells a session's background tasks apart from those of the sessions before it
+    generation: u64,
+    user_id: u64,
+    users: Vec<Collaborator>,
+    /// The level as last shared with the session; local edits are found by comparing
+    /// the open level with it
+    synced: Option<LevelData>,
+    link: Link,
+}
+
+impl Session {
+    fn status(&self) -> CollabStatus {
+        let (role, address, token, last_seq) = match &self.link {
+            Link::Host(host) => (
+                CollabRole::Host,
+                host.address.to_string(),
+                Some(host.token.clone()),
+                host.log.last_seq(),
+            ),
+            Link::Client(client) => (
+                CollabRole::Client,
+                client.address.clone(),
+                None,
+                client.last_seq,
+            ),
+        };
+        CollabStatus {
+            role,
+            address,
+            token,
+            user_id: self.user_id,
+            users: self.users.clone(),
+            last_seq,
+        }
+    }
+
+    /// Send a local operation, already applied here, to the rest of the session.
+    fn share(&mut self, op: LevelOp) {
+        match &mut self.link {
+            Link::Host(host) => {
+                let sequenced = host.log.record(self.user_id, op);
+                broadcast(&host.peers, &ServerMessage::Op(sequenced));
+            }
+            Link::Client(client) => {
+                client.pending.push_back(op.clone());
+                let _ = client.outgoing.send(ClientMessage::Submit {
+                    base_seq: client.last_seq,
+                    op: Box::new(op),
+                });
+            }
+        }
+    }
+
+    /// Tell everyone, this editor included, who is in the session now.
+    fn publish_presence(&self, app_handle: &AppHandle) {
+        if let Link::Host(host) = &self.link {
+            broadcast(
+                &host.peers,
+                &ServerMessage::Presence {
+                    users: self.users.clone(),
+                },
+            );
+        }
+        emit(app_handle, PRESENCE_EVENT, &self.users);
+    }
+}
+
+/// A level change made in this editor, waiting to be shared.
+struct LocalChange {
+    kind: LevelChangeKind,
+    object_ids: Vec<String>,
+}
+
+/// The current session, if any.
+pub struct CollabState {
+    session: Mutex<Option<Session>>,
+    /// Feeds local changes to the running session; `level_changed` is raised from
+    /// synchronous code that may hold the level lock, so changes are picked up later
+    local_changes: std::sync::Mutex<Option<mpsc::UnboundedSender<LocalChange>>>,
+    generations: AtomicU64,
+}
+
+impl CollabState {
+    pub fn new() -> Self {
+        Self {
+            session: Mutex::new(None),
+            local_changes: std::sync::Mutex::new(None),
+            generations: AtomicU64::new(0),
+        }
+    }
+}
+
+fn emit<T: Serialize>(app_handle: &AppHandle, event: &str, payload: &T) {
+    if let Err(e) = app_handle.emit(event, payload) {
+        warn!("Failed to emit {} event: {}", event, e);
+    }
+}
+
+fn broadcast(peers: &HashMap<u64, mpsc::UnboundedSender<ServerMessage>>, message: &ServerMessage) {
+    for peer in peers.values() {
+        let _ = peer.send(message.clone());
+    }
+}
+
+/// Queue a change to the open level for the running session, if there is one.
+pub fn share_local_change(app_handle: &AppHandle, kind: LevelChangeKind, object_ids: &[String]) {
+    let Some(collab) = app_handle.try_state::<CollabState>() else {
+        return;
+    };
+    let Ok(sender) = collab.local_changes.lock() else {
+        return;
+    };
+    if let Some(sender) = sender.as_ref() {
+        let _ = sender.send(LocalChange {
+            kind,
+            object_ids: object_ids.to_vec(),
+        });
+    }
+}
+
+/// Turn queued local changes into operations and share them, until the session ends.
+async fn share_local_changes(
+    app_handle: AppHandle,
+    generation: u64,
+    mut changes: mpsc::UnboundedReceiver<LocalChange>,
+) {
+    while let Some(change) = changes.recv().await {
+        let collab = app_handle.state::<CollabState>();
+        let mut session = collab.session.lock().await;
+        let Some(session) = session.as_mut().filter(|s| s.generation == generation) else {
+            break;
+        };
+        let state = app_handle.state::<tokio::sync::RwLock<AppState>>();
+        let ops = ops::local_ops(
+            session.synced.as_ref(),
+            state.read().await.current_level.as_ref(),
+            change.kind,
+            &change.object_ids,
+        );
+        for op in ops {
+            let _ = op.apply(&mut session.synced);
+            session.share(op);
+        }
+    }
+}
+
+/// Start a session, replacing any current one, and begin sharing local changes.
+async fn begin(app_handle: &AppHandle, make: impl FnOnce(u64) -> Session) -> CollabStatus {
+    let collab = app_handle.state::<CollabState>();
+    let mut current = collab.session.lock().await;
+    if let Some(previous) = current.take() {
+        close(previous);
+    }
+
+    let generation = collab.generations.fetch_add(1, Ordering::Relaxed) + 1;
+    let session = make(generation);
+    let (sender, receiver) = mpsc::unbounded_channel();
+    if let Ok(mut local_changes) = collab.local_changes.lock() {
+        *local_changes = Some(sender);
+    }
+    tauri::async_runtime::spawn(share_local_changes(
+        app_handle.clone(),
+        generation,
+        receiver,
+    ));
+
+    session.publish_presence(app_handle);
+    let status = session.status();
+    *current = Some(session);
+    status
+}
+
+/// Stop a session's server or connection.
+fn close(session: Session) {
+    match session.link {
+        Link::Host(host) => {
+            let _ = host.shutdown.send(());
+            info!("Stopped hosting collaboration session on {}", host.address);
+        }
+        // Dropping the sender ends the connection task
+        Link::Client(client) => info!("Left collaboration session at {}", client.address),
+    }
+}
+
+/// End the session if it is still `generation`, e.g. when the host goes away.
+async fn end(app_handle: &AppHandle, generation: u64, reason: &str) {
+    let collab = app_handle.state::<CollabState>();
+    let mut current = collab.session.lock().await;
+    if current.as_ref().is_some_and(|s| s.generation == generation) {
+        if let Some(session) = current.take() {
+            close(session);
+        }
+        if let Ok(mut local_changes) = collab.local_changes.lock() {
+            *local_changes = None;
+        }
+        emit(app_handle, ENDED_EVENT, &reason);
+    }
+}
+
+/// Apply another collaborator's operation to the open level and the synced copy, then
+/// put `pending` local operations back on top, since the host will order them after it.
+fn apply_remote(
+    app_handle: &AppHandle,
+    app_state: &mut AppState,
+    synced: &mut Option<LevelData>,
+    op: &LevelOp,
+    pending: &VecDeque<LevelOp>,
+) -> Result<(), String> {
+    let change = op.apply(&mut app_state.current_level)?;
+    let _ = op.apply(synced);
+    ops::refresh_index(app_state, &change);
+    for own in pending {
+        if let Ok(restored) = own.apply(&mut app_state.current_level) {
+            let _ = own.apply(synced);
+            ops::refresh_index(app_state, &restored);
+        }
+    }
+    emit_level_changed(app_handle, change.kind, change.object_ids);
+    Ok(())
+}
+
+/// Replace the open level with the session's copy.
+fn adopt_level(
+    app_handle: &AppHandle,
+    app_state: &mut AppState,
+    synced: &mut Option<LevelData>,
+    level: Option<LevelData>,
+    pending: &VecDeque<LevelOp>,
+) {
+    app_state.current_level = level;
+    for own in pending {
+        let _ = own.apply(&mut app_state.current_level);
+    }
+    synced.clone_from(&app_state.current_level);
+    let change = AppliedChange {
+        kind: LevelChangeKind::LevelReplaced,
+        object_ids: Vec::new(),
+    };
+    ops::refresh_index(app_state, &change);
+    emit_level_changed(app_handle, change.kind, change.object_ids);
+}
+
+/// Tell the collaborators involved in transform conflicts which edit was kept.
+fn report_conflicts(app_handle: &AppHandle, host: &HostLink, conflicts: Vec<TransformConflict>) {
+    for conflict in conflicts {
+        for user_id in [conflict.overwritten_user, conflict.winning_user] {
+            if user_id == HOST_USER_ID {
+                emit(app_handle, CONFLICT_EVENT, &conflict);
+            } else if let Some(peer) = host.peers.get(&user_id) {
+                let _ = peer.send(ServerMessage::Conflict(conflict.clone()));
+            }
+        }
+    }
+}
+
+/// Host side: a collaborator connected and said hello.
+async fn add_peer(
+    app_handle: &AppHandle,
+    generation: u64,
+    name: &str,
+) -> Option<(u64, mpsc::UnboundedReceiver<ServerMessage>)> {
+    let collab = app_handle.state::<CollabState>();
+    let mut current = collab.session.lock().await;
+    let session = current.as_mut().filter(|s| s.generation == generation)?;
+    let Link::Host(host) = &mut session.link else {
+        return None;
+    };
+
+    let user_id = host.next_user_id;
+    host.next_user_id += 1;
+    let collaborator = Collaborator::new(user_id, name);
+    info!("{} joined the collaboration session", collaborator.name);
+    session.users.push(collaborator);
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let level = app_handle
+        .state::<tokio::sync::RwLock<AppState>>()
+        .read()
+        .await
+        .current_level
+        .clone();
+    let _ = sender.send(ServerMessage::Welcome {
+        user_id,
+        last_seq: host.log.last_seq(),
+        level,
+        users: session.users.clone(),
+    });
+    host.peers.insert(user_id, sender);
+    session.publish_presence(app_handle);
+    Some((user_id, receiver))
+}
+
+/// Host side: a collaborator disconnected.
+async fn remove_peer(app_handle: &AppHandle, generation: u64, user_id: u64) {
+    let collab = app_handle.state::<CollabState>();
+    let mut current = collab.session.lock().await;
+    let Some(session) = current.as_mut().filter(|s| s.generation == generation) else {
+        return;
+    };
+    if let Link::Host(host) = &mut session.link {
+        host.peers.remove(&user_id);
+    }
+    if let Some(index) = session.users.iter().position(|u| u.user_id == user_id) {
+        let collaborator = session.users.remove(index);
+        info!("{} left the collaboration session", collaborator.name);
+    }
+    session.publish_presence(app_handle);
+}
+
+/// Host side: handle a message from collaborator `user_id`.
+async fn receive_from_peer(
+    app_handle: &AppHandle,
+    generation: u64,
+    user_id: u64,
+    message: ClientMessage,
+) {
+    let collab = app_handle.state::<CollabState>();
+    let mut current = collab.session.lock().await;
+    let Some(session) = current.as_mut().filter(|s| s.generation == generation) else {
+        return;
+    };
+    let Link::Host(host) = &mut session.link else {
+        return;
+    };
+
+    match message {
+        ClientMessage::Hello { .. } => {}
+        ClientMessage::Submit { base_seq, op } => {
+            let state = app_handle.state::<tokio::sync::RwLock<AppState>>();
+            let mut app_state = state.write().await;
+            match host
+                .log
+                .submit(&mut app_state.current_level, user_id, base_seq, *op)
+            {
+                Ok(submission) => {
+                    let _ = submission.sequenced.op.apply(&mut session.synced);
+                    ops::refresh_index(&mut app_state, &submission.change);
+                    drop(app_state);
+                    broadcast(&host.peers, &ServerMessage::Op(submission.sequenced));
+                    report_conflicts(app_handle, host, submission.conflicts);
+                    emit_level_changed(
+                        app_handle,
+                        submission.change.kind,
+                        submission.change.object_ids,
+                    );
+                }
+                Err(reason) => {
+                    warn!("Refused an edit from collaborator {}: {}", user_id, reason);
+                    if let Some(peer) = host.peers.get(&user_id) {
+                        let _ = peer.send(ServerMessage::Rejected { reason });
+                        let _ = peer.send(ServerMessage::Snapshot {
+                            last_seq: host.log.last_seq(),
+                            level: app_state.current_level.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        ClientMessage::Presence {
+            selected_ids,
+            editing,
+        } => {
+            if let Some(user) = session.users.iter_mut().find(|u| u.user_id == user_id) {
+                user.selected_ids = selected_ids;
+                user.editing = editing;
+            }
+            session.publish_presence(app_handle);
+        }
+    }
+}
+
+/// Collaborator side: handle a message from the host.
+async fn receive_from_host(app_handle: &AppHandle, generation: u64, message: ServerMessage) {
+    let collab = app_handle.state::<CollabState>();
+    let mut current = collab.session.lock().await;
+    let Some(session) = current.as_mut().filter(|s| s.generation == generation) else {
+        return;
+    };
+    let Link::Client(client) = &mut session.link else {
+        return;
+    };
+    let state = app_handle.state::<tokio::sync::RwLock<AppState>>();
+
+    match message {
+        ServerMessage::Welcome { .. } => {}
+        ServerMessage::Op(sequenced) => {
+            client.last_seq = sequenced.seq;
+            if sequenced.user_id == session.user_id {
+                client.pending.pop_front();
+                return;
+            }
+            let mut app_state = state.write().await;
+            let applied = apply_remote(
+                app_handle,
+                &mut app_state,
+                &mut session.synced,
+                &sequenced.op,
+                &client.pending,
+            );
+            if let Err(e) = applied {
+                warn!(
+                    "Failed to apply edit {} from the host: {}",
+                    sequenced.seq, e
+                );
+            }
+        }
+        ServerMessage::Rejected { reason } => {
+            client.pending.pop_front();
+            emit(app_handle, ERROR_EVENT, &reason);
+        }
+        ServerMessage::Snapshot { last_seq, level } => {
+            client.last_seq = last_seq;
+            let mut app_state = state.write().await;
+            adopt_level(
+                app_handle,
+                &mut app_state,
+                &mut session.synced,
+                level,
+                &client.pending,
+            );
+        }
+        ServerMessage::Conflict(conflict) => emit(app_handle, CONFLICT_EVENT, &conflict),
+        ServerMessage::Presence { users } => {
+            session.users = users;
+            emit(app_handle, PRESENCE_EVENT, &session.users);
+        }
+    }
+}
+
+/// Host a session on `port` (7879 by default), on all network interfaces so teammates
+/// can join with this machine's address and the returned token.
+#[tauri::command]
+pub async fn host_collab_session(
+    user_name: String,
+    port: Option<u16>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<CollabStatus, String> {
+    let port = port.unwrap_or(DEFAULT_PORT);
+    let listener = tokio::net::TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))
+        .await
+        .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+    let address = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read session address: {}", e))?;
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let synced = state.read().await.current_level.clone();
+
+    let (shutdown, stopped) = oneshot::channel();
+    let serve_handle = app_handle.clone();
+    let serve_token = token.clone();
+    let status = begin(&app_handle, move |generation| {
+        tauri::async_runtime::spawn(host::serve(
+            listener,
+            host::HostContext {
+                app_handle: serve_handle,
+                token: serve_token,
+                generation,
+            },
+            stopped,
+        ));
+        Session {
+            generation,
+            user_id: HOST_USER_ID,
+            users: vec![Collaborator::new(HOST_USER_ID, &user_name)],
+            synced,
+            link: Link::Host(HostLink {
+                address,
+                token,
+                log: OpLog::default(),
+                peers: HashMap::new(),
+                next_user_id: HOST_USER_ID + 1,
+                shutdown,
+            }),
+        }
+    })
+    .await;
+
+    info!("Hosting collaboration session on {}", address);
+    Ok(status)
+}
+
+/// Join the session hosted at `address` (`host:port`). The host's level replaces the
+/// open one.
+#[tauri::command]
+pub async fn join_collab_session(
+    address: String,
+    token: String,
+    user_name: String,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<CollabStatus, String> {
+    let address = address.trim().to_string();
+    let (socket, welcome) = client::connect(&address, token.trim(), &user_name).await?;
+    let ServerMessage::Welcome {
+        user_id,
+        last_seq,
+        level,
+        users,
+    } = welcome
+    else {
+        return Err("The host did not accept the connection".to_string());
+    };
+
+    let mut synced = None;
+    adopt_level(
+        &app_handle,
+        &mut *state.write().await,
+        &mut synced,
+        level,
+        &VecDeque::new(),
+    );
+
+    let (outgoing, outgoing_receiver) = mpsc::unbounded_channel();
+    let connection_handle = app_handle.clone();
+    let session_address = address.clone();
+    let status = begin(&app_handle, move |generation| {
+        tauri::async_runtime::spawn(client::run(
+            connection_handle,
+            generation,
+            socket,
+            outgoing_receiver,
+        ));
+        Session {
+            generation,
+            user_id,
+            users,
+            synced,
+            link: Link::Client(ClientLink {
+                address: session_address,
+                outgoing,
+                last_seq,
+                pending: VecDeque::new(),
+            }),
+        }
+    })
+    .await;
+
+    info!("Joined collaboration session at {}", address);
+    Ok(status)
+}
+
+/// Leave the current session, or stop hosting it.
+#[tauri::command]
+pub async fn leave_collab_session(app_handle: AppHandle) -> Result<(), String> {
+    let collab = app_handle.state::<CollabState>();
+    let generation = collab
+        .session
+        .lock()
+        .await
+        .as_ref()
+        .map(|session| session.generation);
+    if let Some(generation) = generation {
+        end(&app_handle, generation, "Left the session").await;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_collab_status(app_handle: AppHandle) -> Result<Option<CollabStatus>, String> {
+    let collab = app_handle.state::<CollabState>();
+    let current = collab.session.lock().await;
+    Ok(current.as_ref().map(Session::status))
+}
+
+/// Share what this editor has selected and is editing with the rest of the session.
+#[tauri::command]
+pub async fn update_collab_presence(
+    selected_ids: Vec<String>,
+    editing: Option<String>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let collab = app_handle.state::<CollabState>();
+    let mut current = collab.session.lock().await;
+    let session = current
+        .as_mut()
+        .ok_or_else(|| "Not in a collaboration session".to_string())?;
+
+    let user_id = session.user_id;
+    if let Some(user) = session.users.iter_mut().find(|u| u.user_id == user_id) {
+        user.selected_ids.clone_from(&selected_ids);
+        user.editing.clone_from(&editing);
+    }
+    match &session.link {
+        Link::Host(_) => session.publish_presence(&app_handle),
+        Link::Client(client) => {
+            let _ = client.outgoing.send(ClientMessage::Presence {
+                selected_ids,
+                editing,
+            });
+        }
+    }
+    Ok(())
+}
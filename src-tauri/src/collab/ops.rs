@@ -0,0 +1,500 @@
+// Level edits as operations that every collaborator replays on their own copy
+use crate::level::annotations::Annotation;
+use crate::level::bookmarks::CameraBookmark;
+use crate::level::events::LevelChangeKind;
+use crate::{AppState, GameObject, LevelData, Transform3D};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Operations kept for conflict checks. Edits based on anything older are applied
+/// without looking for conflicts.
+const LOG_CAPACITY: usize = 2048;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransformComponent {
+    Position,
+    Rotation,
+    Scale,
+}
+
+/// The parts of a transform one edit changed. Concurrent edits to different parts of
+/// the same object both apply, so one person can move an object while another scales it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransformEdit {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<[f32; 3]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<[f32; 4]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<[f32; 3]>,
+}
+
+/// Exact comparison, so any change at all is shared.
+fn differs<const N: usize>(a: [f32; N], b: [f32; N]) -> bool {
+    a.map(f32::to_bits) != b.map(f32::to_bits)
+}
+
+impl TransformEdit {
+    /// The edit turning `old` into `new`, if they differ.
+    pub fn between(old: &Transform3D, new: &Transform3D) -> Option<Self> {
+        let edit = Self {
+            position: differs(old.position, new.position).then_some(new.position),
+            rotation: differs(old.rotation, new.rotation).then_some(new.rotation),
+            scale: differs(old.scale, new.scale).then_some(new.scale),
+        };
+        (!edit.components().is_empty()).then_some(edit)
+    }
+
+    pub fn components(&self) -> Vec<TransformComponent> {
+        [
+            (self.position.is_some(), TransformComponent::Position),
+            (self.rotation.is_some(), TransformComponent::Rotation),
+            (self.scale.is_some(), TransformComponent::Scale),
+        ]
+        .into_iter()
+        .filter_map(|(set, component)| set.then_some(component))
+        .collect()
+    }
+
+    pub fn apply(&self, transform: &mut Transform3D) {
+        if let Some(position) = self.position {
+            transform.position = position;
+        }
+        if let Some(rotation) = self.rotation {
+            transform.rotation = rotation;
+        }
+        if let Some(scale) = self.scale {
+            transform.scale = scale;
+        }
+    }
+}
+
+/// One level mutation, as sent between collaborating editors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LevelOp {
+    /// A level was generated, loaded or restored
+    ReplaceLevel {
+        level: LevelData,
+    },
+    /// New objects; an object whose id already exists replaces it
+    AddObjects {
+        objects: Vec<GameObject>,
+    },
+    /// Everything about an object except its transform
+    UpdateObject {
+        object: GameObject,
+    },
+    SetTransform {
+        object_id: String,
+        edit: TransformEdit,
+    },
+    SetAnnotations {
+        annotations: Vec<Annotation>,
+    },
+    SetBookmarks {
+        bookmarks: Vec<CameraBookmark>,
+    },
+}
+
+/// What applying an operation changed, for the spatial index and `level_changed`.
+#[derive(Debug, Clone)]
+pub struct AppliedChange {
+    pub kind: LevelChangeKind,
+    pub object_ids: Vec<String>,
+}
+
+fn loaded(level: &mut Option<LevelData>) -> Result<&mut LevelData, String> {
+    level
+        .as_mut()
+        .ok_or_else(|| "No level currently loaded".to_string())
+}
+
+fn object_mut<'a>(level: &'a mut LevelData, object_id: &str) -> Result<&'a mut GameObject, String> {
+    level
+        .objects
+        .iter_mut()
+        .find(|obj| obj.id == object_id)
+        .ok_or_else(|| format!("Object not found: {}", object_id))
+}
+
+impl LevelOp {
+    pub fn apply(&self, level: &mut Option<LevelData>) -> Result<AppliedChange, String> {
+        let (kind, object_ids) = match self {
+            Self::ReplaceLevel { level: replacement } => {
+                *level = Some(replacement.clone());
+                (LevelChangeKind::LevelReplaced, Vec::new())
+            }
+            Self::AddObjects { objects } => {
+                let level = loaded(level)?;
+                for obj in objects {
+                    match level.objects.iter_mut().find(|o| o.id == obj.id) {
+                        Some(existing) => *existing = obj.clone(),
+                        None => level.objects.push(obj.clone()),
+                    }
+                }
+                let ids = objects.iter().map(|obj| obj.id.clone()).collect();
+                (LevelChangeKind::ObjectsAdded, ids)
+            }
+            Self::UpdateObject { object } => {
+                let existing = object_mut(loaded(level)?, &object.id)?;
+                let transform = existing.transform.clone();
+                *existing = object.clone();
+                existing.transform = transform;
+                (LevelChangeKind::ObjectsUpdated, vec![object.id.clone()])
+            }
+            Self::SetTransform { object_id, edit } => {
+                edit.apply(&mut object_mut(loaded(level)?, object_id)?.transform);
+                (LevelChangeKind::ObjectsUpdated, vec![object_id.clone()])
+            }
+            Self::SetAnnotations { annotations } => {
+                loaded(level)?.annotations.clone_from(annotations);
+                (LevelChangeKind::AnnotationsChanged, Vec::new())
+            }
+            Self::SetBookmarks { bookmarks } => {
+                loaded(level)?.camera_bookmarks.clone_from(bookmarks);
+                (LevelChangeKind::BookmarksChanged, Vec::new())
+            }
+        };
+        Ok(AppliedChange { kind, object_ids })
+    }
+}
+
+/// Bring the spatial index in line with an applied change.
+pub fn refresh_index(app_state: &mut AppState, change: &AppliedChange) {
+    let AppState {
+        current_level,
+        spatial_index,
+    } = app_state;
+    let Some(level) = current_level else {
+        spatial_index.clear();
+        return;
+    };
+    if change.kind == LevelChangeKind::LevelReplaced {
+        spatial_index.clear();
+        for obj in &level.objects {
+            spatial_index.insert(&obj.id, &obj.transform);
+        }
+        return;
+    }
+    for obj in &level.objects {
+        if change.object_ids.contains(&obj.id) {
+            spatial_index.update(&obj.id, &obj.transform);
+        }
+    }
+}
+
+/// Whether two objects match in everything but their transform.
+fn same_apart_from_transform(a: &GameObject, b: &GameObject) -> bool {
+    let without_transform = |obj: &GameObject| {
+        serde_json::to_value(obj).ok().map(|mut value| {
+            if let Some(fields) = value.as_object_mut() {
+                fields.remove("transform");
+            }
+            value
+        })
+    };
+    without_transform(a) == without_transform(b)
+}
+
+/// Operations that carry a local change from `synced`, the level as collaborators last
+/// saw it, to `current`. `object_ids` are the objects the change touched; none means
+/// every object.
+pub fn local_ops(
+    synced: Option<&LevelData>,
+    current: Option<&LevelData>,
+    kind: LevelChangeKind,
+    object_ids: &[String],
+) -> Vec<LevelOp> {
+    let Some(current) = current else {
+        return Vec::new();
+    };
+    let synced = match synced {
+        Some(synced) if synced.id == current.id && kind != LevelChangeKind::LevelReplaced => synced,
+        _ => {
+            return vec![LevelOp::ReplaceLevel {
+                level: current.clone(),
+            }]
+        }
+    };
+
+    match kind {
+        LevelChangeKind::LevelReplaced => Vec::new(),
+        LevelChangeKind::AnnotationsChanged => {
+            let changed = serde_json::to_value(&synced.annotations).ok()
+                != serde_json::to_value(&current.annotations).ok();
+            changed
+                .then(|| LevelOp::SetAnnotations {
+                    annotations: current.annotations.clone(),
+                })
+                .into_iter()
+                .collect()
+        }
+        LevelChangeKind::BookmarksChanged => {
+            let changed = serde_json::to_value(&synced.camera_bookmarks).ok()
+                != serde_json::to_value(&current.camera_bookmarks).ok();
+            changed
+                .then(|| LevelOp::SetBookmarks {
+                    bookmarks: current.camera_bookmarks.clone(),
+                })
+                .into_iter()
+                .collect()
+        }
+        LevelChangeKind::ObjectsAdded | LevelChangeKind::ObjectsUpdated => {
+            let mut added = Vec::new();
+            let mut ops = Vec::new();
+            let touched = current
+                .objects
+                .iter()
+                .filter(|obj| object_ids.is_empty() || object_ids.contains(&obj.id));
+            for obj in touched {
+                let Some(before) = synced.objects.iter().find(|o| o.id == obj.id) else {
+                    added.push(obj.clone());
+                    continue;
+                };
+                if !same_apart_from_transform(before, obj) {
+                    ops.push(LevelOp::UpdateObject {
+                        object: obj.clone(),
+                    });
+                }
+                if let Some(edit) = TransformEdit::between(&before.transform, &obj.transform) {
+                    ops.push(LevelOp::SetTransform {
+                        object_id: obj.id.clone(),
+                        edit,
+                    });
+                }
+            }
+            if !added.is_empty() {
+                ops.insert(0, LevelOp::AddObjects { objects: added });
+            }
+            ops
+        }
+    }
+}
+
+/// An operation with its place in the session's history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencedOp {
+    pub seq: u64,
+    pub user_id: u64,
+    pub op: LevelOp,
+}
+
+/// Two collaborators changed the same part of an object's transform at the same time.
+/// The edit that reached the host last is kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformConflict {
+    pub object_id: String,
+    pub component: TransformComponent,
+    /// Collaborator whose edit was replaced
+    pub overwritten_user: u64,
+    /// Collaborator whose edit now stands
+    pub winning_user: u64,
+}
+
+/// A collaborator's operation once the host accepted it.
+#[derive(Debug)]
+pub struct Submission {
+    pub sequenced: SequencedOp,
+    pub change: AppliedChange,
+    pub conflicts: Vec<TransformConflict>,
+}
+
+/// The host's ordered history of the session. The host's copy of the level is the
+/// authority: every operation is applied there first, numbered, then sent to everyone.
+#[derive(Debug, Default)]
+pub struct OpLog {
+    last_seq: u64,
+    recent: VecDeque<SequencedOp>,
+}
+
+impl OpLog {
+    pub const fn last_seq(&self) -> u64 {
+        self.last_seq
+    }
+
+    /// Number an operation that has already been applied to the host's level.
+    pub fn record(&mut self, user_id: u64, op: LevelOp) -> SequencedOp {
+        self.last_seq += 1;
+        let sequenced = SequencedOp {
+            seq: self.last_seq,
+            user_id,
+            op,
+        };
+        if self.recent.len() == LOG_CAPACITY {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(sequenced.clone());
+        sequenced
+    }
+
+    /// Apply a collaborator's operation to the host's level and number it. `base_seq` is
+    /// the last operation the collaborator had seen when they made the edit; transform
+    /// edits others made since then to the same parts of the same object are reported.
+    pub fn submit(
+        &mut self,
+        level: &mut Option<LevelData>,
+        user_id: u64,
+        base_seq: u64,
+        op: LevelOp,
+    ) -> Result<Submission, String> {
+        let conflicts = self.conflicts(user_id, base_seq, &op);
+        let change = op.apply(level)?;
+        Ok(Submission {
+            sequenced: self.record(user_id, op),
+            change,
+            conflicts,
+        })
+    }
+
+    fn conflicts(&self, user_id: u64, base_seq: u64, op: &LevelOp) -> Vec<TransformConflict> {
+        let LevelOp::SetTransform { object_id, edit } = op else {
+            return Vec::new();
+        };
+        let components = edit.components();
+        let mut conflicts: Vec<TransformConflict> = Vec::new();
+        for earlier in self
+            .recent
+            .iter()
+            .filter(|s| s.seq > base_seq && s.user_id != user_id)
+        {
+            let LevelOp::SetTransform {
+                object_id: other_id,
+                edit: other,
+            } = &earlier.op
+            else {
+                continue;
+            };
+            if other_id != object_id {
+                continue;
+            }
+            for component in other.components() {
+                let reported = conflicts
+                    .iter()
+                    .any(|c| c.component == component && c.overwritten_user == earlier.user_id);
+                if components.contains(&component) && !reported {
+                    conflicts.push(TransformConflict {
+                        object_id: object_id.clone(),
+                        component,
+                        overwritten_user: earlier.user_id,
+                        winning_user: user_id,
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, prop};
+
+    fn level() -> LevelData {
+        LevelData {
+            layers: vec!["Props".to_string()],
+            ..testing::level(vec![prop("a", "crate.glb"), prop("b", "crate.glb")])
+        }
+    }
+
+    fn transform_op(object_id: &str, edit: TransformEdit) -> LevelOp {
+        LevelOp::SetTransform {
+            object_id: object_id.to_string(),
+            edit,
+        }
+    }
+
+    #[test]
+    fn resolves_concurrent_transform_edits() {
+        let mut host_level = Some(level());
+        let mut log = OpLog::default();
+
+        // Alice moves `a` while Bob, who has not seen that yet, scales it
+        let moved = TransformEdit {
+            position: Some([4.0, 0.0, 0.0]),
+            ..TransformEdit::default()
+        };
+        let scaled = TransformEdit {
+            scale: Some([2.0; 3]),
+            ..TransformEdit::default()
+        };
+        let first = log
+            .submit(&mut host_level, 1, 0, transform_op("a", moved))
+            .unwrap();
+        assert!(first.conflicts.is_empty());
+        let second = log
+            .submit(&mut host_level, 2, 0, transform_op("a", scaled))
+            .unwrap();
+        assert!(second.conflicts.is_empty());
+        assert_eq!(second.sequenced.seq, 2);
+        let a = &host_level.as_ref().unwrap().objects[0];
+        assert!(!differs(a.transform.position, [4.0, 0.0, 0.0]));
+        assert!(!differs(a.transform.scale, [2.0; 3]));
+
+        // Both now move it; Bob's edit arrives last and wins
+        let alice = TransformEdit {
+            position: Some([1.0; 3]),
+            ..TransformEdit::default()
+        };
+        let bob = TransformEdit {
+            position: Some([9.0; 3]),
+            scale: Some([3.0; 3]),
+            ..TransformEdit::default()
+        };
+        log.submit(&mut host_level, 1, 2, transform_op("a", alice))
+            .unwrap();
+        let last = log
+            .submit(&mut host_level, 2, 2, transform_op("a", bob))
+            .unwrap();
+        assert_eq!(last.conflicts.len(), 1);
+        let conflict = &last.conflicts[0];
+        assert_eq!(conflict.component, TransformComponent::Position);
+        assert_eq!((conflict.overwritten_user, conflict.winning_user), (1, 2));
+        let a = &host_level.as_ref().unwrap().objects[0];
+        assert!(!differs(a.transform.position, [9.0; 3]));
+
+        // Edits to objects that are gone are refused without taking a number
+        let missing = transform_op("gone", TransformEdit::default());
+        assert!(log.submit(&mut host_level, 1, 4, missing).is_err());
+        assert_eq!(log.last_seq(), 4);
+    }
+
+    #[test]
+    fn finds_local_changes() {
+        let synced = level();
+        let mut current = level();
+        current.objects[0].transform.rotation = [0.0, 1.0, 0.0, 0.0];
+        current.objects[1].tags.push("loot".to_string());
+        current.objects.push(prop("c", "crate.glb"));
+
+        let ops = local_ops(
+            Some(&synced),
+            Some(&current),
+            LevelChangeKind::ObjectsUpdated,
+            &[],
+        );
+        assert!(matches!(&ops[0], LevelOp::AddObjects { objects } if objects[0].id == "c"));
+        assert!(matches!(
+            &ops[1],
+            LevelOp::SetTransform { object_id, edit }
+                if object_id == "a" && edit.components() == [TransformComponent::Rotation]
+        ));
+        assert!(matches!(&ops[2], LevelOp::UpdateObject { object } if object.id == "b"));
+        assert_eq!(ops.len(), 3);
+
+        // Replaying them brings the synced copy up to date, so nothing is left to send
+        let mut replayed = Some(synced);
+        for op in &ops {
+            op.apply(&mut replayed).unwrap();
+        }
+        let again = local_ops(
+            replayed.as_ref(),
+            Some(&current),
+            LevelChangeKind::ObjectsUpdated,
+            &[],
+        );
+        assert!(again.is_empty());
+    }
+}
@@ -0,0 +1,480 @@
+//! Tracing-based diagnostics: console output, recent events and span timings kept for
+//! `get_diagnostics`, and trace captures for seeing where the time goes.
+//!
+//! `log` records from the rest of the editor are bridged into tracing, so everything
+//! ends up in one place. Console output still follows `RUST_LOG`. Slow commands and the
+//! generators and exporters run in spans; while a capture is running their timings are
+//! written as a Chrome trace (open in Perfetto or `chrome://tracing`) or as folded
+//! stacks for `inferno-flamegraph`.
+
+use crate::assets;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, State};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_log::{AsLog, NormalizeEvent};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+
+const EVENT_CAPACITY: usize = 1000;
+const SPAN_CAPACITY: usize = 500;
+const DEFAULT_EVENT_LIMIT: usize = 200;
+const TRACES_DIRECTORY: &str = "traces";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticEvent {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// Spans the event happened in, outermost first
+    pub spans: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpanTiming {
+    pub name: String,
+    pub target: String,
+    /// The span's fields as `name=value` pairs
+    pub fields: String,
+    pub parent: Option<String>,
+    pub closed_at: DateTime<Utc>,
+    pub duration_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpanStats {
+    pub name: String,
+    pub count: usize,
+    pub total_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsReport {
+    /// Most recent last
+    pub events: Vec<DiagnosticEvent>,
+    pub spans: Vec<SpanTiming>,
+    /// Recorded spans grouped by name, largest total first
+    pub stats: Vec<SpanStats>,
+    /// File the running trace capture writes to
+    pub trace_capture: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceFormat {
+    /// Chrome trace event JSON
+    #[default]
+    Chrome,
+    /// Folded stacks, as read by `inferno-flamegraph`
+    Flamegraph,
+}
+
+#[derive(Default)]
+struct Recorded {
+    events: VecDeque<DiagnosticEvent>,
+    spans: VecDeque<SpanTiming>,
+}
+
+fn push_capped<T>(queue: &mut VecDeque<T>, item: T, capacity: usize) {
+    if queue.len() == capacity {
+        queue.pop_front();
+    }
+    queue.push_back(item);
+}
+
+impl Recorded {
+    fn report(&self, min_level: Level, limit: usize) -> DiagnosticsReport {
+        let events: Vec<_> = self
+            .events
+            .iter()
+            .filter(|event| Level::from_str(&event.level).is_ok_and(|level| level <= min_level))
+            .cloned()
+            .collect();
+        let events = events[events.len().saturating_sub(limit)..].to_vec();
+
+        let mut by_name: BTreeMap<&str, SpanStats> = BTreeMap::new();
+        for span in &self.spans {
+            let stats = by_name.entry(&span.name).or_insert_with(|| SpanStats {
+                name: span.name.clone(),
+                count: 0,
+                total_ms: 0.0,
+                max_ms: 0.0,
+            });
+            stats.count += 1;
+            stats.total_ms += span.duration_ms;
+            stats.max_ms = stats.max_ms.max(span.duration_ms);
+        }
+        let mut stats: Vec<_> = by_name.into_values().collect();
+        stats.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+
+        DiagnosticsReport {
+            events,
+            spans: self.spans.iter().cloned().collect(),
+            stats,
+            trace_capture: None,
+        }
+    }
+}
+
+/// Collects an event's or span's message and other fields.
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: String,
+}
+
+impl FieldVisitor {
+    fn add_field(&mut self, name: &str, value: std::fmt::Arguments<'_>) {
+        // Bridged `log` records carry their metadata as fields
+        if name.starts_with("log.") {
+            return;
+        }
+        if name == "message" {
+            let _ = self.message.write_fmt(value);
+            return;
+        }
+        if !self.fields.is_empty() {
+            self.fields.push(' ');
+        }
+        let _ = write!(self.fields, "{}={}", name, value);
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.add_field(field.name(), format_args!("{}", value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.add_field(field.name(), format_args!("{:?}", value));
+    }
+}
+
+/// Kept in a span's extensions while it is open.
+struct SpanStart {
+    started: Instant,
+    fields: String,
+}
+
+/// Keeps recent events and the timings of closed spans.
+struct RecordingLayer {
+    recorded: Arc<Mutex<Recorded>>,
+}
+
+impl<S> Layer<S> for RecordingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        span.extensions_mut().insert(SpanStart {
+            started: Instant::now(),
+            fields: visitor.fields,
+        });
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let normalized = event.normalized_metadata();
+        let metadata = normalized.as_ref().unwrap_or_else(|| event.metadata());
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let message = match (visitor.message.is_empty(), visitor.fields.is_empty()) {
+            (_, true) => visitor.message,
+            (true, false) => visitor.fields,
+            (false, false) => format!("{} {}", visitor.message, visitor.fields),
+        };
+        let spans = ctx
+            .event_scope(event)
+            .map(|scope| {
+                scope
+                    .from_root()
+                    .map(|span| span.name().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let entry = DiagnosticEvent {
+            timestamp: Utc::now(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message,
+            spans,
+        };
+        if let Ok(mut recorded) = self.recorded.lock() {
+            push_capped(&mut recorded.events, entry, EVENT_CAPACITY);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let timing = {
+            let extensions = span.extensions();
+            let Some(start) = extensions.get::<SpanStart>() else {
+                return;
+            };
+            SpanTiming {
+                name: span.name().to_string(),
+                target: span.metadata().target().to_string(),
+                fields: start.fields.clone(),
+                parent: span.parent().map(|parent| parent.name().to_string()),
+                closed_at: Utc::now(),
+                duration_ms: start.started.elapsed().as_secs_f64() * 1000.0,
+            }
+        };
+        if let Ok(mut recorded) = self.recorded.lock() {
+            push_capped(&mut recorded.spans, timing, SPAN_CAPACITY);
+        }
+    }
+}
+
+type CaptureLayer = Option<Box<dyn Layer<Registry> + Send + Sync>>;
+
+/// Flushes the capture file when dropped.
+enum CaptureGuard {
+    Chrome(tracing_chrome::FlushGuard),
+    Flamegraph(tracing_flame::FlushGuard<BufWriter<File>>),
+}
+
+impl CaptureGuard {
+    fn flush(&self) {
+        match self {
+            Self::Chrome(guard) => guard.flush(),
+            Self::Flamegraph(guard) => {
+                if let Err(e) = guard.flush() {
+                    log::warn!("Failed to flush flamegraph capture: {}", e);
+                }
+            }
+        }
+    }
+}
+
+struct Capture {
+    path: PathBuf,
+    guard: CaptureGuard,
+}
+
+/// Recorded diagnostics and the trace capture slot, managed as Tauri state.
+pub struct Diagnostics {
+    recorded: Arc<Mutex<Recorded>>,
+    capture_layer: reload::Handle<CaptureLayer, Registry>,
+    capture: Mutex<Option<Capture>>,
+}
+
+impl Diagnostics {
+    fn capture_path(&self) -> Option<String> {
+        let capture = self.capture.lock().ok()?;
+        capture
+            .as_ref()
+            .map(|capture| capture.path.to_string_lossy().to_string())
+    }
+
+    fn start_capture(&self, directory: &Path, format: TraceFormat) -> Result<String, String> {
+        let mut capture = self
+            .capture
+            .lock()
+            .map_err(|_| "Trace capture lock poisoned".to_string())?;
+        if let Some(running) = capture.as_ref() {
+            return Err(format!(
+                "A trace capture is already running: {}",
+                running.path.display()
+            ));
+        }
+        fs::create_dir_all(directory)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", directory, e))?;
+
+        let stamp = Utc::now().format("%Y%m%d-%H%M%S");
+        let (layer, guard, path): (Box<dyn Layer<Registry> + Send + Sync>, _, _) = match format {
+            TraceFormat::Chrome => {
+                let path = directory.join(format!("trace-{}.json", stamp));
+                let file = File::create(&path)
+                    .map_err(|e| format!("Failed to create {:?}: {}", path, e))?;
+                let (layer, guard) = tracing_chrome::ChromeLayerBuilder::new()
+                    .writer(file)
+                    .include_args(true)
+                    .build();
+                (Box::new(layer), CaptureGuard::Chrome(guard), path)
+            }
+            TraceFormat::Flamegraph => {
+                let path = directory.join(format!("trace-{}.folded", stamp));
+                let (layer, guard) = tracing_flame::FlameLayer::with_file(&path)
+                    .map_err(|e| format!("Failed to create {:?}: {}", path, e))?;
+                let layer = layer.with_threads_collapsed(true);
+                (Box::new(layer), CaptureGuard::Flamegraph(guard), path)
+            }
+        };
+        self.capture_layer
+            .modify(|slot| *slot = Some(layer))
+            .map_err(|e| format!("Failed to start trace capture: {}", e))?;
+
+        let path_string = path.to_string_lossy().to_string();
+        *capture = Some(Capture { path, guard });
+        Ok(path_string)
+    }
+
+    fn stop_capture(&self) -> Result<Option<String>, String> {
+        let mut capture = self
+            .capture
+            .lock()
+            .map_err(|_| "Trace capture lock poisoned".to_string())?;
+        let Some(running) = capture.take() else {
+            return Ok(None);
+        };
+        self.capture_layer
+            .modify(|slot| *slot = None)
+            .map_err(|e| format!("Failed to stop trace capture: {}", e))?;
+        running.guard.flush();
+        Ok(Some(running.path.to_string_lossy().to_string()))
+    }
+}
+
+/// Install the global subscriber and bridge `log` into it. Call once at startup,
+/// before anything logs.
+pub fn init() -> Diagnostics {
+    // Like env_logger, only errors reach the console unless RUST_LOG says otherwise
+    let console_filter = EnvFilter::from_default_env();
+    let log_level = console_filter
+        .max_level_hint()
+        .map_or(LevelFilter::INFO, |level| level.max(LevelFilter::INFO));
+
+    let recorded = Arc::new(Mutex::new(Recorded::default()));
+    let (capture_layer, capture_handle) = reload::Layer::new(CaptureLayer::None);
+    let subscriber = Registry::default()
+        .with(capture_layer)
+        .with(
+            RecordingLayer {
+                recorded: recorded.clone(),
+            }
+            .with_filter(LevelFilter::INFO),
+        )
+        .with(fmt::layer().with_filter(console_filter));
+
+    if let Err(e) = tracing_log::LogTracer::builder()
+        .with_max_level(log_level.as_log())
+        .init()
+    {
+        eprintln!("Failed to forward log records to diagnostics: {}", e);
+    }
+    if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
+        eprintln!("Failed to install diagnostics: {}", e);
+    }
+
+    Diagnostics {
+        recorded,
+        capture_layer: capture_handle,
+        capture: Mutex::new(None),
+    }
+}
+
+/// Recent log events at `min_level` or above (default `info`), recorded span timings
+/// and per-span totals.
+#[tauri::command]
+pub async fn get_diagnostics(
+    min_level: Option<String>,
+    limit: Option<usize>,
+    diagnostics: State<'_, Diagnostics>,
+) -> Result<DiagnosticsReport, String> {
+    let min_level = match min_level {
+        Some(level) => {
+            Level::from_str(&level).map_err(|_| format!("Unknown log level: {}", level))?
+        }
+        None => Level::INFO,
+    };
+    let mut report = diagnostics
+        .recorded
+        .lock()
+        .map_err(|_| "Diagnostics are unavailable".to_string())?
+        .report(min_level, limit.unwrap_or(DEFAULT_EVENT_LIMIT));
+    report.trace_capture = diagnostics.capture_path();
+    Ok(report)
+}
+
+/// Start writing spans to a trace file in the app data `traces` folder, returning its
+/// path. Only one capture runs at a time.
+#[tauri::command]
+pub async fn start_trace_capture(
+    format: Option<TraceFormat>,
+    diagnostics: State<'_, Diagnostics>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let directory = assets::morgana_directory(&app_handle)?.join(TRACES_DIRECTORY);
+    let path = diagnostics.start_capture(&directory, format.unwrap_or_default())?;
+    log::info!("Capturing trace to {}", path);
+    Ok(path)
+}
+
+/// Stop the running capture and flush its file, returning the file's path.
+#[tauri::command]
+pub async fn stop_trace_capture(
+    diagnostics: State<'_, Diagnostics>,
+) -> Result<Option<String>, String> {
+    let path = diagnostics.stop_capture()?;
+    if let Some(path) = &path {
+        log::info!("Trace capture written to {}", path);
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_events_and_span_timings() {
+        let recorded = Arc::new(Mutex::new(Recorded::default()));
+        let subscriber = Registry::default().with(RecordingLayer {
+            recorded: recorded.clone(),
+        });
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("generate", width = 64).in_scope(|| {
+                tracing::info_span!("place_rooms").in_scope(|| {
+                    tracing::info!(rooms = 12, "Placed rooms");
+                });
+                tracing::debug!("Corridors done");
+                tracing::warn!("Room too small");
+            });
+        });
+
+        let report = recorded.lock().unwrap().report(Level::INFO, 10);
+        assert_eq!(report.events.len(), 2);
+        assert_eq!(report.events[0].message, "Placed rooms rooms=12");
+        assert_eq!(report.events[0].spans, ["generate", "place_rooms"]);
+        let names: Vec<_> = report.spans.iter().map(|span| span.name.as_str()).collect();
+        assert_eq!(names, ["place_rooms", "generate"]);
+        assert_eq!(report.spans[0].parent.as_deref(), Some("generate"));
+        assert_eq!(report.spans[1].fields, "width=64");
+        assert_eq!(report.stats.len(), 2);
+
+        let warnings = recorded.lock().unwrap().report(Level::WARN, 10);
+        assert_eq!(warnings.events.len(), 1);
+        assert_eq!(warnings.events[0].level, "WARN");
+        assert_eq!(
+            recorded
+                .lock()
+                .unwrap()
+                .report(Level::TRACE, 1)
+                .events
+                .len(),
+            1
+        );
+    }
+}
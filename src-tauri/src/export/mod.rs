@@ -1,5 +0,0 @@
-pub mod formats;
-pub mod exporters;
-
-pub use formats::ExportFormat;
-pub use exporters::LevelExporter;
\ No newline at end of file
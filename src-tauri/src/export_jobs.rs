@@ -0,0 +1,109 @@
+//! Exports running in the background, so the frontend can follow and stop them.
+
+use log::{info, warn};
+use morgan_core::export::exporters::ExportProgress;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tauri::{Emitter, State};
+
+pub const EXPORT_PROGRESS_EVENT: &str = "export_progress";
+
+#[derive(Debug, Clone, Serialize)]
+struct ExportProgressEvent<'a> {
+    export_id: u64,
+    #[serde(flatten)]
+    progress: &'a ExportProgress,
+}
+
+/// The exports in progress, each with the flag that cancels it.
+#[derive(Default)]
+pub struct ExportJobs {
+    next_id: AtomicU64,
+    running: Mutex<HashMap<u64, Arc<AtomicBool>>>,
+}
+
+impl ExportJobs {
+    /// Registers an export, returning its ID and the flag its exporter polls.
+    pub fn start(&self) -> Result<(u64, Arc<AtomicBool>), String> {
+        let export_id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.running()?.insert(export_id, cancel.clone());
+        Ok((export_id, cancel))
+    }
+
+    pub fn finish(&self, export_id: u64) -> Result<(), String> {
+        self.running()?.remove(&export_id);
+        Ok(())
+    }
+
+    /// Cancels an export, or every running one without an ID, returning how many were
+    /// running.
+    pub fn cancel(&self, export_id: Option<u64>) -> Result<usize, String> {
+        let running = self.running()?;
+        if let Some(export_id) = export_id {
+            let cancel = running
+                .get(&export_id)
+                .ok_or_else(|| format!("No running export with ID {}", export_id))?;
+            cancel.store(true, Ordering::Relaxed);
+            Ok(1)
+        } else {
+            for cancel in running.values() {
+                cancel.store(true, Ordering::Relaxed);
+            }
+            Ok(running.len())
+        }
+    }
+
+    fn running(&self) -> Result<MutexGuard<'_, HashMap<u64, Arc<AtomicBool>>>, String> {
+        self.running
+            .lock()
+            .map_err(|e| format!("Export jobs lock poisoned: {}", e))
+    }
+}
+
+/// Announces an export's progress as `export_progress`, tagged with its ID.
+pub fn emit_progress(app_handle: &tauri::AppHandle, export_id: u64, progress: &ExportProgress) {
+    let event = ExportProgressEvent {
+        export_id,
+        progress,
+    };
+    if let Err(e) = app_handle.emit(EXPORT_PROGRESS_EVENT, &event) {
+        warn!("Failed to emit export progress: {}", e);
+    }
+}
+
+/// Stops an export, or every running one without an ID. The format being written is
+/// left out; files already written stay.
+#[tauri::command]
+pub async fn cancel_export(
+    export_id: Option<u64>,
+    jobs: State<'_, ExportJobs>,
+) -> Result<usize, String> {
+    let cancelled = jobs.cancel(export_id)?;
+    info!("Cancelling {} export(s)", cancelled);
+    Ok(cancelled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancels_one_export_or_all() {
+        let jobs = ExportJobs::default();
+        let (first, first_cancel) = jobs.start().unwrap();
+        let (second, second_cancel) = jobs.start().unwrap();
+        assert_ne!(first, second);
+
+        assert_eq!(jobs.cancel(Some(first)), Ok(1));
+        assert!(first_cancel.load(Ordering::Relaxed));
+        assert!(!second_cancel.load(Ordering::Relaxed));
+
+        jobs.finish(first).unwrap();
+        assert!(jobs.cancel(Some(first)).is_err());
+        assert_eq!(jobs.cancel(None), Ok(1));
+        assert!(second_cancel.load(Ordering::Relaxed));
+    }
+}
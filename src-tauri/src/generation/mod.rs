@@ -1,7 +0,0 @@
-pub mod bsp;
-pub mod wfc;
-pub mod themes;
-
-// pub use bsp::BSPGenerator;
-// pub use wfc::WFCGenerator;
-// pub use themes::Theme;
\ No newline at end of file
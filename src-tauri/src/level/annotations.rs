@@ -0,0 +1,146 @@
+// Review annotations attached to objects or free positions in a level
+use super::events::{emit_level_changed, LevelChangeKind};
+use super::{read_current_level, with_current_level};
+use crate::{AppState, LevelData};
+use chrono::Utc;
+use log::info;
+pub use morgan_core::level::annotations::{Annotation, AnnotationAnchor, AnnotationReply};
+use tauri::{AppHandle, State};
+
+fn find_annotation<'a>(level: &'a mut LevelData, id: &str) -> Result<&'a mut Annotation, String> {
+    level
+        .annotations
+        .iter_mut()
+        .find(|a| a.id == id)
+        .ok_or_else(|| format!("Annotation not found: {}", id))
+}
+
+#[tauri::command]
+pub async fn add_annotation(
+    anchor: AnnotationAnchor,
+    author: String,
+    text: String,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<Annotation, String> {
+    let mut app_state = state.write().await;
+    let annotation = with_current_level(&mut app_state, |level| {
+        if let AnnotationAnchor::Object { ref object_id } = anchor {
+            if !level.objects.iter().any(|o| &o.id == object_id) {
+                return Err(format!("Object not found: {}", object_id));
+            }
+        }
+
+        let annotation = Annotation::new(anchor, &author, &text);
+        level.annotations.push(annotation.clone());
+        info!("Added annotation {} by {}", annotation.id, author);
+        Ok(annotation)
+    })?;
+
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::AnnotationsChanged,
+        annotation.anchor.object_ids(),
+    );
+    Ok(annotation)
+}
+
+#[tauri::command]
+pub async fn list_annotations(
+    include_resolved: Option<bool>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<Vec<Annotation>, String> {
+    let app_state = state.read().await;
+    let include_resolved = include_resolved.unwrap_or(true);
+    read_current_level(&app_state, |level| {
+        Ok(level
+            .annotations
+            .iter()
+            .filter(|a| include_resolved || !a.resolved)
+            .cloned()
+            .collect())
+    })
+}
+
+#[tauri::command]
+pub async fn update_annotation(
+    annotation_id: String,
+    text: Option<String>,
+    resolved: Option<bool>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<Annotation, String> {
+    let mut app_state = state.write().await;
+    let annotation = with_current_level(&mut app_state, |level| {
+        let annotation = find_annotation(level, &annotation_id)?;
+        if let Some(text) = text {
+            annotation.text = text;
+        }
+        if let Some(resolved) = resolved {
+            annotation.resolved = resolved;
+        }
+        annotation.updated_at = Utc::now();
+        Ok(annotation.clone())
+    })?;
+
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::AnnotationsChanged,
+        annotation.anchor.object_ids(),
+    );
+    Ok(annotation)
+}
+
+#[tauri::command]
+pub async fn reply_to_annotation(
+    annotation_id: String,
+    author: String,
+    text: String,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<Annotation, String> {
+    let mut app_state = state.write().await;
+    let annotation = with_current_level(&mut app_state, |level| {
+        let annotation = find_annotation(level, &annotation_id)?;
+        let now = Utc::now();
+        annotation.replies.push(AnnotationReply {
+            author,
+            text,
+            created_at: now,
+        });
+        annotation.updated_at = now;
+        Ok(annotation.clone())
+    })?;
+
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::AnnotationsChanged,
+        annotation.anchor.object_ids(),
+    );
+    Ok(annotation)
+}
+
+#[tauri::command]
+pub async fn delete_annotation(
+    annotation_id: String,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let mut app_state = state.write().await;
+    let removed = with_current_level(&mut app_state, |level| {
+        let index = level
+            .annotations
+            .iter()
+            .position(|a| a.id == annotation_id)
+            .ok_or_else(|| format!("Annotation not found: {}", annotation_id))?;
+        info!("Deleted annotation: {}", annotation_id);
+        Ok(level.annotations.remove(index))
+    })?;
+
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::AnnotationsChanged,
+        removed.anchor.object_ids(),
+    );
+    Ok(())
+}
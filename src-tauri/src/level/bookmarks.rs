@@ -0,0 +1,65 @@
+// Named camera viewpoints saved with the level
+use super::events::{emit_level_changed, LevelChangeKind};
+use super::{read_current_level, with_current_level};
+use crate::AppState;
+use log::info;
+pub use morgan_core::level::bookmarks::CameraBookmark;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn save_camera_bookmark(
+    bookmark: CameraBookmark,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<Vec<CameraBookmark>, String> {
+    if bookmark.name.trim().is_empty() {
+        return Err("Bookmark name cannot be empty".to_string());
+    }
+
+    let mut app_state = state.write().await;
+    let bookmarks = with_current_level(&mut app_state, |level| {
+        info!("Saving camera bookmark: {}", bookmark.name);
+        // Saving under an existing name replaces that viewpoint
+        match level
+            .camera_bookmarks
+            .iter_mut()
+            .find(|b| b.name == bookmark.name)
+        {
+            Some(existing) => *existing = bookmark,
+            None => level.camera_bookmarks.push(bookmark),
+        }
+        Ok(level.camera_bookmarks.clone())
+    })?;
+
+    emit_level_changed(&app_handle, LevelChangeKind::BookmarksChanged, Vec::new());
+    Ok(bookmarks)
+}
+
+#[tauri::command]
+pub async fn list_camera_bookmarks(
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<Vec<CameraBookmark>, String> {
+    let app_state = state.read().await;
+    read_current_level(&app_state, |level| Ok(level.camera_bookmarks.clone()))
+}
+
+#[tauri::command]
+pub async fn delete_camera_bookmark(
+    name: String,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let mut app_state = state.write().await;
+    with_current_level(&mut app_state, |level| {
+        let before = level.camera_bookmarks.len();
+        level.camera_bookmarks.retain(|b| b.name != name);
+        if level.camera_bookmarks.len() == before {
+            return Err(format!("Camera bookmark not found: {}", name));
+        }
+        info!("Deleted camera bookmark: {}", name);
+        Ok(())
+    })?;
+
+    emit_level_changed(&app_handle, LevelChangeKind::BookmarksChanged, Vec::new());
+    Ok(())
+}
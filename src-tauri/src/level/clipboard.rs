@@ -0,0 +1,227 @@
+// Copy and paste of objects between levels and sessions
+use super::events::{emit_level_changed, LevelChangeKind};
+use super::{read_current_level, with_current_level};
+use crate::{AppState, GameObject, LevelData, ObjectKind};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+/// Marks a clipboard string as copied objects, so pasting arbitrary text fails cleanly.
+const CLIPBOARD_FORMAT: &str = "morgan-bevy/objects";
+const CLIPBOARD_VERSION: u32 = 1;
+
+/// Copied objects as put on the system clipboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardPayload {
+    pub format: String,
+    pub version: u32,
+    /// Level the objects were copied from
+    #[serde(default)]
+    pub source_level: Option<String>,
+    pub objects: Vec<GameObject>,
+}
+
+/// The objects of `level` with the given IDs, in level order.
+pub fn copy_from(level: &LevelData, ids: &[String]) -> Result<ClipboardPayload, String> {
+    let wanted: HashSet<&str> = ids.iter().map(String::as_str).collect();
+    let objects: Vec<GameObject> = level
+        .objects
+        .iter()
+        .filter(|o| wanted.contains(o.id.as_str()))
+        .cloned()
+        .collect();
+    if let Some(missing) = ids.iter().find(|id| !objects.iter().any(|o| &o.id == *id)) {
+        return Err(format!("Object not found: {}", missing));
+    }
+    if objects.is_empty() {
+        return Err("No objects to copy".to_string());
+    }
+
+    Ok(ClipboardPayload {
+        format: CLIPBOARD_FORMAT.to_string(),
+        version: CLIPBOARD_VERSION,
+        source_level: Some(level.id.clone()),
+        objects,
+    })
+}
+
+pub fn parse_payload(payload: &str) -> Result<ClipboardPayload, String> {
+    let payload: ClipboardPayload =
+        serde_json::from_str(payload).map_err(|_| "Clipboard does not hold objects".to_string())?;
+    if payload.format != CLIPBOARD_FORMAT {
+        return Err("Clipboard does not hold objects".to_string());
+    }
+    if payload.version > CLIPBOARD_VERSION {
+        return Err(format!(
+            "Clipboard objects are from a newer version (format {})",
+            payload.version
+        ));
+    }
+    Ok(payload)
+}
+
+/// Adds copies of the payload's objects to `level` with fresh IDs, moved by `offset`.
+///
+/// References between pasted objects (zone targets, door switches) follow the copies.
+/// References to objects that aren't pasted are kept if `level` has them and dropped
+/// otherwise, so pasting into another level doesn't leave dangling IDs.
+pub fn paste_into(
+    level: &mut LevelData,
+    payload: ClipboardPayload,
+    offset: [f32; 3],
+) -> Vec<GameObject> {
+    let new_ids: HashMap<String, String> = payload
+        .objects
+        .iter()
+        .map(|o| (o.id.clone(), Uuid::new_v4().to_string()))
+        .collect();
+    let existing: HashSet<String> = level.objects.iter().map(|o| o.id.clone()).collect();
+    let remap = |id: &String| -> Option<String> {
+        new_ids
+            .get(id)
+            .cloned()
+            .or_else(|| existing.contains(id).then(|| id.clone()))
+    };
+
+    let mut pasted = Vec::with_capacity(payload.objects.len());
+    for mut object in payload.objects {
+        let new_id = new_ids[&object.id].clone();
+        object.id = new_id;
+        for (position, offset) in object.transform.position.iter_mut().zip(offset) {
+            *position += offset;
+        }
+        match object.kind {
+            ObjectKind::Zone(ref mut zone) => {
+                zone.targets = zone.targets.iter().filter_map(remap).collect();
+            }
+            ObjectKind::Door(ref mut door) => {
+                door.linked_switch_id = door.linked_switch_id.as_ref().and_then(remap);
+            }
+            _ => {}
+        }
+        if !level.layers.contains(&object.layer) {
+            level.layers.push(object.layer.clone());
+        }
+        pasted.push(object);
+    }
+    level.objects.extend(pasted.iter().cloned());
+    pasted
+}
+
+/// Serializes the given objects for the clipboard.
+#[tauri::command]
+pub async fn copy_objects(
+    ids: Vec<String>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<String, String> {
+    let app_state = state.read().await;
+    let payload = read_current_level(&app_state, |level| copy_from(level, &ids))?;
+    serde_json::to_string(&payload).map_err(|e| e.to_string())
+}
+
+/// Pastes objects from `copy_objects` into the current level, `offset` from where
+/// they were copied.
+#[tauri::command]
+pub async fn paste_objects(
+    payload: String,
+    offset: Option<[f32; 3]>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<Vec<GameObject>, String> {
+    let payload = parse_payload(&payload)?;
+
+    let mut guard = state.write().await;
+    let app_state = &mut *guard;
+    let pasted = with_current_level(app_state, |level| {
+        Ok(paste_into(level, payload, offset.unwrap_or([0.0; 3])))
+    })?;
+
+    for object in &pasted {
+        app_state
+            .spatial_index
+            .insert(&object.id, &object.transform);
+    }
+    info!("Pasted {} objects", pasted.len());
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::ObjectsAdded,
+        pasted.iter().map(|o| o.id.clone()).collect(),
+    );
+    Ok(pasted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, assert_close};
+    use morgan_core::level::zones::{ZoneProperties, ZoneShape, ZoneType};
+
+    fn object(id: &str, layer: &str, kind: ObjectKind) -> GameObject {
+        let mut object = GameObject {
+            kind,
+            ..testing::object(id, layer)
+        };
+        object.transform.position = [1.0, 0.0, 1.0];
+        object
+    }
+
+    fn level(id: &str, objects: Vec<GameObject>) -> LevelData {
+        LevelData {
+            id: id.to_string(),
+            layers: vec!["Default".to_string()],
+            ..testing::level(objects)
+        }
+    }
+
+    fn zone(targets: &[&str]) -> ObjectKind {
+        ObjectKind::Zone(ZoneProperties {
+            shape: ZoneShape::Sphere { radius: 1.0 },
+            zone_type: ZoneType::Trigger,
+            targets: targets.iter().map(|t| t.to_string()).collect(),
+            trigger_once: false,
+        })
+    }
+
+    #[test]
+    fn paste_remaps_ids_and_references() {
+        let source = level(
+            "source",
+            vec![
+                object("crate", "Props", ObjectKind::Mesh),
+                object("door", "Default", ObjectKind::Mesh),
+                object("trigger", "Zones", zone(&["crate", "door"])),
+            ],
+        );
+        let copied = copy_from(&source, &["trigger".to_string(), "crate".to_string()]).unwrap();
+        let text = serde_json::to_string(&copied).unwrap();
+        assert!(copy_from(&source, &["missing".to_string()]).is_err());
+
+        let mut target = level("target", vec![object("door", "Default", ObjectKind::Mesh)]);
+        let pasted = paste_into(&mut target, parse_payload(&text).unwrap(), [2.0, 0.0, 0.0]);
+        assert_eq!(pasted.len(), 2);
+        assert_eq!(target.objects.len(), 3);
+        assert!(pasted.iter().all(|o| o.id != "crate" && o.id != "trigger"));
+        assert_close(pasted[0].transform.position, [3.0, 0.0, 1.0]);
+        assert_eq!(target.layers, vec!["Default", "Props", "Zones"]);
+
+        // The copied crate is retargeted, the door exists here too and stays
+        let ObjectKind::Zone(ref zone) = pasted[1].kind else {
+            panic!("pasted trigger is not a zone");
+        };
+        assert_eq!(zone.targets, vec![pasted[0].id.clone(), "door".to_string()]);
+
+        // Pasting again gives another set of IDs; pasting elsewhere drops the door
+        let mut empty = level("empty", Vec::new());
+        let again = paste_into(&mut empty, parse_payload(&text).unwrap(), [0.0; 3]);
+        assert_ne!(again[0].id, pasted[0].id);
+        let ObjectKind::Zone(ref zone) = again[1].kind else {
+            panic!("pasted trigger is not a zone");
+        };
+        assert_eq!(zone.targets, vec![again[0].id.clone()]);
+
+        assert!(parse_payload("{\"objects\": []}").is_err());
+        assert!(parse_payload("not json").is_err());
+    }
+}
@@ -0,0 +1,85 @@
+// Prop scatter over the current level
+use super::events::{emit_level_changed, LevelChangeKind};
+use super::{read_current_level, with_current_level};
+use crate::assets;
+use crate::{AppState, GameObject};
+use log::{info, warn};
+use morgan_core::generation::decoration::{self, DecorationParams};
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+
+/// Meshes for props given as `(theme, prop name)`: the theme's first mesh variant for
+/// the prop, then the best-rated model in the asset database with the prop's name.
+/// Props with neither are left out.
+pub async fn prop_meshes(
+    app_handle: &AppHandle,
+    props: Vec<(Option<String>, String)>,
+) -> HashMap<String, String> {
+    let library = assets::theme_library();
+    let mut meshes = HashMap::new();
+    for (theme, name) in props {
+        if meshes.contains_key(&name) {
+            continue;
+        }
+        let variant = theme
+            .and_then(|theme| library.get_theme(&theme))
+            .and_then(|theme| theme.mesh_variants.get(&name)?.first().cloned());
+        let mesh = match variant {
+            Some(variant) => Some(variant),
+            None => assets::find_model_path(app_handle, name.clone())
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("No mesh lookup for prop {}: {}", name, e);
+                    None
+                }),
+        };
+        if let Some(mesh) = mesh {
+            meshes.insert(name, mesh);
+        }
+    }
+    meshes
+}
+
+/// Scatters the theme's props, or the ones in `params`, over the rooms of the current
+/// level. Works on generated and hand-edited levels alike; see [`prop_meshes`] for
+/// where meshes come from.
+#[tauri::command]
+pub async fn decorate_level(
+    params: DecorationParams,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<Vec<GameObject>, String> {
+    let (theme, props) = {
+        let app_state = state.read().await;
+        read_current_level(&app_state, |level| {
+            let props = params.props_for(level).map_err(|e| e.to_string())?;
+            Ok((params.theme_for(level).ok(), props))
+        })?
+    };
+    let props = props
+        .into_iter()
+        .filter(|p| p.mesh.is_none())
+        .map(|p| (theme.clone(), p.name))
+        .collect();
+    let meshes = prop_meshes(&app_handle, props).await;
+
+    let mut guard = state.write().await;
+    let app_state = &mut *guard;
+    let added = with_current_level(app_state, |level| {
+        decoration::decorate(level, &params, |prop| meshes.get(&prop.name).cloned())
+            .map_err(|e| e.to_string())
+    })?;
+
+    for object in &added {
+        app_state
+            .spatial_index
+            .insert(&object.id, &object.transform);
+    }
+    info!("Decorated level with {} props", added.len());
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::ObjectsAdded,
+        added.iter().map(|o| o.id.clone()).collect(),
+    );
+    Ok(added)
+}
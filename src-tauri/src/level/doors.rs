@@ -0,0 +1,44 @@
+// Structured door and gate behavior
+use super::events::{emit_level_changed, LevelChangeKind};
+use super::with_current_level;
+use crate::{AppState, GameObject, ObjectKind};
+use log::info;
+pub use morgan_core::level::doors::{migrate_legacy_doors, DoorProperties};
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn set_door_properties(
+    object_id: String,
+    door: DoorProperties,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<GameObject, String> {
+    let mut app_state = state.write().await;
+    let object = with_current_level(&mut app_state, |level| {
+        door.validate(level, &object_id)?;
+
+        let obj = level
+            .objects
+            .iter_mut()
+            .find(|o| o.id == object_id)
+            .ok_or_else(|| format!("Object not found: {}", object_id))?;
+        // Plain meshes can be promoted to doors; other gameplay objects cannot
+        if !matches!(obj.kind, ObjectKind::Mesh | ObjectKind::Door(_)) {
+            return Err(format!("Object cannot be a door: {}", object_id));
+        }
+
+        if !obj.tags.iter().any(|t| t == "door") {
+            obj.tags.push("door".to_string());
+        }
+        obj.kind = ObjectKind::Door(door);
+        info!("Updated door properties for: {}", object_id);
+        Ok(obj.clone())
+    })?;
+
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::ObjectsUpdated,
+        vec![object_id],
+    );
+    Ok(object)
+}
@@ -0,0 +1,37 @@
+// Change notifications sent to the frontend after level edits
+use log::warn;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// Event name the frontend listens on.
+pub const LEVEL_CHANGED_EVENT: &str = "level_changed";
+
+/// What part of the level a mutation touched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelChangeKind {
+    /// A whole new level was generated or loaded; panels should reload
+    LevelReplaced,
+    ObjectsAdded,
+    ObjectsUpdated,
+    AnnotationsChanged,
+    BookmarksChanged,
+}
+
+/// Payload of the `level_changed` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelChangedEvent {
+    pub kind: LevelChangeKind,
+    /// Objects affected by the change; empty for level-wide changes
+    pub object_ids: Vec<String>,
+}
+
+/// Notify listeners that the current level changed.
+pub fn emit_level_changed(app_handle: &AppHandle, kind: LevelChangeKind, object_ids: Vec<String>) {
+    crate::collab::share_local_change(app_handle, kind, &object_ids);
+    let event = LevelChangedEvent { kind, object_ids };
+    if let Err(e) = app_handle.emit(LEVEL_CHANGED_EVENT, &event) {
+        warn!("Failed to emit {} event: {}", LEVEL_CHANGED_EVENT, e);
+    }
+    crate::server::publish_event(app_handle, LEVEL_CHANGED_EVENT, &event);
+}
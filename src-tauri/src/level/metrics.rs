@@ -0,0 +1,249 @@
+// Walkability and pacing metrics computed from a level's tile objects
+use super::read_current_level;
+use crate::{AppState, LevelData};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::State;
+
+use morgan_core::level::connectivity::{
+    cell_of, check_connectivity, Cell, CellKind, CellMap, ConnectivityReport,
+};
+
+/// Tags used to find key markers when the caller does not provide any.
+const DEFAULT_MARKER_TAGS: &[&str] = &["spawn", "player_start", "exit", "key", "marker"];
+
+/// Length of the walkable run through `cell` along one axis of its floor.
+fn run_length(map: &CellMap, cell: Cell, step: (i32, i32)) -> u32 {
+    let (floor, x, z) = cell;
+    let mut length = 1;
+    for sign in [1, -1] {
+        let mut current = (floor, x + step.0 * sign, z + step.1 * sign);
+        while map.is_walkable(current) {
+            length += 1;
+            current = (floor, current.1 + step.0 * sign, current.2 + step.1 * sign);
+        }
+    }
+    length
+}
+
+/// Passage width at a cell: the narrower of its horizontal and vertical runs.
+fn passage_width(map: &CellMap, cell: Cell) -> u32 {
+    run_length(map, cell, (1, 0)).min(run_length(map, cell, (0, 1)))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomMetrics {
+    pub index: usize,
+    pub floor: i32,
+    pub walkable_area: f32,
+    pub min: [i32; 2],
+    pub max: [i32; 2],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WidthBucket {
+    pub width: u32,
+    pub count: usize,
+}
+
+/// Pacing metrics for charting in the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelMetrics {
+    pub total_walkable_area: f32,
+    pub room_area: f32,
+    pub corridor_area: f32,
+    pub corridor_to_room_ratio: f32,
+    pub rooms: Vec<RoomMetrics>,
+    /// Histogram of passage widths measured at corridor and door cells
+    pub choke_point_widths: Vec<WidthBucket>,
+    pub marker_count: usize,
+    pub average_marker_path_length: Option<f32>,
+    pub unreachable_marker_pairs: usize,
+}
+
+pub fn compute_metrics(level: &LevelData, marker_tags: &[String]) -> LevelMetrics {
+    let map = CellMap::from_level(level);
+    let count = |kind: CellKind| map.cells.values().filter(|&&k| k == kind).count();
+
+    // Stairs aren't rooms, so each room is on one floor
+    let rooms: Vec<RoomMetrics> = map
+        .regions(|k| k == CellKind::Room)
+        .iter()
+        .enumerate()
+        .map(|(index, room)| {
+            let min_x = room.iter().map(|c| c.1).min().unwrap_or(0);
+            let max_x = room.iter().map(|c| c.1).max().unwrap_or(0);
+            let min_z = room.iter().map(|c| c.2).min().unwrap_or(0);
+            let max_z = room.iter().map(|c| c.2).max().unwrap_or(0);
+            RoomMetrics {
+                index,
+                floor: room[0].0,
+                walkable_area: room.len() as f32,
+                min: [min_x, min_z],
+                max: [max_x, max_z],
+            }
+        })
+        .collect();
+
+    let room_area = count(CellKind::Room) as f32;
+    let corridor_area = count(CellKind::Corridor) as f32;
+
+    let mut widths: BTreeMap<u32, usize> = BTreeMap::new();
+    for (&cell, _) in map
+        .cells
+        .iter()
+        .filter(|(_, &k)| matches!(k, CellKind::Corridor | CellKind::Door))
+    {
+        *widths.entry(passage_width(&map, cell)).or_insert(0) += 1;
+    }
+
+    let markers: Vec<Cell> = level
+        .objects
+        .iter()
+        .filter(|o| o.tags.iter().any(|t| marker_tags.contains(t)))
+        .map(cell_of)
+        .collect();
+
+    let mut total_length = 0usize;
+    let mut reachable_pairs = 0usize;
+    let mut unreachable_pairs = 0usize;
+    for (i, &from) in markers.iter().enumerate() {
+        let distances = map.distances_from(from);
+        for &to in &markers[i + 1..] {
+            match distances.get(&to) {
+                Some(&d) => {
+                    total_length += d;
+                    reachable_pairs += 1;
+                }
+                None => unreachable_pairs += 1,
+            }
+        }
+    }
+
+    LevelMetrics {
+        total_walkable_area: map.cells.values().filter(|k| k.is_walkable()).count() as f32,
+        room_area,
+        corridor_area,
+        corridor_to_room_ratio: if room_area > 0.0 {
+            corridor_area / room_area
+        } else {
+            0.0
+        },
+        rooms,
+        choke_point_widths: widths
+            .into_iter()
+            .map(|(width, count)| WidthBucket { width, count })
+            .collect(),
+        marker_count: markers.len(),
+        average_marker_path_length: (reachable_pairs > 0)
+            .then(|| total_length as f32 / reachable_pairs as f32),
+        unreachable_marker_pairs: unreachable_pairs,
+    }
+}
+
+#[tauri::command]
+pub async fn compute_level_metrics(
+    marker_tags: Option<Vec<String>>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<LevelMetrics, String> {
+    let marker_tags =
+        marker_tags.unwrap_or_else(|| DEFAULT_MARKER_TAGS.iter().map(|t| t.to_string()).collect());
+
+    // Analyze a copy so edits aren't blocked while the grid is walked
+    let level = {
+        let app_state = state.read().await;
+        read_current_level(&app_state, |level| Ok(level.clone()))?
+    };
+    Ok(compute_metrics(&level, &marker_tags))
+}
+
+/// Check that every walkable tile of the current level can be reached, reporting
+/// isolated regions and unreachable rooms.
+#[tauri::command]
+pub async fn validate_level_connectivity(
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<ConnectivityReport, String> {
+    let level = {
+        let app_state = state.read().await;
+        read_current_level(&app_state, |level| Ok(level.clone()))?
+    };
+    Ok(check_connectivity(&level))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_close, floor_tile, level, tile};
+
+    #[test]
+    fn test_two_rooms_joined_by_corridor() {
+        let mut objects = Vec::new();
+        for x in 0..3 {
+            for z in 0..3 {
+                objects.push(tile(x as f32, z as f32, "floor"));
+                objects.push(tile(x as f32 + 6.0, z as f32, "floor"));
+            }
+        }
+        for x in 3..6 {
+            objects.push(tile(x as f32, 1.0, "corridor"));
+        }
+        objects.push(tile(0.0, 0.0, "spawn"));
+        objects.push(tile(8.0, 2.0, "exit"));
+
+        let metrics = compute_metrics(&level(objects), &["spawn".to_string(), "exit".to_string()]);
+
+        assert_eq!(metrics.rooms.len(), 2);
+        assert_close([metrics.room_area, metrics.corridor_area], [18.0, 3.0]);
+        assert_eq!(metrics.choke_point_widths.len(), 1);
+        assert_eq!(metrics.choke_point_widths[0].width, 1);
+        assert_close([metrics.average_marker_path_length.unwrap()], [10.0]);
+        assert_eq!(metrics.unreachable_marker_pairs, 0);
+    }
+
+    #[test]
+    fn test_walls_block_paths() {
+        let objects = vec![
+            tile(0.0, 0.0, "floor"),
+            tile(1.0, 0.0, "wall"),
+            tile(2.0, 0.0, "floor"),
+            tile(0.0, 0.0, "spawn"),
+            tile(2.0, 0.0, "exit"),
+        ];
+
+        let metrics = compute_metrics(&level(objects), &["spawn".to_string(), "exit".to_string()]);
+
+        assert_eq!(metrics.rooms.len(), 2);
+        assert_eq!(metrics.average_marker_path_length, None);
+        assert_eq!(metrics.unreachable_marker_pairs, 1);
+    }
+
+    #[test]
+    fn test_floors_stay_apart_and_join_by_stairs() {
+        let mut objects = Vec::new();
+        for x in 0..3 {
+            objects.push(floor_tile(x as f32, 0.0, 0, "floor"));
+            objects.push(floor_tile(x as f32, 0.0, 1, "floor"));
+        }
+        // A wall upstairs doesn't block the room below it
+        objects.push(floor_tile(1.0, 1.0, 0, "floor"));
+        objects.push(floor_tile(1.0, 1.0, 1, "wall"));
+        let mut stairs = floor_tile(2.0, 1.0, 0, "stairs");
+        stairs.metadata.insert("to_floor".to_string(), 1.into());
+        objects.push(stairs);
+        objects.push(floor_tile(0.0, 0.0, 0, "spawn"));
+        objects.push(floor_tile(0.0, 0.0, 1, "exit"));
+        objects.push(floor_tile(2.0, 1.0, 1, "floor"));
+
+        let metrics = compute_metrics(&level(objects), &["spawn".to_string(), "exit".to_string()]);
+
+        assert_eq!(metrics.rooms.len(), 2);
+        assert_eq!(metrics.rooms[0].floor, 0);
+        assert_close(
+            [metrics.rooms[0].walkable_area, metrics.room_area],
+            [4.0, 8.0],
+        );
+        // Across the ground floor, up the stairs and back along the first
+        assert_close([metrics.average_marker_path_length.unwrap()], [7.0]);
+        assert_eq!(metrics.unreachable_marker_pairs, 0);
+    }
+}
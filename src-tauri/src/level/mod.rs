@@ -0,0 +1,44 @@
+//! Editor-side level content that lives alongside the generated geometry.
+//!
+//! Each submodule holds the Tauri commands for one kind of authored data stored in
+//! [`LevelData`]; the data types themselves live in `morgan_core::level`.
+
+pub mod annotations;
+pub mod bookmarks;
+pub mod clipboard;
+pub mod decoration;
+pub mod doors;
+pub mod events;
+pub mod metrics;
+pub mod objects;
+pub mod overlaps;
+pub mod paths;
+pub mod physics;
+pub mod rooms;
+pub mod snapshots;
+pub mod spawns;
+pub mod zones;
+
+use crate::{AppState, LevelData};
+
+/// Run `f` against the currently loaded level, failing if no level is open.
+pub fn with_current_level<T>(
+    app_state: &mut AppState,
+    f: impl FnOnce(&mut LevelData) -> Result<T, String>,
+) -> Result<T, String> {
+    match app_state.current_level.as_mut() {
+        Some(level) => f(level),
+        None => Err("No level currently loaded".to_string()),
+    }
+}
+
+/// Read-only counterpart of [`with_current_level`] for commands holding a read lock.
+pub fn read_current_level<T>(
+    app_state: &AppState,
+    f: impl FnOnce(&LevelData) -> Result<T, String>,
+) -> Result<T, String> {
+    match app_state.current_level.as_ref() {
+        Some(level) => f(level),
+        None => Err("No level currently loaded".to_string()),
+    }
+}
@@ -0,0 +1,134 @@
+// Lightweight level responses and paginated object access for large levels
+use super::read_current_level;
+use crate::spatial::BoundingBox;
+use crate::{AppState, GameObject, LevelData};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tauri::State;
+
+/// Objects returned per page unless the caller asks for another size.
+const DEFAULT_PAGE_SIZE: usize = 500;
+/// Upper bound on a single page so one call can't pull the whole level.
+const MAX_PAGE_SIZE: usize = 5000;
+
+/// How much of a level a generate/load command sends back over IPC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseMode {
+    /// The complete `LevelData`
+    #[default]
+    Full,
+    /// Counts, bounds and the first page of objects; fetch the rest with `get_objects`
+    Summary,
+}
+
+/// One page of a level's objects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectPage {
+    pub objects: Vec<GameObject>,
+    pub offset: usize,
+    /// Number of objects matching the request across all pages
+    pub total: usize,
+    /// Offset of the next page, if there is one
+    pub next_offset: Option<usize>,
+}
+
+/// Everything about a level except the bulk of its objects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelSummary {
+    pub id: String,
+    pub name: String,
+    pub layers: Vec<String>,
+    pub generation_seed: Option<u64>,
+    pub bounds: BoundingBox,
+    pub object_count: usize,
+    pub annotation_count: usize,
+    pub first_page: ObjectPage,
+}
+
+/// Response of commands that replace the current level.
+///
+/// Untagged so the full form serializes exactly like `LevelData`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum LevelResponse {
+    Full(Box<LevelData>),
+    Summary(LevelSummary),
+}
+
+impl LevelResponse {
+    pub fn new(level: LevelData, mode: Option<ResponseMode>) -> Self {
+        match mode.unwrap_or_default() {
+            ResponseMode::Full => LevelResponse::Full(Box::new(level)),
+            ResponseMode::Summary => LevelResponse::Summary(summarize(&level)),
+        }
+    }
+}
+
+pub fn summarize(level: &LevelData) -> LevelSummary {
+    LevelSummary {
+        id: level.id.clone(),
+        name: level.name.clone(),
+        layers: level.layers.clone(),
+        generation_seed: level.generation_seed,
+        bounds: level.bounds.clone(),
+        object_count: level.objects.len(),
+        annotation_count: level.annotations.len(),
+        first_page: paginate(level.objects.iter(), 0, DEFAULT_PAGE_SIZE),
+    }
+}
+
+fn paginate<'a>(
+    objects: impl Iterator<Item = &'a GameObject>,
+    offset: usize,
+    limit: usize,
+) -> ObjectPage {
+    let matching: Vec<&GameObject> = objects.collect();
+    let total = matching.len();
+    let page: Vec<GameObject> = matching
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .cloned()
+        .collect();
+    let end = offset + page.len();
+
+    ObjectPage {
+        objects: page,
+        offset,
+        total,
+        next_offset: (end < total).then_some(end),
+    }
+}
+
+/// Page through the current level's objects, optionally restricted to IDs or a layer.
+#[tauri::command]
+pub async fn get_objects(
+    offset: Option<usize>,
+    limit: Option<usize>,
+    ids: Option<Vec<String>>,
+    layer: Option<String>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<ObjectPage, String> {
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let ids: Option<HashSet<String>> = ids.map(|ids| ids.into_iter().collect());
+
+    let app_state = state.read().await;
+    read_current_level(&app_state, |level| {
+        let objects = level.objects.iter().filter(|o| {
+            ids.as_ref().is_none_or(|ids| ids.contains(&o.id))
+                && layer.as_ref().is_none_or(|layer| &o.layer == layer)
+        });
+        Ok(paginate(objects, offset, limit))
+    })
+}
+
+#[tauri::command]
+pub async fn get_level_summary(
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<LevelSummary, String> {
+    let app_state = state.read().await;
+    read_current_level(&app_state, |level| Ok(summarize(level)))
+}
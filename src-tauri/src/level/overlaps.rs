@@ -0,0 +1,117 @@
+// Overlap report for checking a level before export
+use super::read_current_level;
+use crate::spatial::{Overlap, SpatialIndex};
+use crate::{AppState, LevelData};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tauri::State;
+
+/// Overlap, in world units, allowed before two objects are reported.
+const DEFAULT_TOLERANCE: f32 = 0.01;
+
+/// Overlapping objects grouped by layer. Pairs on two different layers are keyed by
+/// both names, sorted and joined with " / ", e.g. "Floors / Walls".
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OverlapReport {
+    pub tolerance: f32,
+    pub total: usize,
+    pub duplicates: usize,
+    pub by_layer: BTreeMap<String, Vec<Overlap>>,
+}
+
+fn layer_key(first: &str, second: &str) -> String {
+    match first.cmp(second) {
+        std::cmp::Ordering::Equal => first.to_string(),
+        std::cmp::Ordering::Less => format!("{} / {}", first, second),
+        std::cmp::Ordering::Greater => format!("{} / {}", second, first),
+    }
+}
+
+/// Finds objects in `level` overlapping by more than `tolerance` along every axis.
+pub fn find_overlaps(level: &LevelData, index: &SpatialIndex, tolerance: f32) -> OverlapReport {
+    let layers: HashMap<&str, &str> = level
+        .objects
+        .iter()
+        .map(|obj| (obj.id.as_str(), obj.layer.as_str()))
+        .collect();
+
+    let mut report = OverlapReport {
+        tolerance,
+        ..OverlapReport::default()
+    };
+    for overlap in index.overlapping_pairs(tolerance) {
+        // The index can hold objects from outside the level, such as helpers
+        let (Some(first), Some(second)) = (
+            layers.get(overlap.first.as_str()),
+            layers.get(overlap.second.as_str()),
+        ) else {
+            continue;
+        };
+        report.total += 1;
+        if overlap.duplicate {
+            report.duplicates += 1;
+        }
+        report
+            .by_layer
+            .entry(layer_key(first, second))
+            .or_default()
+            .push(overlap);
+    }
+    report
+}
+
+#[tauri::command]
+pub async fn detect_overlaps(
+    tolerance: Option<f32>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<OverlapReport, String> {
+    let tolerance = tolerance.unwrap_or(DEFAULT_TOLERANCE);
+    if !tolerance.is_finite() || tolerance < 0.0 {
+        return Err(format!("Invalid overlap tolerance: {}", tolerance));
+    }
+
+    let app_state = state.read().await;
+    read_current_level(&app_state, |level| {
+        Ok(find_overlaps(level, &app_state.spatial_index, tolerance))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{level, object};
+    use crate::GameObject;
+
+    fn block(id: &str, layer: &str, position: [f32; 3], scale: [f32; 3]) -> GameObject {
+        let mut block = object(id, layer);
+        block.transform.position = position;
+        block.transform.scale = scale;
+        block
+    }
+
+    #[test]
+    fn groups_overlaps_by_layer() {
+        let objects = vec![
+            block("floor", "Floors", [0.0, 0.0, 0.0], [1.0, 0.2, 1.0]),
+            block("wall", "Walls", [0.0, 0.5, 0.0], [1.0, 1.2, 0.2]),
+            block("crate", "Props", [3.0, 0.5, 0.0], [1.0, 1.0, 1.0]),
+            block("crate_copy", "Props", [3.0, 0.5, 0.0], [1.0, 1.0, 1.0]),
+        ];
+        let mut index = SpatialIndex::new();
+        for obj in &objects {
+            index.insert(&obj.id, &obj.transform);
+        }
+        // Not part of the level, so not reported
+        index.insert("gizmo", &objects[2].transform);
+        let level = level(objects);
+
+        let report = find_overlaps(&level, &index, DEFAULT_TOLERANCE);
+        assert_eq!(report.total, 2);
+        assert_eq!(report.duplicates, 1);
+        assert_eq!(
+            report.by_layer.keys().collect::<Vec<_>>(),
+            vec!["Floors / Walls", "Props"]
+        );
+        assert_eq!(report.by_layer["Props"][0].first, "crate");
+    }
+}
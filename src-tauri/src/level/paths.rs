@@ -0,0 +1,191 @@
+// Waypoint paths for AI patrol routes
+use super::events::{emit_level_changed, LevelChangeKind};
+use super::{read_current_level, with_current_level};
+use crate::{AppState, GameObject, LevelData, ObjectKind};
+use log::info;
+pub use morgan_core::level::paths::{PathMode, PathNode, PathPolyline, PathProperties};
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+/// Layer new paths are placed on unless the caller picks another one.
+const DEFAULT_PATH_LAYER: &str = "Paths";
+
+fn find_path<'a>(level: &'a mut LevelData, object_id: &str) -> Result<&'a mut GameObject, String> {
+    let obj = level
+        .objects
+        .iter_mut()
+        .find(|o| o.id == object_id)
+        .ok_or_else(|| format!("Object not found: {}", object_id))?;
+    if !matches!(obj.kind, ObjectKind::Path(_)) {
+        return Err(format!("Object is not a path: {}", object_id));
+    }
+    Ok(obj)
+}
+
+/// Apply `edit` to a path object's properties, re-validating and refitting its bounds.
+fn edit_path(
+    app_state: &mut AppState,
+    app_handle: &AppHandle,
+    object_id: &str,
+    edit: impl FnOnce(&mut PathProperties) -> Result<(), String>,
+) -> Result<GameObject, String> {
+    let object = with_current_level(app_state, |level| {
+        let obj = find_path(level, object_id)?;
+        let ObjectKind::Path(ref mut path) = obj.kind else {
+            unreachable!("find_path only returns path objects");
+        };
+
+        let mut edited = path.clone();
+        edit(&mut edited)?;
+        edited.validate()?;
+
+        obj.transform = edited.enclosing_transform();
+        *path = edited;
+        Ok(obj.clone())
+    })?;
+
+    app_state
+        .spatial_index
+        .update(&object.id, &object.transform);
+    emit_level_changed(
+        app_handle,
+        LevelChangeKind::ObjectsUpdated,
+        vec![object.id.clone()],
+    );
+    Ok(object)
+}
+
+#[tauri::command]
+pub async fn create_path(
+    name: String,
+    path: PathProperties,
+    layer: Option<String>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<GameObject, String> {
+    path.validate()?;
+
+    let mut guard = state.write().await;
+    let app_state = &mut *guard;
+    let object = with_current_level(app_state, |level| {
+        let layer = layer.unwrap_or_else(|| DEFAULT_PATH_LAYER.to_string());
+        if !level.layers.contains(&layer) {
+            level.layers.push(layer.clone());
+        }
+
+        let object = GameObject {
+            id: Uuid::new_v4().to_string(),
+            name,
+            transform: path.enclosing_transform(),
+            material: None,
+            mesh: None,
+            layer,
+            tags: vec!["path".to_string()],
+            metadata: HashMap::new(),
+            kind: ObjectKind::Path(path),
+            physics: None,
+        };
+        level.objects.push(object.clone());
+        Ok(object)
+    })?;
+
+    app_state
+        .spatial_index
+        .insert(&object.id, &object.transform);
+    info!("Created path: {} ({})", object.name, object.id);
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::ObjectsAdded,
+        vec![object.id.clone()],
+    );
+    Ok(object)
+}
+
+#[tauri::command]
+pub async fn insert_path_node(
+    object_id: String,
+    index: Option<usize>,
+    node: PathNode,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<GameObject, String> {
+    let mut app_state = state.write().await;
+    edit_path(&mut app_state, &app_handle, &object_id, |path| {
+        let index = index.unwrap_or(path.nodes.len());
+        if index > path.nodes.len() {
+            return Err(format!("Node index out of range: {}", index));
+        }
+        path.nodes.insert(index, node);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn update_path_node(
+    object_id: String,
+    index: usize,
+    node: PathNode,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<GameObject, String> {
+    let mut app_state = state.write().await;
+    edit_path(&mut app_state, &app_handle, &object_id, |path| {
+        let slot = path
+            .nodes
+            .get_mut(index)
+            .ok_or_else(|| format!("Node index out of range: {}", index))?;
+        *slot = node;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn remove_path_node(
+    object_id: String,
+    index: usize,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<GameObject, String> {
+    let mut app_state = state.write().await;
+    edit_path(&mut app_state, &app_handle, &object_id, |path| {
+        if index >= path.nodes.len() {
+            return Err(format!("Node index out of range: {}", index));
+        }
+        path.nodes.remove(index);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn set_path_mode(
+    object_id: String,
+    mode: PathMode,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<GameObject, String> {
+    let mut app_state = state.write().await;
+    edit_path(&mut app_state, &app_handle, &object_id, |path| {
+        path.mode = mode;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn get_path_polyline(
+    object_id: String,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<PathPolyline, String> {
+    let app_state = state.read().await;
+    read_current_level(&app_state, |level| {
+        let obj = level
+            .objects
+            .iter()
+            .find(|o| o.id == object_id)
+            .ok_or_else(|| format!("Object not found: {}", object_id))?;
+        match obj.kind {
+            ObjectKind::Path(ref path) => Ok(path.polyline()),
+            _ => Err(format!("Object is not a path: {}", object_id)),
+        }
+    })
+}
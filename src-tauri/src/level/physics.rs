@@ -0,0 +1,67 @@
+// Per-object rigid body and collider settings
+use super::events::{emit_level_changed, LevelChangeKind};
+use super::with_current_level;
+use crate::{AppState, GameObject};
+use log::info;
+pub use morgan_core::level::physics::{ColliderShape, PhysicsProperties};
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn set_object_physics(
+    object_id: String,
+    physics: PhysicsProperties,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<GameObject, String> {
+    physics.validate()?;
+
+    let mut app_state = state.write().await;
+    let object = with_current_level(&mut app_state, |level| {
+        let obj = level
+            .objects
+            .iter_mut()
+            .find(|o| o.id == object_id)
+            .ok_or_else(|| format!("Object not found: {}", object_id))?;
+        if physics.collider == Some(ColliderShape::Mesh) && obj.mesh.is_none() {
+            return Err(format!("Object has no mesh to collide with: {}", object_id));
+        }
+
+        obj.physics = Some(physics);
+        info!("Updated physics for: {}", object_id);
+        Ok(obj.clone())
+    })?;
+
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::ObjectsUpdated,
+        vec![object_id],
+    );
+    Ok(object)
+}
+
+#[tauri::command]
+pub async fn clear_object_physics(
+    object_id: String,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<GameObject, String> {
+    let mut app_state = state.write().await;
+    let object = with_current_level(&mut app_state, |level| {
+        let obj = level
+            .objects
+            .iter_mut()
+            .find(|o| o.id == object_id)
+            .ok_or_else(|| format!("Object not found: {}", object_id))?;
+
+        obj.physics = None;
+        info!("Cleared physics for: {}", object_id);
+        Ok(obj.clone())
+    })?;
+
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::ObjectsUpdated,
+        vec![object_id],
+    );
+    Ok(object)
+}
@@ -0,0 +1,18 @@
+// Room graph of generated levels
+use super::read_current_level;
+use crate::AppState;
+pub use morgan_core::level::rooms::RoomGraph;
+use tauri::State;
+
+/// Rooms of the current level and the corridors and stairs between them, as recorded by
+/// the generator.
+#[tauri::command]
+pub async fn get_room_graph(
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<RoomGraph, String> {
+    let app_state = state.read().await;
+    read_current_level(&app_state, |level| {
+        RoomGraph::from_level(level)
+            .ok_or_else(|| "Level has no room graph; only BSP levels record one".to_string())
+    })
+}
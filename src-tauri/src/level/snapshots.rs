@@ -0,0 +1,205 @@
+// Named restore points kept as compressed copies of the level
+use super::events::{emit_level_changed, LevelChangeKind};
+use super::objects::{LevelResponse, ResponseMode};
+use super::read_current_level;
+use crate::{assets, AppState, LevelData};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+const SNAPSHOT_EXTENSION: &str = ".json.gz";
+
+/// On-disk snapshot contents.
+#[derive(Debug, Serialize, Deserialize)]
+struct LevelSnapshot {
+    name: String,
+    created_at: DateTime<Utc>,
+    level: LevelData,
+}
+
+/// Snapshot listing entry, read from the file system without decompressing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+/// Snapshots live in the project next to the `Assets` folder, one directory per level.
+fn snapshot_directory(level_id: &str) -> Result<PathBuf, String> {
+    let project_root = assets::project_directory().ok_or("Project directory not found")?;
+    let level_dir: String = level_id
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Ok(project_root
+        .join(".morgan")
+        .join("snapshots")
+        .join(level_dir))
+}
+
+fn snapshot_path(level_id: &str, name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Snapshot name cannot be empty".to_string());
+    }
+    // Names become file names, so keep them to a safe character set
+    if name.starts_with('.')
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.'))
+    {
+        return Err(format!("Invalid snapshot name: {}", name));
+    }
+
+    Ok(snapshot_directory(level_id)?.join(format!("{}{}", name, SNAPSHOT_EXTENSION)))
+}
+
+fn current_level_id(app_state: &AppState) -> Result<String, String> {
+    read_current_level(app_state, |level| Ok(level.id.clone()))
+}
+
+/// Writes `snapshot` to `path`, returning its compressed and uncompressed sizes.
+fn write_snapshot(path: &Path, snapshot: &LevelSnapshot) -> Result<(usize, usize), String> {
+    if path.exists() {
+        return Err(format!("Snapshot already exists: {}", snapshot.name));
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create snapshot directory: {}", e))?;
+    }
+
+    let json =
+        serde_json::to_vec(snapshot).map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&json)
+        .map_err(|e| format!("Failed to compress snapshot: {}", e))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| format!("Failed to compress snapshot: {}", e))?;
+    fs::write(path, &compressed).map_err(|e| format!("Failed to write snapshot: {}", e))?;
+    Ok((compressed.len(), json.len()))
+}
+
+fn read_snapshot(path: &Path, name: &str) -> Result<LevelSnapshot, String> {
+    let compressed = fs::read(path).map_err(|_| format!("Snapshot not found: {}", name))?;
+    let mut json = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut json)
+        .map_err(|e| format!("Failed to decompress snapshot: {}", e))?;
+    serde_json::from_slice(&json).map_err(|e| format!("Failed to parse snapshot: {}", e))
+}
+
+#[tauri::command]
+pub async fn create_snapshot(
+    name: String,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<SnapshotInfo, String> {
+    // Work from a copy so compression and disk I/O happen outside the lock
+    let level = read_current_level(&*state.read().await, |level| Ok(level.clone()))?;
+
+    let path = snapshot_path(&level.id, &name)?;
+    let snapshot = LevelSnapshot {
+        name: name.trim().to_string(),
+        created_at: Utc::now(),
+        level,
+    };
+    let (snapshot, (compressed_len, json_len)) = assets::run_blocking(move || {
+        let sizes = write_snapshot(&path, &snapshot)?;
+        Ok((snapshot, sizes))
+    })
+    .await?;
+
+    info!(
+        "Created snapshot '{}' ({} bytes, {} uncompressed)",
+        snapshot.name, compressed_len, json_len
+    );
+    Ok(SnapshotInfo {
+        name: snapshot.name,
+        created_at: snapshot.created_at,
+        size_bytes: compressed_len as u64,
+    })
+}
+
+#[tauri::command]
+pub async fn restore_snapshot(
+    name: String,
+    response_mode: Option<ResponseMode>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<LevelResponse, String> {
+    let level_id = current_level_id(&*state.read().await)?;
+
+    let path = snapshot_path(&level_id, &name)?;
+    let snapshot = assets::run_blocking(move || read_snapshot(&path, &name)).await?;
+
+    let mut app_state = state.write().await;
+    app_state.spatial_index.clear();
+    for obj in &snapshot.level.objects {
+        app_state.spatial_index.insert(&obj.id, &obj.transform);
+    }
+    app_state.current_level = Some(snapshot.level.clone());
+
+    info!(
+        "Restored snapshot '{}' from {}",
+        snapshot.name, snapshot.created_at
+    );
+    emit_level_changed(&app_handle, LevelChangeKind::LevelReplaced, Vec::new());
+    Ok(LevelResponse::new(snapshot.level, response_mode))
+}
+
+#[tauri::command]
+pub async fn list_snapshots(
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<Vec<SnapshotInfo>, String> {
+    let level_id = current_level_id(&*state.read().await)?;
+
+    let dir = snapshot_directory(&level_id)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&dir).map_err(|e| format!("Failed to read snapshots: {}", e))?;
+    let mut snapshots: Vec<SnapshotInfo> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let name = file_name.strip_suffix(SNAPSHOT_EXTENSION)?.to_string();
+            let metadata = entry.metadata().ok()?;
+            Some(SnapshotInfo {
+                name,
+                created_at: metadata.modified().ok()?.into(),
+                size_bytes: metadata.len(),
+            })
+        })
+        .collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+    Ok(snapshots)
+}
+
+#[tauri::command]
+pub async fn delete_snapshot(
+    name: String,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<(), String> {
+    let level_id = current_level_id(&*state.read().await)?;
+
+    let path = snapshot_path(&level_id, &name)?;
+    fs::remove_file(&path).map_err(|_| format!("Snapshot not found: {}", name))?;
+    info!("Deleted snapshot: {}", name);
+    Ok(())
+}
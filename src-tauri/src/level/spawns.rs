@@ -0,0 +1,146 @@
+// Typed spawn points: player starts, enemy spawners, item spawns and level exits
+use super::events::{emit_level_changed, LevelChangeKind};
+use super::{read_current_level, with_current_level};
+use crate::{AppState, GameObject, LevelData, ObjectKind};
+use log::info;
+pub use morgan_core::generation::placement::{place_spawns, SpawnPlacement, SpawnPlacementParams};
+pub use morgan_core::level::spawns::{
+    new_spawn_object, validate_level_spawns, SpawnPointProperties, SpawnValidationReport,
+};
+use tauri::{AppHandle, State};
+
+/// Layer new spawn points are placed on unless the caller picks another one.
+const DEFAULT_SPAWN_LAYER: &str = "Spawns";
+
+fn has_other_player_start(level: &LevelData, except_id: Option<&str>) -> bool {
+    level.objects.iter().any(|o| {
+        Some(o.id.as_str()) != except_id
+            && matches!(
+                o.kind,
+                ObjectKind::SpawnPoint(SpawnPointProperties::PlayerStart)
+            )
+    })
+}
+
+#[tauri::command]
+pub async fn create_spawn_point(
+    name: String,
+    position: [f32; 3],
+    rotation: Option<[f32; 4]>,
+    spawn: SpawnPointProperties,
+    layer: Option<String>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<GameObject, String> {
+    spawn.validate()?;
+
+    let mut guard = state.write().await;
+    let app_state = &mut *guard;
+    let object = with_current_level(app_state, |level| {
+        if spawn == SpawnPointProperties::PlayerStart && has_other_player_start(level, None) {
+            return Err("Level already has a player start".to_string());
+        }
+
+        let layer = layer.unwrap_or_else(|| DEFAULT_SPAWN_LAYER.to_string());
+        if !level.layers.contains(&layer) {
+            level.layers.push(layer.clone());
+        }
+
+        let object = new_spawn_object(
+            name,
+            position,
+            rotation.unwrap_or([0.0, 0.0, 0.0, 1.0]),
+            spawn,
+            layer,
+        );
+        level.objects.push(object.clone());
+        Ok(object)
+    })?;
+
+    app_state
+        .spatial_index
+        .insert(&object.id, &object.transform);
+    info!("Created spawn point: {} ({})", object.name, object.id);
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::ObjectsAdded,
+        vec![object.id.clone()],
+    );
+    Ok(object)
+}
+
+#[tauri::command]
+pub async fn update_spawn_point(
+    object_id: String,
+    spawn: SpawnPointProperties,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<GameObject, String> {
+    spawn.validate()?;
+
+    let mut app_state = state.write().await;
+    let object = with_current_level(&mut app_state, |level| {
+        if spawn == SpawnPointProperties::PlayerStart
+            && has_other_player_start(level, Some(&object_id))
+        {
+            return Err("Level already has a player start".to_string());
+        }
+
+        let obj = level
+            .objects
+            .iter_mut()
+            .find(|o| o.id == object_id)
+            .ok_or_else(|| format!("Object not found: {}", object_id))?;
+        if !matches!(obj.kind, ObjectKind::SpawnPoint(_)) {
+            return Err(format!("Object is not a spawn point: {}", object_id));
+        }
+
+        obj.tags = vec!["spawn".to_string(), spawn.tag().to_string()];
+        obj.kind = ObjectKind::SpawnPoint(spawn);
+        info!("Updated spawn point: {}", object_id);
+        Ok(obj.clone())
+    })?;
+
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::ObjectsUpdated,
+        vec![object_id],
+    );
+    Ok(object)
+}
+
+#[tauri::command]
+pub async fn validate_spawn_points(
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<SpawnValidationReport, String> {
+    let app_state = state.read().await;
+    read_current_level(&app_state, |level| Ok(validate_level_spawns(level)))
+}
+
+/// Places a player start, enemy spawners and exits in the current level, keeping any
+/// player start it already has.
+#[tauri::command]
+pub async fn place_spawn_points(
+    params: SpawnPlacementParams,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<SpawnPlacement, String> {
+    let mut guard = state.write().await;
+    let app_state = &mut *guard;
+    let placement = with_current_level(app_state, |level| {
+        place_spawns(level, &params).map_err(|e| e.to_string())
+    })?;
+
+    for object in &placement.placed {
+        app_state
+            .spatial_index
+            .insert(&object.id, &object.transform);
+    }
+    info!("Placed {} spawn points", placement.placed.len());
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::ObjectsAdded,
+        placement.placed.iter().map(|o| o.id.clone()).collect(),
+    );
+    Ok(placement)
+}
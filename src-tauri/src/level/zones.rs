@@ -0,0 +1,102 @@
+// Trigger volumes and gameplay zones
+use super::events::{emit_level_changed, LevelChangeKind};
+use super::with_current_level;
+use crate::{AppState, GameObject, ObjectKind, Transform3D};
+use log::info;
+pub use morgan_core::level::zones::ZoneProperties;
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+/// Layer new zones are placed on unless the caller picks another one.
+const DEFAULT_ZONE_LAYER: &str = "Zones";
+
+#[tauri::command]
+pub async fn create_zone(
+    name: String,
+    position: [f32; 3],
+    zone: ZoneProperties,
+    layer: Option<String>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<GameObject, String> {
+    let mut guard = state.write().await;
+    let app_state = &mut *guard;
+    let object = with_current_level(app_state, |level| {
+        zone.validate(level)?;
+
+        let layer = layer.unwrap_or_else(|| DEFAULT_ZONE_LAYER.to_string());
+        if !level.layers.contains(&layer) {
+            level.layers.push(layer.clone());
+        }
+
+        let object = GameObject {
+            id: Uuid::new_v4().to_string(),
+            name,
+            transform: Transform3D {
+                position,
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                scale: zone.shape.scale(),
+            },
+            material: None,
+            mesh: None,
+            layer,
+            tags: vec!["zone".to_string(), zone.zone_type.label().to_string()],
+            metadata: HashMap::new(),
+            kind: ObjectKind::Zone(zone),
+            physics: None,
+        };
+        level.objects.push(object.clone());
+        Ok(object)
+    })?;
+
+    app_state
+        .spatial_index
+        .insert(&object.id, &object.transform);
+    info!("Created zone: {} ({})", object.name, object.id);
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::ObjectsAdded,
+        vec![object.id.clone()],
+    );
+    Ok(object)
+}
+
+#[tauri::command]
+pub async fn update_zone(
+    object_id: String,
+    zone: ZoneProperties,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<GameObject, String> {
+    let mut guard = state.write().await;
+    let app_state = &mut *guard;
+    let object = with_current_level(app_state, |level| {
+        zone.validate(level)?;
+
+        let obj = level
+            .objects
+            .iter_mut()
+            .find(|o| o.id == object_id)
+            .ok_or_else(|| format!("Object not found: {}", object_id))?;
+        if !matches!(obj.kind, ObjectKind::Zone(_)) {
+            return Err(format!("Object is not a zone: {}", object_id));
+        }
+
+        obj.transform.scale = zone.shape.scale();
+        obj.tags = vec!["zone".to_string(), zone.zone_type.label().to_string()];
+        obj.kind = ObjectKind::Zone(zone);
+        Ok(obj.clone())
+    })?;
+
+    app_state
+        .spatial_index
+        .update(&object.id, &object.transform);
+    info!("Updated zone: {}", object_id);
+    emit_level_changed(
+        &app_handle,
+        LevelChangeKind::ObjectsUpdated,
+        vec![object_id],
+    );
+    Ok(object)
+}
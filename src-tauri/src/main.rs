@@ -12,118 +12,39 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use log::{error, info};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tauri::State;
+use log::{error, info, warn};
+use tauri::{Manager, State};
 
 mod assets;
-mod export;
-mod generation;
-mod spatial;
+mod autosave;
+mod collab;
+mod diagnostics;
+mod export_jobs;
+mod level;
+mod project;
+mod scripting;
+mod server;
+mod snapping;
+mod tiled;
+mod wfc_debug;
 
 use assets::AssetDatabaseState;
-use export::{ExportFormat, LevelExporter};
+use export::{ExportFormat, ExportOptions, GltfMeshMode, LevelExporter};
 use generation::bsp::BSPGenerator;
+use generation::pipeline::{GenerationPipeline, StageReport};
+use generation::preview::{preview_level, render_floor, LevelPreview};
+use generation::themes::{Theme, ThemeLibrary, ThemeScan, TileDefinition};
 use generation::wfc::{WFCGenerationParams, WFCGenerator};
-use spatial::{BoundingBox, SpatialIndex};
-use std::path::PathBuf;
+use level::events::{emit_level_changed, LevelChangeKind};
+use level::objects::{LevelResponse, ResponseMode};
+use spatial::{BoundingBox, NearbyObject, RayHit, SpatialIndex};
+use std::path::{Path, PathBuf};
 
-use generation::themes::{Theme, ThemeLibrary};
-
-// Core data structures for level editing
-/// 3D transformation data for positioning, rotating, and scaling objects in 3D space.
-///
-/// Uses standard 3D graphics conventions with Y-up coordinate system.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Transform3D {
-    /// Position coordinates in 3D space [x, y, z] in world units
-    pub position: [f32; 3],
-    /// Rotation as quaternion [x, y, z, w] for smooth interpolation
-    pub rotation: [f32; 4], // quaternion [x, y, z, w]
-    /// Scale factors [x, y, z] for non-uniform scaling support
-    pub scale: [f32; 3],
-}
-
-/// Represents a 3D object in the editor with transform, material, and metadata.
-///
-/// GameObjects are the fundamental building blocks of levels in Morgan-Bevy.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GameObject {
-    /// Unique identifier for the object within the level
-    pub id: String,
-    /// Human-readable name displayed in the editor hierarchy
-    pub name: String,
-    /// 3D transformation (position, rotation, scale) data
-    pub transform: Transform3D,
-    /// Optional material reference for rendering
-    pub material: Option<String>,
-    /// Optional mesh reference for geometry
-    pub mesh: Option<String>,
-    /// Layer assignment for organization and visibility control
-    pub layer: String,
-    /// Tags for categorization and scripting hooks
-    pub tags: Vec<String>,
-    /// Additional metadata for custom properties and game logic
-    pub metadata: HashMap<String, serde_json::Value>,
-}
-
-/// Complete level data containing all objects, layers, and generation information.
-///
-/// This is the main data structure for saving and loading levels in Morgan-Bevy.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LevelData {
-    /// Unique identifier for the level
-    pub id: String,
-    /// Human-readable level name
-    pub name: String,
-    /// All game objects contained in this level
-    pub objects: Vec<GameObject>,
-    /// Layer names for organization and visibility control
-    pub layers: Vec<String>,
-    /// Random seed used for procedural generation (if applicable)
-    pub generation_seed: Option<u64>,
-    /// Parameters used for procedural generation algorithms
-    pub generation_params: Option<serde_json::Value>,
-    /// 3D bounding box defining the level's spatial extent
-    pub bounds: BoundingBox,
-}
-
-/// Project data for saving and loading complete editor sessions.
-///
-/// Contains versioning information and scene state for persistence.
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ProjectData {
-    /// Morgan-Bevy version used to create this project
-    pub version: String,
-    /// ISO timestamp of when the project was last saved
-    pub timestamp: String,
-    /// Complete scene data including objects, settings, and editor state
-    pub scene: serde_json::Value,
-}
-
-/// Parameters for Binary Space Partitioning (BSP) level generation.
-///
-/// Controls the procedural generation of rooms and corridors using BSP algorithm.
-#[derive(Debug, Serialize, Deserialize)]
-pub struct BSPGenerationParams {
-    /// Level width in grid units
-    pub width: u32,
-    /// Level height in grid units
-    pub height: u32,
-    /// Level depth/floors for multi-story generation
-    pub depth: u32,
-    /// Minimum room size to prevent tiny rooms
-    pub min_room_size: u32,
-    /// Maximum room size to prevent oversized rooms
-    pub max_room_size: u32,
-    /// Width of corridors connecting rooms
-    pub corridor_width: u32,
-    /// Theme name determining tiles, materials, and styling
-    pub theme: String,
-    /// Optional random seed for reproducible generation
-    pub seed: Option<u64>,
-}
+// Level data, generation and export live in the GUI-free core crate
+#[cfg(test)]
+use morgan_core::testing;
+use morgan_core::{export, generation, spatial};
+use morgan_core::{BSPGenerationParams, GameObject, LevelData, ObjectKind, Transform3D};
 
 // Application state
 /// Global application state managed by Tauri for the Morgan-Bevy editor.
@@ -152,13 +73,76 @@ impl Default for AppState {
 #[tauri::command]
 async fn get_available_themes() -> Result<Vec<Theme>, String> {
     info!("Getting available themes");
-    Ok(ThemeLibrary::get_all_themes())
+    Ok(assets::theme_library().get_all_themes())
+}
+
+/// Copies a JSON or RON theme file into the user's themes, or the project's when
+/// `project` is set, so it can be picked like a built-in theme.
+#[tauri::command]
+async fn install_theme(path: String, project: Option<bool>) -> Result<Theme, String> {
+    info!("Installing theme from {}", path);
+    let theme =
+        assets::theme_library().install_theme(Path::new(&path), project.unwrap_or(false))?;
+    info!("Installed theme {} ({})", theme.name, theme.id);
+    Ok(theme)
+}
+
+/// Reads every theme file again, reporting the ones that don't load or validate.
+#[tauri::command]
+async fn reload_themes() -> Result<ThemeScan, String> {
+    let scan = assets::theme_library().scan();
+    info!(
+        "Reloaded {} themes, {} theme files with problems",
+        scan.themes.len(),
+        scan.problems.len()
+    );
+    Ok(scan)
+}
+
+/// Adds a new theme to the user's themes.
+#[tauri::command]
+async fn create_theme(theme: Theme) -> Result<Theme, String> {
+    let path = assets::theme_library().create_theme(&theme)?;
+    info!("Created theme {} at {:?}", theme.id, path);
+    Ok(theme)
+}
+
+/// Copies a theme, built-in or not, as a new theme the user can edit.
+#[tauri::command]
+async fn duplicate_theme(
+    theme_id: String,
+    new_id: String,
+    name: Option<String>,
+) -> Result<Theme, String> {
+    let theme = assets::theme_library().duplicate_theme(&theme_id, &new_id, name)?;
+    info!("Duplicated theme {} as {}", theme_id, new_id);
+    Ok(theme)
+}
+
+/// Adds or replaces one of a theme's tiles, or removes it when `tile` is left out.
+/// Editing a built-in theme saves a copy to the user's themes that overrides it.
+#[tauri::command]
+async fn update_theme_tile(
+    theme_id: String,
+    tile_key: String,
+    tile: Option<TileDefinition>,
+) -> Result<Theme, String> {
+    info!("Updating tile {} of theme {}", tile_key, theme_id);
+    assets::theme_library().update_theme_tile(&theme_id, &tile_key, tile)
+}
+
+/// Deletes a theme's files; a built-in theme goes back to how it shipped.
+#[tauri::command]
+async fn delete_theme(theme_id: String) -> Result<(), String> {
+    let removed = assets::theme_library().delete_theme(&theme_id)?;
+    info!("Deleted theme {} ({:?})", theme_id, removed);
+    Ok(())
 }
 
 #[tauri::command]
 async fn get_theme_by_id(theme_id: String) -> Result<Theme, String> {
     info!("Getting theme by ID: {}", theme_id);
-    match ThemeLibrary::get_theme(&theme_id) {
+    match assets::theme_library().get_theme(&theme_id) {
         Some(theme) => Ok(theme),
         None => Err(format!("Theme not found: {}", theme_id)),
     }
@@ -167,7 +151,7 @@ async fn get_theme_by_id(theme_id: String) -> Result<Theme, String> {
 #[tauri::command]
 async fn get_theme_legend(theme_id: String) -> Result<String, String> {
     info!("Getting theme legend for: {}", theme_id);
-    match ThemeLibrary::get_theme(&theme_id) {
+    match assets::theme_library().get_theme(&theme_id) {
         Some(theme) => Ok(generation::themes::generate_theme_legend(&theme)),
         None => Err(format!("Theme not found: {}", theme_id)),
     }
@@ -179,7 +163,7 @@ async fn parse_grid_to_tiles(
     grid_string: String,
 ) -> Result<Vec<Vec<String>>, String> {
     info!("Parsing grid string to tiles for theme: {}", theme_id);
-    match ThemeLibrary::get_theme(&theme_id) {
+    match assets::theme_library().get_theme(&theme_id) {
         Some(theme) => Ok(generation::themes::parse_grid_string(&theme, &grid_string)),
         None => Err(format!("Theme not found: {}", theme_id)),
     }
@@ -191,26 +175,47 @@ async fn render_tiles_to_grid(
     tile_map: Vec<Vec<String>>,
 ) -> Result<String, String> {
     info!("Rendering tiles to grid string for theme: {}", theme_id);
-    match ThemeLibrary::get_theme(&theme_id) {
+    match assets::theme_library().get_theme(&theme_id) {
         Some(theme) => Ok(generation::themes::render_grid_string(&theme, &tile_map)),
         None => Err(format!("Theme not found: {}", theme_id)),
     }
 }
 
+/// Like `parse_grid_to_tiles`, for grids of comma-separated tile keys.
+#[tauri::command]
+async fn parse_token_grid_to_tiles(
+    theme_id: String,
+    grid_string: String,
+) -> Result<Vec<Vec<String>>, String> {
+    info!("Parsing token grid to tiles for theme: {}", theme_id);
+    match assets::theme_library().get_theme(&theme_id) {
+        Some(theme) => generation::themes::parse_token_grid(&theme, &grid_string),
+        None => Err(format!("Theme not found: {}", theme_id)),
+    }
+}
+
+#[tauri::command]
+async fn render_tiles_to_token_grid(tile_map: Vec<Vec<String>>) -> Result<String, String> {
+    Ok(generation::themes::render_token_grid(&tile_map))
+}
+
 // Level Generation Commands
 
 #[tauri::command]
+#[tracing::instrument(skip_all)]
 async fn generate_bsp_level(
     params: BSPGenerationParams,
-    state: State<'_, std::sync::Mutex<AppState>>,
-) -> Result<LevelData, String> {
+    response_mode: Option<ResponseMode>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<LevelResponse, String> {
     info!("Generating BSP level with params: {:?}", params);
 
     let generator = BSPGenerator::new();
     match generator.generate(params).await {
         Ok(level_data) => {
             // Update application state
-            let mut app_state = state.lock().unwrap();
+            let mut app_state = state.write().await;
             app_state.spatial_index.clear();
             for obj in &level_data.objects {
                 app_state.spatial_index.insert(&obj.id, &obj.transform);
@@ -221,7 +226,8 @@ async fn generate_bsp_level(
                 "Successfully generated level with {} objects",
                 level_data.objects.len()
             );
-            Ok(level_data)
+            emit_level_changed(&app_handle, LevelChangeKind::LevelReplaced, Vec::new());
+            Ok(LevelResponse::new(level_data, response_mode))
         }
         Err(e) => {
             error!("Failed to generate BSP level: {}", e);
@@ -231,17 +237,20 @@ async fn generate_bsp_level(
 }
 
 #[tauri::command]
+#[tracing::instrument(skip_all)]
 async fn generate_wfc_level(
     params: WFCGenerationParams,
-    state: State<'_, std::sync::Mutex<AppState>>,
-) -> Result<LevelData, String> {
+    response_mode: Option<ResponseMode>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<LevelResponse, String> {
     info!("Generating WFC level with params: {:?}", params);
 
     let mut generator = WFCGenerator::new();
     match generator.generate(params).await {
         Ok(level_data) => {
             // Update application state
-            let mut app_state = state.lock().unwrap();
+            let mut app_state = state.write().await;
             app_state.spatial_index.clear();
             for obj in &level_data.objects {
                 app_state.spatial_index.insert(&obj.id, &obj.transform);
@@ -252,7 +261,8 @@ async fn generate_wfc_level(
                 "Successfully generated WFC level with {} objects",
                 level_data.objects.len()
             );
-            Ok(level_data)
+            emit_level_changed(&app_handle, LevelChangeKind::LevelReplaced, Vec::new());
+            Ok(LevelResponse::new(level_data, response_mode))
         }
         Err(e) => {
             error!("Failed to generate WFC level: {}", e);
@@ -261,22 +271,172 @@ async fn generate_wfc_level(
     }
 }
 
+/// Generates a BSP level for each seed, all at once, and returns a summary of each with
+/// an ASCII map drawn in the theme's icons. Nothing is kept, so the UI can offer the
+/// seeds as a gallery and generate the chosen one with `generate_bsp_level`.
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+async fn preview_generation_seeds(
+    params: BSPGenerationParams,
+    seeds: Vec<u64>,
+) -> Result<Vec<LevelPreview>, String> {
+    info!(
+        "Previewing {} seeds for theme {}",
+        seeds.len(),
+        params.theme
+    );
+    let theme = assets::theme_library()
+        .get_theme(&params.theme)
+        .ok_or_else(|| format!("Theme not found: {}", params.theme))?;
+
+    let tasks: Vec<_> = seeds
+        .into_iter()
+        .map(|seed| {
+            let params = BSPGenerationParams {
+                seed: Some(seed),
+                ..params.clone()
+            };
+            tauri::async_runtime::spawn(async move { BSPGenerator::new().generate(params).await })
+        })
+        .collect();
+
+    let mut previews = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        let level = task.await.map_err(|e| e.to_string())?.map_err(|e| {
+            error!("Failed to generate seed preview: {}", e);
+            e.to_string()
+        })?;
+        previews.push(preview_level(&level, &theme));
+    }
+    Ok(previews)
+}
+
+/// Draws one floor of a level, the open one by default, as a grid of theme tile icons.
+/// The theme defaults to the one the level was generated with and the floor to the
+/// lowest.
+#[tauri::command]
+async fn render_level_preview(
+    level_data: Option<LevelData>,
+    floor: Option<i32>,
+    theme: Option<String>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<String, String> {
+    let app_state = state.read().await;
+    let level = match &level_data {
+        Some(level) => level,
+        None => app_state
+            .current_level
+            .as_ref()
+            .ok_or("No level currently loaded")?,
+    };
+    let theme_id = theme
+        .as_deref()
+        .or_else(|| generation::decoration::recorded_theme(level))
+        .ok_or("No theme given and the level wasn't generated with one")?;
+    let theme = assets::theme_library()
+        .get_theme(theme_id)
+        .ok_or_else(|| format!("Theme not found: {}", theme_id))?;
+    render_floor(level, &theme, floor).ok_or_else(|| match floor {
+        Some(floor) => format!("Level has no tiles on floor {}", floor),
+        None => "Level has no tiles".to_string(),
+    })
+}
+
+/// Result of `run_generation_pipeline`: the level and what each stage did.
+#[derive(Debug, serde::Serialize)]
+struct PipelineResponse {
+    level: LevelResponse,
+    stages: Vec<StageReport>,
+}
+
+/// Builds a level with a pipeline of generator stages written as JSON or RON, such as
+/// a BSP layout followed by WFC detail, decoration and validation.
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+async fn run_generation_pipeline(
+    pipeline: String,
+    response_mode: Option<ResponseMode>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<PipelineResponse, String> {
+    let pipeline = GenerationPipeline::parse(&pipeline).map_err(|e| e.to_string())?;
+    info!(
+        "Running generation pipeline with {} stages",
+        pipeline.stages.len()
+    );
+
+    let meshes = level::decoration::prop_meshes(&app_handle, pipeline.planned_props()).await;
+    let run = pipeline
+        .run(|prop| meshes.get(&prop.name).cloned())
+        .await
+        .map_err(|e| {
+            error!("Generation pipeline failed: {}", e);
+            e.to_string()
+        })?;
+
+    let mut app_state = state.write().await;
+    app_state.spatial_index.clear();
+    for obj in &run.level.objects {
+        app_state.spatial_index.insert(&obj.id, &obj.transform);
+    }
+    app_state.current_level = Some(run.level.clone());
+
+    info!(
+        "Generation pipeline built a level with {} objects",
+        run.level.objects.len()
+    );
+    emit_level_changed(&app_handle, LevelChangeKind::LevelReplaced, Vec::new());
+    Ok(PipelineResponse {
+        level: LevelResponse::new(run.level, response_mode),
+        stages: run.stages,
+    })
+}
+
 #[tauri::command]
+#[tracing::instrument(skip_all)]
 async fn export_level(
     level_data: LevelData,
     formats: Vec<ExportFormat>,
     output_path: String,
+    deterministic: Option<bool>,
+    gltf_meshes: Option<GltfMeshMode>,
+    options: Option<ExportOptions>,
+    app_handle: tauri::AppHandle,
 ) -> Result<export::exporters::ExportResult, String> {
     info!(
         "Exporting level to {:?} formats at path: {}",
         formats, output_path
     );
+    let level_data = assets::level_for_export(&app_handle, &level_data)?;
 
-    let exporter = LevelExporter::new();
-    match exporter
-        .export_multi_format(&level_data, &formats, &output_path)
-        .await
-    {
+    let jobs = app_handle.state::<export_jobs::ExportJobs>();
+    let (export_id, cancel) = jobs.start()?;
+    let handle = app_handle.clone();
+    let exporter = if deterministic.unwrap_or(false) {
+        LevelExporter::deterministic()
+    } else {
+        LevelExporter::new()
+    }
+    .with_gltf_meshes(gltf_meshes.unwrap_or_default())
+    .with_cancel(cancel)
+    .with_progress(Box::new(move |progress| {
+        export_jobs::emit_progress(&handle, export_id, progress);
+    }));
+    let options = options.unwrap_or_default();
+    // Writers are CPU and disk bound, so they get a thread of their own
+    let outcome = tauri::async_runtime::spawn_blocking(move || {
+        tauri::async_runtime::block_on(exporter.export_multi_format(
+            &level_data,
+            &formats,
+            &output_path,
+            &options,
+        ))
+    })
+    .await;
+    if let Err(e) = jobs.finish(export_id) {
+        warn!("Failed to clear finished export {}: {}", export_id, e);
+    }
+    match outcome.map_err(|e| format!("Export task failed: {}", e))? {
         Ok(export_result) => {
             info!(
                 "Successfully exported {} objects in {}ms",
@@ -290,6 +450,9 @@ async fn export_level(
                     );
                 }
             }
+            if export_result.cancelled {
+                info!("Export {} cancelled", export_id);
+            }
             Ok(export_result)
         }
         Err(e) => {
@@ -302,26 +465,70 @@ async fn export_level(
 #[tauri::command]
 async fn query_objects_in_bounds(
     bounds: BoundingBox,
-    state: State<'_, std::sync::Mutex<AppState>>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
 ) -> Result<Vec<String>, String> {
-    let app_state = state.lock().unwrap();
+    let app_state = state.read().await;
     let object_ids = app_state.spatial_index.query_bounds(&bounds);
     Ok(object_ids)
 }
 
+/// IDs of objects inside the viewport camera's frustum, so the frontend only draws
+/// what it can see.
+#[tauri::command]
+async fn query_frustum_objects(
+    planes: [[f32; 4]; 6],
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<Vec<String>, String> {
+    let app_state = state.read().await;
+    Ok(app_state.spatial_index.query_frustum(planes))
+}
+
+/// Objects under a picking ray, nearest first; unlimited range by default.
+#[tauri::command]
+async fn raycast_objects(
+    origin: [f32; 3],
+    direction: [f32; 3],
+    max_distance: Option<f32>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<Vec<RayHit>, String> {
+    let app_state = state.read().await;
+    Ok(app_state
+        .spatial_index
+        .raycast(origin, direction, max_distance.unwrap_or(f32::INFINITY)))
+}
+
+/// The `k` objects nearest a point, optionally within `radius`, for snapping and
+/// proximity selection.
+#[tauri::command]
+async fn query_nearest_objects(
+    point: [f32; 3],
+    k: usize,
+    radius: Option<f32>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<Vec<NearbyObject>, String> {
+    let app_state = state.read().await;
+    Ok(app_state.spatial_index.query_nearest(point, k, radius))
+}
+
 #[tauri::command]
 async fn update_object_transform(
     object_id: String,
     transform: Transform3D,
-    state: State<'_, std::sync::Mutex<AppState>>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    let mut app_state = state.lock().unwrap();
+    let mut app_state = state.write().await;
 
     if let Some(ref mut level) = app_state.current_level {
         if let Some(obj) = level.objects.iter_mut().find(|o| o.id == object_id) {
             obj.transform = transform.clone();
             app_state.spatial_index.update(&object_id, &transform);
             info!("Updated transform for object: {}", object_id);
+            emit_level_changed(
+                &app_handle,
+                LevelChangeKind::ObjectsUpdated,
+                vec![object_id],
+            );
             Ok(())
         } else {
             Err(format!("Object not found: {}", object_id))
@@ -333,51 +540,87 @@ async fn update_object_transform(
 
 #[tauri::command]
 async fn get_current_level(
-    state: State<'_, std::sync::Mutex<AppState>>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
 ) -> Result<Option<LevelData>, String> {
-    let app_state = state.lock().unwrap();
+    let app_state = state.read().await;
     Ok(app_state.current_level.clone())
 }
 
 #[tauri::command]
-async fn save_level_to_file(level_data: LevelData, file_path: String) -> Result<(), String> {
+#[tracing::instrument(skip_all)]
+async fn save_level_to_file(
+    mut level_data: LevelData,
+    file_path: String,
+    deterministic: Option<bool>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
     info!("Saving level to file: {}", file_path);
 
-    let json_data = serde_json::to_string_pretty(&level_data)
-        .map_err(|e| format!("Failed to serialize level data: {}", e))?;
+    // Store asset references relative to the project so the level stays portable
+    if let Some(resolver) = assets::paths::AssetPathResolver::for_project() {
+        let converted = resolver.make_portable(&mut level_data);
+        if converted > 0 {
+            info!(
+                "Converted {} absolute asset paths to project-relative",
+                converted
+            );
+        }
+    }
+
+    // Deterministic saves only change when the level does, for version control
+    let json_data = if deterministic.unwrap_or(false) {
+        morgan_core::stable::to_stable_json(&morgan_core::stable::stable_level(&level_data))
+    } else {
+        serde_json::to_string_pretty(&level_data)
+    }
+    .map_err(|e| format!("Failed to serialize level data: {}", e))?;
 
     std::fs::write(&file_path, json_data).map_err(|e| format!("Failed to write file: {}", e))?;
+    project::record_recent_level(&app_handle, Path::new(&file_path));
+    assets::track_level_usage(&app_handle, &file_path, level_data);
 
     info!("Successfully saved level to: {}", file_path);
     Ok(())
 }
 
 #[tauri::command]
+#[tracing::instrument(skip_all)]
 async fn load_level_from_file(
     file_path: String,
-    state: State<'_, std::sync::Mutex<AppState>>,
-) -> Result<LevelData, String> {
+    response_mode: Option<ResponseMode>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<LevelResponse, String> {
     info!("Loading level from file: {}", file_path);
 
     let file_content =
         std::fs::read_to_string(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
 
-    let level_data: LevelData = serde_json::from_str(&file_content)
+    let mut level_data: LevelData = serde_json::from_str(&file_content)
         .map_err(|e| format!("Failed to parse level data: {}", e))?;
 
+    let migrated_doors = level::doors::migrate_legacy_doors(&mut level_data);
+    if migrated_doors > 0 {
+        info!("Migrated {} legacy door objects", migrated_doors);
+    }
+
     // Update application state
-    let mut app_state = state.lock().unwrap();
+    let mut app_state = state.write().await;
     app_state.spatial_index.clear();
     for obj in &level_data.objects {
         app_state.spatial_index.insert(&obj.id, &obj.transform);
     }
     app_state.current_level = Some(level_data.clone());
+    // Levels saved by older versions have no usage recorded yet
+    project::record_recent_level(&app_handle, Path::new(&file_path));
+    assets::track_level_usage(&app_handle, &file_path, level_data.clone());
 
     info!(
         "Successfully loaded level with {} objects",
         level_data.objects.len()
     );
-    Ok(level_data)
+    emit_level_changed(&app_handle, LevelChangeKind::LevelReplaced, Vec::new());
+    Ok(LevelResponse::new(level_data, response_mode))
 }
 
 #[tauri::command]
@@ -385,13 +628,19 @@ async fn export_level_simple(
     level_data: LevelData,
     format: String,
     output_path: Option<String>,
+    app_handle: tauri::AppHandle,
 ) -> Result<String, String> {
     info!("Exporting level in format: {}", format);
+    let level_data = assets::level_for_export(&app_handle, &level_data)?;
 
     let export_format = match format.as_str() {
         "json" => ExportFormat::JSON,
         "ron" => ExportFormat::RON,
         "rust" => ExportFormat::RustCode,
+        "tscn" => ExportFormat::GodotScene,
+        "unity" => ExportFormat::Unity,
+        "collision_ron" => ExportFormat::CollisionRon,
+        "collision_json" => ExportFormat::CollisionJson,
         _ => return Err(format!("Unsupported export format: {}", format)),
     };
 
@@ -415,7 +664,12 @@ async fn export_level_simple(
 
     let exporter = LevelExporter::new();
     match exporter
-        .export_multi_format(&level_data, &[export_format], &base_path.to_string_lossy())
+        .export_multi_format(
+            &level_data,
+            &[export_format],
+            &base_path.to_string_lossy(),
+            &ExportOptions::default(),
+        )
         .await
     {
         Ok(result) => {
@@ -437,52 +691,6 @@ async fn export_level_simple(
     }
 }
 
-#[tauri::command]
-async fn save_project(project_data: ProjectData) -> Result<String, String> {
-    info!("Saving project");
-
-    use rfd::FileDialog;
-    let path = match FileDialog::new()
-        .add_filter("Morgan-Bevy Project", &["mbp"])
-        .set_file_name("project.mbp")
-        .save_file()
-    {
-        Some(path) => path,
-        None => return Err("Save cancelled by user".to_string()),
-    };
-
-    let json_data = serde_json::to_string_pretty(&project_data)
-        .map_err(|e| format!("Failed to serialize project: {}", e))?;
-
-    std::fs::write(&path, json_data).map_err(|e| format!("Failed to write project file: {}", e))?;
-
-    info!("Successfully saved project to: {:?}", path);
-    Ok(path.to_string_lossy().to_string())
-}
-
-#[tauri::command]
-async fn load_project() -> Result<ProjectData, String> {
-    info!("Loading project");
-
-    use rfd::FileDialog;
-    let path = match FileDialog::new()
-        .add_filter("Morgan-Bevy Project", &["mbp"])
-        .pick_file()
-    {
-        Some(path) => path,
-        None => return Err("Load cancelled by user".to_string()),
-    };
-
-    let json_data = std::fs::read_to_string(&path)
-        .map_err(|e| format!("Failed to read project file: {}", e))?;
-
-    let project_data: ProjectData = serde_json::from_str(&json_data)
-        .map_err(|e| format!("Failed to parse project file: {}", e))?;
-
-    info!("Successfully loaded project from: {:?}", path);
-    Ok(project_data)
-}
-
 #[tauri::command]
 async fn browse_for_texture() -> Result<Vec<String>, String> {
     info!("Browsing for texture files");
@@ -499,56 +707,150 @@ async fn browse_for_texture() -> Result<Vec<String>, String> {
         .set_title("Select Texture Files")
         .pick_files();
 
-    match paths {
-        Some(file_paths) => {
-            let path_strings: Vec<String> = file_paths
-                .iter()
-                .map(|p| p.to_string_lossy().to_string())
-                .collect();
-            info!("Selected {} texture file(s)", path_strings.len());
-            Ok(path_strings)
-        }
-        None => {
-            info!("Texture selection cancelled by user");
-            Ok(vec![])
-        }
+    if let Some(file_paths) = paths {
+        let path_strings: Vec<String> = file_paths
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        info!("Selected {} texture file(s)", path_strings.len());
+        Ok(path_strings)
+    } else {
+        info!("Texture selection cancelled by user");
+        Ok(vec![])
     }
 }
 
 fn main() {
-    env_logger::init();
+    let diagnostics = diagnostics::init();
     info!("Starting Morgan-Bevy Level Editor");
+    // Generators read themes the way the editor does, user and project themes included
+    ThemeLibrary::set_current_source(assets::theme_library);
 
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
-        .manage(std::sync::Mutex::new(AppState::default()))
+        .manage(tokio::sync::RwLock::new(AppState::default()))
         .manage(AssetDatabaseState::new())
+        .manage(server::ApiServerState::new())
+        .manage(collab::CollabState::new())
+        .manage(diagnostics)
+        .manage(wfc_debug::WfcDebugState::default())
+        .manage(export_jobs::ExportJobs::default())
         .invoke_handler(tauri::generate_handler![
             // Theme System
             get_available_themes,
             get_theme_by_id,
+            install_theme,
+            reload_themes,
+            create_theme,
+            duplicate_theme,
+            update_theme_tile,
+            delete_theme,
             get_theme_legend,
             parse_grid_to_tiles,
             render_tiles_to_grid,
+            parse_token_grid_to_tiles,
+            render_tiles_to_token_grid,
             // Level Generation
             generate_bsp_level,
             generate_wfc_level,
+            run_generation_pipeline,
+            preview_generation_seeds,
+            render_level_preview,
             // Export System
             export_level,
             export_level_simple,
+            export_jobs::cancel_export,
+            project::export_presets::save_export_preset,
+            project::export_presets::list_export_presets,
+            project::export_presets::delete_export_preset,
+            project::export_presets::export_with_preset,
             // Project Management
-            save_project,
-            load_project,
+            project::save_project,
+            project::load_project,
+            project::save_project_to_path,
+            project::load_project_from_path,
+            project::get_recent_projects,
+            project::get_recent_levels,
+            project::pin_project,
+            project::remove_recent_project,
+            project::clear_recent,
+            project::package_project,
+            project::unpack_project,
+            // Diagnostics
+            diagnostics::get_diagnostics,
+            diagnostics::start_trace_capture,
+            diagnostics::stop_trace_capture,
+            // WFC Debugging
+            wfc_debug::wfc_debug_start,
+            wfc_debug::wfc_debug_step,
+            wfc_debug::wfc_debug_snapshot,
             // File Operations
             browse_for_texture,
             // Spatial Queries
             query_objects_in_bounds,
+            raycast_objects,
+            query_nearest_objects,
+            query_frustum_objects,
+            // Snapping
+            snapping::snap_transform,
+            snapping::get_snap_settings,
+            snapping::set_snap_settings,
             update_object_transform,
             get_current_level,
             save_level_to_file,
             load_level_from_file,
+            tiled::import_tiled_map,
+            // Level Annotations
+            level::annotations::add_annotation,
+            level::annotations::list_annotations,
+            level::annotations::update_annotation,
+            level::annotations::reply_to_annotation,
+            level::annotations::delete_annotation,
+            // Camera Bookmarks
+            level::bookmarks::save_camera_bookmark,
+            level::bookmarks::list_camera_bookmarks,
+            level::bookmarks::delete_camera_bookmark,
+            // Level Analysis
+            level::metrics::compute_level_metrics,
+            level::metrics::validate_level_connectivity,
+            level::rooms::get_room_graph,
+            level::decoration::decorate_level,
+            level::overlaps::detect_overlaps,
+            // Clipboard
+            level::clipboard::copy_objects,
+            level::clipboard::paste_objects,
+            // Zones
+            level::zones::create_zone,
+            level::zones::update_zone,
+            // Spawn Points
+            level::spawns::create_spawn_point,
+            level::spawns::update_spawn_point,
+            level::spawns::validate_spawn_points,
+            level::spawns::place_spawn_points,
+            // Patrol Paths
+            level::paths::create_path,
+            level::paths::insert_path_node,
+            level::paths::update_path_node,
+            level::paths::remove_path_node,
+            level::paths::set_path_mode,
+            level::paths::get_path_polyline,
+            // Doors
+            level::doors::set_door_properties,
+            // Physics
+            level::physics::set_object_physics,
+            level::physics::clear_object_physics,
+            // Level Snapshots
+            level::snapshots::create_snapshot,
+            level::snapshots::restore_snapshot,
+            level::snapshots::list_snapshots,
+            level::snapshots::delete_snapshot,
+            autosave::list_recovery_snapshots,
+            autosave::restore_recovery_snapshot,
+            // Paginated Level Access
+            level::objects::get_objects,
+            level::objects::get_level_summary,
             // Legacy Asset System
             assets::scan_assets,
             assets::browse_assets_folder,
@@ -556,13 +858,66 @@ fn main() {
             // New Asset Database System
             assets::initialize_asset_database,
             assets::scan_assets_database,
+            assets::rescan_assets_database,
+            assets::start_scan,
+            assets::get_scan_status,
+            assets::cancel_scan,
+            assets::import_assets,
+            assets::list_kenney_packs,
+            assets::download_kenney_packs,
+            assets::get_asset_extensions,
+            assets::set_asset_extensions,
+            assets::list_asset_roots,
+            assets::add_asset_root,
+            assets::remove_asset_root,
+            assets::start_asset_watcher,
+            assets::stop_asset_watcher,
             assets::search_assets_database,
+            assets::search_assets_page,
+            assets::set_asset_rating,
+            assets::move_asset,
+            assets::rename_asset,
+            assets::delete_assets,
+            assets::get_asset_history,
+            assets::get_material_textures,
+            assets::find_materials_using_texture,
             assets::get_asset_database_stats,
-            assets::get_asset_collections
+            assets::get_asset_collections,
+            assets::get_asset_preview,
+            assets::get_asset_waveform,
+            assets::get_sprite_frames,
+            assets::get_sprite_frame,
+            assets::bind_theme_slot,
+            assets::get_lod_group,
+            assets::backup_asset_database,
+            assets::list_asset_database_backups,
+            assets::restore_asset_database,
+            assets::check_asset_database_integrity,
+            assets::get_asset_storage_report,
+            assets::clean_up_asset_storage,
+            assets::resolve_asset_path,
+            assets::export_asset_bundle,
+            assets::export_asset_catalog,
+            assets::check_asset_references,
+            assets::repair_asset_paths,
+            assets::collect_project_assets,
+            scripting::list_scripts,
+            scripting::run_script,
+            server::get_api_server_status,
+            server::configure_api_server,
+            collab::host_collab_session,
+            collab::join_collab_session,
+            collab::leave_collab_session,
+            collab::get_collab_status,
+            collab::update_collab_presence
         ])
         .setup(|app| {
             info!("Tauri application setup complete");
 
+            if let Err(e) = assets::init_user_themes(app.handle()) {
+                error!("User themes unavailable: {}", e);
+            }
+
             // Initialize asset database in the background
             let handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
@@ -573,6 +928,15 @@ fn main() {
                 }
             });
 
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = server::start_if_enabled(handle).await {
+                    error!("Failed to start API server: {}", e);
+                }
+            });
+
+            autosave::start(app.handle().clone());
+
             Ok(())
         })
         .run(tauri::generate_context!())
@@ -0,0 +1,687 @@
+//! Project files (`.mbp`) and the lists of recently opened projects and levels.
+//!
+//! Paths inside a project file are stored relative to the file's folder, with `/`
+//! separators, so a project kept in git opens the same on every machine. That takes in
+//! the textures scene objects were given from disk as well as levels. They are
+//! resolved to absolute paths when the project is loaded and made relative again when
+//! it is saved. The project opened or saved last becomes the current project, whose
+//! folder asset lookups and project-local files use.
+
+pub mod export_presets;
+pub mod package;
+
+use crate::assets::paths::ASSET_ID_PREFIX;
+use crate::assets::{self, AssetDatabaseState};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use package::{PackageSummary, UnpackSummary};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tauri::{AppHandle, Manager};
+
+const PROJECT_EXTENSION: &str = "mbp";
+pub const DEFAULT_ASSETS_DIRECTORY: &str = "Assets";
+/// Unpinned files kept in each recent list; pinned ones are always kept.
+const MAX_RECENT_FILES: usize = 10;
+
+/// Project data for saving and loading complete editor sessions.
+///
+/// Contains versioning information and scene state for persistence.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectData {
+    /// Morgan-Bevy version used to create this project
+    pub version: String,
+    /// ISO timestamp of when the project was last saved
+    pub timestamp: String,
+    /// Complete scene data including objects, settings, and editor state
+    pub scene: serde_json::Value,
+    /// Level files belonging to the project
+    #[serde(default)]
+    pub levels: Vec<String>,
+    /// Level to open along with the project
+    #[serde(default)]
+    pub active_level: Option<String>,
+    /// The project's asset folder; `Assets` beside the project file when unset
+    #[serde(default)]
+    pub assets_directory: Option<String>,
+}
+
+impl ProjectData {
+    fn map_paths(mut self, map: impl Fn(&str) -> String) -> Self {
+        self.levels = self.levels.iter().map(|level| map(level)).collect();
+        self.active_level = self.active_level.as_deref().map(&map);
+        self.assets_directory = self.assets_directory.as_deref().map(&map);
+        for path in scene_paths(&mut self.scene) {
+            *path = map(path);
+        }
+        self
+    }
+
+    /// The project as stored in a file in `base`.
+    pub fn relative_to(self, base: &Path) -> Self {
+        self.map_paths(|path| relative_path(Path::new(path), base))
+    }
+
+    /// The project as loaded from a file in `base`.
+    pub fn resolved_against(self, base: &Path) -> Self {
+        self.map_paths(|path| resolve_path(path, base).to_string_lossy().to_string())
+    }
+}
+
+/// Whether a scene reference is a file on disk, rather than a URL or asset ID.
+fn is_file_path(reference: &str) -> bool {
+    !reference.is_empty()
+        && !reference.contains("://")
+        && !reference.starts_with("data:")
+        && !reference.starts_with(ASSET_ID_PREFIX)
+}
+
+/// File paths held by objects in the editor's scene: their material textures.
+fn scene_paths(scene: &mut serde_json::Value) -> Vec<&mut String> {
+    let objects: Vec<&mut serde_json::Value> = match scene.get_mut("objects") {
+        Some(serde_json::Value::Object(objects)) => objects.values_mut().collect(),
+        Some(serde_json::Value::Array(objects)) => objects.iter_mut().collect(),
+        _ => Vec::new(),
+    };
+    objects
+        .into_iter()
+        .filter_map(|obj| match obj.pointer_mut("/material/texture") {
+            Some(serde_json::Value::String(path)) => Some(path),
+            _ => None,
+        })
+        .filter(|path| is_file_path(path))
+        .collect()
+}
+
+fn with_slashes(path: &Path) -> String {
+    path.components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// `path` relative to the folder `base`, with `/` separators. Relative paths are kept,
+/// as are absolute ones with nothing in common with `base` (another drive).
+pub fn relative_path(path: &Path, base: &Path) -> String {
+    if path.is_relative() {
+        return with_slashes(path);
+    }
+    let path_parts: Vec<_> = path.components().collect();
+    let base_parts: Vec<_> = base.components().collect();
+    let common = path_parts
+        .iter()
+        .zip(&base_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common == 0 {
+        return path.to_string_lossy().to_string();
+    }
+
+    let mut parts = vec!["..".to_string(); base_parts.len() - common];
+    parts.extend(
+        path_parts[common..]
+            .iter()
+            .map(|part| part.as_os_str().to_string_lossy().to_string()),
+    );
+    if parts.is_empty() {
+        ".".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+/// A path from a project file, resolved against the folder the file is in.
+pub fn resolve_path(stored: &str, base: &Path) -> PathBuf {
+    if Path::new(stored).is_absolute() {
+        return PathBuf::from(stored);
+    }
+    stored
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .fold(base.to_path_buf(), |path, part| path.join(part))
+}
+
+struct OpenProject {
+    file: PathBuf,
+    directory: PathBuf,
+    assets_directory: PathBuf,
+}
+
+static OPEN_PROJECT: RwLock<Option<OpenProject>> = RwLock::new(None);
+
+/// The current project file, if a project was opened or saved.
+pub fn open_project_file() -> Option<PathBuf> {
+    let open = OPEN_PROJECT.read().ok()?;
+    open.as_ref().map(|project| project.file.clone())
+}
+
+/// Folder of the current project file, if a project was opened or saved.
+pub fn open_project_directory() -> Option<PathBuf> {
+    let open = OPEN_PROJECT.read().ok()?;
+    open.as_ref().map(|project| project.directory.clone())
+}
+
+/// Asset folder of the current project, if a project was opened or saved.
+pub fn open_assets_directory() -> Option<PathBuf> {
+    let open = OPEN_PROJECT.read().ok()?;
+    open.as_ref()
+        .map(|project| project.assets_directory.clone())
+}
+
+/// Make the project at `file`, with paths already resolved, the current one.
+fn set_open_project(file: &Path, project: &ProjectData) {
+    let directory = file.parent().map(Path::to_path_buf).unwrap_or_default();
+    let assets_directory = project
+        .assets_directory
+        .as_ref()
+        .map_or_else(|| directory.join(DEFAULT_ASSETS_DIRECTORY), PathBuf::from);
+    if let Ok(mut open) = OPEN_PROJECT.write() {
+        *open = Some(OpenProject {
+            file: file.to_path_buf(),
+            directory,
+            assets_directory,
+        });
+    }
+}
+
+/// The recent lists, each kept in a file of its own in app data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecentList {
+    Projects,
+    Levels,
+}
+
+impl RecentList {
+    const ALL: [Self; 2] = [Self::Projects, Self::Levels];
+
+    const fn file_name(self) -> &'static str {
+        match self {
+            Self::Projects => "recent_projects.json",
+            Self::Levels => "recent_levels.json",
+        }
+    }
+}
+
+/// An entry in a recent list: a project file or a level file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentFile {
+    pub path: String,
+    pub name: String,
+    pub last_opened: DateTime<Utc>,
+    /// Pinned files stay at the top and never drop off the list
+    #[serde(default)]
+    pub pinned: bool,
+    /// Whether the file is still there, checked when the list is read
+    #[serde(default)]
+    pub exists: bool,
+}
+
+/// Pinned first, then most recently opened.
+fn sort_recent(projects: &mut [RecentFile]) {
+    projects.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then_with(|| b.last_opened.cmp(&a.last_opened))
+    });
+}
+
+/// Move `path` to the top of the list, keeping its pin, and drop the oldest unpinned
+/// projects beyond the limit.
+fn record_recent(projects: &mut Vec<RecentFile>, path: &Path, now: DateTime<Utc>) {
+    let key = path.to_string_lossy().to_string();
+    let pinned = projects.iter().any(|p| p.path == key && p.pinned);
+    projects.retain(|p| p.path != key);
+    projects.push(RecentFile {
+        name: path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| key.clone()),
+        path: key,
+        last_opened: now,
+        pinned,
+        exists: true,
+    });
+    sort_recent(projects);
+
+    let mut unpinned = 0;
+    projects.retain(|p| {
+        if !p.pinned {
+            unpinned += 1;
+        }
+        p.pinned || unpinned <= MAX_RECENT_FILES
+    });
+}
+
+/// Empty a recent list, keeping pinned files, or with `missing_only` drop just the
+/// files that no longer exist.
+fn clear_recent_files(files: &mut Vec<RecentFile>, missing_only: bool) {
+    if missing_only {
+        files.retain(|file| file.exists);
+    } else {
+        files.retain(|file| file.pinned);
+    }
+}
+
+fn recent_path(app_handle: &AppHandle, list: RecentList) -> Result<PathBuf, String> {
+    assets::morgana_directory(app_handle).map(|dir| dir.join(list.file_name()))
+}
+
+fn load_recent(path: &Path) -> Result<Vec<RecentFile>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read recent projects {:?}: {}", path, e))?;
+    let mut projects: Vec<RecentFile> = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse recent projects {:?}: {}", path, e))?;
+    for project in &mut projects {
+        project.exists = Path::new(&project.path).exists();
+    }
+    sort_recent(&mut projects);
+    Ok(projects)
+}
+
+fn save_recent(path: &Path, projects: &[RecentFile]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+    let json = serde_json::to_string_pretty(projects)
+        .map_err(|e| format!("Failed to serialize recent projects: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write recent projects {:?}: {}", path, e))
+}
+
+/// Change a recent list with `edit` and save it.
+fn update_recent(
+    app_handle: &AppHandle,
+    list: RecentList,
+    edit: impl FnOnce(&mut Vec<RecentFile>),
+) -> Result<Vec<RecentFile>, String> {
+    let path = recent_path(app_handle, list)?;
+    let mut projects = load_recent(&path)?;
+    edit(&mut projects);
+    save_recent(&path, &projects)?;
+    Ok(projects)
+}
+
+/// Current project and recent list bookkeeping after a project was opened or saved.
+fn opened(app_handle: &AppHandle, file: &Path, project: &ProjectData) {
+    set_open_project(file, project);
+    if let Err(e) = update_recent(app_handle, RecentList::Projects, |projects| {
+        record_recent(projects, file, Utc::now());
+    }) {
+        warn!("Failed to update recent projects: {}", e);
+    }
+}
+
+/// Put a level file that was just opened or saved at the top of the recent levels.
+pub fn record_recent_level(app_handle: &AppHandle, file: &Path) {
+    if let Err(e) = update_recent(app_handle, RecentList::Levels, |levels| {
+        record_recent(levels, file, Utc::now());
+    }) {
+        warn!("Failed to update recent levels: {}", e);
+    }
+}
+
+/// Write `project_data` to `path` with its paths made relative to the file's folder,
+/// returning the project with absolute paths.
+fn write_project(path: &Path, project_data: ProjectData) -> Result<ProjectData, String> {
+    let base = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let project_data = project_data.resolved_against(&base);
+
+    let stored = project_data.clone().relative_to(&base);
+    let json_data = serde_json::to_string_pretty(&stored)
+        .map_err(|e| format!("Failed to serialize project: {}", e))?;
+    fs::write(path, json_data).map_err(|e| format!("Failed to write project file: {}", e))?;
+    Ok(project_data)
+}
+
+/// The project stored at `path`, with absolute paths.
+fn read_project(path: &Path) -> Result<ProjectData, String> {
+    let json_data =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read project file: {}", e))?;
+    let project_data: ProjectData = serde_json::from_str(&json_data)
+        .map_err(|e| format!("Failed to parse project file: {}", e))?;
+    let base = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    Ok(project_data.resolved_against(&base))
+}
+
+/// Save the project to `path` and make it the current project.
+#[tauri::command]
+pub async fn save_project_to_path(
+    project_data: ProjectData,
+    path: String,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    info!("Saving project to: {}", path);
+    let path = PathBuf::from(path);
+    let project_data = write_project(&path, project_data)?;
+    opened(&app_handle, &path, &project_data);
+
+    info!("Successfully saved project to: {:?}", path);
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Open the project at `path`. Paths in the returned project are absolute.
+#[tauri::command]
+pub async fn load_project_from_path(
+    path: String,
+    app_handle: AppHandle,
+) -> Result<ProjectData, String> {
+    info!("Loading project from: {}", path);
+    let path = PathBuf::from(path);
+    let project_data = read_project(&path)?;
+    opened(&app_handle, &path, &project_data);
+
+    info!("Successfully loaded project from: {:?}", path);
+    Ok(project_data)
+}
+
+/// Save the project to `file_path`, or a location picked in a dialog when not given.
+#[tauri::command]
+pub async fn save_project(
+    project_data: ProjectData,
+    file_path: Option<String>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let path = if let Some(path) = file_path {
+        path
+    } else {
+        use rfd::FileDialog;
+        FileDialog::new()
+            .add_filter("Morgan-Bevy Project", &[PROJECT_EXTENSION])
+            .set_file_name("project.mbp")
+            .save_file()
+            .ok_or("Save cancelled by user")?
+            .to_string_lossy()
+            .to_string()
+    };
+    save_project_to_path(project_data, path, app_handle).await
+}
+
+/// Open the project at `file_path`, or one picked in a dialog when not given.
+#[tauri::command]
+pub async fn load_project(
+    file_path: Option<String>,
+    app_handle: AppHandle,
+) -> Result<ProjectData, String> {
+    let path = if let Some(path) = file_path {
+        path
+    } else {
+        use rfd::FileDialog;
+        FileDialog::new()
+            .add_filter("Morgan-Bevy Project", &[PROJECT_EXTENSION])
+            .pick_file()
+            .ok_or("Load cancelled by user")?
+            .to_string_lossy()
+            .to_string()
+    };
+    load_project_from_path(path, app_handle).await
+}
+
+#[tauri::command]
+pub async fn get_recent_projects(app_handle: AppHandle) -> Result<Vec<RecentFile>, String> {
+    load_recent(&recent_path(&app_handle, RecentList::Projects)?)
+}
+
+#[tauri::command]
+pub async fn get_recent_levels(app_handle: AppHandle) -> Result<Vec<RecentFile>, String> {
+    load_recent(&recent_path(&app_handle, RecentList::Levels)?)
+}
+
+#[tauri::command]
+pub async fn pin_project(
+    path: String,
+    pinned: bool,
+    app_handle: AppHandle,
+) -> Result<Vec<RecentFile>, String> {
+    update_recent(&app_handle, RecentList::Projects, |projects| {
+        if let Some(project) = projects.iter_mut().find(|p| p.path == path) {
+            project.pinned = pinned;
+        }
+        sort_recent(projects);
+    })
+}
+
+#[tauri::command]
+pub async fn remove_recent_project(
+    path: String,
+    app_handle: AppHandle,
+) -> Result<Vec<RecentFile>, String> {
+    update_recent(&app_handle, RecentList::Projects, |projects| {
+        projects.retain(|p| p.path != path);
+    })
+}
+
+/// Both recent lists, as left by `clear_recent`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentFiles {
+    pub projects: Vec<RecentFile>,
+    pub levels: Vec<RecentFile>,
+}
+
+/// Clear one recent list, or both when `list` isn't given. Pinned files are kept; with
+/// `missing_only` only files that no longer exist are removed, pinned or not.
+#[tauri::command]
+pub async fn clear_recent(
+    list: Option<RecentList>,
+    missing_only: Option<bool>,
+    app_handle: AppHandle,
+) -> Result<RecentFiles, String> {
+    let missing_only = missing_only.unwrap_or(false);
+    let mut cleared = RecentFiles {
+        projects: Vec::new(),
+        levels: Vec::new(),
+    };
+    for each in RecentList::ALL {
+        let files = if list.is_none_or(|list| list == each) {
+            update_recent(&app_handle, each, |files| {
+                clear_recent_files(files, missing_only);
+            })?
+        } else {
+            load_recent(&recent_path(&app_handle, each)?)?
+        };
+        match each {
+            RecentList::Projects => cleared.projects = files,
+            RecentList::Levels => cleared.levels = files,
+        }
+    }
+    info!("Cleared recent files");
+    Ok(cleared)
+}
+
+/// Pack a project with its levels, themes, prefabs, scripts and every asset its levels
+/// use into one zip. Packs the current project when `project_file` isn't given.
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn package_project(
+    project_file: Option<String>,
+    output_path: String,
+    app_handle: AppHandle,
+) -> Result<PackageSummary, String> {
+    let project_file = project_file
+        .map(PathBuf::from)
+        .or_else(open_project_file)
+        .ok_or("No project is open")?;
+    let reader = app_handle.state::<AssetDatabaseState>().reader.clone();
+
+    let summary = assets::run_blocking(move || {
+        let reader_guard = assets::lock(&reader)?;
+        package::write_package(
+            &project_file,
+            reader_guard.as_ref(),
+            Path::new(&output_path),
+        )
+    })
+    .await?;
+    info!(
+        "Packaged {} files ({} bytes) into {}",
+        summary.file_count, summary.total_bytes, summary.output_path
+    );
+    if !summary.missing.is_empty() {
+        warn!(
+            "{} project files could not be packaged",
+            summary.missing.len()
+        );
+    }
+    Ok(summary)
+}
+
+/// Unpack a project package into `destination`, which must not already hold any of its
+/// files. Open the unpacked project with `load_project`.
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn unpack_project(
+    archive_path: String,
+    destination: String,
+) -> Result<UnpackSummary, String> {
+    let summary = assets::run_blocking(move || {
+        package::unpack_package(Path::new(&archive_path), Path::new(&destination))
+    })
+    .await?;
+    info!(
+        "Unpacked {} files to {}",
+        summary.file_count, summary.project_file
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn stores_paths_relative_to_the_project() {
+        let base = Path::new("/work/game");
+        let project = ProjectData {
+            version: "1.0.0".to_string(),
+            timestamp: String::new(),
+            scene: serde_json::json!({
+                "objects": {
+                    "wall": { "material": { "texture": "/work/game/Assets/textures/brick.png" } },
+                    "sky": { "material": { "texture": "https://example.com/sky.png" } },
+                }
+            }),
+            levels: vec![
+                "/work/game/levels/crypt.json".to_string(),
+                "/work/shared/hub.json".to_string(),
+                "levels/already_relative.json".to_string(),
+            ],
+            active_level: Some("/work/game/levels/crypt.json".to_string()),
+            assets_directory: Some("/work/game".to_string()),
+        };
+
+        let stored = project.relative_to(base);
+        assert_eq!(
+            stored.levels,
+            [
+                "levels/crypt.json",
+                "../shared/hub.json",
+                "levels/already_relative.json"
+            ]
+        );
+        assert_eq!(stored.assets_directory.as_deref(), Some("."));
+        let texture = |project: &ProjectData, id: &str| {
+            let pointer = format!("/objects/{}/material/texture", id);
+            project
+                .scene
+                .pointer(&pointer)
+                .unwrap()
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(texture(&stored, "wall"), "Assets/textures/brick.png");
+        assert_eq!(texture(&stored, "sky"), "https://example.com/sky.png");
+
+        // Moving the project folder moves everything with it
+        let moved = stored.resolved_against(Path::new("/home/sam/game"));
+        assert_eq!(
+            PathBuf::from(texture(&moved, "wall")),
+            PathBuf::from("/home/sam/game/Assets/textures/brick.png")
+        );
+        assert_eq!(
+            moved.active_level.map(PathBuf::from),
+            Some(PathBuf::from("/home/sam/game/levels/crypt.json"))
+        );
+        assert_eq!(
+            PathBuf::from(&moved.levels[1]),
+            PathBuf::from("/home/sam/game/../shared/hub.json")
+        );
+        assert_eq!(
+            moved.assets_directory.map(PathBuf::from),
+            Some(PathBuf::from("/home/sam/game"))
+        );
+    }
+
+    #[test]
+    fn saves_and_loads_projects_by_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("game.mbp");
+        let crypt = dir.path().join("levels").join("crypt.json");
+        let project = ProjectData {
+            version: "1.0.0".to_string(),
+            timestamp: String::new(),
+            scene: serde_json::Value::Null,
+            levels: vec![crypt.to_string_lossy().to_string()],
+            active_level: None,
+            assets_directory: None,
+        };
+
+        let saved = write_project(&file, project).unwrap();
+        assert_eq!(PathBuf::from(&saved.levels[0]), crypt);
+        let stored: ProjectData =
+            serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(stored.levels, ["levels/crypt.json"]);
+
+        let loaded = read_project(&file).unwrap();
+        assert_eq!(PathBuf::from(&loaded.levels[0]), crypt);
+        assert!(read_project(&dir.path().join("missing.mbp")).is_err());
+    }
+
+    #[test]
+    fn keeps_pinned_and_recent_projects() {
+        let start = Utc::now();
+        let mut projects = Vec::new();
+        for i in 0..=MAX_RECENT_FILES {
+            let path = PathBuf::from(format!("/projects/p{}.mbp", i));
+            record_recent(&mut projects, &path, start + Duration::seconds(i as i64));
+        }
+        assert_eq!(projects.len(), MAX_RECENT_FILES);
+        assert_eq!(projects[0].name, format!("p{}", MAX_RECENT_FILES));
+        assert!(!projects.iter().any(|p| p.name == "p0"));
+
+        // A pinned project stays first and is never dropped
+        projects.last_mut().unwrap().pinned = true;
+        sort_recent(&mut projects);
+        let pinned = projects[0].path.clone();
+        for i in 0..MAX_RECENT_FILES {
+            let path = PathBuf::from(format!("/projects/q{}.mbp", i));
+            record_recent(
+                &mut projects,
+                &path,
+                start + Duration::minutes(i as i64 + 1),
+            );
+        }
+        assert_eq!(projects[0].path, pinned);
+        assert_eq!(projects.len(), MAX_RECENT_FILES + 1);
+
+        // Reopening keeps the pin
+        record_recent(&mut projects, Path::new(&pinned), start);
+        assert!(projects[0].pinned);
+
+        // Clearing keeps pinned files, unless they are gone
+        let mut missing = projects.clone();
+        missing[0].exists = false;
+        clear_recent_files(&mut missing, true);
+        assert_eq!(missing.len(), MAX_RECENT_FILES);
+        assert!(missing.iter().all(|p| p.path != pinned));
+        clear_recent_files(&mut projects, false);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].path, pinned);
+    }
+}
@@ -0,0 +1,209 @@
+// Named export settings saved with the project, so a repeated export is one click
+use super::{relative_path, resolve_path};
+use crate::assets;
+use crate::export::exporters::ExportResult;
+use crate::export::{ExportFormat, ExportOptions, GltfMeshMode};
+use crate::{AppState, LevelData};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+const PRESETS_FILE: &str = "export_presets.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportPreset {
+    pub name: String,
+    pub formats: Vec<ExportFormat>,
+    #[serde(default)]
+    pub options: ExportOptions,
+    /// Where files are written; stored relative to the project folder
+    pub output_directory: String,
+    #[serde(default)]
+    pub deterministic: bool,
+    #[serde(default)]
+    pub gltf_meshes: GltfMeshMode,
+}
+
+/// The project folder and its presets file under `.morgan`.
+fn presets_file() -> Result<(PathBuf, PathBuf), String> {
+    let project = assets::project_directory().ok_or("Project directory not found")?;
+    let file = project.join(".morgan").join(PRESETS_FILE);
+    Ok((project, file))
+}
+
+/// Presets stored in `path`, with output directories resolved against `base`.
+fn load_presets(path: &Path, base: &Path) -> Result<Vec<ExportPreset>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read export presets {:?}: {}", path, e))?;
+    let mut presets: Vec<ExportPreset> = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse export presets {:?}: {}", path, e))?;
+    for preset in &mut presets {
+        preset.output_directory = resolve_path(&preset.output_directory, base)
+            .to_string_lossy()
+            .to_string();
+    }
+    Ok(presets)
+}
+
+fn save_presets(path: &Path, base: &Path, presets: &[ExportPreset]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+    let stored: Vec<ExportPreset> = presets
+        .iter()
+        .cloned()
+        .map(|mut preset| {
+            preset.output_directory = relative_path(Path::new(&preset.output_directory), base);
+            preset
+        })
+        .collect();
+    let json = serde_json::to_string_pretty(&stored)
+        .map_err(|e| format!("Failed to serialize export presets: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write export presets {:?}: {}", path, e))
+}
+
+/// Saving under an existing name replaces that preset.
+fn upsert(presets: &mut Vec<ExportPreset>, preset: ExportPreset) {
+    match presets.iter_mut().find(|p| p.name == preset.name) {
+        Some(existing) => *existing = preset,
+        None => presets.push(preset),
+    }
+}
+
+#[tauri::command]
+pub async fn save_export_preset(preset: ExportPreset) -> Result<Vec<ExportPreset>, String> {
+    if preset.name.trim().is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+    if preset.formats.is_empty() {
+        return Err("A preset needs at least one export format".to_string());
+    }
+
+    let (project, file) = presets_file()?;
+    let mut presets = load_presets(&file, &project)?;
+    // Relative directories are taken as relative to the project
+    let mut preset = preset;
+    preset.output_directory = resolve_path(&preset.output_directory, &project)
+        .to_string_lossy()
+        .to_string();
+    info!("Saving export preset: {}", preset.name);
+    upsert(&mut presets, preset);
+    save_presets(&file, &project, &presets)?;
+    Ok(presets)
+}
+
+/// The project's presets, with absolute output directories.
+#[tauri::command]
+pub async fn list_export_presets() -> Result<Vec<ExportPreset>, String> {
+    let (project, file) = presets_file()?;
+    load_presets(&file, &project)
+}
+
+#[tauri::command]
+pub async fn delete_export_preset(name: String) -> Result<Vec<ExportPreset>, String> {
+    let (project, file) = presets_file()?;
+    let mut presets = load_presets(&file, &project)?;
+    let before = presets.len();
+    presets.retain(|p| p.name != name);
+    if presets.len() == before {
+        return Err(format!("Export preset not found: {}", name));
+    }
+    save_presets(&file, &project, &presets)?;
+    info!("Deleted export preset: {}", name);
+    Ok(presets)
+}
+
+/// Export a level, or else the open one, with a saved preset's formats, options and
+/// output directory.
+#[tauri::command]
+pub async fn export_with_preset(
+    name: String,
+    level_data: Option<LevelData>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: AppHandle,
+) -> Result<ExportResult, String> {
+    let (project, file) = presets_file()?;
+    let preset = load_presets(&file, &project)?
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("Export preset not found: {}", name))?;
+    let level = match level_data {
+        Some(level) => level,
+        None => state
+            .read()
+            .await
+            .current_level
+            .clone()
+            .ok_or("No level currently loaded")?,
+    };
+
+    info!("Exporting with preset: {}", preset.name);
+    crate::export_level(
+        level,
+        preset.formats,
+        preset.output_directory,
+        Some(preset.deterministic),
+        Some(preset.gltf_meshes),
+        Some(preset.options),
+        app_handle,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str, output_directory: &str) -> ExportPreset {
+        ExportPreset {
+            name: name.to_string(),
+            formats: vec![ExportFormat::RON],
+            options: ExportOptions::default(),
+            output_directory: output_directory.to_string(),
+            deterministic: true,
+            gltf_meshes: GltfMeshMode::default(),
+        }
+    }
+
+    #[test]
+    fn stores_output_directories_relative_to_the_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("game");
+        let file = project.join(".morgan").join(PRESETS_FILE);
+        let build = project.join("build").join("levels");
+
+        let mut presets = Vec::new();
+        upsert(
+            &mut presets,
+            preset("Bevy release", &build.to_string_lossy()),
+        );
+        upsert(&mut presets, preset("Debug", "/tmp/debug"));
+        let mut release = preset("Bevy release", &build.to_string_lossy());
+        release.formats.push(ExportFormat::CollisionRon);
+        upsert(&mut presets, release);
+        assert_eq!(presets.len(), 2);
+        save_presets(&file, &project, &presets).unwrap();
+
+        let stored: Vec<ExportPreset> =
+            serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(stored[0].output_directory, "build/levels");
+        assert_eq!(stored[0].formats.len(), 2);
+
+        // A moved project writes into its own build folder
+        let moved = dir.path().join("moved");
+        fs::create_dir_all(moved.join(".morgan")).unwrap();
+        fs::copy(&file, moved.join(".morgan").join(PRESETS_FILE)).unwrap();
+        let loaded = load_presets(&moved.join(".morgan").join(PRESETS_FILE), &moved).unwrap();
+        assert_eq!(
+            PathBuf::from(&loaded[0].output_directory),
+            moved.join("build").join("levels")
+        );
+        assert!(loaded[1].deterministic);
+    }
+}
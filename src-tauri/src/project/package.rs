@@ -0,0 +1,564 @@
+// Packing a whole project into one zip, and unpacking it on another machine
+use super::{scene_paths, with_slashes, ProjectData, DEFAULT_ASSETS_DIRECTORY};
+use crate::assets::bundle::{self, BundlePlan, BundledAsset};
+use crate::assets::database::AssetDatabase;
+use crate::assets::paths::AssetPathResolver;
+use crate::generation::themes::THEMES_DIRECTORY;
+use crate::scripting::SCRIPTS_DIRECTORY;
+use crate::LevelData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const PACKAGE_FORMAT_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+const PREFABS_DIRECTORY: &str = "prefabs";
+/// Project folders packed as they are, when the project has them.
+const PROJECT_FOLDERS: &[&str] = &[THEMES_DIRECTORY, PREFABS_DIRECTORY, SCRIPTS_DIRECTORY];
+/// Where levels from outside the project folder go.
+const EXTERNAL_LEVELS_DIR: &str = "levels/external";
+/// Where scene textures from outside the project folder go.
+const EXTERNAL_TEXTURES_DIR: &str = "textures/external";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageManifest {
+    pub format_version: u32,
+    pub project_name: String,
+    /// The project file, at the top of the archive
+    pub project_file: String,
+    pub created_at: DateTime<Utc>,
+    pub levels: Vec<String>,
+    /// Every file in the archive besides the manifest, checked when unpacking
+    pub files: Vec<BundledAsset>,
+    /// Levels and asset references that couldn't be packed
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageSummary {
+    pub output_path: String,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnpackSummary {
+    /// The unpacked project file
+    pub project_file: String,
+    pub file_count: usize,
+}
+
+/// Files to write besides the assets, keyed by their path in the archive.
+#[derive(Default)]
+struct PackagePlan {
+    documents: BTreeMap<String, Vec<u8>>,
+    copies: BTreeMap<String, PathBuf>,
+    missing: Vec<String>,
+}
+
+impl PackagePlan {
+    fn contains(&self, archive_path: &str) -> bool {
+        self.documents.contains_key(archive_path) || self.copies.contains_key(archive_path)
+    }
+
+    /// Archive path of a file: as it is in the project folder, or in `external_dir`
+    /// when it lives elsewhere.
+    fn archive_path(&self, path: &Path, base: &Path, external_dir: &str) -> String {
+        if let Some(relative) = path
+            .strip_prefix(base)
+            .ok()
+            .and_then(|relative| bundle::safe_relative(&with_slashes(relative)))
+        {
+            return relative;
+        }
+
+        let file_name = path.file_name().map_or_else(
+            || "file".to_string(),
+            |name| name.to_string_lossy().to_string(),
+        );
+        let mut candidate = format!("{}/{}", external_dir, file_name);
+        let mut n = 1;
+        while self.contains(&candidate) {
+            candidate = format!("{}/{}_{}", external_dir, n, file_name);
+            n += 1;
+        }
+        candidate
+    }
+
+    /// Plan a project folder's files, recursively.
+    fn add_folder(&mut self, dir: &Path, archive_dir: &str) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let archive_path = format!("{}/{}", archive_dir, entry.file_name().to_string_lossy());
+            if path.is_dir() {
+                self.add_folder(&path, &archive_path);
+            } else if path.is_file() {
+                self.copies.insert(archive_path, path);
+            }
+        }
+    }
+}
+
+/// The project's asset folder and its path in the archive.
+fn asset_folder(project: &ProjectData, base: &Path) -> (PathBuf, String) {
+    let assets_dir = project
+        .assets_directory
+        .as_ref()
+        .map_or_else(|| base.join(DEFAULT_ASSETS_DIRECTORY), PathBuf::from);
+    let archive_dir = assets_dir
+        .strip_prefix(base)
+        .ok()
+        .and_then(|relative| bundle::safe_relative(&with_slashes(relative)))
+        .unwrap_or_else(|| DEFAULT_ASSETS_DIRECTORY.to_string());
+    (assets_dir, archive_dir)
+}
+
+/// Write the project at `project_file`, its levels, project folders and every asset its
+/// levels reference to a zip at `output`.
+///
+/// The archive mirrors the project folder, so paths in the packed project file stay
+/// relative. Levels and assets from outside the folder are moved in and their
+/// references rewritten; anything missing is listed in the manifest.
+pub fn write_package(
+    project_file: &Path,
+    database: Option<&AssetDatabase>,
+    output: &Path,
+) -> Result<PackageSummary, String> {
+    let base = project_file
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let json = fs::read_to_string(project_file)
+        .map_err(|e| format!("Failed to read project file {:?}: {}", project_file, e))?;
+    let project: ProjectData = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse project file {:?}: {}", project_file, e))?;
+    let project = project.resolved_against(&base);
+
+    let (assets_dir, assets_archive_dir) = asset_folder(&project, &base);
+    let resolver = assets_dir
+        .is_dir()
+        .then(|| AssetPathResolver::new(&assets_dir));
+    let mut plan = PackagePlan::default();
+    let mut assets = BundlePlan::default();
+
+    let mut level_paths: HashMap<&String, String> = HashMap::new();
+    for level_path in project.levels.iter().chain(&project.active_level) {
+        if level_paths.contains_key(level_path) {
+            continue;
+        }
+        let archive_path = plan.archive_path(Path::new(level_path), &base, EXTERNAL_LEVELS_DIR);
+        let Ok(contents) = fs::read(level_path) else {
+            plan.missing.push(level_path.clone());
+            continue;
+        };
+        let contents = match (serde_json::from_slice::<LevelData>(&contents), &resolver) {
+            (Ok(level), Some(resolver)) => {
+                let level = assets.add_level(&level, resolver, database);
+                serde_json::to_vec_pretty(&level)
+                    .map_err(|e| format!("Failed to serialize level: {}", e))?
+            }
+            (Ok(_), None) => contents,
+            // Keep the file even if its asset references can't be read
+            (Err(e), _) => {
+                plan.missing
+                    .push(format!("Assets of {}: {}", level_path, e));
+                contents
+            }
+        };
+        plan.documents.insert(archive_path.clone(), contents);
+        level_paths.insert(level_path, archive_path);
+    }
+    plan.missing.append(&mut assets.missing);
+
+    for folder in PROJECT_FOLDERS {
+        plan.add_folder(&base.join(folder), folder);
+    }
+
+    let mut scene = project.scene.clone();
+    for texture in scene_paths(&mut scene) {
+        let path = PathBuf::from(texture.as_str());
+        if !path.is_file() {
+            plan.missing.push(texture.clone());
+            continue;
+        }
+        let archive_path = plan.archive_path(&path, &base, EXTERNAL_TEXTURES_DIR);
+        plan.copies.insert(archive_path.clone(), path);
+        *texture = archive_path;
+    }
+
+    let packed_project = ProjectData {
+        scene,
+        levels: project
+            .levels
+            .iter()
+            .filter_map(|level| level_paths.get(level).cloned())
+            .collect(),
+        active_level: project
+            .active_level
+            .as_ref()
+            .and_then(|level| level_paths.get(level).cloned()),
+        assets_directory: project
+            .assets_directory
+            .as_ref()
+            .map(|_| assets_archive_dir.clone()),
+        ..project.clone()
+    };
+    let project_name = project_file.file_name().map_or_else(
+        || "project.mbp".to_string(),
+        |name| name.to_string_lossy().to_string(),
+    );
+    let project_json = serde_json::to_vec_pretty(&packed_project)
+        .map_err(|e| format!("Failed to serialize project: {}", e))?;
+    plan.documents.insert(project_name.clone(), project_json);
+
+    let manifest = PackageManifest {
+        format_version: PACKAGE_FORMAT_VERSION,
+        project_name: project_file.file_stem().map_or_else(
+            || project_name.clone(),
+            |stem| stem.to_string_lossy().to_string(),
+        ),
+        project_file: project_name,
+        created_at: Utc::now(),
+        levels: packed_project.levels.clone(),
+        files: Vec::new(),
+        missing: plan.missing.clone(),
+    };
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+    let result = write_archive(&plan, &assets, &assets_archive_dir, manifest, output);
+    if result.is_err() {
+        let _ = fs::remove_file(output);
+    }
+    let manifest = result?;
+
+    Ok(PackageSummary {
+        output_path: output.to_string_lossy().to_string(),
+        file_count: manifest.files.len(),
+        total_bytes: manifest.files.iter().map(|file| file.size_bytes).sum(),
+        missing: manifest.missing,
+    })
+}
+
+fn write_entry<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    archive_path: &str,
+    contents: &[u8],
+    options: SimpleFileOptions,
+) -> Result<BundledAsset, String> {
+    zip.start_file(archive_path, options)
+        .map_err(|e| format!("Failed to write package: {}", e))?;
+    zip.write_all(contents)
+        .map_err(|e| format!("Failed to write package: {}", e))?;
+    Ok(BundledAsset {
+        path: archive_path.to_string(),
+        size_bytes: contents.len() as u64,
+        sha256: format!("{:x}", Sha256::digest(contents)),
+    })
+}
+
+fn write_archive(
+    plan: &PackagePlan,
+    assets: &BundlePlan,
+    assets_archive_dir: &str,
+    mut manifest: PackageManifest,
+    output: &Path,
+) -> Result<PackageManifest, String> {
+    let file = File::create(output).map_err(|e| format!("Failed to create {:?}: {}", output, e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (archive_path, contents) in &plan.documents {
+        manifest
+            .files
+            .push(write_entry(&mut zip, archive_path, contents, options)?);
+    }
+    for (archive_path, source) in &plan.copies {
+        let contents =
+            fs::read(source).map_err(|e| format!("Failed to read {:?}: {}", source, e))?;
+        manifest
+            .files
+            .push(write_entry(&mut zip, archive_path, &contents, options)?);
+    }
+    let written = bundle::write_assets(&mut zip, assets, assets_archive_dir, options)?;
+    manifest
+        .files
+        .extend(written.into_iter().map(|asset| BundledAsset {
+            path: format!("{}/{}", assets_archive_dir, asset.path),
+            ..asset
+        }));
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize package manifest: {}", e))?;
+    write_entry(&mut zip, MANIFEST_FILE, &manifest_json, options)?;
+    zip.finish()
+        .map_err(|e| format!("Failed to write package: {}", e))?;
+    Ok(manifest)
+}
+
+/// Unpack the files listed in a package's manifest into `destination`, checking each
+/// against its checksum. Existing files are never overwritten.
+pub fn unpack_package(archive: &Path, destination: &Path) -> Result<UnpackSummary, String> {
+    let file = File::open(archive).map_err(|e| format!("Failed to open {:?}: {}", archive, e))?;
+    let mut zip = ZipArchive::new(file)
+        .map_err(|e| format!("Failed to read package {:?}: {}", archive, e))?;
+    let manifest: PackageManifest = {
+        let entry = zip
+            .by_name(MANIFEST_FILE)
+            .map_err(|_| "Not a project package: manifest missing".to_string())?;
+        serde_json::from_reader(entry)
+            .map_err(|e| format!("Failed to parse package manifest: {}", e))?
+    };
+    if manifest.format_version > PACKAGE_FORMAT_VERSION {
+        return Err(format!(
+            "Package format {} is newer than this editor supports",
+            manifest.format_version
+        ));
+    }
+    // The project file is opened next, so it has to be one of the files unpacked
+    let project_file = bundle::safe_relative(&manifest.project_file)
+        .filter(|relative| manifest.files.iter().any(|file| file.path == *relative))
+        .ok_or_else(|| format!("Invalid project file in package: {}", manifest.project_file))?;
+
+    let mut targets = Vec::new();
+    for file in &manifest.files {
+        let relative = bundle::safe_relative(&file.path)
+            .filter(|relative| *relative == file.path)
+            .ok_or_else(|| format!("Unsafe path in package: {}", file.path))?;
+        let target = destination.join(&relative);
+        if target.exists() {
+            return Err(format!("{:?} already exists", target));
+        }
+        targets.push((file, relative));
+    }
+
+    // Nothing lands in the destination until every file has verified
+    let staging = destination.with_extension("partial");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)
+            .map_err(|e| format!("Failed to clear {:?}: {}", staging, e))?;
+    }
+    if let Err(e) = stage_files(&mut zip, &targets, &staging) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    if destination.exists() {
+        for (_, relative) in &targets {
+            let target = destination.join(relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+            }
+            fs::rename(staging.join(relative), &target)
+                .map_err(|e| format!("Failed to move {:?} into place: {}", target, e))?;
+        }
+        let _ = fs::remove_dir_all(&staging);
+    } else {
+        fs::rename(&staging, destination)
+            .map_err(|e| format!("Failed to move package into {:?}: {}", destination, e))?;
+    }
+
+    Ok(UnpackSummary {
+        project_file: destination.join(project_file).to_string_lossy().to_string(),
+        file_count: targets.len(),
+    })
+}
+
+/// Extract and verify the listed package files under `staging`.
+fn stage_files(
+    zip: &mut ZipArchive<File>,
+    targets: &[(&BundledAsset, String)],
+    staging: &Path,
+) -> Result<(), String> {
+    for (file, relative) in targets {
+        let mut contents = Vec::new();
+        zip.by_name(&file.path)
+            .map_err(|e| format!("Failed to read {} from package: {}", file.path, e))?
+            .read_to_end(&mut contents)
+            .map_err(|e| format!("Failed to read {} from package: {}", file.path, e))?;
+        if format!("{:x}", Sha256::digest(&contents)) != file.sha256 {
+            return Err(format!("{} is corrupted in the package", file.path));
+        }
+        let target = staging.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+        }
+        fs::write(&target, contents).map_err(|e| format!("Failed to write {:?}: {}", target, e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, prop};
+    use tempfile::tempdir;
+
+    fn level(id: &str, mesh: &str) -> LevelData {
+        LevelData {
+            id: id.to_string(),
+            ..testing::level(vec![prop("crate", mesh)])
+        }
+    }
+
+    #[test]
+    fn packages_and_unpacks_a_project() {
+        let temp_dir = tempdir().unwrap();
+        let project_dir = temp_dir.path().join("game");
+        let shared_dir = temp_dir.path().join("shared");
+        fs::create_dir_all(project_dir.join("Assets/Models")).unwrap();
+        fs::create_dir_all(project_dir.join("levels")).unwrap();
+        fs::create_dir_all(project_dir.join("themes")).unwrap();
+        fs::create_dir_all(&shared_dir).unwrap();
+        fs::write(project_dir.join("Assets/Models/crate.obj"), "v 0 0 0\n").unwrap();
+        fs::write(project_dir.join("themes/dusk.json"), "{}").unwrap();
+        let prop = shared_dir.join("lamp.obj");
+        fs::write(&prop, "v 1 1 1\n").unwrap();
+        let sky = shared_dir.join("sky.png");
+        fs::write(&sky, "png").unwrap();
+
+        let crypt = project_dir.join("levels/crypt.json");
+        let hub = shared_dir.join("hub.json");
+        let hub_level = level("hub", &prop.to_string_lossy());
+        fs::write(
+            &crypt,
+            serde_json::to_string(&level("crypt", "Models/crate.obj")).unwrap(),
+        )
+        .unwrap();
+        fs::write(&hub, serde_json::to_string(&hub_level).unwrap()).unwrap();
+
+        let project_file = project_dir.join("game.mbp");
+        let project = ProjectData {
+            version: "1.0.0".to_string(),
+            timestamp: String::new(),
+            scene: serde_json::json!({
+                "objects": { "dome": { "material": { "texture": sky.to_string_lossy() } } }
+            }),
+            levels: vec![
+                crypt.to_string_lossy().to_string(),
+                hub.to_string_lossy().to_string(),
+                project_dir
+                    .join("levels/gone.json")
+                    .to_string_lossy()
+                    .to_string(),
+            ],
+            active_level: Some(crypt.to_string_lossy().to_string()),
+            assets_directory: None,
+        }
+        .relative_to(&project_dir);
+        fs::write(&project_file, serde_json::to_string(&project).unwrap()).unwrap();
+
+        let output = temp_dir.path().join("game.zip");
+        let summary = write_package(&project_file, None, &output).unwrap();
+        assert_eq!(summary.missing.len(), 1);
+        assert!(summary.missing[0].ends_with("gone.json"));
+
+        let unpacked = temp_dir.path().join("unpacked");
+        let unpack = unpack_package(&output, &unpacked).unwrap();
+        assert_eq!(unpack.file_count, summary.file_count);
+        assert!(unpacked.join("themes/dusk.json").is_file());
+        assert!(unpacked.join("Assets/Models/crate.obj").is_file());
+
+        let json = fs::read_to_string(&unpack.project_file).unwrap();
+        let project = serde_json::from_str::<ProjectData>(&json)
+            .unwrap()
+            .resolved_against(&unpacked);
+        assert_eq!(project.levels.len(), 2);
+        assert!(project
+            .levels
+            .iter()
+            .all(|level| Path::new(level).is_file()));
+        let texture = project.scene["objects"]["dome"]["material"]["texture"]
+            .as_str()
+            .unwrap();
+        assert_eq!(
+            Path::new(texture),
+            unpacked.join("textures/external/sky.png")
+        );
+
+        // The shared level's prop moved into the package's asset folder
+        let hub_json = fs::read_to_string(&project.levels[1]).unwrap();
+        let hub: LevelData = serde_json::from_str(&hub_json).unwrap();
+        let mesh = hub.objects[0].mesh.clone().unwrap();
+        assert!(unpacked.join("Assets").join(&mesh).is_file(), "{}", mesh);
+
+        // Unpacking over an existing project is refused
+        assert!(unpack_package(&output, &unpacked).is_err());
+    }
+
+    #[test]
+    fn refuses_project_files_outside_the_package() {
+        let temp_dir = tempdir().unwrap();
+        let archive = temp_dir.path().join("tampered.zip");
+        for project_file in ["../game.mbp", "/tmp/game.mbp", "other.mbp"] {
+            let mut zip = ZipWriter::new(File::create(&archive).unwrap());
+            let options = SimpleFileOptions::default();
+            let packed = write_entry(&mut zip, "game.mbp", b"{}", options).unwrap();
+            let manifest = PackageManifest {
+                format_version: PACKAGE_FORMAT_VERSION,
+                project_name: "game".to_string(),
+                project_file: project_file.to_string(),
+                created_at: Utc::now(),
+                levels: Vec::new(),
+                files: vec![packed],
+                missing: Vec::new(),
+            };
+            let manifest_json = serde_json::to_vec(&manifest).unwrap();
+            write_entry(&mut zip, MANIFEST_FILE, &manifest_json, options).unwrap();
+            zip.finish().unwrap();
+
+            let unpacked = temp_dir.path().join("unpacked");
+            let error = unpack_package(&archive, &unpacked).unwrap_err();
+            assert!(error.contains("Invalid project file"), "{}", error);
+            assert!(!unpacked.exists());
+        }
+    }
+
+    #[test]
+    fn corrupted_packages_leave_nothing_behind() {
+        let temp_dir = tempdir().unwrap();
+        let archive = temp_dir.path().join("corrupted.zip");
+        let mut zip = ZipWriter::new(File::create(&archive).unwrap());
+        let options = SimpleFileOptions::default();
+        let project = write_entry(&mut zip, "game.mbp", b"{}", options).unwrap();
+        let mut level = write_entry(&mut zip, "levels/hub.json", b"{}", options).unwrap();
+        level.sha256 = "0".repeat(64);
+        let manifest = PackageManifest {
+            format_version: PACKAGE_FORMAT_VERSION,
+            project_name: "game".to_string(),
+            project_file: "game.mbp".to_string(),
+            created_at: Utc::now(),
+            levels: Vec::new(),
+            files: vec![project, level],
+            missing: Vec::new(),
+        };
+        let manifest_json = serde_json::to_vec(&manifest).unwrap();
+        write_entry(&mut zip, MANIFEST_FILE, &manifest_json, options).unwrap();
+        zip.finish().unwrap();
+
+        let unpacked = temp_dir.path().join("unpacked");
+        let error = unpack_package(&archive, &unpacked).unwrap_err();
+        assert!(error.contains("corrupted"), "{}", error);
+        assert!(!unpacked.exists());
+        assert!(!unpacked.with_extension("partial").exists());
+
+        // An existing destination keeps only what was already there
+        fs::create_dir_all(&unpacked).unwrap();
+        assert!(unpack_package(&archive, &unpacked).is_err());
+        assert_eq!(fs::read_dir(&unpacked).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,273 @@
+// Functions scripts can call, and the glue between Rhai values and level data
+use crate::export::exporters::ExportResult;
+use crate::export::{ExportFormat, ExportOptions, LevelExporter};
+use crate::generation::bsp::BSPGenerator;
+use crate::generation::wfc::{WFCGenerationParams, WFCGenerator};
+use crate::{BSPGenerationParams, GameObject, LevelData, ObjectKind, Transform3D};
+use log::info;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rhai::serde::{from_dynamic, to_dynamic};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map, Scope, FLOAT, INT};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use uuid::Uuid;
+
+/// Operations a script may run before it's stopped, so a runaway loop can't hang the editor.
+const MAX_OPERATIONS: u64 = 50_000_000;
+/// Scope variable holding the level; `()` when no level is open.
+const LEVEL_VARIABLE: &str = "level";
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Turns a level into the asset-resolved copy that gets exported.
+pub type PrepareExport = Arc<dyn Fn(&LevelData) -> Result<LevelData, String> + Send + Sync>;
+
+/// What a script left behind.
+pub struct ScriptOutcome {
+    /// Lines from `print` and `debug`
+    pub output: Vec<String>,
+    /// The `level` variable when the script finished
+    pub level: Option<LevelData>,
+    pub exports: Vec<ExportResult>,
+}
+
+/// Run `source` with `level` in scope, waiting on generators and exporters in place.
+///
+/// Call from a blocking thread: the async generators are driven to completion on the
+/// Tauri runtime.
+pub fn run_script(
+    source: &str,
+    level: Option<LevelData>,
+    prepare_export: Option<PrepareExport>,
+) -> Result<ScriptOutcome, String> {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let exports = Arc::new(Mutex::new(Vec::new()));
+    let seed = level.as_ref().and_then(|level| level.generation_seed);
+    let engine = script_engine(output.clone(), exports.clone(), prepare_export, seed);
+
+    let mut scope = Scope::new();
+    let level = match level {
+        Some(level) => to_script(&level).map_err(|e| format!("Failed to load level: {}", e))?,
+        None => Dynamic::UNIT,
+    };
+    scope.push_dynamic(LEVEL_VARIABLE, level);
+    engine
+        .run_with_scope(&mut scope, source)
+        .map_err(|e| format!("Script failed: {}", e))?;
+
+    let level = scope
+        .get_value::<Dynamic>(LEVEL_VARIABLE)
+        .filter(|level| !level.is_unit())
+        .map(|level| from_script::<LevelData>(&level))
+        .transpose()
+        .map_err(|e| format!("Script left `level` invalid: {}", e))?;
+    let output = std::mem::take(&mut *lock(&output)?);
+    let exports = std::mem::take(&mut *lock(&exports)?);
+    Ok(ScriptOutcome {
+        output,
+        level,
+        exports,
+    })
+}
+
+/// Lock state shared with the engine, which a panicking script callback may have poisoned.
+fn lock<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>, String> {
+    mutex
+        .lock()
+        .map_err(|_| "Script state lock poisoned".to_string())
+}
+
+fn script_engine(
+    output: Arc<Mutex<Vec<String>>>,
+    exports: Arc<Mutex<Vec<ExportResult>>>,
+    prepare_export: Option<PrepareExport>,
+    seed: Option<u64>,
+) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let print_output = output.clone();
+    engine.on_print(move |line| {
+        info!("[script] {}", line);
+        if let Ok(mut output) = print_output.lock() {
+            output.push(line.to_string());
+        }
+    });
+    engine.on_debug(move |line, _, position| {
+        info!("[script] {} {}", position, line);
+        if let Ok(mut output) = output.lock() {
+            output.push(format!("{} {}", position, line));
+        }
+    });
+
+    // Seeded from the level so reruns on the same generated level pick the same objects
+    let rng = Arc::new(Mutex::new(
+        seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64),
+    ));
+    let random_rng = rng.clone();
+    engine.register_fn("random", move || -> ScriptResult<FLOAT> {
+        Ok(lock(&random_rng)?.gen())
+    });
+    let range_rng = rng.clone();
+    engine.register_fn(
+        "random_int",
+        move |low: INT, high: INT| -> ScriptResult<INT> {
+            if low >= high {
+                return Err(format!("random_int needs low < high, got {}..{}", low, high).into());
+            }
+            Ok(lock(&range_rng)?.gen_range(low..high))
+        },
+    );
+    engine.register_fn("seed_random", move |seed: INT| -> ScriptResult<()> {
+        *lock(&rng)? = StdRng::seed_from_u64(seed as u64);
+        Ok(())
+    });
+
+    engine.register_fn("generate_bsp", |params: Map| -> ScriptResult<Dynamic> {
+        let params: BSPGenerationParams = from_script(&params.into())?;
+        let level = tauri::async_runtime::block_on(BSPGenerator::new().generate(params))
+            .map_err(|e| format!("BSP generation failed: {}", e))?;
+        to_script(&level)
+    });
+    engine.register_fn("generate_wfc", |params: Map| -> ScriptResult<Dynamic> {
+        let params: WFCGenerationParams = from_script(&params.into())?;
+        let level = tauri::async_runtime::block_on(WFCGenerator::new().generate(params))
+            .map_err(|e| format!("WFC generation failed: {}", e))?;
+        to_script(&level)
+    });
+
+    engine.register_fn(
+        "export_level",
+        move |level: Dynamic, format: &str, output_path: &str| -> ScriptResult<Dynamic> {
+            let format = export_format(format)?;
+            let mut level: LevelData = from_script(&level)?;
+            if let Some(prepare_export) = &prepare_export {
+                level = prepare_export(&level)?;
+            }
+            let result = tauri::async_runtime::block_on(LevelExporter::new().export_multi_format(
+                &level,
+                &[format],
+                output_path,
+                &ExportOptions::default(),
+            ))
+            .map_err(|e| format!("Export failed: {}", e))?;
+            let summary = to_script(&result)?;
+            lock(&exports)?.push(result);
+            Ok(summary)
+        },
+    );
+
+    engine.register_fn(
+        "new_object",
+        |name: &str, mesh: &str| -> ScriptResult<Dynamic> {
+            to_script(&GameObject {
+                id: Uuid::new_v4().to_string(),
+                name: name.to_string(),
+                transform: Transform3D {
+                    position: [0.0, 0.0, 0.0],
+                    rotation: [0.0, 0.0, 0.0, 1.0],
+                    scale: [1.0, 1.0, 1.0],
+                },
+                material: None,
+                mesh: Some(mesh.to_string()),
+                layer: "Default".to_string(),
+                tags: Vec::new(),
+                metadata: HashMap::new(),
+                kind: ObjectKind::Mesh,
+                physics: None,
+            })
+        },
+    );
+    engine.register_fn(
+        "objects_with_tag",
+        |level: Dynamic, tag: &str| -> ScriptResult<Array> {
+            let level: LevelData = from_script(&level)?;
+            Ok(level
+                .objects
+                .iter()
+                .enumerate()
+                .filter(|(_, object)| object.tags.iter().any(|t| t == tag))
+                .map(|(index, _)| Dynamic::from(index as INT))
+                .collect())
+        },
+    );
+
+    engine
+}
+
+/// Values cross into scripts as JSON, so numbers convert the way level files do: Rhai
+/// only has 64-bit floats, and scripts may write `2` where a float is expected.
+fn to_script<T: Serialize>(value: &T) -> ScriptResult<Dynamic> {
+    let json = serde_json::to_value(value).map_err(|e| e.to_string())?;
+    to_dynamic(json)
+}
+
+fn from_script<T: DeserializeOwned>(value: &Dynamic) -> ScriptResult<T> {
+    let json: serde_json::Value = from_dynamic(value)?;
+    serde_json::from_value(json).map_err(|e| e.to_string().into())
+}
+
+/// Export format named by its file extension, e.g. `"ron"`.
+fn export_format(name: &str) -> ScriptResult<ExportFormat> {
+    match name.to_lowercase().as_str() {
+        "json" => Ok(ExportFormat::JSON),
+        "ron" => Ok(ExportFormat::RON),
+        "rs" | "rust" => Ok(ExportFormat::RustCode),
+        "gltf" => Ok(ExportFormat::GLTF),
+        "fbx" => Ok(ExportFormat::FBX),
+        "tscn" | "godot" => Ok(ExportFormat::GodotScene),
+        "unity" => Ok(ExportFormat::Unity),
+        "collision_ron" => Ok(ExportFormat::CollisionRon),
+        "collision_json" => Ok(ExportFormat::CollisionJson),
+        _ => Err(format!("Unknown export format: {}", name).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn generates_edits_and_exports() {
+        let temp_dir = tempdir().unwrap();
+        let output_path = temp_dir.path().join("out").join("level");
+        let source = format!(
+            r#"
+            level = generate_bsp(#{{
+                width: 40, height: 40, depth: 1, min_room_size: 5, max_room_size: 10,
+                corridor_width: 1, theme: "dungeon", seed: 7
+            }});
+            let floors = objects_with_tag(level, "floor");
+            for i in floors {{
+                if random() < 0.5 {{
+                    level.objects[i].mesh = "floor_cracked.glb";
+                }}
+            }}
+            let prop = new_object("Crate", "crate.glb");
+            prop.tags.push("prop");
+            level.objects.push(prop);
+            print(`floors: ${{floors.len()}}`);
+            export_level(level, "ron", {:?});
+            "#,
+            output_path.to_string_lossy()
+        );
+
+        let outcome = run_script(&source, None, None).unwrap();
+        let level = outcome.level.unwrap();
+        assert!(level
+            .objects
+            .iter()
+            .any(|object| object.mesh.as_deref() == Some("floor_cracked.glb")));
+        assert_eq!(level.objects.last().unwrap().tags, vec!["prop".to_string()]);
+        assert!(outcome.output[0].starts_with("floors: "));
+        assert_eq!(outcome.exports.len(), 1);
+        assert!(outcome.exports[0].exported_files[0].success);
+
+        let error = run_script("export_level(level, \"bmp\", \"x\")", Some(level), None);
+        assert!(matches!(error, Err(e) if e.contains("Unknown export format")));
+    }
+}
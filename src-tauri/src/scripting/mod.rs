@@ -0,0 +1,155 @@
+//! Rhai scripts that automate level pipelines without recompiling the editor.
+//!
+//! Scripts live in the project's `scripts/` folder as `<name>.rhai`. The open level is
+//! in scope as `level`; whatever the script leaves there replaces it. See [`api`] for
+//! the generation, editing and export functions scripts can call.
+
+pub mod api;
+
+use crate::export::exporters::ExportResult;
+use crate::level::events::{emit_level_changed, LevelChangeKind};
+use crate::{assets, AppState};
+use api::PrepareExport;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::State;
+
+pub const SCRIPTS_DIRECTORY: &str = "scripts";
+const SCRIPT_EXTENSION: &str = "rhai";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptInfo {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScriptRunResult {
+    /// Lines the script printed
+    pub output: Vec<String>,
+    /// Whether the script changed or replaced the open level
+    pub level_changed: bool,
+    pub exports: Vec<ExportResult>,
+    pub duration_ms: u64,
+}
+
+fn scripts_directory() -> Result<PathBuf, String> {
+    assets::project_directory()
+        .map(|project| project.join(SCRIPTS_DIRECTORY))
+        .ok_or_else(|| "Project directory not found".to_string())
+}
+
+/// Path of a script in `directory` by name, with or without its extension.
+fn script_path(directory: &Path, name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    let name = name
+        .strip_suffix(&format!(".{}", SCRIPT_EXTENSION))
+        .unwrap_or(name);
+    // Only scripts directly inside the folder can be run by name
+    if name.is_empty()
+        || name.starts_with('.')
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.'))
+    {
+        return Err(format!("Invalid script name: {}", name));
+    }
+    Ok(directory.join(format!("{}.{}", name, SCRIPT_EXTENSION)))
+}
+
+fn scripts_in(directory: &Path) -> Result<Vec<ScriptInfo>, String> {
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(directory).map_err(|e| format!("Failed to read scripts: {}", e))?;
+    let mut scripts: Vec<ScriptInfo> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(SCRIPT_EXTENSION) {
+                return None;
+            }
+            Some(ScriptInfo {
+                name: path.file_stem()?.to_string_lossy().to_string(),
+                path: path.to_string_lossy().to_string(),
+                size_bytes: entry.metadata().ok()?.len(),
+            })
+        })
+        .collect();
+    scripts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(scripts)
+}
+
+/// Scripts in the project's `scripts/` folder.
+#[tauri::command]
+pub async fn list_scripts() -> Result<Vec<ScriptInfo>, String> {
+    scripts_in(&scripts_directory()?)
+}
+
+/// Run a script from the project's `scripts/` folder by `name`, or the given `source`.
+///
+/// The script works on a copy of the open level; the copy it leaves in `level` replaces
+/// the open level only if the script finishes without an error.
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn run_script(
+    name: Option<String>,
+    source: Option<String>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<ScriptRunResult, String> {
+    let (label, source) = match (name, source) {
+        (Some(name), None) => {
+            let path = script_path(&scripts_directory()?, &name)?;
+            let source = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read script {:?}: {}", path, e))?;
+            (name, source)
+        }
+        (None, Some(source)) => ("<inline>".to_string(), source),
+        _ => return Err("Give either a script name or its source".to_string()),
+    };
+
+    let level = state.read().await.current_level.clone();
+    let before = serde_json::to_value(&level).ok();
+    let export_handle = app_handle.clone();
+    let prepare_export: PrepareExport =
+        Arc::new(move |level| assets::level_for_export(&export_handle, level));
+
+    let started = Instant::now();
+    let outcome = tauri::async_runtime::spawn_blocking(move || {
+        api::run_script(&source, level, Some(prepare_export))
+    })
+    .await
+    .map_err(|e| format!("Script task failed: {}", e))??;
+
+    let level_changed = serde_json::to_value(&outcome.level).ok() != before;
+    if level_changed {
+        let mut app_state = state.write().await;
+        app_state.spatial_index.clear();
+        for obj in outcome.level.iter().flat_map(|level| &level.objects) {
+            app_state.spatial_index.insert(&obj.id, &obj.transform);
+        }
+        app_state.current_level = outcome.level;
+        emit_level_changed(&app_handle, LevelChangeKind::LevelReplaced, Vec::new());
+    }
+
+    let duration_ms = started.elapsed().as_millis() as u64;
+    info!(
+        "Ran script {} in {}ms ({} exports{})",
+        label,
+        duration_ms,
+        outcome.exports.len(),
+        if level_changed { ", level changed" } else { "" }
+    );
+    Ok(ScriptRunResult {
+        output: outcome.output,
+        level_changed,
+        exports: outcome.exports,
+        duration_ms,
+    })
+}
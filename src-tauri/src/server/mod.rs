@@ -0,0 +1,224 @@
+//! Optional local HTTP/WebSocket API, so DCC plugins and build scripts can drive the
+//! running editor.
+//!
+//! The server is off until enabled with `configure_api_server`; its settings are kept in
+//! the app data directory and it starts with the editor from then on. It only listens on
+//! the loopback interface and every request must carry the configured token.
+
+pub mod routes;
+
+use crate::assets;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tokio::sync::{broadcast, oneshot, Mutex};
+
+const CONFIG_FILE: &str = "api_server.json";
+const DEFAULT_PORT: u16 = 7878;
+/// Editor events buffered per WebSocket client before the slowest ones start missing some.
+const EVENT_BUFFER: usize = 64;
+
+const fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiServerConfig {
+    /// Start the server with the editor
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Clients send this as `Authorization: Bearer <token>`, or `?token=` for WebSockets
+    #[serde(default)]
+    pub token: String,
+}
+
+impl Default for ApiServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            token: String::new(),
+        }
+    }
+}
+
+impl ApiServerConfig {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read API server config {:?}: {}", path, e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse API server config {:?}: {}", path, e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize API server config: {}", e))?;
+        fs::write(path, json)
+            .map_err(|e| format!("Failed to write API server config {:?}: {}", path, e))
+    }
+
+    /// Make sure there is a token to check requests against, returning whether a new
+    /// one had to be generated.
+    fn ensure_token(&mut self) -> bool {
+        if !self.token.is_empty() {
+            return false;
+        }
+        self.token = uuid::Uuid::new_v4().simple().to_string();
+        true
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiServerStatus {
+    pub config: ApiServerConfig,
+    /// Address the server is listening on, while it runs
+    pub address: Option<String>,
+}
+
+struct RunningServer {
+    address: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+}
+
+/// The running server, if any, and the channel editor events reach WebSocket clients on.
+pub struct ApiServerState {
+    running: Mutex<Option<RunningServer>>,
+    events: broadcast::Sender<String>,
+}
+
+impl ApiServerState {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(None),
+            events: broadcast::channel(EVENT_BUFFER).0,
+        }
+    }
+}
+
+fn config_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    assets::morgana_directory(app_handle).map(|dir| dir.join(CONFIG_FILE))
+}
+
+/// Pass an editor event on to connected WebSocket clients.
+pub fn publish_event<T: Serialize>(app_handle: &AppHandle, event: &str, payload: &T) {
+    let Some(server) = app_handle.try_state::<ApiServerState>() else {
+        return;
+    };
+    if server.events.receiver_count() == 0 {
+        return;
+    }
+    match serde_json::to_string(&serde_json::json!({ "event": event, "payload": payload })) {
+        Ok(message) => {
+            let _ = server.events.send(message);
+        }
+        Err(e) => warn!("Failed to serialize {} event for API clients: {}", event, e),
+    }
+}
+
+/// Start the server at launch if it was left enabled.
+pub async fn start_if_enabled(app_handle: AppHandle) -> Result<(), String> {
+    let path = config_path(&app_handle)?;
+    let mut config = ApiServerConfig::load(&path)?;
+    if !config.enabled {
+        return Ok(());
+    }
+    // The token shown to users is the saved one, so a new one has to be saved
+    if config.ensure_token() {
+        config.save(&path)?;
+    }
+    restart(&app_handle, &config).await.map(|_| ())
+}
+
+/// Stop the running server, then start it again with `config` if enabled.
+async fn restart(
+    app_handle: &AppHandle,
+    config: &ApiServerConfig,
+) -> Result<Option<SocketAddr>, String> {
+    let server = app_handle.state::<ApiServerState>();
+    let mut running = server.running.lock().await;
+    if let Some(previous) = running.take() {
+        let _ = previous.shutdown.send(());
+        info!("Stopped API server on {}", previous.address);
+    }
+    if !config.enabled {
+        return Ok(None);
+    }
+
+    let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, config.port))
+        .await
+        .map_err(|e| format!("Failed to listen on port {}: {}", config.port, e))?;
+    let address = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read server address: {}", e))?;
+    let router = routes::router(routes::ServerContext {
+        app_handle: app_handle.clone(),
+        token: config.token.clone(),
+        events: server.events.clone(),
+    });
+    let (shutdown, stopped) = oneshot::channel();
+    tauri::async_runtime::spawn(async move {
+        let served = axum::serve(listener, router)
+            .with_graceful_shutdown(async {
+                let _ = stopped.await;
+            })
+            .await;
+        if let Err(e) = served {
+            warn!("API server stopped: {}", e);
+        }
+    });
+
+    info!("API server listening on http://{}", address);
+    *running = Some(RunningServer { address, shutdown });
+    Ok(Some(address))
+}
+
+#[tauri::command]
+pub async fn get_api_server_status(app_handle: AppHandle) -> Result<ApiServerStatus, String> {
+    let config = ApiServerConfig::load(&config_path(&app_handle)?)?;
+    let server = app_handle.state::<ApiServerState>();
+    let address = server
+        .running
+        .lock()
+        .await
+        .as_ref()
+        .map(|running| running.address.to_string());
+    Ok(ApiServerStatus { config, address })
+}
+
+/// Turn the API server on or off, change its port, or issue a new token. The settings
+/// are saved and apply immediately.
+#[tauri::command]
+pub async fn configure_api_server(
+    enabled: bool,
+    port: Option<u16>,
+    regenerate_token: Option<bool>,
+    app_handle: AppHandle,
+) -> Result<ApiServerStatus, String> {
+    let path = config_path(&app_handle)?;
+    let mut config = ApiServerConfig::load(&path)?;
+    config.enabled = enabled;
+    config.port = port.unwrap_or(config.port);
+    if regenerate_token.unwrap_or(false) {
+        config.token.clear();
+    }
+    config.ensure_token();
+    config.save(&path)?;
+
+    let address = restart(&app_handle, &config).await?;
+    Ok(ApiServerStatus {
+        config,
+        address: address.map(|address| address.to_string()),
+    })
+}
@@ -0,0 +1,495 @@
+// HTTP and WebSocket routes, both dispatching to the same editor commands
+use crate::assets::{self, AssetSearchParams};
+use crate::export::{ExportFormat, ExportOptions, GltfMeshMode};
+use crate::generation::wfc::WFCGenerationParams;
+use crate::level::objects::{self, ResponseMode};
+use crate::project::{self, export_presets, ProjectData};
+use crate::spatial::BoundingBox;
+use crate::{export_jobs, scripting, AppState, BSPGenerationParams, LevelData};
+use axum::body::Bytes;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use log::info;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+
+/// Commands callable over the API, named as in the Tauri interface.
+pub const COMMANDS: &[&str] = &[
+    "generate_bsp_level",
+    "generate_wfc_level",
+    "run_generation_pipeline",
+    "preview_generation_seeds",
+    "render_level_preview",
+    "get_current_level",
+    "get_level_summary",
+    "get_objects",
+    "query_objects_in_bounds",
+    "load_level_from_file",
+    "import_tiled_map",
+    "save_level_to_file",
+    "export_level",
+    "cancel_export",
+    "list_export_presets",
+    "export_with_preset",
+    "save_project_to_path",
+    "load_project_from_path",
+    "search_assets_page",
+    "list_scripts",
+    "run_script",
+];
+
+#[derive(Clone)]
+pub struct ServerContext {
+    pub app_handle: AppHandle,
+    pub token: String,
+    pub events: broadcast::Sender<String>,
+}
+
+pub fn router(context: ServerContext) -> Router {
+    Router::new()
+        .route("/api/commands", get(list_commands))
+        .route("/api/commands/{command}", post(call_command))
+        .route("/api/ws", get(open_socket))
+        .with_state(context)
+}
+
+#[derive(Deserialize)]
+pub struct TokenQuery {
+    pub token: Option<String>,
+}
+
+/// Whether a request carries the server token, as a bearer header or a `token` query.
+pub fn authorized(headers: &HeaderMap, query_token: Option<&str>, token: &str) -> bool {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    bearer
+        .or(query_token)
+        .is_some_and(|given| !token.is_empty() && constant_time_eq(given, token))
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+fn unauthorized() -> Response {
+    error_response(StatusCode::UNAUTHORIZED, "Missing or invalid API token")
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+async fn list_commands(
+    State(context): State<ServerContext>,
+    Query(query): Query<TokenQuery>,
+    headers: HeaderMap,
+) -> Response {
+    if !authorized(&headers, query.token.as_deref(), &context.token) {
+        return unauthorized();
+    }
+    Json(COMMANDS).into_response()
+}
+
+/// `POST /api/commands/<name>` with the command's arguments as a JSON object.
+async fn call_command(
+    State(context): State<ServerContext>,
+    Path(command): Path<String>,
+    Query(query): Query<TokenQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if !authorized(&headers, query.token.as_deref(), &context.token) {
+        return unauthorized();
+    }
+    if !COMMANDS.contains(&command.as_str()) {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            &format!("Unknown command: {}", command),
+        );
+    }
+    let args = if body.is_empty() {
+        json!({})
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(args) => args,
+            Err(e) => {
+                return error_response(StatusCode::BAD_REQUEST, &format!("Invalid JSON: {}", e))
+            }
+        }
+    };
+
+    info!("API call: {}", command);
+    match dispatch(&context.app_handle, &command, args).await {
+        Ok(result) => Json(result).into_response(),
+        Err(e) => error_response(StatusCode::BAD_REQUEST, &e),
+    }
+}
+
+async fn open_socket(
+    State(context): State<ServerContext>,
+    Query(query): Query<TokenQuery>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    if !authorized(&headers, query.token.as_deref(), &context.token) {
+        return unauthorized();
+    }
+    upgrade.on_upgrade(move |socket| serve_socket(socket, context))
+}
+
+/// A WebSocket request: `{"id": 1, "command": "get_level_summary", "args": {}}`.
+#[derive(Deserialize)]
+struct SocketRequest {
+    #[serde(default)]
+    id: Value,
+    command: String,
+    #[serde(default)]
+    args: Value,
+}
+
+#[derive(Serialize)]
+struct SocketResponse {
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Answer requests in order, and forward editor events such as `level_changed` as
+/// `{"event": ..., "payload": ...}` messages between them.
+async fn serve_socket(mut socket: WebSocket, context: ServerContext) {
+    let mut events = context.events.subscribe();
+    loop {
+        let message = tokio::select! {
+            received = socket.recv() => match received {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if socket.send(Message::Text(event.into())).await.is_err() {
+                        break;
+                    }
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        };
+
+        let response = match serde_json::from_str::<SocketRequest>(&message) {
+            Ok(request) if COMMANDS.contains(&request.command.as_str()) => {
+                let result = dispatch(&context.app_handle, &request.command, request.args).await;
+                SocketResponse {
+                    id: request.id,
+                    error: result.as_ref().err().cloned(),
+                    result: result.ok(),
+                }
+            }
+            Ok(request) => SocketResponse {
+                id: request.id,
+                result: None,
+                error: Some(format!("Unknown command: {}", request.command)),
+            },
+            Err(e) => SocketResponse {
+                id: Value::Null,
+                result: None,
+                error: Some(format!("Invalid request: {}", e)),
+            },
+        };
+        let Ok(text) = serde_json::to_string(&response) else {
+            continue;
+        };
+        if socket.send(Message::Text(text.into())).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GenerateArgs<P> {
+    params: P,
+    response_mode: Option<ResponseMode>,
+}
+
+#[derive(Deserialize)]
+struct PipelineArgs {
+    pipeline: String,
+    response_mode: Option<ResponseMode>,
+}
+
+#[derive(Deserialize)]
+struct SeedPreviewArgs {
+    params: BSPGenerationParams,
+    seeds: Vec<u64>,
+}
+
+#[derive(Deserialize)]
+struct RenderPreviewArgs {
+    /// Defaults to the open level
+    level_data: Option<LevelData>,
+    floor: Option<i32>,
+    theme: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ObjectsArgs {
+    offset: Option<usize>,
+    limit: Option<usize>,
+    ids: Option<Vec<String>>,
+    layer: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BoundsArgs {
+    bounds: BoundingBox,
+}
+
+#[derive(Deserialize)]
+struct LoadArgs {
+    file_path: String,
+    response_mode: Option<ResponseMode>,
+}
+
+#[derive(Deserialize)]
+struct TiledArgs {
+    path: String,
+    theme_id: String,
+    response_mode: Option<ResponseMode>,
+}
+
+#[derive(Deserialize)]
+struct SaveArgs {
+    /// Defaults to the open level
+    level_data: Option<LevelData>,
+    file_path: String,
+    deterministic: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct ExportArgs {
+    /// Defaults to the open level
+    level_data: Option<LevelData>,
+    formats: Vec<ExportFormat>,
+    output_path: String,
+    deterministic: Option<bool>,
+    gltf_meshes: Option<GltfMeshMode>,
+    options: Option<ExportOptions>,
+}
+
+#[derive(Deserialize)]
+struct SaveProjectArgs {
+    project_data: ProjectData,
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct ProjectPathArgs {
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct ExportPresetArgs {
+    name: String,
+    /// Defaults to the open level
+    level_data: Option<LevelData>,
+}
+
+#[derive(Deserialize)]
+struct CancelExportArgs {
+    /// Every running export when missing
+    export_id: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct SearchArgs {
+    params: AssetSearchParams,
+}
+
+#[derive(Deserialize)]
+struct ScriptArgs {
+    name: Option<String>,
+    source: Option<String>,
+}
+
+fn parse<T: DeserializeOwned>(args: Value) -> Result<T, String> {
+    let args = if args.is_null() { json!({}) } else { args };
+    serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))
+}
+
+fn respond<T: Serialize>(result: Result<T, String>) -> Result<Value, String> {
+    result.and_then(|value| {
+        serde_json::to_value(value).map_err(|e| format!("Failed to serialize result: {}", e))
+    })
+}
+
+/// The argument or else a copy of the open level.
+async fn level_or_current(
+    app_handle: &AppHandle,
+    level: Option<LevelData>,
+) -> Result<LevelData, String> {
+    match level {
+        Some(level) => Ok(level),
+        None => app_handle
+            .state::<tokio::sync::RwLock<AppState>>()
+            .read()
+            .await
+            .current_level
+            .clone()
+            .ok_or_else(|| "No level currently loaded".to_string()),
+    }
+}
+
+/// Run one of [`COMMANDS`] with JSON arguments.
+async fn dispatch(app_handle: &AppHandle, command: &str, args: Value) -> Result<Value, String> {
+    let state = || app_handle.state::<tokio::sync::RwLock<AppState>>();
+    let app = app_handle.clone();
+    match command {
+        "generate_bsp_level" => {
+            let args: GenerateArgs<BSPGenerationParams> = parse(args)?;
+            respond(crate::generate_bsp_level(args.params, args.response_mode, state(), app).await)
+        }
+        "generate_wfc_level" => {
+            let args: GenerateArgs<WFCGenerationParams> = parse(args)?;
+            respond(crate::generate_wfc_level(args.params, args.response_mode, state(), app).await)
+        }
+        "run_generation_pipeline" => {
+            let args: PipelineArgs = parse(args)?;
+            respond(
+                crate::run_generation_pipeline(args.pipeline, args.response_mode, state(), app)
+                    .await,
+            )
+        }
+        "preview_generation_seeds" => {
+            let args: SeedPreviewArgs = parse(args)?;
+            respond(crate::preview_generation_seeds(args.params, args.seeds).await)
+        }
+        "render_level_preview" => {
+            let args: RenderPreviewArgs = parse(args)?;
+            respond(
+                crate::render_level_preview(args.level_data, args.floor, args.theme, state()).await,
+            )
+        }
+        "get_current_level" => respond(crate::get_current_level(state()).await),
+        "get_level_summary" => respond(objects::get_level_summary(state()).await),
+        "get_objects" => {
+            let args: ObjectsArgs = parse(args)?;
+            respond(
+                objects::get_objects(args.offset, args.limit, args.ids, args.layer, state()).await,
+            )
+        }
+        "query_objects_in_bounds" => {
+            let args: BoundsArgs = parse(args)?;
+            respond(crate::query_objects_in_bounds(args.bounds, state()).await)
+        }
+        "load_level_from_file" => {
+            let args: LoadArgs = parse(args)?;
+            respond(
+                crate::load_level_from_file(args.file_path, args.response_mode, state(), app).await,
+            )
+        }
+        "import_tiled_map" => {
+            let args: TiledArgs = parse(args)?;
+            respond(
+                crate::tiled::import_tiled_map(
+                    args.path,
+                    args.theme_id,
+                    args.response_mode,
+                    state(),
+                    app,
+                )
+                .await,
+            )
+        }
+        "save_level_to_file" => {
+            let args: SaveArgs = parse(args)?;
+            let level = level_or_current(app_handle, args.level_data).await?;
+            respond(crate::save_level_to_file(level, args.file_path, args.deterministic, app).await)
+        }
+        "export_level" => {
+            let args: ExportArgs = parse(args)?;
+            let level = level_or_current(app_handle, args.level_data).await?;
+            respond(
+                crate::export_level(
+                    level,
+                    args.formats,
+                    args.output_path,
+                    args.deterministic,
+                    args.gltf_meshes,
+                    args.options,
+                    app,
+                )
+                .await,
+            )
+        }
+        "cancel_export" => {
+            let args: CancelExportArgs = parse(args)?;
+            let jobs = app_handle.state::<export_jobs::ExportJobs>();
+            respond(export_jobs::cancel_export(args.export_id, jobs).await)
+        }
+        "list_export_presets" => respond(export_presets::list_export_presets().await),
+        "export_with_preset" => {
+            let args: ExportPresetArgs = parse(args)?;
+            respond(
+                export_presets::export_with_preset(args.name, args.level_data, state(), app).await,
+            )
+        }
+        "save_project_to_path" => {
+            let args: SaveProjectArgs = parse(args)?;
+            respond(project::save_project_to_path(args.project_data, args.path, app).await)
+        }
+        "load_project_from_path" => {
+            let args: ProjectPathArgs = parse(args)?;
+            respond(project::load_project_from_path(args.path, app).await)
+        }
+        "search_assets_page" => {
+            let args: SearchArgs = parse(args)?;
+            respond(assets::search_assets_page(args.params, app).await)
+        }
+        "list_scripts" => respond(scripting::list_scripts().await),
+        "run_script" => {
+            let args: ScriptArgs = parse(args)?;
+            respond(scripting::run_script(args.name, args.source, state(), app).await)
+        }
+        _ => Err(format!("Unknown command: {}", command)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn checks_tokens() {
+        let mut headers = HeaderMap::new();
+        assert!(!authorized(&headers, None, "secret"));
+        assert!(authorized(&headers, Some("secret"), "secret"));
+        assert!(!authorized(&headers, Some("secre"), "secret"));
+        // An unset token never matches, even an empty one
+        assert!(!authorized(&headers, Some(""), ""));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer secret"),
+        );
+        assert!(authorized(&headers, None, "secret"));
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("secret"));
+        assert!(!authorized(&headers, None, "secret"));
+    }
+}
@@ -0,0 +1,106 @@
+//! Snapping commands and the project's saved snapping settings.
+//!
+//! The math lives in `morgan_core::snapping`; this module keeps the settings in the
+//! project's `.morgan/snapping.json` so they come back with the project.
+
+use crate::assets;
+use crate::{AppState, Transform3D};
+use log::info;
+use morgan_core::snapping::{self, SnapSettings};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+fn settings_path() -> Option<PathBuf> {
+    assets::project_directory().map(|project| project.join(".morgan").join("snapping.json"))
+}
+
+fn load_settings(path: &Path) -> Result<SnapSettings, String> {
+    if !path.exists() {
+        return Ok(SnapSettings::default());
+    }
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read snapping settings {:?}: {}", path, e))?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse snapping settings {:?}: {}", path, e))
+}
+
+fn save_settings(settings: &SnapSettings, path: &Path) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize snapping settings: {}", e))?;
+    fs::write(path, json)
+        .map_err(|e| format!("Failed to write snapping settings {:?}: {}", path, e))
+}
+
+/// The project's snapping settings, or the defaults outside a project.
+#[tauri::command]
+pub async fn get_snap_settings() -> Result<SnapSettings, String> {
+    match settings_path() {
+        Some(path) => load_settings(&path),
+        None => Ok(SnapSettings::default()),
+    }
+}
+
+#[tauri::command]
+pub async fn set_snap_settings(settings: SnapSettings) -> Result<SnapSettings, String> {
+    settings.validate()?;
+    let path = settings_path().ok_or("Project directory not found")?;
+    save_settings(&settings, &path)?;
+    info!("Updated snapping settings for project");
+    Ok(settings)
+}
+
+/// Snaps a transform with `settings`, or the project's settings if none are given.
+/// With surface snapping on, the object is dropped onto whatever is below it in the
+/// current level; `object_id` keeps it from landing on itself.
+#[tauri::command]
+pub async fn snap_transform(
+    transform: Transform3D,
+    settings: Option<SnapSettings>,
+    object_id: Option<String>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+) -> Result<Transform3D, String> {
+    let settings = match settings {
+        Some(settings) => settings,
+        None => get_snap_settings().await?,
+    };
+    settings.validate()?;
+
+    let snapped = snapping::snap_transform(&transform, &settings);
+    if !settings.surface_enabled {
+        return Ok(snapped);
+    }
+    let app_state = state.read().await;
+    Ok(
+        snapping::snap_to_surface(&snapped, &app_state.spatial_index, object_id.as_deref())
+            .unwrap_or(snapped),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".morgan").join("snapping.json");
+        assert_eq!(load_settings(&path).unwrap(), SnapSettings::default());
+
+        let settings = SnapSettings {
+            grid_size: 0.25,
+            surface_enabled: true,
+            ..SnapSettings::default()
+        };
+        save_settings(&settings, &path).unwrap();
+        assert_eq!(load_settings(&path).unwrap(), settings);
+
+        // Settings saved before a field existed still load
+        fs::write(&path, r#"{"grid_size": 2.0}"#).unwrap();
+        assert!((load_settings(&path).unwrap().grid_size - 2.0).abs() < f32::EPSILON);
+    }
+}
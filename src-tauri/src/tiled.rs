@@ -0,0 +1,703 @@
+//! Importing maps made in the Tiled editor (`.tmx` or `.tmj`) as themed 3D levels.
+//!
+//! Each tile layer becomes a layer of the level. A tile is placed as the theme tile
+//! named by its `tile` property or else its class (`type` before Tiled 1.9), with the
+//! theme's mesh, height and offset, so a 2D layout comes out as walls, floors and doors.
+//! A layer's integer `floor` property stacks it that many storeys up. Tiles turned in
+//! Tiled keep their turn; mirrored ones are placed unmirrored. Layer data has to be
+//! stored as CSV or XML, the Tiled default, rather than base64.
+
+use crate::assets;
+use crate::generation::themes::{Theme, TileType};
+use crate::level::doors::DoorProperties;
+use crate::level::events::{emit_level_changed, LevelChangeKind};
+use crate::level::objects::{LevelResponse, ResponseMode};
+use crate::spatial::BoundingBox;
+use crate::{AppState, GameObject, LevelData, ObjectKind, Transform3D};
+use log::info;
+use morgan_core::snapping::from_euler_yxz;
+use morgan_core::stable::{generated_id, generated_roll};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+/// Tiled keeps a tile's flips in the top bits of its global ID.
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+const GID_MASK: u32 = 0x0FFF_FFFF;
+
+/// A tile layer, taken out of any groups it was in.
+#[derive(Debug, Clone)]
+struct TileLayer {
+    name: String,
+    floor: i32,
+    /// Global tile IDs row by row, 0 where the layer is empty
+    gids: Vec<u32>,
+}
+
+/// Theme tile keys of a tileset's tiles, by their ID within the tileset.
+#[derive(Debug, Clone, Default)]
+struct Tileset {
+    first_gid: u32,
+    keys: HashMap<u32, String>,
+}
+
+#[derive(Debug, Clone)]
+struct TiledMap {
+    width: usize,
+    height: usize,
+    layers: Vec<TileLayer>,
+    tilesets: Vec<Tileset>,
+}
+
+impl TiledMap {
+    /// Checks every layer holds exactly one tile ID per cell of a non-empty map.
+    fn new(
+        width: usize,
+        height: usize,
+        layers: Vec<TileLayer>,
+        tilesets: Vec<Tileset>,
+    ) -> Result<Self, String> {
+        if width == 0 || height == 0 {
+            return Err(format!("Tiled map is empty ({}x{})", width, height));
+        }
+        if let Some(layer) = layers
+            .iter()
+            .find(|layer| layer.gids.len() != width * height)
+        {
+            return Err(format!(
+                "Layer {} has {} tiles, but the map is {}x{}",
+                layer.name,
+                layer.gids.len(),
+                width,
+                height
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            layers,
+            tilesets,
+        })
+    }
+
+    /// The key of the tile with global ID `gid`, from the tileset it falls in.
+    fn tile_key(&self, gid: u32) -> Option<&str> {
+        let tileset = self
+            .tilesets
+            .iter()
+            .filter(|tileset| tileset.first_gid <= gid)
+            .max_by_key(|tileset| tileset.first_gid)?;
+        tileset
+            .keys
+            .get(&(gid - tileset.first_gid))
+            .map(String::as_str)
+    }
+}
+
+/// Clockwise quarter turns made by flip flags, or `None` for a mirror image.
+fn quarter_turns(raw_gid: u32) -> Option<u32> {
+    let flags = (
+        raw_gid & FLIPPED_HORIZONTALLY != 0,
+        raw_gid & FLIPPED_VERTICALLY != 0,
+        raw_gid & FLIPPED_DIAGONALLY != 0,
+    );
+    match flags {
+        (false, false, false) => Some(0),
+        (true, false, true) => Some(1),
+        (true, true, false) => Some(2),
+        (false, true, true) => Some(3),
+        _ => None,
+    }
+}
+
+/// The theme tile key a tile names, from its `tile` property or else its class.
+fn tile_key(class: Option<&str>, properties: &HashMap<String, String>) -> Option<String> {
+    properties
+        .get("tile")
+        .map(String::as_str)
+        .or(class)
+        .filter(|key| !key.trim().is_empty())
+        .map(|key| key.trim().to_lowercase())
+}
+
+fn parse_floor(properties: &HashMap<String, String>, inherited: i32) -> Result<i32, String> {
+    properties.get("floor").map_or(Ok(inherited), |floor| {
+        floor
+            .trim()
+            .parse()
+            .map_err(|_| format!("Layer floor is not a whole number: {}", floor))
+    })
+}
+
+// TMX, Tiled's XML format
+
+fn xml_properties(node: roxmltree::Node) -> HashMap<String, String> {
+    node.children()
+        .filter(|child| child.has_tag_name("properties"))
+        .flat_map(|properties| properties.children())
+        .filter(|property| property.has_tag_name("property"))
+        .filter_map(|property| {
+            let value = property
+                .attribute("value")
+                .or_else(|| property.text())
+                .unwrap_or_default();
+            Some((property.attribute("name")?.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn xml_number<T: std::str::FromStr>(node: roxmltree::Node, name: &str) -> Result<T, String> {
+    node.attribute(name)
+        .and_then(|value| value.trim().parse().ok())
+        .ok_or_else(|| format!("<{}> has no valid {}", node.tag_name().name(), name))
+}
+
+fn xml_tileset(node: roxmltree::Node, first_gid: u32) -> Tileset {
+    let keys = node
+        .children()
+        .filter(|child| child.has_tag_name("tile"))
+        .filter_map(|tile| {
+            let id = tile.attribute("id")?.parse().ok()?;
+            let class = tile.attribute("class").or_else(|| tile.attribute("type"));
+            Some((id, tile_key(class, &xml_properties(tile))?))
+        })
+        .collect();
+    Tileset { first_gid, keys }
+}
+
+fn xml_layer_data(layer: roxmltree::Node) -> Result<Vec<u32>, String> {
+    let data = layer
+        .children()
+        .find(|child| child.has_tag_name("data"))
+        .ok_or("Tile layer has no data")?;
+    if data.children().any(|child| child.has_tag_name("chunk")) {
+        return Err("Infinite Tiled maps aren't supported".to_string());
+    }
+    match data.attribute("encoding") {
+        Some("csv") => data
+            .text()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|gid| !gid.is_empty())
+            .map(|gid| gid.parse().map_err(|_| format!("Invalid tile ID: {}", gid)))
+            .collect(),
+        None => Ok(data
+            .children()
+            .filter(|child| child.has_tag_name("tile"))
+            .map(|tile| {
+                tile.attribute("gid")
+                    .and_then(|gid| gid.parse().ok())
+                    .unwrap_or(0)
+            })
+            .collect()),
+        Some(encoding) => Err(format!(
+            "Tiled {} layer data isn't supported; save the map with CSV layer data",
+            encoding
+        )),
+    }
+}
+
+fn xml_layers(
+    node: roxmltree::Node,
+    floor: i32,
+    layers: &mut Vec<TileLayer>,
+) -> Result<(), String> {
+    for child in node.children().filter(roxmltree::Node::is_element) {
+        let properties = xml_properties(child);
+        match child.tag_name().name() {
+            "layer" => layers.push(TileLayer {
+                name: child.attribute("name").unwrap_or("Tiles").to_string(),
+                floor: parse_floor(&properties, floor)?,
+                gids: xml_layer_data(child)?,
+            }),
+            "group" => xml_layers(child, parse_floor(&properties, floor)?, layers)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Parses a TMX map, reading external tilesets from `directory`.
+fn parse_tmx(text: &str, directory: &Path) -> Result<TiledMap, String> {
+    let document =
+        roxmltree::Document::parse(text).map_err(|e| format!("Failed to parse TMX: {}", e))?;
+    let map = document.root_element();
+    if !map.has_tag_name("map") {
+        return Err("Not a Tiled map".to_string());
+    }
+    if map.attribute("infinite") == Some("1") {
+        return Err("Infinite Tiled maps aren't supported".to_string());
+    }
+
+    let mut tilesets = Vec::new();
+    for node in map.children().filter(|child| child.has_tag_name("tileset")) {
+        let first_gid = xml_number(node, "firstgid")?;
+        tilesets.push(match node.attribute("source") {
+            Some(source) => external_tileset(&directory.join(source), first_gid)?,
+            None => xml_tileset(node, first_gid),
+        });
+    }
+    let mut layers = Vec::new();
+    xml_layers(map, 0, &mut layers)?;
+
+    TiledMap::new(
+        xml_number(map, "width")?,
+        xml_number(map, "height")?,
+        layers,
+        tilesets,
+    )
+}
+
+// TMJ, Tiled's JSON format
+
+#[derive(Deserialize)]
+struct TmjProperty {
+    name: String,
+    value: serde_json::Value,
+}
+
+fn json_properties(properties: &[TmjProperty]) -> HashMap<String, String> {
+    properties
+        .iter()
+        .map(|property| {
+            let value = match &property.value {
+                serde_json::Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            (property.name.clone(), value)
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct TmjTile {
+    id: u32,
+    #[serde(default, alias = "type")]
+    class: Option<String>,
+    #[serde(default)]
+    properties: Vec<TmjProperty>,
+}
+
+#[derive(Deserialize)]
+struct TmjTileset {
+    #[serde(default)]
+    firstgid: u32,
+    source: Option<String>,
+    #[serde(default)]
+    tiles: Vec<TmjTile>,
+}
+
+impl TmjTileset {
+    fn tileset(&self, first_gid: u32) -> Tileset {
+        let keys = self
+            .tiles
+            .iter()
+            .filter_map(|tile| {
+                let key = tile_key(tile.class.as_deref(), &json_properties(&tile.properties))?;
+                Some((tile.id, key))
+            })
+            .collect();
+        Tileset { first_gid, keys }
+    }
+}
+
+#[derive(Deserialize)]
+struct TmjLayer {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    name: String,
+    data: Option<serde_json::Value>,
+    #[serde(default)]
+    layers: Vec<TmjLayer>,
+    #[serde(default)]
+    properties: Vec<TmjProperty>,
+}
+
+#[derive(Deserialize)]
+struct TmjMap {
+    width: usize,
+    height: usize,
+    #[serde(default)]
+    infinite: bool,
+    layers: Vec<TmjLayer>,
+    #[serde(default)]
+    tilesets: Vec<TmjTileset>,
+}
+
+fn json_layers(source: &[TmjLayer], floor: i32, layers: &mut Vec<TileLayer>) -> Result<(), String> {
+    for layer in source {
+        let floor = parse_floor(&json_properties(&layer.properties), floor)?;
+        match layer.kind.as_str() {
+            "tilelayer" => {
+                let gids = match &layer.data {
+                    Some(serde_json::Value::String(_)) => return Err(
+                        "Tiled base64 layer data isn't supported; save the map with CSV layer data"
+                            .to_string(),
+                    ),
+                    Some(data) => serde_json::from_value(data.clone())
+                        .map_err(|e| format!("Invalid data in layer {}: {}", layer.name, e))?,
+                    None => return Err("Infinite Tiled maps aren't supported".to_string()),
+                };
+                layers.push(TileLayer {
+                    name: layer.name.clone(),
+                    floor,
+                    gids,
+                });
+            }
+            "group" => json_layers(&layer.layers, floor, layers)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Parses a TMJ map, reading external tilesets from `directory`.
+fn parse_tmj(text: &str, directory: &Path) -> Result<TiledMap, String> {
+    let map: TmjMap =
+        serde_json::from_str(text).map_err(|e| format!("Failed to parse TMJ: {}", e))?;
+    if map.infinite {
+        return Err("Infinite Tiled maps aren't supported".to_string());
+    }
+    let tilesets = map
+        .tilesets
+        .iter()
+        .map(|tileset| match &tileset.source {
+            Some(source) => external_tileset(&directory.join(source), tileset.firstgid),
+            None => Ok(tileset.tileset(tileset.firstgid)),
+        })
+        .collect::<Result<_, _>>()?;
+    let mut layers = Vec::new();
+    json_layers(&map.layers, 0, &mut layers)?;
+
+    TiledMap::new(map.width, map.height, layers, tilesets)
+}
+
+/// A tileset kept in a file of its own, as TSX or TSJ.
+fn external_tileset(path: &Path, first_gid: u32) -> Result<Tileset, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read tileset {:?}: {}", path, e))?;
+    if text.trim_start().starts_with('<') {
+        let document = roxmltree::Document::parse(&text)
+            .map_err(|e| format!("Failed to parse tileset {:?}: {}", path, e))?;
+        Ok(xml_tileset(document.root_element(), first_gid))
+    } else {
+        let tileset: TmjTileset = serde_json::from_str(&text)
+            .map_err(|e| format!("Failed to parse tileset {:?}: {}", path, e))?;
+        Ok(tileset.tileset(first_gid))
+    }
+}
+
+/// The level for `map`, with each tile placed as `theme` builds it, and warnings about
+/// tiles left out or changed.
+fn map_to_level(map: &TiledMap, name: &str, theme: &Theme) -> (LevelData, Vec<String>) {
+    let mut objects = Vec::new();
+    let mut layers: Vec<String> = Vec::new();
+    // Tiles the theme has nothing for, by key or global ID
+    let mut unknown: BTreeMap<String, usize> = BTreeMap::new();
+    let mut mirrored = 0;
+
+    for (layer_index, layer) in map.layers.iter().enumerate() {
+        if !layers.contains(&layer.name) {
+            layers.push(layer.name.clone());
+        }
+        let elevation = layer.floor as f32 * theme.wall_height;
+        for (index, &raw_gid) in layer.gids.iter().enumerate() {
+            let gid = raw_gid & GID_MASK;
+            if gid == 0 {
+                continue;
+            }
+            let key = map.tile_key(gid);
+            let Some((key, tile)) = key.and_then(|key| theme.tiles.get_key_value(key)) else {
+                let label = key.map_or_else(|| format!("tile {}", gid), str::to_string);
+                *unknown.entry(label).or_default() += 1;
+                continue;
+            };
+            let turns = quarter_turns(raw_gid).unwrap_or_else(|| {
+                mirrored += 1;
+                0
+            });
+
+            let (x, z) = ((index % map.width) as f32, (index / map.width) as f32);
+            let cell = [x as i64, z as i64, i64::from(layer.floor)];
+            let (pitch, yaw, roll) = tile.mesh.rotation;
+            let (mut ox, oy, mut oz) = tile.mesh.offset;
+            // Clockwise seen from above takes +X to +Z, and the offset with it
+            for _ in 0..turns {
+                (ox, oz) = (-oz, ox);
+            }
+            let yaw = (turns as f32).mul_add(-90.0, yaw);
+            let roll_variant = generated_roll(0, &format!("{}_variant", key), &cell);
+            let mesh = theme.mesh_variant(key, roll_variant).map_or_else(
+                || format!("meshes/{}.mesh", tile.mesh.mesh_type),
+                str::to_string,
+            );
+
+            let mut tags = vec![key.clone()];
+            let collision = tile.collision.then(|| "collision".to_string());
+            for tag in collision.iter().chain(&tile.tags).chain([&theme.id]) {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+            objects.push(GameObject {
+                // Layer names needn't be unique, so IDs go by the layer's place in the map
+                id: generated_id(0, &format!("tiled/{}/{}", name, layer_index), &cell),
+                name: format!("{}_{}_{}", key, x, z),
+                transform: Transform3D {
+                    position: [x + ox, elevation + oy, z + oz],
+                    rotation: from_euler_yxz(
+                        yaw.to_radians(),
+                        pitch.to_radians(),
+                        roll.to_radians(),
+                    ),
+                    scale: tile.mesh.scale.into(),
+                },
+                material: Some(format!("materials/{}/{}.mat", theme.id, tile.mesh.material)),
+                mesh: Some(mesh),
+                layer: layer.name.clone(),
+                tags,
+                metadata: HashMap::from([
+                    ("tile_type".to_string(), key.as_str().into()),
+                    ("floor".to_string(), layer.floor.into()),
+                ]),
+                kind: if tile.tile_type == TileType::Door {
+                    ObjectKind::Door(DoorProperties::default())
+                } else {
+                    ObjectKind::Mesh
+                },
+                physics: None,
+            });
+        }
+    }
+
+    let mut warnings: Vec<String> = unknown
+        .into_iter()
+        .map(|(label, count)| {
+            format!(
+                "{} tiles of {} have no tile in theme {}",
+                count, label, theme.id
+            )
+        })
+        .collect();
+    if mirrored > 0 {
+        warnings.push(format!(
+            "{} mirrored tiles were placed unmirrored",
+            mirrored
+        ));
+    }
+
+    let floors = map
+        .layers
+        .iter()
+        .map(|layer| layer.floor)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let level = LevelData {
+        id: generated_id(
+            0,
+            &format!("tiled/{}", name),
+            &[map.width as i64, map.height as i64],
+        ),
+        name: name.to_string(),
+        objects,
+        layers,
+        generation_seed: None,
+        generation_params: Some(serde_json::json!({
+            "source": "tiled",
+            "theme": theme.id,
+            "width": map.width,
+            "height": map.height,
+        })),
+        bounds: BoundingBox::new(
+            [0.0; 3],
+            [
+                map.width as f32,
+                floors as f32 * theme.wall_height,
+                map.height as f32,
+            ],
+        ),
+        annotations: Vec::new(),
+        camera_bookmarks: Vec::new(),
+    };
+    (level, warnings)
+}
+
+/// Result of `import_tiled_map`: the new level and anything that didn't carry over.
+#[derive(Debug, Serialize)]
+pub struct TiledImportResponse {
+    level: LevelResponse,
+    warnings: Vec<String>,
+}
+
+/// Opens the Tiled map at `path` as a new level built from `theme_id`'s tiles.
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn import_tiled_map(
+    path: String,
+    theme_id: String,
+    response_mode: Option<ResponseMode>,
+    state: State<'_, tokio::sync::RwLock<AppState>>,
+    app_handle: tauri::AppHandle,
+) -> Result<TiledImportResponse, String> {
+    info!("Importing Tiled map {} with theme {}", path, theme_id);
+    let theme = assets::theme_library()
+        .get_theme(&theme_id)
+        .ok_or_else(|| format!("Theme not found: {}", theme_id))?;
+    let file = Path::new(&path);
+    let text = fs::read_to_string(file).map_err(|e| format!("Failed to read Tiled map: {}", e))?;
+    let directory = file.parent().unwrap_or_else(|| Path::new("."));
+    let map = match file.extension().and_then(|ext| ext.to_str()) {
+        Some("tmx") => parse_tmx(&text, directory)?,
+        Some("tmj" | "json") => parse_tmj(&text, directory)?,
+        _ => return Err(format!("Not a Tiled map (.tmx or .tmj): {}", path)),
+    };
+    let name = file.file_stem().map_or_else(
+        || "Tiled Map".to_string(),
+        |stem| stem.to_string_lossy().to_string(),
+    );
+    let (level_data, warnings) = map_to_level(&map, &name, &theme);
+
+    let mut app_state = state.write().await;
+    app_state.spatial_index.clear();
+    for obj in &level_data.objects {
+        app_state.spatial_index.insert(&obj.id, &obj.transform);
+    }
+    app_state.current_level = Some(level_data.clone());
+
+    info!(
+        "Imported {} objects from Tiled map with {} warnings",
+        level_data.objects.len(),
+        warnings.len()
+    );
+    emit_level_changed(&app_handle, LevelChangeKind::LevelReplaced, Vec::new());
+    Ok(TiledImportResponse {
+        level: LevelResponse::new(level_data, response_mode),
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::assert_close;
+
+    const TMX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" width="3" height="2" infinite="0">
+ <tileset firstgid="1" name="dungeon">
+  <tile id="0" type="Floor"/>
+  <tile id="1" class="wall"/>
+  <tile id="2"><properties><property name="tile" value="door"/></properties></tile>
+  <tile id="3" class="lava"/>
+ </tileset>
+ <layer id="1" name="Ground" width="3" height="2">
+  <data encoding="csv">
+1,1,1,
+1,4,0
+</data>
+ </layer>
+ <group name="Upstairs">
+  <properties><property name="floor" type="int" value="1"/></properties>
+  <layer id="2" name="Walls" width="3" height="2">
+   <data encoding="csv">2,3,2684354562,0,0,2147483650</data>
+  </layer>
+ </group>
+</map>"#;
+
+    const TMJ: &str = r#"{
+  "width": 3, "height": 2, "infinite": false,
+  "tilesets": [{
+    "firstgid": 1,
+    "tiles": [
+      {"id": 0, "type": "Floor"},
+      {"id": 1, "class": "wall"},
+      {"id": 2, "properties": [{"name": "tile", "type": "string", "value": "door"}]},
+      {"id": 3, "class": "lava"}
+    ]
+  }],
+  "layers": [
+    {"type": "tilelayer", "name": "Ground", "data": [1, 1, 1, 1, 4, 0]},
+    {"type": "group", "name": "Upstairs",
+     "properties": [{"name": "floor", "type": "int", "value": 1}],
+     "layers": [
+       {"type": "tilelayer", "name": "Walls", "data": [2, 3, 2684354562, 0, 0, 2147483650]}
+     ]}
+  ]
+}"#;
+
+    #[test]
+    fn tmx_and_tmj_maps_become_themed_levels() {
+        let theme = Theme::dungeon();
+        let tmx = parse_tmx(TMX, Path::new(".")).unwrap();
+        let tmj = parse_tmj(TMJ, Path::new(".")).unwrap();
+
+        for map in [tmx, tmj] {
+            let (level, warnings) = map_to_level(&map, "crypt", &theme);
+            assert_eq!(level.layers, ["Ground", "Walls"]);
+            // Four floors, three walls and a door; lava isn't a dungeon tile
+            assert_eq!(level.objects.len(), 8);
+            assert_eq!(warnings.len(), 2);
+            assert!(warnings[0].starts_with("1 tiles of lava"));
+            assert!(warnings[1].starts_with("1 mirrored"));
+
+            let find = |name: &str| level.objects.iter().find(|o| o.name == name).unwrap();
+            let floor = find("floor_1_0");
+            assert_close(floor.transform.position, [1.0, 0.0, 0.0]);
+            assert!(floor.tags.contains(&"dungeon".to_string()));
+
+            // Walls stand a storey up, raised by the theme's offset
+            let wall = &theme.tiles["wall"].mesh;
+            let standing = find("wall_0_0");
+            assert_close(
+                standing.transform.position,
+                [0.0, theme.wall_height + wall.offset.1, 0.0],
+            );
+            assert_close(standing.transform.scale, [1.0, 4.0, 0.3]);
+            assert!(standing.tags.contains(&"collision".to_string()));
+
+            // A quarter turn clockwise, seen from above, is a negative yaw
+            let turned = find("wall_2_0");
+            let half = -std::f32::consts::FRAC_PI_4;
+            assert!((turned.transform.rotation[1] - half.sin()).abs() < 1e-5);
+            assert!(matches!(find("door_1_0").kind, ObjectKind::Door(_)));
+        }
+    }
+
+    #[test]
+    fn rejects_encoded_layer_data() {
+        let base64 = TMX.replace("encoding=\"csv\"", "encoding=\"base64\"");
+        let error = parse_tmx(&base64, Path::new(".")).unwrap_err();
+        assert!(error.contains("CSV"));
+    }
+
+    #[test]
+    fn rejects_maps_whose_layers_dont_fit() {
+        let empty = TMX.replace(
+            "width=\"3\" height=\"2\" infinite",
+            "width=\"0\" height=\"2\" infinite",
+        );
+        assert!(parse_tmx(&empty, Path::new(".")).is_err());
+        let short = TMJ.replace("[1, 1, 1, 1, 4, 0]", "[1, 1, 1, 1, 4]");
+        let error = parse_tmj(&short, Path::new(".")).unwrap_err();
+        assert!(error.contains("Ground"));
+    }
+
+    #[test]
+    fn layers_with_the_same_name_get_distinct_ids() {
+        let mut map = parse_tmj(TMJ, Path::new(".")).unwrap();
+        map.layers.truncate(1);
+        map.layers.push(map.layers[0].clone());
+
+        let (level, _) = map_to_level(&map, "crypt", &Theme::dungeon());
+        assert_eq!(level.layers, ["Ground"]);
+        let ids: std::collections::HashSet<_> = level.objects.iter().map(|o| &o.id).collect();
+        assert_eq!(ids.len(), level.objects.len());
+        assert_eq!(ids.len(), 8);
+    }
+}
@@ -0,0 +1,49 @@
+//! Stepping through a WFC solve, for the frontend's collapse visualizer. One session
+//! is kept at a time; starting another replaces it.
+
+use log::info;
+use morgan_core::generation::wfc::{WFCDebugSession, WFCGenerationParams, WFCSnapshot};
+use tauri::State;
+use tokio::sync::Mutex;
+
+const NO_SESSION: &str = "No WFC debug session; start one with wfc_debug_start";
+
+#[derive(Default)]
+pub struct WfcDebugState {
+    session: Mutex<Option<WFCDebugSession>>,
+}
+
+/// Starts stepping through a solve of `params`, returning the grid before any cell
+/// is collapsed.
+#[tauri::command]
+pub async fn wfc_debug_start(
+    params: WFCGenerationParams,
+    state: State<'_, WfcDebugState>,
+) -> Result<WFCSnapshot, String> {
+    info!(
+        "Starting WFC debug session: {}x{} {}",
+        params.width, params.height, params.tileset
+    );
+    let session = WFCDebugSession::start(&params).map_err(|e| e.to_string())?;
+    let snapshot = session.snapshot();
+    *state.session.lock().await = Some(session);
+    Ok(snapshot)
+}
+
+/// Runs `steps` solver steps, one by default, and returns the grid after them.
+#[tauri::command]
+pub async fn wfc_debug_step(
+    steps: Option<u32>,
+    state: State<'_, WfcDebugState>,
+) -> Result<WFCSnapshot, String> {
+    let mut session = state.session.lock().await;
+    let session = session.as_mut().ok_or(NO_SESSION)?;
+    Ok(session.step(steps.unwrap_or(1)))
+}
+
+#[tauri::command]
+pub async fn wfc_debug_snapshot(state: State<'_, WfcDebugState>) -> Result<WFCSnapshot, String> {
+    let session = state.session.lock().await;
+    let session = session.as_ref().ok_or(NO_SESSION)?;
+    Ok(session.snapshot())
+}
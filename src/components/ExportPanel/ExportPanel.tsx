@@ -1,11 +1,12 @@
-import { useState } from 'react'
+import { useEffect, useState } from 'react'
 import { Download, FolderOpen, FileText, Code, Box } from 'lucide-react'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import { open } from '@tauri-apps/plugin-dialog'
 import { useEditorStore } from '@/store/editorStore'
 
 interface ExportFormat {
-  id: 'JSON' | 'RON' | 'RustCode' | 'GLTF' | 'FBX'
+  id: 'JSON' | 'RON' | 'RustCode' | 'GLTF' | 'FBX' | 'GodotScene' | 'Unity' | 'CollisionRon' | 'CollisionJson'
   name: string
   description: string
   icon: React.ReactNode
@@ -26,13 +27,39 @@ interface ExportResult {
   export_time_ms: number
   errors: string[]
   warnings: string[]
+  cancelled: boolean
+}
+
+interface ExportPreset {
+  name: string
+  formats: ExportFormat['id'][]
+  output_directory: string
+}
+
+interface ExportProgress {
+  export_id: number
+  format: ExportFormat['id']
+  formats_done: number
+  formats_total: number
+  objects_done: number
+  objects_total: number
 }
 
 export default function ExportPanel() {
-  const { sceneObjects } = useEditorStore()
+  const { sceneObjects, selectedObjects } = useEditorStore()
   const [isExporting, setIsExporting] = useState(false)
+  const [selectedOnly, setSelectedOnly] = useState(false)
   const [outputPath, setOutputPath] = useState('')
   const [lastExportResult, setLastExportResult] = useState<ExportResult | null>(null)
+  const [progress, setProgress] = useState<ExportProgress | null>(null)
+  const [presets, setPresets] = useState<ExportPreset[]>([])
+  const [selectedPreset, setSelectedPreset] = useState('')
+
+  useEffect(() => {
+    invoke<ExportPreset[]>('list_export_presets')
+      .then(setPresets)
+      .catch(() => setPresets([]))
+  }, [])
   
   const [exportFormats, setExportFormats] = useState<ExportFormat[]>([
     {
@@ -75,6 +102,38 @@ export default function ExportPanel() {
       fileExtension: 'fbx',
       enabled: false,
     },
+    {
+      id: 'GodotScene',
+      name: 'Godot Scene',
+      description: 'Godot 4 scene with mesh and material resources',
+      icon: <Box className="w-4 h-4" />,
+      fileExtension: 'tscn',
+      enabled: false,
+    },
+    {
+      id: 'Unity',
+      name: 'Unity',
+      description: 'Scene manifest for Unity with left-handed transforms',
+      icon: <Box className="w-4 h-4" />,
+      fileExtension: 'unity.json',
+      enabled: false,
+    },
+    {
+      id: 'CollisionRon',
+      name: 'Collision (RON)',
+      description: 'Merged collision boxes and bodies for Rapier or bevy_xpbd',
+      icon: <Box className="w-4 h-4" />,
+      fileExtension: 'collision.ron',
+      enabled: false,
+    },
+    {
+      id: 'CollisionJson',
+      name: 'Collision (JSON)',
+      description: 'Merged collision boxes and bodies for Rapier or bevy_xpbd',
+      icon: <Box className="w-4 h-4" />,
+      fileExtension: 'collision.json',
+      enabled: false,
+    },
   ])
 
   const toggleFormat = (formatId: ExportFormat['id']) => {
@@ -118,6 +177,9 @@ export default function ExportPanel() {
     }
 
     setIsExporting(true)
+    const unlisten = await listen<ExportProgress>('export_progress', (event) => {
+      setProgress(event.payload)
+    })
     try {
       // Create level data from current scene
       const levelData = {
@@ -153,10 +215,16 @@ export default function ExportPanel() {
         levelData,
         formats: enabledFormats,
         outputPath,
+        options: {
+          filter: { object_ids: selectedOnly ? selectedObjects : [] },
+        },
       })
 
       setLastExportResult(result)
       console.log('Export completed:', result)
+      if (result.cancelled) {
+        return
+      }
       
       // Show success message
       const successFiles = result.exported_files.filter(f => f.success)
@@ -173,10 +241,49 @@ export default function ExportPanel() {
       console.error('Export failed:', error)
       alert(`Export failed: ${error}`)
     } finally {
+      unlisten()
+      setProgress(null)
       setIsExporting(false)
     }
   }
 
+  const savePreset = async () => {
+    const name = prompt('Preset name')
+    if (!name) return
+    try {
+      const saved: ExportPreset[] = await invoke('save_export_preset', {
+        preset: {
+          name,
+          formats: exportFormats.filter(format => format.enabled).map(format => format.id),
+          output_directory: outputPath,
+          options: {},
+        },
+      })
+      setPresets(saved)
+      setSelectedPreset(name)
+    } catch (error) {
+      alert(`Failed to save preset: ${error}`)
+    }
+  }
+
+  const applyPreset = (name: string) => {
+    setSelectedPreset(name)
+    const preset = presets.find(p => p.name === name)
+    if (!preset) return
+    setOutputPath(preset.output_directory)
+    setExportFormats(formats =>
+      formats.map(format => ({ ...format, enabled: preset.formats.includes(format.id) }))
+    )
+  }
+
+  const cancelExport = async () => {
+    try {
+      await invoke('cancel_export', { exportId: progress?.export_id ?? null })
+    } catch (error) {
+      console.error('Failed to cancel export:', error)
+    }
+  }
+
   const formatFileSize = (bytes: number): string => {
     if (bytes < 1024) return `${bytes} B`
     if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`
@@ -198,6 +305,31 @@ export default function ExportPanel() {
         )}
       </div>
 
+      {/* Export Presets */}
+      <div className="mb-3">
+        <label className="block text-xs text-editor-textMuted mb-1">Preset</label>
+        <div className="flex space-x-2">
+          <select
+            value={selectedPreset}
+            onChange={(e) => applyPreset(e.target.value)}
+            className="flex-1 px-2 py-1 text-xs bg-editor-bg border border-editor-border rounded focus:outline-none focus:border-editor-accent"
+          >
+            <option value="">None</option>
+            {presets.map(preset => (
+              <option key={preset.name} value={preset.name}>{preset.name}</option>
+            ))}
+          </select>
+          <button
+            onClick={savePreset}
+            disabled={!outputPath}
+            className="px-2 py-1 text-xs bg-editor-border hover:bg-gray-600 rounded"
+            title="Save the current formats and directory as a preset"
+          >
+            Save
+          </button>
+        </div>
+      </div>
+
       {/* Output Path */}
       <div className="mb-3">
         <label className="block text-xs text-editor-textMuted mb-1">Output Directory</label>
@@ -257,6 +389,16 @@ export default function ExportPanel() {
             <input type="checkbox" className="rounded" />
             <span className="text-xs">Optimize for Size</span>
           </label>
+          <label className="flex items-center space-x-2">
+            <input
+              type="checkbox"
+              checked={selectedOnly}
+              onChange={(e) => setSelectedOnly(e.target.checked)}
+              disabled={selectedObjects.length === 0}
+              className="rounded"
+            />
+            <span className="text-xs">Selected Objects Only ({selectedObjects.length})</span>
+          </label>
         </div>
       </div>
 
@@ -273,7 +415,11 @@ export default function ExportPanel() {
         {isExporting ? (
           <>
             <div className="w-3 h-3 border-2 border-gray-400 border-t-transparent rounded-full animate-spin"></div>
-            <span>Exporting...</span>
+            <span>
+              {progress
+                ? `Exporting ${progress.format} (${Math.min(progress.formats_done + 1, progress.formats_total)}/${progress.formats_total}): ${progress.objects_done}/${progress.objects_total}`
+                : 'Exporting...'}
+            </span>
           </>
         ) : (
           <>
@@ -282,6 +428,14 @@ export default function ExportPanel() {
           </>
         )}
       </button>
+      {isExporting && (
+        <button
+          onClick={cancelExport}
+          className="w-full mt-2 px-3 py-1 text-xs rounded bg-editor-border hover:bg-gray-600"
+        >
+          Cancel Export
+        </button>
+      )}
 
       {/* Export Results */}
       {lastExportResult && (
@@ -301,6 +455,9 @@ export default function ExportPanel() {
                 </div>
               </div>
             ))}
+            {lastExportResult.cancelled && (
+              <div className="text-xs text-yellow-400 mt-2">Export cancelled</div>
+            )}
             {lastExportResult.warnings.length > 0 && (
               <div className="text-xs text-yellow-400 mt-2">
                 Warnings: {lastExportResult.warnings.join(', ')}
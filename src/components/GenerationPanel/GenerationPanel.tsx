@@ -287,6 +287,10 @@ export default function GenerationPanel(_props: GenerationPanelProps) {
           <option value="dungeon">Dungeon</option>
           <option value="scifi">Sci-Fi</option>
           <option value="castle">Castle</option>
+          <option value="cave">Cave</option>
+          <option value="forest">Forest</option>
+          <option value="cyberpunk">Cyberpunk</option>
+          <option value="shipwreck">Shipwreck</option>
         </select>
       </div>
       